@@ -158,8 +158,9 @@ pub struct CodeException {
 }
 
 impl CodeException {
+    //the instruction at 'pc' covered, end_pc exclusive
     pub fn contains(&self, pc: U2) -> bool {
-        (self.start_pc..self.end_pc + 1).contains(&pc)
+        (self.start_pc..self.end_pc).contains(&pc)
     }
 
     pub fn is_finally(&self) -> bool {
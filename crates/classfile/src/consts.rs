@@ -31,6 +31,7 @@ pub const J_SECURITY_MANAGER: &[u8] = b"java/lang/SecurityManager";
 pub const J_FIELD: &[u8] = b"java/lang/reflect/Field";
 pub const J_METHOD: &[u8] = b"java/lang/reflect/Method";
 pub const J_METHOD_CTOR: &[u8] = b"java/lang/reflect/Constructor";
+pub const J_PARAMETER: &[u8] = b"java/lang/reflect/Parameter";
 pub const J_ACCESSIBLE_OBJECT: &[u8] = b"java/lang/reflect/AccessibleObject";
 pub const J_METHODHANDLE: &[u8] = b"java/lang/invoke/MethodHandle";
 pub const J_METHODTYPE: &[u8] = b"java/lang/invoke/MethodType";
@@ -38,13 +39,40 @@ pub const J_METHODTYPE: &[u8] = b"java/lang/invoke/MethodType";
 pub const J_INTERNAL_ERROR: &[u8] = b"java/lang/InternalError";
 pub const J_NPE: &[u8] = b"java/lang/NullPointerException";
 pub const J_IOEXCEPTION: &[u8] = b"java/io/IOException";
+pub const J_FILE_NOT_FOUND: &[u8] = b"java/io/FileNotFoundException";
+pub const J_SYNC_FAILED: &[u8] = b"java/io/SyncFailedException";
+pub const J_INDEX_OUT_OF_BOUNDS: &[u8] = b"java/lang/IndexOutOfBoundsException";
 pub const J_ARRAY_INDEX_OUT_OF_BOUNDS: &[u8] = b"java/lang/ArrayIndexOutOfBoundsException";
 pub const J_CLASS_NOT_FOUND: &[u8] = b"java/lang/ClassNotFoundException";
 pub const J_ARITHMETIC_EX: &[u8] = b"java/lang/ArithmeticException";
 pub const J_SOE: &[u8] = b"java/lang/StackOverflowError";
+pub const J_OOM: &[u8] = b"java/lang/OutOfMemoryError";
 pub const J_NASE: &[u8] = b"java/lang/NegativeArraySizeException";
+pub const J_ASE: &[u8] = b"java/lang/ArrayStoreException";
 pub const J_CCE: &[u8] = b"java/lang/ClassCastException";
 pub const J_THROWABLE: &[u8] = b"java/lang/Throwable";
+pub const J_ERROR: &[u8] = b"java/lang/Error";
+pub const J_ILLEGAL_ARGUMENT: &[u8] = b"java/lang/IllegalArgumentException";
+pub const J_INSTANTIATION_EX: &[u8] = b"java/lang/InstantiationException";
+pub const J_INVOCATION_TARGET_EX: &[u8] = b"java/lang/reflect/InvocationTargetException";
+pub const J_LINKAGE_ERROR: &[u8] = b"java/lang/LinkageError";
+pub const J_CLASS_FORMAT_ERROR: &[u8] = b"java/lang/ClassFormatError";
+pub const J_NO_CLASS_DEF_FOUND: &[u8] = b"java/lang/NoClassDefFoundError";
+pub const J_EIIE: &[u8] = b"java/lang/ExceptionInInitializerError";
+pub const J_ABSTRACT_METHOD_ERROR: &[u8] = b"java/lang/AbstractMethodError";
+pub const J_ICCE: &[u8] = b"java/lang/IncompatibleClassChangeError";
+pub const J_NO_SUCH_FIELD_ERROR: &[u8] = b"java/lang/NoSuchFieldError";
+pub const J_NO_SUCH_METHOD_ERROR: &[u8] = b"java/lang/NoSuchMethodError";
+pub const J_ILLEGAL_ACCESS_ERROR: &[u8] = b"java/lang/IllegalAccessError";
+pub const J_ZIP_EX: &[u8] = b"java/util/zip/ZipException";
+pub const J_DATA_FORMAT_EX: &[u8] = b"java/util/zip/DataFormatException";
+pub const J_SOCKET_EX: &[u8] = b"java/net/SocketException";
+pub const J_SOCKET_TIMEOUT_EX: &[u8] = b"java/net/SocketTimeoutException";
+pub const J_CONNECT_EX: &[u8] = b"java/net/ConnectException";
+pub const J_BIND_EX: &[u8] = b"java/net/BindException";
+pub const J_NO_ROUTE_TO_HOST_EX: &[u8] = b"java/net/NoRouteToHostException";
+pub const J_PORT_UNREACHABLE_EX: &[u8] = b"java/net/PortUnreachableException";
+pub const J_UNKNOWN_HOST_EX: &[u8] = b"java/net/UnknownHostException";
 
 pub const CONSTANT_METHOD_REF_TAG: u8 = 10;
 pub const CONSTANT_INTERFACE_METHOD_REF_TAG: u8 = 11;
@@ -0,0 +1,495 @@
+/*
+x86-64 assembler, the instructions used by the compiler only
+
+Memory operands are always [base + disp32], so no special case of rbp/r13
+(mod 00 means rip-relative) is needed; rsp/r12 as base take a SIB byte.
+*/
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(u8)]
+pub enum Reg {
+    Rax = 0,
+    Rcx = 1,
+    Rdx = 2,
+    Rbx = 3,
+    Rsp = 4,
+    Rbp = 5,
+    Rsi = 6,
+    Rdi = 7,
+    R8 = 8,
+    R9 = 9,
+    R10 = 10,
+    R11 = 11,
+    R12 = 12,
+    R13 = 13,
+    R14 = 14,
+    R15 = 15,
+}
+
+//xmm0 ~ xmm7
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Xmm(pub u8);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Mem {
+    pub base: Reg,
+    pub disp: i32,
+}
+
+impl Mem {
+    pub fn new(base: Reg, disp: i32) -> Self {
+        Self { base, disp }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(u8)]
+pub enum Cond {
+    B = 0x2,
+    E = 0x4,
+    NE = 0x5,
+    A = 0x7,
+    P = 0xA,
+    L = 0xC,
+    GE = 0xD,
+    LE = 0xE,
+    G = 0xF,
+}
+
+//two operand integer instructions, reg <- reg op [mem]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Alu {
+    Add,
+    Sub,
+    And,
+    Or,
+    Xor,
+    Cmp,
+}
+
+impl Alu {
+    fn opcode(self) -> u8 {
+        match self {
+            Alu::Add => 0x03,
+            Alu::Or => 0x0B,
+            Alu::And => 0x23,
+            Alu::Sub => 0x2B,
+            Alu::Xor => 0x33,
+            Alu::Cmp => 0x3B,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Shift {
+    Shl,
+    Shr,
+    Sar,
+}
+
+impl Shift {
+    fn ext(self) -> u8 {
+        match self {
+            Shift::Shl => 4,
+            Shift::Shr => 5,
+            Shift::Sar => 7,
+        }
+    }
+}
+
+//scalar SSE, ss for float, sd for double
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Sse {
+    Load,
+    Store,
+    Add,
+    Mul,
+    Sub,
+    Div,
+}
+
+impl Sse {
+    fn opcode(self) -> u8 {
+        match self {
+            Sse::Load => 0x10,
+            Sse::Store => 0x11,
+            Sse::Add => 0x58,
+            Sse::Mul => 0x59,
+            Sse::Sub => 0x5C,
+            Sse::Div => 0x5E,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Label(usize);
+
+#[derive(Default)]
+pub struct Assembler {
+    buf: Vec<u8>,
+    //offset of the label, None until bound
+    labels: Vec<Option<usize>>,
+    //(offset of rel32, target)
+    fixups: Vec<(usize, Label)>,
+}
+
+impl Assembler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn new_label(&mut self) -> Label {
+        self.labels.push(None);
+        Label(self.labels.len() - 1)
+    }
+
+    pub fn bind(&mut self, label: Label) {
+        debug_assert!(self.labels[label.0].is_none());
+        self.labels[label.0] = Some(self.buf.len());
+    }
+
+    //the code with all jumps patched
+    pub fn finish(mut self) -> Vec<u8> {
+        for (at, label) in self.fixups.iter() {
+            let target = self.labels[label.0].expect("unbound label");
+            let rel = target as i64 - (*at as i64 + 4);
+            self.buf[*at..*at + 4].copy_from_slice(&(rel as i32).to_le_bytes());
+        }
+        self.buf
+    }
+
+    //mov r32, [m]
+    pub fn load32(&mut self, dst: Reg, m: Mem) {
+        self.op_rm(None, false, &[0x8B], dst as u8, m);
+    }
+
+    //mov r64, [m]
+    pub fn load64(&mut self, dst: Reg, m: Mem) {
+        self.op_rm(None, true, &[0x8B], dst as u8, m);
+    }
+
+    //mov [m], r64
+    pub fn store64(&mut self, m: Mem, src: Reg) {
+        self.op_rm(None, true, &[0x89], src as u8, m);
+    }
+
+    //mov qword [m], imm32 sign extended
+    pub fn store64_imm(&mut self, m: Mem, imm: i32) {
+        self.op_rm(None, true, &[0xC7], 0, m);
+        self.emit32(imm as u32);
+    }
+
+    //mov dword [m], imm32
+    pub fn store32_imm(&mut self, m: Mem, imm: u32) {
+        self.op_rm(None, false, &[0xC7], 0, m);
+        self.emit32(imm);
+    }
+
+    //mov r32, imm32
+    pub fn mov_imm32(&mut self, dst: Reg, imm: u32) {
+        self.rex(false, 0, dst as u8);
+        self.buf.push(0xB8 + (dst as u8 & 7));
+        self.emit32(imm);
+    }
+
+    //mov r64, imm64
+    pub fn mov_imm64(&mut self, dst: Reg, imm: u64) {
+        self.rex(true, 0, dst as u8);
+        self.buf.push(0xB8 + (dst as u8 & 7));
+        self.buf.extend_from_slice(&imm.to_le_bytes());
+    }
+
+    //mov r64, r64
+    pub fn mov_rr(&mut self, dst: Reg, src: Reg) {
+        self.op_rr(None, true, &[0x8B], dst as u8, src as u8);
+    }
+
+    pub fn alu32(&mut self, op: Alu, dst: Reg, m: Mem) {
+        self.op_rm(None, false, &[op.opcode()], dst as u8, m);
+    }
+
+    pub fn alu64(&mut self, op: Alu, dst: Reg, m: Mem) {
+        self.op_rm(None, true, &[op.opcode()], dst as u8, m);
+    }
+
+    //imul r, [m]
+    pub fn imul32(&mut self, dst: Reg, m: Mem) {
+        self.op_rm(None, false, &[0x0F, 0xAF], dst as u8, m);
+    }
+
+    pub fn imul64(&mut self, dst: Reg, m: Mem) {
+        self.op_rm(None, true, &[0x0F, 0xAF], dst as u8, m);
+    }
+
+    pub fn neg32(&mut self, r: Reg) {
+        self.op_rr(None, false, &[0xF7], 3, r as u8);
+    }
+
+    pub fn neg64(&mut self, r: Reg) {
+        self.op_rr(None, true, &[0xF7], 3, r as u8);
+    }
+
+    //shift r by cl
+    pub fn shift32(&mut self, op: Shift, r: Reg) {
+        self.op_rr(None, false, &[0xD3], op.ext(), r as u8);
+    }
+
+    pub fn shift64(&mut self, op: Shift, r: Reg) {
+        self.op_rr(None, true, &[0xD3], op.ext(), r as u8);
+    }
+
+    //edx:eax <- sign of eax
+    pub fn cdq(&mut self) {
+        self.buf.push(0x99);
+    }
+
+    //rdx:rax <- sign of rax
+    pub fn cqo(&mut self) {
+        self.buf.extend_from_slice(&[0x48, 0x99]);
+    }
+
+    //eax <- edx:eax / r, edx <- remainder
+    pub fn idiv32(&mut self, r: Reg) {
+        self.op_rr(None, false, &[0xF7], 7, r as u8);
+    }
+
+    pub fn idiv64(&mut self, r: Reg) {
+        self.op_rr(None, true, &[0xF7], 7, r as u8);
+    }
+
+    //movsxd r64, dword [m]
+    pub fn movsxd(&mut self, dst: Reg, m: Mem) {
+        self.op_rm(None, true, &[0x63], dst as u8, m);
+    }
+
+    //movsx r32, byte [m]
+    pub fn movsx8(&mut self, dst: Reg, m: Mem) {
+        self.op_rm(None, false, &[0x0F, 0xBE], dst as u8, m);
+    }
+
+    //movsx r32, word [m]
+    pub fn movsx16(&mut self, dst: Reg, m: Mem) {
+        self.op_rm(None, false, &[0x0F, 0xBF], dst as u8, m);
+    }
+
+    //movzx r32, word [m]
+    pub fn movzx16(&mut self, dst: Reg, m: Mem) {
+        self.op_rm(None, false, &[0x0F, 0xB7], dst as u8, m);
+    }
+
+    //movsx r32, r8, only al ~ bl
+    pub fn movsx8_rr(&mut self, dst: Reg, src: Reg) {
+        debug_assert!((src as u8) < 4);
+        self.op_rr(None, false, &[0x0F, 0xBE], dst as u8, src as u8);
+    }
+
+    //sub r8, r8, only al ~ bl
+    pub fn sub8_rr(&mut self, dst: Reg, src: Reg) {
+        debug_assert!((src as u8) < 4 && (dst as u8) < 4);
+        self.op_rr(None, false, &[0x28], src as u8, dst as u8);
+    }
+
+    //setcc r8, only al ~ bl
+    pub fn setcc(&mut self, cond: Cond, dst: Reg) {
+        debug_assert!((dst as u8) < 4);
+        self.op_rr(None, false, &[0x0F, 0x90 + cond as u8], 0, dst as u8);
+    }
+
+    //cmp r32, imm32
+    pub fn cmp32_imm(&mut self, r: Reg, imm: i32) {
+        self.op_rr(None, false, &[0x81], 7, r as u8);
+        self.emit32(imm as u32);
+    }
+
+    //cmp r64, imm32 sign extended
+    pub fn cmp64_imm(&mut self, r: Reg, imm: i32) {
+        self.op_rr(None, true, &[0x81], 7, r as u8);
+        self.emit32(imm as u32);
+    }
+
+    //cmp dword [m], imm32
+    pub fn cmp_mem32_imm(&mut self, m: Mem, imm: i32) {
+        self.op_rm(None, false, &[0x81], 7, m);
+        self.emit32(imm as u32);
+    }
+
+    //cmp byte [m], imm8
+    pub fn cmp_mem8_imm(&mut self, m: Mem, imm: u8) {
+        self.op_rm(None, false, &[0x80], 7, m);
+        self.buf.push(imm);
+    }
+
+    //add dword [m], imm32
+    pub fn add_mem32_imm(&mut self, m: Mem, imm: i32) {
+        self.op_rm(None, false, &[0x81], 0, m);
+        self.emit32(imm as u32);
+    }
+
+    //xor dword [m], imm32
+    pub fn xor_mem32_imm(&mut self, m: Mem, imm: u32) {
+        self.op_rm(None, false, &[0x81], 6, m);
+        self.emit32(imm);
+    }
+
+    //test r32, r32
+    pub fn test32(&mut self, a: Reg, b: Reg) {
+        self.op_rr(None, false, &[0x85], b as u8, a as u8);
+    }
+
+    //movss / addss ..., xmm <- [m], or [m] <- xmm for Store
+    pub fn sse_ss(&mut self, op: Sse, x: Xmm, m: Mem) {
+        self.op_rm(Some(0xF3), false, &[0x0F, op.opcode()], x.0, m);
+    }
+
+    pub fn sse_sd(&mut self, op: Sse, x: Xmm, m: Mem) {
+        self.op_rm(Some(0xF2), false, &[0x0F, op.opcode()], x.0, m);
+    }
+
+    //ucomiss xmm, dword [m]
+    pub fn ucomiss(&mut self, x: Xmm, m: Mem) {
+        self.op_rm(None, false, &[0x0F, 0x2E], x.0, m);
+    }
+
+    //ucomisd xmm, qword [m]
+    pub fn ucomisd(&mut self, x: Xmm, m: Mem) {
+        self.op_rm(Some(0x66), false, &[0x0F, 0x2E], x.0, m);
+    }
+
+    //cvtsi2ss xmm, dword/qword [m]
+    pub fn cvtsi2ss(&mut self, x: Xmm, m: Mem, is_long: bool) {
+        self.op_rm(Some(0xF3), is_long, &[0x0F, 0x2A], x.0, m);
+    }
+
+    //cvtsi2sd xmm, dword/qword [m]
+    pub fn cvtsi2sd(&mut self, x: Xmm, m: Mem, is_long: bool) {
+        self.op_rm(Some(0xF2), is_long, &[0x0F, 0x2A], x.0, m);
+    }
+
+    //cvtss2sd xmm, dword [m]
+    pub fn cvtss2sd(&mut self, x: Xmm, m: Mem) {
+        self.op_rm(Some(0xF3), false, &[0x0F, 0x5A], x.0, m);
+    }
+
+    //cvtsd2ss xmm, qword [m]
+    pub fn cvtsd2ss(&mut self, x: Xmm, m: Mem) {
+        self.op_rm(Some(0xF2), false, &[0x0F, 0x5A], x.0, m);
+    }
+
+    pub fn jcc(&mut self, cond: Cond, target: Label) {
+        self.buf.extend_from_slice(&[0x0F, 0x80 + cond as u8]);
+        self.fixup(target);
+    }
+
+    pub fn jmp(&mut self, target: Label) {
+        self.buf.push(0xE9);
+        self.fixup(target);
+    }
+
+    //call an absolute address, rax clobbered
+    pub fn call_abs(&mut self, addr: u64) {
+        self.mov_imm64(Reg::Rax, addr);
+        self.buf.extend_from_slice(&[0xFF, 0xD0]);
+    }
+
+    pub fn push(&mut self, r: Reg) {
+        self.rex(false, 0, r as u8);
+        self.buf.push(0x50 + (r as u8 & 7));
+    }
+
+    pub fn pop(&mut self, r: Reg) {
+        self.rex(false, 0, r as u8);
+        self.buf.push(0x58 + (r as u8 & 7));
+    }
+
+    pub fn ret(&mut self) {
+        self.buf.push(0xC3);
+    }
+}
+
+impl Assembler {
+    fn emit32(&mut self, v: u32) {
+        self.buf.extend_from_slice(&v.to_le_bytes());
+    }
+
+    fn fixup(&mut self, target: Label) {
+        self.fixups.push((self.buf.len(), target));
+        self.emit32(0);
+    }
+
+    //REX if needed, 'reg' of ModRM.reg & 'rm' of ModRM.rm or the opcode
+    fn rex(&mut self, w: bool, reg: u8, rm: u8) {
+        let rex = 0x40 | (w as u8) << 3 | (reg >> 3) << 2 | (rm >> 3);
+        if rex != 0x40 {
+            self.buf.push(rex);
+        }
+    }
+
+    fn op_rm(&mut self, prefix: Option<u8>, w: bool, opcode: &[u8], reg: u8, m: Mem) {
+        if let Some(p) = prefix {
+            self.buf.push(p);
+        }
+        let base = m.base as u8;
+        self.rex(w, reg, base);
+        self.buf.extend_from_slice(opcode);
+        //mod 10, [base + disp32]
+        self.buf.push(0x80 | (reg & 7) << 3 | (base & 7));
+        if base & 7 == 4 {
+            self.buf.push(0x24);
+        }
+        self.emit32(m.disp as u32);
+    }
+
+    fn op_rr(&mut self, prefix: Option<u8>, w: bool, opcode: &[u8], reg: u8, rm: u8) {
+        if let Some(p) = prefix {
+            self.buf.push(p);
+        }
+        self.rex(w, reg, rm);
+        self.buf.extend_from_slice(opcode);
+        self.buf.push(0xC0 | (reg & 7) << 3 | (rm & 7));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn t_encoding() {
+        let mut a = Assembler::new();
+        //mov rax, [r13 + 8]
+        a.load64(Reg::Rax, Mem::new(Reg::R13, 8));
+        //mov [r12 + 16], rax
+        a.store64(Mem::new(Reg::R12, 16), Reg::Rax);
+        //add eax, [rbx - 8]
+        a.alu32(Alu::Add, Reg::Rax, Mem::new(Reg::Rbx, -8));
+        //push r15
+        a.push(Reg::R15);
+        //addsd xmm0, [r13]
+        a.sse_sd(Sse::Add, Xmm(0), Mem::new(Reg::R13, 0));
+        assert_eq!(
+            a.finish(),
+            vec![
+                0x49, 0x8B, 0x85, 8, 0, 0, 0, //
+                0x49, 0x89, 0x84, 0x24, 16, 0, 0, 0, //
+                0x03, 0x83, 0xF8, 0xFF, 0xFF, 0xFF, //
+                0x41, 0x57, //
+                0xF2, 0x41, 0x0F, 0x58, 0x85, 0, 0, 0, 0,
+            ]
+        );
+    }
+
+    #[test]
+    fn t_labels() {
+        let mut a = Assembler::new();
+        let l = a.new_label();
+        a.jmp(l);
+        a.ret();
+        a.bind(l);
+        a.jcc(Cond::E, l);
+        assert_eq!(
+            a.finish(),
+            vec![0xE9, 1, 0, 0, 0, 0xC3, 0x0F, 0x84, 0xFA, 0xFF, 0xFF, 0xFF]
+        );
+    }
+}
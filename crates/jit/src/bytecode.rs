@@ -0,0 +1,455 @@
+use classfile::{constant_pool, ConstantPool, ConstantPoolType, OpCode};
+
+//an instruction, the effect on the operand stack in slots (long & double take 2)
+pub struct Insn {
+    pub pc: usize,
+    pub op: OpCode,
+    pub len: usize,
+    pub pops: usize,
+    pub pushes: usize,
+    //branch & switch targets
+    pub targets: Vec<usize>,
+    //false for goto, switches, returns & athrow
+    pub falls_through: bool,
+}
+
+pub fn decode(code: &[u8], cp: &ConstantPool) -> Result<Vec<Insn>, String> {
+    let mut insns = Vec::new();
+    let mut pc = 0;
+    while pc < code.len() {
+        let insn = decode_one(code, cp, pc)?;
+        pc += insn.len;
+        insns.push(insn);
+    }
+
+    if pc != code.len() {
+        return Err("truncated code".to_string());
+    }
+    Ok(insns)
+}
+
+fn decode_one(code: &[u8], cp: &ConstantPool, pc: usize) -> Result<Insn, String> {
+    let v = code[pc];
+    //203 ~ 253 undefined
+    if v > 202 && v < 254 {
+        return Err(format!("undefined opcode {}", v));
+    }
+
+    let op = OpCode::from(v);
+    let mut targets = Vec::new();
+    let mut falls_through = true;
+    let (len, pops, pushes) = match op {
+        OpCode::nop => (1, 0, 0),
+        OpCode::aconst_null
+        | OpCode::iconst_m1
+        | OpCode::iconst_0
+        | OpCode::iconst_1
+        | OpCode::iconst_2
+        | OpCode::iconst_3
+        | OpCode::iconst_4
+        | OpCode::iconst_5
+        | OpCode::fconst_0
+        | OpCode::fconst_1
+        | OpCode::fconst_2 => (1, 0, 1),
+        OpCode::lconst_0 | OpCode::lconst_1 | OpCode::dconst_0 | OpCode::dconst_1 => (1, 0, 2),
+        OpCode::bipush => (2, 0, 1),
+        OpCode::sipush => (3, 0, 1),
+        OpCode::ldc => (2, 0, 1),
+        OpCode::ldc_w => (3, 0, 1),
+        OpCode::ldc2_w => (3, 0, 2),
+
+        OpCode::iload | OpCode::fload | OpCode::aload => (2, 0, 1),
+        OpCode::lload | OpCode::dload => (2, 0, 2),
+        OpCode::iload_0
+        | OpCode::iload_1
+        | OpCode::iload_2
+        | OpCode::iload_3
+        | OpCode::fload_0
+        | OpCode::fload_1
+        | OpCode::fload_2
+        | OpCode::fload_3
+        | OpCode::aload_0
+        | OpCode::aload_1
+        | OpCode::aload_2
+        | OpCode::aload_3 => (1, 0, 1),
+        OpCode::lload_0
+        | OpCode::lload_1
+        | OpCode::lload_2
+        | OpCode::lload_3
+        | OpCode::dload_0
+        | OpCode::dload_1
+        | OpCode::dload_2
+        | OpCode::dload_3 => (1, 0, 2),
+        OpCode::iaload
+        | OpCode::faload
+        | OpCode::aaload
+        | OpCode::baload
+        | OpCode::caload
+        | OpCode::saload => (1, 2, 1),
+        OpCode::laload | OpCode::daload => (1, 2, 2),
+
+        OpCode::istore | OpCode::fstore | OpCode::astore => (2, 1, 0),
+        OpCode::lstore | OpCode::dstore => (2, 2, 0),
+        OpCode::istore_0
+        | OpCode::istore_1
+        | OpCode::istore_2
+        | OpCode::istore_3
+        | OpCode::fstore_0
+        | OpCode::fstore_1
+        | OpCode::fstore_2
+        | OpCode::fstore_3
+        | OpCode::astore_0
+        | OpCode::astore_1
+        | OpCode::astore_2
+        | OpCode::astore_3 => (1, 1, 0),
+        OpCode::lstore_0
+        | OpCode::lstore_1
+        | OpCode::lstore_2
+        | OpCode::lstore_3
+        | OpCode::dstore_0
+        | OpCode::dstore_1
+        | OpCode::dstore_2
+        | OpCode::dstore_3 => (1, 2, 0),
+        OpCode::iastore
+        | OpCode::fastore
+        | OpCode::aastore
+        | OpCode::bastore
+        | OpCode::castore
+        | OpCode::sastore => (1, 3, 0),
+        OpCode::lastore | OpCode::dastore => (1, 4, 0),
+
+        OpCode::pop => (1, 1, 0),
+        OpCode::pop2 => (1, 2, 0),
+        OpCode::dup => (1, 1, 2),
+        OpCode::dup_x1 => (1, 2, 3),
+        OpCode::dup_x2 => (1, 3, 4),
+        OpCode::dup2 => (1, 2, 4),
+        OpCode::dup2_x1 => (1, 3, 5),
+        OpCode::dup2_x2 => (1, 4, 6),
+        OpCode::swap => (1, 2, 2),
+
+        OpCode::iadd
+        | OpCode::isub
+        | OpCode::imul
+        | OpCode::idiv
+        | OpCode::irem
+        | OpCode::iand
+        | OpCode::ior
+        | OpCode::ixor
+        | OpCode::ishl
+        | OpCode::ishr
+        | OpCode::iushr
+        | OpCode::fadd
+        | OpCode::fsub
+        | OpCode::fmul
+        | OpCode::fdiv
+        | OpCode::frem => (1, 2, 1),
+        OpCode::ladd
+        | OpCode::lsub
+        | OpCode::lmul
+        | OpCode::ldiv
+        | OpCode::lrem
+        | OpCode::land
+        | OpCode::lor
+        | OpCode::lxor
+        | OpCode::dadd
+        | OpCode::dsub
+        | OpCode::dmul
+        | OpCode::ddiv
+        | OpCode::drem => (1, 4, 2),
+        OpCode::lshl | OpCode::lshr | OpCode::lushr => (1, 3, 2),
+        OpCode::ineg | OpCode::fneg => (1, 1, 1),
+        OpCode::lneg | OpCode::dneg => (1, 2, 2),
+        OpCode::iinc => (3, 0, 0),
+
+        OpCode::i2f | OpCode::f2i | OpCode::i2b | OpCode::i2c | OpCode::i2s => (1, 1, 1),
+        OpCode::i2l | OpCode::i2d | OpCode::f2l | OpCode::f2d => (1, 1, 2),
+        OpCode::l2i | OpCode::l2f | OpCode::d2i | OpCode::d2f => (1, 2, 1),
+        OpCode::l2d | OpCode::d2l => (1, 2, 2),
+
+        OpCode::lcmp | OpCode::dcmpl | OpCode::dcmpg => (1, 4, 1),
+        OpCode::fcmpl | OpCode::fcmpg => (1, 2, 1),
+        OpCode::ifeq
+        | OpCode::ifne
+        | OpCode::iflt
+        | OpCode::ifge
+        | OpCode::ifgt
+        | OpCode::ifle
+        | OpCode::ifnull
+        | OpCode::ifnonnull => {
+            targets.push(branch16(code, pc)?);
+            (3, 1, 0)
+        }
+        OpCode::if_icmpeq
+        | OpCode::if_icmpne
+        | OpCode::if_icmplt
+        | OpCode::if_icmpge
+        | OpCode::if_icmpgt
+        | OpCode::if_icmple
+        | OpCode::if_acmpeq
+        | OpCode::if_acmpne => {
+            targets.push(branch16(code, pc)?);
+            (3, 2, 0)
+        }
+        OpCode::goto => {
+            targets.push(branch16(code, pc)?);
+            falls_through = false;
+            (3, 0, 0)
+        }
+        OpCode::goto_w => {
+            targets.push(branch32(code, pc, pc + 1)?);
+            falls_through = false;
+            (5, 0, 0)
+        }
+        OpCode::tableswitch => {
+            let base = align4(pc);
+            let low = read_i4(code, base + 4)?;
+            let high = read_i4(code, base + 8)?;
+            if high < low {
+                return Err("bad tableswitch".to_string());
+            }
+            let n = (high as i64 - low as i64 + 1) as usize;
+            targets.push(branch32(code, pc, base)?);
+            for i in 0..n {
+                targets.push(branch32(code, pc, base + 12 + i * 4)?);
+            }
+            falls_through = false;
+            (base + 12 + n * 4 - pc, 1, 0)
+        }
+        OpCode::lookupswitch => {
+            let base = align4(pc);
+            let n = read_i4(code, base + 4)?;
+            if n < 0 {
+                return Err("bad lookupswitch".to_string());
+            }
+            let n = n as usize;
+            targets.push(branch32(code, pc, base)?);
+            for i in 0..n {
+                targets.push(branch32(code, pc, base + 12 + i * 8)?);
+            }
+            falls_through = false;
+            (base + 8 + n * 8 - pc, 1, 0)
+        }
+        OpCode::ireturn | OpCode::freturn | OpCode::areturn | OpCode::athrow => {
+            falls_through = false;
+            (1, 1, 0)
+        }
+        OpCode::lreturn | OpCode::dreturn => {
+            falls_through = false;
+            (1, 2, 0)
+        }
+        OpCode::return_void => {
+            falls_through = false;
+            (1, 0, 0)
+        }
+
+        OpCode::getstatic | OpCode::putstatic | OpCode::getfield | OpCode::putfield => {
+            let idx = read_u2(code, pc + 1)?;
+            let (_, desc) = member_name_and_type(cp, idx)?;
+            let n = field_slots(desc);
+            match op {
+                OpCode::getstatic => (3, 0, n),
+                OpCode::putstatic => (3, n, 0),
+                OpCode::getfield => (3, 1, n),
+                _ => (3, 1 + n, 0),
+            }
+        }
+        OpCode::invokevirtual
+        | OpCode::invokespecial
+        | OpCode::invokestatic
+        | OpCode::invokeinterface => {
+            let idx = read_u2(code, pc + 1)?;
+            let (_, desc) = member_name_and_type(cp, idx)?;
+            let (args, ret) = method_slots(desc)?;
+            let len = if op == OpCode::invokeinterface { 5 } else { 3 };
+            let this = if op == OpCode::invokestatic { 0 } else { 1 };
+            (len, args + this, ret)
+        }
+        OpCode::invokedynamic => {
+            let idx = read_u2(code, pc + 1)?;
+            let desc = match cp.get(idx) {
+                Some(ConstantPoolType::InvokeDynamic {
+                    name_and_type_index,
+                    ..
+                }) => constant_pool::get_name_and_type(cp, *name_and_type_index as usize).1,
+                _ => return Err("bad invokedynamic".to_string()),
+            };
+            let (args, ret) = method_slots(desc)?;
+            (5, args, ret)
+        }
+        OpCode::new => (3, 0, 1),
+        OpCode::newarray => (2, 1, 1),
+        OpCode::anewarray | OpCode::checkcast | OpCode::instanceof => (3, 1, 1),
+        OpCode::arraylength => (1, 1, 1),
+        OpCode::monitorenter | OpCode::monitorexit => (1, 1, 0),
+        OpCode::multianewarray => {
+            let dims = *code.get(pc + 3).ok_or("truncated code")? as usize;
+            (4, dims, 1)
+        }
+
+        //wide, jsr, ret, jsr_w, breakpoint, impdep1 & impdep2
+        _ => return Err(format!("unsupported opcode {:?}", op)),
+    };
+
+    if pc + len > code.len() {
+        return Err("truncated code".to_string());
+    }
+
+    Ok(Insn {
+        pc,
+        op,
+        len,
+        pops,
+        pushes,
+        targets,
+        falls_through,
+    })
+}
+
+//slots of a field descriptor, or the return type
+pub fn field_slots(desc: &[u8]) -> usize {
+    match desc.first() {
+        Some(b'J') | Some(b'D') => 2,
+        Some(b'V') => 0,
+        _ => 1,
+    }
+}
+
+//(slots of args, slots of the return)
+fn method_slots(desc: &[u8]) -> Result<(usize, usize), String> {
+    let err = || "bad method descriptor".to_string();
+    if desc.first() != Some(&b'(') {
+        return Err(err());
+    }
+
+    let mut i = 1;
+    let mut args = 0;
+    loop {
+        match desc.get(i) {
+            Some(b')') => break,
+            Some(b'J') | Some(b'D') => {
+                args += 2;
+                i += 1;
+            }
+            Some(b'L') => {
+                args += 1;
+                i += desc[i..].iter().position(|&c| c == b';').ok_or_else(err)? + 1;
+            }
+            Some(b'[') => {
+                args += 1;
+                while desc.get(i) == Some(&b'[') {
+                    i += 1;
+                }
+                if desc.get(i) == Some(&b'L') {
+                    i += desc[i..].iter().position(|&c| c == b';').ok_or_else(err)?;
+                }
+                i += 1;
+            }
+            Some(_) => {
+                args += 1;
+                i += 1;
+            }
+            None => return Err(err()),
+        }
+    }
+
+    Ok((args, field_slots(&desc[i + 1..])))
+}
+
+//(name, desc) of a field or method ref
+fn member_name_and_type(cp: &ConstantPool, idx: usize) -> Result<(&[u8], &[u8]), String> {
+    let nt = match cp.get(idx) {
+        Some(ConstantPoolType::FieldRef {
+            name_and_type_index,
+            ..
+        })
+        | Some(ConstantPoolType::MethodRef {
+            name_and_type_index,
+            ..
+        })
+        | Some(ConstantPoolType::InterfaceMethodRef {
+            name_and_type_index,
+            ..
+        }) => *name_and_type_index as usize,
+        _ => return Err(format!("bad member ref #{}", idx)),
+    };
+    match cp.get(nt) {
+        Some(ConstantPoolType::NameAndType { .. }) => {
+            let (name, desc) = constant_pool::get_name_and_type(cp, nt);
+            Ok((name.as_slice(), desc.as_slice()))
+        }
+        _ => Err(format!("bad name and type #{}", nt)),
+    }
+}
+
+//the operands of switches start at a multiple of 4
+fn align4(pc: usize) -> usize {
+    (pc + 1 + 3) & !3
+}
+
+pub fn read_u2(code: &[u8], at: usize) -> Result<usize, String> {
+    match code.get(at..at + 2) {
+        Some(v) => Ok((v[0] as usize) << 8 | v[1] as usize),
+        None => Err("truncated code".to_string()),
+    }
+}
+
+pub fn read_i4(code: &[u8], at: usize) -> Result<i32, String> {
+    match code.get(at..at + 4) {
+        Some(v) => Ok(i32::from_be_bytes([v[0], v[1], v[2], v[3]])),
+        None => Err("truncated code".to_string()),
+    }
+}
+
+fn branch16(code: &[u8], pc: usize) -> Result<usize, String> {
+    let offset = read_u2(code, pc + 1)? as u16 as i16;
+    target(code, pc, offset as i64)
+}
+
+fn branch32(code: &[u8], pc: usize, at: usize) -> Result<usize, String> {
+    let offset = read_i4(code, at)?;
+    target(code, pc, offset as i64)
+}
+
+fn target(code: &[u8], pc: usize, offset: i64) -> Result<usize, String> {
+    let target = pc as i64 + offset;
+    if target < 0 || target as usize >= code.len() {
+        return Err(format!("branch out of code at {}", pc));
+    }
+    Ok(target as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn t_method_slots() {
+        assert_eq!(method_slots(b"()V"), Ok((0, 0)));
+        assert_eq!(method_slots(b"(IJ)D"), Ok((3, 2)));
+        assert_eq!(
+            method_slots(b"([[Ljava/lang/String;[JLjava/lang/Object;F)Ljava/lang/String;"),
+            Ok((4, 1))
+        );
+    }
+
+    #[test]
+    fn t_switch_len() {
+        let cp = std::sync::Arc::new(vec![]);
+        //nop; tableswitch 0..1, padded to 4
+        let code = [
+            0x00, 0xAA, 0, 0, //
+            0, 0, 0, 23, //default
+            0, 0, 0, 0, //low
+            0, 0, 0, 1, //high
+            0, 0, 0, 23, //
+            0, 0, 0, 23,   //
+            0xB1, //return
+        ];
+        let insns = decode(&code, &cp).unwrap();
+        assert_eq!(insns.len(), 3);
+        assert_eq!(insns[1].len, 23);
+        assert_eq!(insns[1].targets, vec![24, 24, 24]);
+        assert_eq!(insns[2].pc, 24);
+    }
+}
@@ -0,0 +1,154 @@
+use crate::{Context, Exit};
+use std::ptr;
+use std::sync::atomic::{AtomicPtr, AtomicU32, AtomicU8, Ordering};
+
+//machine code of a method, in memory mapped executable
+pub struct CompiledMethod {
+    mem: *mut u8,
+    mem_len: usize,
+    code_len: usize,
+    //stack depth at the entries, by pc; u32::MAX if not an entry
+    entries: Vec<u32>,
+}
+
+unsafe impl Send for CompiledMethod {}
+unsafe impl Sync for CompiledMethod {}
+
+type EntryFn = unsafe extern "C" fn(*mut Context) -> u32;
+
+impl CompiledMethod {
+    pub(crate) fn new(code: Vec<u8>, entries: Vec<u32>) -> Result<Self, String> {
+        let page = unsafe { libc::sysconf(libc::_SC_PAGESIZE) } as usize;
+        let mem_len = (code.len() + page - 1) & !(page - 1);
+        let mem = unsafe {
+            libc::mmap(
+                ptr::null_mut(),
+                mem_len,
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_PRIVATE | libc::MAP_ANONYMOUS,
+                -1,
+                0,
+            )
+        };
+        if mem == libc::MAP_FAILED {
+            return Err("mmap failed".to_string());
+        }
+
+        let mem = mem as *mut u8;
+        unsafe {
+            ptr::copy_nonoverlapping(code.as_ptr(), mem, code.len());
+            if libc::mprotect(
+                mem as *mut libc::c_void,
+                mem_len,
+                libc::PROT_READ | libc::PROT_EXEC,
+            ) != 0
+            {
+                libc::munmap(mem as *mut libc::c_void, mem_len);
+                return Err("mprotect failed".to_string());
+            }
+        }
+
+        Ok(Self {
+            mem,
+            mem_len,
+            code_len: code.len(),
+            entries,
+        })
+    }
+
+    pub fn code_len(&self) -> usize {
+        self.code_len
+    }
+
+    //the stack depth expected, if the compiled code can be entered at 'pc'
+    pub fn entry_depth(&self, pc: usize) -> Option<usize> {
+        match self.entries.get(pc) {
+            Some(&depth) if depth != u32::MAX => Some(depth as usize),
+            _ => None,
+        }
+    }
+
+    /// Runs from ctx.pc, the stack of the depth given by entry_depth
+    ///
+    /// # Safety
+    ///
+    /// The slots of ctx are of the frame of the method compiled, ctx.env as
+    /// the helpers of Runtime expect
+    pub unsafe fn run(&self, ctx: &mut Context) -> Exit {
+        debug_assert!(self.entry_depth(ctx.pc as usize) == Some(ctx.sp as usize));
+        let f: EntryFn = std::mem::transmute(self.mem);
+        Exit::from(f(ctx))
+    }
+}
+
+impl Drop for CompiledMethod {
+    fn drop(&mut self) {
+        unsafe {
+            libc::munmap(self.mem as *mut libc::c_void, self.mem_len);
+        }
+    }
+}
+
+const NOT_COMPILED: u8 = 0;
+const COMPILING: u8 = 1;
+const COMPILED: u8 = 2;
+const FAILED: u8 = 3;
+
+//counters of a method, and the code once compiled
+#[derive(Default)]
+pub struct MethodState {
+    invocations: AtomicU32,
+    backedges: AtomicU32,
+    state: AtomicU8,
+    code: AtomicPtr<CompiledMethod>,
+}
+
+impl MethodState {
+    //invocations & back edges counted
+    pub fn count_invocation(&self) -> u32 {
+        let n = self.invocations.fetch_add(1, Ordering::Relaxed);
+        n.saturating_add(self.backedges.load(Ordering::Relaxed))
+    }
+
+    pub fn count_backedge(&self) -> u32 {
+        let n = self.backedges.fetch_add(1, Ordering::Relaxed);
+        n.saturating_add(self.invocations.load(Ordering::Relaxed))
+    }
+
+    pub fn code(&self) -> Option<&CompiledMethod> {
+        let code = self.code.load(Ordering::Acquire);
+        if code.is_null() {
+            None
+        } else {
+            Some(unsafe { &*code })
+        }
+    }
+
+    //true for the one thread to compile it, others keep interpreting
+    pub fn begin_compile(&self) -> bool {
+        self.state
+            .compare_exchange(NOT_COMPILED, COMPILING, Ordering::AcqRel, Ordering::Relaxed)
+            .is_ok()
+    }
+
+    //None if failed, never tried again
+    pub fn install(&self, code: Option<CompiledMethod>) {
+        match code {
+            Some(code) => {
+                let code = Box::into_raw(Box::new(code));
+                self.code.store(code, Ordering::Release);
+                self.state.store(COMPILED, Ordering::Release);
+            }
+            None => self.state.store(FAILED, Ordering::Release),
+        }
+    }
+}
+
+impl Drop for MethodState {
+    fn drop(&mut self) {
+        let code = *self.code.get_mut();
+        if !code.is_null() {
+            let _ = unsafe { Box::from_raw(code) };
+        }
+    }
+}
@@ -0,0 +1,742 @@
+use crate::asm::{Alu, Assembler, Cond, Label, Mem, Reg, Shift, Sse, Xmm};
+use crate::bytecode::{self, Insn};
+use crate::code::CompiledMethod;
+use crate::{Exit, Runtime};
+use classfile::{ConstantPool, ConstantPoolType, OpCode};
+
+/*
+Baseline compiler, one template for each instruction
+
+The compiled code works on the slots of the frame, as the interpreter does:
+the stack depth at each pc is known at compile time (JVMS 4.10), so the
+operand stack is never kept in registers and the state at any pc is the one
+of the interpreter, which is what makes deoptimization cheap.
+
+Registers:
+  rbx  Context
+  r12  locals
+  r13  operand stack
+  r14  reference map of locals
+
+int, long, float & double arithmetic, locals, constants, compares & branches
+are inline. Others, references, fields, calls, arrays..., are executed by the
+interpreter through Runtime::step, not compiled: the fields of an object are
+behind its lock in the vm, at no offset the code could address, and a call
+needs a frame of the interpreter. Deoptimized, back to the interpreter:
+  - a local holding a reference accessed as a primitive (reflection passes
+    boxed values)
+  - a divisor 0 or -1, ArithmeticException & overflow left to the interpreter
+  - control transferred by Runtime::step, a branch or an exception
+*/
+
+//offsets in Context
+const CTX_LOCALS: i32 = 0;
+const CTX_LOCAL_REFS: i32 = 8;
+const CTX_STACK: i32 = 16;
+const CTX_PC: i32 = 24;
+const CTX_SP: i32 = 28;
+const CTX_RET: i32 = 32;
+
+const NOT_ENTRY: u32 = u32::MAX;
+
+//what to compile
+pub struct Bytecode<'a> {
+    pub code: &'a [u8],
+    pub cp: &'a ConstantPool,
+    pub max_locals: usize,
+    pub max_stack: usize,
+}
+
+pub fn compile(m: &Bytecode, rt: &Runtime) -> Result<CompiledMethod, String> {
+    if !crate::is_supported() {
+        return Err("unsupported platform".to_string());
+    }
+
+    let insns = bytecode::decode(m.code, m.cp)?;
+    let depths = stack_depths(m, &insns)?;
+
+    let mut c = Compiler {
+        m,
+        rt,
+        asm: Assembler::new(),
+        labels: vec![None; m.code.len()],
+        deopts: Vec::new(),
+        exit: None,
+    };
+    let exit = c.asm.new_label();
+    c.exit = Some(exit);
+
+    //entries: pc 0, and every branch target for OSR
+    let mut entries = vec![NOT_ENTRY; m.code.len()];
+    entries[0] = 0;
+    for insn in insns.iter() {
+        if depths[insn.pc].is_some() {
+            for &t in insn.targets.iter() {
+                entries[t] = depths[t].unwrap();
+            }
+        }
+    }
+
+    c.prologue(&entries);
+    for insn in insns.iter() {
+        if let Some(sp) = depths[insn.pc] {
+            let label = c.label(insn.pc);
+            c.asm.bind(label);
+            c.emit(insn, sp as i32)?;
+        }
+    }
+    c.epilogue();
+
+    CompiledMethod::new(c.asm.finish(), entries)
+}
+
+//the depth at each pc reachable from 0, None if not reachable
+fn stack_depths(m: &Bytecode, insns: &[Insn]) -> Result<Vec<Option<u32>>, String> {
+    let mut index = vec![usize::MAX; m.code.len()];
+    for (i, insn) in insns.iter().enumerate() {
+        index[insn.pc] = i;
+    }
+
+    let mut depths = vec![None; m.code.len()];
+    let mut work = vec![(0, 0)];
+    while let Some((pc, depth)) = work.pop() {
+        let i = index[pc];
+        if i == usize::MAX {
+            return Err(format!("branch into an instruction at {}", pc));
+        }
+        match depths[pc] {
+            Some(d) if d == depth => continue,
+            Some(_) => return Err(format!("inconsistent stack depth at {}", pc)),
+            None => depths[pc] = Some(depth),
+        }
+
+        let insn = &insns[i];
+        if (depth as usize) < insn.pops {
+            return Err(format!("stack underflow at {}", pc));
+        }
+        let after = depth as usize - insn.pops + insn.pushes;
+        if after > m.max_stack {
+            return Err(format!("stack overflow at {}", pc));
+        }
+        for &t in insn.targets.iter() {
+            work.push((t, after as u32));
+        }
+        if insn.falls_through {
+            if pc + insn.len >= m.code.len() {
+                return Err("falling off the code".to_string());
+            }
+            work.push((pc + insn.len, after as u32));
+        }
+    }
+
+    Ok(depths)
+}
+
+struct Compiler<'a> {
+    m: &'a Bytecode<'a>,
+    rt: &'a Runtime,
+    asm: Assembler,
+    labels: Vec<Option<Label>>,
+    //(stub, pc, sp)
+    deopts: Vec<(Label, u32, u32)>,
+    exit: Option<Label>,
+}
+
+fn stack(i: i32) -> Mem {
+    Mem::new(Reg::R13, i * 8)
+}
+
+fn local(i: usize) -> Mem {
+    Mem::new(Reg::R12, i as i32 * 8)
+}
+
+impl<'a> Compiler<'a> {
+    fn label(&mut self, pc: usize) -> Label {
+        match self.labels[pc] {
+            Some(l) => l,
+            None => {
+                let l = self.asm.new_label();
+                self.labels[pc] = Some(l);
+                l
+            }
+        }
+    }
+
+    fn exit(&self) -> Label {
+        self.exit.unwrap()
+    }
+
+    fn prologue(&mut self, entries: &[u32]) {
+        let a = &mut self.asm;
+        //5 pushes, rsp aligned to 16 for calls
+        a.push(Reg::Rbx);
+        a.push(Reg::R12);
+        a.push(Reg::R13);
+        a.push(Reg::R14);
+        a.push(Reg::R15);
+        a.mov_rr(Reg::Rbx, Reg::Rdi);
+        a.load64(Reg::R12, Mem::new(Reg::Rbx, CTX_LOCALS));
+        a.load64(Reg::R14, Mem::new(Reg::Rbx, CTX_LOCAL_REFS));
+        a.load64(Reg::R13, Mem::new(Reg::Rbx, CTX_STACK));
+
+        //dispatch by the entry pc
+        self.asm.load32(Reg::Rax, Mem::new(Reg::Rbx, CTX_PC));
+        for (pc, &depth) in entries.iter().enumerate() {
+            if depth != NOT_ENTRY {
+                let l = self.label(pc);
+                self.asm.cmp32_imm(Reg::Rax, pc as i32);
+                self.asm.jcc(Cond::E, l);
+            }
+        }
+        //not an entry, ctx.pc & ctx.sp as they are
+        self.asm.mov_imm32(Reg::Rax, Exit::Deopt as u32);
+        let exit = self.exit();
+        self.asm.jmp(exit);
+    }
+
+    fn epilogue(&mut self) {
+        let deopts = std::mem::take(&mut self.deopts);
+        let exit = self.exit();
+        for (stub, pc, sp) in deopts {
+            self.asm.bind(stub);
+            self.asm.store32_imm(Mem::new(Reg::Rbx, CTX_PC), pc);
+            self.asm.store32_imm(Mem::new(Reg::Rbx, CTX_SP), sp);
+            self.asm.mov_imm32(Reg::Rax, Exit::Deopt as u32);
+            self.asm.jmp(exit);
+        }
+
+        let a = &mut self.asm;
+        a.bind(exit);
+        a.pop(Reg::R15);
+        a.pop(Reg::R14);
+        a.pop(Reg::R13);
+        a.pop(Reg::R12);
+        a.pop(Reg::Rbx);
+        a.ret();
+    }
+
+    //a stub back to the interpreter, at 'pc' with the stack of depth 'sp'
+    fn deopt_stub(&mut self, pc: usize, sp: i32) -> Label {
+        let stub = self.asm.new_label();
+        self.deopts.push((stub, pc as u32, sp as u32));
+        stub
+    }
+
+    //deoptimized if the local holds a reference
+    fn check_primitive(&mut self, insn: &Insn, sp: i32, n: usize) -> Result<(), String> {
+        if n >= self.m.max_locals {
+            return Err(format!("bad local {} at {}", n, insn.pc));
+        }
+        let stub = self.deopt_stub(insn.pc, sp);
+        self.asm.cmp_mem8_imm(Mem::new(Reg::R14, n as i32), 0);
+        self.asm.jcc(Cond::NE, stub);
+        Ok(())
+    }
+
+    fn call_step(&mut self, insn: &Insn, sp: i32) {
+        let exit = self.exit();
+        let a = &mut self.asm;
+        a.mov_rr(Reg::Rdi, Reg::Rbx);
+        a.mov_imm32(Reg::Rsi, insn.pc as u32);
+        a.mov_imm32(Reg::Rdx, sp as u32);
+        a.mov_imm32(Reg::Rcx, (insn.pc + insn.len) as u32);
+        a.call_abs(self.rt.step as usize as u64);
+        a.test32(Reg::Rax, Reg::Rax);
+        a.jcc(Cond::NE, exit);
+    }
+
+    fn return_value(&mut self, sp: i32, has_value: bool) {
+        if has_value {
+            self.asm.load64(Reg::Rax, stack(sp - 1));
+            self.asm.store64(Mem::new(Reg::Rbx, CTX_RET), Reg::Rax);
+        }
+        self.asm.mov_imm32(Reg::Rax, Exit::Return as u32);
+        let exit = self.exit();
+        self.asm.jmp(exit);
+    }
+
+    fn emit(&mut self, insn: &Insn, s: i32) -> Result<(), String> {
+        let code = self.m.code;
+        let pc = insn.pc;
+        match insn.op {
+            OpCode::nop => (),
+            OpCode::iconst_m1
+            | OpCode::iconst_0
+            | OpCode::iconst_1
+            | OpCode::iconst_2
+            | OpCode::iconst_3
+            | OpCode::iconst_4
+            | OpCode::iconst_5 => {
+                let v = insn.op as i32 - OpCode::iconst_0 as i32;
+                self.asm.store64_imm(stack(s), v);
+            }
+            OpCode::bipush => self.asm.store64_imm(stack(s), code[pc + 1] as i8 as i32),
+            OpCode::sipush => {
+                let v = bytecode::read_u2(code, pc + 1)? as u16 as i16;
+                self.asm.store64_imm(stack(s), v as i32);
+            }
+            OpCode::fconst_0 | OpCode::fconst_1 | OpCode::fconst_2 => {
+                let v = (insn.op as u8 - OpCode::fconst_0 as u8) as f32;
+                self.asm.store64_imm(stack(s), v.to_bits() as i32);
+            }
+            OpCode::lconst_0 | OpCode::lconst_1 => {
+                let v = insn.op as i32 - OpCode::lconst_0 as i32;
+                self.asm.store64_imm(stack(s + 1), v);
+            }
+            OpCode::dconst_0 | OpCode::dconst_1 => {
+                let v = (insn.op as u8 - OpCode::dconst_0 as u8) as f64;
+                self.asm.mov_imm64(Reg::Rax, v.to_bits());
+                self.asm.store64(stack(s + 1), Reg::Rax);
+            }
+            OpCode::ldc | OpCode::ldc_w | OpCode::ldc2_w => {
+                let idx = if insn.op == OpCode::ldc {
+                    code[pc + 1] as usize
+                } else {
+                    bytecode::read_u2(code, pc + 1)?
+                };
+                match self.m.cp.get(idx) {
+                    Some(ConstantPoolType::Integer { v }) | Some(ConstantPoolType::Float { v }) => {
+                        self.asm.store64_imm(stack(s), i32::from_be_bytes(*v));
+                    }
+                    Some(ConstantPoolType::Long { v }) | Some(ConstantPoolType::Double { v }) => {
+                        self.asm.mov_imm64(Reg::Rax, u64::from_be_bytes(*v));
+                        self.asm.store64(stack(s + 1), Reg::Rax);
+                    }
+                    //String, Class, MethodType & MethodHandle
+                    _ => self.call_step(insn, s),
+                }
+            }
+
+            OpCode::iload | OpCode::fload => self.load(insn, s, code[pc + 1] as usize, 1)?,
+            OpCode::lload | OpCode::dload => self.load(insn, s, code[pc + 1] as usize, 2)?,
+            OpCode::iload_0 | OpCode::iload_1 | OpCode::iload_2 | OpCode::iload_3 => {
+                let n = insn.op as usize - OpCode::iload_0 as usize;
+                self.load(insn, s, n, 1)?;
+            }
+            OpCode::fload_0 | OpCode::fload_1 | OpCode::fload_2 | OpCode::fload_3 => {
+                let n = insn.op as usize - OpCode::fload_0 as usize;
+                self.load(insn, s, n, 1)?;
+            }
+            OpCode::lload_0 | OpCode::lload_1 | OpCode::lload_2 | OpCode::lload_3 => {
+                let n = insn.op as usize - OpCode::lload_0 as usize;
+                self.load(insn, s, n, 2)?;
+            }
+            OpCode::dload_0 | OpCode::dload_1 | OpCode::dload_2 | OpCode::dload_3 => {
+                let n = insn.op as usize - OpCode::dload_0 as usize;
+                self.load(insn, s, n, 2)?;
+            }
+            OpCode::istore | OpCode::fstore | OpCode::lstore | OpCode::dstore => {
+                self.store(insn, s, code[pc + 1] as usize)?
+            }
+            OpCode::istore_0 | OpCode::istore_1 | OpCode::istore_2 | OpCode::istore_3 => {
+                let n = insn.op as usize - OpCode::istore_0 as usize;
+                self.store(insn, s, n)?;
+            }
+            OpCode::fstore_0 | OpCode::fstore_1 | OpCode::fstore_2 | OpCode::fstore_3 => {
+                let n = insn.op as usize - OpCode::fstore_0 as usize;
+                self.store(insn, s, n)?;
+            }
+            OpCode::lstore_0 | OpCode::lstore_1 | OpCode::lstore_2 | OpCode::lstore_3 => {
+                let n = insn.op as usize - OpCode::lstore_0 as usize;
+                self.store(insn, s, n)?;
+            }
+            OpCode::dstore_0 | OpCode::dstore_1 | OpCode::dstore_2 | OpCode::dstore_3 => {
+                let n = insn.op as usize - OpCode::dstore_0 as usize;
+                self.store(insn, s, n)?;
+            }
+
+            OpCode::iadd => self.int_op(s, Alu::Add),
+            OpCode::isub => self.int_op(s, Alu::Sub),
+            OpCode::iand => self.int_op(s, Alu::And),
+            OpCode::ior => self.int_op(s, Alu::Or),
+            OpCode::ixor => self.int_op(s, Alu::Xor),
+            OpCode::imul => {
+                self.asm.load32(Reg::Rax, stack(s - 2));
+                self.asm.imul32(Reg::Rax, stack(s - 1));
+                self.asm.store64(stack(s - 2), Reg::Rax);
+            }
+            OpCode::ladd => self.long_op(s, Alu::Add),
+            OpCode::lsub => self.long_op(s, Alu::Sub),
+            OpCode::land => self.long_op(s, Alu::And),
+            OpCode::lor => self.long_op(s, Alu::Or),
+            OpCode::lxor => self.long_op(s, Alu::Xor),
+            OpCode::lmul => {
+                self.asm.load64(Reg::Rax, stack(s - 3));
+                self.asm.imul64(Reg::Rax, stack(s - 1));
+                self.asm.store64(stack(s - 3), Reg::Rax);
+            }
+            OpCode::idiv | OpCode::irem => {
+                let stub = self.deopt_stub(pc, s);
+                let a = &mut self.asm;
+                a.load32(Reg::Rcx, stack(s - 1));
+                a.cmp32_imm(Reg::Rcx, 0);
+                a.jcc(Cond::E, stub);
+                a.cmp32_imm(Reg::Rcx, -1);
+                a.jcc(Cond::E, stub);
+                a.load32(Reg::Rax, stack(s - 2));
+                a.cdq();
+                a.idiv32(Reg::Rcx);
+                let r = if insn.op == OpCode::idiv {
+                    Reg::Rax
+                } else {
+                    Reg::Rdx
+                };
+                a.store64(stack(s - 2), r);
+            }
+            OpCode::ldiv | OpCode::lrem => {
+                let stub = self.deopt_stub(pc, s);
+                let a = &mut self.asm;
+                a.load64(Reg::Rcx, stack(s - 1));
+                a.cmp64_imm(Reg::Rcx, 0);
+                a.jcc(Cond::E, stub);
+                a.cmp64_imm(Reg::Rcx, -1);
+                a.jcc(Cond::E, stub);
+                a.load64(Reg::Rax, stack(s - 3));
+                a.cqo();
+                a.idiv64(Reg::Rcx);
+                let r = if insn.op == OpCode::ldiv {
+                    Reg::Rax
+                } else {
+                    Reg::Rdx
+                };
+                a.store64(stack(s - 3), r);
+            }
+            OpCode::ineg => {
+                self.asm.load32(Reg::Rax, stack(s - 1));
+                self.asm.neg32(Reg::Rax);
+                self.asm.store64(stack(s - 1), Reg::Rax);
+            }
+            OpCode::lneg => {
+                self.asm.load64(Reg::Rax, stack(s - 1));
+                self.asm.neg64(Reg::Rax);
+                self.asm.store64(stack(s - 1), Reg::Rax);
+            }
+            OpCode::ishl => self.int_shift(s, Shift::Shl),
+            OpCode::ishr => self.int_shift(s, Shift::Sar),
+            OpCode::iushr => self.int_shift(s, Shift::Shr),
+            OpCode::lshl => self.long_shift(s, Shift::Shl),
+            OpCode::lshr => self.long_shift(s, Shift::Sar),
+            OpCode::lushr => self.long_shift(s, Shift::Shr),
+            OpCode::iinc => {
+                let n = code[pc + 1] as usize;
+                self.check_primitive(insn, s, n)?;
+                self.asm.add_mem32_imm(local(n), code[pc + 2] as i8 as i32);
+            }
+
+            OpCode::fadd => self.float_op(s, Sse::Add),
+            OpCode::fsub => self.float_op(s, Sse::Sub),
+            OpCode::fmul => self.float_op(s, Sse::Mul),
+            OpCode::fdiv => self.float_op(s, Sse::Div),
+            OpCode::dadd => self.double_op(s, Sse::Add),
+            OpCode::dsub => self.double_op(s, Sse::Sub),
+            OpCode::dmul => self.double_op(s, Sse::Mul),
+            OpCode::ddiv => self.double_op(s, Sse::Div),
+            OpCode::fneg => self.asm.xor_mem32_imm(stack(s - 1), 0x8000_0000),
+            OpCode::dneg => {
+                //the sign in the high half
+                let m = stack(s - 1);
+                self.asm
+                    .xor_mem32_imm(Mem::new(m.base, m.disp + 4), 0x8000_0000);
+            }
+
+            OpCode::i2l => {
+                self.asm.movsxd(Reg::Rax, stack(s - 1));
+                self.asm.store64(stack(s), Reg::Rax);
+            }
+            OpCode::l2i => {
+                self.asm.load32(Reg::Rax, stack(s - 1));
+                self.asm.store64(stack(s - 2), Reg::Rax);
+            }
+            OpCode::i2b => {
+                self.asm.movsx8(Reg::Rax, stack(s - 1));
+                self.asm.store64(stack(s - 1), Reg::Rax);
+            }
+            OpCode::i2c => {
+                self.asm.movzx16(Reg::Rax, stack(s - 1));
+                self.asm.store64(stack(s - 1), Reg::Rax);
+            }
+            OpCode::i2s => {
+                self.asm.movsx16(Reg::Rax, stack(s - 1));
+                self.asm.store64(stack(s - 1), Reg::Rax);
+            }
+            OpCode::i2f => {
+                self.asm.cvtsi2ss(Xmm(0), stack(s - 1), false);
+                self.asm.sse_ss(Sse::Store, Xmm(0), stack(s - 1));
+            }
+            OpCode::i2d => {
+                self.asm.cvtsi2sd(Xmm(0), stack(s - 1), false);
+                self.asm.sse_sd(Sse::Store, Xmm(0), stack(s));
+            }
+            OpCode::l2f => {
+                self.asm.cvtsi2ss(Xmm(0), stack(s - 1), true);
+                self.asm.sse_ss(Sse::Store, Xmm(0), stack(s - 2));
+            }
+            OpCode::l2d => {
+                self.asm.cvtsi2sd(Xmm(0), stack(s - 1), true);
+                self.asm.sse_sd(Sse::Store, Xmm(0), stack(s - 1));
+            }
+            OpCode::f2d => {
+                self.asm.cvtss2sd(Xmm(0), stack(s - 1));
+                self.asm.sse_sd(Sse::Store, Xmm(0), stack(s));
+            }
+            OpCode::d2f => {
+                self.asm.cvtsd2ss(Xmm(0), stack(s - 1));
+                self.asm.sse_ss(Sse::Store, Xmm(0), stack(s - 2));
+            }
+
+            OpCode::lcmp => {
+                let a = &mut self.asm;
+                a.load64(Reg::Rax, stack(s - 3));
+                a.alu64(Alu::Cmp, Reg::Rax, stack(s - 1));
+                a.setcc(Cond::G, Reg::Rcx);
+                a.setcc(Cond::L, Reg::Rdx);
+                a.sub8_rr(Reg::Rcx, Reg::Rdx);
+                a.movsx8_rr(Reg::Rax, Reg::Rcx);
+                a.store64(stack(s - 4), Reg::Rax);
+            }
+            OpCode::fcmpl | OpCode::fcmpg => {
+                let nan = if insn.op == OpCode::fcmpl { -1 } else { 1 };
+                self.asm.sse_ss(Sse::Load, Xmm(0), stack(s - 2));
+                self.asm.ucomiss(Xmm(0), stack(s - 1));
+                self.float_compare(nan, stack(s - 2));
+            }
+            OpCode::dcmpl | OpCode::dcmpg => {
+                let nan = if insn.op == OpCode::dcmpl { -1 } else { 1 };
+                self.asm.sse_sd(Sse::Load, Xmm(0), stack(s - 3));
+                self.asm.ucomisd(Xmm(0), stack(s - 1));
+                self.float_compare(nan, stack(s - 4));
+            }
+
+            OpCode::ifeq
+            | OpCode::ifne
+            | OpCode::iflt
+            | OpCode::ifge
+            | OpCode::ifgt
+            | OpCode::ifle => {
+                let cond = cond_of(insn.op as u8 - OpCode::ifeq as u8);
+                let target = self.label(insn.targets[0]);
+                self.asm.cmp_mem32_imm(stack(s - 1), 0);
+                self.asm.jcc(cond, target);
+            }
+            OpCode::if_icmpeq
+            | OpCode::if_icmpne
+            | OpCode::if_icmplt
+            | OpCode::if_icmpge
+            | OpCode::if_icmpgt
+            | OpCode::if_icmple => {
+                let cond = cond_of(insn.op as u8 - OpCode::if_icmpeq as u8);
+                let target = self.label(insn.targets[0]);
+                self.asm.load32(Reg::Rax, stack(s - 2));
+                self.asm.alu32(Alu::Cmp, Reg::Rax, stack(s - 1));
+                self.asm.jcc(cond, target);
+            }
+            OpCode::goto | OpCode::goto_w => {
+                let target = self.label(insn.targets[0]);
+                self.asm.jmp(target);
+            }
+
+            OpCode::ireturn | OpCode::freturn | OpCode::lreturn | OpCode::dreturn => {
+                self.return_value(s, true)
+            }
+            OpCode::return_void => self.return_value(s, false),
+
+            //f2i, f2l, d2i, d2l & frem, drem, of the rounding rules of Java
+            _ => self.call_step(insn, s),
+        }
+
+        Ok(())
+    }
+
+    //a local of 'n' slots pushed
+    fn load(&mut self, insn: &Insn, s: i32, n: usize, slots: i32) -> Result<(), String> {
+        self.check_primitive(insn, s, n)?;
+        self.asm.load64(Reg::Rax, local(n));
+        self.asm.store64(stack(s + slots - 1), Reg::Rax);
+        Ok(())
+    }
+
+    fn store(&mut self, insn: &Insn, s: i32, n: usize) -> Result<(), String> {
+        self.check_primitive(insn, s, n)?;
+        self.asm.load64(Reg::Rax, stack(s - 1));
+        self.asm.store64(local(n), Reg::Rax);
+        Ok(())
+    }
+
+    fn int_op(&mut self, s: i32, op: Alu) {
+        self.asm.load32(Reg::Rax, stack(s - 2));
+        self.asm.alu32(op, Reg::Rax, stack(s - 1));
+        self.asm.store64(stack(s - 2), Reg::Rax);
+    }
+
+    fn long_op(&mut self, s: i32, op: Alu) {
+        self.asm.load64(Reg::Rax, stack(s - 3));
+        self.asm.alu64(op, Reg::Rax, stack(s - 1));
+        self.asm.store64(stack(s - 3), Reg::Rax);
+    }
+
+    fn int_shift(&mut self, s: i32, op: Shift) {
+        self.asm.load32(Reg::Rcx, stack(s - 1));
+        self.asm.load32(Reg::Rax, stack(s - 2));
+        self.asm.shift32(op, Reg::Rax);
+        self.asm.store64(stack(s - 2), Reg::Rax);
+    }
+
+    fn long_shift(&mut self, s: i32, op: Shift) {
+        self.asm.load32(Reg::Rcx, stack(s - 1));
+        self.asm.load64(Reg::Rax, stack(s - 2));
+        self.asm.shift64(op, Reg::Rax);
+        self.asm.store64(stack(s - 2), Reg::Rax);
+    }
+
+    fn float_op(&mut self, s: i32, op: Sse) {
+        self.asm.sse_ss(Sse::Load, Xmm(0), stack(s - 2));
+        self.asm.sse_ss(op, Xmm(0), stack(s - 1));
+        self.asm.sse_ss(Sse::Store, Xmm(0), stack(s - 2));
+    }
+
+    fn double_op(&mut self, s: i32, op: Sse) {
+        self.asm.sse_sd(Sse::Load, Xmm(0), stack(s - 3));
+        self.asm.sse_sd(op, Xmm(0), stack(s - 1));
+        self.asm.sse_sd(Sse::Store, Xmm(0), stack(s - 3));
+    }
+
+    //-1, 0, 1 by the flags of ucomiss/ucomisd, 'nan' if unordered
+    fn float_compare(&mut self, nan: i32, dst: Mem) {
+        let done = self.asm.new_label();
+        let a = &mut self.asm;
+        a.mov_imm32(Reg::Rax, nan as u32);
+        a.jcc(Cond::P, done);
+        a.setcc(Cond::A, Reg::Rcx);
+        a.setcc(Cond::B, Reg::Rdx);
+        a.sub8_rr(Reg::Rcx, Reg::Rdx);
+        a.movsx8_rr(Reg::Rax, Reg::Rcx);
+        a.bind(done);
+        a.store64(dst, Reg::Rax);
+    }
+}
+
+//eq, ne, lt, ge, gt, le
+fn cond_of(i: u8) -> Cond {
+    match i {
+        0 => Cond::E,
+        1 => Cond::NE,
+        2 => Cond::L,
+        3 => Cond::GE,
+        4 => Cond::G,
+        _ => Cond::LE,
+    }
+}
+
+#[cfg(all(test, target_arch = "x86_64", target_os = "linux"))]
+mod tests {
+    use super::*;
+    use crate::Context;
+    use std::sync::Arc;
+
+    extern "C" fn no_step(_ctx: *mut Context, _pc: u32, _sp: u32, _next: u32) -> u32 {
+        unreachable!()
+    }
+
+    //(exit, ctx) of running 'code' from pc 0 with 'locals'
+    fn run(code: &[u8], locals: &mut [u64], max_stack: usize) -> (Exit, Context) {
+        let cp = Arc::new(vec![]);
+        let m = Bytecode {
+            code,
+            cp: &cp,
+            max_locals: locals.len(),
+            max_stack,
+        };
+        let compiled = compile(&m, &Runtime { step: no_step }).unwrap();
+
+        let mut refs = vec![false; locals.len()];
+        let mut stack = vec![0u64; max_stack];
+        let mut ctx = Context {
+            locals: locals.as_mut_ptr(),
+            local_refs: refs.as_mut_ptr(),
+            stack: stack.as_mut_ptr(),
+            pc: 0,
+            sp: 0,
+            ret: 0,
+            env: std::ptr::null_mut(),
+        };
+        let exit = unsafe { compiled.run(&mut ctx) };
+        (exit, ctx)
+    }
+
+    #[test]
+    fn t_loop() {
+        //int sum = 0; for (int i = 0; i < n; i++) sum += i * i; return sum;
+        let code = [
+            0x03, 0x3D, //iconst_0, istore_2
+            0x03, 0x3E, //iconst_0, istore_3
+            0x1D, 0x1B, 0xA2, 0, 15, //iload_3, iload_1, if_icmpge +15
+            0x1C, 0x1D, 0x1D, 0x68, 0x60, 0x3D, //sum += i * i
+            0x84, 3, 1, //iinc 3 1
+            0xA7, 0xFF, 0xF2, //goto -14
+            0x1C, 0xAC, //iload_2, ireturn
+        ];
+        let mut locals = [0, 10, 0, 0];
+        let (exit, ctx) = run(&code, &mut locals, 3);
+        assert_eq!(exit, Exit::Return);
+        assert_eq!(ctx.ret as i32, 285);
+    }
+
+    #[test]
+    fn t_long_float() {
+        //(double)((float)(int)(a * b + 1L) * 2.0f), a -3, b 5L
+        let code = [
+            0x1A, 0x85, //iload_0, i2l
+            0x1F, 0x69, //lload_1, lmul
+            0x0A, 0x61, //lconst_1, ladd
+            0x88, 0x86, //l2i, i2f
+            0x0D, 0x6A, //fconst_2, fmul
+            0x8D, 0xAF, //f2d, dreturn
+        ];
+        let mut locals = [(-3i32) as u32 as u64, 5, 0];
+        let (exit, ctx) = run(&code, &mut locals, 4);
+        assert_eq!(exit, Exit::Return);
+        assert_eq!(f64::from_bits(ctx.ret), -28.0);
+    }
+
+    #[test]
+    fn t_compare() {
+        //lload_0, lload_2, lcmp, ireturn
+        let code = [0x1E, 0x20, 0x94, 0xAC];
+        for (a, b, r) in [(5i64, 7i64, -1), (7, 7, 0), (-1, -8, 1)].iter() {
+            let mut locals = [*a as u64, 0, *b as u64, 0];
+            let (_, ctx) = run(&code, &mut locals, 4);
+            assert_eq!(ctx.ret as i32, *r);
+        }
+
+        //fload_0, fload_1, fcmpl / fcmpg, ireturn
+        for (op, a, b, r) in [
+            (0x95, 2.0f32, 1.0f32, 1),
+            (0x95, 1.0, 2.0, -1),
+            (0x95, f32::NAN, 1.0, -1),
+            (0x96, f32::NAN, 1.0, 1),
+            (0x96, 0.0, -0.0, 0),
+        ]
+        .iter()
+        {
+            let code = [0x22, 0x23, *op, 0xAC];
+            let mut locals = [a.to_bits() as u64, b.to_bits() as u64];
+            let (_, ctx) = run(&code, &mut locals, 2);
+            assert_eq!(ctx.ret as i32, *r);
+        }
+    }
+
+    #[test]
+    fn t_deopt() {
+        //a / b, deoptimized for b == 0 at pc 2, the stack of 2
+        let code = [0x1A, 0x1B, 0x6C, 0xAC];
+        let mut locals = [7, 2];
+        let (exit, ctx) = run(&code, &mut locals, 2);
+        assert_eq!(exit, Exit::Return);
+        assert_eq!(ctx.ret as i32, 3);
+
+        let mut locals = [7, 0];
+        let (exit, ctx) = run(&code, &mut locals, 2);
+        assert_eq!(exit, Exit::Deopt);
+        assert_eq!((ctx.pc, ctx.sp), (2, 2));
+    }
+}
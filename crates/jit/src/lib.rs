@@ -0,0 +1,76 @@
+//! Baseline JIT compiler of the vm.
+//!
+//! Hot methods are compiled from bytecode to x86-64 machine code, one template
+//! for each instruction, by a self-contained assembler. The compiled code works
+//! on the slots of the interpreter frame.
+//!
+//! Only the arithmetic, locals, constants, compares & branches are compiled
+//! inline. Every instruction reaching the heap or another method (fields,
+//! arrays, references, invokes...) is executed by the interpreter, one at a
+//! time, through `Runtime::step`: a method gains as much as its arithmetic and
+//! loops, the calls & field accesses cost as interpreted.
+//!
+//! The crate knows nothing of the vm: the runtime is reached through the
+//! helpers of `Runtime`, the frame through `Context`.
+mod asm;
+mod bytecode;
+mod code;
+mod compiler;
+
+pub use code::{CompiledMethod, MethodState};
+pub use compiler::{compile, Bytecode};
+
+//the state of a frame shared by compiled code & the runtime, the offsets are
+//known by the compiled code
+#[repr(C)]
+pub struct Context {
+    pub locals: *mut u64,
+    //reference map of the locals
+    pub local_refs: *mut bool,
+    pub stack: *mut u64,
+    //in: the entry; out: where the interpreter resumes if Exit::Deopt
+    pub pc: u32,
+    //depth of the operand stack at pc
+    pub sp: u32,
+    //the bits of the value returned if Exit::Return
+    pub ret: u64,
+    //for the helpers of Runtime
+    pub env: *mut std::ffi::c_void,
+}
+
+//how the compiled code exits
+#[repr(u32)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Exit {
+    //returned, the value in ctx.ret
+    Return = 1,
+    //to be interpreted from ctx.pc, with the stack of ctx.sp
+    Deopt = 2,
+    //Runtime::step transferred control, interpreted from the pc of the frame
+    Resume = 3,
+    //Runtime::step finished the method, returned or thrown
+    Done = 4,
+}
+
+impl From<u32> for Exit {
+    fn from(v: u32) -> Self {
+        match v {
+            1 => Exit::Return,
+            2 => Exit::Deopt,
+            3 => Exit::Resume,
+            4 => Exit::Done,
+            _ => unreachable!("exit = {}", v),
+        }
+    }
+}
+
+pub struct Runtime {
+    //executes the instruction at 'pc' by the interpreter, the stack of depth
+    //'sp'; 0 if it goes on to 'next_pc', otherwise the Exit
+    pub step: extern "C" fn(ctx: *mut Context, pc: u32, sp: u32, next_pc: u32) -> u32,
+}
+
+//only x86-64 Linux, otherwise nothing compiled
+pub fn is_supported() -> bool {
+    cfg!(all(target_arch = "x86_64", target_os = "linux"))
+}
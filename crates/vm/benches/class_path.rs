@@ -0,0 +1,84 @@
+/*
+Load all classes of rt.jar through the class path, 1 thread and 4 threads
+
+  RT_JAR=/path/to/rt.jar cargo bench -p vm --bench class_path
+
+rt.jar found by $RT_JAR, or $JAVA_HOME
+*/
+use std::fs::File;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Instant;
+use vm::runtime;
+
+const THREADS: usize = 4;
+
+fn main() {
+    let rt = match find_rt_jar() {
+        Some(rt) => rt,
+        None => {
+            println!("rt.jar not found, set RT_JAR or JAVA_HOME");
+            return;
+        }
+    };
+    let rt = rt.to_str().unwrap();
+
+    let classes = list_classes(rt);
+    println!("{}: {} classes", rt, classes.len());
+
+    let now = Instant::now();
+    runtime::add_class_path(rt);
+    println!("index: {:?}", now.elapsed());
+
+    let now = Instant::now();
+    for name in classes.iter() {
+        assert!(runtime::find_class_in_classpath(name).is_ok());
+    }
+    println!("load, 1 thread: {:?}", now.elapsed());
+
+    let classes = Arc::new(classes);
+    let now = Instant::now();
+    let handles: Vec<_> = (0..THREADS)
+        .map(|i| {
+            let classes = classes.clone();
+            std::thread::spawn(move || {
+                for name in classes.iter().skip(i).step_by(THREADS) {
+                    assert!(runtime::find_class_in_classpath(name).is_ok());
+                }
+            })
+        })
+        .collect();
+    for h in handles {
+        h.join().unwrap();
+    }
+    println!("load, {} threads: {:?}", THREADS, now.elapsed());
+
+    let now = Instant::now();
+    for name in classes.iter() {
+        let name = format!("{}$NoSuch", name);
+        assert!(runtime::find_class_in_classpath(&name).is_err());
+        assert!(runtime::find_class_in_classpath(&name).is_err());
+    }
+    println!("miss x2, 1 thread: {:?}", now.elapsed());
+}
+
+fn find_rt_jar() -> Option<PathBuf> {
+    if let Ok(v) = std::env::var("RT_JAR") {
+        return Some(PathBuf::from(v));
+    }
+
+    let home = PathBuf::from(std::env::var("JAVA_HOME").ok()?);
+    vec![home.join("lib/rt.jar"), home.join("jre/lib/rt.jar")]
+        .into_iter()
+        .find(|p| p.is_file())
+}
+
+//"java/lang/Object"
+fn list_classes(jar: &str) -> Vec<String> {
+    let f = File::open(jar).unwrap();
+    let z = zip::ZipArchive::new(f).unwrap();
+    z.file_names()
+        .filter(|v| v.ends_with(".class"))
+        .map(|v| v.trim_end_matches(".class").to_string())
+        .collect()
+}
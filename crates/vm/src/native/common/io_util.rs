@@ -0,0 +1,289 @@
+use crate::oop::{Class, Oop};
+use crate::runtime::{self, require_class3};
+use crate::util;
+use classfile::consts as cls_consts;
+use std::ffi::{CStr, CString};
+
+/*
+Shared by FileInputStream, FileOutputStream & RandomAccessFile, like io_util.c of jdk.
+
+The 'fd' field of a stream is a FileDescriptor, whose 'fd' is the int of os,
+-1 after closed.
+*/
+
+lazy_static! {
+    static ref FILE_DESCRIPTOR_FD: usize = {
+        let cls = require_class3(None, b"java/io/FileDescriptor").unwrap();
+        let cls = cls.get_class();
+        cls.get_field_id(&util::S_FD, &util::S_I, false).offset
+    };
+}
+
+//offset of the 'fd' field of a stream class
+pub fn stream_fd_offset(cls_name: &[u8]) -> usize {
+    let cls = require_class3(None, cls_name).unwrap();
+    let cls = cls.get_class();
+    cls.get_field_id(&util::S_FD, &util::S_JAVA_IO_FD, false)
+        .offset
+}
+
+pub fn get_fd(this: &Oop, offset: usize) -> i32 {
+    let fd_this = Class::get_field_value2(this.extract_ref(), offset);
+    if fd_this.is_null() {
+        return -1;
+    }
+    file_descriptor_fd(&fd_this)
+}
+
+//the int fd of a FileDescriptor
+pub fn file_descriptor_fd(fd_this: &Oop) -> i32 {
+    let fd = Class::get_field_value2(fd_this.extract_ref(), *FILE_DESCRIPTOR_FD);
+    fd.extract_int()
+}
+
+pub fn set_file_descriptor_fd(fd_this: &Oop, fd: i32) {
+    Class::put_field_value2(fd_this.extract_ref(), *FILE_DESCRIPTOR_FD, Oop::new_int(fd));
+}
+
+pub fn set_fd(this: &Oop, offset: usize, fd: i32) {
+    let fd_this = Class::get_field_value2(this.extract_ref(), offset);
+    if !fd_this.is_null() {
+        set_file_descriptor_fd(&fd_this, fd);
+    }
+}
+
+//fd of an opened stream, "Stream Closed" if not
+pub fn get_open_fd(this: &Oop, offset: usize) -> Result<i32, Oop> {
+    match get_fd(this, offset) {
+        -1 => Err(new_io_ex("Stream Closed")),
+        fd => Ok(fd),
+    }
+}
+
+pub fn new_io_ex(msg: &str) -> Oop {
+    runtime::exception::new(cls_consts::J_IOEXCEPTION, Some(msg.to_string()))
+}
+
+//the message of errno, or 'default' if no error
+pub fn last_error(default: &str) -> String {
+    let errno = std::io::Error::last_os_error().raw_os_error().unwrap_or(0);
+    if errno == 0 {
+        default.to_string()
+    } else {
+        strerror(errno)
+    }
+}
+
+pub fn strerror(errno: i32) -> String {
+    unsafe {
+        let s = libc::strerror(errno);
+        CStr::from_ptr(s).to_string_lossy().to_string()
+    }
+}
+
+//Err as the message of FileNotFoundException, "path (reason)"
+pub fn open(path: &str, flags: i32) -> Result<i32, String> {
+    let name = match CString::new(path) {
+        Ok(name) => name,
+        Err(_) => return Err(format!("{} (Invalid argument)", path)),
+    };
+
+    let fd = unsafe { libc::open(name.as_ptr(), flags | libc::O_CLOEXEC, 0o666) };
+    if fd == -1 {
+        return Err(format!("{} ({})", path, last_error("Open failed")));
+    }
+
+    //a dir opened read only, not a file to stream
+    let mut stat: libc::stat = unsafe { std::mem::zeroed() };
+    let is_dir = unsafe {
+        libc::fstat(fd, &mut stat) == 0 && (stat.st_mode & libc::S_IFMT) == libc::S_IFDIR
+    };
+    if is_dir {
+        unsafe {
+            libc::close(fd);
+        }
+        return Err(format!("{} ({})", path, strerror(libc::EISDIR)));
+    }
+
+    Ok(fd)
+}
+
+pub fn open_or_throw(path: &str, flags: i32) -> Result<i32, Oop> {
+    open(path, flags)
+        .map_err(|msg| runtime::exception::new(cls_consts::J_FILE_NOT_FOUND, Some(msg)))
+}
+
+//the byte read, -1 at end of file
+pub fn read_single(fd: i32) -> Result<i32, String> {
+    let mut v = 0u8;
+    let n = unsafe { libc::read(fd, &mut v as *mut u8 as *mut libc::c_void, 1) };
+    match n {
+        -1 => Err(last_error("Read error")),
+        0 => Ok(-1),
+        _ => Ok(v as i32),
+    }
+}
+
+//count of bytes read, -1 at end of file
+pub fn read(fd: i32, buf: &mut [u8]) -> Result<i32, String> {
+    if buf.is_empty() {
+        return Ok(0);
+    }
+
+    let n = unsafe { libc::read(fd, buf.as_mut_ptr() as *mut libc::c_void, buf.len()) };
+    match n {
+        -1 => Err(last_error("Read error")),
+        0 => Ok(-1),
+        n => Ok(n as i32),
+    }
+}
+
+//write all of 'buf'
+pub fn write(fd: i32, buf: &[u8]) -> Result<(), String> {
+    let mut buf = buf;
+    while !buf.is_empty() {
+        let n = unsafe { libc::write(fd, buf.as_ptr() as *const libc::c_void, buf.len()) };
+        if n == -1 {
+            if std::io::Error::last_os_error().kind() == std::io::ErrorKind::Interrupted {
+                continue;
+            }
+            return Err(last_error("Write error"));
+        }
+        buf = &buf[n as usize..];
+    }
+
+    Ok(())
+}
+
+//stdin, stdout & stderr redirected to /dev/null instead of closed,
+//so the fds not reused by other files
+pub fn close(fd: i32) -> Result<(), String> {
+    let r = if (0..=2).contains(&fd) {
+        unsafe {
+            let null = CString::new("/dev/null").unwrap();
+            let null_fd = libc::open(null.as_ptr(), libc::O_WRONLY);
+            if null_fd == -1 {
+                -1
+            } else {
+                let r = libc::dup2(null_fd, fd);
+                libc::close(null_fd);
+                r
+            }
+        }
+    } else {
+        unsafe { libc::close(fd) }
+    };
+
+    if r == -1 {
+        Err(last_error("close failed"))
+    } else {
+        Ok(())
+    }
+}
+
+//close the FileDescriptor of a stream, nothing if closed
+pub fn close_stream(this: &Oop, offset: usize) -> Result<(), Oop> {
+    let fd = get_fd(this, offset);
+    if fd == -1 {
+        return Ok(());
+    }
+
+    //closed even if close failed
+    set_fd(this, offset, -1);
+    close(fd).map_err(|msg| new_io_ex(&msg))
+}
+
+pub fn seek(fd: i32, offset: i64, whence: i32) -> Result<i64, String> {
+    let v = unsafe { libc::lseek(fd, offset as libc::off_t, whence) };
+    if v == -1 {
+        Err(last_error("Seek error"))
+    } else {
+        Ok(v as i64)
+    }
+}
+
+//bytes readable without blocking
+pub fn available(fd: i32) -> Result<i32, String> {
+    unsafe {
+        let mut stat: libc::stat = std::mem::zeroed();
+        if libc::fstat(fd, &mut stat) != -1 {
+            let mode = stat.st_mode & libc::S_IFMT;
+            if mode == libc::S_IFIFO || mode == libc::S_IFCHR || mode == libc::S_IFSOCK {
+                let mut n: libc::c_int = 0;
+                if libc::ioctl(fd, libc::FIONREAD, &mut n) >= 0 {
+                    return Ok(n);
+                }
+            } else if mode == libc::S_IFREG {
+                let current = seek(fd, 0, libc::SEEK_CUR)?;
+                let mut size = stat.st_size as i64;
+                //size of some special files not known by stat, like in /proc
+                if size < current {
+                    size = seek(fd, 0, libc::SEEK_END)?;
+                    seek(fd, current, libc::SEEK_SET)?;
+                }
+                return Ok((size - current).min(i32::MAX as i64) as i32);
+            }
+        }
+    }
+
+    Err(last_error("Stream Closed"))
+}
+
+//off & len of a byte array checked as jdk, IndexOutOfBoundsException if out of range
+pub fn check_bounds(ary: &Oop, off: i32, len: i32) -> Result<(), Oop> {
+    let ary_len = {
+        let rf = ary.extract_ref();
+        let ary = rf.extract_type_array();
+        ary.extract_bytes().len() as i32
+    };
+
+    if off < 0 || len < 0 || ary_len - off < len {
+        let ex = runtime::exception::new(cls_consts::J_INDEX_OUT_OF_BOUNDS, None);
+        Err(ex)
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn t_read_write() {
+        let dir = std::env::temp_dir().join(format!("jvm_io_test_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("a.txt");
+        let path = path.to_str().unwrap();
+
+        let fd = open(path, libc::O_WRONLY | libc::O_CREAT | libc::O_TRUNC).unwrap();
+        write(fd, b"hello").unwrap();
+        close(fd).unwrap();
+
+        let fd = open(path, libc::O_RDONLY).unwrap();
+        assert_eq!(available(fd), Ok(5));
+        assert_eq!(read_single(fd), Ok(b'h' as i32));
+        assert_eq!(seek(fd, 2, libc::SEEK_CUR), Ok(3));
+        let mut buf = [0u8; 8];
+        assert_eq!(read(fd, &mut buf), Ok(2));
+        assert_eq!(&buf[..2], b"lo");
+        assert_eq!(read(fd, &mut buf), Ok(-1));
+        assert_eq!(read_single(fd), Ok(-1));
+        assert_eq!(read(fd, &mut buf[..0]), Ok(0));
+        close(fd).unwrap();
+
+        let missing = dir.join("missing.txt");
+        let missing = missing.to_str().unwrap();
+        assert_eq!(
+            open(missing, libc::O_RDONLY),
+            Err(format!("{} (No such file or directory)", missing))
+        );
+        let dir_name = dir.to_str().unwrap();
+        assert_eq!(
+            open(dir_name, libc::O_RDONLY),
+            Err(format!("{} (Is a directory)", dir_name))
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
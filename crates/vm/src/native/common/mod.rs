@@ -1,2 +1,7 @@
 mod check_format;
+pub mod io_util;
+pub mod net_util;
+pub mod nio_util;
+pub mod process_util;
 pub mod reflect;
+pub mod sys_props;
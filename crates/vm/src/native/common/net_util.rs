@@ -0,0 +1,677 @@
+use crate::native::common::io_util::strerror;
+use crate::new_br;
+use crate::oop::{self, Class, Oop, OopRef};
+use crate::runtime::{self, require_class3};
+use crate::util;
+use classfile::consts as cls_consts;
+use std::ffi::{CStr, CString};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+/*
+Shared by the natives of java.net, like net_util.c of jdk.
+
+Sockets are AF_INET only, InetAddressImplFactory.isIPv6Supported answers
+false, so the class lib works with Inet4Address; an Inet6Address given to
+a socket gets "Protocol family unavailable".
+*/
+
+//InetAddress.IPv4, IPv6
+const IPV4: i32 = 1;
+const IPV6: i32 = 2;
+
+//java.net.SocketOptions
+pub const TCP_NODELAY: i32 = 0x0001;
+pub const IP_TOS: i32 = 0x0003;
+pub const SO_REUSEADDR: i32 = 0x0004;
+pub const SO_KEEPALIVE: i32 = 0x0008;
+pub const SO_REUSEPORT: i32 = 0x000E;
+pub const SO_BINDADDR: i32 = 0x000F;
+pub const IP_MULTICAST_IF: i32 = 0x0010;
+pub const IP_MULTICAST_LOOP: i32 = 0x0012;
+pub const IP_MULTICAST_IF2: i32 = 0x001F;
+pub const SO_BROADCAST: i32 = 0x0020;
+pub const SO_LINGER: i32 = 0x0080;
+pub const SO_SNDBUF: i32 = 0x1001;
+pub const SO_RCVBUF: i32 = 0x1002;
+pub const SO_OOBINLINE: i32 = 0x1003;
+
+lazy_static! {
+    //InetAddress.holder, InetAddressHolder.address & family
+    static ref IA_HOLDER: usize = field_offset(
+        b"java/net/InetAddress",
+        "holder",
+        "Ljava/net/InetAddress$InetAddressHolder;"
+    );
+    static ref IAH_ADDRESS: usize =
+        field_offset(b"java/net/InetAddress$InetAddressHolder", "address", "I");
+    static ref IAH_FAMILY: usize =
+        field_offset(b"java/net/InetAddress$InetAddressHolder", "family", "I");
+    //Inet6Address.holder6, Inet6AddressHolder.ipaddress
+    static ref IA6_HOLDER6: usize = field_offset(
+        b"java/net/Inet6Address",
+        "holder6",
+        "Ljava/net/Inet6Address$Inet6AddressHolder;"
+    );
+    static ref IA6H_IPADDRESS: usize =
+        field_offset(b"java/net/Inet6Address$Inet6AddressHolder", "ipaddress", "[B");
+}
+
+pub fn field_offset(cls_name: &[u8], name: &str, desc: &str) -> usize {
+    let cls = require_class3(None, cls_name).unwrap();
+    let cls = cls.get_class();
+    cls.get_field_id(&new_br(name), &new_br(desc), false).offset
+}
+
+pub fn get_int_field(this: &Oop, offset: usize) -> i32 {
+    Class::get_field_value2(this.extract_ref(), offset).extract_int()
+}
+
+pub fn put_int_field(this: &Oop, offset: usize, v: i32) {
+    Class::put_field_value2(this.extract_ref(), offset, Oop::new_int(v));
+}
+
+//Inet4Address or Inet6Address, 'host' null if None
+pub fn new_inet_address(host: Option<&str>, ip: IpAddr) -> Result<Oop, Oop> {
+    let (name, bytes): (&[u8], Vec<u8>) = match ip {
+        IpAddr::V4(ip) => (b"java/net/Inet4Address", ip.octets().to_vec()),
+        IpAddr::V6(ip) => (b"java/net/Inet6Address", ip.octets().to_vec()),
+    };
+
+    let cls = oop::class::load_and_init(name)?;
+    let v = Oop::new_inst(cls.clone());
+    let host = match host {
+        Some(host) => util::oop::new_java_lang_string2(host),
+        None => oop::consts::get_null(),
+    };
+    let args = vec![v.clone(), host, Oop::new_byte_ary2(bytes)];
+    runtime::invoke::invoke_ctor(cls, new_br("(Ljava/lang/String;[B)V"), args);
+    Ok(v)
+}
+
+pub fn inet_address_ip(ia: &Oop) -> Option<IpAddr> {
+    if ia.is_null() {
+        return None;
+    }
+
+    let holder = Class::get_field_value2(ia.extract_ref(), *IA_HOLDER);
+    match get_int_field(&holder, *IAH_FAMILY) {
+        IPV4 => {
+            let v = get_int_field(&holder, *IAH_ADDRESS);
+            Some(IpAddr::V4(Ipv4Addr::from(v as u32)))
+        }
+        IPV6 => {
+            let holder6 = Class::get_field_value2(ia.extract_ref(), *IA6_HOLDER6);
+            let ary = Class::get_field_value2(holder6.extract_ref(), *IA6H_IPADDRESS);
+            let rf = ary.extract_ref();
+            let ary = rf.extract_type_array();
+            let mut v = [0u8; 16];
+            v.copy_from_slice(ary.extract_bytes());
+            Some(IpAddr::V6(Ipv6Addr::from(v)))
+        }
+        _ => None,
+    }
+}
+
+//address of an Inet4Address updated, as the out param of peek
+pub fn set_inet4_address(ia: &Oop, ip: Ipv4Addr) {
+    let holder = Class::get_field_value2(ia.extract_ref(), *IA_HOLDER);
+    put_int_field(&holder, *IAH_ADDRESS, u32::from(ip) as i32);
+    put_int_field(&holder, *IAH_FAMILY, IPV4);
+}
+
+//the address of an AF_INET socket
+pub fn inet4_address(ia: &Oop) -> Result<Ipv4Addr, Oop> {
+    match inet_address_ip(ia) {
+        Some(IpAddr::V4(ip)) => Ok(ip),
+        Some(IpAddr::V6(_)) => Err(new_socket_ex("Protocol family unavailable")),
+        None => Err(runtime::exception::new(
+            cls_consts::J_NPE,
+            Some("inet address argument is null.".to_string()),
+        )),
+    }
+}
+
+pub fn new_net_ex(cls_name: &[u8], msg: &str) -> Oop {
+    runtime::exception::new(cls_name, Some(msg.to_string()))
+}
+
+pub fn new_socket_ex(msg: &str) -> Oop {
+    new_net_ex(cls_consts::J_SOCKET_EX, msg)
+}
+
+pub fn errno() -> i32 {
+    std::io::Error::last_os_error().raw_os_error().unwrap_or(0)
+}
+
+//errors of connect, as NET_ThrowByNameWithLastError of jdk
+pub fn connect_ex(errno: i32) -> Oop {
+    match errno {
+        libc::ECONNREFUSED => new_net_ex(cls_consts::J_CONNECT_EX, "Connection refused"),
+        libc::ETIMEDOUT => new_net_ex(cls_consts::J_CONNECT_EX, "Connection timed out"),
+        libc::EHOSTUNREACH => new_net_ex(cls_consts::J_NO_ROUTE_TO_HOST_EX, "Host unreachable"),
+        libc::EADDRNOTAVAIL => {
+            new_net_ex(cls_consts::J_NO_ROUTE_TO_HOST_EX, "Address not available")
+        }
+        libc::EBADF => new_socket_ex("Socket closed"),
+        _ => new_socket_ex(&strerror(errno)),
+    }
+}
+
+pub fn bind_ex(errno: i32) -> Oop {
+    match errno {
+        libc::EBADF => new_socket_ex("Socket closed"),
+        _ => new_net_ex(cls_consts::J_BIND_EX, &strerror(errno)),
+    }
+}
+
+//errors of read & write
+pub fn io_ex(errno: i32) -> Oop {
+    match errno {
+        libc::ECONNRESET => new_socket_ex("Connection reset"),
+        libc::EBADF => new_socket_ex("Socket closed"),
+        _ => new_socket_ex(&strerror(errno)),
+    }
+}
+
+pub fn to_sockaddr(ip: Ipv4Addr, port: u16) -> libc::sockaddr_in {
+    let mut sa: libc::sockaddr_in = unsafe { std::mem::zeroed() };
+    sa.sin_family = libc::AF_INET as libc::sa_family_t;
+    sa.sin_port = port.to_be();
+    sa.sin_addr.s_addr = u32::from(ip).to_be();
+    sa
+}
+
+pub fn from_sockaddr(sa: &libc::sockaddr_storage) -> Option<(IpAddr, u16)> {
+    match sa.ss_family as i32 {
+        libc::AF_INET => {
+            let sa = unsafe { &*(sa as *const _ as *const libc::sockaddr_in) };
+            let ip = Ipv4Addr::from(u32::from_be(sa.sin_addr.s_addr));
+            Some((IpAddr::V4(ip), u16::from_be(sa.sin_port)))
+        }
+        libc::AF_INET6 => {
+            let sa = unsafe { &*(sa as *const _ as *const libc::sockaddr_in6) };
+            let ip = Ipv6Addr::from(sa.sin6_addr.s6_addr);
+            Some((IpAddr::V6(ip), u16::from_be(sa.sin6_port)))
+        }
+        _ => None,
+    }
+}
+
+pub fn socket(tp: i32) -> Result<i32, i32> {
+    let fd = unsafe { libc::socket(libc::AF_INET, tp, 0) };
+    if fd == -1 {
+        return Err(errno());
+    }
+
+    unsafe {
+        libc::fcntl(fd, libc::F_SETFD, libc::FD_CLOEXEC);
+    }
+
+    //no SIGPIPE when the peer closed, MSG_NOSIGNAL used on linux
+    #[cfg(any(target_os = "macos", target_os = "ios", target_os = "freebsd"))]
+    {
+        let _ = set_int_option(fd, libc::SOL_SOCKET, libc::SO_NOSIGPIPE, 1);
+    }
+
+    Ok(fd)
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub const SEND_FLAGS: i32 = libc::MSG_NOSIGNAL;
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+pub const SEND_FLAGS: i32 = 0;
+
+pub fn bind(fd: i32, ip: Ipv4Addr, port: u16) -> Result<(), i32> {
+    let sa = to_sockaddr(ip, port);
+    let r = unsafe {
+        libc::bind(
+            fd,
+            &sa as *const _ as *const libc::sockaddr,
+            std::mem::size_of::<libc::sockaddr_in>() as libc::socklen_t,
+        )
+    };
+    if r == -1 {
+        Err(errno())
+    } else {
+        Ok(())
+    }
+}
+
+//wait for 'events' of poll, Ok(false) if timeout; 'timeout' in millis, <= 0 forever
+pub fn wait(fd: i32, events: i16, timeout: i32) -> Result<bool, i32> {
+    let deadline = if timeout > 0 {
+        Some(std::time::Instant::now() + std::time::Duration::from_millis(timeout as u64))
+    } else {
+        None
+    };
+
+    loop {
+        let remain = match deadline {
+            Some(deadline) => {
+                let now = std::time::Instant::now();
+                if now >= deadline {
+                    return Ok(false);
+                }
+                (deadline - now).as_millis().max(1) as i32
+            }
+            None => -1,
+        };
+
+        let mut pfd = libc::pollfd {
+            fd,
+            events,
+            revents: 0,
+        };
+        match unsafe { libc::poll(&mut pfd, 1, remain) } {
+            -1 if errno() == libc::EINTR => continue,
+            -1 => return Err(errno()),
+            0 => return Ok(false),
+            _ => return Ok(true),
+        }
+    }
+}
+
+//Err(Some(errno)) if failed, Err(None) if timeout
+pub fn connect(fd: i32, ip: Ipv4Addr, port: u16, timeout: i32) -> Result<(), Option<i32>> {
+    let sa = to_sockaddr(ip, port);
+    let sa_len = std::mem::size_of::<libc::sockaddr_in>() as libc::socklen_t;
+    let do_connect =
+        || unsafe { libc::connect(fd, &sa as *const _ as *const libc::sockaddr, sa_len) };
+
+    if timeout <= 0 {
+        loop {
+            if do_connect() == 0 {
+                return Ok(());
+            }
+            match errno() {
+                libc::EINTR => continue,
+                e => return Err(Some(e)),
+            }
+        }
+    }
+
+    //non-blocking connect, poll for the timeout
+    let flags = unsafe { libc::fcntl(fd, libc::F_GETFL) };
+    unsafe {
+        libc::fcntl(fd, libc::F_SETFL, flags | libc::O_NONBLOCK);
+    }
+    let r = if do_connect() == 0 {
+        Ok(())
+    } else {
+        match errno() {
+            libc::EINPROGRESS | libc::EINTR => match wait(fd, libc::POLLOUT, timeout) {
+                Ok(true) => match get_int_option(fd, libc::SOL_SOCKET, libc::SO_ERROR) {
+                    Ok(0) => Ok(()),
+                    Ok(e) | Err(e) => Err(Some(e)),
+                },
+                Ok(false) => Err(None),
+                Err(e) => Err(Some(e)),
+            },
+            e => Err(Some(e)),
+        }
+    };
+    unsafe {
+        libc::fcntl(fd, libc::F_SETFL, flags);
+    }
+
+    r
+}
+
+pub fn local_address(fd: i32) -> Option<(IpAddr, u16)> {
+    let mut sa: libc::sockaddr_storage = unsafe { std::mem::zeroed() };
+    let mut len = std::mem::size_of::<libc::sockaddr_storage>() as libc::socklen_t;
+    let r = unsafe { libc::getsockname(fd, &mut sa as *mut _ as *mut libc::sockaddr, &mut len) };
+    if r == -1 {
+        None
+    } else {
+        from_sockaddr(&sa)
+    }
+}
+
+pub fn available(fd: i32) -> Result<i32, i32> {
+    let mut n: libc::c_int = 0;
+    if unsafe { libc::ioctl(fd, libc::FIONREAD, &mut n) } == -1 {
+        Err(errno())
+    } else {
+        Ok(n)
+    }
+}
+
+pub fn set_int_option(fd: i32, level: i32, name: i32, v: i32) -> Result<(), i32> {
+    let r = unsafe {
+        libc::setsockopt(
+            fd,
+            level,
+            name,
+            &v as *const i32 as *const libc::c_void,
+            std::mem::size_of::<i32>() as libc::socklen_t,
+        )
+    };
+    if r == -1 {
+        Err(errno())
+    } else {
+        Ok(())
+    }
+}
+
+pub fn get_int_option(fd: i32, level: i32, name: i32) -> Result<i32, i32> {
+    let mut v: i32 = 0;
+    let mut len = std::mem::size_of::<i32>() as libc::socklen_t;
+    let r = unsafe {
+        libc::getsockopt(
+            fd,
+            level,
+            name,
+            &mut v as *mut i32 as *mut libc::c_void,
+            &mut len,
+        )
+    };
+    if r == -1 {
+        Err(errno())
+    } else {
+        Ok(v)
+    }
+}
+
+//(level, name) of the int options shared by stream & datagram sockets
+pub fn map_option(opt: i32) -> Option<(i32, i32)> {
+    let v = match opt {
+        TCP_NODELAY => (libc::IPPROTO_TCP, libc::TCP_NODELAY),
+        IP_TOS => (libc::IPPROTO_IP, libc::IP_TOS),
+        SO_REUSEADDR => (libc::SOL_SOCKET, libc::SO_REUSEADDR),
+        SO_REUSEPORT => (libc::SOL_SOCKET, libc::SO_REUSEPORT),
+        SO_KEEPALIVE => (libc::SOL_SOCKET, libc::SO_KEEPALIVE),
+        SO_BROADCAST => (libc::SOL_SOCKET, libc::SO_BROADCAST),
+        SO_SNDBUF => (libc::SOL_SOCKET, libc::SO_SNDBUF),
+        SO_RCVBUF => (libc::SOL_SOCKET, libc::SO_RCVBUF),
+        SO_OOBINLINE => (libc::SOL_SOCKET, libc::SO_OOBINLINE),
+        _ => return None,
+    };
+    Some(v)
+}
+
+//value of an option, Integer or Boolean
+pub fn int_or_bool(v: &Oop) -> i32 {
+    if v.is_null() {
+        return 0;
+    }
+
+    let rf = v.extract_ref();
+    let is_bool = {
+        let inst = rf.extract_inst();
+        let cls = inst.class.get_class();
+        cls.name.as_slice() == b"java/lang/Boolean"
+    };
+    if is_bool {
+        let offset = field_offset(b"java/lang/Boolean", "value", "Z");
+        get_int_field(v, offset)
+    } else {
+        OopRef::java_lang_integer_value(rf)
+    }
+}
+
+pub fn set_linger(fd: i32, on: bool, secs: i32) -> Result<(), i32> {
+    let v = libc::linger {
+        l_onoff: on as i32,
+        l_linger: if on { secs } else { 0 },
+    };
+    let r = unsafe {
+        libc::setsockopt(
+            fd,
+            libc::SOL_SOCKET,
+            libc::SO_LINGER,
+            &v as *const _ as *const libc::c_void,
+            std::mem::size_of::<libc::linger>() as libc::socklen_t,
+        )
+    };
+    if r == -1 {
+        Err(errno())
+    } else {
+        Ok(())
+    }
+}
+
+//the seconds to linger, -1 if off
+pub fn get_linger(fd: i32) -> Result<i32, i32> {
+    let mut v: libc::linger = unsafe { std::mem::zeroed() };
+    let mut len = std::mem::size_of::<libc::linger>() as libc::socklen_t;
+    let r = unsafe {
+        libc::getsockopt(
+            fd,
+            libc::SOL_SOCKET,
+            libc::SO_LINGER,
+            &mut v as *mut _ as *mut libc::c_void,
+            &mut len,
+        )
+    };
+    if r == -1 {
+        Err(errno())
+    } else if v.l_onoff != 0 {
+        Ok(v.l_linger)
+    } else {
+        Ok(-1)
+    }
+}
+
+//addresses of host by getaddrinfo, Err as the message of UnknownHostException
+pub fn lookup(host: &str, family: i32) -> Result<Vec<IpAddr>, String> {
+    let name = CString::new(host).map_err(|_| host.to_string())?;
+    let mut hints: libc::addrinfo = unsafe { std::mem::zeroed() };
+    hints.ai_family = family;
+    hints.ai_socktype = libc::SOCK_STREAM;
+    hints.ai_flags = libc::AI_CANONNAME;
+
+    let mut res: *mut libc::addrinfo = std::ptr::null_mut();
+    let r = unsafe { libc::getaddrinfo(name.as_ptr(), std::ptr::null(), &hints, &mut res) };
+    if r != 0 {
+        let reason = unsafe { CStr::from_ptr(libc::gai_strerror(r)) };
+        return Err(format!("{}: {}", host, reason.to_string_lossy()));
+    }
+
+    let mut addrs = vec![];
+    let mut p = res;
+    while !p.is_null() {
+        let ai = unsafe { &*p };
+        if !ai.ai_addr.is_null() {
+            let sa = unsafe { &*(ai.ai_addr as *const libc::sockaddr_storage) };
+            if let Some((ip, _)) = from_sockaddr(sa) {
+                if !addrs.contains(&ip) {
+                    addrs.push(ip);
+                }
+            }
+        }
+        p = ai.ai_next;
+    }
+    unsafe {
+        libc::freeaddrinfo(res);
+    }
+
+    if addrs.is_empty() {
+        Err(format!("{}: Name or service not known", host))
+    } else {
+        Ok(addrs)
+    }
+}
+
+//host name of an address, Err if no name
+pub fn reverse_lookup(ip: IpAddr) -> Result<String, ()> {
+    let mut host = [0 as libc::c_char; 1025];
+    let r = unsafe {
+        match ip {
+            IpAddr::V4(ip) => {
+                let sa = to_sockaddr(ip, 0);
+                libc::getnameinfo(
+                    &sa as *const _ as *const libc::sockaddr,
+                    std::mem::size_of::<libc::sockaddr_in>() as libc::socklen_t,
+                    host.as_mut_ptr(),
+                    host.len() as libc::socklen_t,
+                    std::ptr::null_mut(),
+                    0,
+                    libc::NI_NAMEREQD,
+                )
+            }
+            IpAddr::V6(ip) => {
+                let mut sa: libc::sockaddr_in6 = std::mem::zeroed();
+                sa.sin6_family = libc::AF_INET6 as libc::sa_family_t;
+                sa.sin6_addr.s6_addr = ip.octets();
+                libc::getnameinfo(
+                    &sa as *const _ as *const libc::sockaddr,
+                    std::mem::size_of::<libc::sockaddr_in6>() as libc::socklen_t,
+                    host.as_mut_ptr(),
+                    host.len() as libc::socklen_t,
+                    std::ptr::null_mut(),
+                    0,
+                    libc::NI_NAMEREQD,
+                )
+            }
+        }
+    };
+
+    if r != 0 {
+        return Err(());
+    }
+    let host = unsafe { CStr::from_ptr(host.as_ptr()) };
+    Ok(host.to_string_lossy().to_string())
+}
+
+pub fn local_host_name() -> String {
+    let mut name = [0 as libc::c_char; 256];
+    let r = unsafe { libc::gethostname(name.as_mut_ptr(), name.len()) };
+    if r == 0 {
+        let name = unsafe { CStr::from_ptr(name.as_ptr()) };
+        name.to_string_lossy().to_string()
+    } else {
+        "localhost".to_string()
+    }
+}
+
+//tcp echo port probed as jdk does without raw socket,
+//reachable if connected or refused
+pub fn is_reachable(ip: Ipv4Addr, timeout: i32) -> bool {
+    let fd = match socket(libc::SOCK_STREAM) {
+        Ok(fd) => fd,
+        Err(_) => return false,
+    };
+    let timeout = timeout.max(1);
+    let r = match connect(fd, ip, 7, timeout) {
+        Ok(_) => true,
+        Err(Some(libc::ECONNREFUSED)) => true,
+        Err(_) => false,
+    };
+    unsafe {
+        libc::close(fd);
+    }
+    r
+}
+
+//InetAddress[] of host, shared by Inet4AddressImpl & Inet6AddressImpl
+pub fn lookup_all_host_addr(host: &Oop, family: i32) -> Result<Oop, Oop> {
+    if host.is_null() {
+        return Err(runtime::exception::new(
+            cls_consts::J_NPE,
+            Some("host is null".to_string()),
+        ));
+    }
+
+    let host = OopRef::java_lang_string(host.extract_ref());
+    let addrs =
+        lookup(&host, family).map_err(|msg| new_net_ex(cls_consts::J_UNKNOWN_HOST_EX, &msg))?;
+    let addrs: Vec<Oop> = addrs
+        .into_iter()
+        .map(|ip| new_inet_address(Some(&host), ip))
+        .collect::<Result<_, _>>()?;
+
+    let ary_cls = require_class3(None, b"[Ljava/net/InetAddress;").unwrap();
+    Ok(Oop::new_ref_ary2(ary_cls, addrs))
+}
+
+//host name of the raw address bytes, UnknownHostException if no name
+pub fn host_by_addr(addr: &Oop) -> Result<Oop, Oop> {
+    let ip = {
+        let rf = addr.extract_ref();
+        let ary = rf.extract_type_array();
+        let bytes = ary.extract_bytes();
+        match bytes.len() {
+            4 => {
+                let mut v = [0u8; 4];
+                v.copy_from_slice(bytes);
+                IpAddr::V4(Ipv4Addr::from(v))
+            }
+            16 => {
+                let mut v = [0u8; 16];
+                v.copy_from_slice(bytes);
+                IpAddr::V6(Ipv6Addr::from(v))
+            }
+            _ => return Err(runtime::exception::new(cls_consts::J_UNKNOWN_HOST_EX, None)),
+        }
+    };
+
+    match reverse_lookup(ip) {
+        Ok(host) => Ok(util::oop::new_java_lang_string2(&host)),
+        Err(_) => Err(runtime::exception::new(cls_consts::J_UNKNOWN_HOST_EX, None)),
+    }
+}
+
+pub fn close(fd: i32) {
+    unsafe {
+        libc::close(fd);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn t_loopback() {
+        let lo = Ipv4Addr::LOCALHOST;
+
+        let server = socket(libc::SOCK_STREAM).unwrap();
+        set_int_option(server, libc::SOL_SOCKET, libc::SO_REUSEADDR, 1).unwrap();
+        bind(server, lo, 0).unwrap();
+        assert_eq!(unsafe { libc::listen(server, 1) }, 0);
+        let port = match local_address(server) {
+            Some((IpAddr::V4(ip), port)) if ip == lo => port,
+            v => panic!("local address {:?}", v),
+        };
+
+        //nothing to accept
+        assert_eq!(wait(server, libc::POLLIN, 50), Ok(false));
+
+        let client = socket(libc::SOCK_STREAM).unwrap();
+        connect(client, lo, port, 1000).unwrap();
+        assert_eq!(wait(server, libc::POLLIN, 1000), Ok(true));
+        let conn = unsafe { libc::accept(server, std::ptr::null_mut(), std::ptr::null_mut()) };
+        assert!(conn >= 0);
+
+        //read timeout, then data
+        assert_eq!(wait(conn, libc::POLLIN, 50), Ok(false));
+        let n = unsafe { libc::send(client, b"ping".as_ptr() as *const _, 4, SEND_FLAGS) };
+        assert_eq!(n, 4);
+        assert_eq!(wait(conn, libc::POLLIN, 1000), Ok(true));
+        assert_eq!(available(conn), Ok(4));
+
+        assert!(set_linger(client, true, 3).is_ok());
+        assert_eq!(get_linger(client), Ok(3));
+
+        close(conn);
+        close(client);
+        close(server);
+
+        //nobody listening now
+        let client = socket(libc::SOCK_STREAM).unwrap();
+        assert_eq!(
+            connect(client, lo, port, 1000),
+            Err(Some(libc::ECONNREFUSED))
+        );
+        close(client);
+    }
+
+    #[test]
+    fn t_lookup() {
+        let addrs = lookup("localhost", libc::AF_INET).unwrap();
+        assert!(addrs.contains(&IpAddr::V4(Ipv4Addr::LOCALHOST)));
+        assert!(lookup("no-such-host.invalid", libc::AF_INET).is_err());
+        assert!(!local_host_name().is_empty());
+    }
+}
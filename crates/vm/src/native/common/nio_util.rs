@@ -0,0 +1,69 @@
+use crate::native::common::io_util;
+use crate::new_br;
+use crate::oop::{self, Oop};
+use crate::runtime;
+
+/*
+Shared by the natives of sun.nio.ch, like nio_util.c of jdk.
+
+Results follow sun.nio.ch.IOStatus, the failures not thrown are
+negative codes the channels retry or report.
+*/
+
+//sun.nio.ch.IOStatus
+pub const IOS_EOF: i64 = -1;
+pub const IOS_UNAVAILABLE: i64 = -2;
+pub const IOS_INTERRUPTED: i64 = -3;
+pub const IOS_UNSUPPORTED_CASE: i64 = -6;
+
+pub fn errno() -> i32 {
+    std::io::Error::last_os_error().raw_os_error().unwrap_or(0)
+}
+
+//the result of a read or write, 'n' of os; 0 of a read means end of stream
+pub fn convert_return(n: isize, reading: bool) -> Result<i64, Oop> {
+    if n > 0 {
+        Ok(n as i64)
+    } else if n == 0 {
+        Ok(if reading { IOS_EOF } else { 0 })
+    } else {
+        match errno() {
+            libc::EAGAIN => Ok(IOS_UNAVAILABLE),
+            libc::EINTR => Ok(IOS_INTERRUPTED),
+            e => Err(io_util::new_io_ex(&io_util::strerror(e))),
+        }
+    }
+}
+
+//the os fd of a FileDescriptor
+pub fn fd_val(fd_obj: &Oop) -> i32 {
+    io_util::file_descriptor_fd(fd_obj)
+}
+
+pub fn new_io_ex_errno(default: &str) -> Oop {
+    io_util::new_io_ex(&io_util::last_error(default))
+}
+
+//sun.nio.fs.UnixException of errno, the failures of the file system natives
+pub fn new_unix_ex(errno: i32) -> Oop {
+    let cls = match oop::class::load_and_init(b"sun/nio/fs/UnixException") {
+        Ok(cls) => cls,
+        Err(ex) => return ex,
+    };
+    let ex = Oop::new_inst(cls.clone());
+    let args = vec![ex.clone(), Oop::new_int(errno)];
+    runtime::invoke::invoke_ctor(cls, new_br("(I)V"), args);
+    ex
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn t_convert_return() {
+        assert!(matches!(convert_return(5, true), Ok(5)));
+        assert!(matches!(convert_return(0, true), Ok(IOS_EOF)));
+        assert!(matches!(convert_return(0, false), Ok(0)));
+    }
+}
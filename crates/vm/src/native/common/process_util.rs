@@ -0,0 +1,364 @@
+use crate::native::common::io_util;
+use std::ffi::{CStr, CString};
+use std::os::raw::c_char;
+
+/*
+Child processes of UNIXProcess, like childproc.c of jdk.
+
+Everything the child needs built before fork, the child only calls
+async-signal-safe syscalls until exec; the errno of a failed exec sent back
+on a CLOEXEC pipe, so a successful exec reads as end of stream.
+*/
+
+//the fd of the fail pipe in the child
+const FAIL_FILENO: i32 = 3;
+
+pub struct ChildSpec {
+    pub prog: CString,
+    //argv[1..]
+    pub args: Vec<CString>,
+    //None to inherit the environment
+    pub envs: Option<Vec<CString>>,
+    pub dir: Option<CString>,
+    //stdin, stdout & stderr of the child: -1 for a new pipe, or the fd redirected to
+    pub fds: [i32; 3],
+    pub redirect_error_stream: bool,
+}
+
+//fork & exec, the parent ends of the pipes returned in place of the -1 in fds
+pub fn fork_and_exec(spec: &ChildSpec) -> Result<(i32, [i32; 3]), String> {
+    let candidates = exec_candidates(&spec.prog);
+    let mut argv: Vec<*const c_char> = vec![spec.prog.as_ptr()];
+    argv.extend(spec.args.iter().map(|it| it.as_ptr()));
+    argv.push(std::ptr::null());
+    let envp: Option<Vec<*const c_char>> = spec.envs.as_ref().map(|envs| {
+        let mut envp: Vec<*const c_char> = envs.iter().map(|it| it.as_ptr()).collect();
+        envp.push(std::ptr::null());
+        envp
+    });
+    let max_fd = max_open_fd();
+
+    let mut pipes = [[-1i32; 2]; 3];
+    let mut fail = [-1i32; 2];
+    for (i, p) in pipes.iter_mut().enumerate() {
+        if spec.fds[i] == -1 {
+            if let Err(e) = pipe_cloexec(p) {
+                close_all(&pipes);
+                return Err(e);
+            }
+        }
+    }
+    if let Err(e) = pipe_cloexec(&mut fail) {
+        close_all(&pipes);
+        return Err(e);
+    }
+
+    let pid = unsafe { libc::fork() };
+    if pid == 0 {
+        //child, never returns
+        unsafe {
+            child_process(
+                spec,
+                &pipes,
+                fail[1],
+                max_fd,
+                &candidates,
+                &argv,
+                envp.as_deref(),
+            )
+        }
+    }
+
+    //parent, the child ends closed
+    unsafe {
+        libc::close(fail[1]);
+        close_fd(pipes[0][0]);
+        close_fd(pipes[1][1]);
+        close_fd(pipes[2][1]);
+    }
+    let parent_ends = [pipes[0][1], pipes[1][0], pipes[2][0]];
+    if pid == -1 {
+        let err = error_message(errno(), "Fork failed");
+        unsafe {
+            libc::close(fail[0]);
+        }
+        parent_ends.iter().for_each(|fd| unsafe { close_fd(*fd) });
+        return Err(err);
+    }
+
+    let mut errnum = [0u8; 4];
+    let n = read_fully(fail[0], &mut errnum);
+    unsafe {
+        libc::close(fail[0]);
+    }
+    match n {
+        0 => Ok((pid, parent_ends)),
+        4 => {
+            wait_for_exit(pid);
+            parent_ends.iter().for_each(|fd| unsafe { close_fd(*fd) });
+            let errnum = i32::from_ne_bytes(errnum);
+            Err(error_message(errnum, "Exec failed"))
+        }
+        _ => {
+            let err = error_message(errno(), "Read failed");
+            parent_ends.iter().for_each(|fd| unsafe { close_fd(*fd) });
+            Err(err)
+        }
+    }
+}
+
+//the exit code, or 0x80 + the signal that killed it
+pub fn wait_for_exit(pid: i32) -> i32 {
+    let mut status = 0;
+    loop {
+        if unsafe { libc::waitpid(pid, &mut status, 0) } != -1 {
+            break;
+        }
+        match errno() {
+            libc::EINTR => continue,
+            //ECHILD, the child reaped by somebody else
+            _ => return 0,
+        }
+    }
+
+    unsafe {
+        if libc::WIFEXITED(status) {
+            libc::WEXITSTATUS(status)
+        } else if libc::WIFSIGNALED(status) {
+            0x80 + libc::WTERMSIG(status)
+        } else {
+            status
+        }
+    }
+}
+
+//as jdk: "error=2, No such file or directory"
+fn error_message(errnum: i32, default: &str) -> String {
+    if errnum == 0 {
+        default.to_string()
+    } else {
+        format!("error={}, {}", errnum, io_util::strerror(errnum))
+    }
+}
+
+fn errno() -> i32 {
+    std::io::Error::last_os_error().raw_os_error().unwrap_or(0)
+}
+
+//the paths exec tries in turn, by PATH of the parent if no '/' in prog
+fn exec_candidates(prog: &CStr) -> Vec<CString> {
+    let bytes = prog.to_bytes();
+    if bytes.contains(&b'/') {
+        return vec![prog.to_owned()];
+    }
+
+    let path = std::env::var("PATH").unwrap_or_else(|_| "/bin:/usr/bin".to_string());
+    path.split(':')
+        .map(|dir| if dir.is_empty() { "." } else { dir })
+        .filter_map(|dir| {
+            let mut v = dir.as_bytes().to_vec();
+            v.push(b'/');
+            v.extend_from_slice(bytes);
+            CString::new(v).ok()
+        })
+        .collect()
+}
+
+//the fds to close in the child, those listed in /proc/self/fd where there is
+#[cfg(target_os = "linux")]
+fn max_open_fd() -> i32 {
+    match std::fs::read_dir("/proc/self/fd") {
+        Ok(dir) => dir
+            .filter_map(|it| it.ok())
+            .filter_map(|it| it.file_name().to_str().and_then(|s| s.parse().ok()))
+            .max()
+            .unwrap_or(FAIL_FILENO),
+        Err(_) => sysconf_open_max(),
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn max_open_fd() -> i32 {
+    sysconf_open_max()
+}
+
+fn sysconf_open_max() -> i32 {
+    let v = unsafe { libc::sysconf(libc::_SC_OPEN_MAX) };
+    if v == -1 {
+        1024
+    } else {
+        v.min(i32::MAX as libc::c_long) as i32
+    }
+}
+
+fn pipe_cloexec(fds: &mut [i32; 2]) -> Result<(), String> {
+    if unsafe { libc::pipe(fds.as_mut_ptr()) } == -1 {
+        return Err(error_message(errno(), "Bad file descriptor"));
+    }
+    for fd in fds.iter() {
+        unsafe {
+            libc::fcntl(*fd, libc::F_SETFD, libc::FD_CLOEXEC);
+        }
+    }
+    Ok(())
+}
+
+fn close_all(pipes: &[[i32; 2]; 3]) {
+    for p in pipes.iter() {
+        for fd in p.iter() {
+            unsafe { close_fd(*fd) };
+        }
+    }
+}
+
+unsafe fn close_fd(fd: i32) {
+    if fd != -1 {
+        libc::close(fd);
+    }
+}
+
+fn read_fully(fd: i32, buf: &mut [u8]) -> usize {
+    let mut pos = 0;
+    while pos < buf.len() {
+        let n = unsafe {
+            libc::read(
+                fd,
+                buf[pos..].as_mut_ptr() as *mut libc::c_void,
+                buf.len() - pos,
+            )
+        };
+        if n == 0 {
+            break;
+        }
+        if n == -1 {
+            if errno() == libc::EINTR {
+                continue;
+            }
+            break;
+        }
+        pos += n as usize;
+    }
+    pos
+}
+
+//'from' as 'to', the CLOEXEC of a pipe cleared if already there
+unsafe fn move_descriptor(from: i32, to: i32) -> bool {
+    if from == to {
+        return libc::fcntl(from, libc::F_SETFD, 0) != -1;
+    }
+    while libc::dup2(from, to) == -1 {
+        if errno() != libc::EINTR {
+            return false;
+        }
+    }
+    libc::close(from);
+    true
+}
+
+unsafe fn child_process(
+    spec: &ChildSpec,
+    pipes: &[[i32; 2]; 3],
+    fail: i32,
+    max_fd: i32,
+    candidates: &[CString],
+    argv: &[*const c_char],
+    envp: Option<&[*const c_char]>,
+) -> ! {
+    //the parent ends
+    close_fd(pipes[0][1]);
+    close_fd(pipes[1][0]);
+    close_fd(pipes[2][0]);
+
+    let child_end = |i: usize, end: usize| {
+        if pipes[i][end] != -1 {
+            pipes[i][end]
+        } else {
+            spec.fds[i]
+        }
+    };
+
+    let mut ok = move_descriptor(child_end(0, 0), libc::STDIN_FILENO)
+        && move_descriptor(child_end(1, 1), libc::STDOUT_FILENO);
+    ok = ok
+        && if spec.redirect_error_stream {
+            close_fd(pipes[2][1]);
+            libc::dup2(libc::STDOUT_FILENO, libc::STDERR_FILENO) != -1
+        } else {
+            move_descriptor(child_end(2, 1), libc::STDERR_FILENO)
+        };
+    ok = ok && move_descriptor(fail, FAIL_FILENO);
+
+    if ok {
+        for fd in (FAIL_FILENO + 1)..=max_fd {
+            libc::close(fd);
+        }
+        libc::fcntl(FAIL_FILENO, libc::F_SETFD, libc::FD_CLOEXEC);
+
+        ok = match &spec.dir {
+            Some(dir) => libc::chdir(dir.as_ptr()) != -1,
+            None => true,
+        };
+    }
+
+    let mut errnum = errno();
+    if ok {
+        //the first error other than ENOENT reported, as execvp
+        errnum = libc::ENOENT;
+        for path in candidates.iter() {
+            match envp {
+                Some(envp) => libc::execve(path.as_ptr(), argv.as_ptr(), envp.as_ptr()),
+                None => libc::execv(path.as_ptr(), argv.as_ptr()),
+            };
+            let e = errno();
+            if e != libc::ENOENT && e != libc::ENOTDIR && errnum == libc::ENOENT {
+                errnum = e;
+            }
+        }
+    }
+
+    let v = errnum.to_ne_bytes();
+    libc::write(FAIL_FILENO, v.as_ptr() as *const libc::c_void, v.len());
+    libc::_exit(-1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn c(s: &str) -> CString {
+        CString::new(s).unwrap()
+    }
+
+    #[test]
+    fn t_echo() {
+        let spec = ChildSpec {
+            prog: c("echo"),
+            args: vec![c("hello")],
+            envs: None,
+            dir: None,
+            fds: [-1, -1, -1],
+            redirect_error_stream: false,
+        };
+        let (pid, fds) = fork_and_exec(&spec).unwrap();
+
+        let mut buf = [0u8; 16];
+        let n = read_fully(fds[1], &mut buf);
+        assert_eq!(&buf[..n], b"hello\n");
+        assert_eq!(wait_for_exit(pid), 0);
+        fds.iter().for_each(|fd| unsafe { close_fd(*fd) });
+    }
+
+    #[test]
+    fn t_exec_failed() {
+        let spec = ChildSpec {
+            prog: c("/nonexistent/prog"),
+            args: vec![],
+            envs: Some(vec![c("A=1")]),
+            dir: None,
+            fds: [-1, -1, -1],
+            redirect_error_stream: true,
+        };
+        let err = fork_and_exec(&spec).err().unwrap();
+        assert!(err.starts_with(&format!("error={}, ", libc::ENOENT)));
+    }
+}
@@ -1,15 +1,21 @@
 #![allow(non_snake_case)]
 
 use crate::native::java_lang_Class;
-use crate::oop::{self, Class, Oop, OopRef};
-use crate::runtime::{self, require_class3};
+use crate::new_br;
+use crate::oop::{self, Class, Oop};
+use crate::runtime::{self, cmp, require_class3};
 use crate::types::*;
 use crate::util;
 use class_parser::{FieldSignature, MethodSignature};
 use classfile::consts as cls_const;
-use classfile::SignatureType;
+use classfile::flags::ACC_STATIC;
+use classfile::{BytesRef, SignatureType};
 use std::sync::Arc;
 
+lazy_static! {
+    static ref S_VALUE: BytesRef = new_br("value");
+}
+
 pub fn new_field(fir: FieldIdRef) -> Oop {
     let field_cls = runtime::require_class3(None, cls_const::J_FIELD).unwrap();
 
@@ -17,8 +23,11 @@ pub fn new_field(fir: FieldIdRef) -> Oop {
 
     let field_sig = FieldSignature::new(fir.field.desc.as_slice());
     let typ_mirror = create_value_type(field_sig.field_type);
-    let desc = unsafe { std::str::from_utf8_unchecked(fir.field.desc.as_slice()) };
-    let signature = util::oop::new_java_lang_string2(desc);
+    let signature = new_generic_signature(fir.field.get_attr_signature());
+    let annotations = match fir.field.get_annotation() {
+        Some(raw) => Oop::new_byte_ary2(raw),
+        None => oop::consts::get_null(),
+    };
 
     let field_name = unsafe { std::str::from_utf8_unchecked(fir.field.name.as_slice()) };
     let mut desc = Vec::new();
@@ -34,7 +43,7 @@ pub fn new_field(fir: FieldIdRef) -> Oop {
         ("modifiers", "I", Oop::new_int(fir.field.acc_flags as i32)),
         ("slot", "I", Oop::new_int(fir.offset as i32)),
         ("signature", "Ljava/lang/String;", signature),
-        ("annotations", "[B", oop::consts::get_null()),
+        ("annotations", "[B", annotations),
     ]
     .iter()
     .map(|(_, t, v)| {
@@ -67,17 +76,15 @@ pub fn new_method_ctor(mir: MethodIdRef) -> Oop {
     let cls = require_class3(None, b"[Ljava/lang/Class;").unwrap();
     let parameter_types = Oop::new_ref_ary2(cls, params);
 
-    //fixme: checkedExceptions
-    let cls = require_class3(None, b"[Ljava/lang/Class;").unwrap();
-    let checked_exceptions = Oop::new_ref_ary2(cls, vec![]);
+    //checkedExceptions
+    let checked_exceptions = new_checked_exceptions(&mir);
 
     //modifiers
     let modifiers = mir.method.acc_flags;
     //slot
     let slot = mir.offset;
     //signature
-    let desc = unsafe { std::str::from_utf8_unchecked(mir.method.desc.as_slice()) };
-    let signature = util::oop::new_java_lang_string2(desc);
+    let signature = new_generic_signature(mir.method.get_attr_signature());
     let annotations = {
         let raw = mir.method.get_annotation();
         match raw {
@@ -149,19 +156,15 @@ pub fn new_method_normal(mir: MethodIdRef) -> Oop {
     //returnType
     let return_type = create_value_type(signature.retype);
 
-    //fixme: checkedExceptions
-    let cls = require_class3(None, b"[Ljava/lang/Class;").unwrap();
-    let checked_exceptions = Oop::new_ref_ary2(cls, vec![]);
+    //checkedExceptions
+    let checked_exceptions = new_checked_exceptions(&mir);
 
     //modifiers
     let modifiers = mir.method.acc_flags;
     //slot
     let slot = mir.offset;
     //signature
-    let signature = {
-        let desc = unsafe { std::str::from_utf8_unchecked(mir.method.desc.as_slice()) };
-        util::oop::new_java_lang_string2(desc)
-    };
+    let signature = new_generic_signature(mir.method.get_attr_signature());
     let annotations = {
         let raw = mir.method.get_annotation();
         match raw {
@@ -218,51 +221,262 @@ pub fn new_method_normal(mir: MethodIdRef) -> Oop {
     oop
 }
 
-pub fn get_Constructor_clazz(ctor: &Oop) -> Oop {
-    //todo: optimize, avoid obtain class
+//reflect.Method & reflect.Constructor both have 'clazz' & 'slot'
+//find the MethodId by 'clazz' & 'slot' of reflect.Method or reflect.Constructor,
+//InternalError if not a method of the class
+pub fn get_method_id(executable: &Oop) -> Result<MethodIdRef, Oop> {
+    let rf = executable.extract_ref();
     let cls = {
-        let rf = ctor.extract_ref();
         let inst = rf.extract_inst();
         inst.class.clone()
     };
 
-    //todo: optimize, avoid obtain id
-    let cls = cls.get_class();
-    let id = cls.get_field_id(&util::S_CLAZZ, &util::S_JAVA_LANG_CLASS, false);
-    Class::get_field_value(ctor.extract_ref(), id)
+    let (clazz, slot) = {
+        let cls = cls.get_class();
+        let id = cls.get_field_id(&util::S_CLAZZ, &util::S_JAVA_LANG_CLASS, false);
+        let clazz = Class::get_field_value(rf.clone(), id);
+        let id = cls.get_field_id(&util::S_SLOT, &util::S_I, false);
+        let slot = Class::get_field_value(rf.clone(), id).extract_int();
+        (clazz, slot)
+    };
+
+    let target = declaring_class(&clazz)?;
+    let cls = target.get_class();
+    match cls.get_method_by_slot(slot as usize) {
+        Some(mir) => Ok(mir),
+        None => {
+            let msg = format!("invalid method slot {} of {}", slot, class_name(&target));
+            Err(runtime::exception::new(
+                cls_const::J_INTERNAL_ERROR,
+                Some(msg),
+            ))
+        }
+    }
 }
 
-/*
-pub fn get_Constructor_slot(ctor: &Oop) -> i32 {
-    let cls = {
-        let v = util::oop::extract_ref(ctor);
-        let v = v.read().unwrap();
-        match &v.v {
-            oop::RefKind::Inst(inst) => inst.class.clone(),
-            _ => unreachable!(),
+//find the FieldId by 'clazz', 'slot' & 'modifiers' of reflect.Field,
+//InternalError if not a field of the class
+pub fn get_field_id(field: &Oop) -> Result<FieldIdRef, Oop> {
+    let rf = field.extract_ref();
+    let cls = require_class3(None, cls_const::J_FIELD).unwrap();
+
+    let (clazz, slot, modifiers) = {
+        let cls = cls.get_class();
+        let id = cls.get_field_id(&util::S_CLAZZ, &util::S_JAVA_LANG_CLASS, false);
+        let clazz = Class::get_field_value(rf.clone(), id);
+        let id = cls.get_field_id(&util::S_SLOT, &util::S_I, false);
+        let slot = Class::get_field_value(rf.clone(), id).extract_int();
+        let id = cls.get_field_id(&util::S_MODIFIERS, &util::S_I, false);
+        let modifiers = Class::get_field_value(rf.clone(), id).extract_int();
+        (clazz, slot as usize, modifiers as u16)
+    };
+
+    let target = declaring_class(&clazz)?;
+    let found = match &target.get_class().kind {
+        oop::ClassKind::Instance(cls_obj) => {
+            let fields = if (modifiers & ACC_STATIC) == ACC_STATIC {
+                &cls_obj.static_fields
+            } else {
+                &cls_obj.inst_fields
+            };
+            fields
+                .values()
+                .find(|fir| fir.offset == slot && Arc::ptr_eq(&fir.field.class, &target))
+                .cloned()
         }
+        _ => None,
     };
 
-    let cls = cls.read().unwrap();
-    let id = cls.get_field_id(b"slot", b"I", false);
-    let v = cls.get_field_value(ctor, id);
-    util::oop::extract_int(&v)
+    found.ok_or_else(|| {
+        let msg = format!("invalid field slot {} of {}", slot, class_name(&target));
+        runtime::exception::new(cls_const::J_INTERNAL_ERROR, Some(msg))
+    })
 }
-*/
 
-pub fn get_Constructor_signature(ctor: &Oop) -> String {
-    //todo: optimisze, cache Constructor cls, avoid obtain class
-    let cls = {
-        let rf = ctor.extract_ref();
-        let inst = rf.extract_inst();
-        inst.class.clone()
+//the class of 'clazz' of a reflect object, not null nor a primitive
+fn declaring_class(clazz: &Oop) -> Result<ClassRef, Oop> {
+    let target = match clazz {
+        Oop::Ref(rf) => rf.extract_mirror().target.clone(),
+        _ => None,
+    };
+    target.ok_or_else(|| {
+        let msg = Some("reflection object without a declaring class".to_string());
+        runtime::exception::new(cls_const::J_INTERNAL_ERROR, msg)
+    })
+}
+
+fn class_name(cls: &ClassRef) -> String {
+    String::from_utf8_lossy(cls.get_class().name.as_slice()).replace("/", ".")
+}
+
+//convert 'args' of Method.invoke & Constructor.newInstance to the types of 'mir',
+//unboxing & widening primitives
+pub fn unbox_args(mir: &MethodIdRef, args: &Oop) -> Result<Vec<Oop>, Oop> {
+    let args = match args {
+        Oop::Null => vec![],
+        Oop::Ref(rf) => rf.extract_array().elements.to_vec(),
+        _ => unreachable!(),
+    };
+
+    let params = &mir.method.signature.args;
+    if args.len() != params.len() {
+        let msg = Some("wrong number of arguments".to_string());
+        return Err(runtime::exception::new(cls_const::J_ILLEGAL_ARGUMENT, msg));
+    }
+
+    params
+        .iter()
+        .zip(args.iter())
+        .map(|(t, v)| unbox(t, v))
+        .collect()
+}
+
+pub fn unbox(t: &SignatureType, v: &Oop) -> Result<Oop, Oop> {
+    let to = match t {
+        SignatureType::Object(desc, _, _) | SignatureType::Array(desc) => {
+            return match v {
+                Oop::Null => Ok(v.clone()),
+                _ => {
+                    let name = match t {
+                        SignatureType::Object(_, _, _) => &desc.as_slice()[1..desc.len() - 1],
+                        _ => desc.as_slice(),
+                    };
+                    let target = require_class3(None, name);
+                    let is_inst = match (util::oop::get_oop_class(v), target) {
+                        (Some(cls), Some(target)) => cmp::instance_of(cls, target),
+                        _ => false,
+                    };
+                    if is_inst {
+                        Ok(v.clone())
+                    } else {
+                        Err(new_arg_mismatch())
+                    }
+                }
+            };
+        }
+        SignatureType::Byte => b'B',
+        SignatureType::Char => b'C',
+        SignatureType::Int => b'I',
+        SignatureType::Double => b'D',
+        SignatureType::Float => b'F',
+        SignatureType::Long => b'J',
+        SignatureType::Short => b'S',
+        SignatureType::Boolean => b'Z',
+        SignatureType::Void => unreachable!(),
     };
 
-    //todo: optimize, cache id
-    let cls = cls.get_class();
-    let id = cls.get_field_id(&util::S_SIGNATURE, &util::S_JAVA_LANG_STRING, false);
-    let v = Class::get_field_value(ctor.extract_ref(), id);
-    OopRef::java_lang_string(v.extract_ref())
+    let cls = match util::oop::get_oop_class(v) {
+        Some(cls) => cls,
+        None => return Err(new_arg_mismatch()),
+    };
+    let from = match get_box_type(cls.get_class().name.as_slice()) {
+        Some(from) => from,
+        None => return Err(new_arg_mismatch()),
+    };
+    let value = {
+        let cls = cls.get_class();
+        let desc = new_br(unsafe { std::str::from_utf8_unchecked(&[from]) });
+        let fid = cls.get_field_id(&S_VALUE, &desc, false);
+        Class::get_field_value(v.extract_ref(), fid)
+    };
+
+    //JLS 5.1.2 Widening Primitive Conversion
+    let v = match (from, to) {
+        (from, to) if from == to => value,
+        (b'B', b'S') | (b'B', b'I') | (b'S', b'I') | (b'C', b'I') => value,
+        (b'B', b'J') | (b'S', b'J') | (b'C', b'J') | (b'I', b'J') => {
+            Oop::new_long(value.extract_int() as i64)
+        }
+        (b'B', b'F') | (b'S', b'F') | (b'C', b'F') | (b'I', b'F') => {
+            Oop::new_float(value.extract_int() as f32)
+        }
+        (b'B', b'D') | (b'S', b'D') | (b'C', b'D') | (b'I', b'D') => {
+            Oop::new_double(value.extract_int() as f64)
+        }
+        (b'J', b'F') => Oop::new_float(value.extract_long() as f32),
+        (b'J', b'D') => Oop::new_double(value.extract_long() as f64),
+        (b'F', b'D') => Oop::new_double(value.extract_float() as f64),
+        _ => return Err(new_arg_mismatch()),
+    };
+
+    Ok(v)
+}
+
+//box the primitive 'v' of type 't', others returned as it is
+pub fn box_value(t: &SignatureType, v: Oop) -> Result<Oop, Oop> {
+    let (name, desc): (&[u8], &str) = match t {
+        SignatureType::Byte => (b"java/lang/Byte", "B"),
+        SignatureType::Char => (b"java/lang/Character", "C"),
+        SignatureType::Int => (b"java/lang/Integer", "I"),
+        SignatureType::Double => (b"java/lang/Double", "D"),
+        SignatureType::Float => (b"java/lang/Float", "F"),
+        SignatureType::Long => (b"java/lang/Long", "J"),
+        SignatureType::Short => (b"java/lang/Short", "S"),
+        SignatureType::Boolean => (b"java/lang/Boolean", "Z"),
+        SignatureType::Object(_, _, _) | SignatureType::Array(_) | SignatureType::Void => {
+            return Ok(v)
+        }
+    };
+
+    let cls = oop::class::load_and_init(name)?;
+
+    let fid = {
+        let cls = cls.get_class();
+        cls.get_field_id(&S_VALUE, &new_br(desc), false)
+    };
+    let oop = Oop::new_inst(cls);
+    Class::put_field_value(oop.extract_ref(), fid, v);
+    Ok(oop)
+}
+
+//wrap the exception thrown by the invoked method
+pub fn new_invocation_target_exception(target: Oop) -> Oop {
+    let cls = match oop::class::load_and_init(cls_const::J_INVOCATION_TARGET_EX) {
+        Ok(cls) => cls,
+        Err(ex) => return ex,
+    };
+
+    let ex = Oop::new_inst(cls.clone());
+    let args = vec![ex.clone(), target];
+    runtime::invoke::invoke_ctor(cls, new_br("(Ljava/lang/Throwable;)V"), args);
+    ex
+}
+
+fn new_arg_mismatch() -> Oop {
+    let msg = Some("argument type mismatch".to_string());
+    runtime::exception::new(cls_const::J_ILLEGAL_ARGUMENT, msg)
+}
+
+fn get_box_type(name: &[u8]) -> Option<u8> {
+    match name {
+        b"java/lang/Byte" => Some(b'B'),
+        b"java/lang/Character" => Some(b'C'),
+        b"java/lang/Integer" => Some(b'I'),
+        b"java/lang/Double" => Some(b'D'),
+        b"java/lang/Float" => Some(b'F'),
+        b"java/lang/Long" => Some(b'J'),
+        b"java/lang/Short" => Some(b'S'),
+        b"java/lang/Boolean" => Some(b'Z'),
+        _ => None,
+    }
+}
+
+fn new_generic_signature(signature: Option<BytesRef>) -> Oop {
+    match signature {
+        Some(signature) => util::oop::new_java_lang_string3(signature.as_slice()),
+        None => oop::consts::get_null(),
+    }
+}
+
+fn new_checked_exceptions(mir: &MethodIdRef) -> Oop {
+    let exceptions: Vec<Oop> = mir
+        .method
+        .get_throws()
+        .iter()
+        .map(|cls| cls.get_class().get_mirror())
+        .collect();
+    let cls = require_class3(None, b"[Ljava/lang/Class;").unwrap();
+    Oop::new_ref_ary2(cls, exceptions)
 }
 
 fn create_value_type(t: SignatureType) -> Oop {
@@ -0,0 +1,426 @@
+use crate::util;
+use std::ffi::CStr;
+use std::path::Path;
+
+/*
+System properties of System.initProperties, derived from the host
+like java_props_md.c of jdk: uname, locale env, passwd entry, class path.
+
+'-D' of user overrides all
+*/
+
+pub type Props = Vec<(String, String)>;
+
+pub fn create(class_path: &str, boot_class_path: &str, user_props: &[(String, String)]) -> Props {
+    let mut props = Props::new();
+
+    let fixed = [
+        ("file.encoding.pkg", "sun.io"),
+        ("file.separator", util::FILE_SEP),
+        ("java.class.version", "52.0"),
+        ("java.security.egd", "file:/dev/random"),
+        ("java.specification.version", "1.8"),
+        ("java.specification.name", "Java Platform API Specification"),
+        ("java.specification.vendor", "Oracle Corporation"),
+        ("java.vendor", "Chuan"),
+        ("java.vendor.url", "https://github.com/douchuan/jvm"),
+        ("java.vendor.url.bug", "https://github.com/douchuan/jvm"),
+        ("java.version", "1.8"),
+        ("line.separator", util::LINE_SEP),
+        ("path.separator", util::PATH_SEP),
+        ("sun.cpu.isalist", ""),
+        //lookup cache of URLClassPath, backed by the index of class path
+        ("sun.cds.enableSharedLookupCache", "true"),
+    ];
+    for (k, v) in fixed.iter() {
+        put(&mut props, k, v);
+    }
+
+    //os & cpu
+    let (sys_name, release) = uname();
+    put(&mut props, "os.name", &os_name(&sys_name));
+    put(&mut props, "os.version", &release);
+    put(&mut props, "os.arch", os_arch());
+    put(
+        &mut props,
+        "sun.arch.data.model",
+        &(std::mem::size_of::<usize>() * 8).to_string(),
+    );
+    if cfg!(target_endian = "little") {
+        put(&mut props, "sun.cpu.endian", "little");
+        put(&mut props, "sun.io.unicode.encoding", "UnicodeLittle");
+    } else {
+        put(&mut props, "sun.cpu.endian", "big");
+        put(&mut props, "sun.io.unicode.encoding", "UnicodeBig");
+    }
+
+    //locale
+    let locale = parse_locale(&locale_env());
+    put(&mut props, "user.language", &locale.language);
+    put(&mut props, "user.country", &locale.country);
+    put(&mut props, "file.encoding", &locale.encoding);
+    put(&mut props, "sun.jnu.encoding", &locale.encoding);
+    //only for terminal, like jdk
+    if is_tty(libc::STDOUT_FILENO) {
+        put(&mut props, "sun.stdout.encoding", &locale.encoding);
+    }
+    if is_tty(libc::STDERR_FILENO) {
+        put(&mut props, "sun.stderr.encoding", &locale.encoding);
+    }
+
+    //user
+    let (user_name, user_home) = user_info();
+    put(&mut props, "user.name", &user_name);
+    put(&mut props, "user.home", &user_home);
+    let user_dir = std::env::current_dir()
+        .map(|v| v.to_string_lossy().to_string())
+        .unwrap_or_else(|_| "?".to_string());
+    put(&mut props, "user.dir", &user_dir);
+    put(&mut props, "java.io.tmpdir", &tmp_dir());
+
+    //paths
+    let java_home = java_home(std::env::var("JAVA_HOME").ok(), boot_class_path);
+    let boot_library_path = Path::new(&java_home)
+        .join("lib")
+        .join(os_arch())
+        .to_string_lossy()
+        .to_string();
+    put(&mut props, "java.home", &java_home);
+    put(&mut props, "sun.boot.library.path", &boot_library_path);
+    put(&mut props, "java.library.path", &library_path());
+    put(&mut props, "sun.boot.class.path", boot_class_path);
+    put(&mut props, "java.class.path", class_path);
+
+    //test.src for jdk/test/java/lang/Character/CheckProp.java
+    if let Ok(v) = std::env::var("TEST_SRC") {
+        put(&mut props, "test.src", &v);
+    }
+
+    for (k, v) in user_props.iter() {
+        put(&mut props, k, v);
+    }
+
+    props
+}
+
+//replace the old value, keep the order
+fn put(props: &mut Props, k: &str, v: &str) {
+    match props.iter_mut().find(|(k2, _)| k2 == k) {
+        Some(it) => it.1 = v.to_string(),
+        None => props.push((k.to_string(), v.to_string())),
+    }
+}
+
+//(sysname, release)
+fn uname() -> (String, String) {
+    unsafe {
+        let mut name: libc::utsname = std::mem::zeroed();
+        if libc::uname(&mut name) != 0 {
+            return ("Unknown".to_string(), "Unknown".to_string());
+        }
+
+        let sys_name = CStr::from_ptr(name.sysname.as_ptr());
+        let release = CStr::from_ptr(name.release.as_ptr());
+        (
+            sys_name.to_string_lossy().to_string(),
+            release.to_string_lossy().to_string(),
+        )
+    }
+}
+
+fn os_name(sys_name: &str) -> String {
+    match sys_name {
+        "Darwin" => "Mac OS X".to_string(),
+        "SunOS" => "SunOS".to_string(),
+        v => v.to_string(),
+    }
+}
+
+fn os_arch() -> &'static str {
+    match std::env::consts::ARCH {
+        //"x86_64" on mac
+        "x86_64" if cfg!(target_os = "macos") => "x86_64",
+        "x86_64" => "amd64",
+        "x86" => "i386",
+        v => v,
+    }
+}
+
+struct Locale {
+    language: String,
+    country: String,
+    encoding: String,
+}
+
+//the same order with setlocale(LC_CTYPE, "")
+fn locale_env() -> String {
+    ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .filter_map(|k| std::env::var(k).ok())
+        .find(|v| !v.is_empty())
+        .unwrap_or_else(|| "C".to_string())
+}
+
+//language[_territory][.codeset][@modifier]
+fn parse_locale(v: &str) -> Locale {
+    let v = match v.find('@') {
+        Some(idx) => &v[..idx],
+        None => v,
+    };
+    let (name, codeset) = match v.find('.') {
+        Some(idx) => (&v[..idx], Some(&v[idx + 1..])),
+        None => (v, None),
+    };
+
+    if name == "C" || name == "POSIX" || name.is_empty() {
+        let encoding = codeset
+            .map(normalize_encoding)
+            .unwrap_or_else(|| "ANSI_X3.4-1968".to_string());
+        return Locale {
+            language: "en".to_string(),
+            country: "".to_string(),
+            encoding,
+        };
+    }
+
+    let (language, country) = match name.find('_') {
+        Some(idx) => (&name[..idx], &name[idx + 1..]),
+        None => (name, ""),
+    };
+    //'C' is the default codeset of glibc for a locale without '.codeset'
+    let encoding = codeset
+        .map(normalize_encoding)
+        .unwrap_or_else(|| "ISO-8859-1".to_string());
+
+    Locale {
+        language: language.to_string(),
+        country: country.to_string(),
+        encoding,
+    }
+}
+
+fn normalize_encoding(v: &str) -> String {
+    match v.to_ascii_lowercase().as_str() {
+        "utf8" | "utf-8" => "UTF-8".to_string(),
+        "iso88591" | "iso-8859-1" => "ISO-8859-1".to_string(),
+        "iso885915" | "iso-8859-15" => "ISO-8859-15".to_string(),
+        "eucjp" | "euc-jp" => "EUC-JP".to_string(),
+        "gbk" => "GBK".to_string(),
+        "gb2312" => "GB2312".to_string(),
+        "big5" => "Big5".to_string(),
+        _ => v.to_string(),
+    }
+}
+
+fn is_tty(fd: libc::c_int) -> bool {
+    unsafe { libc::isatty(fd) == 1 }
+}
+
+//(user.name, user.home) of passwd entry, "?" if unknown, like jdk
+fn user_info() -> (String, String) {
+    let mut name = None;
+    let mut home = None;
+
+    unsafe {
+        let mut pwd: libc::passwd = std::mem::zeroed();
+        let mut result: *mut libc::passwd = std::ptr::null_mut();
+        let mut buf = vec![0 as libc::c_char; 16 * 1024];
+        let r = libc::getpwuid_r(
+            libc::getuid(),
+            &mut pwd,
+            buf.as_mut_ptr(),
+            buf.len(),
+            &mut result,
+        );
+        if r == 0 && !result.is_null() {
+            if !pwd.pw_name.is_null() {
+                name = Some(CStr::from_ptr(pwd.pw_name).to_string_lossy().to_string());
+            }
+            if !pwd.pw_dir.is_null() {
+                home = Some(CStr::from_ptr(pwd.pw_dir).to_string_lossy().to_string());
+            }
+        }
+    }
+
+    let name = name
+        .or_else(|| std::env::var("USER").ok())
+        .unwrap_or_else(|| "?".to_string());
+    let home = home
+        .or_else(|| std::env::var("HOME").ok())
+        .unwrap_or_else(|| "?".to_string());
+
+    (name, home)
+}
+
+fn tmp_dir() -> String {
+    if cfg!(target_os = "macos") {
+        std::env::temp_dir().to_string_lossy().to_string()
+    } else {
+        //P_tmpdir, jdk doesn't respect TMPDIR on linux
+        "/tmp".to_string()
+    }
+}
+
+fn library_path() -> String {
+    let mut paths = vec![];
+    if let Ok(v) = std::env::var("LD_LIBRARY_PATH") {
+        paths.extend(v.split(util::PATH_SEP).map(|v| v.to_string()));
+    }
+    paths.push(format!("/usr/java/packages/lib/{}", os_arch()));
+    paths.extend(
+        ["/usr/lib64", "/lib64", "/lib", "/usr/lib"]
+            .iter()
+            .map(|v| v.to_string()),
+    );
+    paths.retain(|v| !v.is_empty());
+    paths.join(util::PATH_SEP)
+}
+
+/*
+the jre contains rt.jar of boot class path, such as "$JRE/lib/rt.jar" => "$JRE",
+or JAVA_HOME
+*/
+fn java_home(env: Option<String>, boot_class_path: &str) -> String {
+    let from_rt = boot_class_path.split(util::PATH_SEP).find_map(|p| {
+        let p = Path::new(p);
+        if p.file_name()? == "rt.jar" {
+            let lib = p.parent()?;
+            let home = lib.parent()?;
+            Some(home.to_string_lossy().to_string())
+        } else {
+            None
+        }
+    });
+
+    if let Some(v) = from_rt {
+        return v;
+    }
+
+    match env {
+        Some(v) if !v.is_empty() => v,
+        //dir of the binary, as the launcher of jdk does
+        _ => std::env::current_exe()
+            .ok()
+            .and_then(|v| v.parent().and_then(|v| v.parent()).map(|v| v.to_path_buf()))
+            .map(|v| v.to_string_lossy().to_string())
+            .unwrap_or_else(|| ".".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::Command;
+
+    fn get<'a>(props: &'a Props, k: &str) -> &'a str {
+        props
+            .iter()
+            .find(|(k2, _)| k2 == k)
+            .map(|(_, v)| v.as_str())
+            .unwrap_or_else(|| panic!("no property {}", k))
+    }
+
+    fn shell(cmd: &str, args: &[&str]) -> String {
+        let out = Command::new(cmd).args(args).output().unwrap();
+        String::from_utf8(out.stdout).unwrap().trim().to_string()
+    }
+
+    #[test]
+    fn t_locale() {
+        let l = parse_locale("en_US.UTF-8");
+        assert_eq!(l.language, "en");
+        assert_eq!(l.country, "US");
+        assert_eq!(l.encoding, "UTF-8");
+
+        let l = parse_locale("de_DE.utf8@euro");
+        assert_eq!(l.language, "de");
+        assert_eq!(l.country, "DE");
+        assert_eq!(l.encoding, "UTF-8");
+
+        let l = parse_locale("zh_CN.GBK");
+        assert_eq!(l.language, "zh");
+        assert_eq!(l.country, "CN");
+        assert_eq!(l.encoding, "GBK");
+
+        let l = parse_locale("fr_FR");
+        assert_eq!(l.language, "fr");
+        assert_eq!(l.encoding, "ISO-8859-1");
+
+        let l = parse_locale("C");
+        assert_eq!(l.language, "en");
+        assert_eq!(l.country, "");
+        assert_eq!(l.encoding, "ANSI_X3.4-1968");
+
+        let l = parse_locale("C.UTF-8");
+        assert_eq!(l.language, "en");
+        assert_eq!(l.encoding, "UTF-8");
+    }
+
+    #[test]
+    fn t_java_home() {
+        assert_eq!(java_home(Some("/opt/jre".to_string()), ""), "/opt/jre");
+        assert_eq!(
+            java_home(None, "/a/b.jar:/opt/jdk/jre/lib/rt.jar:."),
+            "/opt/jdk/jre"
+        );
+        assert_eq!(
+            java_home(Some("".to_string()), "/usr/jre/lib/rt.jar"),
+            "/usr/jre"
+        );
+        assert_eq!(
+            java_home(Some("/opt/jdk".to_string()), "/opt/jdk/jre/lib/rt.jar"),
+            "/opt/jdk/jre"
+        );
+        assert!(!java_home(None, "a.jar").is_empty());
+    }
+
+    #[test]
+    fn t_put_override() {
+        let user = vec![
+            ("os.name".to_string(), "Plan9".to_string()),
+            ("my.key".to_string(), "v".to_string()),
+        ];
+        let props = create(".", "/x/lib/rt.jar", &user);
+        assert_eq!(get(&props, "os.name"), "Plan9");
+        assert_eq!(get(&props, "my.key"), "v");
+        assert_eq!(props.iter().filter(|(k, _)| k == "os.name").count(), 1);
+        assert_eq!(get(&props, "java.class.path"), ".");
+        assert_eq!(get(&props, "sun.boot.class.path"), "/x/lib/rt.jar");
+        assert_eq!(get(&props, "java.home"), "/x");
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn t_linux() {
+        let props = create(".", "", &[]);
+
+        assert_eq!(get(&props, "os.name"), "Linux");
+        assert_eq!(get(&props, "os.version"), shell("uname", &["-r"]));
+        match shell("uname", &["-m"]).as_str() {
+            "x86_64" => assert_eq!(get(&props, "os.arch"), "amd64"),
+            v => assert_eq!(get(&props, "os.arch"), v),
+        }
+        assert_eq!(get(&props, "sun.arch.data.model"), "64");
+        assert_eq!(get(&props, "file.separator"), "/");
+        assert_eq!(get(&props, "path.separator"), ":");
+        assert_eq!(get(&props, "line.separator"), "\n");
+
+        let locale = parse_locale(&locale_env());
+        assert_eq!(get(&props, "file.encoding"), locale.encoding);
+        assert_eq!(get(&props, "sun.jnu.encoding"), locale.encoding);
+        assert_eq!(get(&props, "user.language"), locale.language);
+        assert_eq!(get(&props, "user.country"), locale.country);
+
+        assert_eq!(get(&props, "user.name"), shell("id", &["-un"]));
+        assert!(Path::new(get(&props, "user.home")).is_absolute());
+        assert_eq!(
+            get(&props, "user.dir"),
+            std::env::current_dir().unwrap().to_str().unwrap()
+        );
+        assert_eq!(get(&props, "java.io.tmpdir"), "/tmp");
+        assert!(!get(&props, "java.home").is_empty());
+        assert!(get(&props, "java.library.path").contains("/usr/lib"));
+        assert_eq!(get(&props, "java.class.path"), ".");
+        assert_eq!(get(&props, "java.version"), "1.8");
+    }
+}
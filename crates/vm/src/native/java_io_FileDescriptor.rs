@@ -1,12 +1,33 @@
 #![allow(non_snake_case)]
 
+use crate::native::common::io_util;
 use crate::native::{new_fn, JNIEnv, JNINativeMethod, JNIResult};
 use crate::oop::Oop;
+use crate::runtime;
+use classfile::consts as cls_consts;
 
 pub fn get_native_methods() -> Vec<JNINativeMethod> {
-    vec![new_fn("initIDs", "()V", Box::new(jvm_initIDs))]
+    vec![
+        new_fn("initIDs", "()V", Box::new(jvm_initIDs)),
+        new_fn("sync", "()V", Box::new(jvm_sync)),
+    ]
 }
 
 fn jvm_initIDs(_env: JNIEnv, _args: &[Oop]) -> JNIResult {
     Ok(None)
 }
+
+fn jvm_sync(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let this = args.get(0).unwrap();
+    let fd = io_util::file_descriptor_fd(this);
+
+    if unsafe { libc::fsync(fd) } == -1 {
+        let ex = runtime::exception::new(
+            cls_consts::J_SYNC_FAILED,
+            Some(io_util::last_error("sync failed")),
+        );
+        return Err(ex);
+    }
+
+    Ok(None)
+}
@@ -1,113 +1,87 @@
 #![allow(non_snake_case)]
 
+use crate::native::common::io_util;
 use crate::native::{new_fn, JNIEnv, JNINativeMethod, JNIResult};
-use crate::oop::{Class, Oop, OopRef};
-use crate::runtime::require_class3;
-use crate::util;
+use crate::oop::{Oop, OopRef};
 
 static mut FILE_OUTPUT_STREAM_FD: usize = 0;
-static mut FILE_DESCRIPTOR_FD: usize = 0;
 
 pub fn get_native_methods() -> Vec<JNINativeMethod> {
     vec![
         new_fn("initIDs", "()V", Box::new(jvm_initIDs)),
-        new_fn("writeBytes", "([BIIZ)V", Box::new(jvm_writeBytes)),
         new_fn("open0", "(Ljava/lang/String;Z)V", Box::new(jvm_open0)),
+        new_fn("write", "(IZ)V", Box::new(jvm_write)),
+        new_fn("writeBytes", "([BIIZ)V", Box::new(jvm_writeBytes)),
+        new_fn("close0", "()V", Box::new(jvm_close0)),
     ]
 }
 
 fn jvm_initIDs(_env: JNIEnv, _args: &[Oop]) -> JNIResult {
     //setup: java.io.FileOutputStream fd
-    let cls = require_class3(None, b"java/io/FileOutputStream").unwrap();
-    let cls = cls.get_class();
-    let id = cls.get_field_id(&util::S_FD, &util::S_JAVA_IO_FD, false);
-    unsafe {
-        FILE_OUTPUT_STREAM_FD = id.offset;
-    }
-
-    //setup: java.io.FileDescriptor fd
-    let cls = require_class3(None, b"java/io/FileDescriptor").unwrap();
-    let cls = cls.get_class();
-    let id = cls.get_field_id(&util::S_FD, &util::S_I, false);
+    let offset = io_util::stream_fd_offset(b"java/io/FileOutputStream");
     unsafe {
-        FILE_DESCRIPTOR_FD = id.offset;
+        FILE_OUTPUT_STREAM_FD = offset;
     }
 
     Ok(None)
 }
 
+//'append' ignored by write, the fd opened with O_APPEND
+fn jvm_write(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let this = args.get(0).unwrap();
+    let b = args.get(1).unwrap().extract_int();
+    let fd = io_util::get_open_fd(this, fd_offset())?;
+
+    match io_util::write(fd, &[b as u8]) {
+        Ok(_) => Ok(None),
+        Err(msg) => Err(io_util::new_io_ex(&msg)),
+    }
+}
+
 fn jvm_writeBytes(_env: JNIEnv, args: &[Oop]) -> JNIResult {
-    let os = args.get(0).unwrap();
-    let fd = get_file_descriptor_fd(os);
+    let this = args.get(0).unwrap();
     let byte_ary = args.get(1).unwrap();
     let off = args.get(2).unwrap().extract_int();
     let len = args.get(3).unwrap().extract_int();
-    let append = args.get(4).unwrap().extract_int();
+
+    io_util::check_bounds(byte_ary, off, len)?;
+    let fd = io_util::get_open_fd(this, fd_offset())?;
 
     let rf = byte_ary.extract_ref();
     let ary = rf.extract_type_array();
     let ary = ary.extract_bytes();
-    let (_, ary) = ary.split_at(off as usize);
-    let len = len as usize;
+    let buf = &ary[off as usize..(off + len) as usize];
 
-    unsafe {
-        if append == 1 {
-            libc::lseek(fd, 0, libc::SEEK_END);
-        }
-
-        if -1 == libc::write(fd, ary.as_ptr() as *const libc::c_void, len) {
-            panic!("write failed");
-        }
+    match io_util::write(fd, buf) {
+        Ok(_) => Ok(None),
+        Err(msg) => Err(io_util::new_io_ex(&msg)),
     }
-
-    Ok(None)
 }
 
 fn jvm_open0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
     let this = args.get(0).unwrap();
     let name = args.get(1).unwrap();
     let name = OopRef::java_lang_string(name.extract_ref());
-    let append = {
-        let v = args.get(2).unwrap().extract_int();
-        v == 1
-    };
-    let fd = unsafe {
-        use std::ffi::CString;
-        let name = CString::new(name).unwrap();
-        let mut flag = libc::O_WRONLY | libc::O_CREAT;
-        if append {
-            flag |= libc::O_APPEND;
-        } else {
-            flag |= libc::O_TRUNC;
-        }
-        libc::open(name.as_ptr(), flag)
-    };
-
-    set_file_descriptor_fd(this, fd);
+    let append = args.get(2).unwrap().extract_int() == 1;
+
+    let mut flags = libc::O_WRONLY | libc::O_CREAT;
+    if append {
+        flags |= libc::O_APPEND;
+    } else {
+        flags |= libc::O_TRUNC;
+    }
+    let fd = io_util::open_or_throw(&name, flags)?;
+    io_util::set_fd(this, fd_offset(), fd);
 
     Ok(None)
 }
 
-fn get_file_descriptor_fd(fos: &Oop) -> i32 {
-    let fd_this = {
-        let offset = unsafe { FILE_OUTPUT_STREAM_FD };
-        Class::get_field_value2(fos.extract_ref(), offset)
-    };
-
-    let fd = {
-        let offset = unsafe { FILE_DESCRIPTOR_FD };
-        Class::get_field_value2(fd_this.extract_ref(), offset)
-    };
-
-    fd.extract_int()
+fn jvm_close0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let this = args.get(0).unwrap();
+    io_util::close_stream(this, fd_offset())?;
+    Ok(None)
 }
 
-fn set_file_descriptor_fd(fos: &Oop, fd: i32) {
-    let fd_this = {
-        let offset = unsafe { FILE_OUTPUT_STREAM_FD };
-        Class::get_field_value2(fos.extract_ref(), offset)
-    };
-
-    let offset = unsafe { FILE_DESCRIPTOR_FD };
-    Class::put_field_value2(fd_this.extract_ref(), offset, Oop::new_int(fd));
+fn fd_offset() -> usize {
+    unsafe { FILE_OUTPUT_STREAM_FD }
 }
@@ -0,0 +1,196 @@
+#![allow(non_snake_case)]
+
+use crate::native::common::io_util;
+use crate::native::{new_fn, JNIEnv, JNINativeMethod, JNIResult};
+use crate::oop::{Oop, OopRef};
+
+static mut RANDOM_ACCESS_FILE_FD: usize = 0;
+
+//RandomAccessFile.java define
+const O_RDONLY: i32 = 1;
+const O_RDWR: i32 = 2;
+const O_SYNC: i32 = 4;
+const O_DSYNC: i32 = 8;
+
+pub fn get_native_methods() -> Vec<JNINativeMethod> {
+    vec![
+        new_fn("initIDs", "()V", Box::new(jvm_initIDs)),
+        new_fn("open0", "(Ljava/lang/String;I)V", Box::new(jvm_open0)),
+        new_fn("read0", "()I", Box::new(jvm_read0)),
+        new_fn("readBytes", "([BII)I", Box::new(jvm_readBytes)),
+        new_fn("write0", "(I)V", Box::new(jvm_write0)),
+        new_fn("writeBytes", "([BII)V", Box::new(jvm_writeBytes)),
+        new_fn("getFilePointer", "()J", Box::new(jvm_getFilePointer)),
+        new_fn("seek0", "(J)V", Box::new(jvm_seek0)),
+        new_fn("length", "()J", Box::new(jvm_length)),
+        new_fn("setLength", "(J)V", Box::new(jvm_setLength)),
+        new_fn("close0", "()V", Box::new(jvm_close0)),
+    ]
+}
+
+fn jvm_initIDs(_env: JNIEnv, _args: &[Oop]) -> JNIResult {
+    //setup: java.io.RandomAccessFile fd
+    let offset = io_util::stream_fd_offset(b"java/io/RandomAccessFile");
+    unsafe {
+        RANDOM_ACCESS_FILE_FD = offset;
+    }
+
+    Ok(None)
+}
+
+fn jvm_open0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let this = args.get(0).unwrap();
+    let name = args.get(1).unwrap();
+    let name = OopRef::java_lang_string(name.extract_ref());
+    let mode = args.get(2).unwrap().extract_int();
+
+    let mut flags = 0;
+    if mode & O_RDONLY != 0 {
+        flags = libc::O_RDONLY;
+    } else if mode & O_RDWR != 0 {
+        flags = libc::O_RDWR | libc::O_CREAT;
+        if mode & O_SYNC != 0 {
+            flags |= libc::O_SYNC;
+        } else if mode & O_DSYNC != 0 {
+            flags |= libc::O_DSYNC;
+        }
+    }
+    let fd = io_util::open_or_throw(&name, flags)?;
+    io_util::set_fd(this, fd_offset(), fd);
+
+    Ok(None)
+}
+
+fn jvm_read0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let this = args.get(0).unwrap();
+    let fd = io_util::get_open_fd(this, fd_offset())?;
+
+    match io_util::read_single(fd) {
+        Ok(v) => Ok(Some(Oop::new_int(v))),
+        Err(msg) => Err(io_util::new_io_ex(&msg)),
+    }
+}
+
+fn jvm_readBytes(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let this = args.get(0).unwrap();
+    let byte_ary = args.get(1).unwrap();
+    let off = args.get(2).unwrap().extract_int();
+    let len = args.get(3).unwrap().extract_int();
+
+    io_util::check_bounds(byte_ary, off, len)?;
+    let fd = io_util::get_open_fd(this, fd_offset())?;
+
+    let rf = byte_ary.extract_ref();
+    let ary = rf.extract_mut_type_array();
+    let ary = ary.extract_mut_bytes();
+    let buf = &mut ary[off as usize..(off + len) as usize];
+
+    match io_util::read(fd, buf) {
+        Ok(n) => Ok(Some(Oop::new_int(n))),
+        Err(msg) => Err(io_util::new_io_ex(&msg)),
+    }
+}
+
+fn jvm_write0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let this = args.get(0).unwrap();
+    let b = args.get(1).unwrap().extract_int();
+    let fd = io_util::get_open_fd(this, fd_offset())?;
+
+    match io_util::write(fd, &[b as u8]) {
+        Ok(_) => Ok(None),
+        Err(msg) => Err(io_util::new_io_ex(&msg)),
+    }
+}
+
+fn jvm_writeBytes(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let this = args.get(0).unwrap();
+    let byte_ary = args.get(1).unwrap();
+    let off = args.get(2).unwrap().extract_int();
+    let len = args.get(3).unwrap().extract_int();
+
+    io_util::check_bounds(byte_ary, off, len)?;
+    let fd = io_util::get_open_fd(this, fd_offset())?;
+
+    let rf = byte_ary.extract_ref();
+    let ary = rf.extract_type_array();
+    let ary = ary.extract_bytes();
+    let buf = &ary[off as usize..(off + len) as usize];
+
+    match io_util::write(fd, buf) {
+        Ok(_) => Ok(None),
+        Err(msg) => Err(io_util::new_io_ex(&msg)),
+    }
+}
+
+fn jvm_getFilePointer(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let this = args.get(0).unwrap();
+    let fd = io_util::get_open_fd(this, fd_offset())?;
+
+    match io_util::seek(fd, 0, libc::SEEK_CUR) {
+        Ok(v) => Ok(Some(Oop::new_long(v))),
+        Err(msg) => Err(io_util::new_io_ex(&msg)),
+    }
+}
+
+fn jvm_seek0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let this = args.get(0).unwrap();
+    let pos = args.get(1).unwrap().extract_long();
+    let fd = io_util::get_open_fd(this, fd_offset())?;
+
+    if pos < 0 {
+        return Err(io_util::new_io_ex("Negative seek offset"));
+    }
+
+    match io_util::seek(fd, pos, libc::SEEK_SET) {
+        Ok(_) => Ok(None),
+        Err(msg) => Err(io_util::new_io_ex(&msg)),
+    }
+}
+
+fn jvm_length(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let this = args.get(0).unwrap();
+    let fd = io_util::get_open_fd(this, fd_offset())?;
+
+    //file pointer kept
+    let len = io_util::seek(fd, 0, libc::SEEK_CUR).and_then(|cur| {
+        let end = io_util::seek(fd, 0, libc::SEEK_END)?;
+        io_util::seek(fd, cur, libc::SEEK_SET)?;
+        Ok(end)
+    });
+    match len {
+        Ok(v) => Ok(Some(Oop::new_long(v))),
+        Err(msg) => Err(io_util::new_io_ex(&msg)),
+    }
+}
+
+fn jvm_setLength(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let this = args.get(0).unwrap();
+    let new_len = args.get(1).unwrap().extract_long();
+    let fd = io_util::get_open_fd(this, fd_offset())?;
+
+    //file pointer moved to the end, if beyond it
+    let r = io_util::seek(fd, 0, libc::SEEK_CUR).and_then(|cur| {
+        if unsafe { libc::ftruncate(fd, new_len as libc::off_t) } == -1 {
+            return Err(io_util::last_error("setLength failed"));
+        }
+        if cur > new_len {
+            io_util::seek(fd, 0, libc::SEEK_END)
+        } else {
+            io_util::seek(fd, cur, libc::SEEK_SET)
+        }
+    });
+    match r {
+        Ok(_) => Ok(None),
+        Err(msg) => Err(io_util::new_io_ex(&msg)),
+    }
+}
+
+fn jvm_close0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let this = args.get(0).unwrap();
+    io_util::close_stream(this, fd_offset())?;
+    Ok(None)
+}
+
+fn fd_offset() -> usize {
+    unsafe { RANDOM_ACCESS_FILE_FD }
+}
@@ -1,10 +1,14 @@
 #![allow(non_snake_case)]
 
+use crate::native::common::io_util;
 use crate::native::{new_fn, JNIEnv, JNINativeMethod, JNIResult};
-use crate::oop::{Class, Oop, OopRef};
+use crate::oop::{self, Class, Oop, OopRef};
 use crate::runtime::require_class3;
 use crate::{new_br, util};
+use std::ffi::CString;
 use std::fs;
+use std::os::unix::fs::{MetadataExt, PermissionsExt};
+use std::path::{Path, PathBuf};
 
 static mut FILE_PATH: usize = 0;
 
@@ -16,6 +20,9 @@ const _BA_HIDDEN: i32 = 0x08;
 const ACCESS_READ: i32 = 0x04;
 const ACCESS_WRITE: i32 = 0x02;
 const ACCESS_EXECUTE: i32 = 0x01;
+const SPACE_TOTAL: i32 = 0;
+const SPACE_FREE: i32 = 1;
+const SPACE_USABLE: i32 = 2;
 
 pub fn get_native_methods() -> Vec<JNINativeMethod> {
     vec![
@@ -40,6 +47,44 @@ pub fn get_native_methods() -> Vec<JNINativeMethod> {
             "(Ljava/lang/String;)Z",
             Box::new(jvm_createFileExclusively),
         ),
+        new_fn(
+            "getLastModifiedTime",
+            "(Ljava/io/File;)J",
+            Box::new(jvm_getLastModifiedTime),
+        ),
+        new_fn("getLength", "(Ljava/io/File;)J", Box::new(jvm_getLength)),
+        new_fn(
+            "setPermission",
+            "(Ljava/io/File;IZZ)Z",
+            Box::new(jvm_setPermission),
+        ),
+        new_fn("delete0", "(Ljava/io/File;)Z", Box::new(jvm_delete0)),
+        new_fn(
+            "list",
+            "(Ljava/io/File;)[Ljava/lang/String;",
+            Box::new(jvm_list),
+        ),
+        new_fn(
+            "createDirectory",
+            "(Ljava/io/File;)Z",
+            Box::new(jvm_createDirectory),
+        ),
+        new_fn(
+            "rename0",
+            "(Ljava/io/File;Ljava/io/File;)Z",
+            Box::new(jvm_rename0),
+        ),
+        new_fn(
+            "setLastModifiedTime",
+            "(Ljava/io/File;J)Z",
+            Box::new(jvm_setLastModifiedTime),
+        ),
+        new_fn(
+            "setReadOnly",
+            "(Ljava/io/File;)Z",
+            Box::new(jvm_setReadOnly),
+        ),
+        new_fn("getSpace", "(Ljava/io/File;I)J", Box::new(jvm_getSpace)),
     ]
 }
 
@@ -90,7 +135,6 @@ fn jvm_checkAccess(_env: JNIEnv, args: &[Oop]) -> JNIResult {
     }
 
     let r = unsafe {
-        use std::ffi::CString;
         let path = CString::new(path).unwrap();
         if libc::access(path.as_ptr(), amode) == 0 {
             1
@@ -105,34 +149,316 @@ fn jvm_checkAccess(_env: JNIEnv, args: &[Oop]) -> JNIResult {
 fn jvm_canonicalize0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
     let path = args.get(1).unwrap();
     let path = OopRef::java_lang_string(path.extract_ref());
-    let path = std::path::Path::new(&path);
-    let path = path.canonicalize().expect("path canonicalize failed");
-    let path = path.to_str().expect("path to_str failed");
-    let path = util::oop::new_java_lang_string2(path);
 
-    Ok(Some(path))
+    match canonicalize(&path) {
+        Ok(path) => Ok(Some(util::oop::new_java_lang_string2(&path))),
+        Err(_) => Err(io_util::new_io_ex("Bad pathname")),
+    }
 }
 
+//'/' excluded, jdk returns false for it
 fn jvm_createFileExclusively(_env: JNIEnv, args: &[Oop]) -> JNIResult {
     let path = args.get(1).unwrap();
     let path = OopRef::java_lang_string(path.extract_ref());
-    let v = match std::fs::OpenOptions::new()
+    if path == "/" {
+        return Ok(Some(Oop::new_int(0)));
+    }
+
+    let v = match fs::OpenOptions::new()
         .read(true)
         .write(true)
-        .create(true)
+        .create_new(true)
         .open(&path)
     {
         Ok(_) => 1,
+        Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => 0,
         Err(e) => {
-            error!("open {}, error = {:?}", path, e);
-            0
+            let msg = e
+                .raw_os_error()
+                .map(io_util::strerror)
+                .unwrap_or_else(|| e.to_string());
+            return Err(io_util::new_io_ex(&msg));
         }
     };
     Ok(Some(Oop::new_int(v)))
 }
 
+//millis, 0 if not exist
+fn jvm_getLastModifiedTime(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let path = get_File_path(args.get(1).unwrap());
+    let v = match fs::metadata(path) {
+        Ok(attr) => attr.mtime() * 1000 + attr.mtime_nsec() / 1_000_000,
+        Err(_) => 0,
+    };
+    Ok(Some(Oop::new_long(v)))
+}
+
+fn jvm_getLength(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let path = get_File_path(args.get(1).unwrap());
+    let v = match fs::metadata(path) {
+        Ok(attr) => attr.len() as i64,
+        Err(_) => 0,
+    };
+    Ok(Some(Oop::new_long(v)))
+}
+
+fn jvm_setPermission(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let path = get_File_path(args.get(1).unwrap());
+    let access = args.get(2).unwrap().extract_int();
+    let enable = args.get(3).unwrap().extract_int() != 0;
+    let owner_only = args.get(4).unwrap().extract_int() != 0;
+
+    let v = set_permission(&path, access, enable, owner_only);
+    Ok(Some(Oop::new_int(v as i32)))
+}
+
+fn jvm_delete0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let path = get_File_path(args.get(1).unwrap());
+    let v = match CString::new(path) {
+        Ok(path) => unsafe { libc::remove(path.as_ptr()) == 0 },
+        Err(_) => false,
+    };
+    Ok(Some(Oop::new_int(v as i32)))
+}
+
+//names in the dir, "." & ".." excluded; null if not a dir
+fn jvm_list(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let path = get_File_path(args.get(1).unwrap());
+    let names = match list(&path) {
+        Some(names) => names,
+        None => return Ok(Some(oop::consts::get_null())),
+    };
+
+    let elms = names
+        .iter()
+        .map(|name| util::oop::new_java_lang_string2(name))
+        .collect();
+    let ary_cls = require_class3(None, b"[Ljava/lang/String;").unwrap();
+    Ok(Some(Oop::new_ref_ary2(ary_cls, elms)))
+}
+
+fn jvm_createDirectory(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let path = get_File_path(args.get(1).unwrap());
+    let v = match CString::new(path) {
+        Ok(path) => unsafe { libc::mkdir(path.as_ptr(), 0o777) == 0 },
+        Err(_) => false,
+    };
+    Ok(Some(Oop::new_int(v as i32)))
+}
+
+fn jvm_rename0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let from = get_File_path(args.get(1).unwrap());
+    let to = get_File_path(args.get(2).unwrap());
+    let v = fs::rename(from, to).is_ok();
+    Ok(Some(Oop::new_int(v as i32)))
+}
+
+//access time kept
+fn jvm_setLastModifiedTime(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let path = get_File_path(args.get(1).unwrap());
+    let time = args.get(2).unwrap().extract_long();
+
+    let v = match fs::metadata(&path) {
+        Ok(attr) => {
+            let tv = [
+                libc::timeval {
+                    tv_sec: attr.atime() as libc::time_t,
+                    tv_usec: (attr.atime_nsec() / 1000) as libc::suseconds_t,
+                },
+                libc::timeval {
+                    tv_sec: (time / 1000) as libc::time_t,
+                    tv_usec: ((time % 1000) * 1000) as libc::suseconds_t,
+                },
+            ];
+            let path = CString::new(path).unwrap();
+            unsafe { libc::utimes(path.as_ptr(), tv.as_ptr()) == 0 }
+        }
+        Err(_) => false,
+    };
+    Ok(Some(Oop::new_int(v as i32)))
+}
+
+fn jvm_setReadOnly(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let path = get_File_path(args.get(1).unwrap());
+    let v = match fs::metadata(&path) {
+        Ok(attr) => {
+            let mut perm = attr.permissions();
+            perm.set_mode(perm.mode() & !0o222);
+            fs::set_permissions(&path, perm).is_ok()
+        }
+        Err(_) => false,
+    };
+    Ok(Some(Oop::new_int(v as i32)))
+}
+
+fn jvm_getSpace(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let path = get_File_path(args.get(1).unwrap());
+    let t = args.get(2).unwrap().extract_int();
+
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    let ok = match CString::new(path) {
+        Ok(path) => unsafe { libc::statvfs(path.as_ptr(), &mut stat) == 0 },
+        Err(_) => false,
+    };
+
+    let v = if ok {
+        let frsize = stat.f_frsize as i64;
+        match t {
+            SPACE_TOTAL => frsize * stat.f_blocks as i64,
+            SPACE_FREE => frsize * stat.f_bfree as i64,
+            SPACE_USABLE => frsize * stat.f_bavail as i64,
+            _ => 0,
+        }
+    } else {
+        0
+    };
+    Ok(Some(Oop::new_long(v)))
+}
+
 fn get_File_path(file: &Oop) -> String {
     let offset = unsafe { FILE_PATH };
     let path = Class::get_field_value2(file.extract_ref(), offset);
     OopRef::java_lang_string(path.extract_ref())
 }
+
+//the path may not exist, the longest existing prefix resolved, the rest
+//appended with "." & ".." collapsed, as canonicalize_md.c
+fn canonicalize(path: &str) -> std::io::Result<String> {
+    let path = Path::new(path);
+    if let Ok(v) = path.canonicalize() {
+        return Ok(v.to_string_lossy().to_string());
+    }
+
+    let mut rest = vec![];
+    let mut prefix = path;
+    let mut base = loop {
+        match prefix.parent() {
+            Some(parent) => {
+                rest.push(prefix.file_name().map(|v| v.to_os_string()));
+                prefix = parent;
+                let parent = if parent.as_os_str().is_empty() {
+                    Path::new(".")
+                } else {
+                    parent
+                };
+                if let Ok(v) = parent.canonicalize() {
+                    break v;
+                }
+            }
+            None => return Err(std::io::Error::from(std::io::ErrorKind::NotFound)),
+        }
+    };
+
+    //file_name is None for a trailing ".."
+    for name in rest.into_iter().rev() {
+        match name {
+            Some(name) => base.push(name),
+            None => {
+                base.pop();
+            }
+        }
+    }
+
+    Ok(collapse(&base).to_string_lossy().to_string())
+}
+
+//"." & ".." in a path of an absolute base
+fn collapse(path: &Path) -> PathBuf {
+    let mut v = PathBuf::new();
+    for c in path.components() {
+        match c {
+            std::path::Component::CurDir => (),
+            std::path::Component::ParentDir => {
+                v.pop();
+            }
+            c => v.push(c),
+        }
+    }
+    v
+}
+
+fn list(path: &str) -> Option<Vec<String>> {
+    let dir = fs::read_dir(path).ok()?;
+    let names = dir
+        .filter_map(|e| e.ok())
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .collect();
+    Some(names)
+}
+
+fn set_permission(path: &str, access: i32, enable: bool, owner_only: bool) -> bool {
+    let amode = match access {
+        ACCESS_READ if owner_only => 0o400,
+        ACCESS_READ => 0o444,
+        ACCESS_WRITE if owner_only => 0o200,
+        ACCESS_WRITE => 0o222,
+        ACCESS_EXECUTE if owner_only => 0o100,
+        ACCESS_EXECUTE => 0o111,
+        _ => return false,
+    };
+
+    match fs::metadata(path) {
+        Ok(attr) => {
+            let mut perm = attr.permissions();
+            let mode = if enable {
+                perm.mode() | amode
+            } else {
+                perm.mode() & !amode
+            };
+            perm.set_mode(mode);
+            fs::set_permissions(path, perm).is_ok()
+        }
+        Err(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn t_file_system() {
+        let dir = std::env::temp_dir().join(format!("jvm_fs_test_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("sub")).unwrap();
+        fs::write(dir.join("a.txt"), b"abc").unwrap();
+        let dir = dir.canonicalize().unwrap();
+        let base = dir.to_str().unwrap();
+
+        let mut names = list(base).unwrap();
+        names.sort();
+        assert_eq!(names, vec!["a.txt", "sub"]);
+        assert!(list(&format!("{}/a.txt", base)).is_none());
+
+        //not exist
+        assert_eq!(
+            canonicalize(&format!("{}/sub/../x/./y", base)).unwrap(),
+            format!("{}/x/y", base)
+        );
+        assert_eq!(
+            canonicalize(&format!("{}/sub/./a.txt", base)).unwrap(),
+            format!("{}/sub/a.txt", base)
+        );
+        assert_eq!(
+            canonicalize(&format!("{}/sub/../a.txt", base)).unwrap(),
+            format!("{}/a.txt", base)
+        );
+
+        let a = format!("{}/a.txt", base);
+        assert!(set_permission(&a, ACCESS_EXECUTE, true, true));
+        assert_eq!(
+            fs::metadata(&a).unwrap().permissions().mode() & 0o100,
+            0o100
+        );
+        assert!(set_permission(&a, ACCESS_EXECUTE, false, false));
+        assert_eq!(fs::metadata(&a).unwrap().permissions().mode() & 0o111, 0);
+        assert!(!set_permission(
+            &format!("{}/no_exist", base),
+            ACCESS_READ,
+            true,
+            true
+        ));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -261,8 +261,25 @@ fn jvm_registerNatives(_env: JNIEnv, _args: &[Oop]) -> JNIResult {
     Ok(None)
 }
 
-fn jvm_desiredAssertionStatus0(_env: JNIEnv, _args: &[Oop]) -> JNIResult {
-    Ok(Some(Oop::new_int(0)))
+fn jvm_desiredAssertionStatus0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let target = {
+        let arg0 = args.get(0).unwrap();
+        let rf = arg0.extract_ref();
+        let mirror = rf.extract_mirror();
+        mirror.target.clone()
+    };
+
+    //primitive & array types have no assertions
+    let enabled = match target {
+        Some(target) => {
+            let cls = target.get_class();
+            let name = unsafe { std::str::from_utf8_unchecked(cls.name.as_slice()) };
+            runtime::options::with(|opts| opts.desired_assertion_status(name))
+        }
+        None => false,
+    };
+
+    Ok(Some(Oop::new_int(enabled as i32)))
 }
 
 fn jvm_getPrimitiveClass(_env: JNIEnv, args: &[Oop]) -> JNIResult {
@@ -382,9 +399,8 @@ fn jvm_forName0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
 
     match cls {
         Some(cls) => {
-            oop::class::init_class(&cls);
             if initialize {
-                oop::class::init_class_fully(&cls);
+                oop::class::init_class(&cls)?;
             }
 
             let mirror = cls.get_class().get_mirror();
@@ -2,7 +2,7 @@
 
 use crate::native::{new_fn, JNIEnv, JNINativeMethod, JNIResult};
 use crate::oop::{self, Oop, OopRef};
-use crate::runtime;
+use crate::runtime::{self, ClassLoader};
 use crate::util;
 
 pub fn get_native_methods() -> Vec<JNINativeMethod> {
@@ -37,12 +37,18 @@ fn jvm_findBuiltinLib(_env: JNIEnv, args: &[Oop]) -> JNIResult {
     Ok(None)
 }
 
+//the classes defined by 'this' (see Proxy.defineClass0) first
 fn jvm_findLoadedClass0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let this = args.first().unwrap();
     let name = args.get(1).unwrap();
     let name = OopRef::java_lang_string(name.extract_ref());
     info!("findLoadedClass0: {}", name);
     let name = name.replace(".", util::FILE_SEP);
-    let v = match runtime::sys_dic_find(name.as_bytes()) {
+    let found = match ClassLoader::defined_by(this) {
+        ClassLoader::Defined(id) => runtime::sys_dic_find_defined(id, name.as_bytes()),
+        _ => None,
+    };
+    let v = match found.or_else(|| runtime::sys_dic_find(name.as_bytes())) {
         Some(cls) => {
             let cls = cls.get_class();
             cls.get_mirror()
@@ -52,8 +58,21 @@ fn jvm_findLoadedClass0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
     Ok(Some(v))
 }
 
-// fixme: Is this correct? uncertain
+//search the boot class path only, null for classes of app
 fn jvm_findBootstrapClass(_env: JNIEnv, args: &[Oop]) -> JNIResult {
-    info!("findBootstrapClass");
-    jvm_findLoadedClass0(_env, args)
+    let name = args.get(1).unwrap();
+    let name = OopRef::java_lang_string(name.extract_ref());
+    info!("findBootstrapClass: {}", name);
+    let name = name.replace(".", util::FILE_SEP);
+    let v = match runtime::require_class3(Some(ClassLoader::Bootstrap), name.as_bytes()) {
+        Some(cls) => {
+            let cls = cls.get_class();
+            match cls.class_loader {
+                Some(ClassLoader::Base) => oop::consts::get_null(),
+                _ => cls.get_mirror(),
+            }
+        }
+        None => oop::consts::get_null(),
+    };
+    Ok(Some(v))
 }
@@ -2,6 +2,7 @@
 
 use crate::native::{new_fn, JNIEnv, JNINativeMethod, JNIResult};
 use crate::oop::Oop;
+use crate::runtime::heap::{self, GcCause};
 
 pub fn get_native_methods() -> Vec<JNINativeMethod> {
     vec![
@@ -10,7 +11,11 @@ pub fn get_native_methods() -> Vec<JNINativeMethod> {
             "()I",
             Box::new(jvm_availableProcessors),
         ),
+        new_fn("freeMemory", "()J", Box::new(jvm_freeMemory)),
+        new_fn("totalMemory", "()J", Box::new(jvm_totalMemory)),
+        new_fn("maxMemory", "()J", Box::new(jvm_maxMemory)),
         new_fn("gc", "()V", Box::new(jvm_gc)),
+        new_fn("runFinalization0", "()V", Box::new(jvm_runFinalization0)),
     ]
 }
 
@@ -19,6 +24,25 @@ fn jvm_availableProcessors(_env: JNIEnv, _args: &[Oop]) -> JNIResult {
     Ok(Some(Oop::new_int(1)))
 }
 
+fn jvm_freeMemory(_env: JNIEnv, _args: &[Oop]) -> JNIResult {
+    let free = heap::total_memory().saturating_sub(heap::used());
+    Ok(Some(Oop::new_long(free as i64)))
+}
+
+fn jvm_totalMemory(_env: JNIEnv, _args: &[Oop]) -> JNIResult {
+    Ok(Some(Oop::new_long(heap::total_memory() as i64)))
+}
+
+fn jvm_maxMemory(_env: JNIEnv, _args: &[Oop]) -> JNIResult {
+    Ok(Some(Oop::new_long(heap::max_memory() as i64)))
+}
+
 fn jvm_gc(_env: JNIEnv, _args: &[Oop]) -> JNIResult {
+    heap::collect(GcCause::SystemGc);
+    Ok(None)
+}
+
+fn jvm_runFinalization0(_env: JNIEnv, _args: &[Oop]) -> JNIResult {
+    heap::run_finalization()?;
     Ok(None)
 }
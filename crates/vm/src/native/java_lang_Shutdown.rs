@@ -0,0 +1,22 @@
+#![allow(non_snake_case)]
+
+use crate::native::{new_fn, JNIEnv, JNINativeMethod, JNIResult};
+use crate::oop::Oop;
+use crate::runtime::{heap, vm};
+
+pub fn get_native_methods() -> Vec<JNINativeMethod> {
+    vec![
+        new_fn("halt0", "(I)V", Box::new(jvm_halt0)),
+        new_fn("runAllFinalizers", "()V", Box::new(jvm_runAllFinalizers)),
+    ]
+}
+
+fn jvm_halt0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let status = args.get(0).unwrap().extract_int();
+    vm::halt(status)
+}
+
+fn jvm_runAllFinalizers(_env: JNIEnv, _args: &[Oop]) -> JNIResult {
+    heap::run_all_finalizers()?;
+    Ok(None)
+}
@@ -2,8 +2,9 @@
 
 use crate::native::{self, new_fn, JNIEnv, JNINativeMethod, JNIResult};
 use crate::oop::{self, Oop, OopRef};
-use crate::runtime::{self, thread, JavaCall};
+use crate::runtime::{self, cmp, exception, thread, JavaCall};
 use crate::{new_br, util};
+use classfile::consts as cls_consts;
 use std::sync::Arc;
 use std::time::SystemTime;
 
@@ -56,99 +57,167 @@ fn jvm_arraycopy(_env: JNIEnv, args: &[Oop]) -> JNIResult {
     let dest_pos = args.get(3).unwrap().extract_int();
     let length = args.get(4).unwrap().extract_int();
 
-    //todo: do check & throw exception
+    let (src_rf, dest_rf) = match (src, dest) {
+        (Oop::Ref(src_rf), Oop::Ref(dest_rf)) => (src_rf.clone(), dest_rf.clone()),
+        _ => return Err(exception::new(cls_consts::J_NPE, None)),
+    };
+
+    //the checks & messages of HotSpot, in the same order
+    let (src_len, src_type) = match array_desc(&src_rf) {
+        Some(it) => it,
+        None => {
+            let msg = format!("arraycopy: source type {} is not an array", type_name(src));
+            return Err(exception::new(cls_consts::J_ASE, Some(msg)));
+        }
+    };
+    let (dest_len, dest_type) = match array_desc(&dest_rf) {
+        Some(it) => it,
+        None => {
+            let msg = format!(
+                "arraycopy: destination type {} is not an array",
+                type_name(dest)
+            );
+            return Err(exception::new(cls_consts::J_ASE, Some(msg)));
+        }
+    };
+    if src_type != dest_type {
+        let msg = format!(
+            "arraycopy: type mismatch: can not copy {}[] into {}[]",
+            src_type, dest_type
+        );
+        return Err(exception::new(cls_consts::J_ASE, Some(msg)));
+    }
+
+    let msg = if src_pos < 0 {
+        Some(format!(
+            "arraycopy: source index {} out of bounds for {}[{}]",
+            src_pos, src_type, src_len
+        ))
+    } else if dest_pos < 0 {
+        Some(format!(
+            "arraycopy: destination index {} out of bounds for {}[{}]",
+            dest_pos, dest_type, dest_len
+        ))
+    } else if length < 0 {
+        Some(format!("arraycopy: length {} is negative", length))
+    } else if src_pos as usize + length as usize > src_len {
+        Some(format!(
+            "arraycopy: last source index {} out of bounds for {}[{}]",
+            src_pos as usize + length as usize,
+            src_type,
+            src_len
+        ))
+    } else if dest_pos as usize + length as usize > dest_len {
+        Some(format!(
+            "arraycopy: last destination index {} out of bounds for {}[{}]",
+            dest_pos as usize + length as usize,
+            dest_type,
+            dest_len
+        ))
+    } else {
+        None
+    };
+    if let Some(msg) = msg {
+        return Err(exception::new(
+            cls_consts::J_ARRAY_INDEX_OUT_OF_BOUNDS,
+            Some(msg),
+        ));
+    }
 
     if length == 0 {
         return Ok(None);
     }
 
-    let is_same_obj = OopRef::is_eq(src, dest);
-
-    if is_same_obj {
-        arraycopy_same_obj(
-            src.extract_ref(),
-            src_pos as usize,
-            dest_pos as usize,
-            length as usize,
-        );
+    let (src_pos, dest_pos, length) = (src_pos as usize, dest_pos as usize, length as usize);
+    if Arc::ptr_eq(&src_rf, &dest_rf) {
+        arraycopy_same_obj(src_rf, src_pos, dest_pos, length);
+    } else if src_type == OBJECT_ARRAY {
+        arraycopy_checked(src_rf, src_pos, dest_rf, dest_pos, length)?;
     } else {
-        arraycopy_diff_obj(
-            src.extract_ref(),
-            src_pos as usize,
-            dest.extract_ref(),
-            dest_pos as usize,
-            length as usize,
-        );
+        arraycopy_diff_obj(src_rf, src_pos, dest_rf, dest_pos, length);
     }
 
     Ok(None)
 }
 
-fn jvm_initProperties(_env: JNIEnv, args: &[Oop]) -> JNIResult {
-    //fixme:
-    let props = vec![
-        ("file.encoding.pkg", "sun.io"),
-        ("file.encoding", "US-ASCII"),
-        ("file.separator", util::FILE_SEP),
-        ("java.class.path", "."),
-        ("java.class.version", "52.0"),
-        ("java.security.egd", "file:/dev/random"),
-        // ("java.security.debug", "all"),
-        // ("java.security.auth.debug", "all"),
-        ("java.specification.version", "1.8"),
-        ("java.specification.name", "Java Platform API Specification"),
-        ("java.specification.vendor", "Oracle Corporation"),
-        ("java.vendor", "Chuan"),
-        ("java.vendor.url", "https://github.com/douchuan/jvm"),
-        ("java.vendor.url.bug", "https://github.com/douchuan/jvm"),
-        ("java.version", "1.8"),
-        ("line.separator", util::LINE_SEP),
-        ("os.arch", "x86_64"),
-        ("os.name", "Mac OS X"),
-        ("os.version", "18.7.0"),
-        ("path.separator", util::PATH_SEP),
-        ("sun.arch.data.model", "64"),
-        ("sun.cpu.endian", "little"),
-        ("sun.cpu.isalist", ""),
-        // ("sun.misc.URLClassPath.debug", "true"),
-        // ("sun.misc.URLClassPath.debugLookupCache", "true"),
-        ("sun.stdout.encoding", "UTF-8"),
-        ("sun.stderr.encoding", "UTF-8"),
-        ("user.language", "en"),
-        ("user.name", "chuan"),
-        ("user.region", "US"),
-        //        ("java.security.manager", ""),
-        //        ("sun.jnu.encoding", "UTF-8"),
-        //        ("sun.io.unicode.encoding", "UnicodeBig"),
-    ];
-
-    let props_oop = args.get(0).unwrap();
-    for (k, v) in props.iter() {
-        put_props_kv(props_oop, k, v);
+const OBJECT_ARRAY: &str = "object array";
+
+//the length & the element type named as by HotSpot, None if not an array
+fn array_desc(rf: &Arc<OopRef>) -> Option<(usize, &'static str)> {
+    let ptr = rf.get_raw_ptr();
+    unsafe {
+        match &(*ptr).v {
+            oop::RefKind::Array(ary) => Some((ary.elements.len(), OBJECT_ARRAY)),
+            oop::RefKind::TypeArray(ary) => {
+                let t = match ary {
+                    oop::TypeArrayDesc::Byte(_) => "byte",
+                    oop::TypeArrayDesc::Bool(_) => "boolean",
+                    oop::TypeArrayDesc::Char(_) => "char",
+                    oop::TypeArrayDesc::Short(_) => "short",
+                    oop::TypeArrayDesc::Float(_) => "float",
+                    oop::TypeArrayDesc::Double(_) => "double",
+                    oop::TypeArrayDesc::Int(_) => "int",
+                    oop::TypeArrayDesc::Long(_) => "long",
+                };
+                Some((ary.len(), t))
+            }
+            _ => None,
+        }
     }
+}
 
-    //user.dir
-    let v = std::env::current_dir().expect("current_dir failed");
-    let v = v.to_str().expect("current_dir to_str faield");
-    put_props_kv(props_oop, "user.dir", v);
+fn type_name(v: &Oop) -> String {
+    let cls = util::oop::get_oop_class(v).unwrap();
+    runtime::access::external_name(&cls)
+}
 
-    //java.io.tmpdir
-    let v = std::env::temp_dir();
-    let v = v.to_str().expect("temp_dir to_str failed");
-    put_props_kv(props_oop, "java.io.tmpdir", v);
+//the elements of 'src' stored to 'dest' one by one, if not assignable to the
+//component of 'dest', ArrayStoreException, those before copied
+fn arraycopy_checked(
+    src: Arc<OopRef>,
+    src_pos: usize,
+    dest: Arc<OopRef>,
+    dest_pos: usize,
+    length: usize,
+) -> Result<(), Oop> {
+    let src_component = src.extract_array().class.get_class().get_array_component();
+    let dest_component = dest.extract_array().class.get_class().get_array_component();
+    let (src_component, dest_component) = (src_component.unwrap(), dest_component.unwrap());
+    if cmp::instance_of(src_component.clone(), dest_component.clone()) {
+        arraycopy_diff_obj(src, src_pos, dest, dest_pos, length);
+        return Ok(());
+    }
 
-    //user.home
-    let v = dirs::home_dir().expect("get home_dir failed");
-    let v = v.to_str().expect("home_dir to_str failed");
-    put_props_kv(props_oop, "user.home", v);
+    let src = src.extract_array();
+    let dest = dest.extract_mut_array();
+    for i in 0..length {
+        let v = &src.elements[src_pos + i];
+        if let Some(cls) = util::oop::get_oop_class(v) {
+            if !cmp::instance_of(cls, dest_component.clone()) {
+                let msg = format!(
+                    "arraycopy: element type mismatch: can not cast one of the elements of {}[] to the type of the destination array, {}",
+                    runtime::access::external_name(&src_component),
+                    runtime::access::external_name(&dest_component)
+                );
+                return Err(exception::new(cls_consts::J_ASE, Some(msg)));
+            }
+        }
+        dest.elements[dest_pos + i] = v.clone();
+    }
+
+    Ok(())
+}
 
-    //JAVA_HOME
-    let v = std::env::var("JAVA_HOME").expect("Please Setup JAVA_HOME env");
-    put_props_kv(props_oop, "java.home", v.as_str());
+fn jvm_initProperties(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let props = runtime::options::with(|opts| {
+        let cp = opts.class_path.clone().unwrap_or_else(|| ".".to_string());
+        let boot_cp = runtime::boot_class_path();
+        native::common::sys_props::create(&cp, &boot_cp, &opts.properties)
+    });
 
-    //test.src for jdk/test/java/lang/Character/CheckProp.java
-    if let Ok(v) = std::env::var("TEST_SRC") {
-        put_props_kv(props_oop, "test.src", v.as_str());
+    let props_oop = args.get(0).unwrap();
+    for (k, v) in props.iter() {
+        put_props_kv(props_oop, k, v);
     }
 
     if thread::is_meet_ex() {
@@ -286,51 +355,32 @@ fn jvm_getProperty(jt: &mut JavaThread, env: JNIEnv, args: Vec<OopRef>) -> JNIRe
 }
 */
 
-fn arraycopy_same_obj(buf: Arc<OopRef>, src_pos: usize, dest_pos: usize, length: usize) {
-    let is_type_ary = {
-        let ptr = buf.get_raw_ptr();
-        unsafe {
-            match &(*ptr).v {
-                oop::RefKind::TypeArray(_) => true,
-                oop::RefKind::Array(_) => false,
-                _ => unreachable!(),
-            }
-        }
-    };
-
-    if is_type_ary {
-        let ptr = buf.get_mut_raw_ptr();
-        unsafe {
-            match &mut (*ptr).v {
-                oop::RefKind::TypeArray(ary) => match ary {
-                    oop::TypeArrayDesc::Char(ary) => {
-                        ary.copy_within(src_pos..(src_pos + length), dest_pos)
-                    }
-                    oop::TypeArrayDesc::Byte(ary) => {
-                        ary.copy_within(src_pos..(src_pos + length), dest_pos)
-                    }
-                    t => unreachable!("t = {:?}", t),
-                },
-
-                _ => unreachable!(),
+//the bounds & the types checked
+pub fn arraycopy_same_obj(buf: Arc<OopRef>, src_pos: usize, dest_pos: usize, length: usize) {
+    let src = src_pos..(src_pos + length);
+    let ptr = buf.get_mut_raw_ptr();
+    unsafe {
+        match &mut (*ptr).v {
+            oop::RefKind::TypeArray(ary) => match ary {
+                oop::TypeArrayDesc::Byte(ary) => ary.copy_within(src, dest_pos),
+                oop::TypeArrayDesc::Bool(ary) => ary.copy_within(src, dest_pos),
+                oop::TypeArrayDesc::Char(ary) => ary.copy_within(src, dest_pos),
+                oop::TypeArrayDesc::Short(ary) => ary.copy_within(src, dest_pos),
+                oop::TypeArrayDesc::Float(ary) => ary.copy_within(src, dest_pos),
+                oop::TypeArrayDesc::Double(ary) => ary.copy_within(src, dest_pos),
+                oop::TypeArrayDesc::Int(ary) => ary.copy_within(src, dest_pos),
+                oop::TypeArrayDesc::Long(ary) => ary.copy_within(src, dest_pos),
+            },
+            oop::RefKind::Array(ary) => {
+                let tmp = ary.elements[src].to_vec();
+                ary.elements[dest_pos..(dest_pos + length)].clone_from_slice(&tmp);
             }
+            _ => unreachable!(),
         }
-    } else {
-        let tmp = {
-            let ary = buf.extract_array();
-            let mut tmp = vec![oop::consts::get_null(); length];
-
-            let (_, ary) = ary.elements.split_at(src_pos);
-            tmp.clone_from_slice(&ary[..length]);
-            tmp
-        };
-
-        let ary = buf.extract_mut_array();
-        let (_, ary) = ary.elements.split_at_mut(dest_pos);
-        ary[..length].clone_from_slice(&tmp[..]);
     }
 }
 
+//the bounds & the types checked, 'src' & 'dest' not the same array
 pub fn arraycopy_diff_obj(
     src: Arc<OopRef>,
     src_pos: usize,
@@ -338,59 +388,31 @@ pub fn arraycopy_diff_obj(
     dest_pos: usize,
     length: usize,
 ) {
-    let is_type_ary = {
-        let ptr = src.get_raw_ptr();
-        unsafe {
-            match &(*ptr).v {
-                oop::RefKind::TypeArray(_) => true,
-                oop::RefKind::Array(_) => false,
+    let src_ptr = src.get_raw_ptr();
+    let dest_ptr = dest.get_mut_raw_ptr();
+    let (src, dest) = (src_pos..(src_pos + length), dest_pos..(dest_pos + length));
+
+    macro_rules! copy_type_ary {
+        ($src_ary:ident, $dest_ary:ident, $($t:ident),*) => {
+            match ($src_ary, $dest_ary) {
+                $((oop::TypeArrayDesc::$t(src_ary), oop::TypeArrayDesc::$t(dest_ary)) => {
+                    dest_ary[dest].copy_from_slice(&src_ary[src])
+                })*
                 _ => unreachable!(),
             }
-        }
-    };
-
-    // error!("src={}, dest={}, length={}, is_type_ary={}", src_pos, dest_pos, length, is_type_ary);
-
-    let src_ptr = src.get_raw_ptr();
+        };
+    }
 
-    if is_type_ary {
-        unsafe {
-            match &(*src_ptr).v {
-                oop::RefKind::TypeArray(src_ary) => match src_ary {
-                    oop::TypeArrayDesc::Byte(src_ary) => {
-                        let dest_ary = dest.extract_mut_type_array();
-                        let dest_ary = dest_ary.extract_mut_bytes();
-                        let (_, dest_ptr) = dest_ary.split_at_mut(dest_pos);
-                        let (_, src_ptr) = src_ary.split_at(src_pos);
-                        dest_ptr[..length].copy_from_slice(&src_ptr[..length]);
-                    }
-                    oop::TypeArrayDesc::Char(src_ary) => {
-                        let dest_ary = dest.extract_mut_type_array();
-                        let dest_ary = dest_ary.extract_mut_chars();
-                        let (_, dest_ptr) = dest_ary.split_at_mut(dest_pos);
-                        let (_, src_ptr) = src_ary.split_at(src_pos);
-                        dest_ptr[..length].copy_from_slice(&src_ptr[..length]);
-                    }
-                    oop::TypeArrayDesc::Int(src_ary) => {
-                        let dest_ary = dest.extract_mut_type_array();
-                        let dest_ary = dest_ary.extract_mut_ints();
-                        let (_, dest_ptr) = dest_ary.split_at_mut(dest_pos);
-                        let (_, src_ptr) = src_ary.split_at(src_pos);
-                        dest_ptr[..length].copy_from_slice(&src_ptr[..length]);
-                    }
-                    t => unreachable!("t = {:?}", t),
-                },
-                _ => unreachable!(),
+    unsafe {
+        match (&(*src_ptr).v, &mut (*dest_ptr).v) {
+            (oop::RefKind::TypeArray(src_ary), oop::RefKind::TypeArray(dest_ary)) => {
+                copy_type_ary!(src_ary, dest_ary, Byte, Bool, Char, Short, Float, Double, Int, Long)
+            }
+            (oop::RefKind::Array(src_ary), oop::RefKind::Array(dest_ary)) => {
+                dest_ary.elements[dest].clone_from_slice(&src_ary.elements[src])
             }
+            _ => unreachable!(),
         }
-    } else {
-        let src = src.extract_array();
-        let dest = dest.extract_mut_array();
-
-        let (_, src_ptr) = src.elements.split_at(src_pos);
-        let (_, dest_ptr) = dest.elements.split_at_mut(dest_pos);
-
-        dest_ptr[..length].clone_from_slice(&src_ptr[..length]);
     }
 }
 
@@ -5,6 +5,7 @@ use crate::new_br;
 use crate::oop::{Class, Oop, OopRef};
 use crate::runtime::vm::get_vm;
 use crate::runtime::{self, vm, JavaCall, JavaThread};
+use classfile::consts as cls_consts;
 
 pub fn get_native_methods() -> Vec<JNINativeMethod> {
     vec![
@@ -65,51 +66,51 @@ fn jvm_start0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
         inst.class.clone()
     };
 
-    let cls = clazz.get_class();
-    if cls.name.as_slice() == "java/lang/ref/Reference$ReferenceHandler".as_bytes() {
-        Ok(None)
-    } else {
-        let vm = vm::get_vm();
-
-        let jt = JavaThread::new(None, vm.threads.next_id());
-        vm.threads.attach_java_thread(jt.clone());
-
-        let args = vec![thread_oop.clone()];
-        vm.threads.spawn_java_thread(move || {
-            //setup current thread
-            let current_thread = jt.clone();
-            runtime::thread::THREAD.with(|t| {
-                *t.borrow_mut() = current_thread;
-            });
-
-            let cls = clazz.get_class();
-            let mir = {
-                //setup eetop
-                let eetop = jt.read().unwrap().eetop;
-                let fid = cls.get_field_id(&new_br("eetop"), &new_br("J"), false);
-                Class::put_field_value(thread_oop.extract_ref(), fid, Oop::new_long(eetop));
-
-                //obtain 'run' method
-                cls.get_virtual_method(&new_br("run"), &new_br("()V"))
-                    .unwrap()
-            };
-
-            //invoke 'run'
-            let mut jc = JavaCall::new_with_args(mir, args);
-            jt.write().unwrap().is_alive = true;
-            jt.write().unwrap().java_thread_obj = Some(thread_oop.clone());
-            jc.invoke(None, false);
-            jt.write().unwrap().is_alive = false;
-
-            //notify thread that invoke 'join'
-            let v = thread_oop.extract_ref();
-            v.notify_all();
-
-            vm.threads.detach_current_thread();
-        });
-
-        Ok(None)
+    let vm = vm::get_vm();
+
+    //java_thread_obj set before attached, a daemon thread never waited by join_all
+    let jt = JavaThread::new(None, vm.threads.next_id());
+    jt.write().unwrap().set_java_thread_obj(thread_oop.clone());
+    vm.threads.attach_java_thread(jt.clone());
+    let attached = jt.clone();
+
+    let args = vec![thread_oop.clone()];
+    let started = vm.threads.spawn_java_thread(move || {
+        //setup current thread
+        runtime::thread::set_current_java_thread(jt.clone());
+
+        let cls = clazz.get_class();
+        let mir = {
+            //setup eetop
+            let eetop = jt.read().unwrap().eetop;
+            let fid = cls.get_field_id(&new_br("eetop"), &new_br("J"), false);
+            Class::put_field_value(thread_oop.extract_ref(), fid, Oop::new_long(eetop));
+
+            //obtain 'run' method
+            cls.get_virtual_method(&new_br("run"), &new_br("()V"))
+                .unwrap()
+        };
+
+        //invoke 'run'
+        let mut jc = JavaCall::new_with_args(mir, args);
+        jt.write().unwrap().is_alive = true;
+        jc.invoke(None, false);
+        jt.write().unwrap().is_alive = false;
+
+        //notify thread that invoke 'join'
+        let v = thread_oop.extract_ref();
+        v.notify_all();
+
+        vm.threads.detach_current_thread();
+    });
+
+    if started.is_err() {
+        vm.threads.detach_java_thread(&attached);
+        let msg = Some("unable to create new native thread".to_string());
+        return Err(runtime::exception::new(cls_consts::J_OOM, msg));
     }
+
+    Ok(None)
 }
 
 fn jvm_isInterrupted(_env: JNIEnv, _args: &[Oop]) -> JNIResult {
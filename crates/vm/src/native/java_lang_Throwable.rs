@@ -2,8 +2,11 @@
 
 use crate::native::{new_fn, JNIEnv, JNINativeMethod, JNIResult};
 use crate::oop::{self, Class, Oop};
-use crate::runtime::{self, require_class3};
+use crate::runtime::{self, cmp, options, require_class3};
+use crate::types::{ClassRef, MethodIdRef};
 use crate::{new_br, util};
+use classfile::U2;
+use std::cmp::max;
 use std::sync::atomic::Ordering;
 
 pub fn get_native_methods() -> Vec<JNINativeMethod> {
@@ -27,79 +30,36 @@ pub fn get_native_methods() -> Vec<JNINativeMethod> {
 }
 
 fn jvm_fillInStackTrace(_env: JNIEnv, args: &[Oop]) -> JNIResult {
-    let jt = runtime::thread::current_java_thread();
-
-    let elm_cls = oop::class::load_and_init(b"java/lang/StackTraceElement");
+    let elm_cls = oop::class::load_and_init(b"java/lang/StackTraceElement")?;
     let ary_cls = require_class3(None, b"[Ljava/lang/StackTraceElement;").unwrap();
 
     let throwable_oop = args.get(0).unwrap();
-    let mut backtrace = Vec::with_capacity(jt.read().unwrap().frames.len());
-
-    let mut found_ex_here = false;
-    let jth = jt.read().unwrap();
-    for it in jth.frames.iter() {
-        let ex_here = {
-            let it = it.try_read().unwrap();
-            it.ex_here.load(Ordering::Relaxed)
-        };
-
-        backtrace.push(it.clone());
-
-        if ex_here {
-            found_ex_here = true;
-            break;
-        }
-    }
-    drop(jth);
-
-    /*
-       todo: how handle throw better?
-
-    if no ex_here found, it's:
-      throw new AnnotationFormatError("Unexpected end of annotations.");
-
-    new Throwable
-      Throwable.fillInStackTrace invoked, and come here
-
-    there are stacktraces for build 'Throwable' obj, not necessary for user, need discard
-
-    Exception in thread "main" java.lang.annotation.AnnotationFormatError: Unexpected end of annotations.
-       at java.lang.Throwable.fillInStackTrace(Throwable.java)
-       at java.lang.Throwable.fillInStackTrace(Throwable.java:783)
-       at java.lang.Throwable.<init>(Throwable.java:265)
-       at java.lang.Error.<init>(Error.java:70)
-       */
-    if !found_ex_here {
-        backtrace.pop();
-        backtrace.pop();
-        backtrace.pop();
-        backtrace.pop();
-    }
-
-    let mut traces = Vec::new();
-    for caller in backtrace.iter().rev() {
-        let (mir, pc) = {
-            let caller = caller.try_read().unwrap();
-            let pc = caller.pc.load(Ordering::Relaxed);
-            (caller.mir.clone(), pc)
-        };
+    let throwable_cls = {
+        let rf = throwable_oop.extract_ref();
+        let inst = rf.extract_inst();
+        inst.class.clone()
+    };
+    let backtrace = backtrace(throwable_cls);
 
+    let mut traces = Vec::with_capacity(backtrace.len());
+    for (mir, pc) in backtrace {
         let cls = mir.method.class.get_class();
         let cls_name = unsafe { std::str::from_utf8_unchecked(cls.name.as_slice()) };
         let cls_name = cls_name.replace("/", ".");
         let method_name = unsafe { std::str::from_utf8_unchecked(mir.method.name.as_slice()) };
-        let src_file = {
-            let cls = mir.method.class.get_class();
-            cls.get_source_file()
-        };
-        let src_file = match src_file {
+        let src_file = match cls.get_source_file() {
             Some(name) => {
                 let name = unsafe { std::str::from_utf8_unchecked(name.as_slice()) };
                 util::oop::new_java_lang_string2(name)
             }
-            None => util::oop::new_java_lang_string2(""),
+            None => oop::consts::get_null(),
+        };
+        //-2, the native method as StackTraceElement.isNativeMethod
+        let line_num = if mir.method.is_native() {
+            -2
+        } else {
+            mir.method.get_line_num(pc as U2)
         };
-        let line_num = mir.method.get_line_num((pc - 1) as u16);
 
         let elm = Oop::new_inst(elm_cls.clone());
         let args = vec![
@@ -135,6 +95,60 @@ fn jvm_fillInStackTrace(_env: JNIEnv, args: &[Oop]) -> JNIResult {
     Ok(Some(throwable_oop.clone()))
 }
 
+/*
+The frames of the current thread, the innermost first, as HotSpot
+java_lang_Throwable::fill_in_stack_trace:
+  1. the fillInStackTrace frames skipped, the native one & Throwable.fillInStackTrace()
+     or an override, methods of the throwable's class & its super classes only
+  2. the <init> frames of the throwable's class & its super classes skipped,
+     so the trace starts where the throwable is new'ed
+  3. at most -XX:MaxJavaStackTraceDepth frames (1024), 0 for no limit
+
+The pc of a frame invoking is past the operands of the invoke,
+(pc - 1) is still in the instruction for LineNumberTable lookup
+*/
+fn backtrace(throwable_cls: ClassRef) -> Vec<(MethodIdRef, i32)> {
+    let max_depth = options::with(|opts| opts.xx_usize("MaxJavaStackTraceDepth", 1024));
+
+    let jt = runtime::thread::current_java_thread();
+    let jt = jt.read().unwrap();
+    let frames = jt.frames.iter().rev().map(|it| {
+        let it = it.try_read().unwrap();
+        let pc = it.pc.load(Ordering::Relaxed);
+        (it.mir.clone(), max(pc - 1, 0))
+    });
+
+    let mut backtrace = Vec::new();
+    let mut skip_fill_in = true;
+    let mut skip_init = true;
+    for (mir, pc) in frames {
+        let method = &mir.method;
+        if skip_fill_in {
+            if method.name.as_slice() == b"fillInStackTrace"
+                && cmp::instance_of(throwable_cls.clone(), method.class.clone())
+            {
+                continue;
+            }
+            skip_fill_in = false;
+        }
+        if skip_init {
+            if method.name.as_slice() == b"<init>"
+                && cmp::instance_of(throwable_cls.clone(), method.class.clone())
+            {
+                continue;
+            }
+            skip_init = false;
+        }
+
+        if max_depth != 0 && backtrace.len() == max_depth {
+            break;
+        }
+        backtrace.push((mir, pc));
+    }
+
+    backtrace
+}
+
 fn jvm_getStackTraceDepth(_env: JNIEnv, args: &[Oop]) -> JNIResult {
     let throwable = args.get(0).unwrap();
     let cls = {
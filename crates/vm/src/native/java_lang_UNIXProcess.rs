@@ -0,0 +1,117 @@
+#![allow(non_snake_case)]
+
+use crate::native::common::{io_util, process_util};
+use crate::native::{new_fn, JNIEnv, JNINativeMethod, JNIResult};
+use crate::oop::Oop;
+use std::ffi::CString;
+
+/*
+Every launch mechanism (FORK, VFORK & POSIX_SPAWN) done by fork, jspawnhelper
+not used; waitForProcessExit called by the "process reaper" threads.
+*/
+
+pub fn get_native_methods() -> Vec<JNINativeMethod> {
+    vec![
+        new_fn("init", "()V", Box::new(jvm_init)),
+        new_fn(
+            "forkAndExec",
+            "(I[B[B[BI[BI[B[IZ)I",
+            Box::new(jvm_forkAndExec),
+        ),
+        new_fn(
+            "waitForProcessExit",
+            "(I)I",
+            Box::new(jvm_waitForProcessExit),
+        ),
+        new_fn("destroyProcess", "(IZ)V", Box::new(jvm_destroyProcess)),
+    ]
+}
+
+fn jvm_init(_env: JNIEnv, _args: &[Oop]) -> JNIResult {
+    Ok(None)
+}
+
+//(mode, helperpath, prog, argBlock, argc, envBlock, envc, dir, fds, redirectErrorStream)
+fn jvm_forkAndExec(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let prog = c_string(args.get(3).unwrap()).unwrap();
+    let arg_block = args.get(4).unwrap();
+    let argc = args.get(5).unwrap().extract_int() as usize;
+    let env_block = args.get(6).unwrap();
+    let envc = args.get(7).unwrap().extract_int() as usize;
+    let dir = c_string(args.get(8).unwrap());
+    let fds = args.get(9).unwrap();
+    let redirect_error_stream = args.get(10).unwrap().extract_int() != 0;
+
+    let mut spec = process_util::ChildSpec {
+        prog,
+        args: split_block(arg_block, argc),
+        envs: if env_block.is_null() {
+            None
+        } else {
+            Some(split_block(env_block, envc))
+        },
+        dir,
+        fds: [-1; 3],
+        redirect_error_stream,
+    };
+    {
+        let rf = fds.extract_ref();
+        let ary = rf.extract_type_array();
+        spec.fds.copy_from_slice(&ary.extract_ints()[..3]);
+    }
+
+    match process_util::fork_and_exec(&spec) {
+        Ok((pid, parent_ends)) => {
+            let rf = fds.extract_ref();
+            let ary = rf.extract_mut_type_array();
+            ary.extract_mut_ints()[..3].copy_from_slice(&parent_ends);
+            Ok(Some(Oop::new_int(pid)))
+        }
+        Err(msg) => Err(io_util::new_io_ex(&msg)),
+    }
+}
+
+fn jvm_waitForProcessExit(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let pid = args.get(1).unwrap().extract_int();
+    let v = process_util::wait_for_exit(pid);
+    Ok(Some(Oop::new_int(v)))
+}
+
+fn jvm_destroyProcess(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let pid = args.get(0).unwrap().extract_int();
+    let force = args.get(1).unwrap().extract_int() != 0;
+
+    let sig = if force { libc::SIGKILL } else { libc::SIGTERM };
+    unsafe {
+        libc::kill(pid, sig);
+    }
+    Ok(None)
+}
+
+//a NUL terminated byte[] from UNIXProcess.toCString, None if null
+fn c_string(v: &Oop) -> Option<CString> {
+    if v.is_null() {
+        return None;
+    }
+
+    let rf = v.extract_ref();
+    let ary = rf.extract_type_array();
+    let bytes = ary.extract_bytes();
+    let end = bytes.iter().position(|b| *b == 0).unwrap_or(bytes.len());
+    CString::new(&bytes[..end]).ok()
+}
+
+//the first 'count' of the NUL separated strings of a block
+fn split_block(block: &Oop, count: usize) -> Vec<CString> {
+    if block.is_null() {
+        return vec![];
+    }
+
+    let rf = block.extract_ref();
+    let ary = rf.extract_type_array();
+    ary.extract_bytes()
+        .split(|b| *b == 0)
+        .take(count)
+        .map(|s| CString::new(s).unwrap())
+        .collect()
+}
@@ -0,0 +1,15 @@
+#![allow(non_snake_case)]
+
+use crate::native::{new_fn, JNIEnv, JNINativeMethod, JNIResult};
+use crate::oop::Oop;
+use crate::runtime::heap;
+
+pub fn get_native_methods() -> Vec<JNINativeMethod> {
+    vec![new_fn("get", "()Ljava/lang/Object;", Box::new(jvm_get))]
+}
+
+//the Java method 'get' run as a native, not racing the collection clearing the referent
+fn jvm_get(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let this = args.first().unwrap();
+    Ok(Some(heap::get_referent(this)))
+}
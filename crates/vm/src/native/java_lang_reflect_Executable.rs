@@ -0,0 +1,72 @@
+#![allow(non_snake_case)]
+
+use crate::native::{common, new_fn, JNIEnv, JNINativeMethod, JNIResult};
+use crate::oop::{self, Oop};
+use crate::runtime::{self, require_class3};
+use crate::{new_br, util};
+use classfile::consts as cls_consts;
+
+pub fn get_native_methods() -> Vec<JNINativeMethod> {
+    vec![
+        new_fn(
+            "getParameters0",
+            "()[Ljava/lang/reflect/Parameter;",
+            Box::new(jvm_getParameters0),
+        ),
+        new_fn(
+            "getTypeAnnotationBytes0",
+            "()[B",
+            Box::new(jvm_getTypeAnnotationBytes0),
+        ),
+    ]
+}
+
+//null if no 'MethodParameters' attribute, Executable.getParameters synthesizes them
+fn jvm_getParameters0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let this = args.get(0).unwrap();
+    let mir = common::reflect::get_method_id(this)?;
+
+    let params = match mir.method.get_method_parameters() {
+        Some(params) => params,
+        None => return Ok(Some(oop::consts::get_null())),
+    };
+
+    let param_cls = require_class3(None, cls_consts::J_PARAMETER).unwrap();
+    oop::class::init_class(&param_cls)?;
+
+    let desc = new_br("(Ljava/lang/String;ILjava/lang/reflect/Executable;I)V");
+    let params: Vec<Oop> = params
+        .iter()
+        .enumerate()
+        .map(|(i, (name, acc_flags))| {
+            let name = match name {
+                Some(name) => util::oop::new_java_lang_string3(name.as_slice()),
+                None => oop::consts::get_null(),
+            };
+
+            let param = Oop::new_inst(param_cls.clone());
+            let args = vec![
+                param.clone(),
+                name,
+                Oop::new_int(*acc_flags as i32),
+                this.clone(),
+                Oop::new_int(i as i32),
+            ];
+            runtime::invoke::invoke_ctor(param_cls.clone(), desc.clone(), args);
+            param
+        })
+        .collect();
+
+    let ary_cls = require_class3(None, b"[Ljava/lang/reflect/Parameter;").unwrap();
+    Ok(Some(Oop::new_ref_ary2(ary_cls, params)))
+}
+
+fn jvm_getTypeAnnotationBytes0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let this = args.get(0).unwrap();
+    let mir = common::reflect::get_method_id(this)?;
+    let v = match mir.method.get_type_annotation() {
+        Some(raw) => Oop::new_byte_ary2(raw),
+        None => oop::consts::get_null(),
+    };
+    Ok(Some(v))
+}
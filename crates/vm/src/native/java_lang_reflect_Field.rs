@@ -0,0 +1,22 @@
+#![allow(non_snake_case)]
+
+use crate::native::{common, new_fn, JNIEnv, JNINativeMethod, JNIResult};
+use crate::oop::{self, Oop};
+
+pub fn get_native_methods() -> Vec<JNINativeMethod> {
+    vec![new_fn(
+        "getTypeAnnotationBytes0",
+        "()[B",
+        Box::new(jvm_getTypeAnnotationBytes0),
+    )]
+}
+
+fn jvm_getTypeAnnotationBytes0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let this = args.get(0).unwrap();
+    let fir = common::reflect::get_field_id(this)?;
+    let v = match fir.field.get_type_annotation() {
+        Some(raw) => Oop::new_byte_ary2(raw),
+        None => oop::consts::get_null(),
+    };
+    Ok(Some(v))
+}
@@ -2,12 +2,19 @@
 
 use crate::native;
 use crate::native::{new_fn, JNIEnv, JNINativeMethod, JNIResult};
+use crate::new_br;
 use crate::oop::class::ClassPtr;
 use crate::oop::{self, Class, Oop, OopRef};
-use crate::runtime;
+use crate::runtime::{self, require_class3, ClassLoader};
 use crate::types::ClassRef;
 use class_parser::parse_class;
-use std::sync::Arc;
+use classfile::{constant_pool, consts as cls_consts};
+use std::sync::{Arc, Mutex};
+
+lazy_static! {
+    //serialize defineClass0, make the duplicate check & the put atomic
+    static ref DEFINE_LOCK: Mutex<()> = Mutex::new(());
+}
 
 pub fn get_native_methods() -> Vec<JNINativeMethod> {
     vec![new_fn(
@@ -18,7 +25,7 @@ pub fn get_native_methods() -> Vec<JNINativeMethod> {
 }
 
 fn jvm_defineClass0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
-    let _loader = args.get(0).unwrap();
+    let loader = args.get(0).unwrap();
     let name = args.get(1).unwrap();
     let name = OopRef::java_lang_string(name.extract_ref());
     let b = args.get(2).unwrap();
@@ -27,9 +34,30 @@ fn jvm_defineClass0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
 
     let name = name.replace(".", "/");
 
-    //parse bytes => class, put in sys_dic
-    let class = do_parse_class(b, off as usize, len as usize);
-    runtime::sys_dic_put(name.as_bytes(), class.clone());
+    let class_loader = ClassLoader::defined_by(loader);
+    let class = do_parse_class(b, off as usize, len as usize, &name, class_loader)?;
+
+    //a name defined once by a loader
+    let defined = {
+        let _l = DEFINE_LOCK.lock().unwrap();
+        match loader {
+            Oop::Null if runtime::sys_dic_find(name.as_bytes()).is_some() => false,
+            Oop::Null => {
+                runtime::sys_dic_put(name.as_bytes(), class.clone());
+                true
+            }
+            _ => runtime::sys_dic_put_defined(loader, name.as_bytes(), class.clone()),
+        }
+    };
+    if !defined {
+        let msg = format!(
+            "attempted duplicate class definition for name: \"{}\"",
+            name
+        );
+        let ex = runtime::exception::new(cls_consts::J_LINKAGE_ERROR, Some(msg));
+        return Err(ex);
+    }
+
     {
         let this_ref = class.clone();
         let cls = class.get_mut_class();
@@ -38,21 +66,59 @@ fn jvm_defineClass0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
     }
     native::java_lang_Class::create_mirror(class.clone());
 
-    let v = Oop::new_mirror(class);
-    Ok(Some(v))
+    let mirror = class.get_class().get_mirror();
+    set_class_loader(&mirror, loader);
+
+    Ok(Some(mirror))
 }
 
-fn do_parse_class(v: &Oop, off: usize, len: usize) -> ClassRef {
+fn do_parse_class(
+    v: &Oop,
+    off: usize,
+    len: usize,
+    name: &str,
+    class_loader: ClassLoader,
+) -> Result<ClassRef, Oop> {
     let rf = v.extract_ref();
     let ary = rf.extract_type_array();
     let ary = ary.extract_bytes();
     match parse_class(&ary[off..(off + len)]) {
         Ok(r) => {
-            let cfr = Arc::new(Box::new(r.1));
-            //fixme: setup classloader
-            let class = Class::new_class(cfr, None);
-            ClassPtr::new(class)
+            let cf = r.1;
+            let this_name = constant_pool::get_class_name(&cf.cp, cf.this_class as usize);
+            if this_name.as_slice() != name.as_bytes() {
+                let msg = format!(
+                    "{} (wrong name: {})",
+                    name,
+                    String::from_utf8_lossy(this_name.as_slice())
+                );
+                let ex = runtime::exception::new(cls_consts::J_NO_CLASS_DEF_FOUND, Some(msg));
+                return Err(ex);
+            }
+
+            let cfr = Arc::new(Box::new(cf));
+            //the loader oop returned by getClassLoader0, see set_class_loader
+            let class = Class::new_class(cfr, Some(class_loader));
+            Ok(ClassPtr::new(class))
+        }
+        Err(e) => {
+            let msg = format!("{}: {:?}", name, e);
+            let ex = runtime::exception::new(cls_consts::J_CLASS_FORMAT_ERROR, Some(msg));
+            Err(ex)
         }
-        Err(e) => unreachable!("e = {:?}", e),
     }
 }
+
+//java.lang.Class.classLoader, returned by Class.getClassLoader0
+fn set_class_loader(mirror: &Oop, loader: &Oop) {
+    let cls = require_class3(None, cls_consts::J_CLASS).unwrap();
+    let fid = {
+        let cls = cls.get_class();
+        cls.get_field_id(
+            &new_br("classLoader"),
+            &new_br("Ljava/lang/ClassLoader;"),
+            false,
+        )
+    };
+    Class::put_field_value(mirror.extract_ref(), fid, loader.clone());
+}
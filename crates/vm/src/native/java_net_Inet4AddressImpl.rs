@@ -0,0 +1,61 @@
+#![allow(non_snake_case)]
+
+use crate::native::common::net_util;
+use crate::native::{new_fn, JNIEnv, JNINativeMethod, JNIResult};
+use crate::oop::Oop;
+use crate::util;
+use std::net::Ipv4Addr;
+
+pub fn get_native_methods() -> Vec<JNINativeMethod> {
+    vec![
+        new_fn(
+            "getLocalHostName",
+            "()Ljava/lang/String;",
+            Box::new(jvm_getLocalHostName),
+        ),
+        new_fn(
+            "lookupAllHostAddr",
+            "(Ljava/lang/String;)[Ljava/net/InetAddress;",
+            Box::new(jvm_lookupAllHostAddr),
+        ),
+        new_fn(
+            "getHostByAddr",
+            "([B)Ljava/lang/String;",
+            Box::new(jvm_getHostByAddr),
+        ),
+        new_fn("isReachable0", "([BI[BI)Z", Box::new(jvm_isReachable0)),
+    ]
+}
+
+fn jvm_getLocalHostName(_env: JNIEnv, _args: &[Oop]) -> JNIResult {
+    let name = net_util::local_host_name();
+    Ok(Some(util::oop::new_java_lang_string2(&name)))
+}
+
+fn jvm_lookupAllHostAddr(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let host = args.get(1).unwrap();
+    let v = net_util::lookup_all_host_addr(host, libc::AF_INET)?;
+    Ok(Some(v))
+}
+
+fn jvm_getHostByAddr(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let addr = args.get(1).unwrap();
+    let v = net_util::host_by_addr(addr)?;
+    Ok(Some(v))
+}
+
+//(addr, timeout, ifaddr, ttl), ifaddr & ttl ignored
+fn jvm_isReachable0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let addr = args.get(1).unwrap();
+    let timeout = args.get(2).unwrap().extract_int();
+
+    let ip = {
+        let rf = addr.extract_ref();
+        let ary = rf.extract_type_array();
+        let bytes = ary.extract_bytes();
+        Ipv4Addr::new(bytes[0], bytes[1], bytes[2], bytes[3])
+    };
+    let v = net_util::is_reachable(ip, timeout);
+
+    Ok(Some(Oop::new_int(v as i32)))
+}
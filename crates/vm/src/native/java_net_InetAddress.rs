@@ -0,0 +1,12 @@
+#![allow(non_snake_case)]
+
+use crate::native::{new_fn, JNIEnv, JNINativeMethod, JNIResult};
+use crate::oop::Oop;
+
+pub fn get_native_methods() -> Vec<JNINativeMethod> {
+    vec![new_fn("init", "()V", Box::new(jvm_init))]
+}
+
+fn jvm_init(_env: JNIEnv, _args: &[Oop]) -> JNIResult {
+    Ok(None)
+}
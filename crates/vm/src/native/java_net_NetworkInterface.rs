@@ -0,0 +1,400 @@
+#![allow(non_snake_case)]
+
+use crate::native::common::net_util;
+use crate::native::{new_fn, JNIEnv, JNINativeMethod, JNIResult};
+use crate::new_br;
+use crate::oop::{self, Class, Oop, OopRef};
+use crate::runtime::{self, require_class3};
+use crate::util;
+use std::ffi::{CStr, CString};
+use std::net::{IpAddr, Ipv4Addr};
+
+lazy_static! {
+    static ref NI_DISPLAY_NAME: usize = net_util::field_offset(
+        b"java/net/NetworkInterface",
+        "displayName",
+        "Ljava/lang/String;"
+    );
+    static ref NI_BINDINGS: usize = net_util::field_offset(
+        b"java/net/NetworkInterface",
+        "bindings",
+        "[Ljava/net/InterfaceAddress;"
+    );
+    static ref NI_CHILDS: usize = net_util::field_offset(
+        b"java/net/NetworkInterface",
+        "childs",
+        "[Ljava/net/NetworkInterface;"
+    );
+    static ref IFA_ADDRESS: usize = net_util::field_offset(
+        b"java/net/InterfaceAddress",
+        "address",
+        "Ljava/net/InetAddress;"
+    );
+    static ref IFA_BROADCAST: usize = net_util::field_offset(
+        b"java/net/InterfaceAddress",
+        "broadcast",
+        "Ljava/net/Inet4Address;"
+    );
+    static ref IFA_MASK_LENGTH: usize =
+        net_util::field_offset(b"java/net/InterfaceAddress", "maskLength", "S");
+}
+
+pub fn get_native_methods() -> Vec<JNINativeMethod> {
+    vec![
+        new_fn("init", "()V", Box::new(jvm_init)),
+        new_fn(
+            "getAll",
+            "()[Ljava/net/NetworkInterface;",
+            Box::new(jvm_getAll),
+        ),
+        new_fn(
+            "getByName0",
+            "(Ljava/lang/String;)Ljava/net/NetworkInterface;",
+            Box::new(jvm_getByName0),
+        ),
+        new_fn(
+            "getByIndex0",
+            "(I)Ljava/net/NetworkInterface;",
+            Box::new(jvm_getByIndex0),
+        ),
+        new_fn(
+            "getByInetAddress0",
+            "(Ljava/net/InetAddress;)Ljava/net/NetworkInterface;",
+            Box::new(jvm_getByInetAddress0),
+        ),
+        new_fn("isUp0", "(Ljava/lang/String;I)Z", Box::new(jvm_isUp0)),
+        new_fn(
+            "isLoopback0",
+            "(Ljava/lang/String;I)Z",
+            Box::new(jvm_isLoopback0),
+        ),
+        new_fn(
+            "supportsMulticast0",
+            "(Ljava/lang/String;I)Z",
+            Box::new(jvm_supportsMulticast0),
+        ),
+        new_fn("isP2P0", "(Ljava/lang/String;I)Z", Box::new(jvm_isP2P0)),
+        new_fn(
+            "getMacAddr0",
+            "([BLjava/lang/String;I)[B",
+            Box::new(jvm_getMacAddr0),
+        ),
+        new_fn("getMTU0", "(Ljava/lang/String;I)I", Box::new(jvm_getMTU0)),
+    ]
+}
+
+//an interface of os, with the IPv4 addresses bound
+struct Interface {
+    name: String,
+    index: i32,
+    flags: u32,
+    //(address, broadcast, mask length)
+    addrs: Vec<(Ipv4Addr, Option<Ipv4Addr>, i16)>,
+    mac: Option<Vec<u8>>,
+}
+
+fn jvm_init(_env: JNIEnv, _args: &[Oop]) -> JNIResult {
+    Ok(None)
+}
+
+fn jvm_getAll(_env: JNIEnv, _args: &[Oop]) -> JNIResult {
+    let all = interfaces().map_err(|msg| net_util::new_socket_ex(&msg))?;
+    let elms: Vec<Oop> = all
+        .iter()
+        .map(new_network_interface)
+        .collect::<Result<_, _>>()?;
+    let ary_cls = require_class3(None, b"[Ljava/net/NetworkInterface;").unwrap();
+    Ok(Some(Oop::new_ref_ary2(ary_cls, elms)))
+}
+
+fn jvm_getByName0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let name = args.get(0).unwrap();
+    let name = OopRef::java_lang_string(name.extract_ref());
+    find(|it| it.name == name)
+}
+
+fn jvm_getByIndex0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let index = args.get(0).unwrap().extract_int();
+    find(|it| it.index == index)
+}
+
+fn jvm_getByInetAddress0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let address = args.get(0).unwrap();
+    match net_util::inet_address_ip(address) {
+        Some(IpAddr::V4(ip)) => find(|it| it.addrs.iter().any(|(v, _, _)| *v == ip)),
+        _ => Ok(Some(oop::consts::get_null())),
+    }
+}
+
+fn jvm_isUp0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let flags = get_flags(args.get(0).unwrap())?;
+    let v = flags & libc::IFF_UP as u32 != 0 && flags & libc::IFF_RUNNING as u32 != 0;
+    Ok(Some(Oop::new_int(v as i32)))
+}
+
+fn jvm_isLoopback0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let flags = get_flags(args.get(0).unwrap())?;
+    let v = flags & libc::IFF_LOOPBACK as u32 != 0;
+    Ok(Some(Oop::new_int(v as i32)))
+}
+
+fn jvm_supportsMulticast0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let flags = get_flags(args.get(0).unwrap())?;
+    let v = flags & libc::IFF_MULTICAST as u32 != 0;
+    Ok(Some(Oop::new_int(v as i32)))
+}
+
+fn jvm_isP2P0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let flags = get_flags(args.get(0).unwrap())?;
+    let v = flags & libc::IFF_POINTOPOINT as u32 != 0;
+    Ok(Some(Oop::new_int(v as i32)))
+}
+
+//(addrArray, name, index), null if no hardware address
+fn jvm_getMacAddr0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let name = args.get(1).unwrap();
+    let name = OopRef::java_lang_string(name.extract_ref());
+
+    let all = interfaces().map_err(|msg| net_util::new_socket_ex(&msg))?;
+    let mac = all
+        .into_iter()
+        .find(|it| it.name == name)
+        .and_then(|it| it.mac)
+        .filter(|mac| mac.iter().any(|b| *b != 0));
+
+    match mac {
+        Some(mac) => Ok(Some(Oop::new_byte_ary2(mac))),
+        None => Ok(Some(oop::consts::get_null())),
+    }
+}
+
+fn jvm_getMTU0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let name = args.get(0).unwrap();
+    let name = OopRef::java_lang_string(name.extract_ref());
+    Ok(Some(Oop::new_int(mtu(&name))))
+}
+
+fn find<F>(f: F) -> JNIResult
+where
+    F: Fn(&Interface) -> bool,
+{
+    let all = interfaces().map_err(|msg| net_util::new_socket_ex(&msg))?;
+    match all.iter().find(|it| f(it)) {
+        Some(it) => Ok(Some(new_network_interface(it)?)),
+        None => Ok(Some(oop::consts::get_null())),
+    }
+}
+
+//flags of the interface named, SocketException if no such one
+fn get_flags(name: &Oop) -> Result<u32, Oop> {
+    let name = OopRef::java_lang_string(name.extract_ref());
+    let all = interfaces().map_err(|msg| net_util::new_socket_ex(&msg))?;
+    match all.iter().find(|it| it.name == name) {
+        Some(it) => Ok(it.flags),
+        None => Err(net_util::new_socket_ex("No such device")),
+    }
+}
+
+fn new_network_interface(it: &Interface) -> Result<Oop, Oop> {
+    let name = util::oop::new_java_lang_string2(&it.name);
+
+    let addrs: Vec<Oop> = it
+        .addrs
+        .iter()
+        .map(|(ip, _, _)| net_util::new_inet_address(None, IpAddr::V4(*ip)))
+        .collect::<Result<_, _>>()?;
+    let ary_cls = require_class3(None, b"[Ljava/net/InetAddress;").unwrap();
+    let addrs = Oop::new_ref_ary2(ary_cls, addrs);
+
+    let cls = oop::class::load_and_init(b"java/net/NetworkInterface")?;
+    let v = Oop::new_inst(cls.clone());
+    let args = vec![v.clone(), name.clone(), Oop::new_int(it.index), addrs];
+    runtime::invoke::invoke_ctor(
+        cls,
+        new_br("(Ljava/lang/String;I[Ljava/net/InetAddress;)V"),
+        args,
+    );
+
+    let bindings: Vec<Oop> = it
+        .addrs
+        .iter()
+        .map(|(ip, broadcast, mask_len)| new_interface_address(*ip, *broadcast, *mask_len))
+        .collect::<Result<_, _>>()?;
+    let ary_cls = require_class3(None, b"[Ljava/net/InterfaceAddress;").unwrap();
+    let bindings = Oop::new_ref_ary2(ary_cls, bindings);
+    let ary_cls = require_class3(None, b"[Ljava/net/NetworkInterface;").unwrap();
+    let childs = Oop::new_ref_ary2(ary_cls, vec![]);
+
+    let rf = v.extract_ref();
+    Class::put_field_value2(rf.clone(), *NI_DISPLAY_NAME, name);
+    Class::put_field_value2(rf.clone(), *NI_BINDINGS, bindings);
+    Class::put_field_value2(rf, *NI_CHILDS, childs);
+
+    Ok(v)
+}
+
+fn new_interface_address(
+    ip: Ipv4Addr,
+    broadcast: Option<Ipv4Addr>,
+    mask_len: i16,
+) -> Result<Oop, Oop> {
+    let cls = oop::class::load_and_init(b"java/net/InterfaceAddress")?;
+    let v = Oop::new_inst(cls.clone());
+    runtime::invoke::invoke_ctor(cls, new_br("()V"), vec![v.clone()]);
+
+    let address = net_util::new_inet_address(None, IpAddr::V4(ip))?;
+    let broadcast = match broadcast {
+        Some(ip) => net_util::new_inet_address(None, IpAddr::V4(ip))?,
+        None => oop::consts::get_null(),
+    };
+
+    let rf = v.extract_ref();
+    Class::put_field_value2(rf.clone(), *IFA_ADDRESS, address);
+    Class::put_field_value2(rf.clone(), *IFA_BROADCAST, broadcast);
+    Class::put_field_value2(rf, *IFA_MASK_LENGTH, Oop::new_int(mask_len as i32));
+
+    Ok(v)
+}
+
+//interfaces by getifaddrs, in the order of os
+fn interfaces() -> Result<Vec<Interface>, String> {
+    let mut ifap: *mut libc::ifaddrs = std::ptr::null_mut();
+    if unsafe { libc::getifaddrs(&mut ifap) } == -1 {
+        return Err(crate::native::common::io_util::strerror(net_util::errno()));
+    }
+
+    let mut all: Vec<Interface> = vec![];
+    let mut p = ifap;
+    while !p.is_null() {
+        let ifa = unsafe { &*p };
+        p = ifa.ifa_next;
+
+        let name = unsafe { CStr::from_ptr(ifa.ifa_name) };
+        let name = name.to_string_lossy().to_string();
+        let i = match all.iter().position(|it| it.name == name) {
+            Some(i) => i,
+            None => {
+                let index = CString::new(name.as_str())
+                    .map(|s| unsafe { libc::if_nametoindex(s.as_ptr()) } as i32)
+                    .unwrap_or(0);
+                all.push(Interface {
+                    name,
+                    index,
+                    flags: ifa.ifa_flags as u32,
+                    addrs: vec![],
+                    mac: None,
+                });
+                all.len() - 1
+            }
+        };
+        let it = &mut all[i];
+
+        if ifa.ifa_addr.is_null() {
+            continue;
+        }
+        let family = unsafe { (*ifa.ifa_addr).sa_family } as i32;
+        if family == libc::AF_INET {
+            let ip = sockaddr_ip(ifa.ifa_addr).unwrap();
+            let mask_len = sockaddr_ip(ifa.ifa_netmask)
+                .map(|mask| u32::from(mask).count_ones() as i16)
+                .unwrap_or(0);
+            let broadcast = if ifa.ifa_flags & libc::IFF_BROADCAST as u32 != 0 {
+                sockaddr_ip(broadcast_addr(ifa))
+            } else {
+                None
+            };
+            it.addrs.push((ip, broadcast, mask_len));
+        } else if let Some(mac) = hardware_addr(ifa.ifa_addr) {
+            it.mac = Some(mac);
+        }
+    }
+    unsafe {
+        libc::freeifaddrs(ifap);
+    }
+
+    Ok(all)
+}
+
+fn sockaddr_ip(sa: *const libc::sockaddr) -> Option<Ipv4Addr> {
+    if sa.is_null() || unsafe { (*sa).sa_family } as i32 != libc::AF_INET {
+        return None;
+    }
+    let sa = unsafe { &*(sa as *const libc::sockaddr_in) };
+    Some(Ipv4Addr::from(u32::from_be(sa.sin_addr.s_addr)))
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn broadcast_addr(ifa: &libc::ifaddrs) -> *const libc::sockaddr {
+    ifa.ifa_ifu
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+fn broadcast_addr(ifa: &libc::ifaddrs) -> *const libc::sockaddr {
+    ifa.ifa_dstaddr
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn hardware_addr(sa: *const libc::sockaddr) -> Option<Vec<u8>> {
+    if unsafe { (*sa).sa_family } as i32 != libc::AF_PACKET {
+        return None;
+    }
+    let sa = unsafe { &*(sa as *const libc::sockaddr_ll) };
+    let len = (sa.sll_halen as usize).min(sa.sll_addr.len());
+    Some(sa.sll_addr[..len].to_vec())
+}
+
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+fn hardware_addr(sa: *const libc::sockaddr) -> Option<Vec<u8>> {
+    if unsafe { (*sa).sa_family } as i32 != libc::AF_LINK {
+        return None;
+    }
+    let sa = unsafe { &*(sa as *const libc::sockaddr_dl) };
+    let data = sa.sdl_data.as_ptr() as *const u8;
+    let mac =
+        unsafe { std::slice::from_raw_parts(data.add(sa.sdl_nlen as usize), sa.sdl_alen as usize) };
+    Some(mac.to_vec())
+}
+
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "android",
+    target_os = "macos",
+    target_os = "ios"
+)))]
+fn hardware_addr(_sa: *const libc::sockaddr) -> Option<Vec<u8>> {
+    None
+}
+
+//-1 if not known
+#[cfg(target_os = "linux")]
+fn mtu(name: &str) -> i32 {
+    let path = format!("/sys/class/net/{}/mtu", name);
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|s| s.trim().parse().ok())
+        .unwrap_or(-1)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn mtu(_name: &str) -> i32 {
+    -1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn t_loopback() {
+        let all = interfaces().unwrap();
+        let lo = all
+            .iter()
+            .find(|it| it.flags & libc::IFF_LOOPBACK as u32 != 0)
+            .expect("no loopback");
+        assert!(lo.index > 0);
+        assert!(lo
+            .addrs
+            .iter()
+            .any(|(ip, _, mask_len)| *ip == Ipv4Addr::LOCALHOST && *mask_len == 8));
+    }
+}
@@ -0,0 +1,637 @@
+#![allow(non_snake_case)]
+
+use crate::native::common::{io_util, net_util, reflect};
+use crate::native::{new_fn, JNIEnv, JNINativeMethod, JNIResult};
+use crate::oop::{Class, Oop};
+use classfile::consts as cls_consts;
+use classfile::SignatureType;
+use std::net::{IpAddr, Ipv4Addr};
+
+lazy_static! {
+    //DatagramSocketImpl fd & localPort
+    static ref DS_IMPL_FD: usize = io_util::stream_fd_offset(b"java/net/DatagramSocketImpl");
+    static ref DS_IMPL_LOCAL_PORT: usize =
+        net_util::field_offset(b"java/net/DatagramSocketImpl", "localPort", "I");
+    //AbstractPlainDatagramSocketImpl timeout & connected
+    static ref DS_IMPL_TIMEOUT: usize =
+        net_util::field_offset(b"java/net/AbstractPlainDatagramSocketImpl", "timeout", "I");
+    static ref DS_IMPL_CONNECTED: usize =
+        net_util::field_offset(b"java/net/AbstractPlainDatagramSocketImpl", "connected", "Z");
+    //DatagramPacket
+    static ref DP_BUF: usize = net_util::field_offset(b"java/net/DatagramPacket", "buf", "[B");
+    static ref DP_OFFSET: usize = net_util::field_offset(b"java/net/DatagramPacket", "offset", "I");
+    static ref DP_LENGTH: usize = net_util::field_offset(b"java/net/DatagramPacket", "length", "I");
+    static ref DP_BUF_LENGTH: usize =
+        net_util::field_offset(b"java/net/DatagramPacket", "bufLength", "I");
+    static ref DP_ADDRESS: usize =
+        net_util::field_offset(b"java/net/DatagramPacket", "address", "Ljava/net/InetAddress;");
+    static ref DP_PORT: usize = net_util::field_offset(b"java/net/DatagramPacket", "port", "I");
+    //NetworkInterface addrs, the interface of multicast
+    static ref NI_ADDRS: usize =
+        net_util::field_offset(b"java/net/NetworkInterface", "addrs", "[Ljava/net/InetAddress;");
+}
+
+pub fn get_native_methods() -> Vec<JNINativeMethod> {
+    vec![
+        new_fn("init", "()V", Box::new(jvm_init)),
+        new_fn("bind0", "(ILjava/net/InetAddress;)V", Box::new(jvm_bind0)),
+        new_fn("send", "(Ljava/net/DatagramPacket;)V", Box::new(jvm_send)),
+        new_fn("peek", "(Ljava/net/InetAddress;)I", Box::new(jvm_peek)),
+        new_fn(
+            "peekData",
+            "(Ljava/net/DatagramPacket;)I",
+            Box::new(jvm_peekData),
+        ),
+        new_fn(
+            "receive0",
+            "(Ljava/net/DatagramPacket;)V",
+            Box::new(jvm_receive0),
+        ),
+        new_fn("setTimeToLive", "(I)V", Box::new(jvm_setTimeToLive)),
+        new_fn("getTimeToLive", "()I", Box::new(jvm_getTimeToLive)),
+        new_fn("setTTL", "(B)V", Box::new(jvm_setTimeToLive)),
+        new_fn("getTTL", "()B", Box::new(jvm_getTTL)),
+        new_fn(
+            "join",
+            "(Ljava/net/InetAddress;Ljava/net/NetworkInterface;)V",
+            Box::new(jvm_join),
+        ),
+        new_fn(
+            "leave",
+            "(Ljava/net/InetAddress;Ljava/net/NetworkInterface;)V",
+            Box::new(jvm_leave),
+        ),
+        new_fn(
+            "datagramSocketCreate",
+            "()V",
+            Box::new(jvm_datagramSocketCreate),
+        ),
+        new_fn(
+            "datagramSocketClose",
+            "()V",
+            Box::new(jvm_datagramSocketClose),
+        ),
+        new_fn(
+            "socketSetOption",
+            "(ILjava/lang/Object;)V",
+            Box::new(jvm_socketSetOption),
+        ),
+        new_fn(
+            "socketSetOption0",
+            "(ILjava/lang/Object;)V",
+            Box::new(jvm_socketSetOption),
+        ),
+        new_fn(
+            "socketGetOption",
+            "(I)Ljava/lang/Object;",
+            Box::new(jvm_socketGetOption),
+        ),
+        new_fn(
+            "connect0",
+            "(Ljava/net/InetAddress;I)V",
+            Box::new(jvm_connect0),
+        ),
+        new_fn("disconnect0", "(I)V", Box::new(jvm_disconnect0)),
+        new_fn("dataAvailable", "()I", Box::new(jvm_dataAvailable)),
+    ]
+}
+
+fn jvm_init(_env: JNIEnv, _args: &[Oop]) -> JNIResult {
+    Ok(None)
+}
+
+fn jvm_bind0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let this = args.get(0).unwrap();
+    let port = args.get(1).unwrap().extract_int();
+    let address = args.get(2).unwrap();
+
+    let fd = get_open_fd(this)?;
+    let ip = net_util::inet4_address(address)?;
+    net_util::bind(fd, ip, port as u16).map_err(net_util::bind_ex)?;
+
+    let port = if port == 0 {
+        net_util::local_address(fd).map_or(0, |(_, port)| port as i32)
+    } else {
+        port
+    };
+    net_util::put_int_field(this, *DS_IMPL_LOCAL_PORT, port);
+
+    Ok(None)
+}
+
+fn jvm_send(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let this = args.get(0).unwrap();
+    let packet = args.get(1).unwrap();
+
+    if packet.is_null() {
+        return Err(npe("packet"));
+    }
+    let fd = get_open_fd(this)?;
+    let connected = net_util::get_int_field(this, *DS_IMPL_CONNECTED) != 0;
+
+    let buf = Class::get_field_value2(packet.extract_ref(), *DP_BUF);
+    if buf.is_null() {
+        return Err(npe("packet buffer"));
+    }
+    let off = net_util::get_int_field(packet, *DP_OFFSET);
+    let len = net_util::get_int_field(packet, *DP_LENGTH);
+
+    let rf = buf.extract_ref();
+    let ary = rf.extract_type_array();
+    let ary = ary.extract_bytes();
+    let data = &ary[off as usize..(off + len) as usize];
+
+    //connected socket sends to the peer connected
+    let n = if connected {
+        unsafe {
+            libc::send(
+                fd,
+                data.as_ptr() as *const libc::c_void,
+                data.len(),
+                net_util::SEND_FLAGS,
+            )
+        }
+    } else {
+        let address = Class::get_field_value2(packet.extract_ref(), *DP_ADDRESS);
+        let ip = net_util::inet4_address(&address)?;
+        let port = net_util::get_int_field(packet, *DP_PORT);
+        let sa = net_util::to_sockaddr(ip, port as u16);
+        unsafe {
+            libc::sendto(
+                fd,
+                data.as_ptr() as *const libc::c_void,
+                data.len(),
+                net_util::SEND_FLAGS,
+                &sa as *const _ as *const libc::sockaddr,
+                std::mem::size_of::<libc::sockaddr_in>() as libc::socklen_t,
+            )
+        }
+    };
+
+    if n == -1 {
+        return Err(send_recv_ex(net_util::errno()));
+    }
+
+    Ok(None)
+}
+
+//address of the next packet put into 'i', its port returned
+fn jvm_peek(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let this = args.get(0).unwrap();
+    let address = args.get(1).unwrap();
+
+    if address.is_null() {
+        return Err(npe("Null address in peek()"));
+    }
+    let fd = get_open_fd(this)?;
+
+    let mut buf = [0u8; 1];
+    let (_, ip, port) = recv_from(this, fd, &mut buf, libc::MSG_PEEK)?;
+    if let IpAddr::V4(ip) = ip {
+        net_util::set_inet4_address(address, ip);
+    }
+
+    Ok(Some(Oop::new_int(port as i32)))
+}
+
+fn jvm_peekData(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let this = args.get(0).unwrap();
+    let packet = args.get(1).unwrap();
+
+    let port = receive(this, packet, libc::MSG_PEEK)?;
+    Ok(Some(Oop::new_int(port as i32)))
+}
+
+fn jvm_receive0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let this = args.get(0).unwrap();
+    let packet = args.get(1).unwrap();
+
+    receive(this, packet, 0)?;
+    Ok(None)
+}
+
+fn jvm_setTimeToLive(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let this = args.get(0).unwrap();
+    let ttl = args.get(1).unwrap().extract_int();
+
+    let fd = get_open_fd(this)?;
+    set_byte_option(fd, libc::IP_MULTICAST_TTL, ttl as u8)
+        .map_err(|e| net_util::new_socket_ex(&io_util::strerror(e)))?;
+
+    Ok(None)
+}
+
+fn jvm_getTimeToLive(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let this = args.get(0).unwrap();
+
+    let fd = get_open_fd(this)?;
+    match get_byte_option(fd, libc::IP_MULTICAST_TTL) {
+        Ok(v) => Ok(Some(Oop::new_int(v as i32))),
+        Err(e) => Err(net_util::new_socket_ex(&io_util::strerror(e))),
+    }
+}
+
+fn jvm_getTTL(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let this = args.get(0).unwrap();
+
+    let fd = get_open_fd(this)?;
+    match get_byte_option(fd, libc::IP_MULTICAST_TTL) {
+        Ok(v) => Ok(Some(Oop::new_int(v as i8 as i32))),
+        Err(e) => Err(net_util::new_socket_ex(&io_util::strerror(e))),
+    }
+}
+
+fn jvm_join(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    membership(args, libc::IP_ADD_MEMBERSHIP)
+}
+
+fn jvm_leave(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    membership(args, libc::IP_DROP_MEMBERSHIP)
+}
+
+//broadcast allowed as jdk
+fn jvm_datagramSocketCreate(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let this = args.get(0).unwrap();
+
+    let fd_obj = Class::get_field_value2(this.extract_ref(), *DS_IMPL_FD);
+    if fd_obj.is_null() {
+        return Err(net_util::new_socket_ex("Socket closed"));
+    }
+
+    let fd = net_util::socket(libc::SOCK_DGRAM)
+        .map_err(|e| net_util::new_socket_ex(&io_util::strerror(e)))?;
+    let _ = net_util::set_int_option(fd, libc::SOL_SOCKET, libc::SO_BROADCAST, 1);
+    io_util::set_fd(this, *DS_IMPL_FD, fd);
+
+    Ok(None)
+}
+
+fn jvm_datagramSocketClose(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let this = args.get(0).unwrap();
+
+    let fd = io_util::get_fd(this, *DS_IMPL_FD);
+    if fd != -1 {
+        io_util::set_fd(this, *DS_IMPL_FD, -1);
+        net_util::close(fd);
+    }
+
+    Ok(None)
+}
+
+//(opt, value), value Integer, Boolean, InetAddress or NetworkInterface
+fn jvm_socketSetOption(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let this = args.get(0).unwrap();
+    let opt = args.get(1).unwrap().extract_int();
+    let value = args.get(2).unwrap();
+
+    if value.is_null() {
+        return Err(npe("value argument"));
+    }
+    let fd = get_open_fd(this)?;
+
+    let r = match opt {
+        net_util::IP_MULTICAST_IF => {
+            let ip = net_util::inet4_address(value)?;
+            set_multicast_if(fd, ip)
+        }
+        net_util::IP_MULTICAST_IF2 => {
+            let ip = interface_ip(value).unwrap_or(Ipv4Addr::UNSPECIFIED);
+            set_multicast_if(fd, ip)
+        }
+        //true to disable the loopback
+        net_util::IP_MULTICAST_LOOP => {
+            let disable = net_util::int_or_bool(value) != 0;
+            set_byte_option(fd, libc::IP_MULTICAST_LOOP, !disable as u8)
+        }
+        net_util::SO_SNDBUF | net_util::SO_RCVBUF | net_util::IP_TOS => {
+            let (level, name) = net_util::map_option(opt).unwrap();
+            net_util::set_int_option(fd, level, name, net_util::int_or_bool(value))
+        }
+        _ => match net_util::map_option(opt) {
+            Some((level, name)) => {
+                let on = net_util::int_or_bool(value) != 0;
+                net_util::set_int_option(fd, level, name, on as i32)
+            }
+            None => return Err(net_util::new_socket_ex("Invalid option")),
+        },
+    };
+    r.map_err(|e| net_util::new_socket_ex(&io_util::strerror(e)))?;
+
+    Ok(None)
+}
+
+fn jvm_socketGetOption(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let this = args.get(0).unwrap();
+    let opt = args.get(1).unwrap().extract_int();
+
+    let fd = get_open_fd(this)?;
+    let to_ex = |e| net_util::new_socket_ex(&io_util::strerror(e));
+
+    let v = match opt {
+        net_util::SO_BINDADDR => match net_util::local_address(fd) {
+            Some((ip, _)) => net_util::new_inet_address(None, ip)?,
+            None => return Err(to_ex(net_util::errno())),
+        },
+        net_util::IP_MULTICAST_IF | net_util::IP_MULTICAST_IF2 => {
+            let ip = get_multicast_if(fd).map_err(to_ex)?;
+            net_util::new_inet_address(None, IpAddr::V4(ip))?
+        }
+        net_util::IP_MULTICAST_LOOP => {
+            let loop_on = get_byte_option(fd, libc::IP_MULTICAST_LOOP).map_err(to_ex)?;
+            box_bool(loop_on == 0)?
+        }
+        net_util::SO_SNDBUF | net_util::SO_RCVBUF | net_util::IP_TOS => {
+            let (level, name) = net_util::map_option(opt).unwrap();
+            let v = net_util::get_int_option(fd, level, name).map_err(to_ex)?;
+            reflect::box_value(&SignatureType::Int, Oop::new_int(v))?
+        }
+        _ => match net_util::map_option(opt) {
+            Some((level, name)) => {
+                let v = net_util::get_int_option(fd, level, name).map_err(to_ex)?;
+                box_bool(v != 0)?
+            }
+            None => return Err(net_util::new_socket_ex("Invalid option")),
+        },
+    };
+
+    Ok(Some(v))
+}
+
+fn jvm_connect0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let this = args.get(0).unwrap();
+    let address = args.get(1).unwrap();
+    let port = args.get(2).unwrap().extract_int();
+
+    let fd = get_open_fd(this)?;
+    let ip = net_util::inet4_address(address)?;
+    match net_util::connect(fd, ip, port as u16, 0) {
+        Ok(_) => Ok(None),
+        Err(e) => Err(net_util::connect_ex(e.unwrap_or(libc::ETIMEDOUT))),
+    }
+}
+
+//dissolve the association by connecting to AF_UNSPEC
+fn jvm_disconnect0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let this = args.get(0).unwrap();
+
+    let fd = io_util::get_fd(this, *DS_IMPL_FD);
+    if fd != -1 {
+        let mut sa: libc::sockaddr_in = unsafe { std::mem::zeroed() };
+        sa.sin_family = libc::AF_UNSPEC as libc::sa_family_t;
+        unsafe {
+            libc::connect(
+                fd,
+                &sa as *const _ as *const libc::sockaddr,
+                std::mem::size_of::<libc::sockaddr_in>() as libc::socklen_t,
+            );
+        }
+    }
+
+    Ok(None)
+}
+
+fn jvm_dataAvailable(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let this = args.get(0).unwrap();
+
+    let fd = get_open_fd(this)?;
+    match net_util::available(fd) {
+        Ok(n) => Ok(Some(Oop::new_int(n))),
+        Err(e) => Err(net_util::io_ex(e)),
+    }
+}
+
+fn get_open_fd(this: &Oop) -> Result<i32, Oop> {
+    match io_util::get_fd(this, *DS_IMPL_FD) {
+        -1 => Err(net_util::new_socket_ex("Socket closed")),
+        fd => Ok(fd),
+    }
+}
+
+fn npe(msg: &str) -> Oop {
+    crate::runtime::exception::new(cls_consts::J_NPE, Some(msg.to_string()))
+}
+
+//ICMP port unreachable reported by the next send or receive of a connected socket
+fn send_recv_ex(errno: i32) -> Oop {
+    match errno {
+        libc::ECONNREFUSED => {
+            net_util::new_net_ex(cls_consts::J_PORT_UNREACHABLE_EX, "ICMP Port Unreachable")
+        }
+        e => net_util::io_ex(e),
+    }
+}
+
+//(bytes, address, port) of the packet, waiting at most the SO_TIMEOUT of this
+fn recv_from(this: &Oop, fd: i32, buf: &mut [u8], flags: i32) -> Result<(usize, IpAddr, u16), Oop> {
+    let timeout = net_util::get_int_field(this, *DS_IMPL_TIMEOUT);
+    if timeout > 0 {
+        match net_util::wait(fd, libc::POLLIN, timeout) {
+            Ok(true) => (),
+            Ok(false) => {
+                let ex = net_util::new_net_ex(cls_consts::J_SOCKET_TIMEOUT_EX, "Receive timed out");
+                return Err(ex);
+            }
+            Err(e) => return Err(net_util::io_ex(e)),
+        }
+    }
+
+    loop {
+        let mut sa: libc::sockaddr_storage = unsafe { std::mem::zeroed() };
+        let mut len = std::mem::size_of::<libc::sockaddr_storage>() as libc::socklen_t;
+        let n = unsafe {
+            libc::recvfrom(
+                fd,
+                buf.as_mut_ptr() as *mut libc::c_void,
+                buf.len(),
+                flags,
+                &mut sa as *mut _ as *mut libc::sockaddr,
+                &mut len,
+            )
+        };
+        if n == -1 {
+            match net_util::errno() {
+                libc::EINTR => continue,
+                e => return Err(send_recv_ex(e)),
+            }
+        }
+
+        return match net_util::from_sockaddr(&sa) {
+            Some((ip, port)) => Ok((n as usize, ip, port)),
+            None => Err(net_util::new_socket_ex("Protocol family unavailable")),
+        };
+    }
+}
+
+//packet filled with the data, its length, address & port updated; the port returned
+fn receive(this: &Oop, packet: &Oop, flags: i32) -> Result<u16, Oop> {
+    if packet.is_null() {
+        return Err(npe("packet"));
+    }
+    let fd = get_open_fd(this)?;
+
+    let buf = Class::get_field_value2(packet.extract_ref(), *DP_BUF);
+    if buf.is_null() {
+        return Err(npe("packet buffer"));
+    }
+    let off = net_util::get_int_field(packet, *DP_OFFSET);
+    let buf_len = net_util::get_int_field(packet, *DP_BUF_LENGTH);
+
+    let (n, ip, port) = {
+        let rf = buf.extract_ref();
+        let ary = rf.extract_mut_type_array();
+        let ary = ary.extract_mut_bytes();
+        let data = &mut ary[off as usize..(off + buf_len) as usize];
+        recv_from(this, fd, data, flags)?
+    };
+
+    //larger packet truncated
+    let n = n.min(buf_len as usize);
+    let address = net_util::new_inet_address(None, ip)?;
+    let rf = packet.extract_ref();
+    Class::put_field_value2(rf.clone(), *DP_ADDRESS, address);
+    Class::put_field_value2(rf.clone(), *DP_PORT, Oop::new_int(port as i32));
+    Class::put_field_value2(rf, *DP_LENGTH, Oop::new_int(n as i32));
+
+    Ok(port)
+}
+
+//(this, group, interface), interface null for the default
+fn membership(args: &[Oop], name: i32) -> JNIResult {
+    let this = args.get(0).unwrap();
+    let group = args.get(1).unwrap();
+    let interface = args.get(2).unwrap();
+
+    let fd = get_open_fd(this)?;
+    let group = net_util::inet4_address(group)?;
+    let interface = if interface.is_null() {
+        get_multicast_if(fd).unwrap_or(Ipv4Addr::UNSPECIFIED)
+    } else {
+        interface_ip(interface).unwrap_or(Ipv4Addr::UNSPECIFIED)
+    };
+
+    let mreq = libc::ip_mreq {
+        imr_multiaddr: libc::in_addr {
+            s_addr: u32::from(group).to_be(),
+        },
+        imr_interface: libc::in_addr {
+            s_addr: u32::from(interface).to_be(),
+        },
+    };
+    let r = unsafe {
+        libc::setsockopt(
+            fd,
+            libc::IPPROTO_IP,
+            name,
+            &mreq as *const _ as *const libc::c_void,
+            std::mem::size_of::<libc::ip_mreq>() as libc::socklen_t,
+        )
+    };
+    if r == -1 {
+        let e = net_util::errno();
+        let msg = if name == libc::IP_ADD_MEMBERSHIP {
+            format!("Join failed: {}", io_util::strerror(e))
+        } else {
+            format!("Leave failed: {}", io_util::strerror(e))
+        };
+        return Err(net_util::new_socket_ex(&msg));
+    }
+
+    Ok(None)
+}
+
+//the first IPv4 address of a NetworkInterface
+fn interface_ip(ni: &Oop) -> Option<Ipv4Addr> {
+    let addrs = Class::get_field_value2(ni.extract_ref(), *NI_ADDRS);
+    if addrs.is_null() {
+        return None;
+    }
+
+    let rf = addrs.extract_ref();
+    let ary = rf.extract_array();
+    ary.elements
+        .iter()
+        .find_map(|ia| match net_util::inet_address_ip(ia) {
+            Some(IpAddr::V4(ip)) => Some(ip),
+            _ => None,
+        })
+}
+
+fn box_bool(v: bool) -> Result<Oop, Oop> {
+    reflect::box_value(&SignatureType::Boolean, Oop::new_int(v as i32))
+}
+
+fn set_multicast_if(fd: i32, ip: Ipv4Addr) -> Result<(), i32> {
+    let v = libc::in_addr {
+        s_addr: u32::from(ip).to_be(),
+    };
+    let r = unsafe {
+        libc::setsockopt(
+            fd,
+            libc::IPPROTO_IP,
+            libc::IP_MULTICAST_IF,
+            &v as *const _ as *const libc::c_void,
+            std::mem::size_of::<libc::in_addr>() as libc::socklen_t,
+        )
+    };
+    if r == -1 {
+        Err(net_util::errno())
+    } else {
+        Ok(())
+    }
+}
+
+fn get_multicast_if(fd: i32) -> Result<Ipv4Addr, i32> {
+    let mut v: libc::in_addr = unsafe { std::mem::zeroed() };
+    let mut len = std::mem::size_of::<libc::in_addr>() as libc::socklen_t;
+    let r = unsafe {
+        libc::getsockopt(
+            fd,
+            libc::IPPROTO_IP,
+            libc::IP_MULTICAST_IF,
+            &mut v as *mut _ as *mut libc::c_void,
+            &mut len,
+        )
+    };
+    if r == -1 {
+        Err(net_util::errno())
+    } else {
+        Ok(Ipv4Addr::from(u32::from_be(v.s_addr)))
+    }
+}
+
+//IP_MULTICAST_TTL & IP_MULTICAST_LOOP are u_char on bsd, linux takes both
+fn set_byte_option(fd: i32, name: i32, v: u8) -> Result<(), i32> {
+    let r = unsafe {
+        libc::setsockopt(
+            fd,
+            libc::IPPROTO_IP,
+            name,
+            &v as *const u8 as *const libc::c_void,
+            1,
+        )
+    };
+    if r == -1 {
+        Err(net_util::errno())
+    } else {
+        Ok(())
+    }
+}
+
+fn get_byte_option(fd: i32, name: i32) -> Result<u8, i32> {
+    let mut v = 0u8;
+    let mut len: libc::socklen_t = 1;
+    let r = unsafe {
+        libc::getsockopt(
+            fd,
+            libc::IPPROTO_IP,
+            name,
+            &mut v as *mut u8 as *mut libc::c_void,
+            &mut len,
+        )
+    };
+    if r == -1 {
+        Err(net_util::errno())
+    } else {
+        Ok(v)
+    }
+}
@@ -0,0 +1,392 @@
+#![allow(non_snake_case)]
+
+use crate::native::common::{io_util, net_util};
+use crate::native::{new_fn, JNIEnv, JNINativeMethod, JNIResult};
+use crate::oop::{Class, Oop};
+use crate::runtime;
+use classfile::consts as cls_consts;
+
+lazy_static! {
+    //SocketImpl fd, address, port, localport, serverSocket
+    static ref SOCKET_IMPL_FD: usize = io_util::stream_fd_offset(b"java/net/SocketImpl");
+    static ref SOCKET_IMPL_ADDRESS: usize =
+        net_util::field_offset(b"java/net/SocketImpl", "address", "Ljava/net/InetAddress;");
+    static ref SOCKET_IMPL_PORT: usize = net_util::field_offset(b"java/net/SocketImpl", "port", "I");
+    static ref SOCKET_IMPL_LOCALPORT: usize =
+        net_util::field_offset(b"java/net/SocketImpl", "localport", "I");
+    static ref SOCKET_IMPL_SERVER_SOCKET: usize = net_util::field_offset(
+        b"java/net/SocketImpl",
+        "serverSocket",
+        "Ljava/net/ServerSocket;"
+    );
+    //AbstractPlainSocketImpl timeout, the millis of SO_TIMEOUT
+    static ref SOCKET_IMPL_TIMEOUT: usize =
+        net_util::field_offset(b"java/net/AbstractPlainSocketImpl", "timeout", "I");
+    //InetAddressContainer addr, out param of SO_BINDADDR
+    static ref IA_CONTAINER_ADDR: usize =
+        net_util::field_offset(b"java/net/InetAddressContainer", "addr", "Ljava/net/InetAddress;");
+}
+
+//SocketOptions.SO_TIMEOUT, kept by AbstractPlainSocketImpl
+const SO_TIMEOUT: i32 = 0x1006;
+
+pub fn get_native_methods() -> Vec<JNINativeMethod> {
+    vec![
+        new_fn("initProto", "()V", Box::new(jvm_initProto)),
+        new_fn("socketCreate", "(Z)V", Box::new(jvm_socketCreate)),
+        new_fn(
+            "socketConnect",
+            "(Ljava/net/InetAddress;II)V",
+            Box::new(jvm_socketConnect),
+        ),
+        new_fn(
+            "socketBind",
+            "(Ljava/net/InetAddress;I)V",
+            Box::new(jvm_socketBind),
+        ),
+        new_fn("socketListen", "(I)V", Box::new(jvm_socketListen)),
+        new_fn(
+            "socketAccept",
+            "(Ljava/net/SocketImpl;)V",
+            Box::new(jvm_socketAccept),
+        ),
+        new_fn("socketAvailable", "()I", Box::new(jvm_socketAvailable)),
+        new_fn("socketClose0", "(Z)V", Box::new(jvm_socketClose0)),
+        new_fn("socketShutdown", "(I)V", Box::new(jvm_socketShutdown)),
+        new_fn(
+            "socketSetOption",
+            "(IZLjava/lang/Object;)V",
+            Box::new(jvm_socketSetOption),
+        ),
+        new_fn(
+            "socketSetOption0",
+            "(IZLjava/lang/Object;)V",
+            Box::new(jvm_socketSetOption),
+        ),
+        new_fn(
+            "socketGetOption",
+            "(ILjava/lang/Object;)I",
+            Box::new(jvm_socketGetOption),
+        ),
+        new_fn(
+            "socketSendUrgentData",
+            "(I)V",
+            Box::new(jvm_socketSendUrgentData),
+        ),
+    ]
+}
+
+fn jvm_initProto(_env: JNIEnv, _args: &[Oop]) -> JNIResult {
+    Ok(None)
+}
+
+fn jvm_socketCreate(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let this = args.get(0).unwrap();
+    let stream = args.get(1).unwrap().extract_int() != 0;
+
+    let tp = if stream {
+        libc::SOCK_STREAM
+    } else {
+        libc::SOCK_DGRAM
+    };
+    let fd = net_util::socket(tp).map_err(|e| net_util::new_socket_ex(&io_util::strerror(e)))?;
+
+    //a server socket rebinds the port in TIME_WAIT, as jdk
+    let server_socket = Class::get_field_value2(this.extract_ref(), *SOCKET_IMPL_SERVER_SOCKET);
+    if !server_socket.is_null() {
+        let _ = net_util::set_int_option(fd, libc::SOL_SOCKET, libc::SO_REUSEADDR, 1);
+    }
+
+    io_util::set_fd(this, *SOCKET_IMPL_FD, fd);
+
+    Ok(None)
+}
+
+fn jvm_socketConnect(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let this = args.get(0).unwrap();
+    let address = args.get(1).unwrap();
+    let port = args.get(2).unwrap().extract_int();
+    let timeout = args.get(3).unwrap().extract_int();
+
+    let fd = get_open_fd(this)?;
+    let ip = net_util::inet4_address(address)?;
+
+    match net_util::connect(fd, ip, port as u16, timeout) {
+        Ok(_) => (),
+        Err(Some(e)) => return Err(net_util::connect_ex(e)),
+        Err(None) => {
+            let ex = net_util::new_net_ex(cls_consts::J_SOCKET_TIMEOUT_EX, "connect timed out");
+            return Err(ex);
+        }
+    }
+
+    let rf = this.extract_ref();
+    Class::put_field_value2(rf.clone(), *SOCKET_IMPL_ADDRESS, address.clone());
+    Class::put_field_value2(rf, *SOCKET_IMPL_PORT, Oop::new_int(port));
+    if net_util::get_int_field(this, *SOCKET_IMPL_LOCALPORT) == 0 {
+        set_local_port(this, fd);
+    }
+
+    Ok(None)
+}
+
+fn jvm_socketBind(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let this = args.get(0).unwrap();
+    let address = args.get(1).unwrap();
+    let port = args.get(2).unwrap().extract_int();
+
+    let fd = get_open_fd(this)?;
+    let ip = net_util::inet4_address(address)?;
+    net_util::bind(fd, ip, port as u16).map_err(net_util::bind_ex)?;
+
+    Class::put_field_value2(this.extract_ref(), *SOCKET_IMPL_ADDRESS, address.clone());
+    if port == 0 {
+        set_local_port(this, fd);
+    } else {
+        net_util::put_int_field(this, *SOCKET_IMPL_LOCALPORT, port);
+    }
+
+    Ok(None)
+}
+
+fn jvm_socketListen(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let this = args.get(0).unwrap();
+    let count = args.get(1).unwrap().extract_int();
+
+    let fd = get_open_fd(this)?;
+    let count = if count < 1 { 50 } else { count };
+    if unsafe { libc::listen(fd, count) } == -1 {
+        return Err(net_util::new_socket_ex(&io_util::strerror(
+            net_util::errno(),
+        )));
+    }
+
+    Ok(None)
+}
+
+//accepted into the SocketImpl 's', waiting at most 'timeout' of this
+fn jvm_socketAccept(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let this = args.get(0).unwrap();
+    let s = args.get(1).unwrap();
+
+    if s.is_null() {
+        let ex = runtime::exception::new(cls_consts::J_NPE, Some("socket is null".to_string()));
+        return Err(ex);
+    }
+
+    let fd = get_open_fd(this)?;
+    let timeout = net_util::get_int_field(this, *SOCKET_IMPL_TIMEOUT);
+
+    let (new_fd, sa) = loop {
+        if timeout > 0 {
+            match net_util::wait(fd, libc::POLLIN, timeout) {
+                Ok(true) => (),
+                Ok(false) => {
+                    let ex =
+                        net_util::new_net_ex(cls_consts::J_SOCKET_TIMEOUT_EX, "Accept timed out");
+                    return Err(ex);
+                }
+                Err(e) => return Err(net_util::io_ex(e)),
+            }
+        }
+
+        let mut sa: libc::sockaddr_storage = unsafe { std::mem::zeroed() };
+        let mut len = std::mem::size_of::<libc::sockaddr_storage>() as libc::socklen_t;
+        let new_fd =
+            unsafe { libc::accept(fd, &mut sa as *mut _ as *mut libc::sockaddr, &mut len) };
+        if new_fd >= 0 {
+            break (new_fd, sa);
+        }
+
+        match net_util::errno() {
+            //connection gone before accepted, or woken without one
+            libc::EINTR | libc::ECONNABORTED | libc::EAGAIN => continue,
+            //listening fd shutdown by close
+            libc::EINVAL | libc::EBADF => return Err(net_util::new_socket_ex("Socket closed")),
+            e => return Err(net_util::io_ex(e)),
+        }
+    };
+
+    unsafe {
+        libc::fcntl(new_fd, libc::F_SETFD, libc::FD_CLOEXEC);
+    }
+    #[cfg(any(target_os = "macos", target_os = "ios", target_os = "freebsd"))]
+    {
+        let _ = net_util::set_int_option(new_fd, libc::SOL_SOCKET, libc::SO_NOSIGPIPE, 1);
+    }
+
+    let (ip, port) = match net_util::from_sockaddr(&sa) {
+        Some(v) => v,
+        None => {
+            net_util::close(new_fd);
+            return Err(net_util::new_socket_ex("Protocol family unavailable"));
+        }
+    };
+
+    io_util::set_fd(s, *SOCKET_IMPL_FD, new_fd);
+    let address = net_util::new_inet_address(None, ip)?;
+    let rf = s.extract_ref();
+    Class::put_field_value2(rf.clone(), *SOCKET_IMPL_ADDRESS, address);
+    Class::put_field_value2(rf.clone(), *SOCKET_IMPL_PORT, Oop::new_int(port as i32));
+    let local_port = net_util::get_int_field(this, *SOCKET_IMPL_LOCALPORT);
+    Class::put_field_value2(rf, *SOCKET_IMPL_LOCALPORT, Oop::new_int(local_port));
+
+    Ok(None)
+}
+
+fn jvm_socketAvailable(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let this = args.get(0).unwrap();
+    let fd = get_open_fd(this)?;
+
+    match net_util::available(fd) {
+        Ok(n) => Ok(Some(Oop::new_int(n))),
+        Err(e) => Err(net_util::io_ex(e)),
+    }
+}
+
+//deferred close shuts the socket down only, waking the threads blocked on it;
+//the fd closed when no longer used
+fn jvm_socketClose0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let this = args.get(0).unwrap();
+    let use_deferred_close = args.get(1).unwrap().extract_int() != 0;
+
+    let fd_obj = Class::get_field_value2(this.extract_ref(), *SOCKET_IMPL_FD);
+    if fd_obj.is_null() {
+        return Err(net_util::new_socket_ex("socket already closed"));
+    }
+
+    let fd = io_util::file_descriptor_fd(&fd_obj);
+    if fd != -1 {
+        if use_deferred_close {
+            unsafe {
+                libc::shutdown(fd, libc::SHUT_RDWR);
+            }
+        } else {
+            io_util::set_fd(this, *SOCKET_IMPL_FD, -1);
+            net_util::close(fd);
+        }
+    }
+
+    Ok(None)
+}
+
+fn jvm_socketShutdown(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let this = args.get(0).unwrap();
+    let howto = args.get(1).unwrap().extract_int();
+
+    //SocketImpl.SHUT_RD & SHUT_WR same as os
+    let fd = get_open_fd(this)?;
+    unsafe {
+        libc::shutdown(fd, howto);
+    }
+
+    Ok(None)
+}
+
+//(cmd, on, value), value an Integer for the int options
+fn jvm_socketSetOption(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let this = args.get(0).unwrap();
+    let cmd = args.get(1).unwrap().extract_int();
+    let on = args.get(2).unwrap().extract_int() != 0;
+    let value = args.get(3).unwrap();
+
+    let fd = get_open_fd(this)?;
+
+    let r = match cmd {
+        SO_TIMEOUT => Ok(()),
+        net_util::SO_LINGER => {
+            let secs = if on { net_util::int_or_bool(value) } else { 0 };
+            net_util::set_linger(fd, on, secs)
+        }
+        net_util::SO_SNDBUF | net_util::SO_RCVBUF | net_util::IP_TOS => {
+            let (level, name) = net_util::map_option(cmd).unwrap();
+            net_util::set_int_option(fd, level, name, net_util::int_or_bool(value))
+        }
+        _ => match net_util::map_option(cmd) {
+            Some((level, name)) => net_util::set_int_option(fd, level, name, on as i32),
+            None => {
+                let ex = net_util::new_socket_ex("Invalid option");
+                return Err(ex);
+            }
+        },
+    };
+    r.map_err(|e| net_util::new_socket_ex(&io_util::strerror(e)))?;
+
+    Ok(None)
+}
+
+//(opt, iaContainerObj), booleans as 1 or -1; SO_BINDADDR put into the container
+fn jvm_socketGetOption(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let this = args.get(0).unwrap();
+    let opt = args.get(1).unwrap().extract_int();
+    let container = args.get(2).unwrap();
+
+    let fd = get_open_fd(this)?;
+
+    let r = match opt {
+        net_util::SO_BINDADDR => {
+            let ip = match net_util::local_address(fd) {
+                Some((ip, _)) => ip,
+                None => {
+                    let msg = io_util::strerror(net_util::errno());
+                    return Err(net_util::new_socket_ex(&msg));
+                }
+            };
+            let address = net_util::new_inet_address(None, ip)?;
+            Class::put_field_value2(container.extract_ref(), *IA_CONTAINER_ADDR, address);
+            Ok(0)
+        }
+        net_util::SO_LINGER => net_util::get_linger(fd),
+        net_util::SO_SNDBUF | net_util::SO_RCVBUF | net_util::IP_TOS => {
+            let (level, name) = net_util::map_option(opt).unwrap();
+            net_util::get_int_option(fd, level, name)
+        }
+        _ => match net_util::map_option(opt) {
+            Some((level, name)) => {
+                net_util::get_int_option(fd, level, name).map(|v| if v != 0 { 1 } else { -1 })
+            }
+            None => {
+                let ex = net_util::new_socket_ex("Invalid option");
+                return Err(ex);
+            }
+        },
+    };
+
+    match r {
+        Ok(v) => Ok(Some(Oop::new_int(v))),
+        Err(e) => Err(net_util::new_socket_ex(&io_util::strerror(e))),
+    }
+}
+
+fn jvm_socketSendUrgentData(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let this = args.get(0).unwrap();
+    let data = args.get(1).unwrap().extract_int() as u8;
+
+    let fd = get_open_fd(this)?;
+    let n = unsafe {
+        libc::send(
+            fd,
+            &data as *const u8 as *const libc::c_void,
+            1,
+            libc::MSG_OOB | net_util::SEND_FLAGS,
+        )
+    };
+    if n == -1 {
+        return Err(net_util::io_ex(net_util::errno()));
+    }
+
+    Ok(None)
+}
+
+fn get_open_fd(this: &Oop) -> Result<i32, Oop> {
+    match io_util::get_fd(this, *SOCKET_IMPL_FD) {
+        -1 => Err(net_util::new_socket_ex("Socket closed")),
+        fd => Ok(fd),
+    }
+}
+
+fn set_local_port(this: &Oop, fd: i32) {
+    if let Some((_, port)) = net_util::local_address(fd) {
+        net_util::put_int_field(this, *SOCKET_IMPL_LOCALPORT, port as i32);
+    }
+}
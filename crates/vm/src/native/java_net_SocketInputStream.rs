@@ -0,0 +1,66 @@
+#![allow(non_snake_case)]
+
+use crate::native::common::{io_util, net_util};
+use crate::native::{new_fn, JNIEnv, JNINativeMethod, JNIResult};
+use crate::oop::Oop;
+use classfile::consts as cls_consts;
+
+pub fn get_native_methods() -> Vec<JNINativeMethod> {
+    vec![
+        new_fn("init", "()V", Box::new(jvm_init)),
+        new_fn(
+            "socketRead0",
+            "(Ljava/io/FileDescriptor;[BIII)I",
+            Box::new(jvm_socketRead0),
+        ),
+    ]
+}
+
+fn jvm_init(_env: JNIEnv, _args: &[Oop]) -> JNIResult {
+    Ok(None)
+}
+
+//(fd, b, off, len, timeout), -1 at end of stream
+fn jvm_socketRead0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let fd_obj = args.get(1).unwrap();
+    let byte_ary = args.get(2).unwrap();
+    let off = args.get(3).unwrap().extract_int();
+    let len = args.get(4).unwrap().extract_int();
+    let timeout = args.get(5).unwrap().extract_int();
+
+    let fd = if fd_obj.is_null() {
+        -1
+    } else {
+        io_util::file_descriptor_fd(fd_obj)
+    };
+    if fd == -1 {
+        return Err(net_util::new_socket_ex("Socket closed"));
+    }
+    io_util::check_bounds(byte_ary, off, len)?;
+
+    if timeout > 0 {
+        match net_util::wait(fd, libc::POLLIN, timeout) {
+            Ok(true) => (),
+            Ok(false) => {
+                let ex = net_util::new_net_ex(cls_consts::J_SOCKET_TIMEOUT_EX, "Read timed out");
+                return Err(ex);
+            }
+            Err(e) => return Err(net_util::io_ex(e)),
+        }
+    }
+
+    let rf = byte_ary.extract_ref();
+    let ary = rf.extract_mut_type_array();
+    let ary = ary.extract_mut_bytes();
+    let buf = &mut ary[off as usize..(off + len) as usize];
+
+    loop {
+        let n = unsafe { libc::recv(fd, buf.as_mut_ptr() as *mut libc::c_void, buf.len(), 0) };
+        match n {
+            -1 if net_util::errno() == libc::EINTR => continue,
+            -1 => return Err(net_util::io_ex(net_util::errno())),
+            0 if !buf.is_empty() => return Ok(Some(Oop::new_int(-1))),
+            n => return Ok(Some(Oop::new_int(n as i32))),
+        }
+    }
+}
@@ -0,0 +1,64 @@
+#![allow(non_snake_case)]
+
+use crate::native::common::{io_util, net_util};
+use crate::native::{new_fn, JNIEnv, JNINativeMethod, JNIResult};
+use crate::oop::Oop;
+
+pub fn get_native_methods() -> Vec<JNINativeMethod> {
+    vec![
+        new_fn("init", "()V", Box::new(jvm_init)),
+        new_fn(
+            "socketWrite0",
+            "(Ljava/io/FileDescriptor;[BII)V",
+            Box::new(jvm_socketWrite0),
+        ),
+    ]
+}
+
+fn jvm_init(_env: JNIEnv, _args: &[Oop]) -> JNIResult {
+    Ok(None)
+}
+
+//(fd, b, off, len), all written
+fn jvm_socketWrite0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let fd_obj = args.get(1).unwrap();
+    let byte_ary = args.get(2).unwrap();
+    let off = args.get(3).unwrap().extract_int();
+    let len = args.get(4).unwrap().extract_int();
+
+    let fd = if fd_obj.is_null() {
+        -1
+    } else {
+        io_util::file_descriptor_fd(fd_obj)
+    };
+    if fd == -1 {
+        return Err(net_util::new_socket_ex("Socket closed"));
+    }
+    io_util::check_bounds(byte_ary, off, len)?;
+
+    let rf = byte_ary.extract_ref();
+    let ary = rf.extract_type_array();
+    let ary = ary.extract_bytes();
+    let mut buf = &ary[off as usize..(off + len) as usize];
+
+    while !buf.is_empty() {
+        let n = unsafe {
+            libc::send(
+                fd,
+                buf.as_ptr() as *const libc::c_void,
+                buf.len(),
+                net_util::SEND_FLAGS,
+            )
+        };
+        if n == -1 {
+            match net_util::errno() {
+                libc::EINTR => continue,
+                libc::EPIPE => return Err(net_util::new_socket_ex("Broken pipe")),
+                e => return Err(net_util::io_ex(e)),
+            }
+        }
+        buf = &buf[n as usize..];
+    }
+
+    Ok(None)
+}
@@ -0,0 +1,161 @@
+#![allow(non_snake_case)]
+
+use crate::native::{new_fn, JNIEnv, JNINativeMethod, JNIResult};
+use crate::oop::{Oop, TypeArrayDesc};
+
+/*
+Bulk copies between arrays and raw memory with the bytes swapped, for the
+buffers not in the native order.
+
+Positions are byte offsets, arrayBaseOffset 0; the array of a Short copy may be
+a char[], Int a float[], Long a double[].
+*/
+
+pub fn get_native_methods() -> Vec<JNINativeMethod> {
+    vec![
+        new_fn(
+            "copyFromShortArray",
+            "(Ljava/lang/Object;JJJ)V",
+            Box::new(jvm_copyFromShortArray),
+        ),
+        new_fn(
+            "copyToShortArray",
+            "(JLjava/lang/Object;JJ)V",
+            Box::new(jvm_copyToShortArray),
+        ),
+        new_fn(
+            "copyFromIntArray",
+            "(Ljava/lang/Object;JJJ)V",
+            Box::new(jvm_copyFromIntArray),
+        ),
+        new_fn(
+            "copyToIntArray",
+            "(JLjava/lang/Object;JJ)V",
+            Box::new(jvm_copyToIntArray),
+        ),
+        new_fn(
+            "copyFromLongArray",
+            "(Ljava/lang/Object;JJJ)V",
+            Box::new(jvm_copyFromLongArray),
+        ),
+        new_fn(
+            "copyToLongArray",
+            "(JLjava/lang/Object;JJ)V",
+            Box::new(jvm_copyToLongArray),
+        ),
+    ]
+}
+
+fn jvm_copyFromShortArray(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    copy_from_array(args, 2);
+    Ok(None)
+}
+
+fn jvm_copyToShortArray(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    copy_to_array(args, 2);
+    Ok(None)
+}
+
+fn jvm_copyFromIntArray(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    copy_from_array(args, 4);
+    Ok(None)
+}
+
+fn jvm_copyToIntArray(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    copy_to_array(args, 4);
+    Ok(None)
+}
+
+fn jvm_copyFromLongArray(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    copy_from_array(args, 8);
+    Ok(None)
+}
+
+fn jvm_copyToLongArray(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    copy_to_array(args, 8);
+    Ok(None)
+}
+
+//(src, srcPos, dstAddr, length)
+fn copy_from_array(args: &[Oop], size: usize) {
+    let src = args.get(0).unwrap();
+    let src_pos = args.get(1).unwrap().extract_long() as usize;
+    let dst_addr = args.get(2).unwrap().extract_long() as *mut u8;
+    let length = args.get(3).unwrap().extract_long() as usize;
+
+    let rf = src.extract_ref();
+    let ary = rf.extract_type_array();
+    let (start, n) = (src_pos / size, length / size);
+    for i in 0..n {
+        let v = swap(get_bits(ary, start + i), size).to_ne_bytes();
+        unsafe {
+            std::ptr::copy_nonoverlapping(v.as_ptr(), dst_addr.add(i * size), size);
+        }
+    }
+}
+
+//(srcAddr, dst, dstPos, length)
+fn copy_to_array(args: &[Oop], size: usize) {
+    let src_addr = args.get(0).unwrap().extract_long() as *const u8;
+    let dst = args.get(1).unwrap();
+    let dst_pos = args.get(2).unwrap().extract_long() as usize;
+    let length = args.get(3).unwrap().extract_long() as usize;
+
+    let rf = dst.extract_ref();
+    let ary = rf.extract_mut_type_array();
+    let (start, n) = (dst_pos / size, length / size);
+    for i in 0..n {
+        let mut v = [0u8; 8];
+        unsafe {
+            std::ptr::copy_nonoverlapping(src_addr.add(i * size), v.as_mut_ptr(), size);
+        }
+        let v = swap(u64::from_ne_bytes(v), size);
+        set_bits(ary, start + i, v);
+    }
+}
+
+//the low 'size' bytes reversed, kept in the low bytes
+fn swap(v: u64, size: usize) -> u64 {
+    match size {
+        2 => (v as u16).swap_bytes() as u64,
+        4 => (v as u32).swap_bytes() as u64,
+        _ => v.swap_bytes(),
+    }
+}
+
+//bits of an element, in the low bytes
+fn get_bits(ary: &TypeArrayDesc, i: usize) -> u64 {
+    match ary {
+        TypeArrayDesc::Short(ary) => ary[i] as u16 as u64,
+        TypeArrayDesc::Char(ary) => ary[i] as u64,
+        TypeArrayDesc::Int(ary) => ary[i] as u32 as u64,
+        TypeArrayDesc::Float(ary) => ary[i].to_bits() as u64,
+        TypeArrayDesc::Long(ary) => ary[i] as u64,
+        TypeArrayDesc::Double(ary) => ary[i].to_bits(),
+        _ => unreachable!(),
+    }
+}
+
+fn set_bits(ary: &mut TypeArrayDesc, i: usize, v: u64) {
+    match ary {
+        TypeArrayDesc::Short(ary) => ary[i] = v as i16,
+        TypeArrayDesc::Char(ary) => ary[i] = v as u16,
+        TypeArrayDesc::Int(ary) => ary[i] = v as i32,
+        TypeArrayDesc::Float(ary) => ary[i] = f32::from_bits(v as u32),
+        TypeArrayDesc::Long(ary) => ary[i] = v as i64,
+        TypeArrayDesc::Double(ary) => ary[i] = f64::from_bits(v),
+        _ => unreachable!(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn t_swap() {
+        assert_eq!(swap(0x0102, 2), 0x0201);
+        assert_eq!(swap(0x0102_0304, 4), 0x0403_0201);
+        assert_eq!(swap(0x0102_0304_0506_0708, 8), 0x0807_0605_0403_0201);
+    }
+}
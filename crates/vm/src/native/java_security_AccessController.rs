@@ -51,7 +51,7 @@ fn jvm_doPrivileged(_env: JNIEnv, args: &[Oop]) -> JNIResult {
     jc.invoke(Some(&area), false);
 
     if !thread::is_meet_ex() {
-        let mut stack = area.stack.borrow_mut();
+        let stack = &area.stack;
         let r = stack.pop_ref();
         Ok(Some(r))
     } else {
@@ -7,6 +7,7 @@ pub fn get_native_methods() -> Vec<JNINativeMethod> {
     vec![new_fn("VMSupportsCS8", "()Z", Box::new(jvm_VMSupportsCS8))]
 }
 
+//compareAndSwapLong is atomic, see Class::compare_and_swap_field_value2
 fn jvm_VMSupportsCS8(_env: JNIEnv, _args: &[Oop]) -> JNIResult {
-    Ok(Some(Oop::new_int(0)))
+    Ok(Some(Oop::new_int(1)))
 }
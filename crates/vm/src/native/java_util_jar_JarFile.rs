@@ -0,0 +1,49 @@
+#![allow(non_snake_case)]
+
+use crate::native::java_util_zip_ZipFile::ZipHandle;
+use crate::native::{new_fn, JNIEnv, JNINativeMethod, JNIResult};
+use crate::new_br;
+use crate::oop::{self, Class, Oop};
+use crate::runtime::require_class3;
+use crate::util;
+
+pub fn get_native_methods() -> Vec<JNINativeMethod> {
+    vec![new_fn(
+        "getMetaInfEntryNames",
+        "()[Ljava/lang/String;",
+        Box::new(jvm_getMetaInfEntryNames),
+    )]
+}
+
+//names of entries in "META-INF/", ignoring case; null if none
+fn jvm_getMetaInfEntryNames(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let this = args.get(0).unwrap();
+
+    let cls = require_class3(None, b"java/util/zip/ZipFile").unwrap();
+    let fid = {
+        let cls = cls.get_class();
+        cls.get_field_id(&new_br("jzfile"), &new_br("J"), false)
+    };
+    let addr = Class::get_field_value(this.extract_ref(), fid).extract_long();
+    let zip = ZipHandle::from_addr(addr);
+
+    let names: Vec<Oop> = zip
+        .jar
+        .entries()
+        .iter()
+        .filter(|e| is_meta_name(&e.name))
+        .map(|e| util::oop::new_java_lang_string2(&e.name))
+        .collect();
+
+    if names.is_empty() {
+        Ok(Some(oop::consts::get_null()))
+    } else {
+        let ary_cls = require_class3(None, b"[Ljava/lang/String;").unwrap();
+        Ok(Some(Oop::new_ref_ary2(ary_cls, names)))
+    }
+}
+
+fn is_meta_name(name: &str) -> bool {
+    const META_INF: &str = "META-INF/";
+    name.len() >= META_INF.len() && name[..META_INF.len()].eq_ignore_ascii_case(META_INF)
+}
@@ -0,0 +1,57 @@
+#![allow(non_snake_case)]
+
+use crate::native::{new_fn, JNIEnv, JNINativeMethod, JNIResult};
+use crate::oop::Oop;
+use adler32::RollingAdler32;
+
+pub fn get_native_methods() -> Vec<JNINativeMethod> {
+    vec![
+        new_fn("update", "(II)I", Box::new(jvm_update)),
+        new_fn("updateBytes", "(I[BII)I", Box::new(jvm_updateBytes)),
+        new_fn(
+            "updateByteBuffer",
+            "(IJII)I",
+            Box::new(jvm_updateByteBuffer),
+        ),
+    ]
+}
+
+fn jvm_update(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let adler = args.get(0).unwrap().extract_int();
+    let b = args.get(1).unwrap().extract_int();
+    let v = update(adler, &[b as u8]);
+    Ok(Some(Oop::new_int(v)))
+}
+
+fn jvm_updateBytes(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let adler = args.get(0).unwrap().extract_int();
+    let b = args.get(1).unwrap();
+    let off = args.get(2).unwrap().extract_int() as usize;
+    let len = args.get(3).unwrap().extract_int() as usize;
+
+    let rf = b.extract_ref();
+    let ary = rf.extract_type_array();
+    let ary = ary.extract_bytes();
+    let v = update(adler, &ary[off..off + len]);
+
+    Ok(Some(Oop::new_int(v)))
+}
+
+//direct buffer, address of memory allocated by Unsafe
+fn jvm_updateByteBuffer(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let adler = args.get(0).unwrap().extract_int();
+    let addr = args.get(1).unwrap().extract_long();
+    let off = args.get(2).unwrap().extract_int() as usize;
+    let len = args.get(3).unwrap().extract_int() as usize;
+
+    let buf = unsafe { std::slice::from_raw_parts((addr as *const u8).add(off), len) };
+    let v = update(adler, buf);
+
+    Ok(Some(Oop::new_int(v)))
+}
+
+fn update(adler: i32, buf: &[u8]) -> i32 {
+    let mut h = RollingAdler32::from_value(adler as u32);
+    h.update_buffer(buf);
+    h.hash() as i32
+}
@@ -0,0 +1,72 @@
+#![allow(non_snake_case)]
+
+use crate::native::{new_fn, JNIEnv, JNINativeMethod, JNIResult};
+use crate::oop::Oop;
+use crc32fast::Hasher;
+
+pub fn get_native_methods() -> Vec<JNINativeMethod> {
+    vec![
+        new_fn("update", "(II)I", Box::new(jvm_update)),
+        new_fn("updateBytes", "(I[BII)I", Box::new(jvm_updateBytes)),
+        new_fn(
+            "updateByteBuffer",
+            "(IJII)I",
+            Box::new(jvm_updateByteBuffer),
+        ),
+    ]
+}
+
+fn jvm_update(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let crc = args.get(0).unwrap().extract_int();
+    let b = args.get(1).unwrap().extract_int();
+    let v = update(crc, &[b as u8]);
+    Ok(Some(Oop::new_int(v)))
+}
+
+fn jvm_updateBytes(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let crc = args.get(0).unwrap().extract_int();
+    let b = args.get(1).unwrap();
+    let off = args.get(2).unwrap().extract_int() as usize;
+    let len = args.get(3).unwrap().extract_int() as usize;
+
+    let rf = b.extract_ref();
+    let ary = rf.extract_type_array();
+    let ary = ary.extract_bytes();
+    let v = update(crc, &ary[off..off + len]);
+
+    Ok(Some(Oop::new_int(v)))
+}
+
+//direct buffer, address of memory allocated by Unsafe
+fn jvm_updateByteBuffer(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let crc = args.get(0).unwrap().extract_int();
+    let addr = args.get(1).unwrap().extract_long();
+    let off = args.get(2).unwrap().extract_int() as usize;
+    let len = args.get(3).unwrap().extract_int() as usize;
+
+    let buf = unsafe { std::slice::from_raw_parts((addr as *const u8).add(off), len) };
+    let v = update(crc, buf);
+
+    Ok(Some(Oop::new_int(v)))
+}
+
+fn update(crc: i32, buf: &[u8]) -> i32 {
+    let mut h = Hasher::new_with_initial(crc as u32);
+    h.update(buf);
+    h.finalize() as i32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn t_update() {
+        let v = update(0, b"123456789");
+        assert_eq!(v as u32, 0xcbf4_3926);
+
+        //incremental
+        let v = update(update(0, b"1234"), b"56789");
+        assert_eq!(v as u32, 0xcbf4_3926);
+    }
+}
@@ -0,0 +1,216 @@
+#![allow(non_snake_case)]
+
+use crate::native::{new_fn, JNIEnv, JNINativeMethod, JNIResult};
+use crate::new_br;
+use crate::oop::{Class, Oop};
+use crate::runtime::{self, require_class3};
+use adler32::RollingAdler32;
+use classfile::consts as cls_consts;
+use flate2::{Compress, Compression, FlushCompress, Status};
+
+/*
+The address held by ZStreamRef is a boxed ZStream, freed by 'end'.
+
+The rust backend of flate2 has no deflateParams, a new level takes effect
+only before any input compressed; strategy ignored, the output of
+FILTERED & HUFFMAN_ONLY is still valid deflate data.
+*/
+
+//Deflater.SYNC_FLUSH, FULL_FLUSH
+const SYNC_FLUSH: i32 = 2;
+const FULL_FLUSH: i32 = 3;
+
+static mut DEFLATER_BUF: usize = 0;
+static mut DEFLATER_OFF: usize = 0;
+static mut DEFLATER_LEN: usize = 0;
+static mut DEFLATER_LEVEL: usize = 0;
+static mut DEFLATER_SET_PARAMS: usize = 0;
+static mut DEFLATER_FINISH: usize = 0;
+static mut DEFLATER_FINISHED: usize = 0;
+
+struct ZStream {
+    inner: Compress,
+    //raw deflate, no zlib header & checksum
+    nowrap: bool,
+    //of the uncompressed data
+    adler: RollingAdler32,
+}
+
+pub fn get_native_methods() -> Vec<JNINativeMethod> {
+    vec![
+        new_fn("initIDs", "()V", Box::new(jvm_initIDs)),
+        new_fn("init", "(IIZ)J", Box::new(jvm_init)),
+        new_fn("setDictionary", "(J[BII)V", Box::new(jvm_setDictionary)),
+        new_fn("deflateBytes", "(J[BIII)I", Box::new(jvm_deflateBytes)),
+        new_fn("getAdler", "(J)I", Box::new(jvm_getAdler)),
+        new_fn("getBytesRead", "(J)J", Box::new(jvm_getBytesRead)),
+        new_fn("getBytesWritten", "(J)J", Box::new(jvm_getBytesWritten)),
+        new_fn("reset", "(J)V", Box::new(jvm_reset)),
+        new_fn("end", "(J)V", Box::new(jvm_end)),
+    ]
+}
+
+fn jvm_initIDs(_env: JNIEnv, _args: &[Oop]) -> JNIResult {
+    let cls = require_class3(None, b"java/util/zip/Deflater").unwrap();
+    let cls = cls.get_class();
+    let offset =
+        |name: &str, desc: &str| cls.get_field_id(&new_br(name), &new_br(desc), false).offset;
+    unsafe {
+        DEFLATER_BUF = offset("buf", "[B");
+        DEFLATER_OFF = offset("off", "I");
+        DEFLATER_LEN = offset("len", "I");
+        DEFLATER_LEVEL = offset("level", "I");
+        DEFLATER_SET_PARAMS = offset("setParams", "Z");
+        DEFLATER_FINISH = offset("finish", "Z");
+        DEFLATER_FINISHED = offset("finished", "Z");
+    }
+
+    Ok(None)
+}
+
+fn jvm_init(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let level = args.get(0).unwrap().extract_int();
+    let nowrap = args.get(2).unwrap().extract_int() != 0;
+    let stream = ZStream {
+        inner: Compress::new(compression(level), !nowrap),
+        nowrap,
+        adler: RollingAdler32::new(),
+    };
+    let addr = Box::into_raw(Box::new(stream)) as i64;
+    Ok(Some(Oop::new_long(addr)))
+}
+
+//preset dictionary not supported by the rust backend of flate2
+fn jvm_setDictionary(_env: JNIEnv, _args: &[Oop]) -> JNIResult {
+    let ex = runtime::exception::new(
+        cls_consts::J_ILLEGAL_ARGUMENT,
+        Some(String::from("preset dictionary not supported")),
+    );
+    Err(ex)
+}
+
+fn jvm_deflateBytes(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let this = args.get(0).unwrap().extract_ref();
+    let stream = get_stream(args.get(1).unwrap());
+    let b = args.get(2).unwrap();
+    let off = args.get(3).unwrap().extract_int() as usize;
+    let len = args.get(4).unwrap().extract_int() as usize;
+    let flush = args.get(5).unwrap().extract_int();
+
+    let get_field = |offset: usize| Class::get_field_value2(this.clone(), offset).extract_int();
+    let in_buf = Class::get_field_value2(this.clone(), unsafe { DEFLATER_BUF });
+    let in_off = get_field(unsafe { DEFLATER_OFF });
+    let in_len = get_field(unsafe { DEFLATER_LEN });
+
+    if get_field(unsafe { DEFLATER_SET_PARAMS }) != 0 {
+        if stream.inner.total_in() == 0 {
+            let level = get_field(unsafe { DEFLATER_LEVEL });
+            stream.inner = Compress::new(compression(level), !stream.nowrap);
+        }
+        Class::put_field_value2(this, unsafe { DEFLATER_SET_PARAMS }, Oop::new_int(0));
+        return Ok(Some(Oop::new_int(0)));
+    }
+
+    let flush = if get_field(unsafe { DEFLATER_FINISH }) != 0 {
+        FlushCompress::Finish
+    } else {
+        match flush {
+            SYNC_FLUSH => FlushCompress::Sync,
+            FULL_FLUSH => FlushCompress::Full,
+            //NO_FLUSH
+            _ => FlushCompress::None,
+        }
+    };
+
+    //input & output may be the same array
+    let input = {
+        let rf = in_buf.extract_ref();
+        let ary = rf.extract_type_array();
+        let ary = ary.extract_bytes();
+        ary[in_off as usize..(in_off + in_len) as usize].to_vec()
+    };
+    let rf = b.extract_ref();
+    let ary = rf.extract_mut_type_array();
+    let output = &mut ary.extract_mut_bytes()[off..off + len];
+
+    let (total_in, total_out) = (stream.inner.total_in(), stream.inner.total_out());
+    let status = stream.inner.compress(&input, output, flush);
+    let consumed = (stream.inner.total_in() - total_in) as usize;
+    let produced = (stream.inner.total_out() - total_out) as i32;
+
+    match status {
+        Ok(status) => {
+            if !stream.nowrap {
+                stream.adler.update_buffer(&input[..consumed]);
+            }
+
+            Class::put_field_value2(
+                this.clone(),
+                unsafe { DEFLATER_OFF },
+                Oop::new_int(in_off + consumed as i32),
+            );
+            Class::put_field_value2(
+                this.clone(),
+                unsafe { DEFLATER_LEN },
+                Oop::new_int(in_len - consumed as i32),
+            );
+            if let Status::StreamEnd = status {
+                Class::put_field_value2(this, unsafe { DEFLATER_FINISHED }, Oop::new_int(1));
+            }
+
+            Ok(Some(Oop::new_int(produced)))
+        }
+        Err(e) => {
+            let ex = runtime::exception::new(cls_consts::J_INTERNAL_ERROR, Some(e.to_string()));
+            Err(ex)
+        }
+    }
+}
+
+fn jvm_getAdler(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let stream = get_stream(args.get(0).unwrap());
+    let v = if stream.nowrap {
+        1
+    } else {
+        stream.adler.hash() as i32
+    };
+    Ok(Some(Oop::new_int(v)))
+}
+
+fn jvm_getBytesRead(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let stream = get_stream(args.get(0).unwrap());
+    Ok(Some(Oop::new_long(stream.inner.total_in() as i64)))
+}
+
+fn jvm_getBytesWritten(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let stream = get_stream(args.get(0).unwrap());
+    Ok(Some(Oop::new_long(stream.inner.total_out() as i64)))
+}
+
+fn jvm_reset(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let stream = get_stream(args.get(0).unwrap());
+    stream.inner.reset();
+    stream.adler = RollingAdler32::new();
+    Ok(None)
+}
+
+fn jvm_end(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let addr = args.get(0).unwrap().extract_long();
+    unsafe {
+        drop(Box::from_raw(addr as *mut ZStream));
+    }
+    Ok(None)
+}
+
+fn get_stream(addr: &Oop) -> &'static mut ZStream {
+    let addr = addr.extract_long();
+    unsafe { &mut *(addr as *mut ZStream) }
+}
+
+//Deflater.DEFAULT_COMPRESSION is -1
+fn compression(level: i32) -> Compression {
+    match level {
+        0..=9 => Compression::new(level as u32),
+        _ => Compression::default(),
+    }
+}
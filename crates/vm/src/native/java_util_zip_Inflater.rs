@@ -0,0 +1,220 @@
+#![allow(non_snake_case)]
+
+use crate::native::{new_fn, JNIEnv, JNINativeMethod, JNIResult};
+use crate::new_br;
+use crate::oop::{Class, Oop};
+use crate::runtime::{self, require_class3};
+use adler32::RollingAdler32;
+use classfile::consts as cls_consts;
+use flate2::{Decompress, FlushDecompress, Status};
+
+/*
+The address held by ZStreamRef is a boxed ZStream, freed by 'end'.
+
+Inflater synchronized on zsRef, no lock needed here.
+
+Preset dictionaries are not supported: flate2 is built with its default
+miniz_oxide backend, which can't take a dictionary (the zlib backend needs
+libz-sys). miniz_oxide fails on the FDICT flag of a zlib header, so the header
+is checked here: the stream stops with needDict after its 6 bytes (CMF, FLG and
+DICTID), as zlib does, and setDictionary throws IllegalArgumentException.
+*/
+
+static mut INFLATER_BUF: usize = 0;
+static mut INFLATER_OFF: usize = 0;
+static mut INFLATER_LEN: usize = 0;
+static mut INFLATER_FINISHED: usize = 0;
+static mut INFLATER_NEED_DICT: usize = 0;
+
+struct ZStream {
+    inner: Decompress,
+    //raw deflate, no zlib header & checksum
+    nowrap: bool,
+    //of the uncompressed data
+    adler: RollingAdler32,
+    //DICTID of a zlib header with FDICT
+    dict_id: Option<u32>,
+}
+
+const ZLIB_HEADER_DICT_LEN: usize = 6;
+
+pub fn get_native_methods() -> Vec<JNINativeMethod> {
+    vec![
+        new_fn("initIDs", "()V", Box::new(jvm_initIDs)),
+        new_fn("init", "(Z)J", Box::new(jvm_init)),
+        new_fn("setDictionary", "(J[BII)V", Box::new(jvm_setDictionary)),
+        new_fn("inflateBytes", "(J[BII)I", Box::new(jvm_inflateBytes)),
+        new_fn("getAdler", "(J)I", Box::new(jvm_getAdler)),
+        new_fn("getBytesRead", "(J)J", Box::new(jvm_getBytesRead)),
+        new_fn("getBytesWritten", "(J)J", Box::new(jvm_getBytesWritten)),
+        new_fn("reset", "(J)V", Box::new(jvm_reset)),
+        new_fn("end", "(J)V", Box::new(jvm_end)),
+    ]
+}
+
+fn jvm_initIDs(_env: JNIEnv, _args: &[Oop]) -> JNIResult {
+    let cls = require_class3(None, b"java/util/zip/Inflater").unwrap();
+    let cls = cls.get_class();
+    let offset =
+        |name: &str, desc: &str| cls.get_field_id(&new_br(name), &new_br(desc), false).offset;
+    unsafe {
+        INFLATER_BUF = offset("buf", "[B");
+        INFLATER_OFF = offset("off", "I");
+        INFLATER_LEN = offset("len", "I");
+        INFLATER_FINISHED = offset("finished", "Z");
+        INFLATER_NEED_DICT = offset("needDict", "Z");
+    }
+
+    Ok(None)
+}
+
+fn jvm_init(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let nowrap = args.get(0).unwrap().extract_int() != 0;
+    let stream = ZStream {
+        inner: Decompress::new(!nowrap),
+        nowrap,
+        adler: RollingAdler32::new(),
+        dict_id: None,
+    };
+    let addr = Box::into_raw(Box::new(stream)) as i64;
+    Ok(Some(Oop::new_long(addr)))
+}
+
+//see the header, no dictionary with the miniz_oxide backend
+fn jvm_setDictionary(_env: JNIEnv, _args: &[Oop]) -> JNIResult {
+    let ex = runtime::exception::new(
+        cls_consts::J_ILLEGAL_ARGUMENT,
+        Some(String::from("preset dictionary not supported")),
+    );
+    Err(ex)
+}
+
+fn jvm_inflateBytes(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let this = args.get(0).unwrap().extract_ref();
+    let stream = get_stream(args.get(1).unwrap());
+    let b = args.get(2).unwrap();
+    let off = args.get(3).unwrap().extract_int() as usize;
+    let len = args.get(4).unwrap().extract_int() as usize;
+
+    let in_buf = Class::get_field_value2(this.clone(), unsafe { INFLATER_BUF });
+    let in_off = Class::get_field_value2(this.clone(), unsafe { INFLATER_OFF }).extract_int();
+    let in_len = Class::get_field_value2(this.clone(), unsafe { INFLATER_LEN }).extract_int();
+
+    //input & output may be the same array
+    let input = {
+        let rf = in_buf.extract_ref();
+        let ary = rf.extract_type_array();
+        let ary = ary.extract_bytes();
+        ary[in_off as usize..(in_off + in_len) as usize].to_vec()
+    };
+    let rf = b.extract_ref();
+    let ary = rf.extract_mut_type_array();
+    let output = &mut ary.extract_mut_bytes()[off..off + len];
+
+    let set_in = |consumed: i32| {
+        Class::put_field_value2(
+            this.clone(),
+            unsafe { INFLATER_OFF },
+            Oop::new_int(in_off + consumed),
+        );
+        Class::put_field_value2(
+            this.clone(),
+            unsafe { INFLATER_LEN },
+            Oop::new_int(in_len - consumed),
+        );
+    };
+
+    //zlib stream compressed with a preset dictionary, its header consumed
+    if let Some(dict_id) = preset_dict_id(stream, &input) {
+        stream.dict_id = Some(dict_id);
+        set_in(ZLIB_HEADER_DICT_LEN as i32);
+        Class::put_field_value2(this, unsafe { INFLATER_NEED_DICT }, Oop::new_int(1));
+        return Ok(Some(Oop::new_int(0)));
+    }
+
+    let (total_in, total_out) = (stream.inner.total_in(), stream.inner.total_out());
+    let status = stream
+        .inner
+        .decompress(&input, output, FlushDecompress::None);
+    let consumed = (stream.inner.total_in() - total_in) as i32;
+    let produced = (stream.inner.total_out() - total_out) as usize;
+
+    match status {
+        Ok(status) => {
+            if !stream.nowrap {
+                stream.adler.update_buffer(&output[..produced]);
+            }
+
+            set_in(consumed);
+            if let Status::StreamEnd = status {
+                Class::put_field_value2(this, unsafe { INFLATER_FINISHED }, Oop::new_int(1));
+            }
+
+            Ok(Some(Oop::new_int(produced as i32)))
+        }
+        Err(e) => {
+            let ex = runtime::exception::new(cls_consts::J_DATA_FORMAT_EX, Some(e.to_string()));
+            Err(ex)
+        }
+    }
+}
+
+//the header of a zlib stream, at its start, with FDICT set
+fn preset_dict_id(stream: &ZStream, input: &[u8]) -> Option<u32> {
+    if stream.nowrap || stream.inner.total_in() != 0 || input.len() < ZLIB_HEADER_DICT_LEN {
+        return None;
+    }
+
+    let (cmf, flg) = (input[0] as u32, input[1] as u32);
+    if ((cmf << 8) | flg) % 31 == 0 && flg & 0x20 != 0 {
+        Some(u32::from_be_bytes([input[2], input[3], input[4], input[5]]))
+    } else {
+        None
+    }
+}
+
+fn jvm_getAdler(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let stream = get_stream(args.get(0).unwrap());
+    let v = if let Some(dict_id) = stream.dict_id {
+        dict_id as i32
+    } else if stream.nowrap {
+        1
+    } else {
+        stream.adler.hash() as i32
+    };
+    Ok(Some(Oop::new_int(v)))
+}
+
+fn jvm_getBytesRead(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let stream = get_stream(args.get(0).unwrap());
+    let header = stream.dict_id.map_or(0, |_| ZLIB_HEADER_DICT_LEN as u64);
+    Ok(Some(Oop::new_long(
+        (stream.inner.total_in() + header) as i64,
+    )))
+}
+
+fn jvm_getBytesWritten(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let stream = get_stream(args.get(0).unwrap());
+    Ok(Some(Oop::new_long(stream.inner.total_out() as i64)))
+}
+
+fn jvm_reset(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let stream = get_stream(args.get(0).unwrap());
+    stream.inner.reset(!stream.nowrap);
+    stream.adler = RollingAdler32::new();
+    stream.dict_id = None;
+    Ok(None)
+}
+
+fn jvm_end(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let addr = args.get(0).unwrap().extract_long();
+    unsafe {
+        drop(Box::from_raw(addr as *mut ZStream));
+    }
+    Ok(None)
+}
+
+fn get_stream(addr: &Oop) -> &'static mut ZStream {
+    let addr = addr.extract_long();
+    unsafe { &mut *(addr as *mut ZStream) }
+}
@@ -0,0 +1,307 @@
+#![allow(non_snake_case)]
+
+use crate::native::{new_fn, JNIEnv, JNINativeMethod, JNIResult};
+use crate::oop::{self, Oop, OopRef};
+use crate::runtime::{self, jar::Jar};
+use classfile::consts as cls_consts;
+use rustc_hash::FxHashMap;
+use std::io;
+
+/*
+jzfile is a boxed ZipHandle, freed by 'close';
+jzentry is a boxed EntryHandle, freed by 'freeEntry'.
+
+Entries read from the mmap-backed Jar shared with class path,
+'read' answers the data as stored, ZipFile inflates it by Inflater.
+*/
+
+//ZipFile.JZENTRY_NAME, JZENTRY_EXTRA, JZENTRY_COMMENT
+const JZENTRY_NAME: i32 = 0;
+const JZENTRY_EXTRA: i32 = 1;
+
+pub struct ZipHandle {
+    pub jar: Jar,
+    //name -> index of entries
+    names: FxHashMap<String, usize>,
+}
+
+struct EntryHandle {
+    zip: *const ZipHandle,
+    idx: usize,
+}
+
+pub fn get_native_methods() -> Vec<JNINativeMethod> {
+    vec![
+        new_fn("initIDs", "()V", Box::new(jvm_initIDs)),
+        new_fn("open", "(Ljava/lang/String;IJZ)J", Box::new(jvm_open)),
+        new_fn("getTotal", "(J)I", Box::new(jvm_getTotal)),
+        new_fn("startsWithLOC", "(J)Z", Box::new(jvm_startsWithLOC)),
+        new_fn("getEntry", "(J[BZ)J", Box::new(jvm_getEntry)),
+        new_fn("freeEntry", "(JJ)V", Box::new(jvm_freeEntry)),
+        new_fn("getNextEntry", "(JI)J", Box::new(jvm_getNextEntry)),
+        new_fn("close", "(J)V", Box::new(jvm_close)),
+        new_fn("read", "(JJJ[BII)I", Box::new(jvm_read)),
+        new_fn("getEntryTime", "(J)J", Box::new(jvm_getEntryTime)),
+        new_fn("getEntryCrc", "(J)J", Box::new(jvm_getEntryCrc)),
+        new_fn("getEntryCSize", "(J)J", Box::new(jvm_getEntryCSize)),
+        new_fn("getEntrySize", "(J)J", Box::new(jvm_getEntrySize)),
+        new_fn("getEntryMethod", "(J)I", Box::new(jvm_getEntryMethod)),
+        new_fn("getEntryFlag", "(J)I", Box::new(jvm_getEntryFlag)),
+        new_fn("getCommentBytes", "(J)[B", Box::new(jvm_getCommentBytes)),
+        new_fn("getEntryBytes", "(JI)[B", Box::new(jvm_getEntryBytes)),
+        new_fn(
+            "getZipMessage",
+            "(J)Ljava/lang/String;",
+            Box::new(jvm_getZipMessage),
+        ),
+    ]
+}
+
+impl ZipHandle {
+    fn open(path: &str) -> io::Result<Self> {
+        let jar = Jar::open(path)?;
+        let names = jar
+            .entries()
+            .iter()
+            .enumerate()
+            .map(|(i, e)| (e.name.clone(), i))
+            .collect();
+        Ok(Self { jar, names })
+    }
+
+    //'add_slash', try "name/" for a dir
+    fn find(&self, name: &str, add_slash: bool) -> Option<usize> {
+        match self.names.get(name) {
+            Some(idx) => Some(*idx),
+            None if add_slash && !name.ends_with('/') => {
+                self.names.get(&format!("{}/", name)).cloned()
+            }
+            None => None,
+        }
+    }
+
+    pub fn from_addr<'a>(addr: i64) -> &'a ZipHandle {
+        unsafe { &*(addr as *const ZipHandle) }
+    }
+}
+
+fn jvm_initIDs(_env: JNIEnv, _args: &[Oop]) -> JNIResult {
+    Ok(None)
+}
+
+fn jvm_open(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let name = args.get(0).unwrap();
+    let name = OopRef::java_lang_string(name.extract_ref());
+
+    match ZipHandle::open(&name) {
+        Ok(zip) => {
+            let addr = Box::into_raw(Box::new(zip)) as i64;
+            Ok(Some(Oop::new_long(addr)))
+        }
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            let msg = format!("{} (No such file or directory)", name);
+            let ex = runtime::exception::new(cls_consts::J_FILE_NOT_FOUND, Some(msg));
+            Err(ex)
+        }
+        Err(e) => {
+            let ex = runtime::exception::new(cls_consts::J_ZIP_EX, Some(e.to_string()));
+            Err(ex)
+        }
+    }
+}
+
+fn jvm_getTotal(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let zip = ZipHandle::from_addr(args.get(0).unwrap().extract_long());
+    Ok(Some(Oop::new_int(zip.jar.entries().len() as i32)))
+}
+
+fn jvm_startsWithLOC(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let zip = ZipHandle::from_addr(args.get(0).unwrap().extract_long());
+    let v = if zip.jar.starts_with_loc() { 1 } else { 0 };
+    Ok(Some(Oop::new_int(v)))
+}
+
+fn jvm_getEntry(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let addr = args.get(0).unwrap().extract_long();
+    let zip = ZipHandle::from_addr(addr);
+    let name = args.get(1).unwrap();
+    let add_slash = args.get(2).unwrap().extract_int() != 0;
+
+    let name = {
+        let rf = name.extract_ref();
+        let ary = rf.extract_type_array();
+        String::from_utf8_lossy(ary.extract_bytes()).to_string()
+    };
+
+    let v = match zip.find(&name, add_slash) {
+        Some(idx) => new_entry(addr, idx),
+        None => 0,
+    };
+    Ok(Some(Oop::new_long(v)))
+}
+
+fn jvm_freeEntry(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let addr = args.get(1).unwrap().extract_long();
+    if addr != 0 {
+        unsafe {
+            drop(Box::from_raw(addr as *mut EntryHandle));
+        }
+    }
+    Ok(None)
+}
+
+fn jvm_getNextEntry(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let addr = args.get(0).unwrap().extract_long();
+    let zip = ZipHandle::from_addr(addr);
+    let i = args.get(1).unwrap().extract_int();
+
+    let v = if i >= 0 && (i as usize) < zip.jar.entries().len() {
+        new_entry(addr, i as usize)
+    } else {
+        0
+    };
+    Ok(Some(Oop::new_long(v)))
+}
+
+fn jvm_close(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let addr = args.get(0).unwrap().extract_long();
+    unsafe {
+        drop(Box::from_raw(addr as *mut ZipHandle));
+    }
+    Ok(None)
+}
+
+fn jvm_read(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let zip = ZipHandle::from_addr(args.get(0).unwrap().extract_long());
+    let (_, e) = get_entry(args.get(1).unwrap());
+    let pos = args.get(2).unwrap().extract_long() as usize;
+    let b = args.get(3).unwrap();
+    let off = args.get(4).unwrap().extract_int() as usize;
+    let len = args.get(5).unwrap().extract_int() as usize;
+
+    let data = match zip.jar.raw(e) {
+        Ok(data) => data,
+        Err(e) => {
+            let ex = runtime::exception::new(cls_consts::J_ZIP_EX, Some(e.to_string()));
+            return Err(ex);
+        }
+    };
+
+    let n = if pos < data.len() {
+        let n = len.min(data.len() - pos);
+        let rf = b.extract_ref();
+        let ary = rf.extract_mut_type_array();
+        let ary = ary.extract_mut_bytes();
+        ary[off..off + n].copy_from_slice(&data[pos..pos + n]);
+        n as i32
+    } else {
+        0
+    };
+
+    Ok(Some(Oop::new_int(n)))
+}
+
+fn jvm_getEntryTime(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let (_, e) = get_entry(args.get(0).unwrap());
+    Ok(Some(Oop::new_long(e.dos_time as i64)))
+}
+
+fn jvm_getEntryCrc(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let (_, e) = get_entry(args.get(0).unwrap());
+    Ok(Some(Oop::new_long(e.crc as i64)))
+}
+
+fn jvm_getEntryCSize(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let (_, e) = get_entry(args.get(0).unwrap());
+    Ok(Some(Oop::new_long(e.compressed_size as i64)))
+}
+
+fn jvm_getEntrySize(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let (_, e) = get_entry(args.get(0).unwrap());
+    Ok(Some(Oop::new_long(e.size as i64)))
+}
+
+fn jvm_getEntryMethod(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let (_, e) = get_entry(args.get(0).unwrap());
+    Ok(Some(Oop::new_int(e.method as i32)))
+}
+
+fn jvm_getEntryFlag(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let (_, e) = get_entry(args.get(0).unwrap());
+    Ok(Some(Oop::new_int(e.flag as i32)))
+}
+
+fn jvm_getCommentBytes(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let zip = ZipHandle::from_addr(args.get(0).unwrap().extract_long());
+    Ok(Some(new_bytes(zip.jar.comment())))
+}
+
+fn jvm_getEntryBytes(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let (zip, e) = get_entry(args.get(0).unwrap());
+    let tp = args.get(1).unwrap().extract_int();
+
+    let v = match tp {
+        JZENTRY_NAME => Oop::new_byte_ary2(e.name.as_bytes().to_vec()),
+        JZENTRY_EXTRA => new_bytes(zip.jar.extra(e)),
+        //JZENTRY_COMMENT
+        _ => new_bytes(zip.jar.entry_comment(e)),
+    };
+    Ok(Some(v))
+}
+
+//errors thrown by open & read, no message kept
+fn jvm_getZipMessage(_env: JNIEnv, _args: &[Oop]) -> JNIResult {
+    Ok(Some(oop::consts::get_null()))
+}
+
+fn new_entry(zip: i64, idx: usize) -> i64 {
+    let zip = zip as *const ZipHandle;
+    Box::into_raw(Box::new(EntryHandle { zip, idx })) as i64
+}
+
+fn get_entry<'a>(addr: &Oop) -> (&'a ZipHandle, &'a runtime::jar::JarEntry) {
+    let addr = addr.extract_long();
+    let h = unsafe { &*(addr as *const EntryHandle) };
+    let zip = unsafe { &*h.zip };
+    (zip, &zip.jar.entries()[h.idx])
+}
+
+//null for empty
+fn new_bytes(v: &[u8]) -> Oop {
+    if v.is_empty() {
+        oop::consts::get_null()
+    } else {
+        Oop::new_byte_ary2(v.to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use zip::write::FileOptions;
+
+    #[test]
+    fn t_find() {
+        let path = std::env::temp_dir().join(format!("jvm_zip_test_{}.zip", std::process::id()));
+        {
+            let f = std::fs::File::create(&path).unwrap();
+            let mut z = zip::ZipWriter::new(f);
+            z.add_directory("META-INF/", FileOptions::default())
+                .unwrap();
+            z.start_file("META-INF/MANIFEST.MF", FileOptions::default())
+                .unwrap();
+            z.write_all(b"Manifest-Version: 1.0\n").unwrap();
+            z.finish().unwrap();
+        }
+
+        let zip = ZipHandle::open(path.to_str().unwrap()).unwrap();
+        assert_eq!(zip.find("META-INF/MANIFEST.MF", false), Some(1));
+        assert_eq!(zip.find("META-INF", false), None);
+        assert_eq!(zip.find("META-INF", true), Some(0));
+        assert_eq!(zip.find("META-INF/", true), Some(0));
+        assert_eq!(zip.find("no_exist", true), None);
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -1,35 +1,71 @@
 #![allow(non_snake_case)]
 
 use crate::oop::Oop;
+use crate::runtime;
 use crate::types::ClassRef;
-use rustc_hash::FxHashMap;
-use std::sync::{Arc, RwLock};
+use rustc_hash::{FxHashMap, FxHashSet};
+use std::sync::{Arc, Mutex, RwLock};
 
 mod common;
 
 mod java_io_FileDescriptor;
 mod java_io_FileInputStream;
 mod java_io_FileOutputStream;
+mod java_io_RandomAccessFile;
 mod java_io_UnixFileSystem;
 pub mod java_lang_Class;
 mod java_lang_ClassLoader;
 mod java_lang_Double;
 mod java_lang_Float;
 mod java_lang_Object;
+mod java_lang_ProcessEnvironment;
 mod java_lang_Runtime;
+mod java_lang_Shutdown;
 mod java_lang_String;
 mod java_lang_System;
 mod java_lang_Thread;
 mod java_lang_Throwable;
+mod java_lang_UNIXProcess;
+mod java_lang_ref_Reference;
 mod java_lang_reflect_Array;
+mod java_lang_reflect_Executable;
+mod java_lang_reflect_Field;
 mod java_lang_reflect_Proxy;
+mod java_net_DatagramPacket;
+mod java_net_Inet4Address;
+mod java_net_Inet4AddressImpl;
+mod java_net_Inet6Address;
+mod java_net_Inet6AddressImpl;
+mod java_net_InetAddress;
+mod java_net_InetAddressImplFactory;
+mod java_net_NetworkInterface;
+mod java_net_PlainDatagramSocketImpl;
+mod java_net_PlainSocketImpl;
+mod java_net_SocketInputStream;
+mod java_net_SocketOutputStream;
+mod java_nio_Bits;
 mod java_security_AccessController;
 mod java_util_concurrent_atomic_AtomicLong;
+mod java_util_jar_JarFile;
+mod java_util_zip_Adler32;
+mod java_util_zip_CRC32;
+mod java_util_zip_Deflater;
+mod java_util_zip_Inflater;
+mod java_util_zip_ZipFile;
 mod sun_misc_Signal;
 mod sun_misc_URLClassPath;
 mod sun_misc_Unsafe;
 mod sun_misc_VM;
+#[cfg(target_os = "linux")]
+mod sun_nio_ch_EPollArrayWrapper;
+mod sun_nio_ch_FileChannelImpl;
+mod sun_nio_ch_FileDispatcherImpl;
+mod sun_nio_ch_FileKey;
+mod sun_nio_ch_IOUtil;
+mod sun_nio_ch_NativeThread;
 mod sun_nio_cs_StreamEncoder;
+mod sun_nio_fs_UnixCopyFile;
+mod sun_nio_fs_UnixNativeDispatcher;
 mod sun_reflect_ConstantPool;
 mod sun_reflect_NativeConstructorAccessorImpl;
 mod sun_reflect_NativeMethodAccessorImpl;
@@ -55,6 +91,9 @@ lazy_static! {
     static ref NATIVES: FxHashMap<(&'static str, &'static str, &'static str), JNINativeMethod> = {
         create_native_fn_tables()
     };
+
+    //methods already reported by -verbose:jni
+    static ref LINKED: Mutex<FxHashSet<String>> = Mutex::new(FxHashSet::default());
 }
 
 pub fn new_fn(
@@ -79,7 +118,20 @@ pub fn find_symbol(package: &[u8], name: &[u8], desc: &[u8]) -> Option<JNINative
     let desc = unsafe { std::str::from_utf8_unchecked(desc) };
 
     let k = (package, name, desc);
-    NATIVES.get(&k).cloned()
+    let method = NATIVES.get(&k).cloned();
+
+    if method.is_some() && runtime::options::with(|opts| opts.verbose_jni) {
+        let mut linked = LINKED.lock().unwrap();
+        if linked.insert(format!("{}.{}{}", package, name, desc)) {
+            println!(
+                "[Dynamic-linking native method {}.{} ... JNI]",
+                package.replace("/", "."),
+                name
+            );
+        }
+    }
+
+    method
 }
 
 pub fn init() {
@@ -96,7 +148,7 @@ impl JNINativeMethodStruct {
 fn create_native_fn_tables(
 ) -> FxHashMap<(&'static str, &'static str, &'static str), JNINativeMethod> {
     let mut dict = FxHashMap::default();
-    let natives = vec![
+    let mut natives = vec![
         (
             "java/io/FileDescriptor",
             java_io_FileDescriptor::get_native_methods(),
@@ -109,6 +161,10 @@ fn create_native_fn_tables(
             "java/io/FileOutputStream",
             java_io_FileOutputStream::get_native_methods(),
         ),
+        (
+            "java/io/RandomAccessFile",
+            java_io_RandomAccessFile::get_native_methods(),
+        ),
         (
             "java/io/UnixFileSystem",
             java_io_UnixFileSystem::get_native_methods(),
@@ -121,15 +177,35 @@ fn create_native_fn_tables(
         ("java/lang/Double", java_lang_Double::get_native_methods()),
         ("java/lang/Float", java_lang_Float::get_native_methods()),
         ("java/lang/Object", java_lang_Object::get_native_methods()),
+        (
+            "java/lang/ProcessEnvironment",
+            java_lang_ProcessEnvironment::get_native_methods(),
+        ),
+        (
+            "java/lang/ref/Reference",
+            java_lang_ref_Reference::get_native_methods(),
+        ),
         (
             "java/lang/reflect/Array",
             java_lang_reflect_Array::get_native_methods(),
         ),
+        (
+            "java/lang/reflect/Executable",
+            java_lang_reflect_Executable::get_native_methods(),
+        ),
+        (
+            "java/lang/reflect/Field",
+            java_lang_reflect_Field::get_native_methods(),
+        ),
         (
             "java/lang/reflect/Proxy",
             java_lang_reflect_Proxy::get_native_methods(),
         ),
         ("java/lang/Runtime", java_lang_Runtime::get_native_methods()),
+        (
+            "java/lang/Shutdown",
+            java_lang_Shutdown::get_native_methods(),
+        ),
         ("java/lang/String", java_lang_String::get_native_methods()),
         ("java/lang/System", java_lang_System::get_native_methods()),
         ("java/lang/Thread", java_lang_Thread::get_native_methods()),
@@ -137,6 +213,59 @@ fn create_native_fn_tables(
             "java/lang/Throwable",
             java_lang_Throwable::get_native_methods(),
         ),
+        (
+            "java/lang/UNIXProcess",
+            java_lang_UNIXProcess::get_native_methods(),
+        ),
+        (
+            "java/net/DatagramPacket",
+            java_net_DatagramPacket::get_native_methods(),
+        ),
+        (
+            "java/net/Inet4Address",
+            java_net_Inet4Address::get_native_methods(),
+        ),
+        (
+            "java/net/Inet4AddressImpl",
+            java_net_Inet4AddressImpl::get_native_methods(),
+        ),
+        (
+            "java/net/Inet6Address",
+            java_net_Inet6Address::get_native_methods(),
+        ),
+        (
+            "java/net/Inet6AddressImpl",
+            java_net_Inet6AddressImpl::get_native_methods(),
+        ),
+        (
+            "java/net/InetAddress",
+            java_net_InetAddress::get_native_methods(),
+        ),
+        (
+            "java/net/InetAddressImplFactory",
+            java_net_InetAddressImplFactory::get_native_methods(),
+        ),
+        (
+            "java/net/NetworkInterface",
+            java_net_NetworkInterface::get_native_methods(),
+        ),
+        (
+            "java/net/PlainDatagramSocketImpl",
+            java_net_PlainDatagramSocketImpl::get_native_methods(),
+        ),
+        (
+            "java/net/PlainSocketImpl",
+            java_net_PlainSocketImpl::get_native_methods(),
+        ),
+        (
+            "java/net/SocketInputStream",
+            java_net_SocketInputStream::get_native_methods(),
+        ),
+        (
+            "java/net/SocketOutputStream",
+            java_net_SocketOutputStream::get_native_methods(),
+        ),
+        ("java/nio/Bits", java_nio_Bits::get_native_methods()),
         (
             "java/security/AccessController",
             java_security_AccessController::get_native_methods(),
@@ -145,6 +274,30 @@ fn create_native_fn_tables(
             "java/util/concurrent/atomic/AtomicLong",
             java_util_concurrent_atomic_AtomicLong::get_native_methods(),
         ),
+        (
+            "java/util/jar/JarFile",
+            java_util_jar_JarFile::get_native_methods(),
+        ),
+        (
+            "java/util/zip/Adler32",
+            java_util_zip_Adler32::get_native_methods(),
+        ),
+        (
+            "java/util/zip/CRC32",
+            java_util_zip_CRC32::get_native_methods(),
+        ),
+        (
+            "java/util/zip/Deflater",
+            java_util_zip_Deflater::get_native_methods(),
+        ),
+        (
+            "java/util/zip/Inflater",
+            java_util_zip_Inflater::get_native_methods(),
+        ),
+        (
+            "java/util/zip/ZipFile",
+            java_util_zip_ZipFile::get_native_methods(),
+        ),
         ("sun/misc/Signal", sun_misc_Signal::get_native_methods()),
         ("sun/misc/Unsafe", sun_misc_Unsafe::get_native_methods()),
         (
@@ -152,10 +305,35 @@ fn create_native_fn_tables(
             sun_misc_URLClassPath::get_native_methods(),
         ),
         ("sun/misc/VM", sun_misc_VM::get_native_methods()),
+        (
+            "sun/nio/ch/FileChannelImpl",
+            sun_nio_ch_FileChannelImpl::get_native_methods(),
+        ),
+        (
+            "sun/nio/ch/FileDispatcherImpl",
+            sun_nio_ch_FileDispatcherImpl::get_native_methods(),
+        ),
+        (
+            "sun/nio/ch/FileKey",
+            sun_nio_ch_FileKey::get_native_methods(),
+        ),
+        ("sun/nio/ch/IOUtil", sun_nio_ch_IOUtil::get_native_methods()),
+        (
+            "sun/nio/ch/NativeThread",
+            sun_nio_ch_NativeThread::get_native_methods(),
+        ),
         (
             "sun/nio/cs/StreamEncoder",
             sun_nio_cs_StreamEncoder::get_native_methods(),
         ),
+        (
+            "sun/nio/fs/UnixCopyFile",
+            sun_nio_fs_UnixCopyFile::get_native_methods(),
+        ),
+        (
+            "sun/nio/fs/UnixNativeDispatcher",
+            sun_nio_fs_UnixNativeDispatcher::get_native_methods(),
+        ),
         (
             "sun/reflect/ConstantPool",
             sun_reflect_ConstantPool::get_native_methods(),
@@ -174,6 +352,13 @@ fn create_native_fn_tables(
         ),
     ];
 
+    //the Selector of other os, like KQueue of mac, not supported yet
+    #[cfg(target_os = "linux")]
+    natives.push((
+        "sun/nio/ch/EPollArrayWrapper",
+        sun_nio_ch_EPollArrayWrapper::get_native_methods(),
+    ));
+
     {
         natives.iter().for_each(|(package, methods)| {
             methods.iter().for_each(|it| {
@@ -1,7 +1,8 @@
 #![allow(non_snake_case)]
 
 use crate::native::{new_fn, JNIEnv, JNINativeMethod, JNIResult};
-use crate::oop::Oop;
+use crate::oop::{Oop, OopRef};
+use crate::runtime::signal;
 
 pub fn get_native_methods() -> Vec<JNINativeMethod> {
     vec![
@@ -14,12 +15,18 @@ pub fn get_native_methods() -> Vec<JNINativeMethod> {
     ]
 }
 
-//todo: impl me
-fn jvm_findSignal(_env: JNIEnv, _args: &[Oop]) -> JNIResult {
-    Ok(Some(Oop::new_int(1)))
+//-1, unknown signal
+fn jvm_findSignal(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let name = args.get(0).unwrap();
+    let name = OopRef::java_lang_string(name.extract_ref());
+    let v = signal::find(name.as_str()).unwrap_or(-1);
+    Ok(Some(Oop::new_int(v)))
 }
 
-//todo: impl me
-fn jvm_handle0(_env: JNIEnv, _args: &[Oop]) -> JNIResult {
-    Ok(Some(Oop::new_long(0)))
+//-1, "Signal already used by VM or OS"
+fn jvm_handle0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let sig = args.get(0).unwrap().extract_int();
+    let handler = args.get(1).unwrap().extract_long();
+    let old = signal::install(sig, handler).unwrap_or(-1);
+    Ok(Some(Oop::new_long(old)))
 }
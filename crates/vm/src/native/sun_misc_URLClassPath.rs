@@ -1,16 +1,112 @@
 #![allow(non_snake_case)]
 
 use crate::native::{new_fn, JNIEnv, JNINativeMethod, JNIResult};
-use crate::oop::{self, Oop};
+use crate::oop::{self, Oop, OopRef};
+use crate::runtime::{self, require_class3};
+use crate::{new_br, util};
+
+/*
+Lookup cache of URLClassPath, answered by the index of class path of vm.
+
+The URLClassPath of AppClassLoader is the class path of app, the one of
+ExtClassLoader is empty (jars of ext dir loaded by bootstrap loader).
+The cache of other loaders disabled by returning null.
+*/
+
+const APP_LOADER: &[u8] = b"sun/misc/Launcher$AppClassLoader";
+const EXT_LOADER: &[u8] = b"sun/misc/Launcher$ExtClassLoader";
 
 pub fn get_native_methods() -> Vec<JNINativeMethod> {
-    vec![new_fn(
-        "getLookupCacheURLs",
-        "(Ljava/lang/ClassLoader;)[Ljava/net/URL;",
-        Box::new(jvm_getLookupCacheURLs),
-    )]
+    vec![
+        new_fn(
+            "getLookupCacheURLs",
+            "(Ljava/lang/ClassLoader;)[Ljava/net/URL;",
+            Box::new(jvm_getLookupCacheURLs),
+        ),
+        new_fn(
+            "getLookupCacheForClassLoader",
+            "(Ljava/lang/ClassLoader;Ljava/lang/String;)[I",
+            Box::new(jvm_getLookupCacheForClassLoader),
+        ),
+        new_fn(
+            "knownToNotExist0",
+            "(Ljava/lang/ClassLoader;Ljava/lang/String;)Z",
+            Box::new(jvm_knownToNotExist0),
+        ),
+    ]
+}
+
+enum Loader {
+    App,
+    Ext,
+    Other,
+}
+
+fn jvm_getLookupCacheURLs(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let urls = match get_loader(args.get(0).unwrap()) {
+        Loader::App => runtime::class_path_urls(),
+        Loader::Ext => vec![],
+        Loader::Other => return Ok(Some(oop::consts::get_null())),
+    };
+
+    let url_cls = require_class3(None, b"java/net/URL").unwrap();
+    let mut elms = Vec::with_capacity(urls.len());
+    for url in urls.iter() {
+        let v = Oop::new_inst(url_cls.clone());
+        let args = vec![v.clone(), util::oop::new_java_lang_string2(url)];
+        runtime::invoke::invoke_ctor(url_cls.clone(), new_br("(Ljava/lang/String;)V"), args);
+
+        //MalformedURLException pending
+        if runtime::thread::is_meet_ex() {
+            return Ok(None);
+        }
+        elms.push(v);
+    }
+
+    let ary_cls = require_class3(None, b"[Ljava/net/URL;").unwrap();
+    Ok(Some(Oop::new_ref_ary2(ary_cls, elms)))
+}
+
+fn jvm_getLookupCacheForClassLoader(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let indices = match get_loader(args.get(0).unwrap()) {
+        Loader::App => {
+            let name = args.get(1).unwrap();
+            let name = OopRef::java_lang_string(name.extract_ref());
+            runtime::lookup_resource(&name)
+        }
+        Loader::Ext => vec![],
+        Loader::Other => return Ok(Some(oop::consts::get_null())),
+    };
+
+    let indices = indices.iter().map(|&i| i as i32).collect();
+    Ok(Some(Oop::new_int_ary2(indices)))
+}
+
+fn jvm_knownToNotExist0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let v = match get_loader(args.get(0).unwrap()) {
+        Loader::App => {
+            let name = args.get(1).unwrap();
+            let name = OopRef::java_lang_string(name.extract_ref());
+            runtime::known_to_not_exist(&name)
+        }
+        _ => false,
+    };
+
+    Ok(Some(Oop::new_int(v as i32)))
 }
 
-fn jvm_getLookupCacheURLs(_env: JNIEnv, _args: &[Oop]) -> JNIResult {
-    Ok(Some(oop::consts::get_null()))
+fn get_loader(loader: &Oop) -> Loader {
+    match loader {
+        Oop::Null => Loader::Other,
+        loader => {
+            let rf = loader.extract_ref();
+            let cls = rf.extract_inst().class.clone();
+            let cls = cls.get_class();
+            match cls.name.as_slice() {
+                APP_LOADER => Loader::App,
+                EXT_LOADER => Loader::Ext,
+                _ => Loader::Other,
+            }
+        }
+    }
 }
@@ -7,6 +7,8 @@ use crate::runtime::require_class3;
 use crate::util;
 use classfile::flags::ACC_STATIC;
 use std::os::raw::c_void;
+use std::sync::atomic::{fence, Ordering};
+use std::sync::Arc;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 pub fn get_native_methods() -> Vec<JNINativeMethod> {
@@ -45,8 +47,21 @@ pub fn get_native_methods() -> Vec<JNINativeMethod> {
         ),
         new_fn("allocateMemory", "(J)J", Box::new(jvm_allocateMemory)),
         new_fn("freeMemory", "(J)V", Box::new(jvm_freeMemory)),
+        new_fn("reallocateMemory", "(JJ)J", Box::new(jvm_reallocateMemory)),
         new_fn("putLong", "(JJ)V", Box::new(jvm_putLong)),
+        new_fn("getLong", "(J)J", Box::new(jvm_getLong)),
+        new_fn("putByte", "(JB)V", Box::new(jvm_putByte2)),
         new_fn("getByte", "(J)B", Box::new(jvm_getByte)),
+        new_fn("putShort", "(JS)V", Box::new(jvm_putShort2)),
+        new_fn("getShort", "(J)S", Box::new(jvm_getShort2)),
+        new_fn("putInt", "(JI)V", Box::new(jvm_putInt2)),
+        new_fn("getInt", "(J)I", Box::new(jvm_getInt2)),
+        new_fn("putFloat", "(JF)V", Box::new(jvm_putFloat2)),
+        new_fn("getFloat", "(J)F", Box::new(jvm_getFloat2)),
+        new_fn("putDouble", "(JD)V", Box::new(jvm_putDouble2)),
+        new_fn("getDouble", "(J)D", Box::new(jvm_getDouble2)),
+        new_fn("putAddress", "(JJ)V", Box::new(jvm_putLong)),
+        new_fn("getAddress", "(J)J", Box::new(jvm_getLong)),
         new_fn(
             "compareAndSwapLong",
             "(Ljava/lang/Object;JJJ)Z",
@@ -98,6 +113,82 @@ pub fn get_native_methods() -> Vec<JNINativeMethod> {
         new_fn("putByte", "(Ljava/lang/Object;JB)V", Box::new(jvm_putByte)),
         new_fn("getByte", "(Ljava/lang/Object;J)B", Box::new(jvm_getByte2)),
         new_fn("park", "(ZJ)V", Box::new(jvm_park)),
+        new_fn(
+            "putIntVolatile",
+            "(Ljava/lang/Object;JI)V",
+            Box::new(jvm_putIntVolatile),
+        ),
+        new_fn(
+            "putLongVolatile",
+            "(Ljava/lang/Object;JJ)V",
+            Box::new(jvm_putLongVolatile),
+        ),
+        new_fn(
+            "putObjectVolatile",
+            "(Ljava/lang/Object;JLjava/lang/Object;)V",
+            Box::new(jvm_putObjectVolatile),
+        ),
+        new_fn(
+            "putOrderedInt",
+            "(Ljava/lang/Object;JI)V",
+            Box::new(jvm_putOrderedInt),
+        ),
+        new_fn(
+            "putOrderedLong",
+            "(Ljava/lang/Object;JJ)V",
+            Box::new(jvm_putOrderedLong),
+        ),
+        new_fn(
+            "putOrderedObject",
+            "(Ljava/lang/Object;JLjava/lang/Object;)V",
+            Box::new(jvm_putOrderedObject),
+        ),
+        new_fn("getInt", "(Ljava/lang/Object;J)I", Box::new(jvm_getInt)),
+        new_fn("putInt", "(Ljava/lang/Object;JI)V", Box::new(jvm_putInt)),
+        new_fn("getLong", "(Ljava/lang/Object;J)J", Box::new(jvm_getLong2)),
+        new_fn("putLong", "(Ljava/lang/Object;JJ)V", Box::new(jvm_putLong2)),
+        new_fn("getFloat", "(Ljava/lang/Object;J)F", Box::new(jvm_getFloat)),
+        new_fn(
+            "putFloat",
+            "(Ljava/lang/Object;JF)V",
+            Box::new(jvm_putFloat),
+        ),
+        new_fn(
+            "getDouble",
+            "(Ljava/lang/Object;J)D",
+            Box::new(jvm_getDouble),
+        ),
+        new_fn(
+            "putDouble",
+            "(Ljava/lang/Object;JD)V",
+            Box::new(jvm_putDouble),
+        ),
+        new_fn(
+            "getBoolean",
+            "(Ljava/lang/Object;J)Z",
+            Box::new(jvm_getBoolean),
+        ),
+        new_fn(
+            "putBoolean",
+            "(Ljava/lang/Object;JZ)V",
+            Box::new(jvm_putBoolean),
+        ),
+        new_fn("getShort", "(Ljava/lang/Object;J)S", Box::new(jvm_getShort)),
+        new_fn(
+            "putShort",
+            "(Ljava/lang/Object;JS)V",
+            Box::new(jvm_putShort),
+        ),
+        new_fn("getChar", "(Ljava/lang/Object;J)C", Box::new(jvm_getChar2)),
+        new_fn("putChar", "(Ljava/lang/Object;JC)V", Box::new(jvm_putChar2)),
+        new_fn(
+            "getObject",
+            "(Ljava/lang/Object;J)Ljava/lang/Object;",
+            Box::new(jvm_getObject),
+        ),
+        new_fn("loadFence", "()V", Box::new(jvm_loadFence)),
+        new_fn("storeFence", "()V", Box::new(jvm_storeFence)),
+        new_fn("fullFence", "()V", Box::new(jvm_fullFence)),
     ]
 }
 
@@ -123,47 +214,29 @@ fn jvm_objectFieldOffset(_env: JNIEnv, args: &[Oop]) -> JNIResult {
     objectFieldOffset(field, false)
 }
 
-// fixme: The semantic requirement here is atomic operation, which needs to be re-implemented here
 fn jvm_compareAndSwapObject(_env: JNIEnv, args: &[Oop]) -> JNIResult {
     let owner = args.get(1).unwrap();
     let offset = args.get(2).unwrap().extract_long();
     let old_data = args.get(3).unwrap();
     let new_data = args.get(4).unwrap();
 
-    let v_at_offset = Class::get_field_value2(owner.extract_ref(), offset as usize);
-
-    if OopRef::is_eq(&v_at_offset, old_data) {
-        Class::put_field_value2(owner.extract_ref(), offset as usize, new_data.clone());
-        Ok(Some(Oop::new_int(1)))
-    } else {
-        Ok(Some(Oop::new_int(0)))
-    }
+    compare_and_swap(owner, offset, old_data, new_data)
 }
 
 fn jvm_getIntVolatile(_env: JNIEnv, args: &[Oop]) -> JNIResult {
     let owner = args.get(1).unwrap();
     let offset = args.get(2).unwrap().extract_long();
-    let v = Class::get_field_value2(owner.extract_ref(), offset as usize);
+    let v = Class::get_field_value2_volatile(owner.extract_ref(), offset as usize);
     Ok(Some(v))
 }
 
 fn jvm_compareAndSwapInt(_env: JNIEnv, args: &[Oop]) -> JNIResult {
     let owner = args.get(1).unwrap();
     let offset = args.get(2).unwrap().extract_long();
-    let old_data = args.get(3).unwrap().extract_int();
+    let old_data = args.get(3).unwrap();
     let new_data = args.get(4).unwrap();
 
-    let v_at_offset = {
-        let v = Class::get_field_value2(owner.extract_ref(), offset as usize);
-        v.extract_int()
-    };
-
-    if v_at_offset == old_data {
-        Class::put_field_value2(owner.extract_ref(), offset as usize, new_data.clone());
-        Ok(Some(Oop::new_int(1)))
-    } else {
-        Ok(Some(Oop::new_int(0)))
-    }
+    compare_and_swap(owner, offset, old_data, new_data)
 }
 
 fn jvm_allocateMemory(_env: JNIEnv, args: &[Oop]) -> JNIResult {
@@ -184,10 +257,18 @@ fn jvm_freeMemory(_env: JNIEnv, args: &[Oop]) -> JNIResult {
     Ok(None)
 }
 
+fn jvm_reallocateMemory(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let ptr = args.get(1).unwrap().extract_long() as *mut libc::c_void;
+    let size = args.get(2).unwrap().extract_long() as usize;
+    let v = unsafe { libc::realloc(ptr, size) } as i64;
+    Ok(Some(Oop::new_long(v)))
+}
+
+//raw memory in the native byte order, shared with os structs like iovec
 fn jvm_putLong(_env: JNIEnv, args: &[Oop]) -> JNIResult {
     let ptr = args.get(1).unwrap().extract_long() as *mut libc::c_void;
     let l = args.get(2).unwrap().extract_long();
-    let v = l.to_be_bytes();
+    let v = l.to_ne_bytes();
     unsafe {
         libc::memcpy(ptr, v.as_ptr() as *const c_void, 8);
     }
@@ -195,35 +276,85 @@ fn jvm_putLong(_env: JNIEnv, args: &[Oop]) -> JNIResult {
     Ok(None)
 }
 
+fn jvm_getLong(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let mut v = [0u8; 8];
+    read_raw(args, &mut v);
+    Ok(Some(Oop::new_long(i64::from_ne_bytes(v))))
+}
+
+fn jvm_putByte2(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let v = args.get(2).unwrap().extract_int() as u8;
+    write_raw(args, &[v]);
+    Ok(None)
+}
+
 fn jvm_getByte(_env: JNIEnv, args: &[Oop]) -> JNIResult {
     let ptr = args.get(1).unwrap().extract_long() as *const u8;
     let v = unsafe { *ptr };
-    Ok(Some(Oop::new_int(v as i32)))
+    Ok(Some(Oop::new_int(v as i8 as i32)))
+}
+
+fn jvm_putShort2(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let v = args.get(2).unwrap().extract_int() as i16;
+    write_raw(args, &v.to_ne_bytes());
+    Ok(None)
+}
+
+fn jvm_getShort2(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let mut v = [0u8; 2];
+    read_raw(args, &mut v);
+    Ok(Some(Oop::new_int(i16::from_ne_bytes(v) as i32)))
+}
+
+fn jvm_putInt2(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let v = args.get(2).unwrap().extract_int();
+    write_raw(args, &v.to_ne_bytes());
+    Ok(None)
+}
+
+fn jvm_getInt2(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let mut v = [0u8; 4];
+    read_raw(args, &mut v);
+    Ok(Some(Oop::new_int(i32::from_ne_bytes(v))))
+}
+
+fn jvm_putFloat2(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let v = args.get(2).unwrap().extract_float();
+    write_raw(args, &v.to_bits().to_ne_bytes());
+    Ok(None)
+}
+
+fn jvm_getFloat2(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let mut v = [0u8; 4];
+    read_raw(args, &mut v);
+    Ok(Some(Oop::new_float(f32::from_bits(u32::from_ne_bytes(v)))))
+}
+
+fn jvm_putDouble2(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let v = args.get(2).unwrap().extract_double();
+    write_raw(args, &v.to_bits().to_ne_bytes());
+    Ok(None)
+}
+
+fn jvm_getDouble2(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let mut v = [0u8; 8];
+    read_raw(args, &mut v);
+    Ok(Some(Oop::new_double(f64::from_bits(u64::from_ne_bytes(v)))))
 }
 
 fn jvm_compareAndSwapLong(_env: JNIEnv, args: &[Oop]) -> JNIResult {
     let owner = args.get(1).unwrap();
     let offset = args.get(2).unwrap().extract_long();
-    let old_data = args.get(3).unwrap().extract_long();
+    let old_data = args.get(3).unwrap();
     let new_data = args.get(4).unwrap();
 
-    let v_at_offset = {
-        let v = Class::get_field_value2(owner.extract_ref(), offset as usize);
-        v.extract_long()
-    };
-
-    if v_at_offset == old_data {
-        Class::put_field_value2(owner.extract_ref(), offset as usize, new_data.clone());
-        Ok(Some(Oop::new_int(1)))
-    } else {
-        Ok(Some(Oop::new_int(0)))
-    }
+    compare_and_swap(owner, offset, old_data, new_data)
 }
 
 fn jvm_getObjectVolatile(_env: JNIEnv, args: &[Oop]) -> JNIResult {
     let owner = args.get(1).unwrap();
     let offset = args.get(2).unwrap().extract_long();
-    let v_at_offset = Class::get_field_value2(owner.extract_ref(), offset as usize);
+    let v_at_offset = Class::get_field_value2_volatile(owner.extract_ref(), offset as usize);
     Ok(Some(v_at_offset))
 }
 
@@ -234,7 +365,7 @@ fn jvm_pageSize(_env: JNIEnv, _args: &[Oop]) -> JNIResult {
 fn jvm_getLongVolatile(_env: JNIEnv, args: &[Oop]) -> JNIResult {
     let owner = args.get(1).unwrap();
     let offset = args.get(2).unwrap().extract_long();
-    let v_at_offset = Class::get_field_value2(owner.extract_ref(), offset as usize);
+    let v_at_offset = Class::get_field_value2_volatile(owner.extract_ref(), offset as usize);
     Ok(Some(v_at_offset))
 }
 
@@ -268,13 +399,8 @@ fn jvm_setMemory(_env: JNIEnv, args: &[Oop]) -> JNIResult {
 }
 
 fn jvm_putChar(_env: JNIEnv, args: &[Oop]) -> JNIResult {
-    let dest = args.get(1).unwrap().extract_long() as *mut libc::c_void;
-    let value = args.get(2).unwrap().extract_int();
-
-    unsafe {
-        libc::memset(dest, value, 1);
-    }
-
+    let v = args.get(2).unwrap().extract_int() as u16;
+    write_raw(args, &v.to_ne_bytes());
     Ok(None)
 }
 
@@ -328,6 +454,14 @@ fn jvm_copyMemory(_env: JNIEnv, args: &[Oop]) -> JNIResult {
                     }
                 }
                 //byte[] -> byte[]
+                Oop::Ref(dest) if Arc::ptr_eq(src, dest) => {
+                    java_lang_System::arraycopy_same_obj(
+                        src.clone(),
+                        src_offset,
+                        dest_offset,
+                        size,
+                    );
+                }
                 Oop::Ref(dest) => {
                     java_lang_System::arraycopy_diff_obj(
                         src.clone(),
@@ -347,10 +481,9 @@ fn jvm_copyMemory(_env: JNIEnv, args: &[Oop]) -> JNIResult {
 }
 
 fn jvm_getChar(_env: JNIEnv, args: &[Oop]) -> JNIResult {
-    let arg1 = args.get(1).unwrap();
-    let ptr = arg1.extract_long() as *const u16;
-    let v = unsafe { *ptr };
-    Ok(Some(Oop::new_int(v as i32)))
+    let mut v = [0u8; 2];
+    read_raw(args, &mut v);
+    Ok(Some(Oop::new_int(u16::from_ne_bytes(v) as i32)))
 }
 
 fn jvm_putObject(_env: JNIEnv, args: &[Oop]) -> JNIResult {
@@ -360,7 +493,7 @@ fn jvm_putObject(_env: JNIEnv, args: &[Oop]) -> JNIResult {
     let x = args.get(3).unwrap();
 
     let rf = o.extract_ref();
-    Class::put_field_value2(rf, offset, x.clone());
+    Class::put_field_value2_locked(rf, offset, x.clone());
     Ok(None)
 }
 
@@ -369,8 +502,7 @@ fn jvm_ensureClassInitialized(_env: JNIEnv, args: &[Oop]) -> JNIResult {
     let rf = clazz.extract_ref();
     let mirror = rf.extract_mirror();
     let target = mirror.target.clone().unwrap();
-    oop::class::init_class(&target);
-    oop::class::init_class_fully(&target);
+    oop::class::init_class(&target)?;
     Ok(None)
 }
 
@@ -401,11 +533,12 @@ fn jvm_putByte(_env: JNIEnv, args: &[Oop]) -> JNIResult {
                 libc::memset(dest, x, 1);
             }
         }
-        Oop::Ref(rf) => {
+        Oop::Ref(rf) if is_type_array(rf) => {
             let ary = rf.extract_mut_type_array();
             let bytes = ary.extract_mut_bytes();
             bytes[offset] = x as u8;
         }
+        Oop::Ref(rf) => Class::put_field_value2_locked(rf.clone(), offset, Oop::new_int(x)),
         t => unimplemented!("{:?}", t),
     }
 
@@ -423,12 +556,14 @@ fn jvm_getByte2(_env: JNIEnv, args: &[Oop]) -> JNIResult {
             let v = unsafe { *ptr };
             Oop::new_int(v as i32)
         }
-        Oop::Ref(rf) => {
+        Oop::Ref(rf) if is_type_array(rf) => {
             let ary = rf.extract_mut_type_array();
             let bytes = ary.extract_bytes();
             let v = bytes[offset];
             Oop::new_int(v as i32)
         }
+        //byte field
+        Oop::Ref(rf) => Class::get_field_value2_locked(rf.clone(), offset),
         t => unimplemented!("{:?}", t),
     };
 
@@ -455,8 +590,178 @@ fn jvm_park(_env: JNIEnv, args: &[Oop]) -> JNIResult {
     Ok(None)
 }
 
+fn jvm_putIntVolatile(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    put_volatile(args);
+    Ok(None)
+}
+
+fn jvm_putLongVolatile(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    put_volatile(args);
+    Ok(None)
+}
+
+fn jvm_putObjectVolatile(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    put_volatile(args);
+    Ok(None)
+}
+
+fn jvm_putOrderedInt(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    put_ordered(args);
+    Ok(None)
+}
+
+fn jvm_putOrderedLong(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    put_ordered(args);
+    Ok(None)
+}
+
+fn jvm_putOrderedObject(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    put_ordered(args);
+    Ok(None)
+}
+
+fn jvm_getInt(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    Ok(Some(get_field(args)))
+}
+
+fn jvm_putInt(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    put_field(args);
+    Ok(None)
+}
+
+fn jvm_getLong2(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    Ok(Some(get_field(args)))
+}
+
+fn jvm_putLong2(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    put_field(args);
+    Ok(None)
+}
+
+fn jvm_getFloat(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    Ok(Some(get_field(args)))
+}
+
+fn jvm_putFloat(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    put_field(args);
+    Ok(None)
+}
+
+fn jvm_getDouble(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    Ok(Some(get_field(args)))
+}
+
+fn jvm_putDouble(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    put_field(args);
+    Ok(None)
+}
+
+fn jvm_getBoolean(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    Ok(Some(get_field(args)))
+}
+
+fn jvm_putBoolean(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    put_field(args);
+    Ok(None)
+}
+
+fn jvm_getShort(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    Ok(Some(get_field(args)))
+}
+
+fn jvm_putShort(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    put_field(args);
+    Ok(None)
+}
+
+fn jvm_getChar2(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    Ok(Some(get_field(args)))
+}
+
+fn jvm_putChar2(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    put_field(args);
+    Ok(None)
+}
+
+fn jvm_getObject(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    Ok(Some(get_field(args)))
+}
+
+fn jvm_loadFence(_env: JNIEnv, _args: &[Oop]) -> JNIResult {
+    fence(Ordering::Acquire);
+    Ok(None)
+}
+
+fn jvm_storeFence(_env: JNIEnv, _args: &[Oop]) -> JNIResult {
+    fence(Ordering::Release);
+    Ok(None)
+}
+
+fn jvm_fullFence(_env: JNIEnv, _args: &[Oop]) -> JNIResult {
+    fence(Ordering::SeqCst);
+    Ok(None)
+}
+
 ////////helper
 
+fn compare_and_swap(owner: &Oop, offset: i64, expected: &Oop, v: &Oop) -> JNIResult {
+    let rf = owner.extract_ref();
+    let r = Class::compare_and_swap_field_value2(rf, offset as usize, expected, v.clone());
+    Ok(Some(Oop::new_int(r as i32)))
+}
+
+//(J)?, the bytes at the address
+fn read_raw(args: &[Oop], v: &mut [u8]) {
+    let ptr = args.get(1).unwrap().extract_long() as *const u8;
+    unsafe {
+        std::ptr::copy_nonoverlapping(ptr, v.as_mut_ptr(), v.len());
+    }
+}
+
+//(J?)V
+fn write_raw(args: &[Oop], v: &[u8]) {
+    let ptr = args.get(1).unwrap().extract_long() as *mut u8;
+    unsafe {
+        std::ptr::copy_nonoverlapping(v.as_ptr(), ptr, v.len());
+    }
+}
+
+fn is_type_array(rf: &OopRef) -> bool {
+    let ptr = rf.get_raw_ptr();
+    unsafe { matches!(&(*ptr).v, oop::RefKind::TypeArray(_)) }
+}
+
+//(Ljava/lang/Object;J)?, fields of object, or static fields with the mirror as base
+fn get_field(args: &[Oop]) -> Oop {
+    let owner = args.get(1).unwrap();
+    let offset = args.get(2).unwrap().extract_long();
+    Class::get_field_value2_locked(owner.extract_ref(), offset as usize)
+}
+
+//(Ljava/lang/Object;J?)V
+fn put_field(args: &[Oop]) {
+    let owner = args.get(1).unwrap();
+    let offset = args.get(2).unwrap().extract_long();
+    let x = args.get(3).unwrap();
+    Class::put_field_value2_locked(owner.extract_ref(), offset as usize, x.clone());
+}
+
+//(Ljava/lang/Object;J?)V
+fn put_volatile(args: &[Oop]) {
+    let owner = args.get(1).unwrap();
+    let offset = args.get(2).unwrap().extract_long();
+    let x = args.get(3).unwrap();
+    Class::put_field_value2_volatile(owner.extract_ref(), offset as usize, x.clone());
+}
+
+//(Ljava/lang/Object;J?)V
+fn put_ordered(args: &[Oop]) {
+    let owner = args.get(1).unwrap();
+    let offset = args.get(2).unwrap().extract_long();
+    let x = args.get(3).unwrap();
+    Class::put_field_value2_ordered(owner.extract_ref(), offset as usize, x.clone());
+}
+
 fn objectFieldOffset(field: &Oop, is_static: bool) -> JNIResult {
     let cls = require_class3(None, b"java/lang/reflect/Field").unwrap();
 
@@ -474,8 +779,14 @@ fn objectFieldOffset(field: &Oop, is_static: bool) -> JNIResult {
         let cls = cls.get_class();
         let id = cls.get_field_id(&util::S_SLOT, &util::S_I, false);
         let v = Class::get_field_value(field.extract_ref(), id);
-        v.extract_int()
+        v.extract_int() as usize
+    };
+
+    let offset = if is_static {
+        slot | oop::class::STATIC_FIELD_OFFSET_MARK
+    } else {
+        slot
     };
 
-    Ok(Some(Oop::new_long(slot as i64)))
+    Ok(Some(Oop::new_long(offset as i64)))
 }
@@ -0,0 +1,117 @@
+#![allow(non_snake_case)]
+
+use crate::native::common::nio_util;
+use crate::native::{new_fn, JNIEnv, JNINativeMethod, JNIResult};
+use crate::oop::Oop;
+use std::time::Instant;
+
+/*
+The epoll of EPollSelectorImpl, the default Selector of linux.
+
+The array of epoll_event allocated by Unsafe, read back by getInt at
+sizeofEPollEvent & offsetofData, so kept in the layout of os.
+*/
+
+pub fn get_native_methods() -> Vec<JNINativeMethod> {
+    vec![
+        new_fn("init", "()V", Box::new(jvm_init)),
+        new_fn("epollCreate", "()I", Box::new(jvm_epollCreate)),
+        new_fn("epollCtl", "(IIII)V", Box::new(jvm_epollCtl)),
+        new_fn("epollWait", "(JIJI)I", Box::new(jvm_epollWait)),
+        new_fn("sizeofEPollEvent", "()I", Box::new(jvm_sizeofEPollEvent)),
+        new_fn("offsetofData", "()I", Box::new(jvm_offsetofData)),
+        new_fn("interrupt", "(I)V", Box::new(jvm_interrupt)),
+    ]
+}
+
+fn jvm_init(_env: JNIEnv, _args: &[Oop]) -> JNIResult {
+    Ok(None)
+}
+
+fn jvm_epollCreate(_env: JNIEnv, _args: &[Oop]) -> JNIResult {
+    //the size hint ignored by the kernels since 2.6.8
+    let epfd = unsafe { libc::epoll_create(256) };
+    if epfd < 0 {
+        return Err(nio_util::new_io_ex_errno("epoll_create failed"));
+    }
+    unsafe {
+        libc::fcntl(epfd, libc::F_SETFD, libc::FD_CLOEXEC);
+    }
+
+    Ok(Some(Oop::new_int(epfd)))
+}
+
+//(epfd, opcode, fd, events)
+fn jvm_epollCtl(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let epfd = args.get(1).unwrap().extract_int();
+    let opcode = args.get(2).unwrap().extract_int();
+    let fd = args.get(3).unwrap().extract_int();
+    let events = args.get(4).unwrap().extract_int();
+
+    let mut event = libc::epoll_event {
+        events: events as u32,
+        u64: fd as u64,
+    };
+    let r = unsafe { libc::epoll_ctl(epfd, opcode, fd, &mut event) };
+
+    //a channel registered with several selectors closed by the last one,
+    //the updates pending in the others end with EPOLL_CTL_DEL anyway
+    if r < 0 {
+        let errno = nio_util::errno();
+        if errno != libc::EBADF && errno != libc::ENOENT && errno != libc::EPERM {
+            return Err(nio_util::new_io_ex_errno("epoll_ctl failed"));
+        }
+    }
+
+    Ok(None)
+}
+
+//(address, numfds, timeout, epfd), timeout in millis, -1 forever
+fn jvm_epollWait(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let address = args.get(1).unwrap().extract_long() as *mut libc::epoll_event;
+    let numfds = args.get(2).unwrap().extract_int();
+    let timeout = args.get(3).unwrap().extract_long();
+    let epfd = args.get(4).unwrap().extract_int();
+
+    let start = Instant::now();
+    let mut remaining = timeout;
+    loop {
+        let r = unsafe { libc::epoll_wait(epfd, address, numfds, remaining as i32) };
+        if r >= 0 {
+            return Ok(Some(Oop::new_int(r)));
+        }
+        if nio_util::errno() != libc::EINTR {
+            return Err(nio_util::new_io_ex_errno("epoll_wait failed"));
+        }
+
+        if timeout >= 0 {
+            let elapsed = start.elapsed().as_millis() as i64;
+            if elapsed >= timeout {
+                return Ok(Some(Oop::new_int(0)));
+            }
+            remaining = timeout - elapsed;
+        }
+    }
+}
+
+fn jvm_sizeofEPollEvent(_env: JNIEnv, _args: &[Oop]) -> JNIResult {
+    let v = std::mem::size_of::<libc::epoll_event>();
+    Ok(Some(Oop::new_int(v as i32)))
+}
+
+//packed on x86_64, the data right after the u32 events
+fn jvm_offsetofData(_env: JNIEnv, _args: &[Oop]) -> JNIResult {
+    let v = std::mem::size_of::<libc::epoll_event>() - std::mem::size_of::<u64>();
+    Ok(Some(Oop::new_int(v as i32)))
+}
+
+//wake up the selector, a byte to the pipe it polls
+fn jvm_interrupt(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let fd = args.get(0).unwrap().extract_int();
+
+    let buf = [1u8];
+    if unsafe { libc::write(fd, buf.as_ptr() as *const libc::c_void, 1) } < 0 {
+        return Err(nio_util::new_io_ex_errno("write to interrupt fd failed"));
+    }
+    Ok(None)
+}
@@ -0,0 +1,128 @@
+#![allow(non_snake_case)]
+
+use crate::native::common::{io_util, nio_util};
+use crate::native::{new_fn, JNIEnv, JNINativeMethod, JNIResult};
+use crate::oop::Oop;
+use crate::runtime;
+use classfile::consts as cls_consts;
+
+//sun.nio.ch.FileChannelImpl
+const MAP_RO: i32 = 0;
+const MAP_RW: i32 = 1;
+const MAP_PV: i32 = 2;
+
+lazy_static! {
+    static ref FILE_CHANNEL_FD: usize = io_util::stream_fd_offset(b"sun/nio/ch/FileChannelImpl");
+}
+
+pub fn get_native_methods() -> Vec<JNINativeMethod> {
+    vec![
+        new_fn("initIDs", "()J", Box::new(jvm_initIDs)),
+        new_fn("map0", "(IJJ)J", Box::new(jvm_map0)),
+        new_fn("unmap0", "(JJ)I", Box::new(jvm_unmap0)),
+        new_fn(
+            "position0",
+            "(Ljava/io/FileDescriptor;J)J",
+            Box::new(jvm_position0),
+        ),
+        new_fn(
+            "transferTo0",
+            "(Ljava/io/FileDescriptor;JJLjava/io/FileDescriptor;)J",
+            Box::new(jvm_transferTo0),
+        ),
+    ]
+}
+
+//the page size, the alignment of map0
+fn jvm_initIDs(_env: JNIEnv, _args: &[Oop]) -> JNIResult {
+    let v = unsafe { libc::sysconf(libc::_SC_PAGESIZE) };
+    Ok(Some(Oop::new_long(v as i64)))
+}
+
+//(prot, pos, len), pos aligned to the page size by FileChannelImpl.map
+fn jvm_map0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let this = args.get(0).unwrap();
+    let prot = args.get(1).unwrap().extract_int();
+    let pos = args.get(2).unwrap().extract_long();
+    let len = args.get(3).unwrap().extract_long();
+
+    let fd = io_util::get_fd(this, *FILE_CHANNEL_FD);
+    let (protections, flags) = match prot {
+        MAP_RO => (libc::PROT_READ, libc::MAP_SHARED),
+        MAP_RW => (libc::PROT_READ | libc::PROT_WRITE, libc::MAP_SHARED),
+        MAP_PV => (libc::PROT_READ | libc::PROT_WRITE, libc::MAP_PRIVATE),
+        _ => unreachable!(),
+    };
+
+    let addr = unsafe {
+        libc::mmap(
+            std::ptr::null_mut(),
+            len as usize,
+            protections,
+            flags,
+            fd,
+            pos as libc::off_t,
+        )
+    };
+    if addr == libc::MAP_FAILED {
+        if nio_util::errno() == libc::ENOMEM {
+            let ex = runtime::exception::new(cls_consts::J_OOM, Some("Map failed".to_string()));
+            return Err(ex);
+        }
+        return Err(nio_util::new_io_ex_errno("Map failed"));
+    }
+
+    Ok(Some(Oop::new_long(addr as i64)))
+}
+
+fn jvm_unmap0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let addr = args.get(0).unwrap().extract_long() as *mut libc::c_void;
+    let len = args.get(1).unwrap().extract_long();
+
+    if unsafe { libc::munmap(addr, len as usize) } == -1 {
+        return Err(nio_util::new_io_ex_errno("Unmap failed"));
+    }
+    Ok(Some(Oop::new_int(0)))
+}
+
+//offset -1 to get the position, or seek to it
+fn jvm_position0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let fd = nio_util::fd_val(args.get(1).unwrap());
+    let offset = args.get(2).unwrap().extract_long();
+
+    let v = if offset < 0 {
+        unsafe { libc::lseek(fd, 0, libc::SEEK_CUR) }
+    } else {
+        unsafe { libc::lseek(fd, offset as libc::off_t, libc::SEEK_SET) }
+    };
+    let v = nio_util::convert_return(v as isize, false)?;
+    Ok(Some(Oop::new_long(v)))
+}
+
+//sendfile of linux, else transferred by FileChannelImpl with buffers
+fn jvm_transferTo0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let src = nio_util::fd_val(args.get(1).unwrap());
+    let position = args.get(2).unwrap().extract_long();
+    let count = args.get(3).unwrap().extract_long();
+    let dst = nio_util::fd_val(args.get(4).unwrap());
+
+    #[cfg(target_os = "linux")]
+    {
+        let mut offset = position as libc::off_t;
+        let n = unsafe { libc::sendfile(dst, src, &mut offset, count as usize) };
+        if n == -1 {
+            let errno = nio_util::errno();
+            if errno == libc::EINVAL || errno == libc::ENOSYS {
+                return Ok(Some(Oop::new_long(nio_util::IOS_UNSUPPORTED_CASE)));
+            }
+        }
+        let v = nio_util::convert_return(n, false)?;
+        Ok(Some(Oop::new_long(v)))
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = (src, position, count, dst);
+        Ok(Some(Oop::new_long(nio_util::IOS_UNSUPPORTED_CASE)))
+    }
+}
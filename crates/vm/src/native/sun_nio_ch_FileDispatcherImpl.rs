@@ -0,0 +1,294 @@
+#![allow(non_snake_case)]
+
+use crate::native::common::{io_util, nio_util};
+use crate::native::{new_fn, JNIEnv, JNINativeMethod, JNIResult};
+use crate::oop::Oop;
+use std::sync::atomic::{AtomicI32, Ordering};
+
+/*
+read & write of FileChannel & the pipes, all static natives taking a
+FileDescriptor, results as sun.nio.ch.IOStatus.
+*/
+
+//sun.nio.ch.FileDispatcher
+const NO_LOCK: i32 = -1;
+const LOCKED: i32 = 0;
+const INTERRUPTED: i32 = 2;
+
+//a socket shut down for both directions, dup2 onto a fd being closed so
+//the threads blocked on it wake up, and the fd not reused before close
+static PRE_CLOSE_FD: AtomicI32 = AtomicI32::new(-1);
+
+pub fn get_native_methods() -> Vec<JNINativeMethod> {
+    vec![
+        new_fn("init", "()V", Box::new(jvm_init)),
+        new_fn(
+            "read0",
+            "(Ljava/io/FileDescriptor;JI)I",
+            Box::new(jvm_read0),
+        ),
+        new_fn(
+            "pread0",
+            "(Ljava/io/FileDescriptor;JIJ)I",
+            Box::new(jvm_pread0),
+        ),
+        new_fn(
+            "readv0",
+            "(Ljava/io/FileDescriptor;JI)J",
+            Box::new(jvm_readv0),
+        ),
+        new_fn(
+            "write0",
+            "(Ljava/io/FileDescriptor;JI)I",
+            Box::new(jvm_write0),
+        ),
+        new_fn(
+            "pwrite0",
+            "(Ljava/io/FileDescriptor;JIJ)I",
+            Box::new(jvm_pwrite0),
+        ),
+        new_fn(
+            "writev0",
+            "(Ljava/io/FileDescriptor;JI)J",
+            Box::new(jvm_writev0),
+        ),
+        new_fn(
+            "force0",
+            "(Ljava/io/FileDescriptor;Z)I",
+            Box::new(jvm_force0),
+        ),
+        new_fn(
+            "truncate0",
+            "(Ljava/io/FileDescriptor;J)I",
+            Box::new(jvm_truncate0),
+        ),
+        new_fn("size0", "(Ljava/io/FileDescriptor;)J", Box::new(jvm_size0)),
+        new_fn(
+            "lock0",
+            "(Ljava/io/FileDescriptor;ZJJZ)I",
+            Box::new(jvm_lock0),
+        ),
+        new_fn(
+            "release0",
+            "(Ljava/io/FileDescriptor;JJ)V",
+            Box::new(jvm_release0),
+        ),
+        new_fn(
+            "close0",
+            "(Ljava/io/FileDescriptor;)V",
+            Box::new(jvm_close0),
+        ),
+        new_fn(
+            "preClose0",
+            "(Ljava/io/FileDescriptor;)V",
+            Box::new(jvm_preClose0),
+        ),
+        new_fn("closeIntFD", "(I)V", Box::new(jvm_closeIntFD)),
+    ]
+}
+
+fn jvm_init(_env: JNIEnv, _args: &[Oop]) -> JNIResult {
+    let mut sp = [0i32; 2];
+    if unsafe { libc::socketpair(libc::AF_UNIX, libc::SOCK_STREAM, 0, sp.as_mut_ptr()) } == -1 {
+        return Err(nio_util::new_io_ex_errno("socketpair failed"));
+    }
+    unsafe {
+        libc::fcntl(sp[0], libc::F_SETFD, libc::FD_CLOEXEC);
+        libc::shutdown(sp[0], libc::SHUT_RDWR);
+        libc::close(sp[1]);
+    }
+    PRE_CLOSE_FD.store(sp[0], Ordering::Relaxed);
+
+    Ok(None)
+}
+
+fn jvm_read0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let fd = nio_util::fd_val(args.get(0).unwrap());
+    let address = args.get(1).unwrap().extract_long() as *mut libc::c_void;
+    let len = args.get(2).unwrap().extract_int() as usize;
+
+    let n = unsafe { libc::read(fd, address, len) };
+    let v = nio_util::convert_return(n, true)?;
+    Ok(Some(Oop::new_int(v as i32)))
+}
+
+fn jvm_pread0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let fd = nio_util::fd_val(args.get(0).unwrap());
+    let address = args.get(1).unwrap().extract_long() as *mut libc::c_void;
+    let len = args.get(2).unwrap().extract_int() as usize;
+    let offset = args.get(3).unwrap().extract_long();
+
+    let n = unsafe { libc::pread(fd, address, len, offset as libc::off_t) };
+    let v = nio_util::convert_return(n, true)?;
+    Ok(Some(Oop::new_int(v as i32)))
+}
+
+//'address' of an array of iovec, built by IOVecWrapper
+fn jvm_readv0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let fd = nio_util::fd_val(args.get(0).unwrap());
+    let address = args.get(1).unwrap().extract_long() as *const libc::iovec;
+    let len = args.get(2).unwrap().extract_int();
+
+    let n = unsafe { libc::readv(fd, address, len) };
+    let v = nio_util::convert_return(n, true)?;
+    Ok(Some(Oop::new_long(v)))
+}
+
+fn jvm_write0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let fd = nio_util::fd_val(args.get(0).unwrap());
+    let address = args.get(1).unwrap().extract_long() as *const libc::c_void;
+    let len = args.get(2).unwrap().extract_int() as usize;
+
+    let n = unsafe { libc::write(fd, address, len) };
+    let v = nio_util::convert_return(n, false)?;
+    Ok(Some(Oop::new_int(v as i32)))
+}
+
+fn jvm_pwrite0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let fd = nio_util::fd_val(args.get(0).unwrap());
+    let address = args.get(1).unwrap().extract_long() as *const libc::c_void;
+    let len = args.get(2).unwrap().extract_int() as usize;
+    let offset = args.get(3).unwrap().extract_long();
+
+    let n = unsafe { libc::pwrite(fd, address, len, offset as libc::off_t) };
+    let v = nio_util::convert_return(n, false)?;
+    Ok(Some(Oop::new_int(v as i32)))
+}
+
+fn jvm_writev0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let fd = nio_util::fd_val(args.get(0).unwrap());
+    let address = args.get(1).unwrap().extract_long() as *const libc::iovec;
+    let len = args.get(2).unwrap().extract_int();
+
+    let n = unsafe { libc::writev(fd, address, len) };
+    let v = nio_util::convert_return(n, false)?;
+    Ok(Some(Oop::new_long(v)))
+}
+
+//fdatasync if the metadata not wanted, where os has it
+fn jvm_force0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let fd = nio_util::fd_val(args.get(0).unwrap());
+    let md = args.get(1).unwrap().extract_int() != 0;
+
+    #[cfg(target_os = "linux")]
+    let r = if md {
+        unsafe { libc::fsync(fd) }
+    } else {
+        unsafe { libc::fdatasync(fd) }
+    };
+    #[cfg(not(target_os = "linux"))]
+    let r = {
+        let _ = md;
+        unsafe { libc::fsync(fd) }
+    };
+
+    if r == -1 {
+        return Err(nio_util::new_io_ex_errno("Force failed"));
+    }
+    Ok(Some(Oop::new_int(0)))
+}
+
+fn jvm_truncate0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let fd = nio_util::fd_val(args.get(0).unwrap());
+    let size = args.get(1).unwrap().extract_long();
+
+    if unsafe { libc::ftruncate(fd, size as libc::off_t) } == -1 {
+        return Err(nio_util::new_io_ex_errno("Truncation failed"));
+    }
+    Ok(Some(Oop::new_int(0)))
+}
+
+fn jvm_size0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let fd = nio_util::fd_val(args.get(0).unwrap());
+
+    let mut stat: libc::stat = unsafe { std::mem::zeroed() };
+    if unsafe { libc::fstat(fd, &mut stat) } == -1 {
+        return Err(nio_util::new_io_ex_errno("Size failed"));
+    }
+    Ok(Some(Oop::new_long(stat.st_size as i64)))
+}
+
+//(fd, blocking, pos, size, shared), a record lock of fcntl
+fn jvm_lock0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let fd = nio_util::fd_val(args.get(0).unwrap());
+    let blocking = args.get(1).unwrap().extract_int() != 0;
+    let pos = args.get(2).unwrap().extract_long();
+    let size = args.get(3).unwrap().extract_long();
+    let shared = args.get(4).unwrap().extract_int() != 0;
+
+    let l_type = if shared { libc::F_RDLCK } else { libc::F_WRLCK };
+    let mut fl = new_flock(l_type, pos, size);
+    let cmd = if blocking {
+        libc::F_SETLKW
+    } else {
+        libc::F_SETLK
+    };
+
+    if unsafe { libc::fcntl(fd, cmd, &mut fl) } == -1 {
+        let errno = nio_util::errno();
+        if !blocking && (errno == libc::EAGAIN || errno == libc::EACCES) {
+            return Ok(Some(Oop::new_int(NO_LOCK)));
+        }
+        if errno == libc::EINTR {
+            return Ok(Some(Oop::new_int(INTERRUPTED)));
+        }
+        return Err(io_util::new_io_ex(&io_util::strerror(errno)));
+    }
+
+    Ok(Some(Oop::new_int(LOCKED)))
+}
+
+fn jvm_release0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let fd = nio_util::fd_val(args.get(0).unwrap());
+    let pos = args.get(1).unwrap().extract_long();
+    let size = args.get(2).unwrap().extract_long();
+
+    let mut fl = new_flock(libc::F_UNLCK, pos, size);
+    if unsafe { libc::fcntl(fd, libc::F_SETLK, &mut fl) } == -1 {
+        return Err(nio_util::new_io_ex_errno("Release failed"));
+    }
+
+    Ok(None)
+}
+
+fn jvm_close0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let fd_obj = args.get(0).unwrap();
+    let fd = nio_util::fd_val(fd_obj);
+    if fd != -1 {
+        io_util::set_file_descriptor_fd(fd_obj, -1);
+        io_util::close(fd).map_err(|msg| io_util::new_io_ex(&msg))?;
+    }
+
+    Ok(None)
+}
+
+fn jvm_preClose0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let fd = nio_util::fd_val(args.get(0).unwrap());
+    let pre_close = PRE_CLOSE_FD.load(Ordering::Relaxed);
+
+    if pre_close >= 0 && unsafe { libc::dup2(pre_close, fd) } == -1 {
+        return Err(nio_util::new_io_ex_errno("dup2 failed"));
+    }
+
+    Ok(None)
+}
+
+fn jvm_closeIntFD(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let fd = args.get(0).unwrap().extract_int();
+    io_util::close(fd).map_err(|msg| io_util::new_io_ex(&msg))?;
+    Ok(None)
+}
+
+//Long.MAX_VALUE of size, to the end of file however it grows
+fn new_flock(l_type: i32, pos: i64, size: i64) -> libc::flock {
+    let mut fl: libc::flock = unsafe { std::mem::zeroed() };
+    fl.l_whence = libc::SEEK_SET as libc::c_short;
+    fl.l_start = pos as libc::off_t;
+    fl.l_len = if size == i64::MAX {
+        0
+    } else {
+        size as libc::off_t
+    };
+    fl.l_type = l_type as libc::c_short;
+    fl
+}
@@ -0,0 +1,37 @@
+#![allow(non_snake_case)]
+
+use crate::native::common::{net_util, nio_util};
+use crate::native::{new_fn, JNIEnv, JNINativeMethod, JNIResult};
+use crate::oop::{Class, Oop};
+
+lazy_static! {
+    static ref FILE_KEY_DEV: usize = net_util::field_offset(b"sun/nio/ch/FileKey", "st_dev", "J");
+    static ref FILE_KEY_INO: usize = net_util::field_offset(b"sun/nio/ch/FileKey", "st_ino", "J");
+}
+
+pub fn get_native_methods() -> Vec<JNINativeMethod> {
+    vec![
+        new_fn("initIDs", "()V", Box::new(jvm_initIDs)),
+        new_fn("init", "(Ljava/io/FileDescriptor;)V", Box::new(jvm_init)),
+    ]
+}
+
+fn jvm_initIDs(_env: JNIEnv, _args: &[Oop]) -> JNIResult {
+    Ok(None)
+}
+
+//the key of the file locks, a file however opened
+fn jvm_init(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let this = args.get(0).unwrap();
+    let fd = nio_util::fd_val(args.get(1).unwrap());
+
+    let mut stat: libc::stat = unsafe { std::mem::zeroed() };
+    if unsafe { libc::fstat(fd, &mut stat) } == -1 {
+        return Err(nio_util::new_io_ex_errno("fstat failed"));
+    }
+
+    let rf = this.extract_ref();
+    Class::put_field_value2(rf.clone(), *FILE_KEY_DEV, Oop::new_long(stat.st_dev as i64));
+    Class::put_field_value2(rf, *FILE_KEY_INO, Oop::new_long(stat.st_ino as i64));
+    Ok(None)
+}
@@ -0,0 +1,147 @@
+#![allow(non_snake_case)]
+
+use crate::native::common::{io_util, nio_util};
+use crate::native::{new_fn, JNIEnv, JNINativeMethod, JNIResult};
+use crate::oop::Oop;
+use std::io::Read;
+
+pub fn get_native_methods() -> Vec<JNINativeMethod> {
+    vec![
+        new_fn("initIDs", "()V", Box::new(jvm_initIDs)),
+        new_fn("randomBytes", "([B)Z", Box::new(jvm_randomBytes)),
+        new_fn("makePipe", "(Z)J", Box::new(jvm_makePipe)),
+        new_fn("drain", "(I)Z", Box::new(jvm_drain)),
+        new_fn(
+            "configureBlocking",
+            "(Ljava/io/FileDescriptor;Z)V",
+            Box::new(jvm_configureBlocking),
+        ),
+        new_fn("fdVal", "(Ljava/io/FileDescriptor;)I", Box::new(jvm_fdVal)),
+        new_fn(
+            "setfdVal",
+            "(Ljava/io/FileDescriptor;I)V",
+            Box::new(jvm_setfdVal),
+        ),
+        new_fn("fdLimit", "()I", Box::new(jvm_fdLimit)),
+        new_fn("iovMax", "()I", Box::new(jvm_iovMax)),
+    ]
+}
+
+fn jvm_initIDs(_env: JNIEnv, _args: &[Oop]) -> JNIResult {
+    Ok(None)
+}
+
+fn jvm_randomBytes(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let bytes = args.get(0).unwrap();
+    let rf = bytes.extract_ref();
+    let ary = rf.extract_mut_type_array();
+    let buf = ary.extract_mut_bytes();
+
+    let v = std::fs::File::open("/dev/urandom")
+        .and_then(|mut f| f.read_exact(buf))
+        .is_ok();
+    Ok(Some(Oop::new_int(v as i32)))
+}
+
+//(read fd << 32) | write fd, both non-blocking if 'blocking' false
+fn jvm_makePipe(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let blocking = args.get(0).unwrap().extract_int() != 0;
+
+    let mut fds = [0i32; 2];
+    if unsafe { libc::pipe(fds.as_mut_ptr()) } == -1 {
+        return Err(nio_util::new_io_ex_errno("Pipe failed"));
+    }
+    for fd in fds.iter() {
+        unsafe {
+            libc::fcntl(*fd, libc::F_SETFD, libc::FD_CLOEXEC);
+        }
+        if !blocking {
+            set_blocking(*fd, false);
+        }
+    }
+
+    let v = ((fds[0] as i64) << 32) | fds[1] as i64;
+    Ok(Some(Oop::new_long(v)))
+}
+
+//read all of a non-blocking fd, true if anything read
+fn jvm_drain(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let fd = args.get(0).unwrap().extract_int();
+
+    let mut buf = [0u8; 128];
+    let mut drained = false;
+    loop {
+        let n = unsafe { libc::read(fd, buf.as_mut_ptr() as *mut libc::c_void, buf.len()) };
+        if n > 0 {
+            drained = true;
+            if (n as usize) < buf.len() {
+                break;
+            }
+        } else if n == -1 && nio_util::errno() == libc::EINTR {
+            continue;
+        } else if n == -1 && nio_util::errno() != libc::EAGAIN {
+            return Err(nio_util::new_io_ex_errno("Drain"));
+        } else {
+            break;
+        }
+    }
+
+    Ok(Some(Oop::new_int(drained as i32)))
+}
+
+fn jvm_configureBlocking(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let fd_obj = args.get(0).unwrap();
+    let blocking = args.get(1).unwrap().extract_int() != 0;
+
+    let fd = nio_util::fd_val(fd_obj);
+    if !set_blocking(fd, blocking) {
+        return Err(nio_util::new_io_ex_errno("Configure blocking failed"));
+    }
+
+    Ok(None)
+}
+
+fn jvm_fdVal(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let fd_obj = args.get(0).unwrap();
+    Ok(Some(Oop::new_int(nio_util::fd_val(fd_obj))))
+}
+
+fn jvm_setfdVal(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let fd_obj = args.get(0).unwrap();
+    let fd = args.get(1).unwrap().extract_int();
+
+    io_util::set_file_descriptor_fd(fd_obj, fd);
+    Ok(None)
+}
+
+fn jvm_fdLimit(_env: JNIEnv, _args: &[Oop]) -> JNIResult {
+    let mut rlp: libc::rlimit = unsafe { std::mem::zeroed() };
+    if unsafe { libc::getrlimit(libc::RLIMIT_NOFILE, &mut rlp) } == -1 {
+        return Err(nio_util::new_io_ex_errno("getrlimit failed"));
+    }
+
+    let v = if rlp.rlim_max == libc::RLIM_INFINITY || rlp.rlim_max > i32::MAX as libc::rlim_t {
+        i32::MAX
+    } else {
+        rlp.rlim_max as i32
+    };
+    Ok(Some(Oop::new_int(v)))
+}
+
+fn jvm_iovMax(_env: JNIEnv, _args: &[Oop]) -> JNIResult {
+    let v = unsafe { libc::sysconf(libc::_SC_IOV_MAX) };
+    let v = if v == -1 { 16 } else { v as i32 };
+    Ok(Some(Oop::new_int(v)))
+}
+
+fn set_blocking(fd: i32, blocking: bool) -> bool {
+    unsafe {
+        let flags = libc::fcntl(fd, libc::F_GETFL);
+        let new_flags = if blocking {
+            flags & !libc::O_NONBLOCK
+        } else {
+            flags | libc::O_NONBLOCK
+        };
+        flags == new_flags || libc::fcntl(fd, libc::F_SETFL, new_flags) != -1
+    }
+}
@@ -0,0 +1,61 @@
+#![allow(non_snake_case)]
+
+use crate::native::common::nio_util;
+use crate::native::{new_fn, JNIEnv, JNINativeMethod, JNIResult};
+use crate::oop::Oop;
+
+/*
+A thread blocked in an i/o of a channel woken up by a signal, the syscall
+fails with EINTR and the channel checks if closed or interrupted.
+*/
+
+pub fn get_native_methods() -> Vec<JNINativeMethod> {
+    vec![
+        new_fn("init", "()V", Box::new(jvm_init)),
+        new_fn("current", "()J", Box::new(jvm_current)),
+        new_fn("signal", "(J)V", Box::new(jvm_signal)),
+    ]
+}
+
+#[cfg(target_os = "linux")]
+fn interrupt_signal() -> i32 {
+    extern "C" {
+        fn __libc_current_sigrtmax() -> libc::c_int;
+    }
+    unsafe { __libc_current_sigrtmax() - 2 }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn interrupt_signal() -> i32 {
+    libc::SIGIO
+}
+
+extern "C" fn null_handler(_sig: libc::c_int) {}
+
+//the handler without SA_RESTART, or the blocked syscall not interrupted
+fn jvm_init(_env: JNIEnv, _args: &[Oop]) -> JNIResult {
+    unsafe {
+        let mut sa: libc::sigaction = std::mem::zeroed();
+        sa.sa_sigaction = null_handler as extern "C" fn(libc::c_int) as libc::sighandler_t;
+        libc::sigemptyset(&mut sa.sa_mask);
+        if libc::sigaction(interrupt_signal(), &sa, std::ptr::null_mut()) == -1 {
+            return Err(nio_util::new_io_ex_errno("sigaction failed"));
+        }
+    }
+
+    Ok(None)
+}
+
+fn jvm_current(_env: JNIEnv, _args: &[Oop]) -> JNIResult {
+    let v = unsafe { libc::pthread_self() } as i64;
+    Ok(Some(Oop::new_long(v)))
+}
+
+fn jvm_signal(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let thread = args.get(0).unwrap().extract_long() as libc::pthread_t;
+
+    if unsafe { libc::pthread_kill(thread, interrupt_signal()) } != 0 {
+        return Err(nio_util::new_io_ex_errno("Thread signal failed"));
+    }
+    Ok(None)
+}
@@ -0,0 +1,53 @@
+#![allow(non_snake_case)]
+
+use crate::native::common::nio_util;
+use crate::native::{new_fn, JNIEnv, JNINativeMethod, JNIResult};
+use crate::oop::Oop;
+
+pub fn get_native_methods() -> Vec<JNINativeMethod> {
+    vec![new_fn("transfer", "(IIJ)V", Box::new(jvm_transfer))]
+}
+
+//(dst, src, cancel), copy until the end of src, stopped when the int at
+//'cancel' set by another thread
+fn jvm_transfer(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let dst = args.get(0).unwrap().extract_int();
+    let src = args.get(1).unwrap().extract_int();
+    let cancel = args.get(2).unwrap().extract_long() as *const i32;
+
+    let mut buf = [0u8; 8192];
+    loop {
+        let n = unsafe { libc::read(src, buf.as_mut_ptr() as *mut libc::c_void, buf.len()) };
+        if n == 0 {
+            return Ok(None);
+        }
+        if n == -1 {
+            if nio_util::errno() == libc::EINTR {
+                continue;
+            }
+            return Err(nio_util::new_unix_ex(nio_util::errno()));
+        }
+
+        if !cancel.is_null() && unsafe { std::ptr::read_volatile(cancel) } != 0 {
+            return Err(nio_util::new_unix_ex(libc::ECANCELED));
+        }
+
+        let mut pos = 0;
+        while pos < n as usize {
+            let w = unsafe {
+                libc::write(
+                    dst,
+                    buf[pos..].as_ptr() as *const libc::c_void,
+                    n as usize - pos,
+                )
+            };
+            if w == -1 {
+                if nio_util::errno() == libc::EINTR {
+                    continue;
+                }
+                return Err(nio_util::new_unix_ex(nio_util::errno()));
+            }
+            pos += w as usize;
+        }
+    }
+}
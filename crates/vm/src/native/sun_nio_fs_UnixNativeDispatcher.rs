@@ -0,0 +1,632 @@
+#![allow(non_snake_case)]
+
+use crate::native::common::{net_util, nio_util};
+use crate::native::{new_fn, JNIEnv, JNINativeMethod, JNIResult};
+use crate::oop::{self, Class, Oop};
+use std::ffi::CStr;
+use std::os::raw::c_char;
+
+/*
+The syscalls of UnixFileSystemProvider, the default file system of java.nio.file.
+
+Paths arrive as the address of a NUL terminated NativeBuffer, the failures
+thrown as UnixException of errno, translated by the java side.
+*/
+
+//sun.nio.fs.UnixNativeDispatcher
+const SUPPORTS_OPENAT: i32 = 1 << 1;
+const SUPPORTS_FUTIMES: i32 = 1 << 2;
+
+lazy_static! {
+    static ref ATTRS_MODE: usize = attrs_field("st_mode", "I");
+    static ref ATTRS_INO: usize = attrs_field("st_ino", "J");
+    static ref ATTRS_DEV: usize = attrs_field("st_dev", "J");
+    static ref ATTRS_RDEV: usize = attrs_field("st_rdev", "J");
+    static ref ATTRS_NLINK: usize = attrs_field("st_nlink", "I");
+    static ref ATTRS_UID: usize = attrs_field("st_uid", "I");
+    static ref ATTRS_GID: usize = attrs_field("st_gid", "I");
+    static ref ATTRS_SIZE: usize = attrs_field("st_size", "J");
+    static ref ATTRS_ATIME_SEC: usize = attrs_field("st_atime_sec", "J");
+    static ref ATTRS_ATIME_NSEC: usize = attrs_field("st_atime_nsec", "J");
+    static ref ATTRS_MTIME_SEC: usize = attrs_field("st_mtime_sec", "J");
+    static ref ATTRS_MTIME_NSEC: usize = attrs_field("st_mtime_nsec", "J");
+    static ref ATTRS_CTIME_SEC: usize = attrs_field("st_ctime_sec", "J");
+    static ref ATTRS_CTIME_NSEC: usize = attrs_field("st_ctime_nsec", "J");
+    static ref ATTRS_FRSIZE: usize = store_attrs_field("f_frsize");
+    static ref ATTRS_BLOCKS: usize = store_attrs_field("f_blocks");
+    static ref ATTRS_BFREE: usize = store_attrs_field("f_bfree");
+    static ref ATTRS_BAVAIL: usize = store_attrs_field("f_bavail");
+}
+
+pub fn get_native_methods() -> Vec<JNINativeMethod> {
+    vec![
+        new_fn("init", "()I", Box::new(jvm_init)),
+        new_fn("getcwd", "()[B", Box::new(jvm_getcwd)),
+        new_fn("dup", "(I)I", Box::new(jvm_dup)),
+        new_fn("open0", "(JII)I", Box::new(jvm_open0)),
+        new_fn("openat0", "(IJII)I", Box::new(jvm_openat0)),
+        new_fn("close", "(I)V", Box::new(jvm_close)),
+        new_fn("fopen0", "(JJ)J", Box::new(jvm_fopen0)),
+        new_fn("fclose", "(J)V", Box::new(jvm_fclose)),
+        new_fn("link0", "(JJ)V", Box::new(jvm_link0)),
+        new_fn("unlink0", "(J)V", Box::new(jvm_unlink0)),
+        new_fn("unlinkat0", "(IJI)V", Box::new(jvm_unlinkat0)),
+        new_fn("mknod0", "(JIJ)V", Box::new(jvm_mknod0)),
+        new_fn("rename0", "(JJ)V", Box::new(jvm_rename0)),
+        new_fn("renameat0", "(IJIJ)V", Box::new(jvm_renameat0)),
+        new_fn("mkdir0", "(JI)V", Box::new(jvm_mkdir0)),
+        new_fn("rmdir0", "(J)V", Box::new(jvm_rmdir0)),
+        new_fn("readlink0", "(J)[B", Box::new(jvm_readlink0)),
+        new_fn("realpath0", "(J)[B", Box::new(jvm_realpath0)),
+        new_fn("symlink0", "(JJ)V", Box::new(jvm_symlink0)),
+        new_fn(
+            "stat0",
+            "(JLsun/nio/fs/UnixFileAttributes;)V",
+            Box::new(jvm_stat0),
+        ),
+        new_fn("stat1", "(J)I", Box::new(jvm_stat1)),
+        new_fn(
+            "lstat0",
+            "(JLsun/nio/fs/UnixFileAttributes;)V",
+            Box::new(jvm_lstat0),
+        ),
+        new_fn(
+            "fstat",
+            "(ILsun/nio/fs/UnixFileAttributes;)V",
+            Box::new(jvm_fstat),
+        ),
+        new_fn(
+            "fstatat0",
+            "(IJILsun/nio/fs/UnixFileAttributes;)V",
+            Box::new(jvm_fstatat0),
+        ),
+        new_fn("chown0", "(JII)V", Box::new(jvm_chown0)),
+        new_fn("lchown0", "(JII)V", Box::new(jvm_lchown0)),
+        new_fn("fchown", "(III)V", Box::new(jvm_fchown)),
+        new_fn("chmod0", "(JI)V", Box::new(jvm_chmod0)),
+        new_fn("fchmod", "(II)V", Box::new(jvm_fchmod)),
+        new_fn("utimes0", "(JJJ)V", Box::new(jvm_utimes0)),
+        new_fn("futimes", "(IJJ)V", Box::new(jvm_futimes)),
+        new_fn("opendir0", "(J)J", Box::new(jvm_opendir0)),
+        new_fn("fdopendir", "(I)J", Box::new(jvm_fdopendir)),
+        new_fn("closedir", "(J)V", Box::new(jvm_closedir)),
+        new_fn("readdir", "(J)[B", Box::new(jvm_readdir)),
+        new_fn("read", "(IJI)I", Box::new(jvm_read)),
+        new_fn("write", "(IJI)I", Box::new(jvm_write)),
+        new_fn("access0", "(JI)V", Box::new(jvm_access0)),
+        new_fn("getpwuid", "(I)[B", Box::new(jvm_getpwuid)),
+        new_fn("getgrgid", "(I)[B", Box::new(jvm_getgrgid)),
+        new_fn("getpwnam0", "(J)I", Box::new(jvm_getpwnam0)),
+        new_fn("getgrnam0", "(J)I", Box::new(jvm_getgrnam0)),
+        new_fn(
+            "statvfs0",
+            "(JLsun/nio/fs/UnixFileStoreAttributes;)V",
+            Box::new(jvm_statvfs0),
+        ),
+        new_fn("pathconf0", "(JI)J", Box::new(jvm_pathconf0)),
+        new_fn("fpathconf", "(II)J", Box::new(jvm_fpathconf)),
+        new_fn("strerror", "(I)[B", Box::new(jvm_strerror)),
+    ]
+}
+
+fn jvm_init(_env: JNIEnv, _args: &[Oop]) -> JNIResult {
+    Ok(Some(Oop::new_int(SUPPORTS_OPENAT | SUPPORTS_FUTIMES)))
+}
+
+fn jvm_getcwd(_env: JNIEnv, _args: &[Oop]) -> JNIResult {
+    let mut buf = [0 as c_char; libc::PATH_MAX as usize + 1];
+    let cwd = unsafe { libc::getcwd(buf.as_mut_ptr(), buf.len()) };
+    if cwd.is_null() {
+        return Err(nio_util::new_unix_ex(nio_util::errno()));
+    }
+    Ok(Some(c_bytes(cwd)))
+}
+
+fn jvm_dup(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let fd = args.get(0).unwrap().extract_int();
+    let v = restartable(|| unsafe { libc::dup(fd) })?;
+    Ok(Some(Oop::new_int(v)))
+}
+
+fn jvm_open0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let path = path_arg(args, 0);
+    let flags = args.get(1).unwrap().extract_int();
+    let mode = args.get(2).unwrap().extract_int() as libc::c_uint;
+
+    let v = restartable(|| unsafe { libc::open(path, flags, mode) })?;
+    Ok(Some(Oop::new_int(v)))
+}
+
+fn jvm_openat0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let dfd = args.get(0).unwrap().extract_int();
+    let path = path_arg(args, 1);
+    let flags = args.get(2).unwrap().extract_int();
+    let mode = args.get(3).unwrap().extract_int() as libc::c_uint;
+
+    let v = restartable(|| unsafe { libc::openat(dfd, path, flags, mode) })?;
+    Ok(Some(Oop::new_int(v)))
+}
+
+//fd closed even if interrupted, not retried
+fn jvm_close(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let fd = args.get(0).unwrap().extract_int();
+    if unsafe { libc::close(fd) } == -1 && nio_util::errno() != libc::EINTR {
+        return Err(nio_util::new_unix_ex(nio_util::errno()));
+    }
+    Ok(None)
+}
+
+fn jvm_fopen0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let path = path_arg(args, 0);
+    let mode = path_arg(args, 1);
+
+    let fp = unsafe { libc::fopen(path, mode) };
+    if fp.is_null() {
+        return Err(nio_util::new_unix_ex(nio_util::errno()));
+    }
+    Ok(Some(Oop::new_long(fp as i64)))
+}
+
+fn jvm_fclose(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let fp = args.get(0).unwrap().extract_long() as *mut libc::FILE;
+    if unsafe { libc::fclose(fp) } == libc::EOF && nio_util::errno() != libc::EINTR {
+        return Err(nio_util::new_unix_ex(nio_util::errno()));
+    }
+    Ok(None)
+}
+
+fn jvm_link0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let existing = path_arg(args, 0);
+    let new = path_arg(args, 1);
+    restartable(|| unsafe { libc::link(existing, new) })?;
+    Ok(None)
+}
+
+fn jvm_unlink0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let path = path_arg(args, 0);
+    check(unsafe { libc::unlink(path) })?;
+    Ok(None)
+}
+
+fn jvm_unlinkat0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let dfd = args.get(0).unwrap().extract_int();
+    let path = path_arg(args, 1);
+    let flags = args.get(2).unwrap().extract_int();
+    check(unsafe { libc::unlinkat(dfd, path, flags) })?;
+    Ok(None)
+}
+
+fn jvm_mknod0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let path = path_arg(args, 0);
+    let mode = args.get(1).unwrap().extract_int() as libc::mode_t;
+    let dev = args.get(2).unwrap().extract_long() as libc::dev_t;
+    restartable(|| unsafe { libc::mknod(path, mode, dev) })?;
+    Ok(None)
+}
+
+fn jvm_rename0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let from = path_arg(args, 0);
+    let to = path_arg(args, 1);
+    check(unsafe { libc::rename(from, to) })?;
+    Ok(None)
+}
+
+fn jvm_renameat0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let from_dfd = args.get(0).unwrap().extract_int();
+    let from = path_arg(args, 1);
+    let to_dfd = args.get(2).unwrap().extract_int();
+    let to = path_arg(args, 3);
+    check(unsafe { libc::renameat(from_dfd, from, to_dfd, to) })?;
+    Ok(None)
+}
+
+fn jvm_mkdir0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let path = path_arg(args, 0);
+    let mode = args.get(1).unwrap().extract_int() as libc::mode_t;
+    check(unsafe { libc::mkdir(path, mode) })?;
+    Ok(None)
+}
+
+fn jvm_rmdir0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let path = path_arg(args, 0);
+    check(unsafe { libc::rmdir(path) })?;
+    Ok(None)
+}
+
+fn jvm_readlink0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let path = path_arg(args, 0);
+
+    let mut buf = vec![0u8; libc::PATH_MAX as usize + 1];
+    let n = unsafe { libc::readlink(path, buf.as_mut_ptr() as *mut c_char, buf.len()) };
+    if n == -1 {
+        return Err(nio_util::new_unix_ex(nio_util::errno()));
+    }
+    //truncated if filled up, as jdk
+    let n = (n as usize).min(libc::PATH_MAX as usize);
+    buf.truncate(n);
+    Ok(Some(Oop::new_byte_ary2(buf)))
+}
+
+fn jvm_realpath0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let path = path_arg(args, 0);
+
+    let mut buf = [0 as c_char; libc::PATH_MAX as usize + 1];
+    let v = unsafe { libc::realpath(path, buf.as_mut_ptr()) };
+    if v.is_null() {
+        return Err(nio_util::new_unix_ex(nio_util::errno()));
+    }
+    Ok(Some(c_bytes(v)))
+}
+
+fn jvm_symlink0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let target = path_arg(args, 0);
+    let link = path_arg(args, 1);
+    check(unsafe { libc::symlink(target, link) })?;
+    Ok(None)
+}
+
+fn jvm_stat0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let path = path_arg(args, 0);
+    let attrs = args.get(1).unwrap();
+
+    let mut stat: libc::stat = unsafe { std::mem::zeroed() };
+    restartable(|| unsafe { libc::stat(path, &mut stat) })?;
+    put_attrs(attrs, &stat);
+    Ok(None)
+}
+
+//st_mode, 0 if failed
+fn jvm_stat1(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let path = path_arg(args, 0);
+
+    let mut stat: libc::stat = unsafe { std::mem::zeroed() };
+    let v = match restartable(|| unsafe { libc::stat(path, &mut stat) }) {
+        Ok(_) => stat.st_mode as i32,
+        Err(_) => 0,
+    };
+    Ok(Some(Oop::new_int(v)))
+}
+
+fn jvm_lstat0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let path = path_arg(args, 0);
+    let attrs = args.get(1).unwrap();
+
+    let mut stat: libc::stat = unsafe { std::mem::zeroed() };
+    restartable(|| unsafe { libc::lstat(path, &mut stat) })?;
+    put_attrs(attrs, &stat);
+    Ok(None)
+}
+
+fn jvm_fstat(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let fd = args.get(0).unwrap().extract_int();
+    let attrs = args.get(1).unwrap();
+
+    let mut stat: libc::stat = unsafe { std::mem::zeroed() };
+    restartable(|| unsafe { libc::fstat(fd, &mut stat) })?;
+    put_attrs(attrs, &stat);
+    Ok(None)
+}
+
+fn jvm_fstatat0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let dfd = args.get(0).unwrap().extract_int();
+    let path = path_arg(args, 1);
+    let flag = args.get(2).unwrap().extract_int();
+    let attrs = args.get(3).unwrap();
+
+    let mut stat: libc::stat = unsafe { std::mem::zeroed() };
+    restartable(|| unsafe { libc::fstatat(dfd, path, &mut stat, flag) })?;
+    put_attrs(attrs, &stat);
+    Ok(None)
+}
+
+fn jvm_chown0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let path = path_arg(args, 0);
+    let uid = args.get(1).unwrap().extract_int() as libc::uid_t;
+    let gid = args.get(2).unwrap().extract_int() as libc::gid_t;
+    restartable(|| unsafe { libc::chown(path, uid, gid) })?;
+    Ok(None)
+}
+
+fn jvm_lchown0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let path = path_arg(args, 0);
+    let uid = args.get(1).unwrap().extract_int() as libc::uid_t;
+    let gid = args.get(2).unwrap().extract_int() as libc::gid_t;
+    restartable(|| unsafe { libc::lchown(path, uid, gid) })?;
+    Ok(None)
+}
+
+fn jvm_fchown(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let fd = args.get(0).unwrap().extract_int();
+    let uid = args.get(1).unwrap().extract_int() as libc::uid_t;
+    let gid = args.get(2).unwrap().extract_int() as libc::gid_t;
+    restartable(|| unsafe { libc::fchown(fd, uid, gid) })?;
+    Ok(None)
+}
+
+fn jvm_chmod0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let path = path_arg(args, 0);
+    let mode = args.get(1).unwrap().extract_int() as libc::mode_t;
+    restartable(|| unsafe { libc::chmod(path, mode) })?;
+    Ok(None)
+}
+
+fn jvm_fchmod(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let fd = args.get(0).unwrap().extract_int();
+    let mode = args.get(1).unwrap().extract_int() as libc::mode_t;
+    restartable(|| unsafe { libc::fchmod(fd, mode) })?;
+    Ok(None)
+}
+
+//access & modification time in micros
+fn jvm_utimes0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let path = path_arg(args, 0);
+    let times = [
+        to_timeval(args.get(1).unwrap().extract_long()),
+        to_timeval(args.get(2).unwrap().extract_long()),
+    ];
+    restartable(|| unsafe { libc::utimes(path, times.as_ptr()) })?;
+    Ok(None)
+}
+
+fn jvm_futimes(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let fd = args.get(0).unwrap().extract_int();
+    let times = [
+        to_timeval(args.get(1).unwrap().extract_long()),
+        to_timeval(args.get(2).unwrap().extract_long()),
+    ];
+    restartable(|| unsafe { libc::futimes(fd, times.as_ptr()) })?;
+    Ok(None)
+}
+
+fn jvm_opendir0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let path = path_arg(args, 0);
+
+    let dir = unsafe { libc::opendir(path) };
+    if dir.is_null() {
+        return Err(nio_util::new_unix_ex(nio_util::errno()));
+    }
+    Ok(Some(Oop::new_long(dir as i64)))
+}
+
+fn jvm_fdopendir(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let dfd = args.get(0).unwrap().extract_int();
+
+    let dir = unsafe { libc::fdopendir(dfd) };
+    if dir.is_null() {
+        return Err(nio_util::new_unix_ex(nio_util::errno()));
+    }
+    Ok(Some(Oop::new_long(dir as i64)))
+}
+
+fn jvm_closedir(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let dir = args.get(0).unwrap().extract_long() as *mut libc::DIR;
+    if unsafe { libc::closedir(dir) } == -1 && nio_util::errno() != libc::EINTR {
+        return Err(nio_util::new_unix_ex(nio_util::errno()));
+    }
+    Ok(None)
+}
+
+//name of the next entry, "." & ".." included, null at the end
+fn jvm_readdir(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let dir = args.get(0).unwrap().extract_long() as *mut libc::DIR;
+
+    clear_errno();
+    let entry = unsafe { libc::readdir(dir) };
+    if entry.is_null() {
+        let errno = nio_util::errno();
+        if errno != 0 {
+            return Err(nio_util::new_unix_ex(errno));
+        }
+        return Ok(Some(oop::consts::get_null()));
+    }
+
+    let name = unsafe { (*entry).d_name.as_ptr() };
+    Ok(Some(c_bytes(name)))
+}
+
+fn jvm_read(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let fd = args.get(0).unwrap().extract_int();
+    let address = args.get(1).unwrap().extract_long() as *mut libc::c_void;
+    let len = args.get(2).unwrap().extract_int() as usize;
+
+    let n = restartable(|| unsafe { libc::read(fd, address, len) as i32 })?;
+    Ok(Some(Oop::new_int(n)))
+}
+
+fn jvm_write(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let fd = args.get(0).unwrap().extract_int();
+    let address = args.get(1).unwrap().extract_long() as *const libc::c_void;
+    let len = args.get(2).unwrap().extract_int() as usize;
+
+    let n = restartable(|| unsafe { libc::write(fd, address, len) as i32 })?;
+    Ok(Some(Oop::new_int(n)))
+}
+
+fn jvm_access0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let path = path_arg(args, 0);
+    let amode = args.get(1).unwrap().extract_int();
+    restartable(|| unsafe { libc::access(path, amode) })?;
+    Ok(None)
+}
+
+fn jvm_getpwuid(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let uid = args.get(0).unwrap().extract_int() as libc::uid_t;
+
+    let pw = unsafe { libc::getpwuid(uid) };
+    if pw.is_null() || unsafe { *(*pw).pw_name } == 0 {
+        return Err(nio_util::new_unix_ex(libc::ENOENT));
+    }
+    Ok(Some(c_bytes(unsafe { (*pw).pw_name })))
+}
+
+fn jvm_getgrgid(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let gid = args.get(0).unwrap().extract_int() as libc::gid_t;
+
+    let gr = unsafe { libc::getgrgid(gid) };
+    if gr.is_null() || unsafe { *(*gr).gr_name } == 0 {
+        return Err(nio_util::new_unix_ex(libc::ENOENT));
+    }
+    Ok(Some(c_bytes(unsafe { (*gr).gr_name })))
+}
+
+//uid of the name, -1 if not found
+fn jvm_getpwnam0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let name = path_arg(args, 0);
+
+    let pw = unsafe { libc::getpwnam(name) };
+    let v = if pw.is_null() {
+        -1
+    } else {
+        unsafe { (*pw).pw_uid as i32 }
+    };
+    Ok(Some(Oop::new_int(v)))
+}
+
+fn jvm_getgrnam0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let name = path_arg(args, 0);
+
+    let gr = unsafe { libc::getgrnam(name) };
+    let v = if gr.is_null() {
+        -1
+    } else {
+        unsafe { (*gr).gr_gid as i32 }
+    };
+    Ok(Some(Oop::new_int(v)))
+}
+
+fn jvm_statvfs0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let path = path_arg(args, 0);
+    let attrs = args.get(1).unwrap();
+
+    let mut buf: libc::statvfs = unsafe { std::mem::zeroed() };
+    restartable(|| unsafe { libc::statvfs(path, &mut buf) })?;
+
+    let rf = attrs.extract_ref();
+    let fields = [
+        (*ATTRS_FRSIZE, buf.f_frsize as i64),
+        (*ATTRS_BLOCKS, buf.f_blocks as i64),
+        (*ATTRS_BFREE, buf.f_bfree as i64),
+        (*ATTRS_BAVAIL, buf.f_bavail as i64),
+    ];
+    for (offset, v) in fields.iter() {
+        Class::put_field_value2(rf.clone(), *offset, Oop::new_long(*v));
+    }
+    Ok(None)
+}
+
+fn jvm_pathconf0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let path = path_arg(args, 0);
+    let name = args.get(1).unwrap().extract_int();
+
+    let v = unsafe { libc::pathconf(path, name) };
+    if v == -1 {
+        return Err(nio_util::new_unix_ex(nio_util::errno()));
+    }
+    Ok(Some(Oop::new_long(v as i64)))
+}
+
+fn jvm_fpathconf(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let fd = args.get(0).unwrap().extract_int();
+    let name = args.get(1).unwrap().extract_int();
+
+    let v = unsafe { libc::fpathconf(fd, name) };
+    if v == -1 {
+        return Err(nio_util::new_unix_ex(nio_util::errno()));
+    }
+    Ok(Some(Oop::new_long(v as i64)))
+}
+
+fn jvm_strerror(_env: JNIEnv, args: &[Oop]) -> JNIResult {
+    let errno = args.get(0).unwrap().extract_int();
+    let msg = unsafe { libc::strerror(errno) };
+    Ok(Some(c_bytes(msg)))
+}
+
+fn attrs_field(name: &str, desc: &str) -> usize {
+    net_util::field_offset(b"sun/nio/fs/UnixFileAttributes", name, desc)
+}
+
+fn store_attrs_field(name: &str) -> usize {
+    net_util::field_offset(b"sun/nio/fs/UnixFileStoreAttributes", name, "J")
+}
+
+fn put_attrs(attrs: &Oop, stat: &libc::stat) {
+    let rf = attrs.extract_ref();
+    let ints = [
+        (*ATTRS_MODE, stat.st_mode as i32),
+        (*ATTRS_NLINK, stat.st_nlink as i32),
+        (*ATTRS_UID, stat.st_uid as i32),
+        (*ATTRS_GID, stat.st_gid as i32),
+    ];
+    for (offset, v) in ints.iter() {
+        Class::put_field_value2(rf.clone(), *offset, Oop::new_int(*v));
+    }
+
+    let longs = [
+        (*ATTRS_INO, stat.st_ino as i64),
+        (*ATTRS_DEV, stat.st_dev as i64),
+        (*ATTRS_RDEV, stat.st_rdev as i64),
+        (*ATTRS_SIZE, stat.st_size as i64),
+        (*ATTRS_ATIME_SEC, stat.st_atime as i64),
+        (*ATTRS_ATIME_NSEC, stat.st_atime_nsec as i64),
+        (*ATTRS_MTIME_SEC, stat.st_mtime as i64),
+        (*ATTRS_MTIME_NSEC, stat.st_mtime_nsec as i64),
+        (*ATTRS_CTIME_SEC, stat.st_ctime as i64),
+        (*ATTRS_CTIME_NSEC, stat.st_ctime_nsec as i64),
+    ];
+    for (offset, v) in longs.iter() {
+        Class::put_field_value2(rf.clone(), *offset, Oop::new_long(*v));
+    }
+}
+
+//the syscall retried while interrupted, UnixException if failed
+fn restartable<F: FnMut() -> i32>(mut f: F) -> Result<i32, Oop> {
+    loop {
+        let r = f();
+        if r != -1 {
+            return Ok(r);
+        }
+        let errno = nio_util::errno();
+        if errno != libc::EINTR {
+            return Err(nio_util::new_unix_ex(errno));
+        }
+    }
+}
+
+fn check(r: i32) -> Result<(), Oop> {
+    if r == -1 {
+        Err(nio_util::new_unix_ex(nio_util::errno()))
+    } else {
+        Ok(())
+    }
+}
+
+fn path_arg(args: &[Oop], i: usize) -> *const c_char {
+    args.get(i).unwrap().extract_long() as *const c_char
+}
+
+fn c_bytes(s: *const c_char) -> Oop {
+    let bytes = unsafe { CStr::from_ptr(s) }.to_bytes().to_vec();
+    Oop::new_byte_ary2(bytes)
+}
+
+fn to_timeval(micros: i64) -> libc::timeval {
+    libc::timeval {
+        tv_sec: (micros / 1_000_000) as libc::time_t,
+        tv_usec: (micros % 1_000_000) as libc::suseconds_t,
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn clear_errno() {
+    unsafe {
+        *libc::__errno_location() = 0;
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn clear_errno() {
+    unsafe {
+        *libc::__error() = 0;
+    }
+}
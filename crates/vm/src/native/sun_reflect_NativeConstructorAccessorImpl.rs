@@ -1,8 +1,9 @@
 #![allow(non_snake_case)]
 
 use crate::native::{common, new_fn, JNIEnv, JNINativeMethod, JNIResult};
-use crate::oop::Oop;
-use crate::{new_br, runtime};
+use crate::oop::{self, Oop};
+use crate::runtime;
+use classfile::consts as cls_consts;
 
 pub fn get_native_methods() -> Vec<JNINativeMethod> {
     vec![new_fn(
@@ -16,34 +17,40 @@ fn jvm_newInstance0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
     let ctor = args.get(0).unwrap();
     let arguments = args.get(1).unwrap();
 
-    let clazz = common::reflect::get_Constructor_clazz(ctor);
-    let target_cls = {
-        let rf = clazz.extract_ref();
-        let mirror = rf.extract_mirror();
-        mirror.target.clone().unwrap()
-    };
+    let mir = common::reflect::get_method_id(ctor)?;
+    let target_cls = mir.method.class.clone();
 
-    let signature = common::reflect::get_Constructor_signature(ctor);
-
-    let cls = target_cls.get_class();
-    let name = unsafe { std::str::from_utf8_unchecked(cls.name.as_slice()) };
-    info!("newInstance0 {}:{}", name, signature);
-
-    let mut ctor_args = Vec::new();
     {
-        match arguments {
-            Oop::Null => (),
-            Oop::Ref(rf) => {
-                let ary = rf.extract_array();
-                ctor_args.extend_from_slice(ary.elements.as_slice());
-            }
-            _ => unreachable!(),
+        let cls = target_cls.get_class();
+        let name = unsafe { std::str::from_utf8_unchecked(cls.name.as_slice()) };
+        info!("newInstance0 {}:{}", name, unsafe {
+            std::str::from_utf8_unchecked(mir.method.desc.as_slice())
+        });
+
+        if cls.is_abstract() || cls.is_interface() {
+            let msg = Some(name.replace("/", "."));
+            let ex = runtime::exception::new(cls_consts::J_INSTANTIATION_EX, msg);
+            return Err(ex);
         }
     }
 
+    oop::class::init_class(&target_cls)?;
+
+    let mut ctor_args = common::reflect::unbox_args(&mir, arguments)?;
+
     let oop = Oop::new_inst(target_cls.clone());
+    if target_cls.extract_inst().has_finalizer {
+        runtime::heap::register_finalizer(&oop);
+    }
     ctor_args.insert(0, oop.clone());
-    runtime::invoke::invoke_ctor(target_cls, new_br(signature.as_str()), ctor_args);
+    let mut jc = runtime::invoke::JavaCall::new_with_args(mir, ctor_args);
+    jc.invoke(None, true);
+
+    if runtime::thread::is_meet_ex() {
+        let jt = runtime::thread::current_java_thread();
+        let ex = jt.write().unwrap().take_ex().unwrap();
+        return Err(common::reflect::new_invocation_target_exception(ex));
+    }
 
     Ok(Some(oop))
 }
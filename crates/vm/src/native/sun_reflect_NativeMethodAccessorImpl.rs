@@ -1,9 +1,8 @@
 #![allow(non_snake_case)]
 
-use crate::native::{new_fn, JNIEnv, JNINativeMethod, JNIResult};
-use crate::new_br;
-use crate::oop::{self, Class, Oop, OopRef};
-use crate::runtime::{self, require_class3};
+use crate::native::{common, new_fn, JNIEnv, JNINativeMethod, JNIResult};
+use crate::oop::{self, Oop};
+use crate::runtime;
 use crate::util;
 use classfile::{consts as cls_consts, SignatureType};
 
@@ -20,37 +19,12 @@ fn jvm_invoke0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
     let obj = args.get(1).unwrap();
     let args = args.get(2).unwrap();
 
-    let cls = require_class3(None, cls_consts::J_METHOD).unwrap();
+    let mir = common::reflect::get_method_id(method)?;
+    let clz = mir.method.class.clone();
 
-    let (m_clazz, m_name, m_signature) = {
-        let cls = cls.get_class();
-
-        let fid = cls.get_field_id(&util::S_CLAZZ, &util::S_JAVA_LANG_CLASS, false);
-        let method_clazz = Class::get_field_value(method.extract_ref(), fid);
-
-        let fid = cls.get_field_id(&util::S_NAME, &util::S_JAVA_LANG_STRING, false);
-        let method_name = Class::get_field_value(method.extract_ref(), fid);
-        let method_name = OopRef::java_lang_string(method_name.extract_ref());
-        let method_name = new_br(method_name.as_str());
-
-        let fid = cls.get_field_id(&util::S_SIGNATURE, &util::S_JAVA_LANG_STRING, false);
-        let signature = Class::get_field_value(method.extract_ref(), fid);
-        let signature = OopRef::java_lang_string(signature.extract_ref());
-        let signature = new_br(signature.as_str());
-
-        (method_clazz, method_name, signature)
-    };
-
-    let clz = {
-        let rf = m_clazz.extract_ref();
-        let mirror = rf.extract_mirror();
-        mirror.target.clone().unwrap()
-    };
-
-    let mir = {
-        let clz = clz.get_class();
-        clz.get_class_method(&m_name, &m_signature).unwrap()
-    };
+    if mir.method.is_static() {
+        oop::class::init_class(&clz)?;
+    }
 
     // {
     //     let cls = clz.read().unwrap();
@@ -65,48 +39,54 @@ fn jvm_invoke0(_env: JNIEnv, args: &[Oop]) -> JNIResult {
     //     );
     // }
 
-    let mut args = {
-        let rf = args.extract_ref();
-        let ary = rf.extract_array();
-        ary.elements.to_vec()
-    };
+    let mut args = common::reflect::unbox_args(&mir, args)?;
 
     if !mir.method.is_static() {
+        if obj.is_null() {
+            return Err(runtime::exception::new(cls_consts::J_NPE, None));
+        }
+
+        let is_inst = match util::oop::get_oop_class(obj) {
+            Some(cls) => runtime::cmp::instance_of(cls, clz.clone()),
+            None => false,
+        };
+        if !is_inst {
+            let msg = Some("object is not an instance of declaring class".to_string());
+            let ex = runtime::exception::new(cls_consts::J_ILLEGAL_ARGUMENT, msg);
+            return Err(ex);
+        }
+
         args.insert(0, obj.clone());
     }
 
     let force_no_resolve = mir.method.name.as_slice() == b"<init>" || mir.method.is_static();
     let mut jc = runtime::invoke::JavaCall::new_with_args(mir, args);
-    let area = runtime::DataArea::new(0, 0);
+    let area = runtime::DataArea::new(0, 2);
     jc.invoke(Some(&area), force_no_resolve);
 
+    if runtime::thread::is_meet_ex() {
+        let jt = runtime::thread::current_java_thread();
+        let ex = jt.write().unwrap().take_ex().unwrap();
+        return Err(common::reflect::new_invocation_target_exception(ex));
+    }
+
     let r = {
-        let mut stack = area.stack.borrow_mut();
-        match jc.mir.method.signature.retype {
+        let stack = &area.stack;
+        let retype = &jc.mir.method.signature.retype;
+        let v = match retype {
             SignatureType::Byte
             | SignatureType::Char
             | SignatureType::Boolean
             | SignatureType::Short
-            | SignatureType::Int => {
-                let v = stack.pop_int();
-                Some(oop::Oop::new_int(v))
-            }
-            SignatureType::Double => {
-                let v = stack.pop_double();
-                Some(oop::Oop::new_double(v))
-            }
-            SignatureType::Float => {
-                let v = stack.pop_float();
-                Some(oop::Oop::new_float(v))
-            }
-            SignatureType::Long => {
-                let v = stack.pop_long();
-                Some(oop::Oop::new_long(v))
-            }
-            SignatureType::Object(_, _, _) | SignatureType::Array(_) => Some(stack.pop_ref()),
-            SignatureType::Void => Some(oop::consts::get_null()),
-        }
+            | SignatureType::Int => Oop::new_int(stack.pop_int()),
+            SignatureType::Double => Oop::new_double(stack.pop_double()),
+            SignatureType::Float => Oop::new_float(stack.pop_float()),
+            SignatureType::Long => Oop::new_long(stack.pop_long()),
+            SignatureType::Object(_, _, _) | SignatureType::Array(_) => stack.pop_ref(),
+            SignatureType::Void => oop::consts::get_null(),
+        };
+        common::reflect::box_value(retype, v)?
     };
 
-    Ok(r)
+    Ok(Some(r))
 }
@@ -1,7 +1,11 @@
+use crate::new_br;
 use crate::oop::method::MethodId;
 use crate::oop::{self, consts as oop_consts, field, method, Oop, OopRef, RefKindDesc, ValueType};
+use crate::runtime::heap::ReferenceType;
 use crate::runtime::thread::ReentrantMutex;
-use crate::runtime::{self, require_class2, ClassLoader, ConstantPoolCache, JavaCall, JavaThread};
+use crate::runtime::{
+    self, access, require_class2, ClassLoader, ConstantPoolCache, JavaCall, JavaThread,
+};
 use crate::types::*;
 use crate::util;
 use classfile::{
@@ -11,8 +15,9 @@ use classfile::{
 use rustc_hash::FxHashMap;
 use std::fmt::{self, Debug, Error, Formatter};
 use std::ops::{Deref, DerefMut};
-use std::sync::atomic::Ordering;
-use std::sync::{Arc, Mutex};
+use std::sync::atomic::{fence, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::ThreadId;
 
 pub struct ClassPtr(u64);
 
@@ -83,7 +88,7 @@ impl Debug for ClassPtr {
 /////////////////////////////////////////////
 
 pub struct Class {
-    clinit_mutex: Arc<std::sync::Mutex<()>>,
+    init_lock: InitLock,
     mutex: ReentrantMutex,
     state: std::sync::atomic::AtomicU8,
 
@@ -99,6 +104,13 @@ pub struct Class {
     pub kind: ClassKind,
 }
 
+//LC of JVMS 5.5, the thread initializing the class, and the threads waiting
+#[derive(Default)]
+struct InitLock {
+    thread: Mutex<Option<ThreadId>>,
+    cond: Condvar,
+}
+
 pub enum ClassKind {
     Instance(ClassObject),
     ObjectArray(ArrayClassObject),
@@ -149,6 +161,10 @@ impl Into<u8> for State {
     }
 }
 
+//Unsafe.staticFieldOffset marks the slot, so that a static field can be
+//accessed with the mirror returned by Unsafe.staticFieldBase
+pub const STATIC_FIELD_OFFSET_MARK: usize = 1 << 30;
+
 pub struct ClassObject {
     pub class_file: ClassFileRef,
 
@@ -156,13 +172,18 @@ pub struct ClassObject {
 
     //  FxHashMap<(name, desc), MethodIdRef>
     pub all_methods: FxHashMap<(BytesRef, BytesRef), MethodIdRef>,
-    v_table: FxHashMap<(BytesRef, BytesRef), MethodIdRef>,
+    //the methods selected for this class, by MethodId.vtable_index of class methods
+    v_table: Vec<MethodIdRef>,
+    //  FxHashMap<interface name, selected by MethodId.vtable_index of the interface methods>
+    i_table: FxHashMap<BytesRef, Vec<ITableEntry>>,
 
     //  FxHashMap<(package, name, desc), FieldIdRef>
     pub static_fields: FxHashMap<(BytesRef, BytesRef, BytesRef), FieldIdRef>,
     pub inst_fields: FxHashMap<(BytesRef, BytesRef, BytesRef), FieldIdRef>,
 
     static_field_values: Vec<Oop>,
+    //guard for volatile static field access
+    static_fields_mutex: Mutex<()>,
 
     interfaces: FxHashMap<BytesRef, ClassRef>,
 
@@ -174,6 +195,19 @@ pub struct ClassObject {
     pub inner_classes: Option<Vec<InnerClass>>,
 
     pub cp_cache: ConstantPoolCache,
+
+    //a subclass of Soft/Weak/Final/PhantomReference, see heap
+    pub reference_type: Option<ReferenceType>,
+    //finalize() overridden by a non-empty one, Finalizer.register on new
+    pub has_finalizer: bool,
+}
+
+//an entry of itable, JVMS 5.4.6
+#[derive(Clone)]
+enum ITableEntry {
+    Method(MethodIdRef),
+    //more than one maximally-specific default method
+    Conflict(Vec<MethodIdRef>),
 }
 
 pub struct ArrayClassObject {
@@ -188,74 +222,151 @@ pub struct ArrayClassObject {
     pub mirror: Option<Oop>,
 }
 
-pub fn init_class(class: &ClassRef) {
-    let need = { class.get_class().get_class_state() == State::Linked };
-    if need {
-        let mut cls = class.get_mut_class();
-        let clinit_mutex = cls.clinit_mutex.clone();
-        let l = clinit_mutex.lock().unwrap();
-
-        cls.set_class_state(State::BeingIni);
-        if let Some(super_class) = &cls.super_class {
-            init_class(super_class);
-            init_class_fully(super_class);
-        }
-
-        match &mut cls.kind {
-            ClassKind::Instance(class_obj) => {
-                class_obj.init_static_fields();
+/*
+Initialization, JVMS 5.5
+
+The state of a class, and the thread initializing it, are changed under its
+init lock (LC). Other threads wait for the initializing one; a recursive
+request by the initializing thread, from <clinit> of the class itself or of a
+superclass, returns at once. A class failed to initialize stays in IniErr,
+each later use throws NoClassDefFoundError.
+
+Err is the exception to be thrown by the caller: the Error thrown by
+<clinit>, any other exception wrapped as ExceptionInInitializerError.
+*/
+pub fn init_class(class: &ClassRef) -> Result<(), Oop> {
+    let cls = class.get_class();
+    if cls.get_class_state() == State::FullyIni {
+        return Ok(());
+    }
+
+    let me = std::thread::current().id();
+    let mut initializer = cls.init_lock.thread.lock().unwrap();
+    loop {
+        match cls.get_class_state() {
+            State::BeingIni if *initializer == Some(me) => return Ok(()),
+            State::BeingIni => initializer = cls.init_lock.cond.wait(initializer).unwrap(),
+            State::FullyIni => return Ok(()),
+            State::IniErr => {
+                drop(initializer);
+                let name = String::from_utf8_lossy(cls.name.as_slice()).replace("/", ".");
+                let msg = format!("Could not initialize class {}", name);
+                return Err(runtime::exception::new(
+                    consts::J_NO_CLASS_DEF_FOUND,
+                    Some(msg),
+                ));
             }
-
-            _ => cls.set_class_state(State::FullyIni),
+            State::Allocated | State::Loaded | State::Linked => break,
         }
     }
+    *initializer = Some(me);
+    class.get_mut_class().set_class_state(State::BeingIni);
+    drop(initializer);
+
+    let r = if cls.is_instance() {
+        if let ClassKind::Instance(class_obj) = &mut class.get_mut_class().kind {
+            class_obj.init_static_fields();
+        }
+        init_supers(class).and_then(|_| run_clinit(class))
+    } else {
+        Ok(())
+    };
+
+    let mut initializer = cls.init_lock.thread.lock().unwrap();
+    let state = if r.is_ok() {
+        State::FullyIni
+    } else {
+        State::IniErr
+    };
+    class.get_mut_class().set_class_state(state);
+    *initializer = None;
+    cls.init_lock.cond.notify_all();
+
+    r
 }
 
-//invoke "<clinit>"
-pub fn init_class_fully(class: &ClassRef) {
-    let need = { class.get_class().get_class_state() == State::BeingIni };
+//the superclass, and the superinterfaces declaring default methods, of a class
+fn init_supers(class: &ClassRef) -> Result<(), Oop> {
+    let cls = class.get_class();
+    if cls.is_interface() {
+        return Ok(());
+    }
 
-    if need {
-        let l = class.get_class().clinit_mutex.lock();
+    if let Some(super_class) = &cls.super_class {
+        init_class(super_class)?;
+    }
 
-        let (mir, name) = {
-            let mut class = class.get_mut_class();
-            class.set_class_state(State::FullyIni);
+    let mut interfaces = Vec::new();
+    class.extract_inst().collect_interfaces(&mut interfaces);
+    for it in interfaces.iter() {
+        if it.extract_inst().has_default_methods() {
+            init_class(it)?;
+        }
+    }
 
-            let mir = class.get_this_class_method(&util::S_CLINIT, &util::S_CLINIT_SIG);
-            (mir, class.name.clone())
-        };
+    Ok(())
+}
 
-        if let Ok(mir) = mir {
-            info!("call {}:<clinit>", unsafe {
-                std::str::from_utf8_unchecked(name.as_slice())
-            });
-            let mut jc = JavaCall::new_with_args(mir, vec![]);
-            jc.invoke(None, true);
+fn run_clinit(class: &ClassRef) -> Result<(), Oop> {
+    let (mir, name) = {
+        let class = class.get_class();
+        let mir = class.get_this_class_method(&util::S_CLINIT, &util::S_CLINIT_SIG);
+        (mir, class.name.clone())
+    };
+
+    if let Ok(mir) = mir {
+        info!("call {}:<clinit>", unsafe {
+            std::str::from_utf8_unchecked(name.as_slice())
+        });
+        let mut jc = JavaCall::new_with_args(mir, vec![]);
+        jc.invoke(None, true);
+
+        let jt = runtime::thread::current_java_thread();
+        let ex = jt.write().unwrap().take_ex();
+        if let Some(ex) = ex {
+            let error = runtime::require_class3(None, consts::J_ERROR).unwrap();
+            let ex_cls = {
+                let rf = ex.extract_ref();
+                let inst = rf.extract_inst();
+                inst.class.clone()
+            };
+            if runtime::cmp::instance_of(ex_cls, error) {
+                return Err(ex);
+            }
+            let ex = runtime::exception::new_with_cause(consts::J_EIIE, ex);
+            return Err(ex);
         }
     }
-}
 
-pub fn load_and_init(name: &[u8]) -> ClassRef {
-    // trace!("load_and_init 1 name={}", String::from_utf8_lossy(name));
-    let cls_name = unsafe { std::str::from_utf8_unchecked(name) };
-    let class = runtime::require_class3(None, name)
-        .unwrap_or_else(|| panic!("Class not found: {}", cls_name));
+    Ok(())
+}
 
-    init_class(&class);
-    init_class_fully(&class);
+//a class of the boot loader, initialized;
+//NoClassDefFoundError if not found, or the error of its initialization
+pub fn load_and_init(name: &[u8]) -> Result<ClassRef, Oop> {
+    let class = match runtime::require_class3(None, name) {
+        Some(class) => class,
+        None => {
+            let msg = String::from_utf8_lossy(name).to_string();
+            return Err(runtime::exception::new(
+                consts::J_NO_CLASS_DEF_FOUND,
+                Some(msg),
+            ));
+        }
+    };
 
-    class
+    init_class(&class)?;
+    Ok(class)
 }
 
 impl Class {
     pub fn get_class_state(&self) -> State {
-        let v = self.state.load(Ordering::Relaxed);
+        let v = self.state.load(Ordering::Acquire);
         State::from(v)
     }
 
     pub fn set_class_state(&mut self, s: State) {
-        self.state.store(s.into(), Ordering::Relaxed);
+        self.state.store(s.into(), Ordering::Release);
     }
 
     pub fn get_name(&self) -> BytesRef {
@@ -321,9 +432,22 @@ impl Class {
                     None => 0,
                 };
                 class_obj.link_fields(self_ref.clone(), self.name.clone(), n);
+                let is_interface = (self.acc_flags & ACC_INTERFACE) == ACC_INTERFACE;
                 class_obj.link_interfaces();
-                class_obj.link_methods(self_ref, self.name.clone());
+                class_obj.link_methods(
+                    self_ref,
+                    self.name.clone(),
+                    self.class_loader,
+                    is_interface,
+                    self.super_class.as_ref(),
+                );
+                if !is_interface {
+                    class_obj.link_i_table(self.super_class.as_ref());
+                }
                 class_obj.link_attributes();
+                class_obj.reference_type =
+                    ReferenceType::of(self.name.as_slice(), self.super_class.as_ref());
+                class_obj.link_finalizer(self.super_class.as_ref());
             }
 
             ClassKind::ObjectArray(ary_class_obj) => {
@@ -362,6 +486,16 @@ impl Class {
         }
     }
 
+    //the component type of an array class, None if primitive
+    pub fn get_array_component(&self) -> Option<ClassRef> {
+        match &self.kind {
+            ClassKind::Instance(_) => None,
+            ClassKind::ObjectArray(ary) | ClassKind::TypeArray(ary) => {
+                ary.down_type.clone().or_else(|| ary.component.clone())
+            }
+        }
+    }
+
     pub fn is_object_ary(&self) -> bool {
         match &self.kind {
             ClassKind::Instance(_) => false,
@@ -457,6 +591,7 @@ impl Class {
 
     pub fn get_class_method(&self, name: &BytesRef, desc: &BytesRef) -> Result<MethodIdRef, ()> {
         self.get_class_method_inner(name, desc, true)
+            .or_else(|_| self.get_superinterface_method(name, desc))
     }
 
     pub fn get_this_class_method(
@@ -467,8 +602,97 @@ impl Class {
         self.get_class_method_inner(name, desc, false)
     }
 
+    //'slot' of reflect.Method & reflect.Constructor, index of ClassFile.methods
+    pub fn get_method_by_slot(&self, slot: usize) -> Option<MethodIdRef> {
+        match &self.kind {
+            ClassKind::Instance(cls_obj) => {
+                let class_file = &cls_obj.class_file;
+                let mi = class_file.methods.get(slot)?;
+                let name = constant_pool::get_utf8(&class_file.cp, mi.name_index as usize);
+                let desc = constant_pool::get_utf8(&class_file.cp, mi.desc_index as usize);
+                let k = (name.clone(), desc.clone());
+                let mir = cls_obj.all_methods.get(&k).cloned();
+                debug_assert!(mir.as_ref().is_none_or(|m| m.offset == slot));
+                mir
+            }
+            _ => None,
+        }
+    }
+
+    //lookup by name, the method selected as invokevirtual does
     pub fn get_virtual_method(&self, name: &BytesRef, desc: &BytesRef) -> Result<MethodIdRef, ()> {
-        self.get_virtual_method_inner(name, desc)
+        let k = (name.clone(), desc.clone());
+        match &self.kind {
+            ClassKind::Instance(cls_obj) => {
+                match lookup_overrider(&cls_obj.all_methods, self.super_class.as_ref(), &k) {
+                    Some(m) => Ok(m),
+                    None => match self.get_superinterface_method(name, desc) {
+                        Ok(m) if !m.method.is_abstract() => Ok(m),
+                        _ => Err(()),
+                    },
+                }
+            }
+            _ => Err(()),
+        }
+    }
+
+    //JVMS 5.4.6, the method selected for the resolved 'mir', by the class of the receiver
+    pub fn select_method(&self, mir: &MethodIdRef) -> Result<MethodIdRef, Oop> {
+        let cls_obj = match (&self.kind, mir.vtable_index) {
+            (ClassKind::Instance(cls_obj), Some(_)) => cls_obj,
+            _ => return Ok(mir.clone()),
+        };
+        let idx = mir.vtable_index.unwrap();
+
+        let owner = mir.method.class.get_class();
+        let m = if owner.is_interface() {
+            match cls_obj.i_table.get(&owner.name).and_then(|it| it.get(idx)) {
+                Some(ITableEntry::Method(m)) => m.clone(),
+                Some(ITableEntry::Conflict(methods)) => {
+                    let methods: Vec<String> = methods
+                        .iter()
+                        .map(|m| {
+                            format!(
+                                "{}.{}",
+                                String::from_utf8_lossy(m.method.cls_name.as_slice())
+                                    .replace("/", "."),
+                                String::from_utf8_lossy(m.method.name.as_slice())
+                            )
+                        })
+                        .collect();
+                    let msg = format!("Conflicting default methods: {}", methods.join(" "));
+                    return Err(runtime::exception::new(consts::J_ICCE, Some(msg)));
+                }
+                None => {
+                    let msg = format!(
+                        "Class {} does not implement the requested interface {}",
+                        String::from_utf8_lossy(self.name.as_slice()).replace("/", "."),
+                        String::from_utf8_lossy(owner.name.as_slice()).replace("/", ".")
+                    );
+                    return Err(runtime::exception::new(consts::J_ICCE, Some(msg)));
+                }
+            }
+        } else {
+            match cls_obj.v_table.get(idx) {
+                Some(m) => m.clone(),
+                None => mir.clone(),
+            }
+        };
+
+        if m.method.is_abstract() {
+            let msg = format!(
+                "{}.{}{}",
+                String::from_utf8_lossy(self.name.as_slice()).replace("/", "."),
+                String::from_utf8_lossy(m.method.name.as_slice()),
+                String::from_utf8_lossy(m.method.desc.as_slice())
+            );
+            return Err(runtime::exception::new(
+                consts::J_ABSTRACT_METHOD_ERROR,
+                Some(msg),
+            ));
+        }
+
+        Ok(m)
     }
 
     pub fn get_interface_method(
@@ -476,9 +700,19 @@ impl Class {
         name: &BytesRef,
         desc: &BytesRef,
     ) -> Result<MethodIdRef, ()> {
+        //JVMS 5.4.3.4, any method declared by the interface itself, static too
+        if let ClassKind::Instance(cls_obj) = &self.kind {
+            if let Some(m) = cls_obj.all_methods.get(&(name.clone(), desc.clone())) {
+                return Ok(m.clone());
+            }
+        }
         self.get_interface_method_inner(name, desc)
     }
 
+    //only for the fields of the JDK classes the vm depends on (Thread.eetop, Inflater.buf,
+    //Reference.referent...), so a missing one means an rt.jar not matching the vm, a panic;
+    //the fields named by bytecode resolve by 'get_field_ref' (NoSuchFieldError if missing),
+    //those of reflection by their slot
     pub fn get_field_id(&self, name: &BytesRef, desc: &BytesRef, is_static: bool) -> FieldIdRef {
         let k = (self.name.clone(), name.clone(), desc.clone());
 
@@ -502,11 +736,42 @@ impl Class {
             }
         }
 
-        let super_class = self.super_class.clone();
-        super_class
-            .unwrap()
-            .get_class()
-            .get_field_id(name, desc, is_static)
+        match &self.super_class {
+            Some(super_class) => super_class.get_class().get_field_id(name, desc, is_static),
+            None => panic!(
+                "Unknown field of the vm {}.{}:{}, not a JDK 8 rt.jar?",
+                String::from_utf8_lossy(self.name.as_slice()),
+                String::from_utf8_lossy(name.as_slice()),
+                String::from_utf8_lossy(desc.as_slice())
+            ),
+        }
+    }
+
+    //JVMS 5.4.3.2, declared by the class, its superinterfaces, or its superclass
+    pub fn lookup_field(&self, name: &BytesRef, desc: &BytesRef) -> Option<FieldIdRef> {
+        let k = (self.name.clone(), name.clone(), desc.clone());
+        let cls_obj = match &self.kind {
+            ClassKind::Instance(cls_obj) => cls_obj,
+            _ => return None,
+        };
+
+        if let Some(fid) = cls_obj
+            .static_fields
+            .get(&k)
+            .or_else(|| cls_obj.inst_fields.get(&k))
+        {
+            return Some(fid.clone());
+        }
+
+        for (_, itf) in cls_obj.interfaces.iter() {
+            if let Some(fid) = itf.get_class().lookup_field(name, desc) {
+                return Some(fid);
+            }
+        }
+
+        self.super_class
+            .as_ref()
+            .and_then(|super_class| super_class.get_class().lookup_field(name, desc))
     }
 
     pub fn put_field_value(rf: Arc<OopRef>, fir: FieldIdRef, v: Oop) {
@@ -518,8 +783,20 @@ impl Class {
         unsafe {
             match &mut (*ptr).v {
                 oop::RefKind::Inst(inst) => inst.field_values[offset] = v,
+                oop::RefKind::Mirror(mirror) if offset & STATIC_FIELD_OFFSET_MARK != 0 => {
+                    let target = mirror.target.clone().unwrap();
+                    let cls = target.get_mut_class();
+                    cls.put_static_field_value2(offset & !STATIC_FIELD_OFFSET_MARK, v);
+                }
                 oop::RefKind::Mirror(mirror) => mirror.field_values[offset] = v,
                 oop::RefKind::Array(ary) => ary.elements[offset] = v,
+                //Unsafe accesses int[] & long[] by index, AtomicIntegerArray, AtomicLongArray
+                oop::RefKind::TypeArray(oop::TypeArrayDesc::Int(ary)) => {
+                    ary[offset] = v.extract_int()
+                }
+                oop::RefKind::TypeArray(oop::TypeArrayDesc::Long(ary)) => {
+                    ary[offset] = v.extract_long()
+                }
                 t => unreachable!("t = {:?}", t),
             }
         }
@@ -534,16 +811,122 @@ impl Class {
             let ptr = rf.get_raw_ptr();
             match &(*ptr).v {
                 oop::RefKind::Inst(inst) => inst.field_values[offset].clone(),
+                oop::RefKind::Mirror(mirror) if offset & STATIC_FIELD_OFFSET_MARK != 0 => {
+                    let target = mirror.target.clone().unwrap();
+                    let cls = target.get_class();
+                    cls.get_static_field_value2(offset & !STATIC_FIELD_OFFSET_MARK)
+                }
                 oop::RefKind::Mirror(mirror) => match mirror.field_values.get(offset) {
                     Some(v) => v.clone(),
                     _ => unreachable!("mirror = {:?}", mirror),
                 },
                 oop::RefKind::Array(ary) => ary.elements[offset].clone(),
+                oop::RefKind::TypeArray(oop::TypeArrayDesc::Int(ary)) => Oop::new_int(ary[offset]),
+                oop::RefKind::TypeArray(oop::TypeArrayDesc::Long(ary)) => {
+                    Oop::new_long(ary[offset])
+                }
                 t => unreachable!("t = {:?}", t),
             }
         }
     }
 
+    /*
+    volatile & atomic access (JSR-133)
+
+    All volatile reads, volatile writes and compareAndSwap of an object are
+    serialized by the object's fields mutex (of a static field, by the static
+    fields mutex of its class, whether by the mirror or by getstatic/putstatic),
+    so they are atomic with respect to each other, and the lock/unlock pair
+    gives acquire/release ordering.
+    The trailing full fence of a volatile write forbids the StoreLoad reordering
+    with a following volatile read.
+    */
+    //plain Unsafe.get*/put*, under the lock of the volatile & CAS accesses, not to
+    //tear an Oop (an Arc) written meanwhile
+    pub fn get_field_value2_locked(rf: Arc<OopRef>, offset: usize) -> Oop {
+        Self::with_fields_locked(&rf, offset, || Self::get_field_value2(rf.clone(), offset))
+    }
+
+    pub fn put_field_value2_locked(rf: Arc<OopRef>, offset: usize, v: Oop) {
+        Self::with_fields_locked(&rf, offset, || {
+            Self::put_field_value2(rf.clone(), offset, v);
+        });
+    }
+
+    pub fn get_field_value2_volatile(rf: Arc<OopRef>, offset: usize) -> Oop {
+        Self::with_fields_locked(&rf, offset, || {
+            let v = Self::get_field_value2(rf.clone(), offset);
+            fence(Ordering::Acquire);
+            v
+        })
+    }
+
+    pub fn put_field_value2_volatile(rf: Arc<OopRef>, offset: usize, v: Oop) {
+        Self::with_fields_locked(&rf, offset, || {
+            fence(Ordering::Release);
+            Self::put_field_value2(rf.clone(), offset, v);
+        });
+        fence(Ordering::SeqCst);
+    }
+
+    //putOrdered*, a release store without the trailing full fence
+    pub fn put_field_value2_ordered(rf: Arc<OopRef>, offset: usize, v: Oop) {
+        Self::with_fields_locked(&rf, offset, || {
+            fence(Ordering::Release);
+            Self::put_field_value2(rf.clone(), offset, v);
+        });
+    }
+
+    //return true, if the value at 'offset' is the same as 'expected', and replaced by 'v'
+    pub fn compare_and_swap_field_value2(
+        rf: Arc<OopRef>,
+        offset: usize,
+        expected: &Oop,
+        v: Oop,
+    ) -> bool {
+        let swapped = Self::with_fields_locked(&rf, offset, || {
+            let cur = Self::get_field_value2(rf.clone(), offset);
+            if OopRef::is_same(&cur, expected) {
+                Self::put_field_value2(rf.clone(), offset, v);
+                true
+            } else {
+                false
+            }
+        });
+        fence(Ordering::SeqCst);
+
+        swapped
+    }
+
+    //a static field by the mirror (Unsafe.staticFieldBase) is guarded by the
+    //static fields mutex of its class, as getstatic/putstatic of a volatile field
+    fn with_fields_locked<R>(rf: &Arc<OopRef>, offset: usize, f: impl FnOnce() -> R) -> R {
+        let target = if offset & STATIC_FIELD_OFFSET_MARK != 0 {
+            let ptr = rf.get_raw_ptr();
+            match unsafe { &(*ptr).v } {
+                oop::RefKind::Mirror(mirror) => mirror.target.clone(),
+                _ => None,
+            }
+        } else {
+            None
+        };
+
+        match target {
+            Some(target) => {
+                let cls = target.get_class();
+                let _l = match &cls.kind {
+                    ClassKind::Instance(cls_obj) => cls_obj.static_fields_mutex.lock().unwrap(),
+                    _ => unreachable!(),
+                };
+                f()
+            }
+            None => {
+                let _l = rf.lock_fields();
+                f()
+            }
+        }
+    }
+
     pub fn put_static_field_value(&mut self, fid: FieldIdRef, v: Oop) {
         match &mut self.kind {
             ClassKind::Instance(cls_obj) => {
@@ -585,6 +968,47 @@ impl Class {
         }
     }
 
+    //access by slot of the declaring class, see STATIC_FIELD_OFFSET_MARK
+    pub fn put_static_field_value2(&mut self, offset: usize, v: Oop) {
+        match &mut self.kind {
+            ClassKind::Instance(cls_obj) => cls_obj.static_field_values[offset] = v,
+            _ => unreachable!(),
+        }
+    }
+
+    pub fn get_static_field_value2(&self, offset: usize) -> Oop {
+        match &self.kind {
+            ClassKind::Instance(cls_obj) => cls_obj.static_field_values[offset].clone(),
+            _ => unreachable!(),
+        }
+    }
+
+    pub fn put_static_field_value_volatile(&mut self, fid: FieldIdRef, v: Oop) {
+        let owner = fid.field.class.clone();
+        {
+            let owner = owner.get_class();
+            let _l = match &owner.kind {
+                ClassKind::Instance(cls_obj) => cls_obj.static_fields_mutex.lock().unwrap(),
+                _ => unreachable!(),
+            };
+            fence(Ordering::Release);
+            self.put_static_field_value(fid, v);
+        }
+        fence(Ordering::SeqCst);
+    }
+
+    pub fn get_static_field_value_volatile(&self, fid: FieldIdRef) -> Oop {
+        let owner = fid.field.class.clone();
+        let owner = owner.get_class();
+        let _l = match &owner.kind {
+            ClassKind::Instance(cls_obj) => cls_obj.static_fields_mutex.lock().unwrap(),
+            _ => unreachable!(),
+        };
+        let v = self.get_static_field_value(fid);
+        fence(Ordering::Acquire);
+        v
+    }
+
     pub fn check_interface(&self, intf: ClassRef) -> bool {
         match &self.kind {
             ClassKind::Instance(inst) => {
@@ -610,37 +1034,6 @@ impl Class {
             None => false,
         }
     }
-
-    pub fn hack_as_native(&mut self, name: &[u8], desc: &[u8]) {
-        match &mut self.kind {
-            ClassKind::Instance(cls) => {
-                let m = {
-                    let name = Arc::new(Vec::from(name));
-                    let desc = Arc::new(Vec::from(desc));
-                    let k = (name, desc);
-                    let it = cls.all_methods.get_mut(&k).unwrap();
-                    let mut method = it.method.clone();
-                    method.acc_flags |= ACC_NATIVE;
-                    let m = Arc::new(method::MethodId {
-                        offset: it.offset,
-                        method,
-                    });
-                    cls.all_methods.insert(k, m.clone());
-
-                    m
-                };
-
-                info!(
-                    "hack_as_native: {}:{}:{}, native={}",
-                    unsafe { std::str::from_utf8_unchecked(self.name.as_slice()) },
-                    unsafe { std::str::from_utf8_unchecked(name) },
-                    unsafe { std::str::from_utf8_unchecked(desc) },
-                    m.method.is_native()
-                );
-            }
-            _ => unreachable!(),
-        }
-    }
 }
 
 //open api new
@@ -653,10 +1046,12 @@ impl Class {
             class_file,
             n_inst_fields: 0,
             all_methods: FxHashMap::default(),
-            v_table: FxHashMap::default(),
+            v_table: Vec::new(),
+            i_table: FxHashMap::default(),
             static_fields: FxHashMap::default(),
             inst_fields: FxHashMap::default(),
             static_field_values: vec![],
+            static_fields_mutex: Mutex::new(()),
             interfaces: FxHashMap::default(),
             mirror: None,
             signature: None,
@@ -664,6 +1059,8 @@ impl Class {
             enclosing_method: None,
             inner_classes: None,
             cp_cache: ConstantPoolCache::new(cp),
+            reference_type: None,
+            has_finalizer: false,
         };
 
         let mutex = unsafe {
@@ -673,7 +1070,7 @@ impl Class {
         };
 
         Self {
-            clinit_mutex: Arc::new(Mutex::new(())),
+            init_lock: InitLock::default(),
             name,
             state: std::sync::atomic::AtomicU8::new(State::Allocated.into()),
             acc_flags,
@@ -702,7 +1099,7 @@ impl Class {
         };
 
         Self {
-            clinit_mutex: Arc::new(Mutex::new(())),
+            init_lock: InitLock::default(),
             name,
             state: std::sync::atomic::AtomicU8::new(State::Allocated.into()),
             acc_flags: 0, //todo: should be 0?
@@ -732,7 +1129,7 @@ impl Class {
         };
 
         Self {
-            clinit_mutex: Arc::new(Mutex::new(())),
+            init_lock: InitLock::default(),
             name: Arc::new(name),
             state: std::sync::atomic::AtomicU8::new(State::Allocated.into()),
             acc_flags: 0, //todo: should be 0?
@@ -784,7 +1181,7 @@ impl Class {
         };
 
         Self {
-            clinit_mutex: Arc::new(Mutex::new(())),
+            init_lock: InitLock::default(),
             name: Arc::new(name2),
             state: std::sync::atomic::AtomicU8::new(State::Allocated.into()),
             acc_flags: 0, //todo: should be 0?
@@ -881,12 +1278,52 @@ impl ClassObject {
             });
     }
 
-    fn link_methods(&mut self, this_ref: ClassRef, cls_name: BytesRef) {
+    //superinterfaces, direct & indirect, each after its own superinterfaces,
+    //in the order of the interfaces arrays (JVMS 5.5, step 7)
+    fn collect_interfaces(&self, out: &mut Vec<ClassRef>) {
+        let cp = &self.class_file.cp;
+        for &it in self.class_file.interfaces.iter() {
+            let name = constant_pool::get_class_name(cp, it as usize);
+            if let Some(class) = self.interfaces.get(name) {
+                class.extract_inst().collect_interfaces(out);
+                if !out.iter().any(|c| Arc::ptr_eq(c, class)) {
+                    out.push(class.clone());
+                }
+            }
+        }
+    }
+
+    //a non-abstract, non-static method declared
+    fn has_default_methods(&self) -> bool {
+        self.class_file
+            .methods
+            .iter()
+            .any(|it| it.acc_flags & (ACC_ABSTRACT | ACC_STATIC) == 0)
+    }
+
+    //v_table starts as a copy of the super's, a method takes the slots of all the
+    //methods it overrides (JVMS 5.4.5), or a new slot;
+    //the methods of an interface numbered in order, as slots of its itable
+    fn link_methods(
+        &mut self,
+        this_ref: ClassRef,
+        cls_name: BytesRef,
+        class_loader: Option<ClassLoader>,
+        is_interface: bool,
+        super_class: Option<&ClassRef>,
+    ) {
         let class_file = self.class_file.clone();
         let cp = &class_file.cp;
 
-        class_file.methods.iter().enumerate().for_each(|(i, it)| {
-            let method = method::Method::new(
+        if !is_interface {
+            if let Some(super_class) = super_class {
+                self.v_table = super_class.extract_inst().v_table.clone();
+            }
+        }
+
+        let mut n_itable = 0;
+        for (i, it) in class_file.methods.iter().enumerate() {
+            let mut method = method::Method::new(
                 cp,
                 it,
                 this_ref.clone(),
@@ -894,18 +1331,102 @@ impl ClassObject {
                 i,
                 cls_name.clone(),
             );
-            let method_id = Arc::new(method::MethodId { offset: i, method });
+            if is_hacked_native(&cls_name, &method) {
+                method.acc_flags |= ACC_NATIVE;
+            }
+
+            let mut slots = vec![];
+            let is_virtual = !method.is_static()
+                && !method.is_private()
+                && method.name.as_slice() != consts::METHOD_NAME_INIT;
+            let vtable_index = if !is_virtual {
+                None
+            } else if is_interface {
+                n_itable += 1;
+                Some(n_itable - 1)
+            } else {
+                slots = self
+                    .v_table
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, m)| {
+                        m.method.name == method.name
+                            && m.method.desc == method.desc
+                            && can_override(cls_name.as_slice(), class_loader, &m.method)
+                    })
+                    .map(|(slot, _)| slot)
+                    .collect();
+                if slots.is_empty() {
+                    slots.push(self.v_table.len());
+                }
+                Some(slots[0])
+            };
+
+            //the slot of reflect.Method & Constructor (see reflect::new_method_normal),
+            //found back by get_method_by_slot: the index in ClassFile.methods
+            let method_id = Arc::new(method::MethodId {
+                offset: i,
+                vtable_index,
+                method,
+            });
+            for slot in slots {
+                if slot == self.v_table.len() {
+                    self.v_table.push(method_id.clone());
+                } else {
+                    self.v_table[slot] = method_id.clone();
+                }
+            }
 
             let name = method_id.method.name.clone();
             let desc = method_id.method.desc.clone();
-            let k = (name, desc);
+            self.all_methods.insert((name, desc), method_id);
+        }
+    }
 
-            self.all_methods.insert(k.clone(), method_id.clone());
+    //for each superinterface, the methods selected for its methods (JVMS 5.4.6):
+    //the one declared by the class hierarchy, or the only maximally-specific default method
+    fn link_i_table(&mut self, super_class: Option<&ClassRef>) {
+        let mut interfaces = vec![];
+        collect_interfaces(&self.interfaces, super_class, &mut interfaces);
+
+        for itf in interfaces.iter() {
+            let mut methods: Vec<&MethodIdRef> = itf
+                .extract_inst()
+                .all_methods
+                .values()
+                .filter(|m| m.vtable_index.is_some())
+                .collect();
+            methods.sort_by_key(|m| m.vtable_index);
+
+            let entries = methods
+                .iter()
+                .map(|m| {
+                    let k = (m.method.name.clone(), m.method.desc.clone());
+                    match lookup_overrider(&self.all_methods, super_class, &k) {
+                        Some(m) => ITableEntry::Method(m),
+                        None => {
+                            let methods = max_specific_methods(&interfaces, &k);
+                            select_default(methods)
+                                .unwrap_or_else(|| ITableEntry::Method((*m).clone()))
+                        }
+                    }
+                })
+                .collect();
+            self.i_table.insert(itf.name(), entries);
+        }
+    }
 
-            if !method_id.method.is_static() {
-                self.v_table.insert(k, method_id);
-            }
-        });
+    fn link_finalizer(&mut self, super_class: Option<&ClassRef>) {
+        let inherited = super_class.is_some_and(|cls| cls.extract_inst().has_finalizer);
+        let k = (new_br("finalize"), new_br("()V"));
+        self.has_finalizer = match self.all_methods.get(&k) {
+            //Object.finalize, an empty one, is 'return' only
+            Some(m) if !m.method.is_static() => match &m.method.code {
+                Some(code) => code.code.as_slice() != [0xb1],
+                None => m.method.is_native(),
+            },
+            _ => inherited,
+        };
     }
 
     fn link_attributes(&mut self) {
@@ -960,10 +1481,8 @@ impl Class {
                     return Ok(m.clone());
                 }
             }
-            ClassKind::ObjectArray(ary) => {
-                //use java/lang/Object, methods
-            }
-            _ => unreachable!(),
+            //use java/lang/Object, methods
+            ClassKind::ObjectArray(_) | ClassKind::TypeArray(_) => (),
         }
 
         if with_super {
@@ -980,7 +1499,9 @@ impl Class {
         Err(())
     }
 
-    fn get_virtual_method_inner(
+    //JVMS 5.4.3.3, not declared by the class hierarchy, the only maximally-specific
+    //default method, or any of the maximally-specific methods
+    fn get_superinterface_method(
         &self,
         name: &BytesRef,
         desc: &BytesRef,
@@ -988,16 +1509,19 @@ impl Class {
         let k = (name.clone(), desc.clone());
         match &self.kind {
             ClassKind::Instance(cls_obj) => {
-                if let Some(m) = cls_obj.v_table.get(&k) {
-                    return Ok(m.clone());
+                let mut interfaces = vec![];
+                collect_interfaces(
+                    &cls_obj.interfaces,
+                    self.super_class.as_ref(),
+                    &mut interfaces,
+                );
+                match select_default(max_specific_methods(&interfaces, &k)) {
+                    Some(ITableEntry::Method(m)) => Ok(m),
+                    Some(ITableEntry::Conflict(methods)) => Ok(methods[0].clone()),
+                    None => Err(()),
                 }
             }
-            _ => unreachable!(),
-        }
-
-        match self.super_class.as_ref() {
-            Some(super_class) => super_class.get_class().get_virtual_method_inner(name, desc),
-            None => Err(()),
+            _ => Err(()),
         }
     }
 
@@ -1008,7 +1532,11 @@ impl Class {
     ) -> Result<MethodIdRef, ()> {
         let k = (name.clone(), desc.clone());
         match &self.kind {
-            ClassKind::Instance(cls_obj) => match cls_obj.v_table.get(&k) {
+            ClassKind::Instance(cls_obj) => match cls_obj
+                .all_methods
+                .get(&k)
+                .filter(|m| !m.method.is_static())
+            {
                 Some(m) => return Ok(m.clone()),
                 None => {
                     for (_, itf) in cls_obj.interfaces.iter() {
@@ -1031,3 +1559,540 @@ impl Class {
         }
     }
 }
+
+//Java methods replaced by natives, marked before the v_table & i_table are
+//built, so the natives are selected by subclasses & interfaces too
+const HACKED_NATIVES: &[(&[u8], &[u8], &[u8])] = &[
+    (
+        b"sun/nio/cs/StreamEncoder",
+        b"forOutputStreamWriter",
+        b"(Ljava/io/OutputStream;Ljava/lang/Object;Ljava/lang/String;)Lsun/nio/cs/StreamEncoder;",
+    ),
+    (b"java/lang/System", b"load", b"(Ljava/lang/String;)V"),
+    //the referent read under the lock of the collection, see heap
+    (b"java/lang/ref/Reference", b"get", b"()Ljava/lang/Object;"),
+    //todo: support load lib
+    (
+        b"java/lang/System",
+        b"loadLibrary",
+        b"(Ljava/lang/String;)V",
+    ),
+];
+
+fn is_hacked_native(cls_name: &BytesRef, method: &method::Method) -> bool {
+    HACKED_NATIVES.iter().any(|(cls, name, desc)| {
+        cls_name.as_slice() == *cls
+            && method.name.as_slice() == *name
+            && method.desc.as_slice() == *desc
+    })
+}
+
+//JVMS 5.4.5, a method of the super v_table overridden by a method of the class
+//'cls_name' defined by 'class_loader'
+fn can_override(cls_name: &[u8], class_loader: Option<ClassLoader>, m: &method::Method) -> bool {
+    m.is_public() || m.is_protected() || access::in_package(&m.class, cls_name, class_loader)
+}
+
+//all superinterfaces, direct or not, of a class
+fn collect_interfaces(
+    interfaces: &FxHashMap<BytesRef, ClassRef>,
+    super_class: Option<&ClassRef>,
+    out: &mut Vec<ClassRef>,
+) {
+    for itf in interfaces.values() {
+        if !out.iter().any(|it| Arc::ptr_eq(it, itf)) {
+            out.push(itf.clone());
+        }
+        collect_interfaces(&itf.extract_inst().interfaces, None, out);
+    }
+
+    if let Some(super_class) = super_class {
+        let cls = super_class.get_class();
+        if let ClassKind::Instance(cls_obj) = &cls.kind {
+            collect_interfaces(&cls_obj.interfaces, cls.super_class.as_ref(), out);
+        }
+    }
+}
+
+//JVMS 5.4.6 step 2, an instance method declared by the class or its superclasses
+fn lookup_overrider(
+    all_methods: &FxHashMap<(BytesRef, BytesRef), MethodIdRef>,
+    super_class: Option<&ClassRef>,
+    k: &(BytesRef, BytesRef),
+) -> Option<MethodIdRef> {
+    match all_methods.get(k) {
+        Some(m) if !m.method.is_static() && !m.method.is_private() => Some(m.clone()),
+        _ => {
+            let cls = super_class?.get_class();
+            match &cls.kind {
+                ClassKind::Instance(cls_obj) => {
+                    lookup_overrider(&cls_obj.all_methods, cls.super_class.as_ref(), k)
+                }
+                _ => None,
+            }
+        }
+    }
+}
+
+//JVMS 5.4.3.3, declared by a superinterface, and not by any of its subinterfaces
+fn max_specific_methods(interfaces: &[ClassRef], k: &(BytesRef, BytesRef)) -> Vec<MethodIdRef> {
+    let declared: Vec<(&ClassRef, &MethodIdRef)> = interfaces
+        .iter()
+        .filter_map(|itf| {
+            itf.extract_inst()
+                .all_methods
+                .get(k)
+                .filter(|m| !m.method.is_static() && !m.method.is_private())
+                .map(|m| (itf, m))
+        })
+        .collect();
+
+    declared
+        .iter()
+        .filter(|(itf, _)| {
+            !declared.iter().any(|(other, _)| {
+                !Arc::ptr_eq(other, itf) && other.get_class().check_interface((*itf).clone())
+            })
+        })
+        .map(|(_, m)| (*m).clone())
+        .collect()
+}
+
+//the only non-abstract one, or an abstract one if none
+fn select_default(methods: Vec<MethodIdRef>) -> Option<ITableEntry> {
+    let defaults: Vec<MethodIdRef> = methods
+        .iter()
+        .filter(|m| !m.method.is_abstract())
+        .cloned()
+        .collect();
+    match defaults.len() {
+        0 => methods.first().cloned().map(ITableEntry::Method),
+        1 => Some(ITableEntry::Method(defaults[0].clone())),
+        _ => Some(ITableEntry::Conflict(defaults)),
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::{init_class, Class, ClassPtr, ITableEntry, State};
+    use crate::oop::Oop;
+    use crate::runtime::{self, ClassLoader};
+    use crate::types::ClassRef;
+    use classfile::{consts, flags::*, U2};
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+    use std::time::Duration;
+
+    const N_THREADS: usize = 8;
+    const N_LOOP: usize = 10_000;
+
+    #[test]
+    fn t_cas_int_ary() {
+        let ary = Oop::new_int_ary(1);
+
+        let workers: Vec<_> = (0..N_THREADS)
+            .map(|_| {
+                let ary = ary.clone();
+                thread::spawn(move || {
+                    for _ in 0..N_LOOP {
+                        //AtomicInteger.getAndIncrement
+                        loop {
+                            let rf = ary.extract_ref();
+                            let cur = Class::get_field_value2_volatile(rf.clone(), 0);
+                            let next = Oop::new_int(cur.extract_int() + 1);
+                            if Class::compare_and_swap_field_value2(rf, 0, &cur, next) {
+                                break;
+                            }
+                        }
+                    }
+                })
+            })
+            .collect();
+        workers.into_iter().for_each(|t| t.join().unwrap());
+
+        let v = Class::get_field_value2_volatile(ary.extract_ref(), 0);
+        assert_eq!(v.extract_int(), (N_THREADS * N_LOOP) as i32);
+    }
+
+    #[test]
+    fn t_cas_long_ary() {
+        let ary = Oop::new_long_ary(2);
+
+        let workers: Vec<_> = (0..N_THREADS)
+            .map(|_| {
+                let ary = ary.clone();
+                thread::spawn(move || {
+                    for _ in 0..N_LOOP {
+                        loop {
+                            let rf = ary.extract_ref();
+                            let cur = Class::get_field_value2_volatile(rf.clone(), 1);
+                            let next = Oop::new_long(cur.extract_long() + 1);
+                            if Class::compare_and_swap_field_value2(rf, 1, &cur, next) {
+                                break;
+                            }
+                        }
+                    }
+                })
+            })
+            .collect();
+        workers.into_iter().for_each(|t| t.join().unwrap());
+
+        let rf = ary.extract_ref();
+        assert_eq!(Class::get_field_value2(rf.clone(), 0).extract_long(), 0);
+        assert_eq!(
+            Class::get_field_value2(rf, 1).extract_long(),
+            (N_THREADS * N_LOOP) as i64
+        );
+    }
+
+    #[test]
+    fn t_cas_mismatch() {
+        let ary = Oop::new_int_ary(1);
+        let rf = ary.extract_ref();
+        assert!(!Class::compare_and_swap_field_value2(
+            rf.clone(),
+            0,
+            &Oop::new_int(1),
+            Oop::new_int(2)
+        ));
+        assert!(Class::compare_and_swap_field_value2(
+            rf.clone(),
+            0,
+            &Oop::new_int(0),
+            Oop::new_int(2)
+        ));
+        assert_eq!(Class::get_field_value2(rf, 0).extract_int(), 2);
+    }
+
+    #[test]
+    fn t_volatile_publish() {
+        //writer publishes data[0], then flag data[1]; reader must see data after flag
+        let ary = Oop::new_int_ary(2);
+        let reader = {
+            let ary = ary.clone();
+            thread::spawn(move || loop {
+                let rf = ary.extract_ref();
+                if Class::get_field_value2_volatile(rf.clone(), 1).extract_int() == 1 {
+                    return Class::get_field_value2_volatile(rf, 0).extract_int();
+                }
+                thread::yield_now();
+            })
+        };
+
+        let rf = ary.extract_ref();
+        Class::put_field_value2_volatile(rf.clone(), 0, Oop::new_int(42));
+        Class::put_field_value2_volatile(rf, 1, Oop::new_int(1));
+
+        assert_eq!(reader.join().unwrap(), 42);
+    }
+
+    const PUBLIC_ABSTRACT: U2 = ACC_PUBLIC | ACC_ABSTRACT;
+    const INTERFACE: U2 = ACC_PUBLIC | ACC_INTERFACE | ACC_ABSTRACT;
+
+    //the constant pool of 'class_file', the index of the next entry
+    struct Pool(Vec<u8>, U2);
+
+    impl Pool {
+        fn utf8(&mut self, v: &str) -> U2 {
+            self.0.push(1);
+            self.0.extend_from_slice(&(v.len() as U2).to_be_bytes());
+            self.0.extend_from_slice(v.as_bytes());
+            self.1 += 1;
+            self.1 - 1
+        }
+
+        fn class(&mut self, v: &str) -> U2 {
+            let name = self.utf8(v);
+            self.0.push(7);
+            self.0.extend_from_slice(&name.to_be_bytes());
+            self.1 += 1;
+            self.1 - 1
+        }
+
+        fn members(&mut self, members: &[(U2, &str, &str)]) -> Vec<u8> {
+            let mut out = (members.len() as U2).to_be_bytes().to_vec();
+            for (acc_flags, name, desc) in members {
+                out.extend_from_slice(&acc_flags.to_be_bytes());
+                out.extend_from_slice(&self.utf8(name).to_be_bytes());
+                out.extend_from_slice(&self.utf8(desc).to_be_bytes());
+                out.extend_from_slice(&[0, 0]);
+            }
+            out
+        }
+    }
+
+    //a class file of fields & methods (acc_flags, name, desc), the methods without code
+    pub(crate) fn class_file(
+        acc_flags: U2,
+        name: &str,
+        super_name: Option<&str>,
+        interfaces: &[&str],
+        fields: &[(U2, &str, &str)],
+        methods: &[(U2, &str, &str)],
+    ) -> Vec<u8> {
+        let mut cp = Pool(vec![], 1);
+        let this_class = cp.class(name);
+        let super_class = super_name.map_or(0, |v| cp.class(v));
+        let interfaces: Vec<U2> = interfaces.iter().map(|v| cp.class(v)).collect();
+        let fields = cp.members(fields);
+        let methods = cp.members(methods);
+
+        let mut buf = vec![0xca, 0xfe, 0xba, 0xbe, 0, 0, 0, 52];
+        buf.extend_from_slice(&cp.1.to_be_bytes());
+        buf.extend_from_slice(&cp.0);
+        buf.extend_from_slice(&acc_flags.to_be_bytes());
+        buf.extend_from_slice(&this_class.to_be_bytes());
+        buf.extend_from_slice(&super_class.to_be_bytes());
+        buf.extend_from_slice(&(interfaces.len() as U2).to_be_bytes());
+        interfaces
+            .iter()
+            .for_each(|v| buf.extend_from_slice(&v.to_be_bytes()));
+        buf.extend_from_slice(&fields);
+        buf.extend_from_slice(&methods);
+        buf.extend_from_slice(&[0, 0]);
+        buf
+    }
+
+    //a class parsed, linked & put into the boot dictionary, as loaded from the boot
+    //class path; an empty java/lang/Object defined first, rt.jar is not needed
+    pub(crate) fn define(class_file: &[u8]) -> ClassRef {
+        define_by(ClassLoader::Bootstrap, class_file)
+    }
+
+    //as define, the class defined by 'class_loader'
+    fn define_by(class_loader: ClassLoader, class_file: &[u8]) -> ClassRef {
+        static DEFINE: Mutex<()> = Mutex::new(());
+        let _guard = DEFINE.lock().unwrap();
+        if runtime::sys_dic_find(consts::J_OBJECT).is_none() {
+            let object = std::str::from_utf8(consts::J_OBJECT).unwrap();
+            let object = self::class_file(ACC_PUBLIC, object, None, &[], &[], &[]);
+            link(ClassLoader::Bootstrap, &object);
+        }
+        link(class_loader, class_file)
+    }
+
+    fn link(class_loader: ClassLoader, class_file: &[u8]) -> ClassRef {
+        let cf = class_parser::parse_class(class_file).unwrap().1;
+        let class = ClassPtr::new(Class::new_class(Arc::new(Box::new(cf)), Some(class_loader)));
+        runtime::sys_dic_put(class.name().as_slice(), class.clone());
+        let mut cls = class.get_mut_class();
+        cls.set_class_state(State::Loaded);
+        cls.link_class(class.clone());
+        class
+    }
+
+    fn vtable_entry(class: &ClassRef, slot: usize) -> &[u8] {
+        class.extract_inst().v_table[slot]
+            .method
+            .cls_name
+            .as_slice()
+    }
+
+    //the class declaring each method selected, by the itable of 'itf'
+    fn itable_entry(class: &ClassRef, itf: &str, slot: usize) -> Vec<String> {
+        let entries = &class.extract_inst().i_table[&crate::new_br(itf)];
+        let methods = match &entries[slot] {
+            ITableEntry::Method(m) => vec![m.clone()],
+            ITableEntry::Conflict(methods) => methods.clone(),
+        };
+        let mut names: Vec<String> = methods
+            .iter()
+            .map(|m| String::from_utf8_lossy(m.method.cls_name.as_slice()).to_string())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn t_vtable() {
+        let methods = [(ACC_PUBLIC, "m", "()V"), (0, "p", "()V")];
+        let a = define(&class_file(
+            ACC_PUBLIC,
+            "t/vt/A",
+            Some("java/lang/Object"),
+            &[],
+            &[],
+            &methods,
+        ));
+        let b = define(&class_file(
+            ACC_PUBLIC,
+            "t/vt/B",
+            Some("t/vt/A"),
+            &[],
+            &[],
+            &methods,
+        ));
+        let c = define(&class_file(
+            ACC_PUBLIC,
+            "t/other/C",
+            Some("t/vt/A"),
+            &[],
+            &[],
+            &methods,
+        ));
+
+        let slot = |class: &ClassRef, name: &str| {
+            let m = class
+                .get_class()
+                .get_virtual_method(&crate::new_br(name), &crate::new_br("()V"));
+            m.unwrap().vtable_index.unwrap()
+        };
+        let (m, p) = (slot(&a, "m"), slot(&a, "p"));
+        assert_eq!(a.extract_inst().v_table.len(), 2);
+
+        //overridden, in the slots of A
+        assert_eq!((slot(&b, "m"), slot(&b, "p")), (m, p));
+        assert_eq!(vtable_entry(&b, m), b"t/vt/B");
+        assert_eq!(vtable_entry(&b, p), b"t/vt/B");
+
+        //package-private A.p not overridden from another package, C.p in a new slot
+        assert_eq!(slot(&c, "m"), m);
+        assert_eq!(vtable_entry(&c, p), b"t/vt/A");
+        assert_eq!(slot(&c, "p"), 2);
+        assert_eq!(vtable_entry(&c, 2), b"t/other/C");
+
+        //nor from the same package name, of another loader
+        let d = class_file(ACC_PUBLIC, "t/vt/D", Some("t/vt/A"), &[], &[], &methods);
+        let d = define_by(ClassLoader::Defined(1), &d);
+        assert_eq!(vtable_entry(&d, p), b"t/vt/A");
+        assert_eq!(slot(&d, "p"), 2);
+    }
+
+    #[test]
+    fn t_itable() {
+        let object = Some("java/lang/Object");
+        let d = [(ACC_PUBLIC, "d", "()V")];
+        let abstract_d = [(PUBLIC_ABSTRACT, "d", "()V")];
+        define(&class_file(INTERFACE, "t/it/I", object, &[], &[], &d));
+        define(&class_file(
+            INTERFACE,
+            "t/it/J",
+            object,
+            &["t/it/I"],
+            &[],
+            &d,
+        ));
+        define(&class_file(INTERFACE, "t/it/K", object, &[], &[], &d));
+        define(&class_file(
+            INTERFACE,
+            "t/it/L",
+            object,
+            &[],
+            &[],
+            &abstract_d,
+        ));
+        define(&class_file(ACC_PUBLIC, "t/it/Base", object, &[], &[], &d));
+
+        //the maximally-specific default, J.d over I.d
+        let a = define(&class_file(
+            ACC_PUBLIC,
+            "t/it/A",
+            object,
+            &["t/it/I", "t/it/J"],
+            &[],
+            &[],
+        ));
+        assert_eq!(itable_entry(&a, "t/it/I", 0), ["t/it/J"]);
+        assert_eq!(itable_entry(&a, "t/it/J", 0), ["t/it/J"]);
+
+        //two maximally-specific defaults, a conflict
+        let b = define(&class_file(
+            ACC_PUBLIC,
+            "t/it/B",
+            object,
+            &["t/it/J", "t/it/K"],
+            &[],
+            &[],
+        ));
+        assert_eq!(itable_entry(&b, "t/it/K", 0), ["t/it/J", "t/it/K"]);
+
+        //a default over an abstract one
+        let c = define(&class_file(
+            ACC_PUBLIC,
+            "t/it/C",
+            object,
+            &["t/it/K", "t/it/L"],
+            &[],
+            &[],
+        ));
+        assert_eq!(itable_entry(&c, "t/it/L", 0), ["t/it/K"]);
+
+        //a method of the superclass over the defaults
+        let d = define(&class_file(
+            ACC_PUBLIC,
+            "t/it/D",
+            Some("t/it/Base"),
+            &["t/it/J", "t/it/K"],
+            &[],
+            &[],
+        ));
+        assert_eq!(itable_entry(&d, "t/it/J", 0), ["t/it/Base"]);
+        assert_eq!(itable_entry(&d, "t/it/K", 0), ["t/it/Base"]);
+        let m = d
+            .get_class()
+            .get_virtual_method(&crate::new_br("d"), &crate::new_br("()V"));
+        assert_eq!(m.unwrap().method.cls_name.as_slice(), b"t/it/Base");
+    }
+
+    #[test]
+    fn t_method_slot() {
+        let methods = [
+            (ACC_PUBLIC, "<init>", "()V"),
+            (ACC_PUBLIC, "<init>", "(I)V"),
+            (ACC_PUBLIC, "add", "(II)I"),
+            (ACC_PUBLIC, "add", "(JJ)J"),
+            (ACC_PUBLIC | ACC_STATIC, "add", "(Ljava/lang/String;)V"),
+        ];
+        let object = Some("java/lang/Object");
+        let a = define(&class_file(
+            ACC_PUBLIC,
+            "t/slot/A",
+            object,
+            &[],
+            &[],
+            &methods,
+        ));
+
+        let cls = a.get_class();
+        for (slot, (_, name, desc)) in methods.iter().enumerate() {
+            let m = cls.get_method_by_slot(slot).unwrap();
+            assert_eq!(m.offset, slot);
+            assert_eq!(m.method.name.as_slice(), name.as_bytes());
+            assert_eq!(m.method.desc.as_slice(), desc.as_bytes());
+        }
+        assert!(cls.get_method_by_slot(methods.len()).is_none());
+    }
+
+    #[test]
+    fn t_init_lock() {
+        let object = Some("java/lang/Object");
+        let a = define(&class_file(ACC_PUBLIC, "t/init/A", object, &[], &[], &[]));
+        assert_eq!(a.get_class().get_class_state(), State::Linked);
+        assert!(init_class(&a).is_ok());
+        assert_eq!(a.get_class().get_class_state(), State::FullyIni);
+
+        //being initialized by this thread, as by its own <clinit>
+        let b = define(&class_file(ACC_PUBLIC, "t/init/B", object, &[], &[], &[]));
+        let mut initializer = b.get_class().init_lock.thread.lock().unwrap();
+        *initializer = Some(thread::current().id());
+        b.get_mut_class().set_class_state(State::BeingIni);
+        drop(initializer);
+        assert!(init_class(&b).is_ok());
+        assert_eq!(b.get_class().get_class_state(), State::BeingIni);
+
+        //another thread waits for this one
+        let waiter = {
+            let b = b.clone();
+            thread::spawn(move || init_class(&b).is_ok())
+        };
+        thread::sleep(Duration::from_millis(100));
+        assert!(!waiter.is_finished());
+
+        let mut initializer = b.get_class().init_lock.thread.lock().unwrap();
+        b.get_mut_class().set_class_state(State::FullyIni);
+        *initializer = None;
+        b.get_class().init_lock.cond.notify_all();
+        drop(initializer);
+        assert!(waiter.join().unwrap());
+    }
+}
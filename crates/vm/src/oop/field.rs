@@ -1,5 +1,5 @@
 use crate::oop::{self, consts as oop_consts, Oop, ValueType};
-use crate::runtime::require_class2;
+use crate::runtime::{access, exception, resolve_class};
 use crate::types::ClassRef;
 use crate::types::*;
 use crate::util;
@@ -12,25 +12,51 @@ use std::fmt;
 use std::ops::Deref;
 use std::sync::Arc;
 
-pub fn get_field_ref(cp: &ConstantPool, idx: usize, is_static: bool) -> FieldIdRef {
+//Fieldref at 'idx' resolved for D, JVMS 5.4.3.2; the class initialized when used (JVMS 5.5)
+pub fn get_field_ref(cp: &ConstantPool, idx: usize, d: &ClassRef) -> Result<FieldIdRef, Oop> {
     let (class_index, name_and_type_index) = constant_pool::get_field_ref(cp, idx);
+    let class = resolve_class(class_index, cp, d)?;
 
-    //load Field's Class, then init it
-    let class = require_class2(class_index, cp).unwrap_or_else(|| {
-        panic!(
-            "Unknown field class {:?}",
-            cp.get(class_index as usize)
-                .expect("Missing item")
-                .as_cp_item(cp)
-        )
-    });
+    let (name, desc) = constant_pool::get_name_and_type(cp, name_and_type_index as usize);
+    let fir = match class.get_class().lookup_field(name, desc) {
+        Some(fir) => fir,
+        None => {
+            let msg = String::from_utf8_lossy(name.as_slice()).to_string();
+            return Err(exception::new(consts::J_NO_SUCH_FIELD_ERROR, Some(msg)));
+        }
+    };
 
-    oop::class::init_class(&class);
-    oop::class::init_class_fully(&class);
+    let field = &fir.field;
+    if !access::member_accessible(&field.class, field.acc_flags, d) {
+        let what = format!("field {}", field_name(field));
+        return Err(access::illegal_access(what, d));
+    }
 
-    let (name, desc) = constant_pool::get_name_and_type(cp, name_and_type_index as usize);
-    let class = class.get_class();
-    class.get_field_id(name, desc, is_static)
+    Ok(fir)
+}
+
+//ICCE if the field resolved isn't static for getstatic & putstatic, or static
+//for getfield & putfield
+pub fn check_static(fir: &FieldIdRef, is_static: bool) -> Result<(), Oop> {
+    let field = &fir.field;
+    if field.is_static() == is_static {
+        return Ok(());
+    }
+
+    let msg = if is_static {
+        format!("Expected static field {}", field_name(field))
+    } else {
+        format!("Expected non-static field {}", field_name(field))
+    };
+    Err(exception::new(consts::J_ICCE, Some(msg)))
+}
+
+fn field_name(field: &Field) -> String {
+    format!(
+        "{}.{}",
+        access::external_name(&field.class),
+        String::from_utf8_lossy(field.name.as_slice())
+    )
 }
 
 pub fn build_inited_field_values(class: ClassRef) -> Vec<Oop> {
@@ -104,6 +130,10 @@ pub struct Field {
     pub acc_flags: U2,
     pub value_type: ValueType,
     pub attr_constant_value: Option<Oop>,
+
+    attr_signature: Option<BytesRef>,
+    annotation: Option<Vec<u8>>,
+    type_annotation: Option<Vec<u8>>,
 }
 
 impl Field {
@@ -125,6 +155,13 @@ impl Field {
             }
         }
 
+        let attr_signature = match util::attributes::get_signature(&fi.attrs) {
+            0 => None,
+            idx => Some(constant_pool::get_utf8(cp, idx as usize).clone()),
+        };
+        let annotation = util::attributes::assemble_annotation(&fi.attrs);
+        let type_annotation = util::attributes::assemble_type_annotation(&fi.attrs);
+
         Self {
             class,
             cls_name,
@@ -133,6 +170,9 @@ impl Field {
             acc_flags,
             value_type,
             attr_constant_value,
+            attr_signature,
+            annotation,
+            type_annotation,
         }
     }
 
@@ -178,6 +218,19 @@ impl Field {
     pub fn get_attr_constant_value(&self) -> Option<Oop> {
         self.attr_constant_value.clone()
     }
+
+    //generic signature, 'Signature' attribute
+    pub fn get_attr_signature(&self) -> Option<BytesRef> {
+        self.attr_signature.clone()
+    }
+
+    pub fn get_annotation(&self) -> Option<Vec<u8>> {
+        self.annotation.clone()
+    }
+
+    pub fn get_type_annotation(&self) -> Option<Vec<u8>> {
+        self.type_annotation.clone()
+    }
 }
 
 impl fmt::Debug for Field {
@@ -1,5 +1,6 @@
-use crate::oop::{self, ValueType};
-use crate::runtime::{self, require_class2};
+use crate::oop::{self, Oop, ValueType};
+use crate::runtime::quick::QuickTable;
+use crate::runtime::{self, access, exception, require_class2};
 use crate::types::ClassRef;
 use crate::types::*;
 use crate::util;
@@ -9,48 +10,74 @@ use classfile::{
     attributes::Code, attributes::LineNumber, constant_pool, consts, flags::*, AttributeType,
     BytesRef, ConstantPool, FieldInfo, MethodInfo, U2,
 };
+use jit::MethodState;
 use std::fmt;
 use std::fmt::Formatter;
 use std::ops::Deref;
 use std::sync::Arc;
 
-pub fn get_method_ref(cp: &ConstantPool, idx: usize) -> Result<MethodIdRef, ()> {
+//Methodref or InterfaceMethodref at 'idx' resolved for D, JVMS 5.4.3.3 & 5.4.3.4;
+//the class initialized when used (JVMS 5.5)
+pub fn get_method_ref(cp: &ConstantPool, idx: usize, d: &ClassRef) -> Result<MethodIdRef, Oop> {
     let (tag, class_index, name_and_type_index) = constant_pool::get_method_ref(cp, idx);
-
-    //load Method's Class, then init it
-    let class = require_class2(class_index, cp).unwrap_or_else(|| {
-        panic!(
-            "Unknown method class {:?}",
-            cp.get(class_index as usize)
-                .expect("Missing item")
-                .as_cp_item(cp)
-        )
-    });
-
-    oop::class::init_class(&class);
-    oop::class::init_class_fully(&class);
+    let class = runtime::resolve_class(class_index, cp, d)?;
+    let is_interface_ref = tag == consts::CONSTANT_INTERFACE_METHOD_REF_TAG;
 
     let (name, desc) = constant_pool::get_name_and_type(cp, name_and_type_index as usize);
-    let class = class.get_class();
+    let cls = class.get_class();
 
     trace!(
         "get_method_ref cls={}, name={}, desc={}",
-        unsafe { std::str::from_utf8_unchecked(class.name.as_slice()) },
+        unsafe { std::str::from_utf8_unchecked(cls.name.as_slice()) },
         unsafe { std::str::from_utf8_unchecked(name.as_slice()) },
         unsafe { std::str::from_utf8_unchecked(desc.as_slice()) },
     );
-    if tag == consts::CONSTANT_METHOD_REF_TAG {
-        // invokespecial, invokestatic and invokevirtual
-        class.get_class_method(name, desc)
-    } else {
+    if cls.is_interface() != is_interface_ref {
+        let msg = if is_interface_ref {
+            format!(
+                "Found class {}, but interface was expected",
+                access::external_name(&class)
+            )
+        } else {
+            format!(
+                "Found interface {}, but class was expected",
+                access::external_name(&class)
+            )
+        };
+        return Err(exception::new(consts::J_ICCE, Some(msg)));
+    }
+
+    let m = if is_interface_ref {
         // invokeinterface
-        class.get_interface_method(name, desc)
+        cls.get_interface_method(name, desc)
+    } else {
+        // invokespecial, invokestatic and invokevirtual
+        cls.get_class_method(name, desc)
+    };
+    let what = format!(
+        "{}.{}{}",
+        access::external_name(&class),
+        String::from_utf8_lossy(name.as_slice()),
+        String::from_utf8_lossy(desc.as_slice())
+    );
+    let mir = match m {
+        Ok(mir) => mir,
+        Err(_) => return Err(exception::new(consts::J_NO_SUCH_METHOD_ERROR, Some(what))),
+    };
+
+    if !access::member_accessible(&mir.method.class, mir.method.acc_flags, d) {
+        return Err(access::illegal_access(format!("method {}", what), d));
     }
+
+    Ok(mir)
 }
 
 #[derive(Debug, Clone)]
 pub struct MethodId {
     pub offset: usize,
+    //slot of v_table of the class, or of the itable of an interface;
+    //None for static, private methods & ctors, which are never selected
+    pub vtable_index: Option<usize>,
     pub method: Method,
 }
 
@@ -66,6 +93,10 @@ pub struct Method {
 
     pub code: Option<Code>,
     pub line_num_table: Vec<LineNumber>,
+    //quick forms of the instructions, by pc
+    pub quick: Arc<QuickTable>,
+    //counters, and the code compiled when hot
+    pub jit: Arc<MethodState>,
 
     method_info_index: usize,
 }
@@ -85,6 +116,7 @@ impl Method {
         let signature = MethodSignature::new(desc.as_slice());
         let code = mi.get_code();
         let line_num_table = mi.get_line_number_table();
+        let quick = QuickTable::new(code.as_ref().map_or(0, |it| it.code.len()));
 
         Self {
             class,
@@ -96,30 +128,12 @@ impl Method {
             signature,
             code,
             line_num_table,
+            quick,
+            jit: Arc::new(MethodState::default()),
             method_info_index,
         }
     }
 
-    pub fn find_exception_handler(&self, cp: &ConstantPool, pc: U2, ex: ClassRef) -> Option<U2> {
-        if let Some(code) = &self.code {
-            for e in code.exceptions.iter() {
-                if e.contains(pc) {
-                    if e.is_finally() {
-                        return Some(e.handler_pc);
-                    }
-
-                    if let Some(class) = runtime::require_class2(e.catch_type, cp) {
-                        if runtime::cmp::instance_of(ex.clone(), class) {
-                            return Some(e.handler_pc);
-                        }
-                    }
-                }
-            }
-        }
-
-        None
-    }
-
     pub fn get_line_num(&self, pc: U2) -> i32 {
         let mut best_bci = 0;
         let mut best_line = -1;
@@ -156,6 +170,52 @@ impl Method {
         util::attributes::assemble_annotation_default(&method_info.attrs)
     }
 
+    //generic signature, 'Signature' attribute
+    pub fn get_attr_signature(&self) -> Option<BytesRef> {
+        let method_info = self.class_file.methods.get(self.method_info_index).unwrap();
+        match util::attributes::get_signature(&method_info.attrs) {
+            0 => None,
+            idx => Some(constant_pool::get_utf8(&self.class_file.cp, idx as usize).clone()),
+        }
+    }
+
+    //classes declared by 'Exceptions' attribute
+    pub fn get_throws(&self) -> Vec<ClassRef> {
+        let method_info = self.class_file.methods.get(self.method_info_index).unwrap();
+        let cp = &self.class_file.cp;
+        match method_info.get_throws() {
+            Some(exceptions) => exceptions
+                .iter()
+                .filter_map(|idx| require_class2(*idx, cp))
+                .collect(),
+            None => vec![],
+        }
+    }
+
+    //(name, access_flags) of 'MethodParameters' attribute, name is None if not present
+    pub fn get_method_parameters(&self) -> Option<Vec<(Option<BytesRef>, U2)>> {
+        let method_info = self.class_file.methods.get(self.method_info_index).unwrap();
+        let cp = &self.class_file.cp;
+
+        for it in method_info.attrs.iter() {
+            if let AttributeType::MethodParameters { parameters } = it {
+                let params = parameters
+                    .iter()
+                    .map(|it| {
+                        let name = match it.name_index {
+                            0 => None,
+                            idx => Some(constant_pool::get_utf8(cp, idx as usize).clone()),
+                        };
+                        (name, it.acc_flags)
+                    })
+                    .collect();
+                return Some(params);
+            }
+        }
+
+        None
+    }
+
     pub fn check_annotation(&self, name: &[u8]) -> bool {
         let method_info = self.class_file.methods.get(self.method_info_index).unwrap();
 
@@ -3,7 +3,7 @@ use classfile::{BytesRef, ClassFile};
 use std::fmt;
 use std::sync::{Arc, Condvar, Mutex, RwLock};
 
-use crate::runtime::{require_class3, ClassLoader};
+use crate::runtime::{heap, require_class3, ClassLoader};
 use crate::types::*;
 
 pub mod ary;
@@ -200,8 +200,13 @@ impl Oop {
     }
 
     pub fn new_inst(cls_obj: ClassRef) -> Oop {
+        let reference_type = cls_obj.extract_inst().reference_type;
         let v = InstOopDesc::new(cls_obj);
-        Self::new_ref(RefKind::Inst(v))
+        let oop = Self::new_ref(RefKind::Inst(v));
+        if let (Some(ty), Oop::Ref(rf)) = (reference_type, &oop) {
+            heap::register_reference(rf, ty);
+        }
+        oop
     }
 }
 
@@ -400,6 +405,11 @@ impl OopRef {
         let ptr = self.get_raw_ptr();
         unsafe { (*ptr).wait_timeout(duration) }
     }
+
+    pub fn lock_fields(&self) -> std::sync::MutexGuard<'_, ()> {
+        let ptr = self.get_raw_ptr();
+        unsafe { (*ptr).lock_fields() }
+    }
 }
 
 impl OopRef {
@@ -426,6 +436,20 @@ impl OopRef {
         }
     }
 
+    //the comparison used by compareAndSwap: primitives by value,
+    //references by identity (no String value comparison)
+    pub fn is_same(l: &Oop, r: &Oop) -> bool {
+        match (l, r) {
+            (Oop::Int(l), Oop::Int(r)) => l == r,
+            (Oop::Long(l), Oop::Long(r)) => l == r,
+            (Oop::Float(l), Oop::Float(r)) => l.to_bits() == r.to_bits(),
+            (Oop::Double(l), Oop::Double(r)) => l.to_bits() == r.to_bits(),
+            (Oop::Null, Oop::Null) => true,
+            (Oop::Ref(l), Oop::Ref(r)) => l.0 == r.0,
+            _ => false,
+        }
+    }
+
     pub fn is_java_lang_string(rf: Arc<Self>) -> bool {
         let ptr = rf.get_raw_ptr();
         unsafe {
@@ -1,7 +1,9 @@
 use crate::oop::{ArrayOopDesc, InstOopDesc, MirrorOopDesc, TypeArrayDesc};
+use crate::runtime::heap;
 use crate::runtime::thread::{Condvar, ReentrantMutex};
 use std::fmt;
 use std::fmt::Formatter;
+use std::sync::{Mutex, MutexGuard};
 use std::time::Duration;
 
 #[derive(Debug)]
@@ -15,9 +17,15 @@ pub enum RefKind {
 pub struct RefKindDesc {
     pub v: RefKind,
     pub hash_code: Option<i32>,
+    //bytes counted by the heap
+    size: usize,
 
     mutex: ReentrantMutex,
     cond_var: Condvar,
+
+    //guard for volatile & atomic field access,
+    //separated from the monitor, java code can hold the monitor for a long time
+    fields_mutex: Mutex<()>,
 }
 
 impl RefKindDesc {
@@ -34,11 +42,16 @@ impl RefKindDesc {
             cond
         };
 
+        let size = heap::object_size(&v);
+        heap::allocated(size);
+
         Self {
             v,
             hash_code: None,
+            size,
             mutex,
             cond_var,
+            fields_mutex: Mutex::new(()),
         }
     }
 }
@@ -73,6 +86,10 @@ impl RefKindDesc {
             self.cond_var.notify_all();
         }
     }
+
+    pub fn lock_fields(&self) -> MutexGuard<'_, ()> {
+        self.fields_mutex.lock().unwrap()
+    }
 }
 
 impl RefKind {
@@ -126,6 +143,7 @@ impl Drop for RefKindDesc {
         unsafe {
             self.mutex.destroy();
         }
+        heap::freed(self.size);
     }
 }
 
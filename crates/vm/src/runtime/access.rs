@@ -0,0 +1,99 @@
+use crate::oop::class::ClassKind;
+use crate::oop::Oop;
+use crate::runtime::{cmp, exception, ClassLoader};
+use crate::types::ClassRef;
+use classfile::{consts as cls_consts, flags::*, U2};
+use std::sync::Arc;
+
+/*
+Access control, JVMS 5.4.4
+
+Checked when a symbolic reference is resolved, D the class of the
+instruction resolving it. A class is accessible if public, or of the runtime
+package of D; a member if public, private of D itself, of the package of D,
+or protected & D a subclass of the declaring class.
+
+The accessors generated by reflection (subclasses of MagicAccessorImpl) are
+not checked, as HotSpot does.
+*/
+
+const MAGIC_ACCESSOR: &[u8] = b"sun/reflect/MagicAccessorImpl";
+
+pub fn class_accessible(c: &ClassRef, d: &ClassRef) -> bool {
+    let cls = c.get_class();
+    match &cls.kind {
+        ClassKind::Instance(_) => cls.is_public() || same_package(c, d) || is_magic(d),
+        ClassKind::ObjectArray(ary) => match &ary.component {
+            Some(component) => class_accessible(component, d),
+            None => true,
+        },
+        ClassKind::TypeArray(_) => true,
+    }
+}
+
+//a member declared by 'owner' of 'acc_flags'
+pub fn member_accessible(owner: &ClassRef, acc_flags: U2, d: &ClassRef) -> bool {
+    let accessible = if acc_flags & ACC_PUBLIC != 0 {
+        true
+    } else if acc_flags & ACC_PRIVATE != 0 {
+        Arc::ptr_eq(owner, d)
+    } else {
+        same_package(owner, d)
+            || (acc_flags & ACC_PROTECTED != 0 && cmp::check_inherit(d.clone(), owner.clone()))
+    };
+    accessible || is_magic(d)
+}
+
+//IllegalAccessError, 'what' like "field Foo.x"
+pub fn illegal_access(what: String, d: &ClassRef) -> Oop {
+    let msg = format!("tried to access {} from class {}", what, external_name(d));
+    exception::new(cls_consts::J_ILLEGAL_ACCESS_ERROR, Some(msg))
+}
+
+//the name as in Java, 'java.lang.Object'
+pub fn external_name(class: &ClassRef) -> String {
+    let name = class.get_class().name.clone();
+    String::from_utf8_lossy(name.as_slice()).replace("/", ".")
+}
+
+//JVMS 5.3, of one runtime package: the package name & the defining loader
+pub fn same_package(a: &ClassRef, b: &ClassRef) -> bool {
+    let b = b.get_class();
+    in_package(a, b.name.as_slice(), b.class_loader)
+}
+
+//'class' of the runtime package of a class 'name' defined by 'class_loader'
+pub fn in_package(class: &ClassRef, name: &[u8], class_loader: Option<ClassLoader>) -> bool {
+    let cls = class.get_class();
+    cls.class_loader == class_loader && package_of(cls.name.as_slice()) == package_of(name)
+}
+
+fn package_of(name: &[u8]) -> &[u8] {
+    match name.iter().rposition(|&c| c == b'/') {
+        Some(i) => &name[..i],
+        None => &[],
+    }
+}
+
+fn is_magic(d: &ClassRef) -> bool {
+    let mut class = Some(d.clone());
+    while let Some(cls) = class {
+        let cls = cls.get_class();
+        if cls.name.as_slice() == MAGIC_ACCESSOR {
+            return true;
+        }
+        class = cls.super_class.clone();
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::package_of;
+
+    #[test]
+    fn t_package_of() {
+        assert_eq!(package_of(b"java/lang/Object"), b"java/lang");
+        assert_eq!(package_of(b"Main"), b"");
+    }
+}
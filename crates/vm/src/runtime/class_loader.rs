@@ -1,17 +1,27 @@
 use crate::native;
 use crate::oop::class::ClassPtr;
-use crate::oop::{self, Class, ValueType};
-use crate::runtime::{self, ClassPathResult};
+use crate::oop::{self, Class, Oop, ValueType};
+use crate::runtime::{self, access, exception, sys_dic, ClassPathResult};
 use crate::types::*;
 use crate::util;
 use class_parser::parse_class;
-use classfile::{constant_pool, BytesRef, ConstantPool, U2};
+use classfile::{constant_pool, consts, BytesRef, ConstantPool, U2};
 use std::sync::{Arc, Mutex};
 
-#[derive(Debug, Copy, Clone)]
+/*
+Base is the loader of app, searches the class path of app (-cp),
+after its parent, Bootstrap, searching the boot class path.
+
+All loaded classes share one dictionary, keyed by name. A class defined by a
+java.lang.ClassLoader object (Proxy.defineClass0) is in the dictionary of that
+loader, Defined by its id (see sys_dic::loader_id); the other classes it refers
+to are loaded as by Base.
+*/
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub enum ClassLoader {
     Base,
     Bootstrap,
+    Defined(u64),
 }
 
 pub fn require_class(class_loader: Option<ClassLoader>, name: &BytesRef) -> Option<ClassRef> {
@@ -24,50 +34,82 @@ pub fn require_class2(index: U2, cp: &ConstantPool) -> Option<ClassRef> {
     require_class3(None, class.as_slice())
 }
 
+//CONSTANT_Class at 'index' resolved for D by the loader of D, JVMS 5.4.3.1
+pub fn resolve_class(index: U2, cp: &ConstantPool, d: &ClassRef) -> Result<ClassRef, Oop> {
+    let name = constant_pool::get_class_name(cp, index as usize);
+    let class_loader = d.get_class().class_loader;
+    match require_class3(class_loader, name.as_slice()) {
+        Some(class) if access::class_accessible(&class, d) => Ok(class),
+        Some(class) => Err(access::illegal_access(
+            format!("class {}", access::external_name(&class)),
+            d,
+        )),
+        None => {
+            let msg = String::from_utf8_lossy(name.as_slice()).to_string();
+            Err(exception::new(consts::J_NO_CLASS_DEF_FOUND, Some(msg)))
+        }
+    }
+}
+
 pub fn require_class3(class_loader: Option<ClassLoader>, name: &[u8]) -> Option<ClassRef> {
-    let class_loader = class_loader.unwrap_or(ClassLoader::Bootstrap);
+    let class_loader = class_loader.unwrap_or(ClassLoader::Base);
     class_loader.load_class(name)
 }
 
 impl ClassLoader {
+    //the loader of classes defined by a java.lang.ClassLoader object, null as Bootstrap
+    pub fn defined_by(loader: &Oop) -> Self {
+        match loader {
+            Oop::Null => ClassLoader::Bootstrap,
+            _ => ClassLoader::Defined(sys_dic::loader_id(loader)),
+        }
+    }
+
     fn load_class(&self, name: &[u8]) -> Option<ClassRef> {
         debug_assert!(!name.contains(&b'.'));
+        if let ClassLoader::Defined(id) = self {
+            if let Some(class) = sys_dic::find_defined(*id, name) {
+                return Some(class);
+            }
+        }
         //        error!("load_class name = {}", String::from_utf8_lossy(name));
+        let it = runtime::sys_dic_find(name);
+        if it.is_some() {
+            //                    info!("load_class in dic: {}", String::from_utf8_lossy(name));
+            return it;
+        }
+
+        if is_array(name) {
+            return self.load_array_class(name);
+        }
+
+        //parent first
         match self {
-            ClassLoader::Base => (),
-            ClassLoader::Bootstrap => {
-                let it = runtime::sys_dic_find(name);
-                if it.is_some() {
-                    //                    info!("load_class in dic: {}", String::from_utf8_lossy(name));
-                    return it;
+            ClassLoader::Base => {
+                let class = ClassLoader::Bootstrap.load_class(name);
+                if class.is_some() {
+                    return class;
                 }
             }
+            ClassLoader::Defined(_) => return ClassLoader::Base.load_class(name),
+            ClassLoader::Bootstrap => (),
         }
 
-        if is_array(name) {
-            self.load_array_class(name)
-        } else {
-            let class = self.load_class_from_path(name);
-            if let Some(class) = &class {
-                match self {
-                    ClassLoader::Base => (),
-                    ClassLoader::Bootstrap => {
-                        runtime::sys_dic_put(name, class.clone());
-                        let this_ref = class.clone();
-
-                        {
-                            let mut cls = class.get_mut_class();
-                            cls.set_class_state(oop::class::State::Loaded);
-                            cls.link_class(this_ref);
-                        }
+        let class = self.load_class_from_path(name);
+        if let Some(class) = &class {
+            runtime::sys_dic_put(name, class.clone());
+            let this_ref = class.clone();
 
-                        native::java_lang_Class::create_mirror(class.clone());
-                    }
-                }
+            {
+                let mut cls = class.get_mut_class();
+                cls.set_class_state(oop::class::State::Loaded);
+                cls.link_class(this_ref);
             }
 
-            class
+            native::java_lang_Class::create_mirror(class.clone());
         }
+
+        class
     }
 
     fn load_array_class(&self, name: &[u8]) -> Option<ClassRef> {
@@ -87,12 +129,7 @@ impl ClassLoader {
                                     let mut class = class.get_mut_class();
                                     class.link_class(this_ref);
                                 }
-                                match self {
-                                    ClassLoader::Base => (),
-                                    ClassLoader::Bootstrap => {
-                                        runtime::sys_dic_put(name, class.clone());
-                                    }
-                                }
+                                runtime::sys_dic_put(name, class.clone());
 
                                 native::java_lang_Class::create_mirror(class.clone());
 
@@ -114,12 +151,7 @@ impl ClassLoader {
                             class.link_class(this_ref);
                         }
 
-                        match self {
-                            ClassLoader::Base => (),
-                            ClassLoader::Bootstrap => {
-                                runtime::sys_dic_put(name, class.clone());
-                            }
-                        }
+                        runtime::sys_dic_put(name, class.clone());
 
                         //mirror has been created when vm inited
 
@@ -137,12 +169,7 @@ impl ClassLoader {
                     Some(down_type) => {
                         let class = Class::new_wrapped_ary(*self, down_type);
                         let class = ClassPtr::new(class);
-                        match self {
-                            ClassLoader::Base => (),
-                            ClassLoader::Bootstrap => {
-                                runtime::sys_dic_put(name, class.clone());
-                            }
-                        }
+                        runtime::sys_dic_put(name, class.clone());
 
                         native::java_lang_Class::create_mirror(class.clone());
 
@@ -157,9 +184,18 @@ impl ClassLoader {
 
     fn load_class_from_path(&self, name: &[u8]) -> Option<ClassRef> {
         let name = unsafe { std::str::from_utf8_unchecked(name) };
-        match runtime::find_class_in_classpath(name) {
-            Ok(ClassPathResult(_, buf)) => match parse_class(&buf) {
+        let r = match self {
+            ClassLoader::Base => runtime::find_class_in_classpath(name),
+            ClassLoader::Bootstrap => runtime::find_class_in_boot_classpath(name),
+            ClassLoader::Defined(_) => unreachable!(),
+        };
+        match r {
+            Ok(ClassPathResult(source, buf)) => match parse_class(&buf) {
                 Ok(cf) => {
+                    if runtime::options::with(|opts| opts.verbose_class) {
+                        println!("[Loaded {} from {}]", name.replace("/", "."), source);
+                    }
+
                     let cfr = Arc::new(Box::new(cf.1));
                     let class = Class::new_class(cfr, Some(*self));
                     Some(ClassPtr::new(class))
@@ -1,16 +1,48 @@
+use crate::runtime::jar::Jar;
+use crate::runtime::options::{self, VMOptions};
 use crate::util;
+use rustc_hash::{FxHashMap, FxHashSet};
 use std::fs::File;
-use std::io::{self, BufReader, Cursor, Read, Seek};
-use std::path::{self, Path};
+use std::io;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex, RwLock};
 use zip::ZipArchive;
 
+/*
+Two class paths:
+  boot class path, searched by bootstrap loader, jars of jre & -Xbootclasspath
+  class path of app, searched by app loader, -cp
+*/
+
 lazy_static! {
+    static ref BOOT_CPM: RwLock<ClassPathManager> = { RwLock::new(ClassPathManager::new()) };
     static ref CPM: RwLock<ClassPathManager> = { RwLock::new(ClassPathManager::new()) };
 }
 
+//jars of jre/lib, in order of sun.boot.class.path of jdk
+const BOOT_JARS: &[&str] = &[
+    "resources.jar",
+    "rt.jar",
+    "sunrsasign.jar",
+    "jsse.jar",
+    "jce.jar",
+    "charsets.jar",
+    "jfr.jar",
+];
+
 pub fn init() {
     lazy_static::initialize(&CPM);
+
+    let paths = options::with(|opts| {
+        let java_home = find_java_home(std::env::var("JAVA_HOME").ok());
+        create_boot_class_path(java_home.as_deref(), opts)
+    });
+    let mut cpm = BOOT_CPM.write().unwrap();
+    for p in paths.iter() {
+        if let Err(e) = cpm.add_class_path(p) {
+            warn!("add boot class path error, path={}, e={:?}", p, e)
+        }
+    }
 }
 
 pub fn find_class(name: &str) -> Result<ClassPathResult, io::Error> {
@@ -18,6 +50,11 @@ pub fn find_class(name: &str) -> Result<ClassPathResult, io::Error> {
     cpm.search_class(name)
 }
 
+pub fn find_boot_class(name: &str) -> Result<ClassPathResult, io::Error> {
+    let cpm = BOOT_CPM.read().unwrap();
+    cpm.search_class(name)
+}
+
 pub fn add_path(path: &str) {
     let mut cpm = CPM.write().unwrap();
     cpm.add_class_path(path);
@@ -28,26 +65,174 @@ pub fn add_paths(path: &str) {
     cpm.add_class_paths(path);
 }
 
+//resource of boot class path first, then class path of app
+pub fn find_resource(name: &str) -> Option<ClassPathResult> {
+    let r = BOOT_CPM.read().unwrap().search(name);
+    r.or_else(|_| CPM.read().unwrap().search(name)).ok()
+}
+
+//all resources of name, such as "META-INF/services/xx"
+pub fn find_resources(name: &str) -> Vec<ClassPathResult> {
+    let mut v = BOOT_CPM.read().unwrap().search_all(name);
+    v.extend(CPM.read().unwrap().search_all(name));
+    v
+}
+
+//urls of the class path of app, the same as sun.misc.Launcher.pathToURLs
+pub fn class_path_urls() -> Vec<String> {
+    let cpm = CPM.read().unwrap();
+    cpm.runtime_class_path
+        .iter()
+        .map(|it| file_url(&it.1))
+        .collect()
+}
+
+//ascending indices of the class path of app contain resource 'name'
+pub fn lookup_resource(name: &str) -> Vec<usize> {
+    let cpm = CPM.read().unwrap();
+    cpm.locate(name)
+}
+
+//not in boot class path, nor the class path of app, name like "java.lang.Object"
+pub fn known_to_not_exist(class_name: &str) -> bool {
+    let mut name = class_name.replace(".", "/");
+    name.push_str(".class");
+    BOOT_CPM.read().unwrap().locate(&name).is_empty()
+        && CPM.read().unwrap().locate(&name).is_empty()
+}
+
+//sun.boot.class.path
+pub fn boot_class_path() -> String {
+    let cpm = BOOT_CPM.read().unwrap();
+    let paths: Vec<&str> = cpm
+        .runtime_class_path
+        .iter()
+        .map(|it| it.1.as_str())
+        .collect();
+    paths.join(util::PATH_SEP)
+}
+
+/*
+the jre dir, contains "lib/rt.jar"
+
+JAVA_HOME may be a jdk, the jre is "$JAVA_HOME/jre".
+Without JAVA_HOME, try the current dir (libjvm run from jre dir),
+then the dir of the binary ("jre/bin/java").
+*/
+fn find_java_home(env: Option<String>) -> Option<PathBuf> {
+    let is_jre = |p: &Path| p.join("lib").join("rt.jar").is_file();
+
+    if let Some(home) = env.filter(|v| !v.is_empty()) {
+        let home = PathBuf::from(home);
+        let jre = home.join("jre");
+        if is_jre(&jre) {
+            return Some(jre);
+        }
+        return Some(home);
+    }
+
+    let mut candidates = vec![];
+    if let Ok(dir) = std::env::current_dir() {
+        candidates.push(dir);
+    }
+    if let Ok(exe) = std::env::current_exe() {
+        if let Some(home) = exe.parent().and_then(|v| v.parent()) {
+            candidates.push(home.to_path_buf());
+        }
+    }
+
+    candidates.into_iter().find(|p| is_jre(p))
+}
+
+fn create_boot_class_path(java_home: Option<&Path>, opts: &VMOptions) -> Vec<String> {
+    let mut paths = vec![];
+
+    for v in opts.boot_class_path_prepend.iter() {
+        paths.extend(split_paths(v));
+    }
+
+    match &opts.boot_class_path {
+        Some(v) => paths.extend(split_paths(v)),
+        None => {
+            if let Some(home) = java_home {
+                let lib = home.join("lib");
+                for jar in BOOT_JARS.iter() {
+                    let p = lib.join(jar);
+                    if p.is_file() {
+                        paths.push(p.to_string_lossy().to_string());
+                    }
+                }
+
+                let classes = home.join("classes");
+                if classes.is_dir() {
+                    paths.push(classes.to_string_lossy().to_string());
+                }
+
+                //no extension loader, jars of ext dir loaded by bootstrap loader
+                if let Ok(dir) = std::fs::read_dir(lib.join("ext")) {
+                    let mut jars: Vec<String> = dir
+                        .filter_map(|it| it.ok())
+                        .map(|it| it.path())
+                        .filter(|p| {
+                            matches!(
+                                p.extension().and_then(|v| v.to_str()),
+                                Some("jar") | Some("zip")
+                            )
+                        })
+                        .map(|p| p.to_string_lossy().to_string())
+                        .collect();
+                    jars.sort();
+                    paths.extend(jars);
+                }
+            }
+        }
+    }
+
+    for v in opts.boot_class_path_append.iter() {
+        paths.extend(split_paths(v));
+    }
+
+    paths
+}
+
+fn split_paths(v: &str) -> Vec<String> {
+    v.split(util::PATH_SEP)
+        .filter(|v| !v.is_empty())
+        .map(|v| v.to_string())
+        .collect()
+}
+
 #[derive(Debug)]
 pub struct ClassPathResult(pub String, pub Vec<u8>);
 
-type ZipRef = Arc<Mutex<Box<ZipArchive<File>>>>;
-
 enum ClassSource {
     DIR,
-    JAR(ZipRef),
+    JAR(Arc<Jar>),
 }
 
 struct ClassPathEntry(ClassSource, String);
 
+//an entry of jar, 'cp_idx' is the index of jar in class path
+struct JarLoc {
+    cp_idx: usize,
+    entry: usize,
+}
+
 struct ClassPathManager {
     runtime_class_path: Vec<ClassPathEntry>,
+    //package -> (file name -> entries of jars in order of class path), built when jar added
+    packages: FxHashMap<String, FxHashMap<String, Vec<JarLoc>>>,
+    //names not found, cleared when class path changed; only when all entries
+    //are jars, the contents of a jar is fixed once added
+    misses: RwLock<FxHashSet<String>>,
 }
 
 impl ClassPathManager {
     fn new() -> Self {
         Self {
             runtime_class_path: vec![],
+            packages: FxHashMap::default(),
+            misses: RwLock::new(FxHashSet::default()),
         }
     }
 
@@ -57,13 +242,29 @@ impl ClassPathManager {
             self.runtime_class_path
                 .push(ClassPathEntry(ClassSource::DIR, path.to_string()));
         } else {
-            let f = File::open(p)?;
-            let mut z = ZipArchive::new(f)?;
-            let handle = Arc::new(Mutex::new(Box::new(z)));
-            self.runtime_class_path
-                .push(ClassPathEntry(ClassSource::JAR(handle), path.to_string()));
+            let jar = Jar::open(path)?;
+            let cp_idx = self.runtime_class_path.len();
+            for (i, e) in jar.entries().iter().enumerate() {
+                if e.name.ends_with('/') {
+                    continue;
+                }
+                let (pkg, file) = split_name(&e.name);
+                self.packages
+                    .entry(pkg.to_string())
+                    .or_default()
+                    .entry(file.to_string())
+                    .or_default()
+                    .push(JarLoc { cp_idx, entry: i });
+            }
+
+            self.runtime_class_path.push(ClassPathEntry(
+                ClassSource::JAR(Arc::new(jar)),
+                path.to_string(),
+            ));
         }
 
+        self.misses.write().unwrap().clear();
+
         Ok(())
     }
 
@@ -76,44 +277,120 @@ impl ClassPathManager {
     }
 
     pub fn search_class(&self, name: &str) -> Result<ClassPathResult, io::Error> {
-        let name = name.replace("/", util::FILE_SEP);
-        let name = name.replace(".", util::FILE_SEP);
+        let name = name.replace(".", "/");
 
         trace!("search_class: {}", name);
 
-        for it in self.runtime_class_path.iter() {
-            match &it.0 {
-                ClassSource::DIR => {
-                    let mut p = String::from(&it.1);
-                    p.push_str(util::FILE_SEP);
-                    p.push_str(&name);
-                    p.push_str(".class");
-                    if let Ok(data) = std::fs::read(&p) {
-                        return Ok(ClassPathResult(p, data));
-                    }
-                }
+        let mut p = name;
+        p.push_str(".class");
+        self.search(&p)
+    }
 
-                ClassSource::JAR(handle) => {
-                    let mut p = String::from(&name);
-                    p.push_str(".class");
+    //name of jar entry, such as "java/lang/Object.class"
+    fn search(&self, name: &str) -> Result<ClassPathResult, io::Error> {
+        if self.misses.read().unwrap().contains(name) {
+            return Err(not_found(name));
+        }
 
-                    let mut handle = handle.lock().unwrap();
-                    let mut zf = handle.by_name(&p);
+        let (pkg, file) = split_name(name);
+        let jar_loc = self
+            .packages
+            .get(pkg)
+            .and_then(|files| files.get(file))
+            .and_then(|locs| locs.first());
 
-                    if let Ok(mut zf) = zf {
-                        let mut v = Vec::with_capacity(zf.size() as usize);
-                        let r = zf.read_to_end(&mut v);
-                        debug_assert!(r.is_ok());
-                        return Ok(ClassPathResult(it.1.clone(), v));
-                    }
+        //dirs in front of the jar
+        let limit = jar_loc.map_or(self.runtime_class_path.len(), |loc| loc.cp_idx);
+        for it in self.runtime_class_path[..limit].iter() {
+            if let ClassSource::DIR = &it.0 {
+                let mut p = String::from(&it.1);
+                p.push_str(util::FILE_SEP);
+                p.push_str(&name.replace("/", util::FILE_SEP));
+                if let Ok(data) = std::fs::read(&p) {
+                    return Ok(ClassPathResult(p, data));
                 }
             }
         }
 
-        Err(io::Error::new(
-            io::ErrorKind::NotFound,
-            format!("Search class failed: {}", name),
-        ))
+        if let Some(loc) = jar_loc {
+            let it = &self.runtime_class_path[loc.cp_idx];
+            if let ClassSource::JAR(jar) = &it.0 {
+                let v = jar.read(&jar.entries()[loc.entry])?;
+                return Ok(ClassPathResult(it.1.clone(), v));
+            }
+        }
+
+        //a dir may get the file later
+        if self.only_jars() {
+            self.misses.write().unwrap().insert(name.to_string());
+        }
+        Err(not_found(name))
+    }
+
+    fn only_jars(&self) -> bool {
+        self.runtime_class_path
+            .iter()
+            .all(|it| matches!(it.0, ClassSource::JAR(_)))
+    }
+
+    fn search_all(&self, name: &str) -> Vec<ClassPathResult> {
+        self.locate(name)
+            .into_iter()
+            .filter_map(|i| self.read_at(i, name).ok())
+            .collect()
+    }
+
+    //ascending indices of entries contain 'name', without reading
+    fn locate(&self, name: &str) -> Vec<usize> {
+        if self.misses.read().unwrap().contains(name) {
+            return vec![];
+        }
+
+        let (pkg, file) = split_name(name);
+        let mut jars = self
+            .packages
+            .get(pkg)
+            .and_then(|files| files.get(file))
+            .map(|locs| locs.iter().map(|loc| loc.cp_idx).collect::<Vec<usize>>())
+            .unwrap_or_default();
+        jars.dedup();
+
+        let mut found: Vec<usize> = self
+            .runtime_class_path
+            .iter()
+            .enumerate()
+            .filter(|(_, it)| match &it.0 {
+                ClassSource::DIR => Path::new(&it.1).join(name).is_file(),
+                ClassSource::JAR(_) => false,
+            })
+            .map(|(i, _)| i)
+            .collect();
+        found.extend(jars);
+        found.sort_unstable();
+
+        found
+    }
+
+    fn read_at(&self, idx: usize, name: &str) -> Result<ClassPathResult, io::Error> {
+        let it = &self.runtime_class_path[idx];
+        match &it.0 {
+            ClassSource::DIR => {
+                let p = Path::new(&it.1).join(name);
+                let data = std::fs::read(&p)?;
+                Ok(ClassPathResult(p.to_string_lossy().to_string(), data))
+            }
+            ClassSource::JAR(jar) => {
+                let (pkg, file) = split_name(name);
+                let loc = self
+                    .packages
+                    .get(pkg)
+                    .and_then(|files| files.get(file))
+                    .and_then(|locs| locs.iter().find(|loc| loc.cp_idx == idx))
+                    .ok_or_else(|| not_found(name))?;
+                let v = jar.read(&jar.entries()[loc.entry])?;
+                Ok(ClassPathResult(it.1.clone(), v))
+            }
+        }
     }
 
     pub fn size(&self) -> usize {
@@ -121,6 +398,48 @@ impl ClassPathManager {
     }
 }
 
+//"file:/abs/path/" for dir, like ParseUtil.fileToEncodedURL
+fn file_url(path: &str) -> String {
+    let p = std::fs::canonicalize(path).unwrap_or_else(|_| {
+        std::env::current_dir()
+            .map(|dir| dir.join(path))
+            .unwrap_or_else(|_| PathBuf::from(path))
+    });
+
+    let mut url = String::from("file:");
+    for b in p.to_string_lossy().bytes() {
+        match b {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' => url.push(b as char),
+            b'/' | b'-' | b'_' | b'.' | b'!' | b'~' | b'*' | b'\'' | b'(' | b')' => {
+                url.push(b as char)
+            }
+            b'@' | b':' | b'&' | b'=' | b'+' | b'$' | b',' | b';' => url.push(b as char),
+            _ => url.push_str(&format!("%{:02X}", b)),
+        }
+    }
+
+    if p.is_dir() && !url.ends_with('/') {
+        url.push('/');
+    }
+
+    url
+}
+
+//("java/lang", "Object.class")
+fn split_name(name: &str) -> (&str, &str) {
+    match name.rfind('/') {
+        Some(idx) => (&name[..idx], &name[idx + 1..]),
+        None => ("", name),
+    }
+}
+
+fn not_found(name: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::NotFound,
+        format!("Search class failed: {}", name),
+    )
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -169,6 +488,160 @@ mod tests {
         assert!(cpm.search_class("Foo").is_ok());
     }
 
+    #[test]
+    fn t_boot_class_path() {
+        use super::*;
+
+        let home = std::env::temp_dir().join(format!("jvm_boot_cp_{}", std::process::id()));
+        let jre = home.join("jre");
+        let lib = jre.join("lib");
+        std::fs::create_dir_all(lib.join("ext")).unwrap();
+        for f in &[
+            "rt.jar",
+            "jsse.jar",
+            "charsets.jar",
+            "ext/b.jar",
+            "ext/a.jar",
+            "ext/x.txt",
+        ] {
+            std::fs::write(lib.join(f), b"").unwrap();
+        }
+
+        //jdk layout
+        let java_home = find_java_home(Some(home.to_string_lossy().to_string())).unwrap();
+        assert_eq!(java_home, jre);
+
+        let s = |p: PathBuf| p.to_string_lossy().to_string();
+        let mut opts = VMOptions::default();
+        assert_eq!(
+            create_boot_class_path(Some(&java_home), &opts),
+            vec![
+                s(lib.join("rt.jar")),
+                s(lib.join("jsse.jar")),
+                s(lib.join("charsets.jar")),
+                s(lib.join("ext/a.jar")),
+                s(lib.join("ext/b.jar")),
+            ]
+        );
+
+        opts.parse_option("-Xbootclasspath/p:p1:p2").unwrap();
+        opts.parse_option("-Xbootclasspath/a:a1").unwrap();
+        let paths = create_boot_class_path(Some(&java_home), &opts);
+        assert_eq!(&paths[..2], &["p1".to_string(), "p2".to_string()]);
+        assert_eq!(paths[2], s(lib.join("rt.jar")));
+        assert_eq!(paths.last().unwrap(), "a1");
+
+        opts.parse_option("-Xbootclasspath:x.jar").unwrap();
+        assert_eq!(
+            create_boot_class_path(Some(&java_home), &opts),
+            vec!["p1", "p2", "x.jar", "a1"]
+        );
+
+        let _ = std::fs::remove_dir_all(&home);
+    }
+
+    #[test]
+    fn t_search_order() {
+        use super::*;
+        use std::io::Write;
+
+        let root = std::env::temp_dir().join(format!("jvm_cp_order_{}", std::process::id()));
+        let dir = root.join("classes");
+        std::fs::create_dir_all(dir.join("p")).unwrap();
+        std::fs::write(dir.join("p").join("Dir.class"), b"dir").unwrap();
+
+        let jar = root.join("a.jar");
+        {
+            let f = File::create(&jar).unwrap();
+            let mut z = zip::ZipWriter::new(f);
+            let opts = zip::write::FileOptions::default();
+            z.start_file("p/Dir.class", opts).unwrap();
+            z.write_all(b"jar").unwrap();
+            z.start_file("p/Jar.class", opts).unwrap();
+            z.write_all(b"jar").unwrap();
+            z.finish().unwrap();
+        }
+        let jar = jar.to_str().unwrap();
+        let dir = dir.to_str().unwrap();
+
+        //jar first
+        let mut cpm = ClassPathManager::new();
+        cpm.add_class_paths(&[jar, dir].join(util::PATH_SEP));
+        assert_eq!(cpm.search_class("p/Dir").unwrap().1, b"jar");
+        assert_eq!(cpm.search_class("p.Jar").unwrap().0, jar);
+
+        //dir first
+        let mut cpm = ClassPathManager::new();
+        cpm.add_class_paths(&[dir, jar].join(util::PATH_SEP));
+        assert_eq!(cpm.search_class("p/Dir").unwrap().1, b"dir");
+        assert_eq!(cpm.search_class("p/Jar").unwrap().1, b"jar");
+
+        //no negative cache with a dir, a class written later found
+        assert!(cpm.search_class("p/New").is_err());
+        assert!(cpm.misses.read().unwrap().is_empty());
+        std::fs::write(Path::new(dir).join("p").join("New.class"), b"new").unwrap();
+        assert_eq!(cpm.search_class("p/New").unwrap().1, b"new");
+
+        //negative cache of jars only, cleared by new class path
+        let mut cpm = ClassPathManager::new();
+        cpm.add_class_path(jar).unwrap();
+        assert!(cpm.search_class("p/New").is_err());
+        assert!(cpm.misses.read().unwrap().contains("p/New.class"));
+        cpm.add_class_path(dir).unwrap();
+        assert_eq!(cpm.search_class("p/New").unwrap().1, b"new");
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn t_resources() {
+        use super::*;
+        use std::io::Write;
+
+        let root = std::env::temp_dir().join(format!("jvm_cp_res_{}", std::process::id()));
+        let dir = root.join("res dir");
+        std::fs::create_dir_all(dir.join("META-INF/services")).unwrap();
+        std::fs::write(dir.join("META-INF/services/my.Spi"), b"my.Impl1").unwrap();
+        std::fs::write(dir.join("app.properties"), b"k=v").unwrap();
+
+        let jar = root.join("b.jar");
+        {
+            let f = File::create(&jar).unwrap();
+            let mut z = zip::ZipWriter::new(f);
+            let opts = zip::write::FileOptions::default();
+            z.start_file("META-INF/MANIFEST.MF", opts).unwrap();
+            z.write_all(b"Manifest-Version: 1.0\r\n").unwrap();
+            z.start_file("META-INF/services/my.Spi", opts).unwrap();
+            z.write_all(b"my.Impl2").unwrap();
+            z.finish().unwrap();
+        }
+
+        let mut cpm = ClassPathManager::new();
+        cpm.add_class_path(jar.to_str().unwrap()).unwrap();
+        cpm.add_class_path(dir.to_str().unwrap()).unwrap();
+
+        assert_eq!(cpm.locate("META-INF/services/my.Spi"), vec![0, 1]);
+        assert_eq!(cpm.locate("app.properties"), vec![1]);
+        assert_eq!(cpm.locate("META-INF/MANIFEST.MF"), vec![0]);
+        assert!(cpm.locate("no/such").is_empty());
+
+        let all: Vec<Vec<u8>> = cpm
+            .search_all("META-INF/services/my.Spi")
+            .into_iter()
+            .map(|r| r.1)
+            .collect();
+        assert_eq!(all, vec![b"my.Impl2".to_vec(), b"my.Impl1".to_vec()]);
+        assert_eq!(cpm.search("app.properties").unwrap().1, b"k=v");
+
+        let url = file_url(dir.to_str().unwrap());
+        assert!(url.starts_with("file:/"));
+        assert!(url.ends_with("/res%20dir/"));
+        let url = file_url(jar.to_str().unwrap());
+        assert!(url.ends_with("/b.jar"));
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
     #[test]
     fn t_search_cls2() {
         let mut cpm = super::ClassPathManager::new();
@@ -1,6 +1,6 @@
 use crate::oop;
-use crate::oop::field;
-use crate::types::{FieldIdRef, MethodIdRef};
+use crate::oop::{field, Oop};
+use crate::types::{ClassRef, FieldIdRef, MethodIdRef};
 use classfile::ConstantPool;
 use rustc_hash::FxHashMap;
 use std::cell::RefCell;
@@ -39,16 +39,22 @@ impl ConstantPoolCache {
         }
     }
 
-    pub fn get_field(&self, idx: usize, is_static: bool) -> FieldIdRef {
+    //resolved for D; the errors are not cached, met again by the next resolution
+    pub fn get_field(&self, idx: usize, is_static: bool, d: &ClassRef) -> Result<FieldIdRef, Oop> {
         let cache = self.cache.borrow();
         let it = cache.get(&idx);
         match it {
-            Some(it) => it.extract_field(),
+            Some(it) => {
+                let fid = it.extract_field();
+                field::check_static(&fid, is_static)?;
+                Ok(fid)
+            }
             None => {
                 drop(cache);
-                let fid = field::get_field_ref(&self.cp, idx, is_static);
+                let fid = field::get_field_ref(&self.cp, idx, d)?;
                 self.cache_field(idx, fid.clone());
-                fid
+                field::check_static(&fid, is_static)?;
+                Ok(fid)
             }
         }
     }
@@ -59,16 +65,16 @@ impl ConstantPoolCache {
         cache.insert(k, v);
     }
 
-    pub fn get_method(&self, idx: usize) -> MethodIdRef {
+    pub fn get_method(&self, idx: usize, d: &ClassRef) -> Result<MethodIdRef, Oop> {
         let cache = self.cache.borrow();
         let it = cache.get(&idx);
         match it {
-            Some(it) => it.extract_method(),
+            Some(it) => Ok(it.extract_method()),
             None => {
                 drop(cache);
-                let m = oop::method::get_method_ref(&self.cp, idx).unwrap();
+                let m = oop::method::get_method_ref(&self.cp, idx, d)?;
                 self.cache_method(idx, m.clone());
-                m
+                Ok(m)
             }
         }
     }
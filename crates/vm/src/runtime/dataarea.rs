@@ -1,8 +1,8 @@
 use crate::oop::Oop;
 use crate::runtime::local::Local;
+use crate::runtime::slot::{self, Slot, Window};
 use crate::runtime::stack::Stack;
 use std::cell::RefCell;
-use std::sync::{Arc, RwLock};
 
 /*
 The origin of DataArea
@@ -15,29 +15,59 @@ If an exception occurs, jvm_fillInStackTrace traverses the current thread frames
 extract the class name, method name, and pc (pc for LineNumberTable Attributes from each frame)
 Locate the error line of code) and construct an exception stack.
 
-The DataArea in the Frame is wrapped with RefCell, so that java_call::invoke_java can execute Java
-Method, you can use the read-only frame to execute bytecode; when there is an exception, you can also let
-jvm_fillInStackTrace traverse the frames to get the necessary information.
-The nature of RefCell makes this possible.
-In a read-only Frame context, to modify the DataArea, borrow_mut is fine.
+The locals & the operand stack are on one window of slots, taken from the
+slot stack of the thread (see runtime/slot.rs), locals first. Both work on
+&self, so java_call::invoke_java can execute the bytecode with a read-only
+Frame, and jvm_fillInStackTrace can traverse the frames meanwhile, without a
+borrow for every instruction.
 */
 pub struct DataArea {
-    pub local: RefCell<Local>,
-    pub stack: RefCell<Stack>,
+    pub local: Local,
+    pub stack: Stack,
     pub return_v: RefCell<Option<Oop>>,
+    slots: Window,
+    locals_len: usize,
+    len: usize,
 }
 
+//the slots only accessed by the thread executing the frame
+unsafe impl Send for DataArea {}
 unsafe impl Sync for DataArea {}
 
 impl DataArea {
     pub fn new(max_locals: usize, max_stack: usize) -> Self {
-        let local = RefCell::new(Local::new(max_locals));
-        let stack = RefCell::new(Stack::new(max_stack));
+        let locals_len = max_locals + 1;
+        let len = locals_len + max_stack;
+        let slots = Window::new(len);
+
+        let (ptr, refs) = slots.ptrs(0);
+        let local = Local::new(ptr, refs, locals_len);
+        let (ptr, refs) = slots.ptrs(locals_len);
+        let stack = Stack::new(ptr, refs, max_stack);
 
         Self {
             local,
             stack,
             return_v: RefCell::new(None),
+            slots,
+            locals_len,
+            len,
         }
     }
+
+    //(locals, reference map of locals, operand stack), for the compiled code
+    pub fn jit_ptrs(&self) -> (*mut Slot, *mut bool, *mut Slot) {
+        let (locals, local_refs) = self.slots.ptrs(0);
+        let (stack, _) = self.slots.ptrs(self.locals_len);
+        (locals, local_refs, stack)
+    }
+
+    //the references in the locals & the operand stack, the roots of the frame
+    pub fn refs(&self) -> Vec<Oop> {
+        let (ptr, refs) = self.slots.ptrs(0);
+        (0..self.len)
+            .filter(|&i| unsafe { *refs.add(i) })
+            .map(|i| unsafe { slot::clone_ref(*ptr.add(i)) })
+            .collect()
+    }
 }
@@ -2,7 +2,7 @@ use crate::oop::{self, Oop};
 use crate::runtime::{self, require_class3};
 use crate::types::JavaThreadRef;
 use crate::{new_br, util};
-use std::sync::atomic::Ordering;
+use classfile::consts;
 
 pub fn new(name: &[u8], msg: Option<String>) -> Oop {
     let cls = match require_class3(None, name) {
@@ -10,8 +10,9 @@ pub fn new(name: &[u8], msg: Option<String>) -> Oop {
         None => panic!("ClassNotFound: {}", String::from_utf8_lossy(name)),
     };
 
-    oop::class::init_class(&cls);
-    oop::class::init_class_fully(&cls);
+    if let Err(ex) = oop::class::init_class(&cls) {
+        return ex;
+    }
 
     let ex = Oop::new_inst(cls.clone());
 
@@ -33,15 +34,32 @@ pub fn new(name: &[u8], msg: Option<String>) -> Oop {
     ex
 }
 
-pub fn meet_ex(cls_name: &'static [u8], msg: Option<String>) {
-    let jt = runtime::thread::current_java_thread();
-    {
-        let jt = jt.read().unwrap();
-        let frame = jt.frames.last().unwrap();
-        let frame = frame.try_read().unwrap();
-        frame.ex_here.store(true, Ordering::Relaxed);
+//by the (Throwable) ctor, as ExceptionInInitializerError
+pub fn new_with_cause(name: &[u8], cause: Oop) -> Oop {
+    let cls = match require_class3(None, name) {
+        Some(cls) => cls,
+        None => panic!("ClassNotFound: {}", String::from_utf8_lossy(name)),
+    };
+
+    if let Err(ex) = oop::class::init_class(&cls) {
+        return ex;
     }
 
+    let ex = Oop::new_inst(cls.clone());
+    let args = vec![ex.clone(), cause];
+    runtime::invoke::invoke_ctor(cls, new_br("(Ljava/lang/Throwable;)V"), args);
+
+    ex
+}
+
+pub fn meet_ex(cls_name: &'static [u8], msg: Option<String>) {
+    let jt = runtime::thread::current_java_thread();
     let ex = new(cls_name, msg);
     jt.write().unwrap().set_ex(ex);
 }
+
+//ArrayIndexOutOfBoundsException, as HotSpot "Index 5 out of bounds for length 3"
+pub fn meet_index_out_of_bounds(index: i32, len: usize) {
+    let msg = format!("Index {} out of bounds for length {}", index, len);
+    meet_ex(consts::J_ARRAY_INDEX_OUT_OF_BOUNDS, Some(msg));
+}
@@ -0,0 +1,596 @@
+use crate::new_br;
+use crate::oop::class::State;
+use crate::oop::{self, Class, Oop, OopRef, RefKind, RefKindDesc, TypeArrayDesc};
+use crate::runtime::{self, options, require_class3, JavaCall};
+use crate::types::{ClassRef, FieldIdRef};
+use std::collections::HashMap;
+use std::mem::size_of;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+/*
+The heap
+
+Objects are reference counted, an Oop::Ref is an Arc, and freed when the last
+reference dropped. The bytes of the objects alive are counted here, the heap
+used, against -Xmx (1/4 of the physical memory by default).
+
+java.lang.ref: the References are registered when allocated. A collection,
+by System.gc() or each 1/3 of -Xms allocated (at the allocating instructions),
+discovers the References whose referent is held by References only, by the
+strength of them:
+  Soft     cleared if not got for (free heap MB * -XX:SoftRefLRUPolicyMSPerMB) ms,
+           all when the heap is full
+  Weak     cleared
+  Final    the finalizee of Finalizer.register, kept for finalize(), which the
+           Finalizer thread runs & clears then
+  Phantom  once no Final left, not cleared as JDK 8
+The References discovered are linked by 'discovered' to Reference.pending, and
+Reference.lock notified, for the ReferenceHandler thread to enqueue them.
+
+A referent is found unreachable when held by its References only, its count
+then checked again with the fields of the References locked, before cleared:
+Reference.get, run as a native (see HACKED_NATIVES of oop::class), reads the
+referent under the same lock, so a referent got meanwhile is never cleared.
+
+Cyclic garbage is never reclaimed: the objects of a cycle hold the counts of
+each other, and of the referents reachable from them, never discovered. So the
+keys of a WeakHashMap whose values refer back to them are never cleared (as in
+HotSpot while the map is reachable), nor freed once the map is dropped, the
+entries leaked with them.
+*/
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ReferenceType {
+    Soft,
+    Weak,
+    Final,
+    Phantom,
+}
+
+impl ReferenceType {
+    //the type of class 'name', a subclass of one of the four, as its super class
+    pub fn of(name: &[u8], super_class: Option<&ClassRef>) -> Option<Self> {
+        match name {
+            b"java/lang/ref/SoftReference" => Some(ReferenceType::Soft),
+            b"java/lang/ref/WeakReference" => Some(ReferenceType::Weak),
+            b"java/lang/ref/FinalReference" => Some(ReferenceType::Final),
+            b"java/lang/ref/PhantomReference" => Some(ReferenceType::Phantom),
+            _ => super_class.and_then(|cls| cls.extract_inst().reference_type),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub enum GcCause {
+    SystemGc,
+    AllocationFailure,
+}
+
+static USED: AtomicUsize = AtomicUsize::new(0);
+//since the last collection
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static MAX_HEAP: AtomicUsize = AtomicUsize::new(0);
+static INITIAL_HEAP: AtomicUsize = AtomicUsize::new(0);
+//0 before init, no collection
+static GC_INTERVAL: AtomicUsize = AtomicUsize::new(0);
+static NEED_GC: AtomicBool = AtomicBool::new(false);
+
+lazy_static! {
+    static ref REFERENT: usize = {
+        let cls = require_class3(None, b"java/lang/ref/Reference").unwrap();
+        let cls = cls.get_class();
+        cls.get_field_id(&new_br("referent"), &new_br("Ljava/lang/Object;"), false)
+            .offset
+    };
+}
+
+//a Reference registered, not discovered yet
+type Registered = (Weak<OopRef>, ReferenceType);
+
+static REFERENCES: Mutex<Vec<Registered>> = Mutex::new(Vec::new());
+
+pub fn init() {
+    let (initial, max) = options::with(|opts| (opts.initial_heap_size, opts.max_heap_size));
+    let phys = unsafe { libc::sysconf(libc::_SC_PHYS_PAGES) * libc::sysconf(libc::_SC_PAGESIZE) };
+    let phys = phys.max(0) as usize;
+    let max = max.unwrap_or(phys / 4);
+    let initial = initial.unwrap_or(phys / 64).min(max);
+
+    MAX_HEAP.store(max, Ordering::Relaxed);
+    INITIAL_HEAP.store(initial, Ordering::Relaxed);
+    GC_INTERVAL.store((initial / 3).max(1), Ordering::Relaxed);
+}
+
+pub fn used() -> usize {
+    USED.load(Ordering::Relaxed)
+}
+
+pub fn max_memory() -> usize {
+    MAX_HEAP.load(Ordering::Relaxed)
+}
+
+//the heap committed, -Xms grown to the heap used
+pub fn total_memory() -> usize {
+    INITIAL_HEAP.load(Ordering::Relaxed).max(used())
+}
+
+//the bytes of an object, its header & its fields or elements
+pub fn object_size(v: &RefKind) -> usize {
+    let n = match v {
+        RefKind::Inst(inst) => inst.field_values.len() * size_of::<Oop>(),
+        RefKind::Array(ary) => ary.elements.len() * size_of::<Oop>(),
+        RefKind::Mirror(mirror) => mirror.field_values.len() * size_of::<Oop>(),
+        RefKind::TypeArray(ary) => {
+            let elm = match ary {
+                TypeArrayDesc::Byte(_) | TypeArrayDesc::Bool(_) => 1,
+                TypeArrayDesc::Char(_) | TypeArrayDesc::Short(_) => 2,
+                TypeArrayDesc::Int(_) | TypeArrayDesc::Float(_) => 4,
+                TypeArrayDesc::Long(_) | TypeArrayDesc::Double(_) => 8,
+            };
+            ary.len() * elm
+        }
+    };
+    size_of::<RefKindDesc>() + n
+}
+
+pub fn allocated(size: usize) {
+    USED.fetch_add(size, Ordering::Relaxed);
+    let n = ALLOCATED.fetch_add(size, Ordering::Relaxed) + size;
+    let interval = GC_INTERVAL.load(Ordering::Relaxed);
+    if interval != 0 && n >= interval {
+        NEED_GC.store(true, Ordering::Relaxed);
+    }
+}
+
+pub fn freed(size: usize) {
+    USED.fetch_sub(size, Ordering::Relaxed);
+}
+
+//by the allocating instructions, the collection the allocations requested
+#[inline]
+pub fn safepoint() {
+    if NEED_GC.load(Ordering::Relaxed) {
+        collect(GcCause::AllocationFailure);
+    }
+}
+
+//a Reference allocated, see Oop::new_inst
+pub fn register_reference(rf: &Arc<OopRef>, ty: ReferenceType) {
+    let mut references = REFERENCES.lock().unwrap();
+    references.push((Arc::downgrade(rf), ty));
+}
+
+//an instance of a class with finalize() allocated, Finalizer.register once
+//the Finalizer thread started (by init_vm)
+pub fn register_finalizer(obj: &Oop) {
+    let cls = match runtime::sys_dic_find(b"java/lang/ref/Finalizer") {
+        Some(cls) if cls.get_class().get_class_state() == State::FullyIni => cls,
+        _ => return,
+    };
+    let mir = {
+        let cls = cls.get_class();
+        cls.get_static_method(&new_br("register"), &new_br("(Ljava/lang/Object;)V"))
+    };
+    if let Ok(mir) = mir {
+        let mut jc = JavaCall::new_with_args(mir, vec![obj.clone()]);
+        jc.invoke(None, true);
+    }
+}
+
+//Reference.get, under the lock the referent is cleared with
+pub fn get_referent(reference: &Oop) -> Oop {
+    let rf = reference.extract_ref();
+    let _fields = rf.lock_fields();
+    Class::get_field_value2(rf.clone(), *REFERENT)
+}
+
+//Runtime.runFinalization, the finalizers pending run by a secondary thread
+pub fn run_finalization() -> Result<(), Oop> {
+    call_finalizer("runFinalization")
+}
+
+//Shutdown.runAllFinalizers, for runFinalizersOnExit
+pub fn run_all_finalizers() -> Result<(), Oop> {
+    call_finalizer("runAllFinalizers")
+}
+
+fn call_finalizer(name: &str) -> Result<(), Oop> {
+    let mir = {
+        let cls = oop::class::load_and_init(b"java/lang/ref/Finalizer")?;
+        let cls = cls.get_class();
+        cls.get_static_method(&new_br(name), &new_br("()V"))
+    };
+    if let Ok(mir) = mir {
+        let mut jc = JavaCall::new_with_args(mir, vec![]);
+        jc.invoke(None, true);
+    }
+    Ok(())
+}
+
+pub fn collect(cause: GcCause) {
+    //one collection at a time, the others requested meanwhile are skipped
+    static COLLECTING: Mutex<()> = Mutex::new(());
+    let _collecting = match COLLECTING.try_lock() {
+        Ok(v) => v,
+        Err(_) => return,
+    };
+
+    NEED_GC.store(false, Ordering::Relaxed);
+    ALLOCATED.store(0, Ordering::Relaxed);
+    let start = Instant::now();
+    let before = used();
+
+    let discovered = process_references();
+    enqueue_pending(discovered);
+
+    if options::with(|opts| opts.verbose_gc) {
+        let cause = match cause {
+            GcCause::SystemGc => "System.gc()",
+            GcCause::AllocationFailure => "Allocation Failure",
+        };
+        println!(
+            "[GC ({})  {}K->{}K({}K), {:.7} secs]",
+            cause,
+            before / 1024,
+            used() / 1024,
+            total_memory() / 1024,
+            start.elapsed().as_secs_f64()
+        );
+    }
+}
+
+//fields of java.lang.ref.Reference & SoftReference
+struct Fields {
+    referent: FieldIdRef,
+    next: FieldIdRef,
+    soft_timestamp: Option<FieldIdRef>,
+}
+
+//the soft references cleared, LRUMaxHeapPolicy of HotSpot
+struct SoftPolicy {
+    clock: i64,
+    max_interval: i64,
+    clear_all: bool,
+}
+
+fn process_references() -> Vec<Arc<OopRef>> {
+    let mut references = REFERENCES.lock().unwrap();
+    //Reference.lock set by <clinit>
+    let cls = match runtime::sys_dic_find(b"java/lang/ref/Reference") {
+        Some(cls) if !references.is_empty() => cls,
+        _ => return vec![],
+    };
+    if cls.get_class().get_class_state() != State::FullyIni {
+        return vec![];
+    }
+
+    let fields = {
+        let cls = cls.get_class();
+        let desc = new_br("Ljava/lang/ref/Reference;");
+        Fields {
+            referent: cls.get_field_id(&new_br("referent"), &new_br("Ljava/lang/Object;"), false),
+            next: cls.get_field_id(&new_br("next"), &desc, false),
+            soft_timestamp: runtime::sys_dic_find(b"java/lang/ref/SoftReference").map(|cls| {
+                let cls = cls.get_class();
+                cls.get_field_id(&new_br("timestamp"), &new_br("J"), false)
+            }),
+        }
+    };
+    let soft_clock = soft_clock();
+    let policy = {
+        let ms_per_mb = options::with(|opts| opts.xx_usize("SoftRefLRUPolicyMSPerMB", 1000));
+        let max = max_memory();
+        let free_mb = max.saturating_sub(used()) / (1024 * 1024);
+        SoftPolicy {
+            clock: soft_clock.as_ref().map_or(0, |(cls, fid)| {
+                cls.get_class()
+                    .get_static_field_value(fid.clone())
+                    .extract_long()
+            }),
+            max_interval: (free_mb * ms_per_mb) as i64,
+            clear_all: used() >= max,
+        }
+    };
+
+    //the referents freed may free others, until none cleared
+    let mut discovered = vec![];
+    loop {
+        let (cleared, kept) = discover(&references, &fields, &policy, &mut discovered);
+        *references = kept;
+        if !cleared {
+            break;
+        }
+    }
+
+    //SoftReference.clock, the time of the last collection
+    if let Some((cls, fid)) = soft_clock {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |v| v.as_millis() as i64);
+        cls.get_mut_class()
+            .put_static_field_value(fid, Oop::new_long(now));
+    }
+
+    discovered
+}
+
+fn soft_clock() -> Option<(ClassRef, FieldIdRef)> {
+    let cls = runtime::sys_dic_find(b"java/lang/ref/SoftReference")?;
+    let fid = cls
+        .get_class()
+        .get_field_id(&new_br("clock"), &new_br("J"), true);
+    Some((cls, fid))
+}
+
+//by referent, the referent & the References to it
+type Referents = HashMap<*const OopRef, (Arc<OopRef>, Vec<(Arc<OopRef>, ReferenceType)>)>;
+
+//(any referent cleared, the References left registered)
+fn discover(
+    references: &[Registered],
+    fields: &Fields,
+    policy: &SoftPolicy,
+    discovered: &mut Vec<Arc<OopRef>>,
+) -> (bool, Vec<Registered>) {
+    //by referent, the References active, not enqueued (next null) nor cleared
+    let mut referents: Referents = HashMap::new();
+    for (it, ty) in references.iter() {
+        let rf = match it.upgrade() {
+            Some(rf) => rf,
+            None => continue,
+        };
+        if !Class::get_field_value(rf.clone(), fields.next.clone()).is_null() {
+            continue;
+        }
+        if let Oop::Ref(referent) = Class::get_field_value(rf.clone(), fields.referent.clone()) {
+            referents
+                .entry(Arc::as_ptr(&referent))
+                .or_insert_with(|| (referent, vec![]))
+                .1
+                .push((rf, *ty));
+        }
+    }
+
+    let mut cleared = false;
+    let mut kept = vec![];
+    for (_, (referent, refs)) in referents {
+        //held by the References & here only
+        let reachable = Arc::strong_count(&referent) != refs.len() + 1;
+        let soft_alive = !policy.clear_all
+            && refs
+                .iter()
+                .any(|(rf, ty)| *ty == ReferenceType::Soft && !soft_expired(rf, fields, policy));
+        if reachable || soft_alive {
+            kept.extend(refs.iter().map(|(rf, ty)| (Arc::downgrade(rf), *ty)));
+            continue;
+        }
+
+        //checked again with the References locked, as got by Reference.get meanwhile
+        let locked: Vec<_> = refs.iter().map(|(rf, _)| rf.lock_fields()).collect();
+        if Arc::strong_count(&referent) != refs.len() + 1 {
+            drop(locked);
+            kept.extend(refs.iter().map(|(rf, ty)| (Arc::downgrade(rf), *ty)));
+            continue;
+        }
+        for (rf, ty) in refs.iter() {
+            if let ReferenceType::Soft | ReferenceType::Weak = ty {
+                Class::put_field_value(rf.clone(), fields.referent.clone(), Oop::Null);
+                cleared = true;
+            }
+        }
+        drop(locked);
+
+        let has_final = refs.iter().any(|(_, ty)| *ty == ReferenceType::Final);
+        for (rf, ty) in refs {
+            let found = match ty {
+                ReferenceType::Soft | ReferenceType::Weak | ReferenceType::Final => true,
+                ReferenceType::Phantom => !has_final,
+            };
+            if found {
+                discovered.push(rf);
+            } else {
+                kept.push((Arc::downgrade(&rf), ty));
+            }
+        }
+    }
+
+    (cleared, kept)
+}
+
+fn soft_expired(rf: &Arc<OopRef>, fields: &Fields, policy: &SoftPolicy) -> bool {
+    match &fields.soft_timestamp {
+        Some(fid) => {
+            let timestamp = Class::get_field_value(rf.clone(), fid.clone()).extract_long();
+            policy.clock - timestamp > policy.max_interval
+        }
+        None => true,
+    }
+}
+
+//the pending list handed to the ReferenceHandler thread
+fn enqueue_pending(discovered: Vec<Arc<OopRef>>) {
+    if discovered.is_empty() {
+        return;
+    }
+
+    let cls = require_class3(None, b"java/lang/ref/Reference").unwrap();
+    let (lock, pending_fid, discovered_fid) = {
+        let cls = cls.get_class();
+        let lock = cls.get_field_id(
+            &new_br("lock"),
+            &new_br("Ljava/lang/ref/Reference$Lock;"),
+            true,
+        );
+        let pending = cls.get_field_id(
+            &new_br("pending"),
+            &new_br("Ljava/lang/ref/Reference;"),
+            true,
+        );
+        let discovered = cls.get_field_id(
+            &new_br("discovered"),
+            &new_br("Ljava/lang/ref/Reference;"),
+            false,
+        );
+        (cls.get_static_field_value(lock), pending, discovered)
+    };
+
+    let lock = lock.extract_ref();
+    lock.monitor_enter();
+    let mut pending = cls.get_class().get_static_field_value(pending_fid.clone());
+    for rf in discovered.into_iter().rev() {
+        Class::put_field_value(rf.clone(), discovered_fid.clone(), pending);
+        pending = Oop::Ref(rf);
+    }
+    cls.get_mut_class()
+        .put_static_field_value(pending_fid, pending);
+    lock.notify_all();
+    lock.monitor_exit();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{discover, Fields, ReferenceType, Registered, SoftPolicy};
+    use crate::new_br;
+    use crate::oop::class::tests::{class_file, define};
+    use crate::oop::{Class, Oop};
+    use crate::types::ClassRef;
+    use classfile::flags::ACC_PUBLIC;
+    use std::sync::Arc;
+
+    //a Reference of 'ty' to 'referent', and its registration
+    fn new_reference(
+        cls: &ClassRef,
+        fields: &Fields,
+        ty: ReferenceType,
+        referent: &Oop,
+    ) -> (Oop, Registered) {
+        let reference = Oop::new_inst(cls.clone());
+        let rf = reference.extract_ref();
+        Class::put_field_value(rf.clone(), fields.referent.clone(), referent.clone());
+        (reference, (Arc::downgrade(&rf), ty))
+    }
+
+    fn referent_of(reference: &Oop, fields: &Fields) -> Oop {
+        Class::get_field_value(reference.extract_ref(), fields.referent.clone())
+    }
+
+    #[test]
+    fn t_discover() {
+        let object = Some("java/lang/Object");
+        let ref_fields = [
+            (0, "referent", "Ljava/lang/Object;"),
+            (0, "next", "Ljava/lang/Object;"),
+            (0, "timestamp", "J"),
+        ];
+        let ref_cls = define(&class_file(
+            ACC_PUBLIC,
+            "t/heap/Ref",
+            object,
+            &[],
+            &ref_fields,
+            &[],
+        ));
+        let obj_cls = define(&class_file(ACC_PUBLIC, "t/heap/Obj", object, &[], &[], &[]));
+        let fields = {
+            let cls = ref_cls.get_class();
+            let object = new_br("Ljava/lang/Object;");
+            Fields {
+                referent: cls.get_field_id(&new_br("referent"), &object, false),
+                next: cls.get_field_id(&new_br("next"), &object, false),
+                soft_timestamp: Some(cls.get_field_id(&new_br("timestamp"), &new_br("J"), false)),
+            }
+        };
+        let policy = SoftPolicy {
+            clock: 10_000,
+            max_interval: 1000,
+            clear_all: false,
+        };
+
+        //held by a Weak only, cleared & discovered
+        let (weak, registered) = new_reference(
+            &ref_cls,
+            &fields,
+            ReferenceType::Weak,
+            &Oop::new_inst(obj_cls.clone()),
+        );
+        let mut discovered = vec![];
+        let (cleared, kept) = discover(&[registered], &fields, &policy, &mut discovered);
+        assert!(cleared && kept.is_empty());
+        assert!(Arc::ptr_eq(&discovered[0], &weak.extract_ref()));
+        assert!(referent_of(&weak, &fields).is_null());
+
+        //reachable, kept
+        let obj = Oop::new_inst(obj_cls.clone());
+        let (weak, registered) = new_reference(&ref_cls, &fields, ReferenceType::Weak, &obj);
+        let mut discovered = vec![];
+        let (cleared, kept) = discover(&[registered], &fields, &policy, &mut discovered);
+        assert!(!cleared && discovered.is_empty() && kept.len() == 1);
+        assert!(!referent_of(&weak, &fields).is_null());
+        drop(obj);
+
+        //enqueued already (next set), dropped
+        let (enqueued, registered) = new_reference(
+            &ref_cls,
+            &fields,
+            ReferenceType::Weak,
+            &Oop::new_inst(obj_cls.clone()),
+        );
+        Class::put_field_value(
+            enqueued.extract_ref(),
+            fields.next.clone(),
+            enqueued.clone(),
+        );
+        let mut discovered = vec![];
+        let (cleared, kept) = discover(&[registered], &fields, &policy, &mut discovered);
+        assert!(!cleared && discovered.is_empty() && kept.is_empty());
+
+        //a Soft got recently kept, unless the heap is full; one not got for long cleared
+        let (soft, registered) = new_reference(
+            &ref_cls,
+            &fields,
+            ReferenceType::Soft,
+            &Oop::new_inst(obj_cls.clone()),
+        );
+        let timestamp = fields.soft_timestamp.clone().unwrap();
+        Class::put_field_value(soft.extract_ref(), timestamp.clone(), Oop::new_long(9_500));
+        let mut discovered = vec![];
+        let (cleared, kept) = discover(&[registered], &fields, &policy, &mut discovered);
+        assert!(!cleared && kept.len() == 1);
+        let full = SoftPolicy {
+            clear_all: true,
+            ..policy
+        };
+        let (cleared, _) = discover(&kept, &fields, &full, &mut discovered);
+        assert!(cleared && discovered.len() == 1);
+
+        let (soft, registered) = new_reference(
+            &ref_cls,
+            &fields,
+            ReferenceType::Soft,
+            &Oop::new_inst(obj_cls.clone()),
+        );
+        Class::put_field_value(soft.extract_ref(), timestamp, Oop::new_long(8_000));
+        let mut discovered = vec![];
+        let (cleared, _) = discover(&[registered], &fields, &policy, &mut discovered);
+        assert!(cleared && referent_of(&soft, &fields).is_null());
+
+        //a Final discovered, not cleared, a Phantom to the same referent left
+        let obj = Oop::new_inst(obj_cls.clone());
+        let (_final_ref, registered_final) =
+            new_reference(&ref_cls, &fields, ReferenceType::Final, &obj);
+        let (phantom, registered_phantom) =
+            new_reference(&ref_cls, &fields, ReferenceType::Phantom, &obj);
+        drop(obj);
+        let mut discovered = vec![];
+        let references = [registered_final, registered_phantom];
+        let (cleared, kept) = discover(&references, &fields, &policy, &mut discovered);
+        assert!(!cleared && discovered.len() == 1 && kept.len() == 1);
+        assert!(Arc::ptr_eq(
+            &kept[0].0.upgrade().unwrap(),
+            &phantom.extract_ref()
+        ));
+        assert!(!referent_of(&phantom, &fields).is_null());
+    }
+}
@@ -1,7 +1,7 @@
 use crate::oop;
 use crate::oop::Class;
 use crate::runtime::{self, require_class3};
-use crate::types::JavaThreadRef;
+use crate::types::{ClassRef, JavaThreadRef};
 use crate::util;
 use crate::{native, new_br};
 use classfile::consts::{
@@ -15,8 +15,8 @@ use std::sync::Arc;
 pub fn initialize_jvm() {
     initialize_vm_structs();
 
-    let thread_cls = oop::class::load_and_init(J_THREAD);
-    let thread_group_cls = oop::class::load_and_init(J_THREAD_GROUP);
+    let thread_cls = load_and_init(J_THREAD);
+    let thread_group_cls = load_and_init(J_THREAD_GROUP);
 
     let init_thread_oop = oop::Oop::new_inst(thread_cls.clone());
     {
@@ -49,9 +49,9 @@ pub fn initialize_jvm() {
         Class::put_field_value(init_thread_oop.extract_ref(), id, main_thread_group.clone());
     }
 
-    let _ = oop::class::load_and_init(J_INPUT_STREAM);
-    let _ = oop::class::load_and_init(J_PRINT_STREAM);
-    let _ = oop::class::load_and_init(J_SECURITY_MANAGER);
+    let _ = load_and_init(J_INPUT_STREAM);
+    let _ = load_and_init(J_PRINT_STREAM);
+    let _ = load_and_init(J_SECURITY_MANAGER);
 
     // Construct the main thread group
     let args = vec![
@@ -92,44 +92,49 @@ pub fn initialize_jvm() {
     let mut jc = runtime::invoke::JavaCall::new_with_args(init_system_classes_method, vec![]);
     jc.invoke(None, false);
 
+    //the Finalizer thread, and the ReferenceHandler thread by Reference, see heap
+    let _ = load_and_init(b"java/lang/ref/Finalizer");
+
     //todo: re-enable sun.security.util.Debug
 
     //setup security
-    let _ = oop::class::load_and_init(b"sun/security/provider/Sun");
-    let _ = oop::class::load_and_init(b"sun/security/rsa/SunRsaSign");
-    let _ = oop::class::load_and_init(b"com/sun/net/ssl/internal/ssl/Provider");
+    let _ = load_and_init(b"sun/security/provider/Sun");
+    let _ = load_and_init(b"sun/security/rsa/SunRsaSign");
+    let _ = load_and_init(b"com/sun/net/ssl/internal/ssl/Provider");
+
+    runtime::signal::init();
 }
 
 fn initialize_vm_structs() {
-    let class_obj = oop::class::load_and_init(J_CLASS);
+    let class_obj = load_and_init(J_CLASS);
     native::java_lang_Class::create_delayed_mirrors();
     native::java_lang_Class::create_delayed_ary_mirrors();
 
-    let _ = oop::class::load_and_init(J_OBJECT);
-    let string_cls = oop::class::load_and_init(J_STRING);
+    let _ = load_and_init(J_OBJECT);
+    let string_cls = load_and_init(J_STRING);
     {
         let cls = string_cls.get_class();
         let fir = cls.get_field_id(&new_br("value"), &new_br("[C"), false);
         util::oop::set_java_lang_string_value_offset(fir.offset);
     }
 
-    let integer_cls = oop::class::load_and_init(b"java/lang/Integer");
+    let integer_cls = load_and_init(b"java/lang/Integer");
     {
         let cls = integer_cls.get_class();
         let fir = cls.get_field_id(&new_br("value"), &new_br("I"), false);
         util::oop::set_java_lang_integer_value_offset(fir.offset);
     }
 
-    let _ = oop::class::load_and_init(J_CLONEABLE);
-    let _ = oop::class::load_and_init(J_SERIALIZABLE);
-    let _ = oop::class::load_and_init(J_NPE);
-    let _ = oop::class::load_and_init(J_ARRAY_INDEX_OUT_OF_BOUNDS);
-    let _ = oop::class::load_and_init(J_CLASS_NOT_FOUND);
-    let _ = oop::class::load_and_init(J_INTERNAL_ERROR);
-    let _ = oop::class::load_and_init(J_IOEXCEPTION);
-    let _ = oop::class::load_and_init(J_FIELD);
-    let _ = oop::class::load_and_init(J_METHOD_CTOR);
-    let _ = oop::class::load_and_init(J_THROWABLE);
+    let _ = load_and_init(J_CLONEABLE);
+    let _ = load_and_init(J_SERIALIZABLE);
+    let _ = load_and_init(J_NPE);
+    let _ = load_and_init(J_ARRAY_INDEX_OUT_OF_BOUNDS);
+    let _ = load_and_init(J_CLASS_NOT_FOUND);
+    let _ = load_and_init(J_INTERNAL_ERROR);
+    let _ = load_and_init(J_IOEXCEPTION);
+    let _ = load_and_init(J_FIELD);
+    let _ = load_and_init(J_METHOD_CTOR);
+    let _ = load_and_init(J_THROWABLE);
 
     //todo:
     //java::lang::reflect::Constructor::initialize
@@ -143,8 +148,8 @@ fn initialize_vm_structs() {
 }
 
 fn hack_classes() {
-    let charset_cls = oop::class::load_and_init(b"java/nio/charset/Charset");
-    let ascii_charset_cls = oop::class::load_and_init(b"sun/nio/cs/US_ASCII");
+    let charset_cls = load_and_init(b"java/nio/charset/Charset");
+    let ascii_charset_cls = load_and_init(b"sun/nio/cs/US_ASCII");
 
     let ascii_inst = oop::Oop::new_inst(ascii_charset_cls.clone());
     let args = vec![ascii_inst.clone()];
@@ -159,32 +164,10 @@ fn hack_classes() {
         );
         cls.put_static_field_value(id, ascii_inst);
     }
+}
 
-    let encoder = oop::class::load_and_init(b"sun/nio/cs/StreamEncoder");
-    {
-        let mut cls = encoder.get_mut_class();
-        cls.hack_as_native(b"forOutputStreamWriter", b"(Ljava/io/OutputStream;Ljava/lang/Object;Ljava/lang/String;)Lsun/nio/cs/StreamEncoder;");
-    }
-
-    let system = oop::class::load_and_init(b"java/lang/System");
-
-    {
-        let mut cls = system.get_mut_class();
-        cls.hack_as_native(b"load", b"(Ljava/lang/String;)V");
-
-        //todo: support load lib
-        cls.hack_as_native(b"loadLibrary", b"(Ljava/lang/String;)V");
-
-        //fixme: rm, just for debug
-        //        let id = util::new_method_id(b"getProperty", b"(Ljava/lang/String;)Ljava/lang/String;");
-        //        cls.hack_as_native(id);
-    }
-
-    /*
-    let mut mir = {
-        let cls = encoder.lock().unwrap();
-        let id = util::new_method_id(b"forOutputStreamWriter", b"(Ljava/io/OutputStream;Ljava/lang/Object;Ljava/lang/String;)Lsun/nio/cs/StreamEncoder;");
-        cls.get_static_method(id).unwrap()
-    };
-    */
+//the classes of the bootstrap, the vm can't run without them
+fn load_and_init(name: &[u8]) -> ClassRef {
+    oop::class::load_and_init(name)
+        .unwrap_or_else(|_| panic!("Class init failed: {}", String::from_utf8_lossy(name)))
 }
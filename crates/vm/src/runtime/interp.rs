@@ -2,10 +2,11 @@ use crate::oop::{
     self, consts as oop_consts, field, Class, ClassKind, Oop, OopRef, TypeArrayDesc, ValueType,
 };
 use crate::runtime::local::Local;
+use crate::runtime::quick::{self, InlineCache, Quick};
 use crate::runtime::stack::Stack;
 use crate::runtime::{
-    self, cmp, exception, require_class, require_class2, require_class3, thread, DataArea, Frame,
-    JavaCall,
+    self, cmp, exception, heap, jit, require_class, require_class2, require_class3, thread,
+    DataArea, Frame, JavaCall,
 };
 use crate::types::*;
 use crate::util;
@@ -15,7 +16,7 @@ use classfile::{
 };
 use nix::sys::socket::SockType::Datagram;
 use std::borrow::BorrowMut;
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::collections::HashMap;
 use std::ops::Deref;
 use std::sync::atomic::Ordering;
@@ -25,8 +26,7 @@ macro_rules! array_store {
     ($ary:ident, $pos:ident, $v:ident) => {
         let len = $ary.len();
         if ($pos < 0) || ($pos as usize >= len) {
-            let msg = format!("length is {}, but index is {}", len, $pos);
-            exception::meet_ex(cls_const::J_ARRAY_INDEX_OUT_OF_BOUNDS, Some(msg));
+            exception::meet_index_out_of_bounds($pos, len);
         } else {
             $ary[$pos as usize] = $v;
         }
@@ -37,9 +37,7 @@ macro_rules! iarray_load {
     ($area:ident, $ary:ident, $pos:ident) => {
         let len = $ary.len();
         if ($pos < 0) || ($pos as usize >= len) {
-            drop($area);
-            let msg = format!("length is {}, but index is {}", len, $pos);
-            exception::meet_ex(cls_const::J_ARRAY_INDEX_OUT_OF_BOUNDS, Some(msg));
+            exception::meet_index_out_of_bounds($pos, len);
         } else {
             $area.push_int($ary[$pos as usize] as i32);
         }
@@ -48,11 +46,28 @@ macro_rules! iarray_load {
 
 pub struct Interp<'a> {
     frame: RwLockReadGuard<'a, Box<Frame>>,
+    //a backward branch taken, where the compiled code may be entered (OSR)
+    back_edge: Cell<bool>,
+    //an exception caught by a handler of the frame
+    caught: Cell<bool>,
 }
 
 impl<'a> Interp<'a> {
     pub fn new(frame: RwLockReadGuard<'a, Box<Frame>>) -> Self {
-        Self { frame }
+        Self {
+            frame,
+            back_edge: Cell::new(false),
+            caught: Cell::new(false),
+        }
+    }
+
+    pub(crate) fn frame(&self) -> &Frame {
+        &self.frame
+    }
+
+    //the exception caught since last asked
+    pub(crate) fn take_caught(&self) -> bool {
+        self.caught.replace(false)
     }
 }
 
@@ -74,251 +89,264 @@ impl<'a> Interp<'a> {
     pub fn run(&self) {
         let jt = runtime::thread::current_java_thread();
 
-        loop {
-            let code = self.read_opcode();
-            let code = OpCode::from(code);
-            match code {
-                OpCode::athrow => {
-                    self.athrow(jt);
-                    break;
-                }
-                OpCode::ireturn => {
-                    self.ireturn();
-                    break;
-                }
-                OpCode::lreturn => {
-                    self.lreturn();
-                    break;
-                }
-                OpCode::freturn => {
-                    self.freturn();
-                    break;
-                }
-                OpCode::dreturn => {
-                    self.dreturn();
-                    break;
-                }
-                OpCode::areturn => {
-                    self.areturn();
-                    break;
-                }
-                OpCode::return_void => {
-                    self.return_void();
-                    break;
-                }
-                OpCode::nop => self.nop(),
-                OpCode::aconst_null => self.aconst_null(),
-                OpCode::iconst_m1 => self.iconst_m1(),
-                OpCode::iconst_0 => self.iconst_0(),
-                OpCode::iconst_1 => self.iconst_1(),
-                OpCode::iconst_2 => self.iconst_2(),
-                OpCode::iconst_3 => self.iconst_3(),
-                OpCode::iconst_4 => self.iconst_4(),
-                OpCode::iconst_5 => self.iconst_5(),
-                OpCode::lconst_0 => self.lconst_0(),
-                OpCode::lconst_1 => self.lconst_1(),
-                OpCode::fconst_0 => self.fconst_0(),
-                OpCode::fconst_1 => self.fconst_1(),
-                OpCode::fconst_2 => self.fconst_2(),
-                OpCode::dconst_0 => self.dconst_0(),
-                OpCode::dconst_1 => self.dconst_1(),
-                OpCode::bipush => self.bipush(),
-                OpCode::sipush => self.sipush(),
-                OpCode::ldc => self.ldc(),
-                OpCode::ldc_w => self.ldc_w(),
-                OpCode::ldc2_w => self.ldc2_w(),
-                OpCode::iload => self.iload(),
-                OpCode::lload => self.lload(),
-                OpCode::fload => self.fload(),
-                OpCode::dload => self.dload(),
-                OpCode::aload => self.aload(),
-                OpCode::iload_0 => self.iload_0(),
-                OpCode::iload_1 => self.iload_1(),
-                OpCode::iload_2 => self.iload_2(),
-                OpCode::iload_3 => self.iload_3(),
-                OpCode::lload_0 => self.lload_0(),
-                OpCode::lload_1 => self.lload_1(),
-                OpCode::lload_2 => self.lload_2(),
-                OpCode::lload_3 => self.lload_3(),
-                OpCode::fload_0 => self.fload_0(),
-                OpCode::fload_1 => self.fload_1(),
-                OpCode::fload_2 => self.fload_2(),
-                OpCode::fload_3 => self.fload_3(),
-                OpCode::dload_0 => self.dload_0(),
-                OpCode::dload_1 => self.dload_1(),
-                OpCode::dload_2 => self.dload_2(),
-                OpCode::dload_3 => self.dload_3(),
-                OpCode::aload_0 => self.aload_0(),
-                OpCode::aload_1 => self.aload_1(),
-                OpCode::aload_2 => self.aload_2(),
-                OpCode::aload_3 => self.aload_3(),
-                OpCode::iaload => self.iaload(),
-                OpCode::laload => self.laload(),
-                OpCode::faload => self.faload(),
-                OpCode::daload => self.daload(),
-                OpCode::aaload => self.aaload(),
-                OpCode::baload => self.baload(),
-                OpCode::caload => self.caload(),
-                OpCode::saload => self.saload(),
-                OpCode::istore => self.istore(),
-                OpCode::lstore => self.lstore(),
-                OpCode::fstore => self.fstore(),
-                OpCode::dstore => self.dstore(),
-                OpCode::astore => self.astore(),
-                OpCode::istore_0 => self.istore_0(),
-                OpCode::istore_1 => self.istore_1(),
-                OpCode::istore_2 => self.istore_2(),
-                OpCode::istore_3 => self.istore_3(),
-                OpCode::lstore_0 => self.lstore_0(),
-                OpCode::lstore_1 => self.lstore_1(),
-                OpCode::lstore_2 => self.lstore_2(),
-                OpCode::lstore_3 => self.lstore_3(),
-                OpCode::fstore_0 => self.fstore_0(),
-                OpCode::fstore_1 => self.fstore_1(),
-                OpCode::fstore_2 => self.fstore_2(),
-                OpCode::fstore_3 => self.fstore_3(),
-                OpCode::dstore_0 => self.dstore_0(),
-                OpCode::dstore_1 => self.dstore_1(),
-                OpCode::dstore_2 => self.dstore_2(),
-                OpCode::dstore_3 => self.dstore_3(),
-                OpCode::astore_0 => self.astore_0(),
-                OpCode::astore_1 => self.astore_1(),
-                OpCode::astore_2 => self.astore_2(),
-                OpCode::astore_3 => self.astore_3(),
-                OpCode::iastore => self.iastore(),
-                OpCode::lastore => self.lastore(),
-                OpCode::fastore => self.fastore(),
-                OpCode::dastore => self.dastore(),
-                OpCode::aastore => self.aastore(),
-                OpCode::bastore => self.bastore(),
-                OpCode::castore => self.castore(),
-                OpCode::sastore => self.sastore(),
-                OpCode::pop => self.pop(),
-                OpCode::pop2 => self.pop2(),
-                OpCode::dup => self.dup(),
-                OpCode::dup_x1 => self.dup_x1(),
-                OpCode::dup_x2 => self.dup_x2(),
-                OpCode::dup2 => self.dup2(),
-                OpCode::dup2_x1 => self.dup2_x1(),
-                OpCode::dup2_x2 => self.dup2_x2(),
-                OpCode::swap => self.swap(),
-                OpCode::iadd => self.iadd(),
-                OpCode::ladd => self.ladd(),
-                OpCode::fadd => self.fadd(),
-                OpCode::dadd => self.dadd(),
-                OpCode::isub => self.isub(),
-                OpCode::lsub => self.lsub(),
-                OpCode::fsub => self.fsub(),
-                OpCode::dsub => self.dsub(),
-                OpCode::imul => self.imul(),
-                OpCode::lmul => self.lmul(),
-                OpCode::fmul => self.fmul(),
-                OpCode::dmul => self.dmul(),
-                OpCode::idiv => self.idiv(),
-                OpCode::ldiv => self.ldiv(),
-                OpCode::fdiv => self.fdiv(),
-                OpCode::ddiv => self.ddiv(),
-                OpCode::irem => self.irem(),
-                OpCode::lrem => self.lrem(),
-                OpCode::frem => self.frem(),
-                OpCode::drem => self.drem(),
-                OpCode::ineg => self.ineg(),
-                OpCode::lneg => self.lneg(),
-                OpCode::fneg => self.fneg(),
-                OpCode::dneg => self.dneg(),
-                OpCode::ishl => self.ishl(),
-                OpCode::lshl => self.lshl(),
-                OpCode::ishr => self.ishr(),
-                OpCode::lshr => self.lshr(),
-                OpCode::iushr => self.iushr(),
-                OpCode::lushr => self.lushr(),
-                OpCode::iand => self.iand(),
-                OpCode::land => self.land(),
-                OpCode::ior => self.ior(),
-                OpCode::lor => self.lor(),
-                OpCode::ixor => self.ixor(),
-                OpCode::lxor => self.lxor(),
-                OpCode::iinc => self.iinc(),
-                OpCode::i2l => self.i2l(),
-                OpCode::i2f => self.i2f(),
-                OpCode::i2d => self.i2d(),
-                OpCode::l2i => self.l2i(),
-                OpCode::l2f => self.l2f(),
-                OpCode::l2d => self.l2d(),
-                OpCode::f2i => self.f2i(),
-                OpCode::f2l => self.f2l(),
-                OpCode::f2d => self.f2d(),
-                OpCode::d2i => self.d2i(),
-                OpCode::d2l => self.d2l(),
-                OpCode::d2f => self.d2f(),
-                OpCode::i2b => self.i2b(),
-                OpCode::i2c => self.i2c(),
-                OpCode::i2s => self.i2s(),
-                OpCode::lcmp => self.lcmp(),
-                OpCode::fcmpl => self.fcmpl(),
-                OpCode::fcmpg => self.fcmpg(),
-                OpCode::dcmpl => self.dcmpl(),
-                OpCode::dcmpg => self.dcmpg(),
-                OpCode::ifeq => self.ifeq(),
-                OpCode::ifne => self.ifne(),
-                OpCode::iflt => self.iflt(),
-                OpCode::ifge => self.ifge(),
-                OpCode::ifgt => self.ifgt(),
-                OpCode::ifle => self.ifle(),
-                OpCode::if_icmpeq => self.if_icmpeq(),
-                OpCode::if_icmpne => self.if_icmpne(),
-                OpCode::if_icmplt => self.if_icmplt(),
-                OpCode::if_icmpge => self.if_icmpge(),
-                OpCode::if_icmpgt => self.if_icmpgt(),
-                OpCode::if_icmple => self.if_icmple(),
-                OpCode::if_acmpeq => self.if_acmpeq(),
-                OpCode::if_acmpne => self.if_acmpne(),
-                OpCode::goto => self.goto(),
-                OpCode::jsr => self.jsr(),
-                OpCode::ret => self.ret(),
-                OpCode::tableswitch => self.table_switch(),
-                OpCode::lookupswitch => self.lookup_switch(),
-                OpCode::getstatic => self.get_static(),
-                OpCode::putstatic => self.put_static(),
-                OpCode::getfield => self.get_field(),
-                OpCode::putfield => self.put_field(),
-                OpCode::invokevirtual => self.invoke_virtual(),
-                OpCode::invokespecial => self.invoke_special(),
-                OpCode::invokestatic => self.invoke_static(),
-                OpCode::invokeinterface => self.invoke_interface(),
-                OpCode::invokedynamic => self.invoke_dynamic(),
-                OpCode::new => self.new_(),
-                OpCode::newarray => self.new_array(),
-                OpCode::anewarray => self.anew_array(),
-                OpCode::arraylength => self.array_length(),
-                OpCode::checkcast => self.check_cast(),
-                OpCode::instanceof => self.instance_of(),
-                OpCode::monitorenter => self.monitor_enter(),
-                OpCode::monitorexit => self.monitor_exit(),
-                OpCode::wide => self.wide(),
-                OpCode::multianewarray => self.multi_anew_array(),
-                OpCode::ifnull => self.if_null(),
-                OpCode::ifnonnull => self.if_non_null(),
-                OpCode::goto_w => self.goto_w(),
-                OpCode::jsr_w => self.jsr_w(),
-                _ => unreachable!(),
+        if jit::invoke(self, &jt) {
+            return;
+        }
+
+        while self.step(&jt) {
+            if self.back_edge.replace(false) && jit::back_edge(self, &jt) {
+                return;
             }
+        }
+    }
 
-            let is_meet_ex = thread::is_meet_ex();
-            if is_meet_ex {
-                let mut th = jt.write().unwrap();
-                let ex = th.take_ex().unwrap();
-                drop(th);
-                match self.try_handle_exception(ex) {
-                    Ok(_) => (),
-                    Err(ex) => {
-                        let mut th = jt.write().unwrap();
-                        th.set_ex(ex);
-                        break;
-                    }
+    //executes one instruction, false if the method returned or threw
+    pub(crate) fn step(&self, jt: &JavaThreadRef) -> bool {
+        let pc = self.frame.pc.load(Ordering::Relaxed);
+        let code = self.read_opcode();
+        let code = OpCode::from(code);
+        match code {
+            OpCode::ireturn => {
+                self.ireturn();
+                return false;
+            }
+            OpCode::lreturn => {
+                self.lreturn();
+                return false;
+            }
+            OpCode::freturn => {
+                self.freturn();
+                return false;
+            }
+            OpCode::dreturn => {
+                self.dreturn();
+                return false;
+            }
+            OpCode::areturn => {
+                self.areturn();
+                return false;
+            }
+            OpCode::return_void => {
+                self.return_void();
+                return false;
+            }
+            OpCode::nop => self.nop(),
+            OpCode::aconst_null => self.aconst_null(),
+            OpCode::iconst_m1 => self.iconst_m1(),
+            OpCode::iconst_0 => self.iconst_0(),
+            OpCode::iconst_1 => self.iconst_1(),
+            OpCode::iconst_2 => self.iconst_2(),
+            OpCode::iconst_3 => self.iconst_3(),
+            OpCode::iconst_4 => self.iconst_4(),
+            OpCode::iconst_5 => self.iconst_5(),
+            OpCode::lconst_0 => self.lconst_0(),
+            OpCode::lconst_1 => self.lconst_1(),
+            OpCode::fconst_0 => self.fconst_0(),
+            OpCode::fconst_1 => self.fconst_1(),
+            OpCode::fconst_2 => self.fconst_2(),
+            OpCode::dconst_0 => self.dconst_0(),
+            OpCode::dconst_1 => self.dconst_1(),
+            OpCode::bipush => self.bipush(),
+            OpCode::sipush => self.sipush(),
+            OpCode::ldc => self.ldc(),
+            OpCode::ldc_w => self.ldc_w(),
+            OpCode::ldc2_w => self.ldc2_w(),
+            OpCode::iload => self.iload(),
+            OpCode::lload => self.lload(),
+            OpCode::fload => self.fload(),
+            OpCode::dload => self.dload(),
+            OpCode::aload => self.aload(),
+            OpCode::iload_0 => self.iload_0(),
+            OpCode::iload_1 => self.iload_1(),
+            OpCode::iload_2 => self.iload_2(),
+            OpCode::iload_3 => self.iload_3(),
+            OpCode::lload_0 => self.lload_0(),
+            OpCode::lload_1 => self.lload_1(),
+            OpCode::lload_2 => self.lload_2(),
+            OpCode::lload_3 => self.lload_3(),
+            OpCode::fload_0 => self.fload_0(),
+            OpCode::fload_1 => self.fload_1(),
+            OpCode::fload_2 => self.fload_2(),
+            OpCode::fload_3 => self.fload_3(),
+            OpCode::dload_0 => self.dload_0(),
+            OpCode::dload_1 => self.dload_1(),
+            OpCode::dload_2 => self.dload_2(),
+            OpCode::dload_3 => self.dload_3(),
+            OpCode::aload_0 => self.aload_0(),
+            OpCode::aload_1 => self.aload_1(),
+            OpCode::aload_2 => self.aload_2(),
+            OpCode::aload_3 => self.aload_3(),
+            OpCode::iaload => self.iaload(),
+            OpCode::laload => self.laload(),
+            OpCode::faload => self.faload(),
+            OpCode::daload => self.daload(),
+            OpCode::aaload => self.aaload(),
+            OpCode::baload => self.baload(),
+            OpCode::caload => self.caload(),
+            OpCode::saload => self.saload(),
+            OpCode::istore => self.istore(),
+            OpCode::lstore => self.lstore(),
+            OpCode::fstore => self.fstore(),
+            OpCode::dstore => self.dstore(),
+            OpCode::astore => self.astore(),
+            OpCode::istore_0 => self.istore_0(),
+            OpCode::istore_1 => self.istore_1(),
+            OpCode::istore_2 => self.istore_2(),
+            OpCode::istore_3 => self.istore_3(),
+            OpCode::lstore_0 => self.lstore_0(),
+            OpCode::lstore_1 => self.lstore_1(),
+            OpCode::lstore_2 => self.lstore_2(),
+            OpCode::lstore_3 => self.lstore_3(),
+            OpCode::fstore_0 => self.fstore_0(),
+            OpCode::fstore_1 => self.fstore_1(),
+            OpCode::fstore_2 => self.fstore_2(),
+            OpCode::fstore_3 => self.fstore_3(),
+            OpCode::dstore_0 => self.dstore_0(),
+            OpCode::dstore_1 => self.dstore_1(),
+            OpCode::dstore_2 => self.dstore_2(),
+            OpCode::dstore_3 => self.dstore_3(),
+            OpCode::astore_0 => self.astore_0(),
+            OpCode::astore_1 => self.astore_1(),
+            OpCode::astore_2 => self.astore_2(),
+            OpCode::astore_3 => self.astore_3(),
+            OpCode::iastore => self.iastore(),
+            OpCode::lastore => self.lastore(),
+            OpCode::fastore => self.fastore(),
+            OpCode::dastore => self.dastore(),
+            OpCode::aastore => self.aastore(),
+            OpCode::bastore => self.bastore(),
+            OpCode::castore => self.castore(),
+            OpCode::sastore => self.sastore(),
+            OpCode::pop => self.pop(),
+            OpCode::pop2 => self.pop2(),
+            OpCode::dup => self.dup(),
+            OpCode::dup_x1 => self.dup_x1(),
+            OpCode::dup_x2 => self.dup_x2(),
+            OpCode::dup2 => self.dup2(),
+            OpCode::dup2_x1 => self.dup2_x1(),
+            OpCode::dup2_x2 => self.dup2_x2(),
+            OpCode::swap => self.swap(),
+            OpCode::iadd => self.iadd(),
+            OpCode::ladd => self.ladd(),
+            OpCode::fadd => self.fadd(),
+            OpCode::dadd => self.dadd(),
+            OpCode::isub => self.isub(),
+            OpCode::lsub => self.lsub(),
+            OpCode::fsub => self.fsub(),
+            OpCode::dsub => self.dsub(),
+            OpCode::imul => self.imul(),
+            OpCode::lmul => self.lmul(),
+            OpCode::fmul => self.fmul(),
+            OpCode::dmul => self.dmul(),
+            OpCode::idiv => self.idiv(),
+            OpCode::ldiv => self.ldiv(),
+            OpCode::fdiv => self.fdiv(),
+            OpCode::ddiv => self.ddiv(),
+            OpCode::irem => self.irem(),
+            OpCode::lrem => self.lrem(),
+            OpCode::frem => self.frem(),
+            OpCode::drem => self.drem(),
+            OpCode::ineg => self.ineg(),
+            OpCode::lneg => self.lneg(),
+            OpCode::fneg => self.fneg(),
+            OpCode::dneg => self.dneg(),
+            OpCode::ishl => self.ishl(),
+            OpCode::lshl => self.lshl(),
+            OpCode::ishr => self.ishr(),
+            OpCode::lshr => self.lshr(),
+            OpCode::iushr => self.iushr(),
+            OpCode::lushr => self.lushr(),
+            OpCode::iand => self.iand(),
+            OpCode::land => self.land(),
+            OpCode::ior => self.ior(),
+            OpCode::lor => self.lor(),
+            OpCode::ixor => self.ixor(),
+            OpCode::lxor => self.lxor(),
+            OpCode::iinc => self.iinc(),
+            OpCode::i2l => self.i2l(),
+            OpCode::i2f => self.i2f(),
+            OpCode::i2d => self.i2d(),
+            OpCode::l2i => self.l2i(),
+            OpCode::l2f => self.l2f(),
+            OpCode::l2d => self.l2d(),
+            OpCode::f2i => self.f2i(),
+            OpCode::f2l => self.f2l(),
+            OpCode::f2d => self.f2d(),
+            OpCode::d2i => self.d2i(),
+            OpCode::d2l => self.d2l(),
+            OpCode::d2f => self.d2f(),
+            OpCode::i2b => self.i2b(),
+            OpCode::i2c => self.i2c(),
+            OpCode::i2s => self.i2s(),
+            OpCode::lcmp => self.lcmp(),
+            OpCode::fcmpl => self.fcmpl(),
+            OpCode::fcmpg => self.fcmpg(),
+            OpCode::dcmpl => self.dcmpl(),
+            OpCode::dcmpg => self.dcmpg(),
+            OpCode::ifeq => self.ifeq(),
+            OpCode::ifne => self.ifne(),
+            OpCode::iflt => self.iflt(),
+            OpCode::ifge => self.ifge(),
+            OpCode::ifgt => self.ifgt(),
+            OpCode::ifle => self.ifle(),
+            OpCode::if_icmpeq => self.if_icmpeq(),
+            OpCode::if_icmpne => self.if_icmpne(),
+            OpCode::if_icmplt => self.if_icmplt(),
+            OpCode::if_icmpge => self.if_icmpge(),
+            OpCode::if_icmpgt => self.if_icmpgt(),
+            OpCode::if_icmple => self.if_icmple(),
+            OpCode::if_acmpeq => self.if_acmpeq(),
+            OpCode::if_acmpne => self.if_acmpne(),
+            OpCode::goto => self.goto(),
+            OpCode::jsr => self.jsr(),
+            OpCode::ret => self.ret(),
+            OpCode::tableswitch => self.table_switch(),
+            OpCode::lookupswitch => self.lookup_switch(),
+            OpCode::getstatic => self.get_static(),
+            OpCode::putstatic => self.put_static(),
+            OpCode::getfield => self.get_field(),
+            OpCode::putfield => self.put_field(),
+            OpCode::invokevirtual => self.invoke_virtual(),
+            OpCode::invokespecial => self.invoke_special(),
+            OpCode::invokestatic => self.invoke_static(),
+            OpCode::invokeinterface => self.invoke_interface(),
+            OpCode::invokedynamic => self.invoke_dynamic(),
+            OpCode::new => self.new_(),
+            OpCode::newarray => self.new_array(),
+            OpCode::anewarray => self.anew_array(),
+            OpCode::arraylength => self.array_length(),
+            OpCode::checkcast => self.check_cast(),
+            OpCode::instanceof => self.instance_of(),
+            OpCode::monitorenter => self.monitor_enter(),
+            OpCode::monitorexit => self.monitor_exit(),
+            OpCode::athrow => self.athrow(jt),
+            OpCode::wide => self.wide(),
+            OpCode::multianewarray => self.multi_anew_array(),
+            OpCode::ifnull => self.if_null(),
+            OpCode::ifnonnull => self.if_non_null(),
+            OpCode::goto_w => self.goto_w(),
+            OpCode::jsr_w => self.jsr_w(),
+            _ => unreachable!(),
+        }
+
+        //thrown by the instruction, or by the method invoked & not caught there;
+        //the flag read first, the lock taken only with an exception pending
+        let ex = if runtime::thread::is_meet_ex() {
+            jt.write().unwrap().take_ex()
+        } else {
+            None
+        };
+        if let Some(ex) = ex {
+            match self.try_handle_exception(pc as U2, ex) {
+                Ok(_) => self.caught.set(true),
+                Err(ex) => {
+                    jt.write().unwrap().set_ex(ex);
+                    return false;
                 }
             }
         }
+
+        true
     }
 }
 
@@ -355,42 +383,122 @@ impl<'a> Interp<'a> {
         self.read_u1() << 8 | self.read_u1()
     }
 
+    //pc of the opcode being executed, before its operands read
+    #[inline]
+    fn opcode_pc(&self) -> usize {
+        self.frame.pc.load(Ordering::Relaxed) as usize - 1
+    }
+
+    //the quick form at 'pc', or resolved by cp_cache & recorded
+    //None with the error of the resolution met, JVMS 5.4.3
+    fn quick_field(&self, pc: usize, idx: usize, is_static: bool) -> Option<FieldIdRef> {
+        let quick = &self.frame.mir.method.quick;
+        if let Some(Quick::Field(fir)) = quick.get(pc) {
+            return Some(fir.clone());
+        }
+
+        let class = self.frame.class.extract_inst();
+        let fir = self.linked(class.cp_cache.get_field(idx, is_static, &self.frame.class))?;
+        if quick::rewrite_bytecodes() {
+            quick.put(pc, Quick::Field(fir.clone()));
+        }
+        Some(fir)
+    }
+
+    fn quick_method(&self, pc: usize, idx: usize) -> Option<(MethodIdRef, Option<&InlineCache>)> {
+        let quick = &self.frame.mir.method.quick;
+        let it = match quick.get(pc) {
+            Some(it) => it,
+            None => {
+                let class = self.frame.class.extract_inst();
+                let mir = self.linked(class.cp_cache.get_method(idx, &self.frame.class))?;
+                if !quick::rewrite_bytecodes() {
+                    return Some((mir, None));
+                }
+                quick.put(pc, Quick::Method(mir, InlineCache::default()))
+            }
+        };
+
+        match it {
+            Quick::Method(mir, ic) if quick::use_inline_caches() => Some((mir.clone(), Some(ic))),
+            Quick::Method(mir, _) => Some((mir.clone(), None)),
+            _ => unreachable!(),
+        }
+    }
+
+    fn quick_class(&self, pc: usize, idx: usize) -> Option<ClassRef> {
+        let quick = &self.frame.mir.method.quick;
+        if let Some(Quick::Class(class)) = quick.get(pc) {
+            return Some(class.clone());
+        }
+
+        let class = self.resolve_class(idx)?;
+        if quick::rewrite_bytecodes() {
+            quick.put(pc, Quick::Class(class.clone()));
+        }
+        Some(class)
+    }
+
+    //CONSTANT_Class at 'idx' resolved for the class of the frame
+    fn resolve_class(&self, idx: usize) -> Option<ClassRef> {
+        self.linked(runtime::resolve_class(
+            idx as U2,
+            &self.frame.cp,
+            &self.frame.class,
+        ))
+    }
+
+    //the error of a resolution met, as exceptions thrown by the instruction
+    fn linked<T>(&self, r: Result<T, Oop>) -> Option<T> {
+        match r {
+            Ok(v) => Some(v),
+            Err(ex) => {
+                let jt = runtime::thread::current_java_thread();
+                jt.write().unwrap().set_ex(ex);
+                None
+            }
+        }
+    }
+
+    //initialized as JVMS 5.5 for new, getstatic, putstatic & invokestatic;
+    //false with the exception met
+    fn init_class(&self, class: &ClassRef) -> bool {
+        self.linked(oop::class::init_class(class)).is_some()
+    }
+
     fn load_constant(&self, pos: usize) {
         match &self.frame.cp[pos] {
             ConstantPoolType::Integer { v } => {
-                let mut stack = self.frame.area.stack.borrow_mut();
+                let stack = &self.frame.area.stack;
                 stack.push_int2(v)
             }
             ConstantPoolType::Float { v } => {
-                let mut stack = self.frame.area.stack.borrow_mut();
+                let stack = &self.frame.area.stack;
                 stack.push_float2(v)
             }
             ConstantPoolType::Long { v } => {
-                let mut stack = self.frame.area.stack.borrow_mut();
+                let stack = &self.frame.area.stack;
                 stack.push_long2(v)
             }
             ConstantPoolType::Double { v } => {
-                let mut stack = self.frame.area.stack.borrow_mut();
+                let stack = &self.frame.area.stack;
                 stack.push_double2(v)
             }
             ConstantPoolType::String { string_index } => {
                 let s = get_cp_utf8(&self.frame.cp, *string_index as usize);
                 let s = util::oop::new_java_lang_string3(s.as_slice());
 
-                let mut stack = self.frame.area.stack.borrow_mut();
+                let stack = &self.frame.area.stack;
                 stack.push_ref(s);
             }
-            ConstantPoolType::Class { name_index } => {
-                let name = get_cp_utf8(&self.frame.cp, *name_index as usize);
-                let name = unsafe { std::str::from_utf8_unchecked(name.as_slice()) };
-                let cl = { self.frame.class.get_class().class_loader };
-                trace!("load_constant name={}, cl={:?}", name, cl);
-                let class = runtime::require_class3(cl, name.as_bytes()).unwrap();
-                oop::class::init_class(&class);
-                oop::class::init_class_fully(&class);
+            ConstantPoolType::Class { .. } => {
+                let class = match self.resolve_class(pos) {
+                    Some(class) => class,
+                    None => return,
+                };
 
                 let mirror = { class.get_class().get_mirror() };
-                let mut stack = self.frame.area.stack.borrow_mut();
+                let stack = &self.frame.area.stack;
                 stack.push_ref(mirror);
             }
             _ => unreachable!(),
@@ -415,6 +523,9 @@ impl<'a> Interp<'a> {
         let high = self.frame.code[pc as usize] as i16;
         let low = self.frame.code[(pc + 1) as usize] as i16;
         let branch = (high << 8) | low;
+        if branch < 0 {
+            self.back_edge.set(true);
+        }
 
         self.goto_by_offset_with_occupied(branch as i32, occupied);
     }
@@ -429,20 +540,25 @@ impl<'a> Interp<'a> {
         *return_v = v;
     }
 
-    fn get_field_helper(&self, receiver: Oop, idx: usize, is_static: bool) {
-        let class = self.frame.class.extract_inst();
-        let fir = class.cp_cache.get_field(idx, is_static);
+    fn get_field_helper(&self, receiver: Oop, pc: usize, idx: usize, is_static: bool) {
+        let fir = match self.quick_field(pc, idx, is_static) {
+            Some(fir) => fir,
+            None => return,
+        };
+        if is_static && !self.init_class(&fir.field.class) {
+            return;
+        }
 
-        debug_assert_eq!(fir.field.is_static(), is_static);
         trace!("get_field_helper={:?}, is_static={}", fir.field, is_static);
 
         let value_type = fir.field.value_type;
+        let is_volatile = fir.field.is_volatile();
         let class = fir.field.class.get_class();
-        let v = if is_static {
-            class.get_static_field_value(fir.clone())
-        } else {
-            let rf = receiver.extract_ref();
-            Class::get_field_value2(rf, fir.offset)
+        let v = match (is_static, is_volatile) {
+            (true, false) => class.get_static_field_value(fir.clone()),
+            (true, true) => class.get_static_field_value_volatile(fir.clone()),
+            (false, false) => Class::get_field_value2(receiver.extract_ref(), fir.offset),
+            (false, true) => Class::get_field_value2_volatile(receiver.extract_ref(), fir.offset),
         };
 
         match value_type {
@@ -451,34 +567,38 @@ impl<'a> Interp<'a> {
             | ValueType::CHAR
             | ValueType::BOOLEAN
             | ValueType::BYTE => {
-                let mut stack = self.frame.area.stack.borrow_mut();
+                let stack = &self.frame.area.stack;
                 stack.push_int(v.extract_int());
             }
             ValueType::FLOAT => {
-                let mut stack = self.frame.area.stack.borrow_mut();
+                let stack = &self.frame.area.stack;
                 stack.push_float(v.extract_float());
             }
             ValueType::DOUBLE => {
-                let mut stack = self.frame.area.stack.borrow_mut();
+                let stack = &self.frame.area.stack;
                 stack.push_double(v.extract_double());
             }
             ValueType::LONG => {
-                let mut stack = self.frame.area.stack.borrow_mut();
+                let stack = &self.frame.area.stack;
                 stack.push_long(v.extract_long());
             }
             ValueType::OBJECT | ValueType::ARRAY => {
-                let mut stack = self.frame.area.stack.borrow_mut();
+                let stack = &self.frame.area.stack;
                 stack.push_ref(v)
             }
             _ => unreachable!(),
         }
     }
 
-    fn put_field_helper(&self, idx: usize, is_static: bool) {
-        let class = self.frame.class.extract_inst();
-        let fir = class.cp_cache.get_field(idx, is_static);
+    fn put_field_helper(&self, pc: usize, idx: usize, is_static: bool) {
+        let fir = match self.quick_field(pc, idx, is_static) {
+            Some(fir) => fir,
+            None => return,
+        };
+        if is_static && !self.init_class(&fir.field.class) {
+            return;
+        }
 
-        debug_assert_eq!(fir.field.is_static(), is_static);
         trace!("put_field_helper={:?}, is_static={}", fir.field, is_static);
 
         let value_type = fir.field.value_type;
@@ -489,199 +609,187 @@ impl<'a> Interp<'a> {
             | ValueType::CHAR
             | ValueType::BOOLEAN
             | ValueType::BYTE => {
-                let mut stack = self.frame.area.stack.borrow_mut();
+                let stack = &self.frame.area.stack;
                 let v = stack.pop_int();
                 Oop::new_int(v)
             }
             ValueType::FLOAT => {
-                let mut stack = self.frame.area.stack.borrow_mut();
+                let stack = &self.frame.area.stack;
                 let v = stack.pop_float();
                 Oop::new_float(v)
             }
             ValueType::DOUBLE => {
-                let mut stack = self.frame.area.stack.borrow_mut();
+                let stack = &self.frame.area.stack;
                 let v = stack.pop_double();
                 Oop::new_double(v)
             }
             ValueType::LONG => {
-                let mut stack = self.frame.area.stack.borrow_mut();
+                let stack = &self.frame.area.stack;
                 let v = stack.pop_long();
                 Oop::new_long(v)
             }
             ValueType::ARRAY | ValueType::OBJECT => {
-                let mut stack = self.frame.area.stack.borrow_mut();
+                let stack = &self.frame.area.stack;
                 stack.pop_ref()
             }
             _ => unreachable!(),
         };
 
+        let is_volatile = fir.field.is_volatile();
         let mut class = fir.field.class.get_mut_class();
         if is_static {
-            class.put_static_field_value(fir.clone(), v);
+            if is_volatile {
+                class.put_static_field_value_volatile(fir.clone(), v);
+            } else {
+                class.put_static_field_value(fir.clone(), v);
+            }
         } else {
             let receiver = {
-                let mut stack = self.frame.area.stack.borrow_mut();
+                let stack = &self.frame.area.stack;
                 stack.pop_ref()
             };
             match receiver {
                 Oop::Null => exception::meet_ex(cls_const::J_NPE, None),
+                _ if is_volatile => {
+                    Class::put_field_value2_volatile(receiver.extract_ref(), fir.offset, v)
+                }
                 _ => Class::put_field_value2(receiver.extract_ref(), fir.offset, v),
             }
         }
     }
 
-    fn invoke_helper(&self, is_static: bool, idx: usize, force_no_resolve: bool) {
-        let class = self.frame.class.extract_inst();
-        let mir = class.cp_cache.get_method(idx);
+    fn invoke_helper(&self, pc: usize, is_static: bool, idx: usize, force_no_resolve: bool) {
+        let (mir, ic) = match self.quick_method(pc, idx) {
+            Some(it) => it,
+            None => return,
+        };
+        if mir.method.is_static() != is_static {
+            let method = format!(
+                "{}.{}{}",
+                runtime::access::external_name(&mir.method.class),
+                String::from_utf8_lossy(mir.method.name.as_slice()),
+                String::from_utf8_lossy(mir.method.desc.as_slice())
+            );
+            let msg = if is_static {
+                format!("Expected static method {}", method)
+            } else {
+                format!("Expecting non-static method {}", method)
+            };
+            exception::meet_ex(cls_const::J_ICCE, Some(msg));
+            return;
+        }
+        if is_static && !self.init_class(&mir.method.class) {
+            return;
+        }
         let caller = match &mir.method.signature.retype {
             classfile::SignatureType::Void => None,
             _ => Some(&self.frame.area),
         };
-        debug_assert_eq!(mir.method.is_static(), is_static);
         if let Ok(mut jc) = runtime::invoke::JavaCall::new(&self.frame.area, mir) {
-            jc.invoke(caller, force_no_resolve);
+            match ic {
+                Some(ic) if !force_no_resolve => jc.invoke_cached(caller, ic),
+                _ => jc.invoke(caller, force_no_resolve),
+            }
         }
     }
 
     pub fn check_cast_helper(&self, is_cast: bool) {
-        let cp_idx = self.read_i2();
-        let target_cls = require_class2(cp_idx as U2, &self.frame.cp).unwrap();
+        let pc = self.opcode_pc();
+        let cp_idx = self.read_u2();
+        let target_cls = match self.quick_class(pc, cp_idx) {
+            Some(class) => class,
+            None => return,
+        };
 
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let obj_rf = stack.pop_ref();
-        drop(stack);
-
-        let obj_rf_clone = obj_rf.clone();
-        let op_check_cast = |r: bool, obj_cls: ClassRef, target_cls: ClassRef| {
-            if r {
-                let mut stack = self.frame.area.stack.borrow_mut();
-                stack.push_ref(obj_rf_clone);
-            } else {
-                let obj_name = { obj_cls.get_class().name.clone() };
-                let target_name = { target_cls.get_class().name.clone() };
-
-                let obj_name = String::from_utf8_lossy(obj_name.as_slice()).replace("/", ".");
-                let target_name = String::from_utf8_lossy(target_name.as_slice()).replace("/", ".");
-
-                let msg = format!("{} cannot be cast to {}", obj_name, target_name);
-                exception::meet_ex(cls_const::J_CCE, Some(msg));
+        let obj_cls = match util::oop::get_oop_class(&obj_rf) {
+            Some(obj_cls) => obj_cls,
+            //null passes checkcast, not instanceof
+            None if is_cast => {
+                stack.push_ref(obj_rf);
+                return;
             }
-        };
-        let op_instance_of = |r: bool| {
-            let mut stack = self.frame.area.stack.borrow_mut();
-            if r {
-                stack.push_const1(false);
-            } else {
-                stack.push_const0(false);
+            None => {
+                stack.push_int(0);
+                return;
             }
         };
 
-        match obj_rf {
-            Oop::Null => {
-                let mut stack = self.frame.area.stack.borrow_mut();
-                if is_cast {
-                    stack.push_ref(obj_rf);
-                } else {
-                    stack.push_const0(false);
-                }
-            }
-            Oop::Ref(rf) => {
-                let rf = rf.get_raw_ptr();
-                unsafe {
-                    match &(*rf).v {
-                        oop::RefKind::Inst(inst) => {
-                            let obj_cls = inst.class.clone();
-                            let r = cmp::instance_of(obj_cls.clone(), target_cls.clone());
-
-                            if is_cast {
-                                op_check_cast(r, obj_cls, target_cls);
-                            } else {
-                                op_instance_of(r);
-                            }
-                        }
-
-                        oop::RefKind::Array(ary) => {
-                            let obj_cls = ary.class.clone();
-                            let r = cmp::instance_of(obj_cls.clone(), target_cls.clone());
-                            if is_cast {
-                                op_check_cast(r, obj_cls, target_cls);
-                            } else {
-                                op_instance_of(r);
-                            }
-                        }
-                        oop::RefKind::Mirror(mirror) => {
-                            //run here codes:
-                            //$JDK_TEST/Appendable/Basic.java
-                            //Will eventually call java.security.Security.getSpiClass ("MessageDigest"):
-                            //Exception in thread "main" java.lang.ClassCastException: java.security.MessageDigestSpi cannot be cast to java.lang.Class
-
-                            let obj_cls = mirror.target.clone().unwrap();
-                            let target_name = target_cls.get_class().name.as_slice();
-                            let r = target_name == b"java/lang/Class"
-                                || cmp::instance_of(obj_cls.clone(), target_cls.clone());
-
-                            if is_cast {
-                                op_check_cast(r, obj_cls, target_cls);
-                            } else {
-                                op_instance_of(r);
-                            }
-                        }
-                        _ => unreachable!(),
-                    }
-                }
-            }
-            _ => unreachable!(),
+        let r = cmp::instance_of(obj_cls.clone(), target_cls.clone());
+        if !is_cast {
+            stack.push_int(r as i32);
+        } else if r {
+            stack.push_ref(obj_rf);
+        } else {
+            let msg = format!(
+                "class {} cannot be cast to class {}",
+                runtime::access::external_name(&obj_cls),
+                runtime::access::external_name(&target_cls)
+            );
+            exception::meet_ex(cls_const::J_CCE, Some(msg));
         }
     }
 }
 
 //handle exception
 impl<'a> Interp<'a> {
-    fn try_handle_exception(&self, ex: Oop) -> Result<(), Oop> {
-        let ex_cls = {
-            let rf = ex.extract_ref();
-            let inst = rf.extract_inst();
-            inst.class.clone()
+    /*
+    The handler of the exception thrown by the instruction at 'pc', JVMS 2.10:
+    the first entry of the exception table covering 'pc' & catching the
+    exception, 'any' (finally) catching all.
+
+    The catch types are resolved as CONSTANT_Class for the class of the method,
+    by its loader; the error of a resolution replaces the exception, which is
+    then looked for by the entries left. Err with the exception not caught,
+    to be thrown to the caller.
+    */
+    fn try_handle_exception(&self, pc: U2, mut ex: Oop) -> Result<(), Oop> {
+        let method = &self.frame.mir.method;
+        let table = match &method.code {
+            Some(code) => code.exceptions.as_slice(),
+            None => &[],
         };
 
-        let handler = {
-            let pc = self.frame.pc.load(Ordering::Relaxed);
-            self.frame
-                .mir
-                .method
-                .find_exception_handler(&self.frame.cp, pc as u16, ex_cls)
-        };
+        for e in table.iter().filter(|e| e.contains(pc)) {
+            let caught = e.is_finally()
+                || match runtime::resolve_class(e.catch_type, &self.frame.cp, &self.frame.class) {
+                    Ok(class) => {
+                        let rf = ex.extract_ref();
+                        let inst = rf.extract_inst();
+                        cmp::instance_of(inst.class.clone(), class)
+                    }
+                    Err(err) => {
+                        ex = err;
+                        false
+                    }
+                };
 
-        match handler {
-            Some(pc) => {
-                let mut stack = self.frame.area.stack.borrow_mut();
+            if caught {
+                let stack = &self.frame.area.stack;
                 stack.clear();
                 stack.push_ref(ex);
-                drop(stack);
-
-                let line_num = self.frame.mir.method.get_line_num(pc);
 
                 info!(
                     "Found Exception Handler: line={}, frame_id={}, {:?}",
-                    line_num, self.frame.frame_id, self.frame.mir.method
+                    method.get_line_num(e.handler_pc),
+                    self.frame.frame_id,
+                    method
                 );
 
-                self.goto_abs(pc as i32);
-                Ok(())
+                self.goto_abs(e.handler_pc as i32);
+                return Ok(());
             }
+        }
 
-            None => {
-                let pc = self.frame.pc.load(Ordering::Relaxed);
-                let line_num = self.frame.mir.method.get_line_num(pc as u16);
-
-                info!(
-                    "NotFound Exception Handler: line={}, frame_id={}, {:?}",
-                    line_num, self.frame.frame_id, self.frame.mir.method,
-                );
+        info!(
+            "NotFound Exception Handler: line={}, frame_id={}, {:?}",
+            method.get_line_num(pc),
+            self.frame.frame_id,
+            method,
+        );
 
-                Err(ex)
-            }
-        }
+        Err(ex)
     }
 }
 
@@ -692,99 +800,99 @@ impl<'a> Interp<'a> {
 
     #[inline]
     fn aconst_null(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         stack.push_null();
     }
 
     #[inline]
     fn iconst_m1(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
-        stack.push_const_m1();
+        let stack = &self.frame.area.stack;
+        stack.push_int(-1);
     }
 
     #[inline]
     fn iconst_0(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
-        stack.push_const0(false);
+        let stack = &self.frame.area.stack;
+        stack.push_int(0);
     }
 
     #[inline]
     fn lconst_0(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
-        stack.push_const0(true);
+        let stack = &self.frame.area.stack;
+        stack.push_long(0);
     }
 
     #[inline]
     fn fconst_0(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
-        stack.push_const0(false);
+        let stack = &self.frame.area.stack;
+        stack.push_float(0.0);
     }
 
     #[inline]
     fn dconst_0(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
-        stack.push_const0(true);
+        let stack = &self.frame.area.stack;
+        stack.push_double(0.0);
     }
 
     #[inline]
     fn iconst_1(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
-        stack.push_const1(false);
+        let stack = &self.frame.area.stack;
+        stack.push_int(1);
     }
 
     #[inline]
     fn lconst_1(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
-        stack.push_const1(true);
+        let stack = &self.frame.area.stack;
+        stack.push_long(1);
     }
 
     #[inline]
     fn fconst_1(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
-        stack.push_const1(false);
+        let stack = &self.frame.area.stack;
+        stack.push_float(1.0);
     }
 
     #[inline]
     fn dconst_1(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
-        stack.push_const1(true);
+        let stack = &self.frame.area.stack;
+        stack.push_double(1.0);
     }
 
     #[inline]
     fn iconst_2(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
-        stack.push_const2();
+        let stack = &self.frame.area.stack;
+        stack.push_int(2);
     }
 
     #[inline]
     fn fconst_2(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
-        stack.push_const2();
+        let stack = &self.frame.area.stack;
+        stack.push_float(2.0);
     }
 
     #[inline]
     fn iconst_3(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
-        stack.push_const3();
+        let stack = &self.frame.area.stack;
+        stack.push_int(3);
     }
 
     #[inline]
     fn iconst_4(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
-        stack.push_const4();
+        let stack = &self.frame.area.stack;
+        stack.push_int(4);
     }
 
     #[inline]
     fn iconst_5(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
-        stack.push_const5();
+        let stack = &self.frame.area.stack;
+        stack.push_int(5);
     }
 
     #[inline]
     fn sipush(&self) {
         let v = self.read_i2();
 
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         stack.push_int(v);
     }
 
@@ -792,7 +900,7 @@ impl<'a> Interp<'a> {
     fn bipush(&self) {
         let v = (self.read_byte() as i8) as i32;
 
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         stack.push_int(v);
     }
 
@@ -824,9 +932,9 @@ impl<'a> Interp<'a> {
             self.read_u1()
         };
 
-        let mut local = self.frame.area.local.borrow_mut();
+        let local = &self.frame.area.local;
         let v = local.get_int(pos);
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         stack.push_int(v);
     }
 
@@ -841,9 +949,9 @@ impl<'a> Interp<'a> {
             self.read_u1()
         };
 
-        let mut local = self.frame.area.local.borrow_mut();
+        let local = &self.frame.area.local;
         let v = local.get_long(pos);
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         stack.push_long(v);
     }
 
@@ -858,9 +966,9 @@ impl<'a> Interp<'a> {
             self.read_u1()
         };
 
-        let mut local = self.frame.area.local.borrow_mut();
+        let local = &self.frame.area.local;
         let v = local.get_float(pos);
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         stack.push_float(v);
     }
 
@@ -875,9 +983,9 @@ impl<'a> Interp<'a> {
             self.read_u1()
         };
 
-        let mut local = self.frame.area.local.borrow_mut();
+        let local = &self.frame.area.local;
         let v = local.get_double(pos);
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         stack.push_double(v);
     }
 
@@ -892,175 +1000,175 @@ impl<'a> Interp<'a> {
             self.read_u1()
         };
 
-        let mut local = self.frame.area.local.borrow_mut();
+        let local = &self.frame.area.local;
         let v = local.get_ref(pos);
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         stack.push_ref(v);
     }
 
     #[inline]
     fn iload_0(&self) {
-        let mut local = self.frame.area.local.borrow_mut();
+        let local = &self.frame.area.local;
         let v = local.get_int(0);
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         stack.push_int(v);
     }
 
     #[inline]
     fn lload_0(&self) {
-        let mut local = self.frame.area.local.borrow_mut();
+        let local = &self.frame.area.local;
         let v = local.get_long(0);
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         stack.push_long(v);
     }
 
     #[inline]
     fn fload_0(&self) {
-        let mut local = self.frame.area.local.borrow_mut();
+        let local = &self.frame.area.local;
         let v = local.get_float(0);
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         stack.push_float(v);
     }
 
     #[inline]
     fn dload_0(&self) {
-        let mut local = self.frame.area.local.borrow_mut();
+        let local = &self.frame.area.local;
         let v = local.get_double(0);
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         stack.push_double(v);
     }
 
     #[inline]
     fn aload_0(&self) {
-        let mut local = self.frame.area.local.borrow_mut();
+        let local = &self.frame.area.local;
         let v = local.get_ref(0);
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         stack.push_ref(v);
     }
 
     #[inline]
     fn iload_1(&self) {
-        let mut local = self.frame.area.local.borrow_mut();
+        let local = &self.frame.area.local;
         let v = local.get_int(1);
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         stack.push_int(v);
     }
 
     #[inline]
     fn lload_1(&self) {
-        let mut local = self.frame.area.local.borrow_mut();
+        let local = &self.frame.area.local;
         let v = local.get_long(1);
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         stack.push_long(v);
     }
 
     #[inline]
     fn fload_1(&self) {
-        let mut local = self.frame.area.local.borrow_mut();
+        let local = &self.frame.area.local;
         let v = local.get_float(1);
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         stack.push_float(v);
     }
 
     #[inline]
     fn dload_1(&self) {
-        let mut local = self.frame.area.local.borrow_mut();
+        let local = &self.frame.area.local;
         let v = local.get_double(1);
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         stack.push_double(v);
     }
 
     #[inline]
     fn aload_1(&self) {
-        let mut local = self.frame.area.local.borrow_mut();
+        let local = &self.frame.area.local;
         let v = local.get_ref(1);
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         stack.push_ref(v);
     }
 
     #[inline]
     fn iload_2(&self) {
-        let mut local = self.frame.area.local.borrow_mut();
+        let local = &self.frame.area.local;
         let v = local.get_int(2);
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         stack.push_int(v);
     }
 
     #[inline]
     fn lload_2(&self) {
-        let mut local = self.frame.area.local.borrow_mut();
+        let local = &self.frame.area.local;
         let v = local.get_long(2);
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         stack.push_long(v);
     }
 
     #[inline]
     fn fload_2(&self) {
-        let mut local = self.frame.area.local.borrow_mut();
+        let local = &self.frame.area.local;
         let v = local.get_float(2);
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         stack.push_float(v);
     }
 
     #[inline]
     fn dload_2(&self) {
-        let mut local = self.frame.area.local.borrow_mut();
+        let local = &self.frame.area.local;
         let v = local.get_double(2);
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         stack.push_double(v);
     }
 
     #[inline]
     fn aload_2(&self) {
-        let mut local = self.frame.area.local.borrow_mut();
+        let local = &self.frame.area.local;
         let v = local.get_ref(2);
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         stack.push_ref(v);
     }
 
     #[inline]
     fn iload_3(&self) {
-        let mut local = self.frame.area.local.borrow_mut();
+        let local = &self.frame.area.local;
         let v = local.get_int(3);
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         stack.push_int(v);
     }
 
     #[inline]
     fn lload_3(&self) {
-        let mut local = self.frame.area.local.borrow_mut();
+        let local = &self.frame.area.local;
         let v = local.get_long(3);
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         stack.push_long(v);
     }
 
     #[inline]
     fn fload_3(&self) {
-        let mut local = self.frame.area.local.borrow_mut();
+        let local = &self.frame.area.local;
         let v = local.get_float(3);
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         stack.push_float(v);
     }
 
     #[inline]
     fn dload_3(&self) {
-        let mut local = self.frame.area.local.borrow_mut();
+        let local = &self.frame.area.local;
         let v = local.get_double(3);
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         stack.push_double(v);
     }
 
     #[inline]
     fn aload_3(&self) {
-        let mut local = self.frame.area.local.borrow_mut();
+        let local = &self.frame.area.local;
         let v = local.get_ref(3);
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         stack.push_ref(v);
     }
 
     #[inline]
     fn iaload(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let pos = stack.pop_int();
         let rf = stack.pop_ref();
         match rf {
@@ -1076,7 +1184,7 @@ impl<'a> Interp<'a> {
 
     #[inline]
     fn saload(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let pos = stack.pop_int();
         let rf = stack.pop_ref();
         match rf {
@@ -1092,7 +1200,7 @@ impl<'a> Interp<'a> {
 
     #[inline]
     fn caload(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let pos = stack.pop_int();
         let rf = stack.pop_ref();
         match rf {
@@ -1108,7 +1216,7 @@ impl<'a> Interp<'a> {
 
     #[inline]
     fn baload(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let pos = stack.pop_int();
         let rf = stack.pop_ref();
         match rf {
@@ -1120,8 +1228,7 @@ impl<'a> Interp<'a> {
                     let len = ary.len();
 
                     if (pos < 0) || (pos as usize >= len) {
-                        let msg = format!("length is {}, but index is {}", len, pos);
-                        exception::meet_ex(cls_const::J_ARRAY_INDEX_OUT_OF_BOUNDS, Some(msg));
+                        exception::meet_index_out_of_bounds(pos, len);
                     } else {
                         match ary {
                             TypeArrayDesc::Byte(ary) => {
@@ -1143,7 +1250,7 @@ impl<'a> Interp<'a> {
 
     #[inline]
     fn laload(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let pos = stack.pop_int();
         let rf = stack.pop_ref();
         match rf {
@@ -1153,8 +1260,7 @@ impl<'a> Interp<'a> {
                 let ary = ary.extract_longs();
                 let len = ary.len();
                 if (pos < 0) || (pos as usize >= len) {
-                    let msg = format!("length is {}, but index is {}", len, pos);
-                    exception::meet_ex(cls_const::J_ARRAY_INDEX_OUT_OF_BOUNDS, Some(msg));
+                    exception::meet_index_out_of_bounds(pos, len);
                 } else {
                     let v = ary[pos as usize];
                     stack.push_long(v);
@@ -1166,7 +1272,7 @@ impl<'a> Interp<'a> {
 
     #[inline]
     fn faload(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let pos = stack.pop_int();
         let rf = stack.pop_ref();
         match rf {
@@ -1176,8 +1282,7 @@ impl<'a> Interp<'a> {
                 let ary = ary.extract_floats();
                 let len = ary.len();
                 if (pos < 0) || (pos as usize >= len) {
-                    let msg = format!("length is {}, but index is {}", len, pos);
-                    exception::meet_ex(cls_const::J_ARRAY_INDEX_OUT_OF_BOUNDS, Some(msg));
+                    exception::meet_index_out_of_bounds(pos, len);
                 } else {
                     let v = ary[pos as usize];
                     stack.push_float(v);
@@ -1189,7 +1294,7 @@ impl<'a> Interp<'a> {
 
     #[inline]
     fn daload(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let pos = stack.pop_int();
         let rf = stack.pop_ref();
         match rf {
@@ -1199,8 +1304,7 @@ impl<'a> Interp<'a> {
                 let ary = ary.extract_doubles();
                 let len = ary.len();
                 if (pos < 0) || (pos as usize >= len) {
-                    let msg = format!("length is {}, but index is {}", len, pos);
-                    exception::meet_ex(cls_const::J_ARRAY_INDEX_OUT_OF_BOUNDS, Some(msg));
+                    exception::meet_index_out_of_bounds(pos, len);
                 } else {
                     let v = ary[pos as usize];
                     stack.push_double(v);
@@ -1212,7 +1316,7 @@ impl<'a> Interp<'a> {
 
     #[inline]
     fn aaload(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let pos = stack.pop_int();
         let rf = stack.pop_ref();
         match rf {
@@ -1223,8 +1327,7 @@ impl<'a> Interp<'a> {
                 let len = ary.len();
 
                 if (pos < 0) || (pos as usize >= len) {
-                    let msg = format!("length is {}, but index is {}", len, pos);
-                    exception::meet_ex(cls_const::J_ARRAY_INDEX_OUT_OF_BOUNDS, Some(msg));
+                    exception::meet_index_out_of_bounds(pos, len);
                 } else {
                     let v = ary[pos as usize].clone();
                     stack.push_ref(v);
@@ -1245,9 +1348,9 @@ impl<'a> Interp<'a> {
             self.read_u1()
         };
 
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v = stack.pop_int();
-        let mut local = self.frame.area.local.borrow_mut();
+        let local = &self.frame.area.local;
         local.set_int(pos, v);
     }
 
@@ -1262,9 +1365,9 @@ impl<'a> Interp<'a> {
             self.read_u1()
         };
 
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v = stack.pop_long();
-        let mut local = self.frame.area.local.borrow_mut();
+        let local = &self.frame.area.local;
         local.set_long(pos, v);
     }
 
@@ -1279,9 +1382,9 @@ impl<'a> Interp<'a> {
             self.read_u1()
         };
 
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v = stack.pop_float();
-        let mut local = self.frame.area.local.borrow_mut();
+        let local = &self.frame.area.local;
         local.set_float(pos, v);
     }
 
@@ -1296,9 +1399,9 @@ impl<'a> Interp<'a> {
             self.read_u1()
         };
 
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v = stack.pop_double();
-        let mut local = self.frame.area.local.borrow_mut();
+        let local = &self.frame.area.local;
         local.set_double(pos, v);
     }
 
@@ -1313,179 +1416,178 @@ impl<'a> Interp<'a> {
             self.read_u1()
         };
 
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v = stack.pop_ref();
-        let mut local = self.frame.area.local.borrow_mut();
+        let local = &self.frame.area.local;
         local.set_ref(pos, v);
     }
 
     #[inline]
     fn istore_0(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v = stack.pop_int();
-        let mut local = self.frame.area.local.borrow_mut();
+        let local = &self.frame.area.local;
         local.set_int(0, v);
     }
 
     #[inline]
     fn istore_1(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v = stack.pop_int();
-        let mut local = self.frame.area.local.borrow_mut();
+        let local = &self.frame.area.local;
         local.set_int(1, v);
     }
 
     #[inline]
     fn istore_2(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v = stack.pop_int();
-        let mut local = self.frame.area.local.borrow_mut();
+        let local = &self.frame.area.local;
         local.set_int(2, v);
     }
 
     #[inline]
     fn istore_3(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v = stack.pop_int();
-        let mut local = self.frame.area.local.borrow_mut();
+        let local = &self.frame.area.local;
         local.set_int(3, v);
     }
 
     #[inline]
     fn lstore_0(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v = stack.pop_long();
-        let mut local = self.frame.area.local.borrow_mut();
+        let local = &self.frame.area.local;
         local.set_long(0, v);
     }
 
     #[inline]
     fn lstore_1(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v = stack.pop_long();
-        let mut local = self.frame.area.local.borrow_mut();
+        let local = &self.frame.area.local;
         local.set_long(1, v);
     }
 
     #[inline]
     fn lstore_2(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v = stack.pop_long();
-        let mut local = self.frame.area.local.borrow_mut();
+        let local = &self.frame.area.local;
         local.set_long(2, v);
     }
 
     #[inline]
     fn lstore_3(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v = stack.pop_long();
-        let mut local = self.frame.area.local.borrow_mut();
+        let local = &self.frame.area.local;
         local.set_long(3, v);
     }
 
     #[inline]
     fn fstore_0(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v = stack.pop_float();
-        let mut local = self.frame.area.local.borrow_mut();
+        let local = &self.frame.area.local;
         local.set_float(0, v);
     }
 
     #[inline]
     fn fstore_1(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v = stack.pop_float();
-        let mut local = self.frame.area.local.borrow_mut();
+        let local = &self.frame.area.local;
         local.set_float(1, v);
     }
 
     #[inline]
     fn fstore_2(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v = stack.pop_float();
-        let mut local = self.frame.area.local.borrow_mut();
+        let local = &self.frame.area.local;
         local.set_float(2, v);
     }
 
     #[inline]
     fn fstore_3(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v = stack.pop_float();
-        let mut local = self.frame.area.local.borrow_mut();
+        let local = &self.frame.area.local;
         local.set_float(3, v);
     }
 
     #[inline]
     fn dstore_0(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v = stack.pop_double();
-        let mut local = self.frame.area.local.borrow_mut();
+        let local = &self.frame.area.local;
         local.set_double(0, v);
     }
 
     #[inline]
     fn dstore_1(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v = stack.pop_double();
-        let mut local = self.frame.area.local.borrow_mut();
+        let local = &self.frame.area.local;
         local.set_double(1, v);
     }
 
     #[inline]
     fn dstore_2(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v = stack.pop_double();
-        let mut local = self.frame.area.local.borrow_mut();
+        let local = &self.frame.area.local;
         local.set_double(2, v);
     }
 
     #[inline]
     fn dstore_3(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v = stack.pop_double();
-        let mut local = self.frame.area.local.borrow_mut();
+        let local = &self.frame.area.local;
         local.set_double(3, v);
     }
 
     #[inline]
     fn astore_0(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v = stack.pop_ref();
-        let mut local = self.frame.area.local.borrow_mut();
+        let local = &self.frame.area.local;
         local.set_ref(0, v);
     }
 
     #[inline]
     fn astore_1(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v = stack.pop_ref();
-        let mut local = self.frame.area.local.borrow_mut();
+        let local = &self.frame.area.local;
         local.set_ref(1, v);
     }
 
     #[inline]
     fn astore_2(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v = stack.pop_ref();
-        let mut local = self.frame.area.local.borrow_mut();
+        let local = &self.frame.area.local;
         local.set_ref(2, v);
     }
 
     #[inline]
     fn astore_3(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v = stack.pop_ref();
-        let mut local = self.frame.area.local.borrow_mut();
+        let local = &self.frame.area.local;
         local.set_ref(3, v);
     }
 
     #[inline]
     fn bastore(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v = stack.pop_int();
         let pos = stack.pop_int();
         let rf = stack.pop_ref();
-        drop(stack);
 
         match rf {
             Oop::Null => exception::meet_ex(cls_const::J_NPE, None),
@@ -1512,11 +1614,10 @@ impl<'a> Interp<'a> {
 
     #[inline]
     fn castore(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v = stack.pop_int();
         let pos = stack.pop_int();
         let rf = stack.pop_ref();
-        drop(stack);
 
         match rf {
             Oop::Null => exception::meet_ex(cls_const::J_NPE, None),
@@ -1532,11 +1633,10 @@ impl<'a> Interp<'a> {
 
     #[inline]
     fn sastore(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v = stack.pop_int();
         let pos = stack.pop_int();
         let rf = stack.pop_ref();
-        drop(stack);
 
         match rf {
             Oop::Null => exception::meet_ex(cls_const::J_NPE, None),
@@ -1552,11 +1652,10 @@ impl<'a> Interp<'a> {
 
     #[inline]
     fn iastore(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v = stack.pop_int();
         let pos = stack.pop_int();
         let rf = stack.pop_ref();
-        drop(stack);
 
         match rf {
             Oop::Null => exception::meet_ex(cls_const::J_NPE, None),
@@ -1571,11 +1670,10 @@ impl<'a> Interp<'a> {
 
     #[inline]
     fn lastore(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v = stack.pop_long();
         let pos = stack.pop_int();
         let rf = stack.pop_ref();
-        drop(stack);
 
         match rf {
             Oop::Null => exception::meet_ex(cls_const::J_NPE, None),
@@ -1590,11 +1688,10 @@ impl<'a> Interp<'a> {
 
     #[inline]
     fn fastore(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v = stack.pop_float();
         let pos = stack.pop_int();
         let rf = stack.pop_ref();
-        drop(stack);
 
         match rf {
             Oop::Null => exception::meet_ex(cls_const::J_NPE, None),
@@ -1609,11 +1706,10 @@ impl<'a> Interp<'a> {
 
     #[inline]
     fn dastore(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v = stack.pop_double();
         let pos = stack.pop_int();
         let rf = stack.pop_ref();
-        drop(stack);
 
         match rf {
             Oop::Null => exception::meet_ex(cls_const::J_NPE, None),
@@ -1628,18 +1724,31 @@ impl<'a> Interp<'a> {
 
     #[inline]
     fn aastore(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v = stack.pop_ref();
         let pos = stack.pop_int();
         let ary_rf = stack.pop_ref();
-        drop(stack);
 
         match ary_rf {
             Oop::Null => exception::meet_ex(cls_const::J_NPE, None),
             Oop::Ref(rf) => {
                 let ary = rf.extract_mut_array();
-                let ary = &mut ary.elements;
-                array_store!(ary, pos, v);
+                let len = ary.elements.len();
+                if pos < 0 || pos as usize >= len {
+                    exception::meet_index_out_of_bounds(pos, len);
+                    return;
+                }
+
+                //null is stored into any, else assignable to the component
+                if let Some(v_cls) = util::oop::get_oop_class(&v) {
+                    let component = ary.class.get_class().get_array_component().unwrap();
+                    if !cmp::instance_of(v_cls.clone(), component) {
+                        let msg = runtime::access::external_name(&v_cls);
+                        exception::meet_ex(cls_const::J_ASE, Some(msg));
+                        return;
+                    }
+                }
+                ary.elements[pos as usize] = v;
             }
             _ => unreachable!(),
         }
@@ -1647,62 +1756,62 @@ impl<'a> Interp<'a> {
 
     #[inline]
     fn pop(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         stack.drop_top();
     }
 
     #[inline]
     fn pop2(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         stack.drop_top();
         stack.drop_top();
     }
 
     #[inline]
     fn dup(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         stack.dup();
     }
 
     #[inline]
     fn dup_x1(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         stack.dup_x1();
     }
 
     #[inline]
     fn dup_x2(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         stack.dup_x2();
     }
 
     #[inline]
     fn dup2(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         stack.dup2();
     }
 
     #[inline]
     fn dup2_x1(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         stack.dup2_x1();
     }
 
     #[inline]
     fn dup2_x2(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         stack.dup2_x2();
     }
 
     #[inline]
     fn swap(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         stack.swap();
     }
 
     #[inline]
     fn iadd(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v2 = stack.pop_int();
         let v1 = stack.pop_int();
         let v = v1.wrapping_add(v2);
@@ -1711,7 +1820,7 @@ impl<'a> Interp<'a> {
 
     #[inline]
     fn ladd(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v2 = stack.pop_long();
         let v1 = stack.pop_long();
         let v = v1.wrapping_add(v2);
@@ -1720,7 +1829,7 @@ impl<'a> Interp<'a> {
 
     #[inline]
     fn fadd(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v2 = stack.pop_float();
         let v1 = stack.pop_float();
         stack.push_float(v1 + v2);
@@ -1728,7 +1837,7 @@ impl<'a> Interp<'a> {
 
     #[inline]
     fn dadd(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v2 = stack.pop_double();
         let v1 = stack.pop_double();
         stack.push_double(v1 + v2);
@@ -1736,7 +1845,7 @@ impl<'a> Interp<'a> {
 
     #[inline]
     fn isub(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v2 = stack.pop_int();
         let v1 = stack.pop_int();
         let v = v1.wrapping_sub(v2);
@@ -1745,7 +1854,7 @@ impl<'a> Interp<'a> {
 
     #[inline]
     fn lsub(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v2 = stack.pop_long();
         let v1 = stack.pop_long();
         let v = v1.wrapping_sub(v2);
@@ -1754,7 +1863,7 @@ impl<'a> Interp<'a> {
 
     #[inline]
     fn fsub(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v2 = stack.pop_float();
         let v1 = stack.pop_float();
         stack.push_float(v1 - v2);
@@ -1762,7 +1871,7 @@ impl<'a> Interp<'a> {
 
     #[inline]
     fn dsub(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v2 = stack.pop_double();
         let v1 = stack.pop_double();
         stack.push_double(v1 - v2);
@@ -1770,7 +1879,7 @@ impl<'a> Interp<'a> {
 
     #[inline]
     fn imul(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v2 = stack.pop_int();
         let v1 = stack.pop_int();
         let v = v1.wrapping_mul(v2);
@@ -1779,7 +1888,7 @@ impl<'a> Interp<'a> {
 
     #[inline]
     fn lmul(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v2 = stack.pop_long();
         let v1 = stack.pop_long();
         let v = v1.wrapping_mul(v2);
@@ -1788,7 +1897,7 @@ impl<'a> Interp<'a> {
 
     #[inline]
     fn fmul(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v2 = stack.pop_float();
         let v1 = stack.pop_float();
         stack.push_float(v1 * v2);
@@ -1796,7 +1905,7 @@ impl<'a> Interp<'a> {
 
     #[inline]
     fn dmul(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v2 = stack.pop_double();
         let v1 = stack.pop_double();
         stack.push_double(v1 * v2);
@@ -1804,143 +1913,116 @@ impl<'a> Interp<'a> {
 
     #[inline]
     fn idiv(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v2 = stack.pop_int();
         let v1 = stack.pop_int();
         if v2 == 0 {
-            drop(stack);
-
-            exception::meet_ex(
-                cls_const::J_ARITHMETIC_EX,
-                Some("divide by zero".to_string()),
-            );
+            exception::meet_ex(cls_const::J_ARITHMETIC_EX, Some("/ by zero".to_string()));
         } else {
-            stack.push_int(v1 / v2);
+            stack.push_int(v1.wrapping_div(v2));
         }
     }
 
     #[inline]
     fn ldiv(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v2 = stack.pop_long();
         let v1 = stack.pop_long();
         if v2 == 0 {
-            drop(stack);
-
-            exception::meet_ex(
-                cls_const::J_ARITHMETIC_EX,
-                Some("divide by zero".to_string()),
-            );
+            exception::meet_ex(cls_const::J_ARITHMETIC_EX, Some("/ by zero".to_string()));
         } else {
-            stack.push_long(v1 / v2);
+            stack.push_long(v1.wrapping_div(v2));
         }
     }
 
     #[inline]
     fn fdiv(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v2 = stack.pop_float();
         let v1 = stack.pop_float();
-        if v2 == 0.0 {
-            drop(stack);
-
-            exception::meet_ex(
-                cls_const::J_ARITHMETIC_EX,
-                Some("divide by zero".to_string()),
-            );
-        } else {
-            stack.push_float(v1 / v2);
-        }
+        stack.push_float(v1 / v2);
     }
 
     #[inline]
     fn ddiv(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v2 = stack.pop_double();
         let v1 = stack.pop_double();
-        if v2 == 0.0 {
-            drop(stack);
-
-            exception::meet_ex(
-                cls_const::J_ARITHMETIC_EX,
-                Some("divide by zero".to_string()),
-            );
-        } else {
-            stack.push_double(v1 / v2);
-        }
+        stack.push_double(v1 / v2);
     }
 
     #[inline]
     fn irem(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v2 = stack.pop_int();
         let v1 = stack.pop_int();
         if v2 == 0 {
-            drop(stack);
-
-            exception::meet_ex(
-                cls_const::J_ARITHMETIC_EX,
-                Some("divide by zero".to_string()),
-            );
+            exception::meet_ex(cls_const::J_ARITHMETIC_EX, Some("/ by zero".to_string()));
         } else {
-            stack.push_int(v1 - (v1 / v2) * v2);
+            stack.push_int(v1.wrapping_rem(v2));
         }
     }
 
     #[inline]
     fn lrem(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v2 = stack.pop_long();
         let v1 = stack.pop_long();
         if v2 == 0 {
-            drop(stack);
-
-            exception::meet_ex(
-                cls_const::J_ARITHMETIC_EX,
-                Some("divide by zero".to_string()),
-            );
+            exception::meet_ex(cls_const::J_ARITHMETIC_EX, Some("/ by zero".to_string()));
         } else {
-            stack.push_long(v1 - (v1 / v2) * v2);
+            stack.push_long(v1.wrapping_rem(v2));
         }
     }
 
     #[inline]
     fn frem(&self) {
-        panic!("Use of deprecated instruction frem, please check your Java compiler");
+        //as fmod, the sign of the dividend
+        let stack = &self.frame.area.stack;
+        let v2 = stack.pop_float();
+        let v1 = stack.pop_float();
+        stack.push_float(v1 % v2);
     }
 
     #[inline]
     fn drem(&self) {
-        panic!("Use of deprecated instruction drem, please check your Java compiler");
+        let stack = &self.frame.area.stack;
+        let v2 = stack.pop_double();
+        let v1 = stack.pop_double();
+        stack.push_double(v1 % v2);
     }
 
     #[inline]
     fn ineg(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v = stack.pop_int();
-        stack.push_int(-v);
+        stack.push_int(v.wrapping_neg());
     }
 
     #[inline]
     fn lneg(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v = stack.pop_long();
-        stack.push_long(-v);
+        stack.push_long(v.wrapping_neg());
     }
 
     #[inline]
     fn fneg(&self) {
-        panic!("Use of deprecated instruction fneg, please check your Java compiler");
+        let stack = &self.frame.area.stack;
+        let v = stack.pop_float();
+        stack.push_float(-v);
     }
 
     #[inline]
     fn dneg(&self) {
-        panic!("Use of deprecated instruction dneg, please check your Java compiler");
+        let stack = &self.frame.area.stack;
+        let v = stack.pop_double();
+        stack.push_double(-v);
     }
 
     #[inline]
     fn ishl(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v2 = stack.pop_int();
         let v1 = stack.pop_int();
         let s = v2 & 0x1F;
@@ -1949,7 +2031,7 @@ impl<'a> Interp<'a> {
 
     #[inline]
     fn lshl(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v2 = stack.pop_int();
         let v1 = stack.pop_long();
         let s = (v2 & 0x3F) as i64;
@@ -1958,7 +2040,7 @@ impl<'a> Interp<'a> {
 
     #[inline]
     fn ishr(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v2 = stack.pop_int();
         let v1 = stack.pop_int();
         let s = v2 & 0x1F;
@@ -1967,7 +2049,7 @@ impl<'a> Interp<'a> {
 
     #[inline]
     fn lshr(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v2 = stack.pop_int();
         let v1 = stack.pop_long();
         let s = (v2 & 0x3F) as i64;
@@ -1976,7 +2058,7 @@ impl<'a> Interp<'a> {
 
     #[inline]
     fn iushr(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v2 = stack.pop_int();
         let v1 = stack.pop_int() as u32;
         let s = (v2 & 0x1F) as u32;
@@ -1985,7 +2067,7 @@ impl<'a> Interp<'a> {
 
     #[inline]
     fn lushr(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v2 = stack.pop_int();
         let v1 = stack.pop_long() as u64;
         let s = (v2 & 0x3F) as u64;
@@ -1994,7 +2076,7 @@ impl<'a> Interp<'a> {
 
     #[inline]
     fn iand(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v2 = stack.pop_int();
         let v1 = stack.pop_int();
         stack.push_int(v1 & v2);
@@ -2002,7 +2084,7 @@ impl<'a> Interp<'a> {
 
     #[inline]
     fn land(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v2 = stack.pop_long();
         let v1 = stack.pop_long();
         stack.push_long(v1 & v2);
@@ -2010,7 +2092,7 @@ impl<'a> Interp<'a> {
 
     #[inline]
     fn ior(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v2 = stack.pop_int();
         let v1 = stack.pop_int();
         stack.push_int(v1 | v2);
@@ -2018,7 +2100,7 @@ impl<'a> Interp<'a> {
 
     #[inline]
     fn lor(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v2 = stack.pop_long();
         let v1 = stack.pop_long();
         stack.push_long(v1 | v2);
@@ -2026,7 +2108,7 @@ impl<'a> Interp<'a> {
 
     #[inline]
     fn ixor(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v2 = stack.pop_int();
         let v1 = stack.pop_int();
         stack.push_int(v1 ^ v2);
@@ -2034,7 +2116,7 @@ impl<'a> Interp<'a> {
 
     #[inline]
     fn lxor(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v2 = stack.pop_long();
         let v1 = stack.pop_long();
         stack.push_long(v1 ^ v2);
@@ -2057,7 +2139,7 @@ impl<'a> Interp<'a> {
             (self.read_byte() as i8) as i32
         };
 
-        let mut local = self.frame.area.local.borrow_mut();
+        let local = &self.frame.area.local;
         let v = local.get_int(pos);
         let v = v.wrapping_add(factor);
         local.set_int(pos, v);
@@ -2065,49 +2147,49 @@ impl<'a> Interp<'a> {
 
     #[inline]
     fn i2l(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v = stack.pop_int();
         stack.push_long(v as i64);
     }
 
     #[inline]
     fn i2f(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v = stack.pop_int();
         stack.push_float(v as f32);
     }
 
     #[inline]
     fn i2d(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v = stack.pop_int();
         stack.push_double(v as f64);
     }
 
     #[inline]
     fn l2i(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v = stack.pop_long();
         stack.push_int(v as i32);
     }
 
     #[inline]
     fn l2f(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v = stack.pop_long();
         stack.push_float(v as f32);
     }
 
     #[inline]
     fn l2d(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v = stack.pop_long();
         stack.push_double(v as f64);
     }
 
     #[inline]
     fn f2i(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v = stack.pop_float();
         if v.is_nan() {
             stack.push_int(0);
@@ -2124,7 +2206,7 @@ impl<'a> Interp<'a> {
 
     #[inline]
     fn f2l(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v = stack.pop_float();
         if v.is_nan() {
             stack.push_long(0);
@@ -2141,14 +2223,14 @@ impl<'a> Interp<'a> {
 
     #[inline]
     fn f2d(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v = stack.pop_float();
         stack.push_double(v as f64);
     }
 
     #[inline]
     fn d2i(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v = stack.pop_double();
         if v.is_nan() {
             stack.push_int(0);
@@ -2165,7 +2247,7 @@ impl<'a> Interp<'a> {
 
     #[inline]
     fn d2l(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v = stack.pop_double();
         if v.is_nan() {
             stack.push_long(0);
@@ -2182,14 +2264,14 @@ impl<'a> Interp<'a> {
 
     #[inline]
     fn d2f(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v = stack.pop_double();
         stack.push_float(v as f32);
     }
 
     #[inline]
     fn i2b(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v = stack.pop_int();
         let v = v as i8;
         stack.push_int(v as i32);
@@ -2197,7 +2279,7 @@ impl<'a> Interp<'a> {
 
     #[inline]
     fn i2c(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v = stack.pop_int();
         let v = v as u16;
         stack.push_int(v as i32);
@@ -2205,7 +2287,7 @@ impl<'a> Interp<'a> {
 
     #[inline]
     fn i2s(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v = stack.pop_int();
         let v = v as i16;
         stack.push_int(v as i32);
@@ -2213,7 +2295,7 @@ impl<'a> Interp<'a> {
 
     #[inline]
     fn lcmp(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v1 = stack.pop_long();
         let v2 = stack.pop_long();
         let v = match v1.cmp(&v2) {
@@ -2227,7 +2309,7 @@ impl<'a> Interp<'a> {
 
     #[inline]
     fn fcmpl(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v1 = stack.pop_float();
         let v2 = stack.pop_float();
         let v = if v1.is_nan() || v2.is_nan() {
@@ -2245,7 +2327,7 @@ impl<'a> Interp<'a> {
 
     #[inline]
     fn fcmpg(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v1 = stack.pop_float();
         let v2 = stack.pop_float();
         let v = if v1.is_nan() || v2.is_nan() {
@@ -2263,7 +2345,7 @@ impl<'a> Interp<'a> {
 
     #[inline]
     fn dcmpl(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v1 = stack.pop_double();
         let v2 = stack.pop_double();
         let v = if v1.is_nan() || v2.is_nan() {
@@ -2281,7 +2363,7 @@ impl<'a> Interp<'a> {
 
     #[inline]
     fn dcmpg(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v1 = stack.pop_double();
         let v2 = stack.pop_double();
         if v1.is_nan() || v2.is_nan() {
@@ -2297,11 +2379,10 @@ impl<'a> Interp<'a> {
 
     #[inline]
     fn ifeq(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v = stack.pop_int();
 
         if v == 0 {
-            drop(stack);
             self.goto_by_offset_hardcoded(2);
         } else {
             let _ = self.frame.pc.fetch_add(2, Ordering::Relaxed);
@@ -2310,11 +2391,10 @@ impl<'a> Interp<'a> {
 
     #[inline]
     fn ifne(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v = stack.pop_int();
 
         if v != 0 {
-            drop(stack);
             self.goto_by_offset_hardcoded(2);
         } else {
             let _ = self.frame.pc.fetch_add(2, Ordering::Relaxed);
@@ -2323,11 +2403,10 @@ impl<'a> Interp<'a> {
 
     #[inline]
     fn iflt(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v = stack.pop_int();
 
         if v < 0 {
-            drop(stack);
             self.goto_by_offset_hardcoded(2);
         } else {
             let _ = self.frame.pc.fetch_add(2, Ordering::Relaxed);
@@ -2336,11 +2415,10 @@ impl<'a> Interp<'a> {
 
     #[inline]
     fn ifge(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v = stack.pop_int();
 
         if v >= 0 {
-            drop(stack);
             self.goto_by_offset_hardcoded(2);
         } else {
             let _ = self.frame.pc.fetch_add(2, Ordering::Relaxed);
@@ -2349,11 +2427,10 @@ impl<'a> Interp<'a> {
 
     #[inline]
     fn ifgt(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v = stack.pop_int();
 
         if v > 0 {
-            drop(stack);
             self.goto_by_offset_hardcoded(2);
         } else {
             let _ = self.frame.pc.fetch_add(2, Ordering::Relaxed);
@@ -2362,11 +2439,10 @@ impl<'a> Interp<'a> {
 
     #[inline]
     fn ifle(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v = stack.pop_int();
 
         if v <= 0 {
-            drop(stack);
             self.goto_by_offset_hardcoded(2);
         } else {
             let _ = self.frame.pc.fetch_add(2, Ordering::Relaxed);
@@ -2375,12 +2451,11 @@ impl<'a> Interp<'a> {
 
     #[inline]
     fn if_icmpeq(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v2 = stack.pop_int();
         let v1 = stack.pop_int();
 
         if v1 == v2 {
-            drop(stack);
             self.goto_by_offset_hardcoded(2);
         } else {
             let _ = self.frame.pc.fetch_add(2, Ordering::Relaxed);
@@ -2389,12 +2464,11 @@ impl<'a> Interp<'a> {
 
     #[inline]
     fn if_icmpne(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v2 = stack.pop_int();
         let v1 = stack.pop_int();
 
         if v1 != v2 {
-            drop(stack);
             self.goto_by_offset_hardcoded(2);
         } else {
             let _ = self.frame.pc.fetch_add(2, Ordering::Relaxed);
@@ -2403,12 +2477,11 @@ impl<'a> Interp<'a> {
 
     #[inline]
     fn if_icmplt(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v2 = stack.pop_int();
         let v1 = stack.pop_int();
 
         if v1 < v2 {
-            drop(stack);
             self.goto_by_offset_hardcoded(2);
         } else {
             let _ = self.frame.pc.fetch_add(2, Ordering::Relaxed);
@@ -2417,12 +2490,11 @@ impl<'a> Interp<'a> {
 
     #[inline]
     fn if_icmpge(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v2 = stack.pop_int();
         let v1 = stack.pop_int();
 
         if v1 >= v2 {
-            drop(stack);
             self.goto_by_offset_hardcoded(2);
         } else {
             let _ = self.frame.pc.fetch_add(2, Ordering::Relaxed);
@@ -2431,12 +2503,11 @@ impl<'a> Interp<'a> {
 
     #[inline]
     fn if_icmpgt(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v2 = stack.pop_int();
         let v1 = stack.pop_int();
 
         if v1 > v2 {
-            drop(stack);
             self.goto_by_offset_hardcoded(2);
         } else {
             let _ = self.frame.pc.fetch_add(2, Ordering::Relaxed);
@@ -2445,12 +2516,11 @@ impl<'a> Interp<'a> {
 
     #[inline]
     fn if_icmple(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v2 = stack.pop_int();
         let v1 = stack.pop_int();
 
         if v1 <= v2 {
-            drop(stack);
             self.goto_by_offset_hardcoded(2);
         } else {
             let _ = self.frame.pc.fetch_add(2, Ordering::Relaxed);
@@ -2459,12 +2529,11 @@ impl<'a> Interp<'a> {
 
     #[inline]
     fn if_acmpeq(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v2 = stack.pop_ref();
         let v1 = stack.pop_ref();
 
         if OopRef::is_eq(&v1, &v2) {
-            drop(stack);
             self.goto_by_offset_hardcoded(2);
         } else {
             let _ = self.frame.pc.fetch_add(2, Ordering::Relaxed);
@@ -2473,12 +2542,11 @@ impl<'a> Interp<'a> {
 
     #[inline]
     fn if_acmpne(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v2 = stack.pop_ref();
         let v1 = stack.pop_ref();
 
         if !OopRef::is_eq(&v1, &v2) {
-            drop(stack);
             self.goto_by_offset_hardcoded(2);
         } else {
             let _ = self.frame.pc.fetch_add(2, Ordering::Relaxed);
@@ -2563,7 +2631,7 @@ impl<'a> Interp<'a> {
         jump_table.push(default_byte + origin_bc);
 
         let top_value = {
-            let mut stack = self.frame.area.stack.borrow_mut();
+            let stack = &self.frame.area.stack;
             stack.pop_int()
         };
         if (top_value > (jump_table.len() as i32 - 1 + low_byte)) || top_value < low_byte {
@@ -2625,7 +2693,7 @@ impl<'a> Interp<'a> {
         }
 
         let top_value = {
-            let mut stack = self.frame.area.stack.borrow_mut();
+            let stack = &self.frame.area.stack;
             stack.pop_int()
         };
         match jump_table.get(&(top_value as u32)) {
@@ -2636,49 +2704,44 @@ impl<'a> Interp<'a> {
 
     #[inline]
     fn ireturn(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v = stack.pop_int();
         let v = Oop::new_int(v);
-        drop(stack);
 
         self.set_return(Some(v));
     }
 
     #[inline]
     fn lreturn(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v = stack.pop_long();
         let v = Oop::new_long(v);
-        drop(stack);
 
         self.set_return(Some(v));
     }
 
     #[inline]
     fn freturn(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v = stack.pop_float();
         let v = Oop::new_float(v);
-        drop(stack);
 
         self.set_return(Some(v));
     }
 
     #[inline]
     fn dreturn(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v = stack.pop_double();
         let v = Oop::new_double(v);
-        drop(stack);
 
         self.set_return(Some(v));
     }
 
     #[inline]
     fn areturn(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v = stack.pop_ref();
-        drop(stack);
 
         self.set_return(Some(v));
     }
@@ -2690,60 +2753,67 @@ impl<'a> Interp<'a> {
 
     #[inline]
     fn get_static(&self) {
+        let pc = self.opcode_pc();
         let cp_idx = self.read_u2();
-        self.get_field_helper(oop_consts::get_null(), cp_idx, true);
+        self.get_field_helper(oop_consts::get_null(), pc, cp_idx, true);
     }
 
     #[inline]
     fn put_static(&self) {
+        let pc = self.opcode_pc();
         let cp_idx = self.read_u2();
-        self.put_field_helper(cp_idx, true);
+        self.put_field_helper(pc, cp_idx, true);
     }
 
     #[inline]
     fn get_field(&self) {
+        let pc = self.opcode_pc();
         let idx = self.read_u2();
 
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let rf = stack.pop_ref();
-        drop(stack);
 
         match rf {
             Oop::Null => {
                 exception::meet_ex(cls_const::J_NPE, None);
             }
             _ => {
-                self.get_field_helper(rf, idx, false);
+                self.get_field_helper(rf, pc, idx, false);
             }
         }
     }
 
     #[inline]
     fn put_field(&self) {
+        let pc = self.opcode_pc();
         let idx = self.read_u2();
-        self.put_field_helper(idx, false);
+        self.put_field_helper(pc, idx, false);
     }
 
     #[inline]
     fn invoke_virtual(&self) {
+        let pc = self.opcode_pc();
         let idx = self.read_u2();
-        self.invoke_helper(false, idx, false);
+        self.invoke_helper(pc, false, idx, false);
     }
 
     #[inline]
     fn invoke_special(&self) {
+        let pc = self.opcode_pc();
         let idx = self.read_u2();
-        self.invoke_helper(false, idx, true);
+        self.invoke_helper(pc, false, idx, true);
     }
 
     #[inline]
     fn invoke_static(&self) {
+        let pc = self.opcode_pc();
         let idx = self.read_u2();
-        self.invoke_helper(true, idx, true);
+        self.invoke_helper(pc, true, idx, true);
     }
 
     #[inline]
     fn invoke_interface(&self) {
+        let pc = self.opcode_pc();
         let cp_idx = self.read_u2();
         let _count = self.read_u1();
         let zero = self.read_u1();
@@ -2752,7 +2822,7 @@ impl<'a> Interp<'a> {
             warn!("interpreter: invalid invokeinterface: the value of the fourth operand byte must always be zero.");
         }
 
-        self.invoke_helper(false, cp_idx, false);
+        self.invoke_helper(pc, false, cp_idx, false);
     }
 
     #[inline]
@@ -2763,35 +2833,35 @@ impl<'a> Interp<'a> {
 
     #[inline]
     fn new_(&self) {
+        let pc = self.opcode_pc();
         let idx = self.read_u2();
-
-        let class = {
-            match runtime::require_class2(idx as u16, &self.frame.cp) {
-                Some(class) => {
-                    oop::class::init_class(&class);
-                    oop::class::init_class_fully(&class);
-
-                    class
-                }
-                None => unreachable!("Cannot get class info from constant pool"),
-            }
+        let class = match self.quick_class(pc, idx) {
+            Some(class) => class,
+            None => return,
         };
+        if !self.init_class(&class) {
+            return;
+        }
 
+        let has_finalizer = class.extract_inst().has_finalizer;
         let v = oop::Oop::new_inst(class);
-        let mut stack = self.frame.area.stack.borrow_mut();
+        if has_finalizer {
+            heap::register_finalizer(&v);
+        }
+        let stack = &self.frame.area.stack;
         stack.push_ref(v);
+        heap::safepoint();
     }
 
     #[inline]
     fn new_array(&self) {
         let t = self.read_byte();
 
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let len = stack.pop_int();
 
         if len < 0 {
-            drop(stack);
-            exception::meet_ex(cls_const::J_NASE, Some("length < 0".to_string()));
+            exception::meet_ex(cls_const::J_NASE, Some(len.to_string()));
         } else {
             let len = len as usize;
             let ary = match t {
@@ -2815,6 +2885,7 @@ impl<'a> Interp<'a> {
             };
 
             stack.push_ref(ary);
+            heap::safepoint();
         }
     }
 
@@ -2822,22 +2893,18 @@ impl<'a> Interp<'a> {
     fn anew_array(&self) {
         let cp_idx = self.read_i2();
 
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let length = stack.pop_int();
-        drop(stack);
 
         //        info!("anew_array length={}", length);
         if length < 0 {
-            exception::meet_ex(cls_const::J_NASE, Some("length < 0".to_string()));
+            exception::meet_ex(cls_const::J_NASE, Some(length.to_string()));
         } else {
-            let class = match runtime::require_class2(cp_idx as u16, &self.frame.cp) {
+            let class = match self.resolve_class(cp_idx as usize) {
                 Some(class) => class,
-                None => panic!("Cannot get class info from constant pool"),
+                None => return,
             };
 
-            oop::class::init_class(&class);
-            oop::class::init_class_fully(&class);
-
             let (name, cl) = {
                 let class = class.get_class();
                 let t = class.get_class_kind_type();
@@ -2869,12 +2936,10 @@ impl<'a> Interp<'a> {
             });
             match runtime::require_class(cl, &name) {
                 Some(ary_cls_obj) => {
-                    oop::class::init_class(&ary_cls_obj);
-                    oop::class::init_class_fully(&ary_cls_obj);
-
-                    let mut stack = self.frame.area.stack.borrow_mut();
+                    let stack = &self.frame.area.stack;
                     let ary = Oop::new_ref_ary(ary_cls_obj, length as usize);
                     stack.push_ref(ary);
+                    heap::safepoint();
                 }
                 None => unreachable!(),
             }
@@ -2883,14 +2948,11 @@ impl<'a> Interp<'a> {
 
     #[inline]
     fn array_length(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v = stack.pop_ref();
 
         match v {
-            Oop::Null => {
-                drop(stack);
-                exception::meet_ex(cls_const::J_NPE, None)
-            }
+            Oop::Null => exception::meet_ex(cls_const::J_NPE, None),
             Oop::Ref(rf) => {
                 let v = rf.get_raw_ptr();
                 unsafe {
@@ -2912,12 +2974,12 @@ impl<'a> Interp<'a> {
     }
 
     #[inline]
-    fn athrow(&self, jt: JavaThreadRef) {
-        let mut stack = self.frame.area.stack.borrow_mut();
-        let ex = stack.pop_ref();
-        drop(stack);
-
-        jt.write().unwrap().set_ex(ex);
+    fn athrow(&self, jt: &JavaThreadRef) {
+        let stack = &self.frame.area.stack;
+        match stack.pop_ref() {
+            Oop::Null => exception::meet_ex(cls_const::J_NPE, None),
+            ex => jt.write().unwrap().set_ex(ex),
+        }
     }
 
     #[inline]
@@ -2932,9 +2994,8 @@ impl<'a> Interp<'a> {
 
     #[inline]
     fn monitor_enter(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v = stack.pop_ref();
-        drop(stack);
 
         match v {
             Oop::Null => {
@@ -2947,9 +3008,8 @@ impl<'a> Interp<'a> {
 
     #[inline]
     fn monitor_exit(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let mut v = stack.pop_ref();
-        drop(stack);
 
         match v {
             Oop::Null => {
@@ -2970,32 +3030,35 @@ impl<'a> Interp<'a> {
         let cp_idx = self.read_u2();
         let dimension = self.read_u1();
 
-        let mut lens = Vec::new();
-        let mut stack = self.frame.area.stack.borrow_mut();
-        for _ in 0..dimension {
-            let sub = stack.pop_int();
-            //todo: check java/lang/NegativeArraySizeException
-            lens.push(sub);
+        //count1 .. countN, countN on the top
+        let mut lens = vec![0; dimension];
+        let stack = &self.frame.area.stack;
+        for i in (0..dimension).rev() {
+            lens[i] = stack.pop_int();
         }
-        drop(stack);
 
-        let cls = require_class2(cp_idx as u16, &self.frame.cp).unwrap();
+        let cls = match self.resolve_class(cp_idx) {
+            Some(cls) => cls,
+            None => return,
+        };
+        if let Some(len) = lens.iter().find(|&&len| len < 0) {
+            exception::meet_ex(cls_const::J_NASE, Some(len.to_string()));
+            return;
+        }
         let ary = new_multi_object_array_helper(cls, &lens, 0);
 
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         stack.push_ref(ary);
+        heap::safepoint();
     }
 
     #[inline]
     fn if_null(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v = stack.pop_ref();
 
         match v {
-            Oop::Null => {
-                drop(stack);
-                self.goto_by_offset_hardcoded(2)
-            }
+            Oop::Null => self.goto_by_offset_hardcoded(2),
             _ => {
                 let _ = self.frame.pc.fetch_add(2, Ordering::Relaxed);
             }
@@ -3004,24 +3067,27 @@ impl<'a> Interp<'a> {
 
     #[inline]
     fn if_non_null(&self) {
-        let mut stack = self.frame.area.stack.borrow_mut();
+        let stack = &self.frame.area.stack;
         let v = stack.pop_ref();
 
         match v {
             Oop::Null => {
                 let _ = self.frame.pc.fetch_add(2, Ordering::Relaxed);
             }
-            _ => {
-                drop(stack);
-                self.goto_by_offset_hardcoded(2)
-            }
+            _ => self.goto_by_offset_hardcoded(2),
         }
     }
 
     #[inline]
     fn goto_w(&self) {
-        let _ = self.frame.pc.fetch_add(4, Ordering::Relaxed);
-        panic!("Use of deprecated instruction goto_w, please check your Java compiler")
+        let pc = self.opcode_pc();
+        let code = &self.frame.code;
+        let branch = i32::from_be_bytes([code[pc + 1], code[pc + 2], code[pc + 3], code[pc + 4]]);
+        if branch < 0 {
+            self.back_edge.set(true);
+        }
+
+        self.goto_abs(pc as i32 + branch);
     }
 
     #[inline]
@@ -3048,20 +3114,40 @@ fn new_multi_object_array_helper(cls: ClassRef, lens: &[i32], idx: usize) -> Oop
         let cls = cls.get_class();
         match &cls.kind {
             oop::ClassKind::Instance(_) => unreachable!(),
-            ClassKind::ObjectArray(obj_ary) => obj_ary.down_type.clone().unwrap(),
-            ClassKind::TypeArray(typ_ary) => typ_ary.down_type.clone().unwrap(),
+            //the last dimension created, of primitives
+            ClassKind::TypeArray(typ_ary) if typ_ary.down_type.is_none() => {
+                return new_prime_ary(typ_ary.value_type, length);
+            }
+            _ if idx == lens.len() - 1 => None,
+            ClassKind::ObjectArray(obj_ary) => obj_ary.down_type.clone(),
+            ClassKind::TypeArray(typ_ary) => typ_ary.down_type.clone(),
         }
     };
 
-    if idx < lens.len() - 1 {
-        let mut elms = Vec::with_capacity(length);
-        for i in 0..length {
-            let e = new_multi_object_array_helper(down_type.clone(), lens, idx + 1);
-            elms.push(e);
+    match down_type {
+        Some(down_type) => {
+            let mut elms = Vec::with_capacity(length);
+            for i in 0..length {
+                let e = new_multi_object_array_helper(down_type.clone(), lens, idx + 1);
+                elms.push(e);
+            }
+
+            Oop::new_ref_ary2(cls, elms)
         }
+        None => Oop::new_ref_ary(cls, length),
+    }
+}
 
-        Oop::new_ref_ary2(cls, elms)
-    } else {
-        Oop::new_ref_ary(cls, length)
+fn new_prime_ary(value_type: ValueType, len: usize) -> Oop {
+    match value_type {
+        ValueType::BOOLEAN => Oop::new_bool_ary(len),
+        ValueType::CHAR => Oop::new_char_ary(len),
+        ValueType::FLOAT => Oop::new_float_ary(len),
+        ValueType::DOUBLE => Oop::new_double_ary(len),
+        ValueType::BYTE => Oop::new_byte_ary(len),
+        ValueType::SHORT => Oop::new_short_ary(len),
+        ValueType::INT => Oop::new_int_ary(len),
+        ValueType::LONG => Oop::new_long_ary(len),
+        t => unreachable!("t = {:?}", t),
     }
 }
@@ -1,5 +1,6 @@
 use crate::native;
 use crate::oop::{self, Oop, ValueType};
+use crate::runtime::quick::InlineCache;
 use crate::runtime::{self, exception, frame::Frame, thread, DataArea, Interp};
 use crate::types::{ClassRef, FrameRef, JavaThreadRef, MethodIdRef};
 use crate::util;
@@ -42,7 +43,7 @@ impl JavaCall {
         let has_this = !mir.method.is_static();
         if has_this {
             let this = {
-                let mut stack = caller.stack.borrow_mut();
+                let stack = &caller.stack;
                 stack.pop_ref()
             };
 
@@ -73,6 +74,31 @@ impl JavaCall {
 }
 
 impl JavaCall {
+    //select by the inline cache of the call site, or by v_table & itable
+    //with the selected one cached, then invoke it
+    pub fn invoke_cached(&mut self, caller: Option<&DataArea>, ic: &InlineCache) {
+        if self.mir.vtable_index.is_some() {
+            if let Some(cls) = self.receiver_class() {
+                match ic.get(&cls) {
+                    Some(mir) => self.mir = mir,
+                    None => match cls.get_class().select_method(&self.mir) {
+                        Ok(mir) => {
+                            ic.set(cls.clone(), mir.clone());
+                            self.mir = mir;
+                        }
+                        Err(ex) => {
+                            let jt = runtime::thread::current_java_thread();
+                            jt.write().unwrap().set_ex(ex);
+                            return;
+                        }
+                    },
+                }
+            }
+        }
+
+        self.invoke(caller, true);
+    }
+
     //the 'caller' for store return value
     pub fn invoke(&mut self, caller: Option<&DataArea>, force_no_resolve: bool) {
         /*
@@ -82,7 +108,11 @@ impl JavaCall {
 
             public native boolean checkAccess(File f, int access);
         */
-        self.resolve_virtual_method(force_no_resolve);
+        if let Err(ex) = self.resolve_virtual_method(force_no_resolve) {
+            let jt = runtime::thread::current_java_thread();
+            jt.write().unwrap().set_ex(ex);
+            return;
+        }
         self.debug();
 
         if self.mir.method.is_native() {
@@ -217,7 +247,7 @@ impl JavaCall {
 
         if !is_native {
             //JVM spec, 2.6.1
-            let mut local = frame.area.local.borrow_mut();
+            let local = &frame.area.local;
             let mut slot_pos: usize = 0;
             self.args.iter().for_each(|v| {
                 let step = match v {
@@ -251,53 +281,36 @@ impl JavaCall {
         Ok(frame_ref)
     }
 
-    fn resolve_virtual_method(&mut self, force_no_resolve: bool) {
-        let resolve_again = if force_no_resolve {
-            false
-        } else {
-            //todo: why is the value of 0 possible in acc_flags?
-            /*
-            This situation occurs when:
-            java/util/regex/Matcher.java
-            bool search(int from)
-              boolean result = parentPattern.root.match(this, from, text);
-
-            The acc_flags of the match method is 0, and what is found is java/util/regex/Patter$Node#match，
-            Correct should use java/util/regex/Patter$Start#match
-            */
-            self.mir.method.is_abstract()
-                || (self.mir.method.is_public() && !self.mir.method.is_final())
-                || (self.mir.method.is_protected() && !self.mir.method.is_final())
-                || (self.mir.method.acc_flags == 0)
-        };
-        trace!(
-            "resolve_virtual_method resolve_again={}, acc_flags = {}",
-            resolve_again,
-            self.mir.method.acc_flags
-        );
-        if resolve_again {
-            let this = self.args.get(0).unwrap();
-            let rf = this.extract_ref();
-            let ptr = rf.get_raw_ptr();
-            unsafe {
-                if let oop::RefKind::Inst(inst) = &(*ptr).v {
-                    let name = self.mir.method.name.clone();
-                    let desc = self.mir.method.desc.clone();
-                    let cls = inst.class.get_class();
-                    match cls.get_virtual_method(&name, &desc) {
-                        Ok(mir) => self.mir = mir,
-                        _ => {
-                            let cls = self.mir.method.class.get_class();
-                            warn!(
-                                "resolve again failed, {}:{}:{}, acc_flags = {}",
-                                String::from_utf8_lossy(cls.name.as_slice()),
-                                String::from_utf8_lossy(name.as_slice()),
-                                String::from_utf8_lossy(desc.as_slice()),
-                                self.mir.method.acc_flags
-                            );
-                        }
-                    }
-                }
+    //JVMS 5.4.6, by the class of the receiver, the slot of the resolved method
+    //in v_table or the itable, taken at link time
+    fn resolve_virtual_method(&mut self, force_no_resolve: bool) -> Result<(), Oop> {
+        if force_no_resolve || self.mir.vtable_index.is_none() {
+            return Ok(());
+        }
+
+        if let Some(cls) = self.receiver_class() {
+            let cls = cls.get_class();
+            self.mir = cls.select_method(&self.mir)?;
+            trace!(
+                "resolve_virtual_method {}:{}:{}",
+                String::from_utf8_lossy(self.mir.method.cls_name.as_slice()),
+                String::from_utf8_lossy(self.mir.method.name.as_slice()),
+                String::from_utf8_lossy(self.mir.method.desc.as_slice())
+            );
+        }
+        Ok(())
+    }
+
+    //None for arrays, the methods of Object
+    fn receiver_class(&self) -> Option<ClassRef> {
+        let this = self.args.first().unwrap();
+        let rf = this.extract_ref();
+        let ptr = rf.get_raw_ptr();
+        unsafe {
+            match &(*ptr).v {
+                oop::RefKind::Inst(inst) => Some(inst.class.clone()),
+                oop::RefKind::Mirror(_) => runtime::require_class3(None, cls_const::J_CLASS),
+                _ => None,
             }
         }
     }
@@ -314,7 +327,7 @@ impl JavaCall {
 }
 
 fn build_args_from_caller_stack(caller: &DataArea, sig: &MethodSignature) -> Vec<Oop> {
-    let mut caller = caller.stack.borrow_mut();
+    let caller = &caller.stack;
     let mut args = Vec::with_capacity(sig.args.len() + 1);
 
     //build args from caller's stack, so should rev the signature args
@@ -354,7 +367,7 @@ fn build_args_from_caller_stack(caller: &DataArea, sig: &MethodSignature) -> Vec
 }
 
 pub fn set_return(caller: &DataArea, return_type: &SignatureType, v: Oop) {
-    let mut stack = caller.stack.borrow_mut();
+    let stack = &caller.stack;
     match return_type {
         SignatureType::Byte
         | SignatureType::Short
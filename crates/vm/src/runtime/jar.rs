@@ -0,0 +1,420 @@
+use flate2::read::DeflateDecoder;
+use std::fs::File;
+use std::io::{self, Read};
+use std::os::unix::io::AsRawFd;
+
+/*
+Read only jar, mmap-backed
+
+The central directory is parsed once when opened, entries are read
+from the mapped memory without lock, so threads load classes concurrently.
+
+ZIP64: the count & offset of the central directory by the zip64 end record,
+when its locator precedes the end record; the sizes & offset of an entry
+saturated (0xFFFFFFFF) by its zip64 extra field (0x0001).
+*/
+
+const LOCAL_HEADER_SIG: u32 = 0x0403_4b50;
+const CENTRAL_HEADER_SIG: u32 = 0x0201_4b50;
+const END_SIG: u32 = 0x0605_4b50;
+const ZIP64_END_SIG: u32 = 0x0606_4b50;
+const ZIP64_LOCATOR_SIG: u32 = 0x0706_4b50;
+const ZIP64_EXTRA_ID: u16 = 0x0001;
+const LOCAL_HEADER_LEN: usize = 30;
+const CENTRAL_HEADER_LEN: usize = 46;
+const END_LEN: usize = 22;
+const ZIP64_END_LEN: usize = 56;
+const ZIP64_LOCATOR_LEN: usize = 20;
+
+pub const METHOD_STORED: u16 = 0;
+pub const METHOD_DEFLATED: u16 = 8;
+
+//dirs included, name ends with '/'
+pub struct JarEntry {
+    pub name: String,
+    pub method: u16,
+    pub flag: u16,
+    //(date << 16) | time, of dos
+    pub dos_time: u32,
+    pub crc: u32,
+    pub compressed_size: usize,
+    pub size: usize,
+    header_offset: usize,
+    //ranges of central directory
+    extra: (usize, usize),
+    comment: (usize, usize),
+}
+
+pub struct Jar {
+    map: Mmap,
+    entries: Vec<JarEntry>,
+    comment: (usize, usize),
+}
+
+impl Jar {
+    pub fn open(path: &str) -> io::Result<Self> {
+        let f = File::open(path)?;
+        let map = Mmap::new(&f)?;
+        let (entries, comment) = read_central_directory(map.as_slice())?;
+        Ok(Self {
+            map,
+            entries,
+            comment,
+        })
+    }
+
+    pub fn entries(&self) -> &[JarEntry] {
+        &self.entries
+    }
+
+    pub fn comment(&self) -> &[u8] {
+        &self.map.as_slice()[self.comment.0..self.comment.1]
+    }
+
+    pub fn extra(&self, e: &JarEntry) -> &[u8] {
+        &self.map.as_slice()[e.extra.0..e.extra.1]
+    }
+
+    pub fn entry_comment(&self, e: &JarEntry) -> &[u8] {
+        &self.map.as_slice()[e.comment.0..e.comment.1]
+    }
+
+    //the file begins with a local file header, not a self-extracting one
+    pub fn starts_with_loc(&self) -> bool {
+        let buf = self.map.as_slice();
+        buf.len() >= 4 && read_u32(buf, 0) == LOCAL_HEADER_SIG
+    }
+
+    //uncompressed data
+    pub fn read(&self, e: &JarEntry) -> io::Result<Vec<u8>> {
+        let data = self.raw(e)?;
+        match e.method {
+            METHOD_STORED => Ok(data.to_vec()),
+            METHOD_DEFLATED => {
+                let mut v = Vec::with_capacity(e.size);
+                DeflateDecoder::new(data).read_to_end(&mut v)?;
+                Ok(v)
+            }
+            _ => Err(invalid("unsupported compression method")),
+        }
+    }
+
+    //data as stored, compressed or not
+    pub fn raw(&self, e: &JarEntry) -> io::Result<&[u8]> {
+        let buf = self.map.as_slice();
+        let h = e.header_offset;
+        if h >= buf.len()
+            || h + LOCAL_HEADER_LEN > buf.len()
+            || read_u32(buf, h) != LOCAL_HEADER_SIG
+        {
+            return Err(invalid("invalid local file header"));
+        }
+
+        let name_len = read_u16(buf, h + 26) as usize;
+        let extra_len = read_u16(buf, h + 28) as usize;
+        let start = h + LOCAL_HEADER_LEN + name_len + extra_len;
+        if e.compressed_size > buf.len() || start + e.compressed_size > buf.len() {
+            return Err(invalid("entry out of bounds"));
+        }
+
+        Ok(&buf[start..start + e.compressed_size])
+    }
+}
+
+//(entries, range of comment of zip)
+fn read_central_directory(buf: &[u8]) -> io::Result<(Vec<JarEntry>, (usize, usize))> {
+    //the end record is followed by a comment, at most 64k
+    if buf.len() < END_LEN {
+        return Err(invalid("not a zip file"));
+    }
+    let lowest = buf.len().saturating_sub(END_LEN + u16::MAX as usize);
+    let end = (lowest..=buf.len() - END_LEN)
+        .rev()
+        .find(|&i| read_u32(buf, i) == END_SIG)
+        .ok_or_else(|| invalid("end of central directory not found"))?;
+
+    let (count, cd_offset) = match read_zip64_end(buf, end)? {
+        Some(v) => v,
+        None => (
+            read_u16(buf, end + 10) as usize,
+            read_u32(buf, end + 16) as usize,
+        ),
+    };
+    let comment_start = end + END_LEN;
+    let comment = (
+        comment_start,
+        buf.len()
+            .min(comment_start + read_u16(buf, end + 20) as usize),
+    );
+
+    let mut entries = Vec::with_capacity(count.min(buf.len() / CENTRAL_HEADER_LEN));
+    let mut p = cd_offset;
+    for _ in 0..count {
+        if p >= buf.len()
+            || p + CENTRAL_HEADER_LEN > buf.len()
+            || read_u32(buf, p) != CENTRAL_HEADER_SIG
+        {
+            return Err(invalid("invalid central directory"));
+        }
+
+        let flag = read_u16(buf, p + 8);
+        let method = read_u16(buf, p + 10);
+        let dos_time = read_u32(buf, p + 12);
+        let crc = read_u32(buf, p + 16);
+        let mut compressed_size = read_u32(buf, p + 20) as usize;
+        let mut size = read_u32(buf, p + 24) as usize;
+        let name_len = read_u16(buf, p + 28) as usize;
+        let extra_len = read_u16(buf, p + 30) as usize;
+        let comment_len = read_u16(buf, p + 32) as usize;
+        let mut header_offset = read_u32(buf, p + 42) as usize;
+
+        let name_start = p + CENTRAL_HEADER_LEN;
+        let extra_start = name_start + name_len;
+        let comment_start = extra_start + extra_len;
+        let next = comment_start + comment_len;
+        if next > buf.len() {
+            return Err(invalid("invalid central directory"));
+        }
+        let name = String::from_utf8_lossy(&buf[name_start..extra_start]).to_string();
+
+        //the saturated ones in order, by the zip64 extra field
+        if let Some(mut zip64) = find_extra(&buf[extra_start..comment_start], ZIP64_EXTRA_ID) {
+            for v in [&mut size, &mut compressed_size, &mut header_offset] {
+                if *v == u32::MAX as usize {
+                    if zip64.len() < 8 {
+                        return Err(invalid("invalid zip64 extra field"));
+                    }
+                    *v = read_u64(zip64, 0) as usize;
+                    zip64 = &zip64[8..];
+                }
+            }
+        }
+
+        entries.push(JarEntry {
+            name,
+            method,
+            flag,
+            dos_time,
+            crc,
+            compressed_size,
+            size,
+            header_offset,
+            extra: (extra_start, comment_start),
+            comment: (comment_start, next),
+        });
+
+        p = next;
+    }
+
+    Ok((entries, comment))
+}
+
+//(count of entries, offset of central directory) of the zip64 end record,
+//None if no locator before the end record at 'end'
+fn read_zip64_end(buf: &[u8], end: usize) -> io::Result<Option<(usize, usize)>> {
+    if end < ZIP64_LOCATOR_LEN {
+        return Ok(None);
+    }
+    let locator = end - ZIP64_LOCATOR_LEN;
+    if read_u32(buf, locator) != ZIP64_LOCATOR_SIG {
+        return Ok(None);
+    }
+
+    let p = read_u64(buf, locator + 8) as usize;
+    if p >= buf.len() || p + ZIP64_END_LEN > buf.len() || read_u32(buf, p) != ZIP64_END_SIG {
+        return Err(invalid("invalid zip64 end of central directory"));
+    }
+    let count = read_u64(buf, p + 32) as usize;
+    let cd_offset = read_u64(buf, p + 48) as usize;
+    Ok(Some((count, cd_offset)))
+}
+
+//data of the extra field 'id', of the extra fields of an entry
+fn find_extra(mut extra: &[u8], id: u16) -> Option<&[u8]> {
+    while extra.len() >= 4 {
+        let len = read_u16(extra, 2) as usize;
+        let data = extra.get(4..4 + len)?;
+        if read_u16(extra, 0) == id {
+            return Some(data);
+        }
+        extra = &extra[4 + len..];
+    }
+    None
+}
+
+fn read_u16(buf: &[u8], p: usize) -> u16 {
+    u16::from_le_bytes([buf[p], buf[p + 1]])
+}
+
+fn read_u32(buf: &[u8], p: usize) -> u32 {
+    u32::from_le_bytes([buf[p], buf[p + 1], buf[p + 2], buf[p + 3]])
+}
+
+fn read_u64(buf: &[u8], p: usize) -> u64 {
+    let mut v = [0u8; 8];
+    v.copy_from_slice(&buf[p..p + 8]);
+    u64::from_le_bytes(v)
+}
+
+fn invalid(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+struct Mmap {
+    ptr: *mut libc::c_void,
+    len: usize,
+}
+
+//read only after mapped
+unsafe impl Send for Mmap {}
+unsafe impl Sync for Mmap {}
+
+impl Mmap {
+    fn new(f: &File) -> io::Result<Self> {
+        let len = f.metadata()?.len() as usize;
+        if len == 0 {
+            return Err(invalid("empty file"));
+        }
+
+        let ptr = unsafe {
+            libc::mmap(
+                std::ptr::null_mut(),
+                len,
+                libc::PROT_READ,
+                libc::MAP_PRIVATE,
+                f.as_raw_fd(),
+                0,
+            )
+        };
+        if ptr == libc::MAP_FAILED {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(Self { ptr, len })
+    }
+
+    fn as_slice(&self) -> &[u8] {
+        unsafe { std::slice::from_raw_parts(self.ptr as *const u8, self.len) }
+    }
+}
+
+impl Drop for Mmap {
+    fn drop(&mut self) {
+        unsafe {
+            libc::munmap(self.ptr, self.len);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use zip::write::FileOptions;
+    use zip::CompressionMethod;
+
+    #[test]
+    fn t_read() {
+        let path = std::env::temp_dir().join(format!("jvm_jar_test_{}.jar", std::process::id()));
+        {
+            let f = File::create(&path).unwrap();
+            let mut z = zip::ZipWriter::new(f);
+            let stored = FileOptions::default().compression_method(CompressionMethod::Stored);
+            let deflated = FileOptions::default().compression_method(CompressionMethod::Deflated);
+            z.set_comment("test jar");
+            z.add_directory("a/", stored).unwrap();
+            z.start_file("a/A.class", stored).unwrap();
+            z.write_all(b"stored data").unwrap();
+            z.start_file("a/b/B.class", deflated).unwrap();
+            z.write_all(&[7u8; 4096]).unwrap();
+            z.finish().unwrap();
+        }
+
+        let jar = Jar::open(path.to_str().unwrap()).unwrap();
+        let names: Vec<&str> = jar.entries().iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["a/", "a/A.class", "a/b/B.class"]);
+        assert!(jar.starts_with_loc());
+        assert_eq!(jar.comment(), b"test jar");
+
+        let e = &jar.entries()[1];
+        assert_eq!(jar.read(e).unwrap(), b"stored data");
+        assert_eq!(jar.raw(e).unwrap(), b"stored data");
+        assert_eq!(e.method, METHOD_STORED);
+        let mut crc = crc32fast::Hasher::new();
+        crc.update(b"stored data");
+        assert_eq!(e.crc, crc.finalize());
+
+        let e = &jar.entries()[2];
+        assert_eq!(jar.read(e).unwrap(), vec![7u8; 4096]);
+        assert_eq!(e.method, METHOD_DEFLATED);
+        assert_eq!(e.size, 4096);
+        assert_eq!(jar.raw(e).unwrap().len(), e.compressed_size);
+
+        let _ = std::fs::remove_file(&path);
+        assert!(Jar::open("no_exist.jar").is_err());
+
+        //zip64, count & offset by the zip64 end record, sizes & offset by the extra field
+        let data = b"zip64 data";
+        let name = b"a/Z.class";
+        let mut crc = crc32fast::Hasher::new();
+        crc.update(data);
+        let crc = crc.finalize();
+        let mut buf: Vec<u8> = vec![];
+        let u16s = |buf: &mut Vec<u8>, v: &[u16]| {
+            v.iter()
+                .for_each(|v| buf.extend_from_slice(&v.to_le_bytes()))
+        };
+        let u32s = |buf: &mut Vec<u8>, v: &[u32]| {
+            v.iter()
+                .for_each(|v| buf.extend_from_slice(&v.to_le_bytes()))
+        };
+        let u64s = |buf: &mut Vec<u8>, v: &[u64]| {
+            v.iter()
+                .for_each(|v| buf.extend_from_slice(&v.to_le_bytes()))
+        };
+        let len = data.len() as u64;
+
+        u32s(&mut buf, &[LOCAL_HEADER_SIG]);
+        u16s(&mut buf, &[45, 0, METHOD_STORED]);
+        u32s(&mut buf, &[0, crc, u32::MAX, u32::MAX]);
+        u16s(&mut buf, &[name.len() as u16, 20]);
+        buf.extend_from_slice(name);
+        u16s(&mut buf, &[ZIP64_EXTRA_ID, 16]);
+        u64s(&mut buf, &[len, len]);
+        buf.extend_from_slice(data);
+
+        let cd_offset = buf.len() as u64;
+        u32s(&mut buf, &[CENTRAL_HEADER_SIG]);
+        u16s(&mut buf, &[45, 45, 0, METHOD_STORED]);
+        u32s(&mut buf, &[0, crc, u32::MAX, u32::MAX]);
+        u16s(&mut buf, &[name.len() as u16, 28, 0, 0, 0]);
+        u32s(&mut buf, &[0, u32::MAX]);
+        buf.extend_from_slice(name);
+        u16s(&mut buf, &[ZIP64_EXTRA_ID, 24]);
+        u64s(&mut buf, &[len, len, 0]);
+        let cd_size = buf.len() as u64 - cd_offset;
+
+        let end64 = buf.len() as u64;
+        u32s(&mut buf, &[ZIP64_END_SIG]);
+        u64s(&mut buf, &[(ZIP64_END_LEN - 12) as u64]);
+        u16s(&mut buf, &[45, 45]);
+        u32s(&mut buf, &[0, 0]);
+        u64s(&mut buf, &[1, 1, cd_size, cd_offset]);
+        u32s(&mut buf, &[ZIP64_LOCATOR_SIG, 0]);
+        u64s(&mut buf, &[end64]);
+        u32s(&mut buf, &[1, END_SIG]);
+        u16s(&mut buf, &[0, 0, u16::MAX, u16::MAX]);
+        u32s(&mut buf, &[u32::MAX, u32::MAX]);
+        u16s(&mut buf, &[0]);
+
+        let path = std::env::temp_dir().join(format!("jvm_jar64_test_{}.jar", std::process::id()));
+        std::fs::write(&path, &buf).unwrap();
+        let jar = Jar::open(path.to_str().unwrap()).unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(jar.entries().len(), 1);
+        let e = &jar.entries()[0];
+        assert_eq!(e.name, "a/Z.class");
+        assert_eq!((e.size, e.compressed_size, e.header_offset), (10, 10, 0));
+        assert_eq!(jar.read(e).unwrap(), data);
+    }
+}
@@ -0,0 +1,168 @@
+use crate::oop::method::Method;
+use crate::oop::Oop;
+use crate::runtime::{options, Interp};
+use crate::types::JavaThreadRef;
+use classfile::SignatureType;
+use jit::{Bytecode, CompiledMethod, Context, Exit, Runtime};
+use std::ffi::c_void;
+use std::sync::atomic::Ordering;
+
+/*
+Mixed mode, the interpreter & the baseline compiler (crates/jit)
+
+Invocations & back edges are counted by the method. Once over the threshold,
+the method is compiled by the thread counting it, and entered at its next
+invocation, or at the target of the back edge (OSR), if the compiled code has
+an entry there of the same stack depth.
+
+The compiled code works on the slots of the frame; what it doesn't compile
+inline goes through step, one instruction executed by the interpreter. Back
+in the interpreter (Exit::Deopt, Exit::Resume), the frame is where the
+compiled code left it, and the interpreter just goes on.
+
+  -Xint, interpreted only
+  -XX:CompileThreshold=n, invocations & back edges to compile, 1500 default
+  -XX:+PrintCompilation, the methods compiled
+*/
+
+lazy_static! {
+    static ref INTERPRET_ONLY: bool =
+        !jit::is_supported() || options::with(|opts| opts.interpret_only);
+    static ref COMPILE_THRESHOLD: u32 =
+        options::with(|opts| opts.xx_usize("CompileThreshold", 1500)) as u32;
+    static ref PRINT_COMPILATION: bool =
+        options::with(|opts| opts.xx_flag("PrintCompilation", false));
+}
+
+//what step needs, pointed by Context::env
+struct Env<'a, 'b> {
+    interp: &'a Interp<'b>,
+    jt: &'a JavaThreadRef,
+}
+
+//counts the invocation; true if the method executed by its compiled code
+pub fn invoke(interp: &Interp, jt: &JavaThreadRef) -> bool {
+    if *INTERPRET_ONLY {
+        return false;
+    }
+
+    let method = &interp.frame().mir.method;
+    let count = method.jit.count_invocation();
+    enter(interp, jt, method, count)
+}
+
+//counts the back edge taken; true if the method finished by its compiled code
+pub fn back_edge(interp: &Interp, jt: &JavaThreadRef) -> bool {
+    if *INTERPRET_ONLY {
+        return false;
+    }
+
+    let method = &interp.frame().mir.method;
+    let count = method.jit.count_backedge();
+    enter(interp, jt, method, count)
+}
+
+fn enter(interp: &Interp, jt: &JavaThreadRef, method: &Method, count: u32) -> bool {
+    if count >= *COMPILE_THRESHOLD && method.jit.begin_compile() {
+        compile(method);
+    }
+
+    match method.jit.code() {
+        Some(code) => {
+            let frame = interp.frame();
+            let pc = frame.pc.load(Ordering::Relaxed) as usize;
+            if code.entry_depth(pc) == Some(frame.area.stack.top()) {
+                run(interp, jt, method, code)
+            } else {
+                false
+            }
+        }
+        None => false,
+    }
+}
+
+fn compile(method: &Method) {
+    let code = method.code.as_ref().unwrap();
+    let m = Bytecode {
+        code: code.code.as_slice(),
+        cp: &method.class_file.cp,
+        //as DataArea, one more local
+        max_locals: code.max_locals as usize + 1,
+        max_stack: code.max_stack as usize,
+    };
+    let rt = Runtime { step };
+
+    match jit::compile(&m, &rt) {
+        Ok(compiled) => {
+            if *PRINT_COMPILATION {
+                println!("compiled {:?}, {} bytes", method, compiled.code_len());
+            }
+            method.jit.install(Some(compiled));
+        }
+        Err(e) => {
+            if *PRINT_COMPILATION {
+                println!("COMPILE SKIPPED {:?}: {}", method, e);
+            }
+            method.jit.install(None);
+        }
+    }
+}
+
+fn run(interp: &Interp, jt: &JavaThreadRef, method: &Method, code: &CompiledMethod) -> bool {
+    let frame = interp.frame();
+    let area = &frame.area;
+    let (locals, local_refs, stack) = area.jit_ptrs();
+
+    let mut env = Env { interp, jt };
+    let mut ctx = Context {
+        locals,
+        local_refs,
+        stack,
+        pc: frame.pc.load(Ordering::Relaxed) as u32,
+        sp: area.stack.top() as u32,
+        ret: 0,
+        env: &mut env as *mut Env as *mut c_void,
+    };
+
+    match unsafe { code.run(&mut ctx) } {
+        Exit::Return => {
+            let v = match method.signature.retype {
+                SignatureType::Void => None,
+                SignatureType::Long => Some(Oop::new_long(ctx.ret as i64)),
+                SignatureType::Float => Some(Oop::new_float(f32::from_bits(ctx.ret as u32))),
+                SignatureType::Double => Some(Oop::new_double(f64::from_bits(ctx.ret))),
+                _ => Some(Oop::new_int(ctx.ret as i32)),
+            };
+            *area.return_v.borrow_mut() = v;
+            true
+        }
+        Exit::Done => true,
+        Exit::Deopt => {
+            frame.pc.store(ctx.pc as i32, Ordering::Relaxed);
+            area.stack.set_top(ctx.sp as usize);
+            false
+        }
+        Exit::Resume => false,
+    }
+}
+
+//Runtime::step, the instruction at 'pc' interpreted
+extern "C" fn step(ctx: *mut Context, pc: u32, sp: u32, next_pc: u32) -> u32 {
+    let env = unsafe { &mut *((*ctx).env as *mut Env) };
+    let interp = env.interp;
+    let frame = interp.frame();
+
+    frame.pc.store(pc as i32, Ordering::Relaxed);
+    frame.area.stack.set_top(sp as usize);
+    interp.take_caught();
+
+    //a panic never unwinds through the compiled code: the hook of vm_error
+    //aborts first on a vm thread
+    if !interp.step(env.jt) {
+        Exit::Done as u32
+    } else if interp.take_caught() || frame.pc.load(Ordering::Relaxed) != next_pc as i32 {
+        Exit::Resume as u32
+    } else {
+        0
+    }
+}
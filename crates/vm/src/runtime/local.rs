@@ -1,84 +1,99 @@
 use crate::oop::{Oop, OopRef};
-use crate::runtime::Slot;
-use crate::util;
+use crate::runtime::slot::{self, Slot};
 
+//the locals of a frame, on the slots of DataArea
 pub struct Local {
-    locals: Vec<Slot>,
+    slots: *mut Slot,
+    refs: *mut bool,
+    len: usize,
 }
 
 impl Local {
-    pub fn new(size: usize) -> Self {
-        let size = size + 1;
-        let locals = vec![Slot::Nop; size];
-        Self { locals }
+    pub fn new(slots: *mut Slot, refs: *mut bool, len: usize) -> Self {
+        Self { slots, refs, len }
     }
 
     #[inline]
-    pub fn set_int(&mut self, pos: usize, v: i32) {
-        self.locals[pos] = Slot::I32(v);
+    pub fn set_int(&self, pos: usize, v: i32) {
+        self.set(pos, v as u32 as Slot, false);
     }
 
     #[inline]
-    pub fn set_long(&mut self, pos: usize, v: i64) {
-        self.locals[pos] = Slot::I64(v);
+    pub fn set_long(&self, pos: usize, v: i64) {
+        self.set(pos, v as Slot, false);
     }
 
     #[inline]
-    pub fn set_float(&mut self, pos: usize, v: f32) {
-        self.locals[pos] = Slot::F32(v);
+    pub fn set_float(&self, pos: usize, v: f32) {
+        self.set(pos, v.to_bits() as Slot, false);
     }
 
     #[inline]
-    pub fn set_double(&mut self, pos: usize, v: f64) {
-        self.locals[pos] = Slot::F64(v);
+    pub fn set_double(&self, pos: usize, v: f64) {
+        self.set(pos, v.to_bits(), false);
     }
 
     #[inline]
-    pub fn set_ref(&mut self, pos: usize, v: Oop) {
-        self.locals[pos] = Slot::Ref(v);
+    pub fn set_ref(&self, pos: usize, v: Oop) {
+        let (v, is_ref) = slot::from_ref(v);
+        self.set(pos, v, is_ref);
     }
 
     #[inline]
     pub fn get_int(&self, pos: usize) -> i32 {
-        match self.locals.get(pos).unwrap() {
-            Slot::I32(v) => *v,
-            Slot::Ref(v) => OopRef::java_lang_integer_value(v.extract_ref()),
-            t => panic!("Illegal type {:?}", t),
+        let (v, is_ref) = self.get(pos);
+        if is_ref {
+            let v = unsafe { slot::clone_ref(v) };
+            OopRef::java_lang_integer_value(v.extract_ref())
+        } else {
+            v as u32 as i32
         }
     }
 
     #[inline]
     pub fn get_long(&self, pos: usize) -> i64 {
-        if let Slot::I64(v) = self.locals.get(pos).unwrap() {
-            *v
-        } else {
-            panic!("Illegal type");
-        }
+        self.get(pos).0 as i64
     }
 
     #[inline]
     pub fn get_float(&self, pos: usize) -> f32 {
-        if let Slot::F32(v) = self.locals.get(pos).unwrap() {
-            *v
-        } else {
-            panic!("Illegal type");
-        }
+        f32::from_bits(self.get(pos).0 as u32)
     }
 
     #[inline]
     pub fn get_double(&self, pos: usize) -> f64 {
-        if let Slot::F64(v) = self.locals.get(pos).unwrap() {
-            *v
+        f64::from_bits(self.get(pos).0)
+    }
+
+    #[inline]
+    pub fn get_ref(&self, pos: usize) -> Oop {
+        let (v, is_ref) = self.get(pos);
+        if is_ref {
+            unsafe { slot::clone_ref(v) }
         } else {
-            panic!("Illegal type");
+            Oop::Null
         }
     }
+}
 
+impl Local {
     #[inline]
-    pub fn get_ref(&self, pos: usize) -> Oop {
-        match self.locals.get(pos) {
-            Some(Slot::Ref(v)) => v.clone(),
-            t => panic!("Illegal type = {:?}", t),
+    fn set(&self, pos: usize, v: Slot, is_ref: bool) {
+        assert!(pos < self.len, "Illegal local index = {}", pos);
+        unsafe {
+            let old = self.slots.add(pos);
+            let old_is_ref = self.refs.add(pos);
+            if *old_is_ref {
+                slot::release_ref(*old);
+            }
+            *old = v;
+            *old_is_ref = is_ref;
         }
     }
+
+    #[inline]
+    fn get(&self, pos: usize) -> (Slot, bool) {
+        assert!(pos < self.len, "Illegal local index = {}", pos);
+        unsafe { (*self.slots.add(pos), *self.refs.add(pos)) }
+    }
 }
@@ -1,5 +1,6 @@
 #![allow(unused)]
 
+pub mod access;
 mod class_loader;
 mod class_path_manager;
 pub mod cmp;
@@ -8,21 +9,29 @@ mod consts;
 mod dataarea;
 pub mod exception;
 mod frame;
+pub mod heap;
 mod init_vm;
 pub mod interp;
 pub mod invoke;
+pub mod jar;
+pub mod jit;
 mod local;
+pub mod options;
+pub mod quick;
+pub mod signal;
 mod slot;
 mod stack;
 mod sys_dic;
 pub mod thread;
 pub mod vm;
+mod vm_error;
 
-pub use class_loader::{require_class, require_class2, require_class3, ClassLoader};
+pub use class_loader::{require_class, require_class2, require_class3, resolve_class, ClassLoader};
 
 pub use class_path_manager::{
-    add_path as add_class_path, add_paths as add_class_paths,
-    find_class as find_class_in_classpath, ClassPathResult,
+    add_path as add_class_path, add_paths as add_class_paths, boot_class_path, class_path_urls,
+    find_boot_class as find_class_in_boot_classpath, find_class as find_class_in_classpath,
+    find_resource, find_resources, known_to_not_exist, lookup_resource, ClassPathResult,
 };
 pub use constant_pool::ConstantPoolCache;
 pub use consts::THREAD_MAX_STACK_FRAMES;
@@ -31,10 +40,15 @@ pub use frame::Frame;
 pub use interp::Interp;
 pub use invoke::JavaCall;
 pub use slot::Slot;
-pub use sys_dic::{find as sys_dic_find, put as sys_dic_put};
+pub use sys_dic::{
+    find as sys_dic_find, find_defined as sys_dic_find_defined, put as sys_dic_put,
+    put_defined as sys_dic_put_defined,
+};
 pub use thread::JavaThread;
 
 pub fn init() {
+    vm_error::install();
     sys_dic::init();
     class_path_manager::init();
+    heap::init();
 }
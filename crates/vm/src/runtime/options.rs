@@ -0,0 +1,377 @@
+use rustc_hash::FxHashMap;
+use std::sync::RwLock;
+
+/*
+Options of vm, the part of the command line handled by vm, not the launcher
+
+Shared by the 'java' launcher and JNI_CreateJavaVM, each option
+is one 'JavaVMOption.optionString', such as "-Dfoo=bar", "-Xss1m"
+*/
+
+lazy_static! {
+    static ref OPTIONS: RwLock<VMOptions> = RwLock::new(VMOptions::default());
+}
+
+pub fn set(opts: VMOptions) {
+    *OPTIONS.write().unwrap() = opts;
+}
+
+pub fn with<F, R>(f: F) -> R
+where
+    F: FnOnce(&VMOptions) -> R,
+{
+    let opts = OPTIONS.read().unwrap();
+    f(&opts)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum AssertionScope {
+    All,
+    //"pkg..."
+    Package(String),
+    Class(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct AssertionDirective {
+    scope: AssertionScope,
+    enabled: bool,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct VMOptions {
+    //java.class.path, None means the default "."
+    pub class_path: Option<String>,
+    //-Dkey=value, in order of command line
+    pub properties: Vec<(String, String)>,
+
+    pub verbose_class: bool,
+    pub verbose_gc: bool,
+    pub verbose_jni: bool,
+
+    //-Xbootclasspath:, replace the default boot class path
+    pub boot_class_path: Option<String>,
+    //-Xbootclasspath/p:, -Xbootclasspath/a:
+    pub boot_class_path_prepend: Vec<String>,
+    pub boot_class_path_append: Vec<String>,
+
+    //-Xms, -Xmx, -Xss in bytes
+    pub initial_heap_size: Option<usize>,
+    pub max_heap_size: Option<usize>,
+    pub thread_stack_size: Option<usize>,
+
+    //-Xint, no methods compiled
+    pub interpret_only: bool,
+
+    //-XX:+Name => "true", -XX:-Name => "false", -XX:Name=v => "v"
+    pub xx: FxHashMap<String, String>,
+
+    assertions: Vec<AssertionDirective>,
+    //-esa / -dsa
+    system_assertions: bool,
+}
+
+impl VMOptions {
+    //Err with the message printed by launcher
+    pub fn parse_option(&mut self, opt: &str) -> Result<(), String> {
+        if let Some(kv) = opt.strip_prefix("-D") {
+            let (k, v) = match kv.find('=') {
+                Some(idx) => (&kv[..idx], &kv[idx + 1..]),
+                None => (kv, ""),
+            };
+            if k.is_empty() {
+                return Err(format!("Invalid property: {}", opt));
+            }
+
+            if k == "java.class.path" {
+                self.class_path = Some(v.to_string());
+            } else {
+                self.properties.push((k.to_string(), v.to_string()));
+            }
+            return Ok(());
+        }
+
+        if let Some(v) = opt.strip_prefix("-verbose") {
+            match v {
+                "" | ":class" => self.verbose_class = true,
+                ":gc" => self.verbose_gc = true,
+                ":jni" => self.verbose_jni = true,
+                _ => return Err(format!("Unrecognized option: {}", opt)),
+            }
+            return Ok(());
+        }
+
+        if let Some(v) = opt.strip_prefix("-XX:") {
+            let (k, v) = if let Some(k) = v.strip_prefix('+') {
+                (k, "true")
+            } else if let Some(k) = v.strip_prefix('-') {
+                (k, "false")
+            } else {
+                match v.find('=') {
+                    Some(idx) => (&v[..idx], &v[idx + 1..]),
+                    None => return Err(format!("Unrecognized VM option '{}'", v)),
+                }
+            };
+            if k.is_empty() {
+                return Err(format!("Unrecognized VM option '{}'", v));
+            }
+            self.xx.insert(k.to_string(), v.to_string());
+            return Ok(());
+        }
+
+        if let Some(v) = opt.strip_prefix("-Xbootclasspath") {
+            if let Some(v) = v.strip_prefix("/p:") {
+                //the latter one is in front
+                self.boot_class_path_prepend.insert(0, v.to_string());
+            } else if let Some(v) = v.strip_prefix("/a:") {
+                self.boot_class_path_append.push(v.to_string());
+            } else if let Some(v) = v.strip_prefix(':') {
+                self.boot_class_path = Some(v.to_string());
+            } else {
+                return Err(format!("Unrecognized option: {}", opt));
+            }
+            return Ok(());
+        }
+
+        if let Some(v) = opt.strip_prefix("-Xms") {
+            self.initial_heap_size = Some(parse_size(opt, v)?);
+            return Ok(());
+        }
+        if let Some(v) = opt.strip_prefix("-Xmx") {
+            self.max_heap_size = Some(parse_size(opt, v)?);
+            return Ok(());
+        }
+        if let Some(v) = opt.strip_prefix("-Xss") {
+            self.thread_stack_size = Some(parse_size(opt, v)?);
+            return Ok(());
+        }
+
+        match opt {
+            "-ea" | "-enableassertions" => self.add_assertion("", true),
+            "-da" | "-disableassertions" => self.add_assertion("", false),
+            "-esa" | "-enablesystemassertions" => self.system_assertions = true,
+            "-dsa" | "-disablesystemassertions" => self.system_assertions = false,
+            "-Xint" => self.interpret_only = true,
+            "-Xmixed" => self.interpret_only = false,
+            _ => {
+                if let Some(v) = opt
+                    .strip_prefix("-ea:")
+                    .or_else(|| opt.strip_prefix("-enableassertions:"))
+                {
+                    self.add_assertion(v, true);
+                } else if let Some(v) = opt
+                    .strip_prefix("-da:")
+                    .or_else(|| opt.strip_prefix("-disableassertions:"))
+                {
+                    self.add_assertion(v, false);
+                } else {
+                    return Err(format!("Unrecognized option: {}", opt));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    //-XX:+Name / -XX:-Name, 'default' if not given
+    pub fn xx_flag(&self, name: &str, default: bool) -> bool {
+        match self.xx.get(name).map(|v| v.as_str()) {
+            Some("true") => true,
+            Some("false") => false,
+            _ => default,
+        }
+    }
+
+    //-XX:Name=v, 'default' if not given or not a number
+    pub fn xx_usize(&self, name: &str, default: usize) -> usize {
+        self.xx
+            .get(name)
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(default)
+    }
+
+    //name like "java/lang/Object"
+    pub fn desired_assertion_status(&self, name: &str) -> bool {
+        let name = name.replace("/", ".");
+        if is_system_class(&name) {
+            return self.system_assertions;
+        }
+
+        let pkg = match name.rfind('.') {
+            Some(idx) => &name[..idx],
+            None => "",
+        };
+
+        //the most specific directive wins, later one wins if the same
+        let mut status = None;
+        let mut best = 0;
+        for it in self.assertions.iter() {
+            let rank = match &it.scope {
+                AssertionScope::All => 1,
+                AssertionScope::Package(p) => {
+                    let matched = pkg == p.as_str()
+                        || (!p.is_empty()
+                            && pkg.starts_with(p.as_str())
+                            && pkg.as_bytes()[p.len()] == b'.');
+                    if matched {
+                        //longer package is more specific
+                        2 + p.len()
+                    } else {
+                        continue;
+                    }
+                }
+                AssertionScope::Class(c) if *c == name => usize::MAX,
+                AssertionScope::Class(_) => continue,
+            };
+
+            if rank >= best {
+                best = rank;
+                status = Some(it.enabled);
+            }
+        }
+
+        status.unwrap_or(false)
+    }
+
+    fn add_assertion(&mut self, v: &str, enabled: bool) {
+        let scope = if v.is_empty() {
+            AssertionScope::All
+        } else if let Some(p) = v.strip_suffix("...") {
+            AssertionScope::Package(p.to_string())
+        } else {
+            AssertionScope::Class(v.to_string())
+        };
+
+        self.assertions.push(AssertionDirective { scope, enabled });
+    }
+}
+
+//all classes loaded by the bootstrap loader, only these packages live in rt.jar
+fn is_system_class(name: &str) -> bool {
+    const SYS_PKGS: &[&str] = &[
+        "java.",
+        "javax.",
+        "sun.",
+        "com.sun.",
+        "jdk.",
+        "org.xml.sax.",
+    ];
+    SYS_PKGS.iter().any(|p| name.starts_with(p))
+}
+
+//"64m", "1g", "512k", "1024"
+fn parse_size(opt: &str, v: &str) -> Result<usize, String> {
+    let err = || format!("Invalid size: {}", opt);
+    let (n, unit) = match v.chars().last() {
+        Some('k') | Some('K') => (&v[..v.len() - 1], 1024),
+        Some('m') | Some('M') => (&v[..v.len() - 1], 1024 * 1024),
+        Some('g') | Some('G') => (&v[..v.len() - 1], 1024 * 1024 * 1024),
+        Some(_) => (v, 1),
+        None => return Err(err()),
+    };
+
+    n.parse::<usize>()
+        .ok()
+        .and_then(|n| n.checked_mul(unit))
+        .ok_or_else(err)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(opts: &[&str]) -> VMOptions {
+        let mut vm_opts = VMOptions::default();
+        for opt in opts {
+            vm_opts.parse_option(opt).unwrap();
+        }
+        vm_opts
+    }
+
+    #[test]
+    fn t_properties() {
+        let opts = parse(&["-Dfoo=bar", "-Dempty", "-Da=b=c", "-Djava.class.path=x.jar"]);
+        assert_eq!(
+            opts.properties,
+            vec![
+                ("foo".to_string(), "bar".to_string()),
+                ("empty".to_string(), "".to_string()),
+                ("a".to_string(), "b=c".to_string()),
+            ]
+        );
+        assert_eq!(opts.class_path, Some("x.jar".to_string()));
+        assert!(VMOptions::default().parse_option("-D=v").is_err());
+    }
+
+    #[test]
+    fn t_x_options() {
+        let opts = parse(&[
+            "-Xss1m",
+            "-Xmx2g",
+            "-Xms512k",
+            "-XX:+UseFoo",
+            "-XX:-UseBar",
+            "-XX:MaxDepth=10",
+            "-verbose:class",
+            "-verbose:jni",
+            "-Xint",
+        ]);
+        assert_eq!(opts.thread_stack_size, Some(1024 * 1024));
+        assert_eq!(opts.max_heap_size, Some(2 * 1024 * 1024 * 1024));
+        assert_eq!(opts.initial_heap_size, Some(512 * 1024));
+        assert_eq!(opts.xx.get("UseFoo").map(|v| v.as_str()), Some("true"));
+        assert_eq!(opts.xx.get("UseBar").map(|v| v.as_str()), Some("false"));
+        assert_eq!(opts.xx.get("MaxDepth").map(|v| v.as_str()), Some("10"));
+        assert!(!opts.xx_flag("UseBar", true));
+        assert!(opts.xx_flag("UseBaz", true));
+        assert_eq!(opts.xx_usize("MaxDepth", 1), 10);
+        assert_eq!(opts.xx_usize("UseFoo", 1), 1);
+        assert!(opts.verbose_class);
+        assert!(opts.verbose_jni);
+        assert!(!opts.verbose_gc);
+        assert!(opts.interpret_only);
+
+        let opts = parse(&[
+            "-Xbootclasspath/a:a1",
+            "-Xbootclasspath/p:p1",
+            "-Xbootclasspath/a:a2",
+            "-Xbootclasspath/p:p2",
+        ]);
+        assert_eq!(opts.boot_class_path, None);
+        assert_eq!(opts.boot_class_path_prepend, vec!["p2", "p1"]);
+        assert_eq!(opts.boot_class_path_append, vec!["a1", "a2"]);
+        let opts = parse(&["-Xbootclasspath:rt.jar"]);
+        assert_eq!(opts.boot_class_path, Some("rt.jar".to_string()));
+
+        let mut opts = VMOptions::default();
+        assert!(opts.parse_option("-Xbootclasspath/x:a").is_err());
+        assert!(opts.parse_option("-Xss").is_err());
+        assert!(opts.parse_option("-Xmxabc").is_err());
+        assert!(opts.parse_option("-XX:Foo").is_err());
+        assert!(opts.parse_option("-verbose:foo").is_err());
+        assert!(opts.parse_option("-Xfoo").is_err());
+    }
+
+    #[test]
+    fn t_assertions() {
+        let opts = VMOptions::default();
+        assert!(!opts.desired_assertion_status("my/App"));
+
+        let opts = parse(&["-ea"]);
+        assert!(opts.desired_assertion_status("my/App"));
+        assert!(!opts.desired_assertion_status("java/lang/Object"));
+
+        let opts = parse(&["-ea", "-da:my.ns...", "-ea:my.ns.Keep", "-esa"]);
+        assert!(opts.desired_assertion_status("App"));
+        assert!(!opts.desired_assertion_status("my/ns/App"));
+        assert!(!opts.desired_assertion_status("my/ns/sub/App"));
+        assert!(opts.desired_assertion_status("my/ns/Keep"));
+        assert!(opts.desired_assertion_status("my/nsx/App"));
+        assert!(opts.desired_assertion_status("java/lang/Object"));
+
+        //"..." alone is the unnamed package
+        let opts = parse(&["-ea:..."]);
+        assert!(opts.desired_assertion_status("App"));
+        assert!(!opts.desired_assertion_status("my/App"));
+    }
+}
@@ -0,0 +1,176 @@
+use crate::runtime::options;
+use crate::types::{ClassRef, FieldIdRef, MethodIdRef};
+use std::ptr;
+use std::sync::atomic::{AtomicPtr, Ordering};
+use std::sync::Arc;
+
+/*
+Quickening of the interpreter
+
+The quick form of an instruction is kept in the side table of its method, by
+the pc of the opcode: the field or method resolved by ConstantPoolCache, or
+the class of new & checkcast. Once recorded, executing it again is an index,
+instead of the hash of cp_cache or the lookup of sys_dic.
+
+Each slot written once, the first writer wins, so a quick form never changes
+once read by a thread. The call sites of invokevirtual & invokeinterface keep
+a monomorphic inline cache: the first class of the receiver, and the method
+selected for it; other receivers go through v_table & itable.
+
+  -XX:-RewriteBytecodes, no quick forms
+  -XX:-UseInlineCaches, select by v_table & itable every call
+*/
+
+lazy_static! {
+    static ref REWRITE_BYTECODES: bool =
+        options::with(|opts| opts.xx_flag("RewriteBytecodes", true));
+    static ref USE_INLINE_CACHES: bool =
+        options::with(|opts| opts.xx_flag("UseInlineCaches", true));
+}
+
+pub fn rewrite_bytecodes() -> bool {
+    *REWRITE_BYTECODES
+}
+
+pub fn use_inline_caches() -> bool {
+    *USE_INLINE_CACHES
+}
+
+pub enum Quick {
+    //getfield, putfield, getstatic & putstatic
+    Field(FieldIdRef),
+    //invoke*, the resolved method
+    Method(MethodIdRef, InlineCache),
+    //new, checkcast & instanceof
+    Class(ClassRef),
+}
+
+pub struct QuickTable {
+    len: usize,
+    //allocated at the first record, most methods never run
+    slots: AtomicPtr<Vec<AtomicPtr<Quick>>>,
+}
+
+impl QuickTable {
+    //'len', the length of the code
+    pub fn new(len: usize) -> Arc<Self> {
+        Arc::new(Self {
+            len,
+            slots: AtomicPtr::new(ptr::null_mut()),
+        })
+    }
+
+    pub fn get(&self, pc: usize) -> Option<&Quick> {
+        let slots = self.slots.load(Ordering::Acquire);
+        if slots.is_null() {
+            return None;
+        }
+
+        let slots = unsafe { &*slots };
+        let v = slots[pc].load(Ordering::Acquire);
+        if v.is_null() {
+            None
+        } else {
+            Some(unsafe { &*v })
+        }
+    }
+
+    //the one recorded, if another thread did it first
+    pub fn put(&self, pc: usize, v: Quick) -> &Quick {
+        let slots = self.get_or_alloc_slots();
+        let v = Box::into_raw(Box::new(v));
+        match slots[pc].compare_exchange(ptr::null_mut(), v, Ordering::AcqRel, Ordering::Acquire) {
+            Ok(_) => unsafe { &*v },
+            Err(cur) => {
+                let _ = unsafe { Box::from_raw(v) };
+                unsafe { &*cur }
+            }
+        }
+    }
+
+    fn get_or_alloc_slots(&self) -> &Vec<AtomicPtr<Quick>> {
+        let slots = self.slots.load(Ordering::Acquire);
+        if !slots.is_null() {
+            return unsafe { &*slots };
+        }
+
+        let v: Vec<AtomicPtr<Quick>> = (0..self.len)
+            .map(|_| AtomicPtr::new(ptr::null_mut()))
+            .collect();
+        let v = Box::into_raw(Box::new(v));
+        match self
+            .slots
+            .compare_exchange(ptr::null_mut(), v, Ordering::AcqRel, Ordering::Acquire)
+        {
+            Ok(_) => unsafe { &*v },
+            Err(cur) => {
+                let _ = unsafe { Box::from_raw(v) };
+                unsafe { &*cur }
+            }
+        }
+    }
+}
+
+impl Drop for QuickTable {
+    fn drop(&mut self) {
+        let slots = *self.slots.get_mut();
+        if slots.is_null() {
+            return;
+        }
+
+        let slots = unsafe { Box::from_raw(slots) };
+        for it in slots.iter() {
+            let v = it.load(Ordering::Relaxed);
+            if !v.is_null() {
+                let _ = unsafe { Box::from_raw(v) };
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct InlineCache {
+    //(class of the receiver, the method selected)
+    entry: AtomicPtr<(ClassRef, MethodIdRef)>,
+}
+
+impl InlineCache {
+    pub fn get(&self, cls: &ClassRef) -> Option<MethodIdRef> {
+        let entry = self.entry.load(Ordering::Acquire);
+        if entry.is_null() {
+            return None;
+        }
+
+        let (cached, mir) = unsafe { &*entry };
+        if Arc::ptr_eq(cached, cls) {
+            Some(mir.clone())
+        } else {
+            None
+        }
+    }
+
+    //only the first receiver kept, a site seen more classes is left to v_table
+    pub fn set(&self, cls: ClassRef, mir: MethodIdRef) {
+        if !self.entry.load(Ordering::Acquire).is_null() {
+            return;
+        }
+
+        let v = Box::into_raw(Box::new((cls, mir)));
+        if self
+            .entry
+            .compare_exchange(ptr::null_mut(), v, Ordering::AcqRel, Ordering::Acquire)
+            .is_err()
+        {
+            let _ = unsafe { Box::from_raw(v) };
+        }
+    }
+}
+
+impl Drop for InlineCache {
+    fn drop(&mut self) {
+        let entry = *self.entry.get_mut();
+        if !entry.is_null() {
+            let _ = unsafe { Box::from_raw(entry) };
+        }
+    }
+}
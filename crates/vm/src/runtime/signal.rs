@@ -0,0 +1,381 @@
+use crate::oop::{self, Class, Oop};
+use crate::runtime::{self, require_class3, vm, JavaCall, JavaThread};
+use crate::types::JavaThreadRef;
+use crate::{new_br, util};
+use classfile::consts::J_THREAD;
+use libc::c_int;
+use std::sync::atomic::Ordering;
+
+/*
+POSIX signals behind sun.misc.Signal
+
+The C handler only writes the signal number to a pipe (async-signal-safe),
+the "Signal Dispatcher" thread reads the pipe, and calls Signal.dispatch,
+just like the dispatcher thread of hotspot.
+
+SIGQUIT is reserved by vm, prints thread dump.
+*/
+
+//values of 'nativeH' of Signal.handle0
+pub const HANDLER_DFL: i64 = 0;
+pub const HANDLER_IGN: i64 = 1;
+pub const HANDLER_JAVA: i64 = 2;
+
+const SIGNALS: &[(&str, c_int)] = &[
+    ("HUP", libc::SIGHUP),
+    ("INT", libc::SIGINT),
+    ("QUIT", libc::SIGQUIT),
+    ("ILL", libc::SIGILL),
+    ("TRAP", libc::SIGTRAP),
+    ("ABRT", libc::SIGABRT),
+    ("BUS", libc::SIGBUS),
+    ("FPE", libc::SIGFPE),
+    ("KILL", libc::SIGKILL),
+    ("USR1", libc::SIGUSR1),
+    ("SEGV", libc::SIGSEGV),
+    ("USR2", libc::SIGUSR2),
+    ("PIPE", libc::SIGPIPE),
+    ("ALRM", libc::SIGALRM),
+    ("TERM", libc::SIGTERM),
+    ("CHLD", libc::SIGCHLD),
+    ("CONT", libc::SIGCONT),
+    ("STOP", libc::SIGSTOP),
+    ("TSTP", libc::SIGTSTP),
+    ("TTIN", libc::SIGTTIN),
+    ("TTOU", libc::SIGTTOU),
+    ("URG", libc::SIGURG),
+    ("XCPU", libc::SIGXCPU),
+    ("XFSZ", libc::SIGXFSZ),
+    ("VTALRM", libc::SIGVTALRM),
+    ("PROF", libc::SIGPROF),
+    ("WINCH", libc::SIGWINCH),
+    ("IO", libc::SIGIO),
+    ("SYS", libc::SIGSYS),
+];
+
+lazy_static! {
+    //(read, write)
+    static ref PIPE: (c_int, c_int) = create_pipe();
+}
+
+//name without "SIG", such as "INT"
+pub fn find(name: &str) -> Option<c_int> {
+    SIGNALS.iter().find(|(n, _)| *n == name).map(|(_, v)| *v)
+}
+
+//return the old handler, Err if the signal can't be caught or used by vm
+pub fn install(sig: c_int, handler: i64) -> Result<i64, ()> {
+    if is_reserved(sig) {
+        return Err(());
+    }
+
+    let new_handler = match handler {
+        HANDLER_DFL => libc::SIG_DFL,
+        HANDLER_IGN => libc::SIG_IGN,
+        HANDLER_JAVA => {
+            lazy_static::initialize(&PIPE);
+            on_signal_addr()
+        }
+        _ => return Err(()),
+    };
+
+    let old = unsafe { set_handler(sig, new_handler)? };
+    let old = if old == libc::SIG_DFL {
+        HANDLER_DFL
+    } else if old == libc::SIG_IGN {
+        HANDLER_IGN
+    } else if old == on_signal_addr() {
+        HANDLER_JAVA
+    } else {
+        old as i64
+    };
+
+    Ok(old)
+}
+
+//called after vm inited, on 'main' thread
+pub fn init() {
+    lazy_static::initialize(&PIPE);
+    unsafe {
+        let _ = set_handler(libc::SIGQUIT, on_signal_addr());
+    }
+
+    let thread_oop = new_dispatcher_thread_oop();
+    let vm = vm::get_vm();
+    let eetop = vm.threads.next_id();
+
+    //daemon, not attached to vm.threads, so 'join_all' not wait for it
+    let r = std::thread::Builder::new()
+        .name("Signal Dispatcher".to_string())
+        .spawn(move || {
+            let jt = JavaThread::new(Some("Signal Dispatcher".to_string()), eetop);
+            runtime::thread::set_current_java_thread(jt.clone());
+
+            {
+                let cls = require_class3(None, J_THREAD).unwrap();
+                let cls = cls.get_class();
+                let fid = cls.get_field_id(&new_br("eetop"), &new_br("J"), false);
+                Class::put_field_value(thread_oop.extract_ref(), fid, Oop::new_long(eetop));
+            }
+
+            {
+                let mut jt = jt.write().unwrap();
+                jt.set_java_thread_obj(thread_oop);
+                jt.is_alive = true;
+            }
+
+            dispatch_loop(jt);
+        });
+
+    if let Err(e) = r {
+        error!("start signal dispatcher failed: {}", e);
+    }
+}
+
+pub fn print_thread_dump() {
+    let vm = vm::get_vm();
+    println!("Full thread dump:");
+    for jt in vm.threads.list().iter() {
+        println!();
+        println!("{}", build_thread_dump(jt));
+    }
+}
+
+fn dispatch_loop(jt: JavaThreadRef) {
+    let dispatch = match oop::class::load_and_init(b"sun/misc/Signal") {
+        Ok(cls) => {
+            let cls = cls.get_class();
+            cls.get_static_method(&new_br("dispatch"), &new_br("(I)V"))
+                .unwrap()
+        }
+        Err(_) => {
+            error!("sun.misc.Signal init failed, no signal dispatched");
+            return;
+        }
+    };
+
+    loop {
+        let sig = match wait_signal() {
+            Some(sig) => sig,
+            None => break,
+        };
+
+        if sig == libc::SIGQUIT {
+            print_thread_dump();
+            continue;
+        }
+
+        let mut jc = JavaCall::new_with_args(dispatch.clone(), vec![Oop::new_int(sig)]);
+        jc.invoke(None, true);
+
+        if runtime::thread::is_meet_ex() {
+            let _ = jt.write().unwrap().take_ex();
+            error!("Signal.dispatch failed, sig = {}", sig);
+        }
+    }
+}
+
+//None if the pipe closed
+fn wait_signal() -> Option<c_int> {
+    let fd = PIPE.0;
+    let mut buf = [0u8; 1];
+
+    loop {
+        let n = unsafe { libc::read(fd, buf.as_mut_ptr() as *mut libc::c_void, 1) };
+        match n {
+            1 => return Some(buf[0] as c_int),
+            -1 if std::io::Error::last_os_error().kind() == std::io::ErrorKind::Interrupted => {
+                continue
+            }
+            _ => return None,
+        }
+    }
+}
+
+fn on_signal_addr() -> libc::sighandler_t {
+    on_signal as extern "C" fn(c_int) as libc::sighandler_t
+}
+
+//errno kept, for the syscall interrupted by the signal
+extern "C" fn on_signal(sig: c_int) {
+    let fd = PIPE.1;
+    let v = sig as u8;
+    unsafe {
+        let errno = errno_location();
+        let saved = *errno;
+        libc::write(fd, &v as *const u8 as *const libc::c_void, 1);
+        *errno = saved;
+    }
+}
+
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+unsafe fn errno_location() -> *mut c_int {
+    libc::__error()
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "ios")))]
+unsafe fn errno_location() -> *mut c_int {
+    libc::__errno_location()
+}
+
+fn is_reserved(sig: c_int) -> bool {
+    match sig {
+        libc::SIGKILL | libc::SIGSTOP => true,
+        //thread dump
+        libc::SIGQUIT => true,
+        //fatal errors, should be reported by vm
+        libc::SIGSEGV | libc::SIGBUS | libc::SIGFPE | libc::SIGILL => true,
+        _ => false,
+    }
+}
+
+unsafe fn set_handler(sig: c_int, handler: libc::sighandler_t) -> Result<libc::sighandler_t, ()> {
+    let mut act: libc::sigaction = std::mem::zeroed();
+    act.sa_sigaction = handler;
+    act.sa_flags = libc::SA_RESTART;
+    libc::sigemptyset(&mut act.sa_mask);
+
+    let mut old: libc::sigaction = std::mem::zeroed();
+    if libc::sigaction(sig, &act, &mut old) == 0 {
+        Ok(old.sa_sigaction)
+    } else {
+        Err(())
+    }
+}
+
+fn create_pipe() -> (c_int, c_int) {
+    let mut fds = [-1 as c_int; 2];
+    unsafe {
+        if libc::pipe(fds.as_mut_ptr()) != 0 {
+            panic!("create signal pipe failed");
+        }
+
+        for fd in fds.iter() {
+            libc::fcntl(*fd, libc::F_SETFD, libc::FD_CLOEXEC);
+        }
+
+        //the handler should never block
+        let flags = libc::fcntl(fds[1], libc::F_GETFL);
+        libc::fcntl(fds[1], libc::F_SETFL, flags | libc::O_NONBLOCK);
+    }
+
+    (fds[0], fds[1])
+}
+
+//java.lang.Thread("Signal Dispatcher"), daemon, in group of 'main' thread
+fn new_dispatcher_thread_oop() -> Oop {
+    let cls = require_class3(None, J_THREAD).unwrap();
+
+    let group = {
+        let jt = runtime::thread::current_java_thread();
+        let main = jt.read().unwrap().java_thread_obj.clone().unwrap();
+        let cls = cls.get_class();
+        let fid = cls.get_field_id(&new_br("group"), &new_br("Ljava/lang/ThreadGroup;"), false);
+        Class::get_field_value(main.extract_ref(), fid)
+    };
+
+    let thread_oop = Oop::new_inst(cls.clone());
+    let args = vec![
+        thread_oop.clone(),
+        group,
+        util::oop::new_java_lang_string2("Signal Dispatcher"),
+    ];
+    runtime::invoke::invoke_ctor(
+        cls.clone(),
+        new_br("(Ljava/lang/ThreadGroup;Ljava/lang/String;)V"),
+        args,
+    );
+
+    {
+        let cls = cls.get_class();
+        let fid = cls.get_field_id(&new_br("daemon"), &new_br("Z"), false);
+        Class::put_field_value(thread_oop.extract_ref(), fid, Oop::new_int(1));
+    }
+
+    thread_oop
+}
+
+fn build_thread_dump(jt: &JavaThreadRef) -> String {
+    let jt = jt.read().unwrap();
+    let name = match &jt.java_thread_obj {
+        Some(obj) => get_thread_name(obj),
+        None => jt.tag.clone(),
+    };
+
+    let mut dump = format!("\"{}\" #{}", name, jt.eetop);
+    for frame in jt.frames.iter().rev() {
+        //frame of running thread may be locked
+        let frame = match frame.try_read() {
+            Ok(frame) => frame,
+            Err(_) => continue,
+        };
+
+        let method = &frame.mir.method;
+        let cls_name = String::from_utf8_lossy(method.cls_name.as_slice()).replace("/", ".");
+        let name = String::from_utf8_lossy(method.name.as_slice());
+        let location = if method.is_native() {
+            "Native Method".to_string()
+        } else {
+            let source = method.class.get_class().get_source_file();
+            let source = source
+                .map(|v| String::from_utf8_lossy(v.as_slice()).to_string())
+                .unwrap_or_else(|| "Unknown Source".to_string());
+            let pc = frame.pc.load(Ordering::Relaxed);
+            format!("{}:{}", source, method.get_line_num(pc as u16))
+        };
+
+        dump.push_str(&format!("\n\tat {}.{}({})", cls_name, name, location));
+    }
+
+    dump
+}
+
+//java.lang.Thread.name, char[]
+fn get_thread_name(obj: &Oop) -> String {
+    let cls = require_class3(None, J_THREAD).unwrap();
+    let cls = cls.get_class();
+    let fid = cls.get_field_id(&new_br("name"), &new_br("[C"), false);
+    let v = Class::get_field_value(obj.extract_ref(), fid);
+    match v {
+        Oop::Null => String::new(),
+        v => {
+            let rf = v.extract_ref();
+            let chars = rf.extract_type_array().extract_chars();
+            String::from_utf16_lossy(chars)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn t_find() {
+        assert_eq!(find("INT"), Some(libc::SIGINT));
+        assert_eq!(find("TERM"), Some(libc::SIGTERM));
+        assert_eq!(find("HUP"), Some(libc::SIGHUP));
+        assert_eq!(find("SIGINT"), None);
+        assert_eq!(find("FOO"), None);
+    }
+
+    #[test]
+    fn t_install_reserved() {
+        assert!(install(libc::SIGKILL, HANDLER_JAVA).is_err());
+        assert!(install(libc::SIGQUIT, HANDLER_JAVA).is_err());
+        assert!(install(libc::SIGUSR2, 100).is_err());
+    }
+
+    #[test]
+    fn t_install_and_raise() {
+        assert_eq!(install(libc::SIGUSR1, HANDLER_JAVA), Ok(HANDLER_DFL));
+        assert_eq!(install(libc::SIGUSR1, HANDLER_JAVA), Ok(HANDLER_JAVA));
+
+        unsafe {
+            libc::raise(libc::SIGUSR1);
+        }
+        assert_eq!(wait_signal(), Some(libc::SIGUSR1));
+
+        assert_eq!(install(libc::SIGUSR1, HANDLER_DFL), Ok(HANDLER_JAVA));
+    }
+}
@@ -1,18 +1,218 @@
-use crate::oop::Oop;
-
-#[derive(Debug, Clone)]
-pub enum Slot {
-    ConstM1,
-    Const0,
-    Const1,
-    Const2,
-    Const3,
-    Const4,
-    Const5,
-    I32(i32),
-    F32(f32),
-    F64(f64),
-    I64(i64),
-    Ref(Oop),
-    Nop, //for Stack long, double
+use crate::oop::{Oop, OopRef};
+use std::mem::ManuallyDrop;
+use std::sync::{Arc, Mutex};
+
+/*
+Slots of locals & operand stacks
+
+A slot is untagged 64 bits: int & float by their bits, long & double in one
+slot (the second of the two slots JVMS 2.6.1 gives them is left unused), a
+reference by the pointer of an Arc<OopRef> owned by the slot, 0 for null.
+
+The reference map, a flag for each slot, tells the slots holding a
+reference. They are released when overwritten, popped or the frame returns,
+and are the roots of the frames for GC.
+
+The slots of a thread are one contiguous stack, in chunks. A frame takes a
+window of max_locals + max_stack slots, returned when the frame is dropped,
+in LIFO order as the frames of the thread are.
+*/
+
+pub type Slot = u64;
+
+const CHUNK_SLOTS: usize = 64 * 1024;
+
+thread_local! {
+    static THREAD_SLOTS: Arc<Mutex<SlotStack>> = Arc::new(Mutex::new(SlotStack::default()));
+}
+
+#[derive(Default)]
+struct SlotStack {
+    chunks: Vec<Chunk>,
+}
+
+struct Chunk {
+    slots: Box<[Slot]>,
+    refs: Box<[bool]>,
+    top: usize,
+    //windows returned out of order, (start, end)
+    freed: Vec<(usize, usize)>,
+}
+
+impl Chunk {
+    fn new(len: usize) -> Self {
+        Self {
+            slots: vec![0; len].into_boxed_slice(),
+            refs: vec![false; len].into_boxed_slice(),
+            top: 0,
+            freed: vec![],
+        }
+    }
+}
+
+impl SlotStack {
+    //(chunk, start), in the chunk of the top frame, or the next one if full
+    fn alloc(&mut self, n: usize) -> (usize, usize) {
+        let mut i = self.chunks.iter().rposition(|c| c.top > 0).unwrap_or(0);
+        loop {
+            if i == self.chunks.len() {
+                self.chunks.push(Chunk::new(CHUNK_SLOTS.max(n)));
+            }
+
+            let chunk = &mut self.chunks[i];
+            if chunk.top + n <= chunk.slots.len() {
+                let start = chunk.top;
+                chunk.top += n;
+                return (i, start);
+            }
+
+            if chunk.top == 0 {
+                //unused, but too small
+                *chunk = Chunk::new(CHUNK_SLOTS.max(n));
+            } else {
+                i += 1;
+            }
+        }
+    }
+
+    fn release(&mut self, chunk: usize, start: usize, len: usize) {
+        let chunk = &mut self.chunks[chunk];
+        let end = start + len;
+        if end != chunk.top {
+            chunk.freed.push((start, end));
+            return;
+        }
+
+        chunk.top = start;
+        while let Some(i) = chunk.freed.iter().position(|(_, end)| *end == chunk.top) {
+            let (start, _) = chunk.freed.swap_remove(i);
+            chunk.top = start;
+        }
+    }
+}
+
+//the slots of a frame
+pub struct Window {
+    owner: Arc<Mutex<SlotStack>>,
+    chunk: usize,
+    start: usize,
+    len: usize,
+    slots: *mut Slot,
+    refs: *mut bool,
+}
+
+impl Window {
+    pub fn new(len: usize) -> Self {
+        let owner = THREAD_SLOTS.with(|it| it.clone());
+        let (chunk, start, slots, refs) = {
+            let mut stack = owner.lock().unwrap();
+            let (chunk, start) = stack.alloc(len);
+            let c = &mut stack.chunks[chunk];
+            let slots = unsafe { c.slots.as_mut_ptr().add(start) };
+            let refs = unsafe { c.refs.as_mut_ptr().add(start) };
+            (chunk, start, slots, refs)
+        };
+
+        Self {
+            owner,
+            chunk,
+            start,
+            len,
+            slots,
+            refs,
+        }
+    }
+
+    //(slots, refs) from 'offset'
+    pub fn ptrs(&self, offset: usize) -> (*mut Slot, *mut bool) {
+        debug_assert!(offset <= self.len);
+        unsafe { (self.slots.add(offset), self.refs.add(offset)) }
+    }
+}
+
+impl Drop for Window {
+    fn drop(&mut self) {
+        for i in 0..self.len {
+            unsafe {
+                let is_ref = self.refs.add(i);
+                if *is_ref {
+                    release_ref(*self.slots.add(i));
+                    *is_ref = false;
+                }
+            }
+        }
+
+        let mut stack = self.owner.lock().unwrap();
+        stack.release(self.chunk, self.start, self.len);
+    }
+}
+
+//(slot, is_ref), the slot owns the reference
+pub fn from_ref(v: Oop) -> (Slot, bool) {
+    match v {
+        Oop::Null => (0, false),
+        Oop::Ref(rf) => (Arc::into_raw(rf) as Slot, true),
+        Oop::ConstUtf8(s) => from_ref(crate::util::oop::new_java_lang_string3(s.as_slice())),
+        t => panic!("Illegal type = {:?}", t),
+    }
+}
+
+//the reference moved out of the slot
+pub unsafe fn take_ref(v: Slot) -> Oop {
+    Oop::Ref(Arc::from_raw(v as *const OopRef))
+}
+
+//the slot keeps its reference
+pub unsafe fn clone_ref(v: Slot) -> Oop {
+    let rf = ManuallyDrop::new(Arc::from_raw(v as *const OopRef));
+    Oop::Ref((*rf).clone())
+}
+
+//a copy of the slot for dup, one more count of a reference
+pub unsafe fn dup_slot(v: Slot, is_ref: bool) -> Slot {
+    if is_ref {
+        let rf = ManuallyDrop::new(Arc::from_raw(v as *const OopRef));
+        Arc::into_raw((*rf).clone()) as Slot
+    } else {
+        v
+    }
+}
+
+pub unsafe fn release_ref(v: Slot) {
+    let _ = Arc::from_raw(v as *const OopRef);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn t_lifo_windows() {
+        let a = Window::new(10);
+        let b = Window::new(20);
+        let (pa, _) = a.ptrs(0);
+        let (pb, _) = b.ptrs(0);
+        assert_eq!(unsafe { pa.add(10) }, pb);
+
+        //a returned out of order, reused once the windows above it returned
+        drop(a);
+        let c = Window::new(5);
+        let (pc, _) = c.ptrs(0);
+        assert_eq!(unsafe { pb.add(20) }, pc);
+        drop(b);
+        drop(c);
+
+        let d = Window::new(1);
+        let (pd, _) = d.ptrs(0);
+        assert_eq!(pa, pd);
+    }
+
+    #[test]
+    fn t_large_window() {
+        let a = Window::new(CHUNK_SLOTS - 1);
+        let b = Window::new(CHUNK_SLOTS + 1);
+        let (pa, _) = a.ptrs(0);
+        let (pb, _) = b.ptrs(0);
+        assert_ne!(unsafe { pa.add(CHUNK_SLOTS - 1) }, pb);
+    }
 }
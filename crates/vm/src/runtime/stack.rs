@@ -1,278 +1,258 @@
-use crate::oop::{consts, Oop};
-use crate::runtime::Slot;
+use crate::oop::Oop;
+use crate::runtime::slot::{self, Slot};
+use std::cell::Cell;
 
-#[derive(Debug)]
+//the operand stack of a frame, on the slots of DataArea
 pub struct Stack {
-    inner: Vec<Slot>,
+    slots: *mut Slot,
+    refs: *mut bool,
+    cap: usize,
+    top: Cell<usize>,
 }
 
 impl Stack {
-    pub fn new(size: usize) -> Self {
+    pub fn new(slots: *mut Slot, refs: *mut bool, cap: usize) -> Self {
         Self {
-            inner: Vec::with_capacity(size),
+            slots,
+            refs,
+            cap,
+            top: Cell::new(0),
         }
     }
 
     #[inline]
-    pub fn push_int(&mut self, v: i32) {
-        self.inner.push(Slot::I32(v));
+    pub fn push_int(&self, v: i32) {
+        self.push(v as u32 as Slot, false);
     }
 
     #[inline]
-    pub fn push_int2(&mut self, v: &[u8; 4]) {
+    pub fn push_int2(&self, v: &[u8; 4]) {
         let v = i32::from_be_bytes(*v);
-        self.inner.push(Slot::I32(v));
+        self.push_int(v);
     }
 
     #[inline]
-    pub fn push_float(&mut self, v: f32) {
-        self.inner.push(Slot::F32(v));
+    pub fn push_float(&self, v: f32) {
+        self.push(v.to_bits() as Slot, false);
     }
 
     #[inline]
-    pub fn push_float2(&mut self, v: &[u8; 4]) {
+    pub fn push_float2(&self, v: &[u8; 4]) {
         let v = u32::from_be_bytes(*v);
         let v = f32::from_bits(v);
-        self.inner.push(Slot::F32(v));
+        self.push_float(v);
     }
 
     #[inline]
-    pub fn push_double(&mut self, v: f64) {
-        self.push_nop();
-        self.inner.push(Slot::F64(v));
+    pub fn push_double(&self, v: f64) {
+        self.push(0, false);
+        self.push(v.to_bits(), false);
     }
 
     #[inline]
-    pub fn push_double2(&mut self, v: &[u8; 8]) {
+    pub fn push_double2(&self, v: &[u8; 8]) {
         let v = u64::from_be_bytes(*v);
         let v = f64::from_bits(v);
         self.push_double(v);
     }
 
     #[inline]
-    pub fn push_long(&mut self, v: i64) {
-        self.push_nop();
-        self.inner.push(Slot::I64(v));
+    pub fn push_long(&self, v: i64) {
+        self.push(0, false);
+        self.push(v as Slot, false);
     }
 
     #[inline]
-    pub fn push_long2(&mut self, v: &[u8; 8]) {
+    pub fn push_long2(&self, v: &[u8; 8]) {
         let v = i64::from_be_bytes(*v);
         self.push_long(v);
     }
 
     #[inline]
-    pub fn push_null(&mut self) {
-        self.inner.push(Slot::Ref(consts::get_null()));
+    pub fn push_null(&self) {
+        self.push(0, false);
     }
 
     #[inline]
-    pub fn push_const_m1(&mut self) {
-        self.inner.push(Slot::ConstM1);
+    pub fn push_ref(&self, v: Oop) {
+        let (v, is_ref) = slot::from_ref(v);
+        self.push(v, is_ref);
     }
 
-    /*
-    double & long, with_nop = true
-    */
     #[inline]
-    pub fn push_const0(&mut self, with_nop: bool) {
-        if with_nop {
-            self.push_nop();
-        }
-        self.inner.push(Slot::Const0);
+    pub fn pop_int(&self) -> i32 {
+        self.pop_value() as u32 as i32
     }
 
-    /*
-       double & long, with_nop = true
-    */
     #[inline]
-    pub fn push_const1(&mut self, with_nop: bool) {
-        if with_nop {
-            self.push_nop();
-        }
-        self.inner.push(Slot::Const1);
+    pub fn pop_float(&self) -> f32 {
+        f32::from_bits(self.pop_value() as u32)
     }
 
     #[inline]
-    pub fn push_const2(&mut self) {
-        self.inner.push(Slot::Const2);
+    pub fn pop_double(&self) -> f64 {
+        let v = self.pop_value();
+        self.pop_value();
+        f64::from_bits(v)
     }
 
     #[inline]
-    pub fn push_const3(&mut self) {
-        self.inner.push(Slot::Const3);
+    pub fn pop_long(&self) -> i64 {
+        let v = self.pop_value();
+        self.pop_value();
+        v as i64
     }
 
     #[inline]
-    pub fn push_const4(&mut self) {
-        self.inner.push(Slot::Const4);
+    pub fn pop_ref(&self) -> Oop {
+        let (v, is_ref) = self.pop();
+        if is_ref {
+            unsafe { slot::take_ref(v) }
+        } else {
+            debug_assert_eq!(v, 0, "Illegal type");
+            Oop::Null
+        }
     }
 
-    #[inline]
-    pub fn push_const5(&mut self) {
-        self.inner.push(Slot::Const5);
+    //the depth, for the compiled code working on the slots directly
+    pub fn top(&self) -> usize {
+        self.top.get()
     }
 
-    #[inline]
-    pub fn push_ref(&mut self, v: Oop) {
-        self.inner.push(Slot::Ref(v));
+    //the slots from 'n' up hold no reference, as the compiled code leaves them
+    pub fn set_top(&self, n: usize) {
+        assert!(n <= self.cap, "Stack overflow, max_stack = {}", self.cap);
+        debug_assert!((n..self.cap).all(|i| unsafe { !*self.refs.add(i) }));
+        self.top.set(n);
     }
 
-    #[inline]
-    pub fn pop_int(&mut self) -> i32 {
-        match self.inner.pop().unwrap() {
-            Slot::ConstM1 => -1,
-            Slot::Const0 => 0,
-            Slot::Const1 => 1,
-            Slot::Const2 => 2,
-            Slot::Const3 => 3,
-            Slot::Const4 => 4,
-            Slot::Const5 => 5,
-            Slot::I32(v) => v,
-            _ => panic!("Illegal type"),
-        }
+    pub fn drop_top(&self) {
+        self.pop_value();
     }
 
-    #[inline]
-    pub fn pop_float(&mut self) -> f32 {
-        match self.inner.pop().unwrap() {
-            Slot::Const0 => 0.0,
-            Slot::Const1 => 1.0,
-            Slot::Const2 => 2.0,
-            Slot::F32(v) => v,
-            _ => panic!("Illegal type"),
+    pub fn clear(&self) {
+        while self.top.get() > 0 {
+            self.pop_value();
         }
     }
 
     #[inline]
-    pub fn pop_double(&mut self) -> f64 {
-        match self.inner.pop() {
-            Some(v) => {
-                self.pop_nop();
-                match v {
-                    Slot::Const0 => 0.0,
-                    Slot::Const1 => 1.0,
-                    Slot::F64(v) => v,
-                    _ => panic!("Illegal type"),
-                }
-            }
-            None => panic!("Empty Stack!"),
-        }
+    pub fn dup(&self) {
+        let v = self.peek(1);
+        self.push_dup(v);
     }
 
     #[inline]
-    pub fn pop_long(&mut self) -> i64 {
-        match self.inner.pop() {
-            Some(v) => {
-                self.pop_nop();
-                match v {
-                    Slot::Const0 => 0,
-                    Slot::Const1 => 1,
-                    Slot::I64(v) => v,
-                    _ => panic!("Illegal type"),
-                }
-            }
-            _ => panic!("Empty Stack!"),
-        }
+    pub fn dup_x1(&self) {
+        let v1 = self.pop();
+        let v2 = self.pop();
+        self.push_dup(v1);
+        self.push(v2.0, v2.1);
+        self.push(v1.0, v1.1);
     }
 
     #[inline]
-    pub fn pop_ref(&mut self) -> Oop {
-        match self.inner.pop() {
-            Some(Slot::Ref(v)) => v,
-            t => panic!("Illegal type = {:?}", t),
-        }
-    }
-
-    pub fn drop_top(&mut self) {
-        let _ = self.inner.pop();
-    }
-
-    pub fn clear(&mut self) {
-        self.inner.clear();
+    pub fn dup_x2(&self) {
+        let v1 = self.pop();
+        let v2 = self.pop();
+        let v3 = self.pop();
+        self.push_dup(v1);
+        self.push(v3.0, v3.1);
+        self.push(v2.0, v2.1);
+        self.push(v1.0, v1.1);
     }
 
     #[inline]
-    pub fn dup(&mut self) {
-        let v = self.inner.pop().unwrap();
-        self.inner.push(v.clone());
-        self.inner.push(v);
+    pub fn dup2(&self) {
+        let v2 = self.peek(2);
+        let v1 = self.peek(1);
+        self.push_dup(v2);
+        self.push_dup(v1);
     }
 
     #[inline]
-    pub fn dup_x1(&mut self) {
-        let v1 = self.inner.pop().unwrap();
-        let v2 = self.inner.pop().unwrap();
-        self.inner.push(v1.clone());
-        self.inner.push(v2);
-        self.inner.push(v1);
+    pub fn dup2_x1(&self) {
+        let v1 = self.pop();
+        let v2 = self.pop();
+        let v3 = self.pop();
+        self.push_dup(v2);
+        self.push_dup(v1);
+        self.push(v3.0, v3.1);
+        self.push(v2.0, v2.1);
+        self.push(v1.0, v1.1);
     }
 
     #[inline]
-    pub fn dup_x2(&mut self) {
-        let v1 = self.inner.pop().unwrap();
-        let v2 = self.inner.pop().unwrap();
-        let v3 = self.inner.pop().unwrap();
-        self.inner.push(v1.clone());
-        self.inner.push(v3);
-        self.inner.push(v2);
-        self.inner.push(v1);
+    pub fn dup2_x2(&self) {
+        let v1 = self.pop();
+        let v2 = self.pop();
+        let v3 = self.pop();
+        let v4 = self.pop();
+        self.push_dup(v2);
+        self.push_dup(v1);
+        self.push(v4.0, v4.1);
+        self.push(v3.0, v3.1);
+        self.push(v2.0, v2.1);
+        self.push(v1.0, v1.1);
     }
 
     #[inline]
-    pub fn dup2(&mut self) {
-        let v1 = self.inner.pop().unwrap();
-        let v2 = self.inner.pop().unwrap();
-        self.inner.push(v2.clone());
-        self.inner.push(v1.clone());
-        self.inner.push(v2);
-        self.inner.push(v1);
+    pub fn swap(&self) {
+        let v1 = self.pop();
+        let v2 = self.pop();
+        self.push(v1.0, v1.1);
+        self.push(v2.0, v2.1);
     }
+}
 
+impl Stack {
     #[inline]
-    pub fn dup2_x1(&mut self) {
-        let v1 = self.inner.pop().unwrap();
-        let v2 = self.inner.pop().unwrap();
-        let v3 = self.inner.pop().unwrap();
-        self.inner.push(v2.clone());
-        self.inner.push(v1.clone());
-        self.inner.push(v3);
-        self.inner.push(v2);
-        self.inner.push(v1);
+    fn push(&self, v: Slot, is_ref: bool) {
+        let top = self.top.get();
+        assert!(top < self.cap, "Stack overflow, max_stack = {}", self.cap);
+        unsafe {
+            *self.slots.add(top) = v;
+            *self.refs.add(top) = is_ref;
+        }
+        self.top.set(top + 1);
     }
 
+    //the reference, if any, moved to the caller
     #[inline]
-    pub fn dup2_x2(&mut self) {
-        let v1 = self.inner.pop().unwrap();
-        let v2 = self.inner.pop().unwrap();
-        let v3 = self.inner.pop().unwrap();
-        let v4 = self.inner.pop().unwrap();
-        self.inner.push(v2.clone());
-        self.inner.push(v1.clone());
-        self.inner.push(v4);
-        self.inner.push(v3);
-        self.inner.push(v2);
-        self.inner.push(v1);
+    fn pop(&self) -> (Slot, bool) {
+        let top = self.top.get();
+        assert!(top > 0, "Empty Stack!");
+        let top = top - 1;
+        self.top.set(top);
+        unsafe {
+            let is_ref = self.refs.add(top);
+            let v = (*self.slots.add(top), *is_ref);
+            *is_ref = false;
+            v
+        }
     }
 
+    //a reference popped as a value is released
     #[inline]
-    pub fn swap(&mut self) {
-        let v1 = self.inner.pop().unwrap();
-        let v2 = self.inner.pop().unwrap();
-        self.inner.push(v1);
-        self.inner.push(v2);
+    fn pop_value(&self) -> Slot {
+        let (v, is_ref) = self.pop();
+        if is_ref {
+            unsafe { slot::release_ref(v) };
+        }
+        v
     }
-}
 
-impl Stack {
-    fn push_nop(&mut self) {
-        self.inner.push(Slot::Nop);
+    //the n-th from top, 1 for the top
+    #[inline]
+    fn peek(&self, n: usize) -> (Slot, bool) {
+        let top = self.top.get();
+        assert!(top >= n, "Empty Stack!");
+        unsafe { (*self.slots.add(top - n), *self.refs.add(top - n)) }
     }
 
-    fn pop_nop(&mut self) {
-        match self.inner.pop() {
-            Some(Slot::Nop) => (),
-            _ => panic!("Should be Nop!"),
-        }
+    #[inline]
+    fn push_dup(&self, (v, is_ref): (Slot, bool)) {
+        let v = unsafe { slot::dup_slot(v, is_ref) };
+        self.push(v, is_ref);
     }
 }
@@ -1,13 +1,27 @@
+use crate::oop::class::ClassPtr;
+use crate::oop::{Oop, OopRef};
 use crate::types::ClassRef;
 use crate::util;
 
 use rustc_hash::FxHashMap;
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, Weak};
 
 type SystemDictionary = Mutex<FxHashMap<String, ClassRef>>;
 
+/*
+By loader id, the loader object & the classes it defined, both weak: a loader is
+kept alive by its classes (the classLoader of their mirrors), as in Java, not by
+the dictionary. The allocation of a loader freed is kept by the Weak, so its id
+is not reused until the entry dropped, by the next put_defined.
+
+A class & its mirror refer to each other, never freed (see heap), so a loader
+that defined a class is never freed either.
+*/
+type LoaderDictionary = Mutex<FxHashMap<u64, (Weak<OopRef>, FxHashMap<String, Weak<ClassPtr>>)>>;
+
 lazy_static! {
     static ref SYS_DIC: SystemDictionary = { Mutex::new(FxHashMap::default()) };
+    static ref LOADER_DIC: LoaderDictionary = { Mutex::new(FxHashMap::default()) };
 }
 
 pub fn put(key: &[u8], klass: ClassRef) {
@@ -27,6 +41,62 @@ pub fn find(key: &[u8]) -> Option<ClassRef> {
     dict.get(key).cloned()
 }
 
+//the id of a java.lang.ClassLoader object, see ClassLoader::Defined
+pub fn loader_id(loader: &Oop) -> u64 {
+    loader.extract_ref().get_raw_ptr() as u64
+}
+
+//a class defined by the 'loader' object, false if it defined one of 'key' before
+pub fn put_defined(loader: &Oop, key: &[u8], klass: ClassRef) -> bool {
+    debug_assert!(!key.contains(&b'.'));
+    let key = String::from_utf8_lossy(key).to_string();
+    let mut dict = LOADER_DIC.lock().unwrap();
+    dict.retain(|_, (loader, _)| loader.strong_count() > 0);
+    let (_, classes) = dict
+        .entry(loader_id(loader))
+        .or_insert_with(|| (Arc::downgrade(&loader.extract_ref()), FxHashMap::default()));
+    if classes.get(&key).is_some_and(|it| it.strong_count() > 0) {
+        return false;
+    }
+    classes.insert(key, Arc::downgrade(&klass));
+    true
+}
+
+pub fn find_defined(loader_id: u64, key: &[u8]) -> Option<ClassRef> {
+    debug_assert!(!key.contains(&b'.'));
+    let key = unsafe { std::str::from_utf8_unchecked(key) };
+    let dict = LOADER_DIC.lock().unwrap();
+    dict.get(&loader_id)
+        .and_then(|(_, classes)| classes.get(key))
+        .and_then(|it| it.upgrade())
+}
+
 pub fn init() {
     lazy_static::initialize(&SYS_DIC);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{find_defined, loader_id, put_defined};
+    use crate::oop::class::tests::{class_file, define};
+    use crate::oop::Oop;
+    use classfile::flags::ACC_PUBLIC;
+    use std::sync::Arc;
+
+    #[test]
+    fn t_loader_not_kept() {
+        let object = Some("java/lang/Object");
+        let class = define(&class_file(ACC_PUBLIC, "t/dic/A", object, &[], &[], &[]));
+        //any object stands for the loader
+        let loader = Oop::new_int_ary(1);
+        let id = loader_id(&loader);
+
+        assert!(put_defined(&loader, b"t/dic/A", class.clone()));
+        assert!(!put_defined(&loader, b"t/dic/A", class.clone()));
+        assert!(Arc::ptr_eq(&find_defined(id, b"t/dic/A").unwrap(), &class));
+
+        let weak = Arc::downgrade(&loader.extract_ref());
+        drop(loader);
+        assert!(weak.upgrade().is_none());
+    }
+}
@@ -1,26 +1,45 @@
-use crate::oop::{self, consts, Oop};
+use crate::new_br;
+use crate::oop::{self, consts, Class, Oop};
+use crate::runtime::require_class3;
 use crate::types::{FrameRef, JavaThreadRef};
-use std::cell::RefCell;
+use classfile::consts::J_THREAD;
+use std::cell::{Cell, RefCell};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, RwLock};
 
 thread_local! {
     pub static THREAD: RefCell<JavaThreadRef> = RefCell::new(JavaThread::main());
-    pub static IS_MEET_EX: AtomicBool = AtomicBool::new(false);
+    //'ex_pending' of THREAD, read without its lock
+    static EX_PENDING: RefCell<Arc<AtomicBool>> =
+        RefCell::new(THREAD.with(|t| t.borrow().read().unwrap().ex_pending.clone()));
+    static IS_VM_THREAD: Cell<bool> = const { Cell::new(false) };
 }
 
 pub fn current_java_thread() -> JavaThreadRef {
     THREAD.with(|t| t.borrow().clone())
 }
 
-#[inline]
-pub fn is_meet_ex() -> bool {
-    IS_MEET_EX.with(|v| v.load(Ordering::Relaxed))
+//the JavaThread run by this os thread
+pub fn set_current_java_thread(jt: JavaThreadRef) {
+    let ex_pending = jt.read().unwrap().ex_pending.clone();
+    EX_PENDING.with(|it| *it.borrow_mut() = ex_pending);
+    THREAD.with(|t| *t.borrow_mut() = jt);
+    mark_vm_thread();
+}
+
+//an os thread running a JavaThread, attached to the vm
+pub fn mark_vm_thread() {
+    IS_VM_THREAD.with(|it| it.set(true));
+}
+
+pub fn is_vm_thread() -> bool {
+    IS_VM_THREAD.with(|it| it.get())
 }
 
+//the exception pending of the current thread, checked by every bytecode, so no lock
 #[inline]
-fn set_meet_ex(val: bool) {
-    IS_MEET_EX.with(|v| v.store(val, Ordering::Relaxed));
+pub fn is_meet_ex() -> bool {
+    EX_PENDING.with(|it| it.borrow().load(Ordering::Acquire))
 }
 
 pub struct JavaThread {
@@ -28,9 +47,14 @@ pub struct JavaThread {
     in_safe_point: bool,
 
     pub java_thread_obj: Option<Oop>,
-    pub ex: Option<Oop>,
+    //the exception pending, thrown & not caught yet
+    ex: Option<Oop>,
+    //mirror of 'ex.is_some()', by set_ex & take_ex
+    ex_pending: Arc<AtomicBool>,
     pub is_alive: bool,
     pub eetop: i64,
+    //java.lang.Thread.daemon of java_thread_obj, fixed once started
+    daemon: bool,
 
     pub tag: String, //for debug
 }
@@ -44,8 +68,10 @@ impl JavaThread {
 
             java_thread_obj: None,
             ex: None,
+            ex_pending: Arc::new(AtomicBool::new(false)),
             is_alive: false,
             eetop,
+            daemon: false,
             tag,
         };
         Arc::new(RwLock::new(Box::new(t)))
@@ -55,20 +81,37 @@ impl JavaThread {
         JavaThread::new(Some("main".to_string()), 0)
     }
 
+    //the daemon flag read once: Thread.setDaemon throws once the thread started
     pub fn set_java_thread_obj(&mut self, obj: Oop) {
+        let cls = require_class3(None, J_THREAD).unwrap();
+        let fid = {
+            let cls = cls.get_class();
+            cls.get_field_id(&new_br("daemon"), &new_br("Z"), false)
+        };
+        self.daemon = Class::get_field_value(obj.extract_ref(), fid).extract_int() != 0;
         self.java_thread_obj = Some(obj);
     }
+
+    //vm not wait for daemon threads when exit
+    pub fn is_daemon(&self) -> bool {
+        self.daemon
+    }
 }
 
 //exception
 impl JavaThread {
     pub fn set_ex(&mut self, ex: Oop) {
-        set_meet_ex(true);
         self.ex = Some(ex);
+        self.ex_pending.store(true, Ordering::Release);
     }
 
     pub fn take_ex(&mut self) -> Option<Oop> {
-        set_meet_ex(false);
+        self.ex_pending.store(false, Ordering::Release);
         self.ex.take()
     }
+
+    #[inline]
+    pub fn has_ex(&self) -> bool {
+        self.ex.is_some()
+    }
 }
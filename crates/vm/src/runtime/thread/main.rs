@@ -1,5 +1,4 @@
 use crate::oop::{self, Class, Oop, OopRef};
-use crate::runtime::thread::thread_pool;
 use crate::runtime::{self, init_vm, vm, DataArea, JavaCall, JavaThread};
 use crate::types::{ClassRef, FrameRef, JavaThreadRef, MethodIdRef};
 use crate::{new_br, util};
@@ -20,8 +19,9 @@ impl MainThread {
         }
     }
 
-    pub fn run(&mut self) {
-        let vm = vm::VM::new(3);
+    //return the exit code of vm
+    pub fn run(&mut self) -> i32 {
+        let vm = vm::VM::new();
 
         //attach 'main' thread
         vm.threads.attach_current_thread();
@@ -30,13 +30,16 @@ impl MainThread {
         init_vm::initialize_jvm();
         info!("init vm end");
 
-        let main_class = oop::class::load_and_init(self.class.as_bytes());
+        let main_class = match runtime::require_class3(None, self.class.as_bytes()) {
+            Some(class) => class,
+            None => panic!("Error: Could not find or load main class {}", self.class),
+        };
 
         let mir = {
             let cls = main_class.get_class();
 
             /*
-            path info should be included in "--cp", and avoid same class load 2
+            path info should be included in "-cp", and avoid same class load 2
             times, otherwise, "<clinit>" invoked 2 times.
 
             For example:
@@ -44,12 +47,12 @@ impl MainThread {
                 private static File gf = newFile();
 
             if allowed, as follows:
-              "cargo run -- --cp $JDK:$MY_TEST test/with_package/my.ns.HelloWorld"
+              "cargo run -- -cp $MY_TEST test/with_package/my.ns.HelloWorld"
             will cause "<clinit>" invoked 2 times, "newFile()" invoked 2 times,
             maybe create 2 files.
 
             should be like this:
-              "cargo run -- --cp $JDK:$MY_TEST:test/with_package my.ns.HelloWorld"
+              "cargo run -- -cp $MY_TEST:test/with_package my.ns.HelloWorld"
             */
             if self.class.as_bytes() != cls.name.as_slice() {
                 panic!("Error: Could not find or load main class {}", self.class);
@@ -59,8 +62,9 @@ impl MainThread {
         };
 
         let jt = runtime::thread::current_java_thread();
-        match mir {
-            Ok(mir) => {
+        match (oop::class::init_class(&main_class), mir) {
+            (Err(ex), _) => jt.write().unwrap().set_ex(ex),
+            (Ok(_), Ok(mir)) => {
                 let args = self.build_main_arg();
                 let mut jc = JavaCall::new_with_args(mir, args);
                 jt.write().unwrap().is_alive = true;
@@ -70,14 +74,15 @@ impl MainThread {
             _ => unreachable!("NotFound \"main\""),
         }
 
-        if jt.read().unwrap().ex.is_some() {
+        if jt.read().unwrap().has_ex() {
             self.uncaught_ex(main_class);
+            vm.set_exit_code(1);
         }
 
         //detach main thread
         vm.threads.detach_current_thread();
 
-        vm.threads.join_all();
+        vm.destroy()
     }
 }
 
@@ -2,17 +2,18 @@ mod condvar;
 mod java_thread;
 mod main;
 mod mutex;
-mod thread_pool;
 mod threads;
 
 pub use condvar::Condvar;
 pub use java_thread::current_java_thread;
+pub use java_thread::is_vm_thread;
+pub use java_thread::mark_vm_thread;
+pub use java_thread::set_current_java_thread;
 pub use java_thread::JavaThread;
 pub use java_thread::THREAD;
 pub use main::MainThread;
 pub use mutex::raw as mutex_raw;
 pub use mutex::ReentrantMutex;
-pub use thread_pool::ThreadPool;
 pub use threads::Threads;
 
 pub use java_thread::is_meet_ex;
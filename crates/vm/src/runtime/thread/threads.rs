@@ -1,21 +1,19 @@
 use crate::runtime;
-use crate::runtime::thread::ThreadPool;
 use crate::types::JavaThreadRef;
 use std::borrow::Borrow;
 use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
 use std::sync::{Arc, Condvar, Mutex};
 
 pub struct Threads {
-    pool: Mutex<ThreadPool>,
-    threads: Mutex<Vec<JavaThreadRef>>,
+    //the threads attached, each with its daemon flag
+    threads: Mutex<Vec<(JavaThreadRef, bool)>>,
     cond_join: Condvar,
     next_id: AtomicI64,
 }
 
 impl Threads {
-    pub fn new(thread_pool_count: usize) -> Threads {
+    pub fn new() -> Threads {
         Threads {
-            pool: Mutex::new(ThreadPool::new(thread_pool_count)),
             threads: Mutex::new(Vec::new()),
             cond_join: Condvar::new(),
             next_id: AtomicI64::new(1),
@@ -23,49 +21,69 @@ impl Threads {
     }
 }
 
+impl Default for Threads {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Threads {
     pub fn next_id(&self) -> i64 {
         self.next_id.fetch_add(1, Ordering::SeqCst)
     }
 
     pub fn attach_current_thread(&self) {
-        runtime::thread::THREAD.with(|thread| {
-            let mut threads = self.threads.lock().unwrap();
-            threads.push(thread.borrow().clone());
-        });
+        runtime::thread::mark_vm_thread();
+        self.attach_java_thread(runtime::thread::current_java_thread());
     }
 
+    //its java_thread_obj set before, the daemon flag recorded
     pub fn attach_java_thread(&self, thread: JavaThreadRef) {
+        let daemon = thread.read().unwrap().is_daemon();
         let mut threads = self.threads.lock().unwrap();
-        threads.push(thread);
+        threads.push((thread, daemon));
     }
 
     pub fn detach_current_thread(&self) {
-        runtime::thread::THREAD.with(|thread| {
-            let mut threads = self.threads.lock().unwrap();
-            threads.retain(|elem| !Arc::ptr_eq(elem, &*thread.borrow()));
-            self.cond_join.notify_all();
-        });
+        self.detach_java_thread(&runtime::thread::current_java_thread());
+    }
+
+    pub fn detach_java_thread(&self, thread: &JavaThreadRef) {
+        let mut threads = self.threads.lock().unwrap();
+        threads.retain(|(elem, _)| !Arc::ptr_eq(elem, thread));
+        self.cond_join.notify_all();
     }
 
     pub fn find_java_thread(&self, eetop: i64) -> Option<JavaThreadRef> {
         let mut threads = self.threads.lock().unwrap();
         threads
             .iter()
+            .map(|(t, _)| t)
             .find(|t| t.read().unwrap().eetop == eetop)
             .cloned()
     }
 
+    pub fn list(&self) -> Vec<JavaThreadRef> {
+        let threads = self.threads.lock().unwrap();
+        threads.iter().map(|(t, _)| t.clone()).collect()
+    }
+
+    //wait all non-daemon threads, by the flags recorded, no JavaThread locked
     pub fn join_all(&self) {
         let mut threads = self.threads.lock().unwrap();
 
-        while threads.len() > 0 {
+        while threads.iter().any(|(_, daemon)| !daemon) {
             threads = self.cond_join.wait(threads).unwrap();
         }
     }
 
-    pub fn spawn_java_thread<F: FnOnce() + Send + 'static>(&self, f: F) {
-        let pool = self.pool.lock().unwrap();
-        pool.execute(f);
+    //an os thread for each java thread, of the stack size of -Xss if given,
+    //not joined: the daemon threads still running end with the process
+    pub fn spawn_java_thread<F: FnOnce() + Send + 'static>(&self, f: F) -> std::io::Result<()> {
+        let mut builder = std::thread::Builder::new();
+        if let Some(size) = runtime::options::with(|opts| opts.thread_stack_size) {
+            builder = builder.stack_size(size);
+        }
+        builder.spawn(f).map(|_| ())
     }
 }
@@ -1,5 +1,10 @@
+use crate::new_br;
+use crate::oop;
 use crate::runtime::thread::Threads;
+use crate::runtime::{self, JavaCall};
+use std::io::Write;
 use std::ptr;
+use std::sync::atomic::{AtomicI32, Ordering};
 
 static mut VM_GLOBAL: *const u8 = ptr::null();
 
@@ -7,6 +12,16 @@ pub fn get_vm() -> &'static VM {
     unsafe { &*(VM_GLOBAL as *const VM) }
 }
 
+pub fn try_get_vm() -> Option<&'static VM> {
+    unsafe {
+        if VM_GLOBAL.is_null() {
+            None
+        } else {
+            Some(&*(VM_GLOBAL as *const VM))
+        }
+    }
+}
+
 pub fn set_vm(vm: &VM) {
     let ptr = vm as *const _ as *const u8;
 
@@ -17,12 +32,15 @@ pub fn set_vm(vm: &VM) {
 
 pub struct VM {
     pub threads: Threads,
+    //returned by 'destroy', 1 if 'main' ended with an uncaught exception
+    exit_code: AtomicI32,
 }
 
 impl VM {
-    pub fn new(thread_pool_count: usize) -> Box<VM> {
+    pub fn new() -> Box<VM> {
         let vm = Box::new(VM {
-            threads: Threads::new(thread_pool_count),
+            threads: Threads::new(),
+            exit_code: AtomicI32::new(0),
         });
 
         set_vm(&vm);
@@ -30,3 +48,42 @@ impl VM {
         vm
     }
 }
+
+impl VM {
+    pub fn set_exit_code(&self, code: i32) {
+        self.exit_code.store(code, Ordering::SeqCst);
+    }
+
+    /*
+    called by the thread that ran 'main' (DestroyJavaVM), after it detached:
+    wait all non-daemon threads, then run shutdown hooks by java.lang.Shutdown.shutdown.
+    Shutdown.exit & Shutdown.halt don't return, see 'halt'
+    */
+    pub fn destroy(&self) -> i32 {
+        self.threads.join_all();
+
+        let shutdown = oop::class::load_and_init(b"java/lang/Shutdown").map(|cls| {
+            let cls = cls.get_class();
+            cls.get_static_method(&new_br("shutdown"), &new_br("()V"))
+        });
+        if let Ok(Ok(mir)) = shutdown {
+            let mut jc = JavaCall::new_with_args(mir, vec![]);
+            jc.invoke(None, true);
+
+            if runtime::thread::is_meet_ex() {
+                let jt = runtime::thread::current_java_thread();
+                let _ = jt.write().unwrap().take_ex();
+                error!("Shutdown.shutdown failed");
+            }
+        }
+
+        self.exit_code.load(Ordering::SeqCst)
+    }
+}
+
+//terminate the process, other threads stopped
+pub fn halt(status: i32) -> ! {
+    let _ = std::io::stdout().flush();
+    let _ = std::io::stderr().flush();
+    std::process::exit(status)
+}
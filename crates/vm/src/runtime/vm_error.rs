@@ -0,0 +1,149 @@
+use crate::runtime::{options, thread};
+use classfile::U2;
+use std::backtrace::Backtrace;
+use std::fmt::Write as _;
+use std::panic::{self, Location};
+use std::sync::atomic::Ordering;
+use std::sync::Mutex;
+
+/*
+Fatal errors, the hs_err report of HotSpot
+
+The Java exceptions are met by the instructions & the natives (see exception);
+a panic left is an internal error of the vm. The hook, installed by init,
+writes the report of the thread panicked, its Java frames & the command line to
+hs_err_pid<pid>.log of the working directory, and aborts, so a panic never
+unwinds on a vm thread (one attached, see thread::is_vm_thread). The panics of
+the other threads, of an embedder of libjvm, go to the hook installed before.
+
+  -XX:ErrorFile=<path>, the report file, %p replaced by the pid
+*/
+
+pub fn install() {
+    let error_file = options::with(|opts| opts.xx.get("ErrorFile").cloned());
+    let previous = panic::take_hook();
+
+    panic::set_hook(Box::new(move |info| {
+        if !thread::is_vm_thread() {
+            return previous(info);
+        }
+
+        let payload = info.payload();
+        let msg = match payload.downcast_ref::<&str>() {
+            Some(s) => s.to_string(),
+            None => match payload.downcast_ref::<String>() {
+                Some(s) => s.clone(),
+                None => "(no message)".to_string(),
+            },
+        };
+        report_and_die(info.location(), &msg, error_file.as_deref());
+    }));
+}
+
+fn report_and_die(location: Option<&Location>, msg: &str, error_file: Option<&str>) -> ! {
+    //the first thread failed reports, the others wait its abort
+    static REPORTING: Mutex<()> = Mutex::new(());
+    let _reporting = REPORTING.lock();
+
+    let pid = std::process::id();
+    let header = header(location, msg, pid);
+    let report = format!("{}\n{}", header, details());
+
+    let path = match error_file {
+        Some(f) => f.replace("%p", &pid.to_string()),
+        None => format!("hs_err_pid{}.log", pid),
+    };
+    let fallback = std::env::temp_dir().join(format!("hs_err_pid{}.log", pid));
+    let saved = std::fs::write(&path, &report)
+        .map(|_| std::path::PathBuf::from(&path))
+        .or_else(|_| std::fs::write(&fallback, &report).map(|_| fallback));
+
+    eprint!("{}", header);
+    match saved {
+        Ok(path) => {
+            let path = std::fs::canonicalize(&path).unwrap_or(path);
+            eprintln!("# An error report file with more information is saved as:");
+            eprintln!("# {}", path.display());
+        }
+        Err(e) => eprintln!("# Failed to write error report file: {}", e),
+    }
+    eprintln!("#");
+
+    std::process::abort()
+}
+
+fn header(location: Option<&Location>, msg: &str, pid: u32) -> String {
+    let location = match location {
+        Some(l) => format!("{}:{}", l.file(), l.line()),
+        None => "unknown".to_string(),
+    };
+    let t = std::thread::current();
+
+    let mut s = String::new();
+    let _ = writeln!(s, "#");
+    let _ = writeln!(
+        s,
+        "# A fatal error has been detected by the Java Runtime Environment:"
+    );
+    let _ = writeln!(s, "#");
+    let _ = writeln!(
+        s,
+        "#  Internal Error ({}), pid={}, tid={:?}",
+        location,
+        pid,
+        t.id()
+    );
+    let _ = writeln!(s, "#  Error: {}", msg);
+    let _ = writeln!(s, "#");
+    s
+}
+
+//the Java frames of the current thread, the innermost first; the locks
+//taken by the frames executing are only tried
+fn details() -> String {
+    let mut s = String::new();
+    let _ = writeln!(s, "---------------  T H R E A D  ---------------\n");
+
+    let jt = thread::THREAD.try_with(|t| t.try_borrow().ok().map(|t| t.clone()));
+    match jt.ok().flatten() {
+        Some(jt) => match jt.try_read() {
+            Ok(jt) => {
+                let _ = writeln!(s, "Current thread: JavaThread \"{}\"\n", jt.tag);
+                let _ = writeln!(s, "Java frames: (j=Java code)");
+                for frame in jt.frames.iter().rev() {
+                    match frame.try_read() {
+                        Ok(frame) => {
+                            let method = &frame.mir.method;
+                            let pc = frame.pc.load(Ordering::Relaxed);
+                            let _ = writeln!(
+                                s,
+                                "j  {}.{}{}+{} (line {})",
+                                String::from_utf8_lossy(method.cls_name.as_slice())
+                                    .replace("/", "."),
+                                String::from_utf8_lossy(method.name.as_slice()),
+                                String::from_utf8_lossy(method.desc.as_slice()),
+                                pc,
+                                method.get_line_num(pc as U2)
+                            );
+                        }
+                        Err(_) => {
+                            let _ = writeln!(s, "j  (frame locked)");
+                        }
+                    }
+                }
+            }
+            Err(_) => {
+                let _ = writeln!(s, "Current thread: JavaThread (locked)");
+            }
+        },
+        None => {
+            let _ = writeln!(s, "Current thread: not a JavaThread");
+        }
+    }
+
+    let _ = writeln!(s, "\n---------------  S Y S T E M  ---------------\n");
+    let args: Vec<String> = std::env::args().collect();
+    let _ = writeln!(s, "Command Line: {}\n", args.join(" "));
+    let _ = writeln!(s, "Native frames:\n{}", Backtrace::force_capture());
+    s
+}
@@ -1,5 +1,7 @@
-use crate::oop::Oop;
+use crate::new_br;
+use crate::oop::{self, Class, Oop};
 use crate::runtime::{self, require_class3};
+use crate::types::ClassRef;
 use crate::util;
 
 static mut JAVA_LANG_STRING_VALUE_OFFSET: usize = 0;
@@ -53,3 +55,81 @@ pub fn new_java_lang_string3(bs: &[u8]) -> Oop {
 
     string_oop
 }
+
+//address of a direct java.nio.Buffer, None if not direct
+pub fn direct_buffer_address(buf: &Oop) -> Option<i64> {
+    if !is_direct_buffer(buf) {
+        return None;
+    }
+    let v = buffer_field(buf, "address", "J");
+    Some(v.extract_long())
+}
+
+//capacity of a direct java.nio.Buffer, None if not direct
+pub fn direct_buffer_capacity(buf: &Oop) -> Option<i32> {
+    if !is_direct_buffer(buf) {
+        return None;
+    }
+    let v = buffer_field(buf, "capacity", "I");
+    Some(v.extract_int())
+}
+
+fn is_direct_buffer(buf: &Oop) -> bool {
+    if buf.is_null() {
+        return false;
+    }
+
+    let cls = {
+        let rf = buf.extract_ref();
+        let ptr = rf.get_raw_ptr();
+        unsafe {
+            match &(*ptr).v {
+                oop::RefKind::Inst(inst) => inst.class.clone(),
+                _ => return false,
+            }
+        }
+    };
+    match require_class3(None, b"sun/nio/ch/DirectBuffer") {
+        Some(direct) => runtime::cmp::instance_of(cls, direct),
+        None => false,
+    }
+}
+
+fn buffer_field(buf: &Oop, name: &str, desc: &str) -> Oop {
+    let cls = require_class3(None, b"java/nio/Buffer").unwrap();
+    let offset = {
+        let cls = cls.get_class();
+        cls.get_field_id(&new_br(name), &new_br(desc), false).offset
+    };
+    Class::get_field_value2(buf.extract_ref(), offset)
+}
+
+//the class of the object, None of null
+pub fn get_oop_class(v: &Oop) -> Option<ClassRef> {
+    match v {
+        Oop::Ref(rf) => {
+            let ptr = rf.get_raw_ptr();
+            unsafe {
+                match &(*ptr).v {
+                    oop::RefKind::Inst(inst) => Some(inst.class.clone()),
+                    oop::RefKind::Array(ary) => Some(ary.class.clone()),
+                    oop::RefKind::TypeArray(ary) => {
+                        let name: &[u8] = match ary {
+                            oop::TypeArrayDesc::Byte(_) => b"[B",
+                            oop::TypeArrayDesc::Bool(_) => b"[Z",
+                            oop::TypeArrayDesc::Char(_) => b"[C",
+                            oop::TypeArrayDesc::Short(_) => b"[S",
+                            oop::TypeArrayDesc::Float(_) => b"[F",
+                            oop::TypeArrayDesc::Double(_) => b"[D",
+                            oop::TypeArrayDesc::Int(_) => b"[I",
+                            oop::TypeArrayDesc::Long(_) => b"[J",
+                        };
+                        require_class3(None, name)
+                    }
+                    oop::RefKind::Mirror(_) => require_class3(None, b"java/lang/Class"),
+                }
+            }
+        }
+        _ => None,
+    }
+}
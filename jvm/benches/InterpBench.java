@@ -0,0 +1,108 @@
+/*
+Microbenchmarks of the interpreter: field access, virtual & interface calls,
+allocation. Prints "<name> <ms>" for each, run by benches/interp.rs.
+*/
+public class InterpBench {
+    static class Point {
+        int x;
+        int y;
+    }
+
+    interface Shape {
+        int area();
+    }
+
+    static class Square implements Shape {
+        private final int side;
+
+        Square(int side) {
+            this.side = side;
+        }
+
+        public int area() {
+            return side * side;
+        }
+    }
+
+    abstract static class Animal {
+        abstract int legs();
+    }
+
+    static class Dog extends Animal {
+        int legs() {
+            return 4;
+        }
+    }
+
+    static class Bird extends Animal {
+        int legs() {
+            return 2;
+        }
+    }
+
+    static int fieldAccess(int n) {
+        Point p = new Point();
+        for (int i = 0; i < n; i++) {
+            p.x += i;
+            p.y = p.x ^ p.y;
+        }
+        return p.y;
+    }
+
+    static long virtualCall(int n) {
+        Animal a = new Dog();
+        Shape s = new Square(3);
+        long sum = 0;
+        for (int i = 0; i < n; i++) {
+            sum += a.legs() + s.area();
+        }
+        return sum;
+    }
+
+    //two receiver classes at one call site
+    static long polymorphicCall(int n) {
+        Animal[] animals = {new Dog(), new Bird()};
+        long sum = 0;
+        for (int i = 0; i < n; i++) {
+            sum += animals[i & 1].legs();
+        }
+        return sum;
+    }
+
+    static int allocation(int n) {
+        int sum = 0;
+        for (int i = 0; i < n; i++) {
+            Point p = new Point();
+            p.x = i;
+            sum += p.x;
+        }
+        return sum;
+    }
+
+    public static void main(String[] args) {
+        int n = args.length > 0 ? Integer.parseInt(args[0]) : 100000;
+
+        long t = System.nanoTime();
+        long r = fieldAccess(n);
+        report("field_access", t, r);
+
+        t = System.nanoTime();
+        r = virtualCall(n);
+        report("virtual_call", t, r);
+
+        t = System.nanoTime();
+        r = polymorphicCall(n);
+        report("polymorphic_call", t, r);
+
+        t = System.nanoTime();
+        r = allocation(n);
+        report("allocation", t, r);
+    }
+
+    //the result printed to stderr, so that the loops are not dead code
+    private static void report(String name, long start, long result) {
+        long ms = (System.nanoTime() - start) / 1000000;
+        System.out.println(name + " " + ms);
+        System.err.println(name + " = " + result);
+    }
+}
@@ -0,0 +1,93 @@
+/*
+Microbenchmarks of the interpreter, benches/InterpBench.java run in the vm
+with quickening & inline caches, without inline caches, and without both,
+all with -Xint; and compiled by the baseline JIT
+
+  JAVA_HOME=/path/to/jre8 cargo bench -p jvm --bench interp [-- iterations]
+
+Needs JAVA_HOME of a jre 8 (lib/rt.jar as the boot class path) and javac.
+*/
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::process::Command;
+
+const CONFIGS: &[(&str, &[&str])] = &[
+    ("quick", &["-Xint"]),
+    ("no-ic", &["-Xint", "-XX:-UseInlineCaches"]),
+    ("no-quick", &["-Xint", "-XX:-RewriteBytecodes"]),
+    ("jit", &[]),
+];
+
+fn main() {
+    let java_home = match std::env::var("JAVA_HOME") {
+        Ok(v) if Path::new(&v).join("lib").join("rt.jar").exists() => v,
+        _ => {
+            println!("JAVA_HOME of jre 8 not found");
+            return;
+        }
+    };
+    //"cargo bench" passes "--bench"
+    let n = std::env::args()
+        .skip(1)
+        .find(|it| !it.starts_with('-'))
+        .unwrap_or_else(|| "100000".to_string());
+
+    let out_dir = std::env::temp_dir().join(format!("jvm_bench_{}", std::process::id()));
+    std::fs::create_dir_all(&out_dir).unwrap();
+    let src = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("benches")
+        .join("InterpBench.java");
+    let compiled = Command::new("javac")
+        .args(&["-source", "8", "-target", "8", "-d"])
+        .arg(&out_dir)
+        .arg(&src)
+        .status();
+    if !matches!(compiled, Ok(status) if status.success()) {
+        println!("javac not available");
+        return;
+    }
+
+    //name -> ms of each config
+    let mut results: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for (_, opts) in CONFIGS.iter() {
+        let output = Command::new(env!("CARGO_BIN_EXE_jvm"))
+            .env("JAVA_HOME", &java_home)
+            .args(opts.iter())
+            .arg("-cp")
+            .arg(&out_dir)
+            .arg("InterpBench")
+            .arg(&n)
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "stderr: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+
+        for line in String::from_utf8_lossy(&output.stdout).lines() {
+            let mut it = line.split_whitespace();
+            if let (Some(name), Some(ms)) = (it.next(), it.next()) {
+                results
+                    .entry(name.to_string())
+                    .or_default()
+                    .push(format!("{} ms", ms));
+            }
+        }
+    }
+    let _ = std::fs::remove_dir_all(&out_dir);
+
+    println!("iterations: {}", n);
+    print!("{:<20}", "");
+    for (name, _) in CONFIGS.iter() {
+        print!("{:>12}", name);
+    }
+    println!();
+    for (name, v) in results.iter() {
+        print!("{:<20}", name);
+        for ms in v.iter() {
+            print!("{:>12}", ms);
+        }
+        println!();
+    }
+}
@@ -12,15 +12,6 @@ JAVA_HOME=/Library/Java/JavaVirtualMachines/jdk1.8.0_151.jdk/Contents/Home/jre
 
 MY_SAMPLE=sample
 
-##############################################
-function join_by { local IFS="$1"; shift; echo "$*"; }
-##############################################
-
-#FIXME: win should be ';'
-SEP=':'
-
-jars=$JAVA_HOME/lib/*.jar
-JDK=$(join_by $SEP ${jars[@]})
-
+#jars of $JAVA_HOME/lib are the boot class path of vm
 export JAVA_HOME
-cargo run -- --cp $JDK:$MY_SAMPLE HelloWorld
+cargo run -- -cp $MY_SAMPLE HelloWorld
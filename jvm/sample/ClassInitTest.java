@@ -0,0 +1,124 @@
+/*
+Class initialization, JVMS 5.5: order & triggers, recursive requests,
+ExceptionInInitializerError, NoClassDefFoundError after a failed
+initialization, a thread waiting for the initialization by another, and
+superinterfaces initialized only if they declare default methods
+*/
+public class ClassInitTest {
+    static StringBuilder log = new StringBuilder();
+
+    static int mark(String s) {
+        log.append(log.length() == 0 ? "" : " ").append(s);
+        return 1;
+    }
+
+    static class Super {
+        static int s = mark("Super");
+    }
+
+    static class Sub extends Super {
+        static int s = mark("Sub");
+        static final int CONST = 42;
+    }
+
+    static class Recursive {
+        static int a = mark("Recursive");
+        static int b = twice();
+
+        static int twice() {
+            return a * 2;
+        }
+    }
+
+    static class Lazy {
+        static int s = mark("Lazy");
+    }
+
+    static class Bad {
+        static int s = fail();
+
+        static int fail() {
+            throw new IllegalStateException("bad");
+        }
+    }
+
+    static class BadError {
+        static int s = error();
+
+        static int error() {
+            throw new AssertionError("error");
+        }
+    }
+
+    static class Slow {
+        static int value;
+
+        static {
+            try {
+                Thread.sleep(200);
+            } catch (InterruptedException e) {
+            }
+            value = 7;
+        }
+    }
+
+    interface WithDefault {
+        int w = mark("WithDefault");
+
+        default int get() {
+            return w;
+        }
+    }
+
+    interface NoDefault {
+        int n = mark("NoDefault");
+    }
+
+    static class Impl implements NoDefault, WithDefault {
+        static int s = mark("Impl");
+    }
+
+    public static void main(String[] args) throws Exception {
+        Class<?> c = Lazy.class;
+        Lazy[] arr = new Lazy[2];
+        System.out.println("const = " + Sub.CONST + ", " + c.getSimpleName() + arr.length + ", log = " + log);
+
+        System.out.println("sub = " + Sub.s + ", log = " + log);
+        System.out.println("recursive = " + Recursive.b);
+
+        try {
+            System.out.println(Bad.s);
+        } catch (ExceptionInInitializerError e) {
+            System.out.println("first = " + e.getClass().getName() + ", cause = " + e.getCause());
+        }
+        try {
+            System.out.println(Bad.s);
+        } catch (NoClassDefFoundError e) {
+            System.out.println("second = " + e.getMessage());
+        }
+        try {
+            System.out.println(BadError.s);
+        } catch (Throwable e) {
+            System.out.println("error = " + e);
+        }
+
+        final int[] seen = new int[1];
+        Thread t = new Thread(new Runnable() {
+            public void run() {
+                seen[0] = Slow.value;
+            }
+        });
+        t.start();
+        int v = Slow.value;
+        t.join();
+        System.out.println("slow = " + v + ", " + seen[0]);
+
+        log.setLength(0);
+        new Impl();
+        System.out.println("impl = " + log);
+        Class.forName("ClassInitTest$Lazy", false, ClassInitTest.class.getClassLoader());
+        System.out.println("forName false = " + log);
+        Class.forName("ClassInitTest$Lazy");
+        System.out.println("forName = " + log);
+    }
+}
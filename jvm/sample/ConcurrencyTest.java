@@ -0,0 +1,140 @@
+import java.lang.reflect.Field;
+import java.util.Map;
+import java.util.concurrent.ConcurrentHashMap;
+import java.util.concurrent.CountDownLatch;
+import java.util.concurrent.atomic.AtomicInteger;
+import java.util.concurrent.atomic.AtomicLongArray;
+import java.util.concurrent.atomic.AtomicReferenceFieldUpdater;
+import sun.misc.Unsafe;
+
+/*
+Volatile & atomic access under real threads: AtomicInteger, AtomicLongArray,
+a field updater, ConcurrentHashMap, double-checked locking, and Unsafe CAS on a
+static field raced with volatile putstatic of another static of the class
+*/
+public class ConcurrencyTest {
+    static final int THREADS = 4;
+    static final int LOOP = 10000;
+
+    static final AtomicInteger counter = new AtomicInteger();
+    static final AtomicLongArray longs = new AtomicLongArray(2);
+    static final Map<Integer, Integer> map = new ConcurrentHashMap<Integer, Integer>();
+
+    volatile String name = "";
+    static final AtomicReferenceFieldUpdater<ConcurrencyTest, String> NAME =
+            AtomicReferenceFieldUpdater.newUpdater(ConcurrencyTest.class, String.class, "name");
+    static final ConcurrencyTest shared = new ConcurrencyTest();
+
+    static class Singleton {
+        static final AtomicInteger created = new AtomicInteger();
+        static volatile Singleton instance;
+
+        Singleton() {
+            created.incrementAndGet();
+        }
+
+        static Singleton get() {
+            Singleton s = instance;
+            if (s == null) {
+                synchronized (Singleton.class) {
+                    s = instance;
+                    if (s == null) {
+                        s = new Singleton();
+                        instance = s;
+                    }
+                }
+            }
+            return s;
+        }
+    }
+
+    static volatile int casCount;
+    static volatile int written;
+    static Unsafe unsafe;
+    static long casOffset;
+
+    static void casIncrement() {
+        while (true) {
+            int v = casCount;
+            if (unsafe.compareAndSwapInt(ConcurrencyTest.class, casOffset, v, v + 1)) {
+                return;
+            }
+        }
+    }
+
+    static void work(int id) {
+        for (int i = 0; i < LOOP; i++) {
+            counter.incrementAndGet();
+            longs.addAndGet(i & 1, 1);
+            casIncrement();
+            written = i;
+            if (i % 100 == 0) {
+                map.put(id * LOOP + i, i);
+            }
+            Integer old = map.putIfAbsent(-1 - (i % 50), 0);
+            while (true) {
+                Integer cur = map.get(-1 - (i % 50));
+                if (map.replace(-1 - (i % 50), cur, cur + 1)) {
+                    break;
+                }
+            }
+            if (old == null && Singleton.get() != Singleton.get()) {
+                throw new IllegalStateException("two singletons");
+            }
+        }
+        while (true) {
+            String cur = shared.name;
+            if (NAME.compareAndSet(shared, cur, cur + id)) {
+                break;
+            }
+        }
+    }
+
+    public static void main(String[] args) throws Exception {
+        Field f = Unsafe.class.getDeclaredField("theUnsafe");
+        f.setAccessible(true);
+        unsafe = (Unsafe) f.get(null);
+        casOffset = unsafe.staticFieldOffset(ConcurrencyTest.class.getDeclaredField("casCount"));
+
+        final CountDownLatch start = new CountDownLatch(1);
+        Thread[] threads = new Thread[THREADS];
+        for (int i = 0; i < THREADS; i++) {
+            final int id = i;
+            threads[i] = new Thread(new Runnable() {
+                public void run() {
+                    try {
+                        start.await();
+                    } catch (InterruptedException e) {
+                        return;
+                    }
+                    work(id);
+                }
+            });
+            threads[i].start();
+        }
+        start.countDown();
+        for (Thread t : threads) {
+            t.join();
+        }
+
+        int total = THREADS * LOOP;
+        System.out.println("counter = " + (counter.get() == total));
+        System.out.println("longs = " + (longs.get(0) + longs.get(1) == total));
+        System.out.println("cas static = " + (casCount == total));
+        System.out.println("singletons = " + Singleton.created.get());
+
+        int entries = 0;
+        int sum = 0;
+        for (Map.Entry<Integer, Integer> e : map.entrySet()) {
+            if (e.getKey() < 0) {
+                sum += e.getValue();
+            } else {
+                entries++;
+            }
+        }
+        System.out.println("map entries = " + (entries == THREADS * LOOP / 100));
+        System.out.println("map counts = " + (sum == total));
+        System.out.println("map size = " + map.size());
+        System.out.println("name length = " + shared.name.length());
+    }
+}
@@ -0,0 +1,39 @@
+import java.util.concurrent.CountDownLatch;
+
+public class DaemonTest {
+    public static void main(String[] args) throws Exception {
+        final CountDownLatch running = new CountDownLatch(1);
+        Thread daemon = new Thread(new Runnable() {
+            public void run() {
+                running.countDown();
+                while (true) {
+                    try {
+                        Thread.sleep(10);
+                    } catch (InterruptedException e) {
+                    }
+                }
+            }
+        });
+        daemon.setDaemon(true);
+        daemon.start();
+        running.await();
+        System.out.println("daemon running: " + daemon.isAlive());
+        try {
+            daemon.setDaemon(false);
+        } catch (IllegalThreadStateException e) {
+            System.out.println("setDaemon once started: refused");
+        }
+
+        Thread worker = new Thread(new Runnable() {
+            public void run() {
+                try {
+                    Thread.sleep(200);
+                } catch (InterruptedException e) {
+                }
+                System.out.println("worker done, daemon still running: " + daemon.isAlive());
+            }
+        });
+        worker.start();
+        System.out.println("main done");
+    }
+}
@@ -0,0 +1,82 @@
+/*
+Method selection of invokevirtual & invokeinterface, JVMS 5.4.6
+*/
+public class DispatchTest {
+    interface Greeter {
+        String greet();
+    }
+
+    interface Polite extends Greeter {
+        default String greet() {
+            return "Polite.greet";
+        }
+    }
+
+    interface Formal extends Polite {
+        default String greet() {
+            return "Formal.greet";
+        }
+    }
+
+    interface Counter {
+        default int count() {
+            return 1;
+        }
+    }
+
+    //the maximally-specific default method, Formal overrides Polite
+    static class Both implements Polite, Formal {
+    }
+
+    //a superclass method wins over a default method
+    static class Base {
+        public String greet() {
+            return "Base.greet";
+        }
+
+        String pkg() {
+            return "Base.pkg";
+        }
+    }
+
+    static class Derived extends Base implements Polite, Counter {
+        //package-private overridden in the same package
+        String pkg() {
+            return "Derived.pkg";
+        }
+    }
+
+    //implements Greeter.greet without declaring it
+    abstract static class Miranda implements Greeter {
+        String hello() {
+            return greet();
+        }
+    }
+
+    static class Concrete extends Miranda {
+        public String greet() {
+            return "Concrete.greet";
+        }
+    }
+
+    public static void main(String[] args) {
+        Greeter g = new Both();
+        System.out.println("both = " + g.greet());
+
+        Base b = new Derived();
+        Greeter d = (Greeter) b;
+        System.out.println("derived = " + d.greet());
+        System.out.println("pkg = " + b.pkg());
+        System.out.println("count = " + ((Counter) b).count());
+
+        Miranda m = new Concrete();
+        System.out.println("miranda = " + m.hello());
+
+        //an Object method by invokeinterface
+        Object o = new Concrete();
+        Greeter og = (Greeter) o;
+        System.out.println("object = " + (og.toString().startsWith("DispatchTest$Concrete@")));
+
+        System.out.println("class = " + ((java.lang.reflect.Type) String.class).getTypeName());
+    }
+}
@@ -0,0 +1,211 @@
+import java.security.AccessController;
+import java.security.PrivilegedAction;
+
+/*
+The handlers of the exceptions, JVMS 2.10: thrown & caught in one method,
+nested try/finally, thrown by the handlers, synchronized blocks unwound,
+thrown across native frames, and a catch type not found (GoneException.class
+deleted once compiled), resolved only when an exception reaches it
+*/
+class GoneException extends RuntimeException {
+}
+
+public class ExceptionTest {
+    static StringBuilder log = new StringBuilder();
+
+    static int local() {
+        try {
+            throw new IllegalStateException("local");
+        } catch (IllegalStateException e) {
+            log.append("caught ").append(e.getMessage());
+            return 1;
+        }
+    }
+
+    static int nested() {
+        try {
+            try {
+                try {
+                    throw new IllegalArgumentException("inner");
+                } finally {
+                    log.append("finally1 ");
+                }
+            } catch (IllegalStateException e) {
+                log.append("wrong ");
+            } finally {
+                log.append("finally2 ");
+            }
+        } catch (IllegalArgumentException e) {
+            log.append("outer ").append(e.getMessage());
+        }
+        return 2;
+    }
+
+    static void inHandler() {
+        try {
+            throw new IllegalStateException("first");
+        } catch (IllegalStateException e) {
+            throw new UnsupportedOperationException("from handler " + e.getMessage());
+        } finally {
+            log.append("finally ");
+        }
+    }
+
+    static int finallyReplaces() {
+        try {
+            try {
+                throw new IllegalStateException("lost");
+            } finally {
+                if (log != null) {
+                    throw new ArithmeticException("finally");
+                }
+            }
+        } catch (RuntimeException e) {
+            log.append(e.getClass().getName()).append(" ").append(e.getMessage());
+        }
+        return 3;
+    }
+
+    static int rethrow(int[] a) {
+        try {
+            return a[3];
+        } catch (ArrayIndexOutOfBoundsException e) {
+            log.append("rethrow ");
+            throw e;
+        }
+    }
+
+    static final Object lock = new Object();
+    static final Object lock2 = new Object();
+
+    static void locked() {
+        synchronized (lock) {
+            synchronized (lock2) {
+                throw new IllegalStateException("locked");
+            }
+        }
+    }
+
+    static synchronized void lockedMethod() {
+        throw new IllegalStateException("locked method");
+    }
+
+    static volatile boolean entered;
+
+    //the monitor of 'o' entered by another thread, so released by this one
+    static boolean released(final Object o) {
+        entered = false;
+        Thread t = new Thread(new Runnable() {
+            public void run() {
+                synchronized (o) {
+                    entered = true;
+                }
+            }
+        });
+        t.setDaemon(true);
+        t.start();
+        try {
+            t.join(5000);
+        } catch (InterruptedException e) {
+        }
+        return entered;
+    }
+
+    static String privileged() {
+        try {
+            return AccessController.doPrivileged(new PrivilegedAction<String>() {
+                public String run() {
+                    throw new SecurityException("in action");
+                }
+            });
+        } catch (SecurityException e) {
+            return "privileged " + e.getMessage();
+        }
+    }
+
+    static String gone(boolean reach) {
+        try {
+            try {
+                if (reach) {
+                    throw new IllegalStateException("reach");
+                }
+                return "gone not reached";
+            } catch (GoneException e) {
+                return "gone caught";
+            }
+        } catch (Throwable t) {
+            return "gone: " + t.getClass().getName() + ": " + t.getMessage();
+        }
+    }
+
+    static String gonePassed() {
+        try {
+            try {
+                throw new IllegalStateException("before");
+            } catch (IllegalStateException e) {
+                return "caught before gone";
+            } catch (GoneException e) {
+                return "gone caught";
+            }
+        } catch (Throwable t) {
+            return "gone: " + t.getClass().getName();
+        }
+    }
+
+    static void flush(String name) {
+        System.out.println(name + ": " + log);
+        log.setLength(0);
+    }
+
+    public static void main(String[] args) {
+        local();
+        flush("local");
+
+        nested();
+        flush("nested");
+
+        try {
+            inHandler();
+        } catch (UnsupportedOperationException e) {
+            log.append(e.getMessage());
+        }
+        flush("handler");
+
+        finallyReplaces();
+        flush("finally");
+
+        try {
+            rethrow(new int[1]);
+        } catch (ArrayIndexOutOfBoundsException e) {
+            log.append(e.getClass().getName());
+        }
+        flush("rethrow");
+
+        try {
+            locked();
+        } catch (IllegalStateException e) {
+            log.append(e.getMessage()).append(" ")
+                    .append(released(lock)).append(" ").append(released(lock2));
+        }
+        flush("synchronized");
+
+        try {
+            lockedMethod();
+        } catch (IllegalStateException e) {
+            log.append(e.getMessage()).append(" ").append(released(ExceptionTest.class));
+        }
+        flush("synchronized method");
+
+        try {
+            throw null;
+        } catch (NullPointerException e) {
+            log.append("throw null");
+        }
+        flush("npe");
+
+        System.out.println(privileged());
+        System.out.println(gone(false));
+        System.out.println(gonePassed());
+        System.out.println(gone(true));
+    }
+}
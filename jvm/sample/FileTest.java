@@ -0,0 +1,80 @@
+import java.io.File;
+import java.io.FileInputStream;
+import java.io.FileNotFoundException;
+import java.io.FileOutputStream;
+import java.io.RandomAccessFile;
+import java.util.Arrays;
+
+/*
+java.io natives: UnixFileSystem (mkdir, length, lastModified, list, rename,
+delete), FileInputStream skip & available, RandomAccessFile seek, read &
+write, streams closed twice and the messages of FileNotFoundException
+*/
+class FileTest
+{
+    public static void main(String[] args) throws Exception
+    {
+        File dir = new File(System.getProperty("java.io.tmpdir"), "jvm_file_test_" + System.nanoTime());
+        System.out.println("mkdir = " + dir.mkdir() + " " + dir.mkdir() + " " + dir.isDirectory());
+
+        File a = new File(dir, "a.txt");
+        FileOutputStream fos = new FileOutputStream(a);
+        fos.write("hello file".getBytes());
+        fos.close();
+        fos.close();
+        System.out.println("length = " + a.length() + " " + new File(dir, "none").length());
+        System.out.println("lastModified > 0 = " + (a.lastModified() > 0));
+        System.out.println("setLastModified = " + a.setLastModified(1000000000000L) + " " + a.lastModified());
+
+        FileInputStream in = new FileInputStream(a);
+        System.out.println("skip = " + in.skip(6));
+        System.out.println("available = " + in.available());
+        System.out.println("read = " + (char) in.read());
+        System.out.println("skip = " + in.skip(-3) + ", read = " + (char) in.read());
+        System.out.println("skip past end = " + in.skip(100) + ", read = " + in.read());
+        in.close();
+        in.close();
+
+        RandomAccessFile raf = new RandomAccessFile(a, "rw");
+        raf.seek(raf.length());
+        raf.write('!');
+        raf.seek(0);
+        System.out.println("raf = " + raf.readLine() + ", pointer = " + raf.getFilePointer() + ", eof = " + raf.read());
+        raf.seek(1);
+        raf.write("ELL".getBytes());
+        raf.seek(0);
+        byte[] buf = new byte[8];
+        raf.readFully(buf, 0, 5);
+        System.out.println("raf write = " + new String(buf, 0, 5) + ", read = " + raf.read(buf) + " " + new String(buf, 0, 6));
+        raf.setLength(5);
+        System.out.println("raf length = " + raf.length() + ", pointer = " + raf.getFilePointer());
+        raf.close();
+        raf.close();
+        System.out.println("close twice = ok");
+
+        File b = new File(dir, "b.txt");
+        System.out.println("rename = " + a.renameTo(b) + " " + a.exists() + " " + b.length());
+        System.out.println("mkdir sub = " + new File(dir, "sub").mkdir());
+        String[] names = dir.list();
+        Arrays.sort(names);
+        System.out.println("list = " + Arrays.toString(names) + " " + b.list());
+
+        try {
+            new FileInputStream(a);
+        } catch (FileNotFoundException e) {
+            System.out.println("FileNotFoundException: " + e.getMessage().replace(dir.getPath(), "dir"));
+        }
+        try {
+            new FileInputStream(dir);
+        } catch (FileNotFoundException e) {
+            System.out.println("FileNotFoundException: " + e.getMessage().replace(dir.getPath(), "dir"));
+        }
+        try {
+            new RandomAccessFile(new File(a, "c.txt"), "rw");
+        } catch (FileNotFoundException e) {
+            System.out.println("FileNotFoundException: " + e.getMessage().replace(dir.getPath(), "dir"));
+        }
+
+        System.out.println("delete = " + dir.delete() + " " + b.delete() + " " + new File(dir, "sub").delete() + " " + dir.delete() + " " + dir.exists());
+    }
+}
@@ -0,0 +1,131 @@
+import java.lang.reflect.Method;
+
+/*
+Hot methods of the baseline compiler: arithmetic, locals, branches, fields
+& calls, and the deoptimizations, run with -Xint & compiled
+*/
+public class JitTest {
+    private int count;
+    private static long total;
+
+    static int sum(int n) {
+        int s = 0;
+        for (int i = 0; i < n; i++) {
+            s += i * 3 - (i >> 1) ^ (i & 7);
+        }
+        return s;
+    }
+
+    static long fib(int n) {
+        long a = 0, b = 1;
+        while (n-- > 0) {
+            long t = a + b;
+            a = b;
+            b = t;
+        }
+        return a;
+    }
+
+    static long mix(long x, int shift) {
+        return (x << shift) ^ (x >>> 7) | (x >> 3) - x * 31L;
+    }
+
+    static double poly(double x) {
+        double r = 0.0;
+        for (int i = 0; i < 10; i++) {
+            r = r * x + (double) i / 3.0;
+        }
+        return r;
+    }
+
+    static float avg(float a, float b) {
+        return (a + b) / 2.0f;
+    }
+
+    static int compare(double a, double b) {
+        if (a < b) {
+            return -1;
+        } else if (a > b) {
+            return 1;
+        } else if (a == b) {
+            return 0;
+        }
+        return 2;
+    }
+
+    static int div(int a, int b) {
+        try {
+            return a / b;
+        } catch (ArithmeticException e) {
+            return -1;
+        }
+    }
+
+    static int toInt(double d) {
+        return (int) d;
+    }
+
+    void inc(int n) {
+        for (int i = 0; i < n; i++) {
+            count++;
+            total += i;
+        }
+    }
+
+    static int twice(int v) {
+        return v * 2;
+    }
+
+    public static void main(String[] args) throws Exception {
+        int s = 0;
+        for (int i = 0; i < 2000; i++) {
+            s += sum(i % 50);
+        }
+        System.out.println("sum = " + s);
+
+        long f = 0;
+        for (int i = 0; i < 2000; i++) {
+            f ^= fib(i % 90);
+        }
+        System.out.println("fib = " + f + ", fib(90) = " + fib(90));
+
+        long m = 17;
+        for (int i = 0; i < 2000; i++) {
+            m = mix(m, i & 63);
+        }
+        System.out.println("mix = " + m);
+
+        double p = 0;
+        for (int i = 0; i < 2000; i++) {
+            p += poly(i / 1000.0);
+        }
+        System.out.println("poly = " + p);
+        System.out.println("avg = " + avg(1.5f, 2.25f) + ", " + avg(Float.MAX_VALUE, Float.MAX_VALUE));
+
+        int c = 0;
+        for (int i = 0; i < 2000; i++) {
+            c += compare(i % 3, 1) + compare(Double.NaN, i);
+        }
+        System.out.println("compare = " + c);
+
+        int d = 0;
+        for (int i = 0; i < 2000; i++) {
+            d += div(1000, i % 5) + div(Integer.MIN_VALUE, -1);
+        }
+        System.out.println("div = " + d);
+        System.out.println("toInt = " + toInt(1e20) + ", " + toInt(Double.NaN) + ", " + toInt(-2.7));
+
+        JitTest t = new JitTest();
+        for (int i = 0; i < 2000; i++) {
+            t.inc(i % 10);
+        }
+        System.out.println("count = " + t.count + ", total = " + total);
+
+        Method twice = JitTest.class.getDeclaredMethod("twice", int.class);
+        int r = 0;
+        for (int i = 0; i < 2000; i++) {
+            r += twice(i) + (Integer) twice.invoke(null, i);
+        }
+        System.out.println("twice = " + r);
+    }
+}
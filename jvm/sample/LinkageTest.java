@@ -0,0 +1,112 @@
+/*
+Errors of the resolution, JVMS 5.4.3, met by the instruction resolving the
+symbolic reference, when executed. Lib, Gone & Shape are replaced by
+linkage/Lib.java, and Gone.class deleted, once compiled; the references of
+LinkageTest to them are stale.
+*/
+public class LinkageTest {
+    static void report(String site, Throwable e) {
+        System.out.println(site + ": " + e.getClass().getName() + ": " + e.getMessage());
+    }
+
+    //never executed, never resolved
+    static int unused() {
+        return Gone.v + Lib.gone;
+    }
+
+    public static void main(String[] args) {
+        System.out.println("sf = " + Lib.sf + ", f = " + new Lib().f);
+
+        for (int i = 0; i < 2; i++) {
+            try {
+                System.out.println(Lib.gone);
+            } catch (NoSuchFieldError e) {
+                report("getstatic", e);
+            }
+        }
+        try {
+            System.out.println(Lib.hidden);
+        } catch (IllegalAccessError e) {
+            report("getstatic", e);
+        }
+        try {
+            Lib.sf = new Lib().moved;
+        } catch (IncompatibleClassChangeError e) {
+            report("getfield", e);
+        }
+        for (int i = 0; i < 2; i++) {
+            try {
+                Lib.sm();
+            } catch (NoSuchMethodError e) {
+                report("invokestatic", e);
+            }
+        }
+        try {
+            Lib.pm();
+        } catch (IllegalAccessError e) {
+            report("invokestatic", e);
+        }
+        try {
+            Lib.mm();
+        } catch (IncompatibleClassChangeError e) {
+            report("invokestatic", e);
+        }
+        try {
+            new Lib().im();
+        } catch (IncompatibleClassChangeError e) {
+            report("invokevirtual", e);
+        }
+        try {
+            Shape s = null;
+            s.area();
+        } catch (IncompatibleClassChangeError e) {
+            report("invokeinterface", e);
+        }
+        for (int i = 0; i < 2; i++) {
+            try {
+                System.out.println(new Gone());
+            } catch (NoClassDefFoundError e) {
+                report("new", e);
+            }
+        }
+        try {
+            System.out.println(Gone.class);
+        } catch (NoClassDefFoundError e) {
+            report("ldc", e);
+        }
+        try {
+            System.out.println(new Gone[1].length);
+        } catch (NoClassDefFoundError e) {
+            report("anewarray", e);
+        }
+        System.out.println("unused = " + (args.length > 0 ? unused() : 0));
+    }
+}
+
+class Lib {
+    public static int sf = 1;
+    public int f = 2;
+    public int moved = 3;
+    public static int hidden = 4;
+    public static int gone = 5;
+
+    public static void sm() {
+    }
+
+    public static void pm() {
+    }
+
+    public static void mm() {
+    }
+
+    public void im() {
+    }
+}
+
+class Gone {
+    static int v = 6;
+}
+
+interface Shape {
+    int area();
+}
@@ -0,0 +1,64 @@
+import java.io.File;
+import java.io.RandomAccessFile;
+import java.nio.ByteBuffer;
+import java.nio.ByteOrder;
+import java.nio.MappedByteBuffer;
+import java.nio.channels.FileChannel;
+import java.nio.channels.Pipe;
+import java.nio.channels.SelectionKey;
+import java.nio.channels.Selector;
+import java.nio.file.Files;
+import java.nio.file.Path;
+
+class NioTest
+{
+    public static void main(String[] args) throws Exception
+    {
+        File f = File.createTempFile("nio", ".txt");
+        f.deleteOnExit();
+
+        try (RandomAccessFile raf = new RandomAccessFile(f, "rw");
+             FileChannel ch = raf.getChannel()) {
+            ByteBuffer direct = ByteBuffer.allocateDirect(16);
+            direct.putInt(0x01020304).putLong(42L).put("hi".getBytes());
+            direct.flip();
+            System.out.println("written = " + ch.write(direct));
+            System.out.println("position = " + ch.position() + ", size = " + ch.size());
+
+            ch.position(4);
+            ByteBuffer buf = ByteBuffer.allocateDirect(8);
+            ch.read(buf);
+            buf.flip();
+            System.out.println("long = " + buf.getLong());
+
+            ByteBuffer le = ByteBuffer.allocateDirect(4).order(ByteOrder.LITTLE_ENDIAN);
+            le.putInt(0, 0x01020304);
+            System.out.println("le first byte = " + le.get(0));
+
+            MappedByteBuffer map = ch.map(FileChannel.MapMode.READ_WRITE, 0, ch.size());
+            System.out.println("mapped int = " + Integer.toHexString(map.getInt(0)));
+            map.put(12, (byte) 'H');
+            map.force();
+        }
+
+        Path p = f.toPath();
+        byte[] all = Files.readAllBytes(p);
+        System.out.println("readAllBytes = " + all.length + ", tail = " + new String(all, 12, 2));
+
+        Pipe pipe = Pipe.open();
+        try (Selector selector = Selector.open()) {
+            pipe.source().configureBlocking(false);
+            pipe.source().register(selector, SelectionKey.OP_READ);
+            System.out.println("ready before = " + selector.selectNow());
+
+            pipe.sink().write(ByteBuffer.wrap("ping".getBytes()));
+            System.out.println("ready after = " + selector.select(1000));
+
+            ByteBuffer in = ByteBuffer.allocate(8);
+            pipe.source().read(in);
+            System.out.println("pipe read = " + new String(in.array(), 0, in.position()));
+        }
+        pipe.sink().close();
+        pipe.source().close();
+    }
+}
@@ -0,0 +1,59 @@
+import java.io.BufferedReader;
+import java.io.File;
+import java.io.IOException;
+import java.io.InputStreamReader;
+import java.io.OutputStream;
+import java.nio.file.Files;
+
+class ProcessTest
+{
+    public static void main(String[] args) throws Exception
+    {
+        Process echo = Runtime.getRuntime().exec(new String[]{"/bin/echo", "hello", "world"});
+        System.out.println("echo = " + readLine(echo));
+        System.out.println("echo exit = " + echo.waitFor());
+
+        Process cat = new ProcessBuilder("/bin/cat").start();
+        try (OutputStream out = cat.getOutputStream()) {
+            out.write("from stdin\n".getBytes());
+        }
+        System.out.println("cat = " + readLine(cat));
+        System.out.println("cat exit = " + cat.waitFor());
+
+        ProcessBuilder env = new ProcessBuilder("/bin/sh", "-c", "echo $JVM_TEST_VAR; pwd; echo oops >&2; exit 3");
+        env.environment().put("JVM_TEST_VAR", "passed");
+        env.directory(new File("/"));
+        env.redirectErrorStream(true);
+        Process sh = env.start();
+        try (BufferedReader r = new BufferedReader(new InputStreamReader(sh.getInputStream()))) {
+            System.out.println("env = " + r.readLine());
+            System.out.println("dir = " + r.readLine());
+            System.out.println("stderr = " + r.readLine());
+        }
+        System.out.println("sh exit = " + sh.waitFor());
+
+        File f = File.createTempFile("process", ".txt");
+        f.deleteOnExit();
+        Process redirected = new ProcessBuilder("/bin/echo", "to file")
+                .redirectOutput(f)
+                .start();
+        redirected.waitFor();
+        System.out.println("redirected = " + new String(Files.readAllBytes(f.toPath())).trim());
+
+        Process sleep = new ProcessBuilder("/bin/sleep", "10").start();
+        sleep.destroy();
+        System.out.println("destroyed exit = " + sleep.waitFor());
+
+        try {
+            new ProcessBuilder("/nonexistent/prog").start();
+        } catch (IOException e) {
+            System.out.println("failed = " + e.getMessage());
+        }
+    }
+
+    static String readLine(Process p) throws IOException
+    {
+        BufferedReader r = new BufferedReader(new InputStreamReader(p.getInputStream()));
+        return r.readLine();
+    }
+}
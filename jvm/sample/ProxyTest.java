@@ -0,0 +1,68 @@
+import java.lang.reflect.InvocationHandler;
+import java.lang.reflect.Method;
+import java.lang.reflect.Proxy;
+import java.lang.reflect.UndeclaredThrowableException;
+
+class ProxyTest
+{
+    interface Calc
+    {
+        int add(int a, int b);
+        long twice(long v);
+        boolean isZero(double v);
+        void fail() throws Exception;
+
+        default String name()
+        {
+            return "calc";
+        }
+    }
+
+    public static void main(String[] args) throws Exception
+    {
+        InvocationHandler h = new InvocationHandler() {
+            public Object invoke(Object proxy, Method m, Object[] a) throws Throwable {
+                switch (m.getName()) {
+                case "add":
+                    return (Integer) a[0] + (Integer) a[1];
+                case "twice":
+                    return (Long) a[0] * 2;
+                case "isZero":
+                    return (Double) a[0] == 0.0;
+                case "fail":
+                    throw new java.io.IOException("declared");
+                case "name":
+                    return "proxy";
+                default:
+                    //undeclared checked exception
+                    throw new Exception(m.getName());
+                }
+            }
+        };
+
+        Calc c = (Calc) Proxy.newProxyInstance(Calc.class.getClassLoader(),
+                                               new Class<?>[] { Calc.class }, h);
+
+        System.out.println("isProxyClass = " + Proxy.isProxyClass(c.getClass()));
+        System.out.println("loader = " + (c.getClass().getClassLoader() == Calc.class.getClassLoader()));
+        Calc c2 = (Calc) Proxy.newProxyInstance(Calc.class.getClassLoader(),
+                                                new Class<?>[] { Calc.class }, h);
+        System.out.println("same class = " + (c.getClass() == c2.getClass()));
+        System.out.println("add = " + c.add(1, 2));
+        System.out.println("twice = " + c.twice(21L));
+        System.out.println("isZero = " + c.isZero(0.0));
+        System.out.println("name = " + c.name());
+
+        try {
+            c.fail();
+        } catch (java.io.IOException e) {
+            System.out.println("fail = " + e.getMessage());
+        }
+
+        try {
+            c.hashCode();
+        } catch (UndeclaredThrowableException e) {
+            System.out.println("hashCode = " + e.getUndeclaredThrowable().getMessage());
+        }
+    }
+}
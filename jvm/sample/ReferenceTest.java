@@ -0,0 +1,98 @@
+import java.lang.ref.PhantomReference;
+import java.lang.ref.Reference;
+import java.lang.ref.ReferenceQueue;
+import java.lang.ref.SoftReference;
+import java.lang.ref.WeakReference;
+import java.util.Map;
+import java.util.WeakHashMap;
+
+/*
+java.lang.ref: the references cleared & enqueued by System.gc() once their
+referents only weakly reachable, the finalizers run by the Finalizer thread,
+and the soft references kept but with -XX:SoftRefLRUPolicyMSPerMB=0
+
+The referents are allocated by helpers, so not held by a local of main
+*/
+public class ReferenceTest {
+    static final long TIMEOUT = 5000;
+
+    static class Finalizable {
+        static final Object lock = new Object();
+        static boolean finalized;
+
+        protected void finalize() {
+            synchronized (lock) {
+                finalized = true;
+                lock.notifyAll();
+            }
+        }
+    }
+
+    static WeakReference<Object> weak(ReferenceQueue<Object> queue) {
+        return new WeakReference<Object>(new Object(), queue);
+    }
+
+    static SoftReference<Object> soft() {
+        return new SoftReference<Object>(new Object());
+    }
+
+    static void put(Map<Object, String> map) {
+        map.put(new Object(), "v");
+    }
+
+    static PhantomReference<Object> finalizable(ReferenceQueue<Object> queue) {
+        return new PhantomReference<Object>(new Finalizable(), queue);
+    }
+
+    static boolean awaitFinalized() throws InterruptedException {
+        long deadline = System.currentTimeMillis() + TIMEOUT;
+        synchronized (Finalizable.lock) {
+            while (!Finalizable.finalized && System.currentTimeMillis() < deadline) {
+                Finalizable.lock.wait(100);
+            }
+            return Finalizable.finalized;
+        }
+    }
+
+    public static void main(String[] args) throws Exception {
+        Object strong = new Object();
+        WeakReference<Object> held = new WeakReference<Object>(strong);
+        ReferenceQueue<Object> queue = new ReferenceQueue<Object>();
+        WeakReference<Object> w = weak(queue);
+        System.gc();
+        Reference<?> r = queue.remove(TIMEOUT);
+        System.out.println("weak: " + (r == w) + " " + (w.get() == null));
+        System.out.println("held: " + (held.get() == strong));
+
+        Map<Object, String> map = new WeakHashMap<Object, String>();
+        put(map);
+        System.gc();
+        long deadline = System.currentTimeMillis() + TIMEOUT;
+        while (map.size() != 0 && System.currentTimeMillis() < deadline) {
+            Thread.sleep(10);
+        }
+        System.out.println("weak map: " + map.size());
+
+        SoftReference<Object> s = soft();
+        System.gc();
+        System.gc();
+        System.out.println("soft: " + (s.get() != null));
+
+        ReferenceQueue<Object> phantoms = new ReferenceQueue<Object>();
+        PhantomReference<Object> p = finalizable(phantoms);
+        System.gc();
+        System.out.println("finalized: " + awaitFinalized());
+        r = null;
+        deadline = System.currentTimeMillis() + TIMEOUT;
+        while (r == null && System.currentTimeMillis() < deadline) {
+            System.gc();
+            r = phantoms.remove(100);
+        }
+        System.out.println("phantom: " + (r == p));
+
+        Runtime rt = Runtime.getRuntime();
+        long free = rt.freeMemory();
+        long total = rt.totalMemory();
+        System.out.println("memory: " + (0 <= free && free <= total && total <= rt.maxMemory()));
+    }
+}
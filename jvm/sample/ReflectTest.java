@@ -0,0 +1,115 @@
+import java.lang.reflect.Constructor;
+import java.lang.reflect.InvocationTargetException;
+import java.lang.reflect.Method;
+
+public class ReflectTest {
+    private int base;
+
+    public ReflectTest() {
+        this(100);
+    }
+
+    public ReflectTest(int base) {
+        this.base = base;
+    }
+
+    ReflectTest(String msg) {
+        throw new UnsupportedOperationException(msg);
+    }
+
+    public int add(int a, int b) {
+        return base + a + b;
+    }
+
+    public long add(long a, long b) {
+        return base + a + b + 1000;
+    }
+
+    public String add(String a, Object b) {
+        return a + b;
+    }
+
+    public static double half(double v) {
+        return v / 2;
+    }
+
+    public static boolean not(boolean v) {
+        return !v;
+    }
+
+    public static char next(char c) {
+        return (char) (c + 1);
+    }
+
+    public static void nothing() {
+    }
+
+    static int check(int code) {
+        if (code > 0) {
+            throw new IllegalStateException("code " + code);
+        }
+        return code;
+    }
+
+    public static void main(String[] args) throws Exception {
+        Class<?> c = ReflectTest.class;
+
+        Method addI = c.getMethod("add", int.class, int.class);
+        Method addJ = c.getMethod("add", long.class, long.class);
+        Method addS = c.getMethod("add", String.class, Object.class);
+        Object o = c.getConstructor(int.class).newInstance(5);
+        System.out.println("add int: " + addI.invoke(o, 1, 2));
+        System.out.println("add long: " + addJ.invoke(o, 1L, 2L));
+        System.out.println("add widened: " + addJ.invoke(o, 1, (short) 2));
+        System.out.println("add object: " + addS.invoke(o, "x", 42));
+        System.out.println("default ctor: " + addI.invoke(c.newInstance(), 1, 2));
+
+        Object half = c.getMethod("half", double.class).invoke(null, 3);
+        System.out.println("half: " + half + " " + half.getClass().getName());
+        Object not = c.getMethod("not", boolean.class).invoke(null, true);
+        System.out.println("not: " + not + " " + not.getClass().getName());
+        Object next = c.getMethod("next", char.class).invoke(null, 'a');
+        System.out.println("next: " + next + " " + next.getClass().getName());
+        System.out.println("void: " + c.getMethod("nothing").invoke(null));
+
+        try {
+            addI.invoke(o, "1", 2);
+        } catch (IllegalArgumentException e) {
+            System.out.println("string for int: " + e.getMessage());
+        }
+        try {
+            addI.invoke(o, 1L, 2);
+        } catch (IllegalArgumentException e) {
+            System.out.println("long for int: " + e.getMessage());
+        }
+        try {
+            addI.invoke(o, 1);
+        } catch (IllegalArgumentException e) {
+            System.out.println("one argument: " + e.getMessage());
+        }
+        try {
+            addI.invoke("not a ReflectTest", 1, 2);
+        } catch (IllegalArgumentException e) {
+            System.out.println("receiver: " + e.getMessage());
+        }
+        try {
+            addI.invoke(null, 1, 2);
+        } catch (NullPointerException e) {
+            System.out.println("null receiver: " + e.getClass().getName());
+        }
+
+        Method check = c.getDeclaredMethod("check", int.class);
+        System.out.println("check 0: " + check.invoke(null, 0));
+        try {
+            check.invoke(null, 7);
+        } catch (InvocationTargetException e) {
+            System.out.println("check 7: " + e.getCause());
+        }
+        Constructor<?> ctor = c.getDeclaredConstructor(String.class);
+        try {
+            ctor.newInstance("ctor");
+        } catch (InvocationTargetException e) {
+            System.out.println("ctor: " + e.getCause());
+        }
+    }
+}
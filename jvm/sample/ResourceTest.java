@@ -0,0 +1,43 @@
+import java.io.BufferedReader;
+import java.io.InputStream;
+import java.io.InputStreamReader;
+import java.util.Properties;
+import java.util.ServiceLoader;
+
+class ResourceTest
+{
+    public interface Greeter
+    {
+        String greet();
+    }
+
+    public static class EnGreeter implements Greeter
+    {
+        public String greet()
+        {
+            return "hello";
+        }
+    }
+
+    public static void main(String[] args) throws Exception
+    {
+        ClassLoader cl = ResourceTest.class.getClassLoader();
+
+        Properties props = new Properties();
+        try (InputStream in = cl.getResourceAsStream("resource_test.properties")) {
+            props.load(in);
+        }
+        System.out.println("name = " + props.getProperty("name"));
+
+        System.out.println("missing = " + cl.getResource("no_such_resource"));
+
+        for (Greeter g : ServiceLoader.load(Greeter.class)) {
+            System.out.println("greet = " + g.greet());
+        }
+
+        //bootstrap resource
+        try (InputStream in = Object.class.getResourceAsStream("/java/lang/Object.class")) {
+            System.out.println("Object.class = " + (in != null));
+        }
+    }
+}
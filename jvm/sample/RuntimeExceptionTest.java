@@ -0,0 +1,185 @@
+/*
+The runtime exceptions of the instructions & System.arraycopy, with the
+messages of HotSpot, and the arithmetic without exceptions
+*/
+public class RuntimeExceptionTest {
+    interface Op {
+        Object run() throws Exception;
+    }
+
+    static void check(String name, Op op) {
+        try {
+            Object v = op.run();
+            System.out.println(name + " = " + v);
+        } catch (Throwable e) {
+            String msg = e.getMessage();
+            //without the module & loader of HotSpot 11+
+            if (msg != null && msg.contains(" (")) {
+                msg = msg.substring(0, msg.indexOf(" ("));
+            }
+            System.out.println(name + ": " + e.getClass().getName() + ": " + msg);
+        }
+    }
+
+    static int zero = 0;
+    static int minusOne = -1;
+
+    public static void main(String[] args) {
+        check("idiv", new Op() {
+            public Object run() {
+                return 1 / zero;
+            }
+        });
+        check("lrem", new Op() {
+            public Object run() {
+                return 1L % zero;
+            }
+        });
+        check("idiv min", new Op() {
+            public Object run() {
+                return Integer.MIN_VALUE / minusOne + ", " + Integer.MIN_VALUE % minusOne;
+            }
+        });
+        check("ldiv min", new Op() {
+            public Object run() {
+                return Long.MIN_VALUE / minusOne + ", " + -Long.MIN_VALUE;
+            }
+        });
+        check("fdiv", new Op() {
+            public Object run() {
+                float f = zero;
+                double d = zero;
+                return 1.0f / f + ", " + -1.0 / d + ", " + d / d;
+            }
+        });
+        check("frem", new Op() {
+            public Object run() {
+                float f = -7.5f;
+                double d = 7.5;
+                return f % 2.0f + ", " + d % -2.0 + ", " + d % zero;
+            }
+        });
+        check("fneg", new Op() {
+            public Object run() {
+                float f = zero;
+                double d = 1.5;
+                return -f + ", " + -d;
+            }
+        });
+
+        final int[] ints = new int[3];
+        final Object[] strs = new String[2];
+        check("iaload", new Op() {
+            public Object run() {
+                return ints[5];
+            }
+        });
+        check("iastore", new Op() {
+            public Object run() {
+                ints[-1] = 1;
+                return null;
+            }
+        });
+        check("aastore", new Op() {
+            public Object run() {
+                strs[0] = "ok";
+                strs[1] = Integer.valueOf(1);
+                return null;
+            }
+        });
+        check("newarray", new Op() {
+            public Object run() {
+                return new int[minusOne];
+            }
+        });
+        check("anewarray", new Op() {
+            public Object run() {
+                return new String[minusOne * 2];
+            }
+        });
+        check("multianewarray", new Op() {
+            public Object run() {
+                return new int[2][minusOne];
+            }
+        });
+        check("multianewarray shape", new Op() {
+            public Object run() {
+                int[][] a = new int[2][3];
+                a[1][2] = 7;
+                String[][][] s = new String[1][2][];
+                return a.length + "x" + a[0].length + " " + a[1][2] + ", " + s[0].length + " " + s[0][1];
+            }
+        });
+        check("checkcast", new Op() {
+            public Object run() {
+                Object o = Integer.valueOf(1);
+                return (String) o;
+            }
+        });
+        check("checkcast array", new Op() {
+            public Object run() {
+                Object o = ints;
+                Object c = String.class;
+                return ((int[]) o).length + ", " + (o instanceof Object[]) + ", " + (c instanceof Comparable);
+            }
+        });
+
+        check("arraycopy null", new Op() {
+            public Object run() {
+                System.arraycopy(null, 0, ints, 0, 1);
+                return null;
+            }
+        });
+        check("arraycopy not array", new Op() {
+            public Object run() {
+                System.arraycopy("abc", 0, ints, 0, 1);
+                return null;
+            }
+        });
+        check("arraycopy type", new Op() {
+            public Object run() {
+                System.arraycopy(ints, 0, new long[3], 0, 1);
+                return null;
+            }
+        });
+        check("arraycopy type object", new Op() {
+            public Object run() {
+                System.arraycopy(ints, 0, strs, 0, 1);
+                return null;
+            }
+        });
+        check("arraycopy last", new Op() {
+            public Object run() {
+                System.arraycopy(ints, 2, new int[3], 0, 2);
+                return null;
+            }
+        });
+        check("arraycopy negative", new Op() {
+            public Object run() {
+                System.arraycopy(ints, 0, new int[3], 0, -1);
+                return null;
+            }
+        });
+        check("arraycopy element", new Op() {
+            public Object run() {
+                Object[] src = {"a", "b", Integer.valueOf(3), "d"};
+                String[] dest = new String[4];
+                try {
+                    System.arraycopy(src, 0, dest, 0, 4);
+                } finally {
+                    System.out.println("copied " + dest[0] + dest[1] + dest[2] + dest[3]);
+                }
+                return null;
+            }
+        });
+        check("arraycopy", new Op() {
+            public Object run() {
+                long[] l = {1, 2, 3, 4, 5};
+                System.arraycopy(l, 0, l, 1, 4);
+                double[] d = new double[2];
+                System.arraycopy(new double[] {0.5, 1.5}, 0, d, 0, 2);
+                return java.util.Arrays.toString(l) + " " + java.util.Arrays.toString(d);
+            }
+        });
+    }
+}
@@ -0,0 +1,91 @@
+import java.io.InputStream;
+import java.io.OutputStream;
+import java.net.DatagramPacket;
+import java.net.DatagramSocket;
+import java.net.InetAddress;
+import java.net.NetworkInterface;
+import java.net.ServerSocket;
+import java.net.Socket;
+import java.net.SocketTimeoutException;
+
+class SocketTest
+{
+    public static void main(String[] args) throws Exception
+    {
+        InetAddress lo = InetAddress.getByName("127.0.0.1");
+        System.out.println("localhost = " + InetAddress.getByName("localhost").getHostAddress());
+
+        NetworkInterface ni = NetworkInterface.getByInetAddress(lo);
+        System.out.println("loopback = " + (ni != null && ni.isLoopback()));
+
+        final ServerSocket server = new ServerSocket(0, 50, lo);
+        Thread t = new Thread(new Runnable() {
+            public void run() {
+                try (Socket s = server.accept()) {
+                    InputStream in = s.getInputStream();
+                    OutputStream out = s.getOutputStream();
+                    byte[] buf = new byte[64];
+                    int n = in.read(buf);
+                    out.write(("echo " + new String(buf, 0, n)).getBytes());
+                    out.flush();
+                    //client times out, then sees the end of stream
+                    Thread.sleep(300);
+                } catch (Exception e) {
+                    e.printStackTrace();
+                }
+            }
+        });
+        t.start();
+
+        try (Socket client = new Socket(lo, server.getLocalPort())) {
+            client.setTcpNoDelay(true);
+            System.out.println("tcpNoDelay = " + client.getTcpNoDelay());
+            client.getOutputStream().write("hello".getBytes());
+
+            InputStream in = client.getInputStream();
+            byte[] buf = new byte[64];
+            int n = in.read(buf);
+            System.out.println("read = " + new String(buf, 0, n));
+
+            client.setSoTimeout(100);
+            try {
+                in.read(buf);
+                System.out.println("no timeout");
+            } catch (SocketTimeoutException e) {
+                System.out.println("timeout = " + e.getMessage());
+            }
+
+            client.setSoTimeout(0);
+            System.out.println("eof = " + in.read(buf));
+        }
+        t.join();
+        server.close();
+        System.out.println("server closed = " + server.isClosed());
+
+        try (DatagramSocket a = new DatagramSocket(0, lo);
+             DatagramSocket b = new DatagramSocket(0, lo)) {
+            byte[] data = "datagram".getBytes();
+            a.send(new DatagramPacket(data, data.length, lo, b.getLocalPort()));
+
+            DatagramPacket p = new DatagramPacket(new byte[64], 64);
+            b.receive(p);
+            System.out.println("received = " + new String(p.getData(), 0, p.getLength())
+                    + " from " + (p.getPort() == a.getLocalPort()));
+
+            b.setSoTimeout(100);
+            try {
+                b.receive(p);
+                System.out.println("no timeout");
+            } catch (SocketTimeoutException e) {
+                System.out.println("timeout = " + e.getMessage());
+            }
+        }
+
+        try {
+            new Socket(lo, server.getLocalPort()).close();
+            System.out.println("connected");
+        } catch (java.net.ConnectException e) {
+            System.out.println("refused = " + e.getMessage());
+        }
+    }
+}
@@ -0,0 +1,136 @@
+import java.lang.reflect.InvocationTargetException;
+import java.lang.reflect.Method;
+
+/*
+The stack traces filled by Throwable: the constructors of the throwable
+skipped, the line numbers of every frame & the native frames
+*/
+public class StackTraceTest {
+    static class AppException extends RuntimeException {
+        AppException(String msg) {
+            this(msg, 1);
+        }
+
+        AppException(String msg, int code) {
+            super(msg + " " + code);
+        }
+    }
+
+    static class Holder {
+        RuntimeException ex;
+
+        Holder() {
+            ex = new RuntimeException("holder");
+        }
+    }
+
+    static RuntimeException make() {
+        return new IllegalStateException("made");
+    }
+
+    static void thrower() {
+        throw make();
+    }
+
+    static void subclass() {
+        throw new AppException("sub");
+    }
+
+    static int zero = 0;
+
+    static int divide(int a) {
+        return a / zero;
+    }
+
+    static void copy() {
+        System.arraycopy(new int[1], 0, new long[1], 0, 1);
+    }
+
+    static void recurse(int n) {
+        if (n == 0) {
+            throw new RuntimeException("deep");
+        }
+        recurse(n - 1);
+    }
+
+    public static void reflected() {
+        throw new UnsupportedOperationException("reflected");
+    }
+
+    static void refill(Throwable e) {
+        e.fillInStackTrace();
+    }
+
+    //the frames of the sample & the native frames, the others differ by jdk
+    static void print(String name, Throwable e) {
+        System.out.println(name + ": " + e.getClass().getName() + ": " + e.getMessage());
+        for (StackTraceElement elm : e.getStackTrace()) {
+            if (elm.getClassName().startsWith("StackTraceTest")) {
+                System.out.println("  at " + elm.getClassName() + "." + elm.getMethodName()
+                        + "(" + elm.getFileName() + ":" + elm.getLineNumber() + ")");
+            } else if (elm.isNativeMethod()) {
+                System.out.println("  at native " + elm.getMethodName() + " " + elm.getLineNumber()
+                        + " " + elm.toString().endsWith("(Native Method)"));
+            }
+        }
+    }
+
+    public static void main(String[] args) throws Exception {
+        try {
+            thrower();
+        } catch (RuntimeException e) {
+            print("made", e);
+        }
+
+        try {
+            subclass();
+        } catch (RuntimeException e) {
+            print("subclass", e);
+        }
+
+        print("holder", new Holder().ex);
+
+        try {
+            divide(1);
+        } catch (ArithmeticException e) {
+            print("divide", e);
+        }
+
+        try {
+            copy();
+        } catch (ArrayStoreException e) {
+            print("arraycopy", e);
+        }
+
+        Method m = StackTraceTest.class.getMethod("reflected");
+        try {
+            m.invoke(null);
+        } catch (InvocationTargetException e) {
+            print("reflected", e.getCause());
+        }
+
+        Throwable t = new Throwable("refilled");
+        refill(t);
+        print("refilled", t);
+        print("filler", Filler.fillInStackTrace());
+
+        try {
+            recurse(50);
+        } catch (RuntimeException e) {
+            int n = 0;
+            for (StackTraceElement elm : e.getStackTrace()) {
+                if (elm.getMethodName().equals("recurse")) {
+                    n++;
+                }
+            }
+            System.out.println("deep: recurse frames = " + n);
+        }
+    }
+
+    //not a Throwable, its fillInStackTrace frame kept
+    static class Filler {
+        static Throwable fillInStackTrace() {
+            return new Throwable("filler");
+        }
+    }
+}
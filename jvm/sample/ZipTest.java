@@ -0,0 +1,94 @@
+import java.io.ByteArrayInputStream;
+import java.io.ByteArrayOutputStream;
+import java.io.File;
+import java.io.FileOutputStream;
+import java.io.InputStream;
+import java.util.Enumeration;
+import java.util.zip.Adler32;
+import java.util.zip.CRC32;
+import java.util.zip.Deflater;
+import java.util.zip.GZIPInputStream;
+import java.util.zip.GZIPOutputStream;
+import java.util.zip.Inflater;
+import java.util.zip.ZipEntry;
+import java.util.zip.ZipFile;
+import java.util.zip.ZipOutputStream;
+
+class ZipTest
+{
+    public static void main(String[] args) throws Exception
+    {
+        byte[] data = "hello, hello, hello, zip".getBytes("UTF-8");
+
+        CRC32 crc = new CRC32();
+        crc.update(data);
+        System.out.println("crc32 = " + Long.toHexString(crc.getValue()));
+        Adler32 adler = new Adler32();
+        adler.update(data);
+        System.out.println("adler32 = " + Long.toHexString(adler.getValue()));
+
+        //deflate & inflate
+        Deflater def = new Deflater();
+        def.setInput(data);
+        def.finish();
+        byte[] buf = new byte[128];
+        int n = def.deflate(buf);
+        def.end();
+
+        Inflater inf = new Inflater();
+        inf.setInput(buf, 0, n);
+        byte[] out = new byte[128];
+        int m = inf.inflate(out);
+        System.out.println("inflated = " + new String(out, 0, m, "UTF-8") + ", finished = " + inf.finished());
+        inf.end();
+
+        //zlib header with FDICT, stops for the dictionary after the 6 header bytes
+        byte[] dict = {0x78, (byte) 0xbb, 0x06, 0x2c, 0x02, 0x15, 0x03, 0x00};
+        inf = new Inflater();
+        inf.setInput(dict);
+        m = inf.inflate(out);
+        System.out.println("needsDictionary = " + inf.needsDictionary() + ", inflated = " + m + ", remaining = " + inf.getRemaining());
+        inf.end();
+
+        //gzip
+        ByteArrayOutputStream bos = new ByteArrayOutputStream();
+        try (GZIPOutputStream gz = new GZIPOutputStream(bos)) {
+            gz.write(data);
+        }
+        try (InputStream in = new GZIPInputStream(new ByteArrayInputStream(bos.toByteArray()))) {
+            System.out.println("gunzip = " + new String(readAll(in), "UTF-8"));
+        }
+
+        //zip file
+        File f = File.createTempFile("zip_test", ".zip");
+        try (ZipOutputStream zos = new ZipOutputStream(new FileOutputStream(f))) {
+            zos.putNextEntry(new ZipEntry("dir/"));
+            zos.putNextEntry(new ZipEntry("dir/a.txt"));
+            zos.write(data);
+            zos.closeEntry();
+        }
+        try (ZipFile zf = new ZipFile(f)) {
+            for (Enumeration<? extends ZipEntry> e = zf.entries(); e.hasMoreElements();) {
+                ZipEntry ze = e.nextElement();
+                System.out.println("entry " + ze.getName() + ", size = " + ze.getSize());
+            }
+            ZipEntry ze = zf.getEntry("dir/a.txt");
+            try (InputStream in = zf.getInputStream(ze)) {
+                System.out.println("dir/a.txt = " + new String(readAll(in), "UTF-8"));
+            }
+            System.out.println("dir = " + zf.getEntry("dir").getName());
+        }
+        f.delete();
+    }
+
+    private static byte[] readAll(InputStream in) throws Exception
+    {
+        ByteArrayOutputStream bos = new ByteArrayOutputStream();
+        byte[] buf = new byte[64];
+        int n;
+        while ((n = in.read(buf)) > 0) {
+            bos.write(buf, 0, n);
+        }
+        return bos.toByteArray();
+    }
+}
@@ -0,0 +1,25 @@
+/*
+Lib of LinkageTest changed, compiled over the first one: fields & methods
+removed, made private, or static changed; Shape a class
+*/
+class Lib {
+    public static int sf = 1;
+    public int f = 2;
+    public static int moved = 3;
+    private static int hidden = 4;
+
+    private static void pm() {
+    }
+
+    public void mm() {
+    }
+
+    public static void im() {
+    }
+}
+
+class Shape {
+    int area() {
+        return 0;
+    }
+}
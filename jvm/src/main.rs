@@ -1,29 +1,70 @@
-extern crate clap;
 extern crate env_logger;
 
+mod manifest;
 mod options;
 
+use options::Launch;
 use vm;
 use vm::runtime::{self, thread::MainThread};
 use vm::util;
 
 fn main() {
     env_logger::init();
-    vm::init_vm();
 
-    let opt = options::parse();
+    let opt = match options::parse() {
+        Ok(opt) => opt,
+        Err(e) => {
+            eprintln!("{}", e);
+            eprintln!("Error: Could not create the Java Virtual Machine.");
+            eprintln!("Error: A fatal exception has occurred. Program will exit.");
+            std::process::exit(1);
+        }
+    };
+
+    let mut vm_opts = opt.vm_opts;
+    let (class, class_path) = match opt.launch {
+        Launch::Exit => return,
+        //-cp ignored, class path from manifest
+        Launch::Jar(jar) => {
+            let m = match manifest::read(&jar) {
+                Ok(m) => m,
+                Err(e) => {
+                    eprintln!("{}", e);
+                    std::process::exit(1);
+                }
+            };
 
-    if let Some(cp) = &opt.cp {
-        runtime::add_class_paths(cp);
-    }
+            let class = match &m.main_class {
+                Some(class) => class.clone(),
+                None => {
+                    eprintln!("no main manifest attribute, in {}", jar);
+                    std::process::exit(1);
+                }
+            };
 
-    if let Some(classpath) = &opt.classpath {
-        runtime::add_class_path(classpath);
-    }
+            let mut cp = vec![jar.clone()];
+            cp.extend(manifest::resolve_class_path(&jar, &m));
+            (class, cp.join(util::PATH_SEP))
+        }
+        //-cp, -Djava.class.path, CLASSPATH, "."
+        Launch::Class(class) => {
+            let cp = opt
+                .class_path
+                .or_else(|| vm_opts.class_path.take())
+                .or_else(|| std::env::var("CLASSPATH").ok())
+                .unwrap_or_else(|| ".".to_string());
+            (class, cp)
+        }
+    };
+
+    //boot class path of vm built from options
+    vm_opts.class_path = Some(class_path.clone());
+    runtime::options::set(vm_opts);
+    vm::init_vm();
+    runtime::add_class_paths(&class_path);
 
-    let class = opt.class;
     let args = opt.args;
-    // println!("main class: {}, args: {:?}", class, args);
     let mut thread = MainThread::new(class.replace(".", util::FILE_SEP), args);
-    thread.run();
+    let code = thread.run();
+    std::process::exit(code);
 }
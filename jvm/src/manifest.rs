@@ -0,0 +1,105 @@
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use zip::ZipArchive;
+
+//META-INF/MANIFEST.MF, main section only
+pub struct Manifest {
+    pub main_class: Option<String>,
+    //urls of 'Class-Path', relative to the jar
+    pub class_path: Vec<String>,
+}
+
+pub fn read(jar: &str) -> Result<Manifest, String> {
+    let err =
+        |e: &dyn std::fmt::Display| format!("Error: Invalid or corrupt jarfile {}: {}", jar, e);
+
+    let f =
+        File::open(jar).map_err(|e| format!("Error: Unable to access jarfile {}: {}", jar, e))?;
+    let mut z = ZipArchive::new(f).map_err(|e| err(&e))?;
+    let mut zf = z.by_name("META-INF/MANIFEST.MF").map_err(|e| err(&e))?;
+    let mut buf = Vec::with_capacity(zf.size() as usize);
+    zf.read_to_end(&mut buf).map_err(|e| err(&e))?;
+
+    let text = String::from_utf8_lossy(&buf);
+    Ok(parse(&text))
+}
+
+//entries of 'Class-Path' as file paths, resolved against the dir of jar
+pub fn resolve_class_path(jar: &str, manifest: &Manifest) -> Vec<String> {
+    let dir = Path::new(jar).parent().unwrap_or_else(|| Path::new(""));
+    manifest
+        .class_path
+        .iter()
+        .map(|v| {
+            let v = v.strip_prefix("file:").unwrap_or(v);
+            if Path::new(v).is_absolute() {
+                v.to_string()
+            } else {
+                dir.join(v).to_string_lossy().to_string()
+            }
+        })
+        .collect()
+}
+
+fn parse(text: &str) -> Manifest {
+    //a line starting with a space continues the previous one
+    let mut lines: Vec<String> = vec![];
+    for line in text.lines() {
+        //main section ends at the first empty line
+        if line.is_empty() {
+            break;
+        }
+
+        match (line.strip_prefix(' '), lines.last_mut()) {
+            (Some(v), Some(last)) => last.push_str(v),
+            _ => lines.push(line.to_string()),
+        }
+    }
+
+    let mut main_class = None;
+    let mut class_path = vec![];
+    for line in lines.iter() {
+        let idx = match line.find(':') {
+            Some(idx) => idx,
+            None => continue,
+        };
+        let (k, v) = (line[..idx].trim(), line[idx + 1..].trim());
+
+        if k.eq_ignore_ascii_case("Main-Class") {
+            main_class = Some(v.to_string());
+        } else if k.eq_ignore_ascii_case("Class-Path") {
+            class_path = v.split_whitespace().map(|v| v.to_string()).collect();
+        }
+    }
+
+    Manifest {
+        main_class,
+        class_path,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn t_parse() {
+        let text = "Manifest-Version: 1.0\r\n\
+                    Main-Class: my.app.Ma\r\n in\r\n\
+                    Class-Path: lib/a.jar  lib/b.jar\r\n\
+                    \r\n\
+                    Name: other\r\n\
+                    Main-Class: Wrong\r\n";
+        let m = parse(text);
+        assert_eq!(m.main_class, Some("my.app.Main".to_string()));
+        assert_eq!(m.class_path, vec!["lib/a.jar", "lib/b.jar"]);
+
+        let cp = resolve_class_path("/opt/app/app.jar", &m);
+        assert_eq!(cp, vec!["/opt/app/lib/a.jar", "/opt/app/lib/b.jar"]);
+
+        let m = parse("Manifest-Version: 1.0\n");
+        assert_eq!(m.main_class, None);
+        assert!(m.class_path.is_empty());
+    }
+}
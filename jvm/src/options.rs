@@ -1,22 +1,178 @@
-use clap::Clap;
+use vm::runtime::options::VMOptions;
 
-#[derive(Clap, Debug)]
-#[clap(version)]
-pub struct Opt {
-    /// class search path of directories and zip/jar files
-    #[clap(long)]
-    pub cp: Option<String>,
+/*
+Command line of launcher, compatible with hotspot 'java':
+
+  jvm [options] <mainclass> [args...]
+  jvm [options] -jar <jarfile> [args...]
+
+options not handled by launcher are passed to vm, see VMOptions::parse_option
+*/
 
-    /// class search path of directories and zip/jar files
-    #[clap(long)]
-    pub classpath: Option<String>,
+const USAGE: &str = "Usage: jvm [options] <mainclass> [args...]
+           (to execute a class)
+   or  jvm [options] -jar <jarfile> [args...]
+           (to execute a jar file)
+where options include:
+    -cp <class search path of directories and zip/jar files>
+    -classpath <class search path of directories and zip/jar files>
+                  A : separated list of directories, JAR archives,
+                  and ZIP archives to search for class files.
+    -D<name>=<value>
+                  set a system property
+    -verbose:[class|gc|jni]
+                  enable verbose output
+    -version      print product version and exit
+    -showversion  print product version and continue
+    -? -help      print this help message
+    -ea[:<packagename>...|:<classname>]
+    -enableassertions[:<packagename>...|:<classname>]
+                  enable assertions with specified granularity
+    -da[:<packagename>...|:<classname>]
+    -disableassertions[:<packagename>...|:<classname>]
+                  disable assertions with specified granularity
+    -esa | -enablesystemassertions
+                  enable system assertions
+    -dsa | -disablesystemassertions
+                  disable system assertions
+    -Xbootclasspath:<directories and zip/jar files separated by :>
+                  set search path for bootstrap classes and resources
+    -Xbootclasspath/a:<directories and zip/jar files separated by :>
+                  append to end of bootstrap class path
+    -Xbootclasspath/p:<directories and zip/jar files separated by :>
+                  prepend in front of bootstrap class path
+    -Xint         interpreted mode execution only
+    -Xmixed       mixed mode execution (default)
+    -Xms<size>    set initial Java heap size
+    -Xmx<size>    set maximum Java heap size
+    -Xss<size>    set java thread stack size
+    -XX:+<name> -XX:-<name> -XX:<name>=<value>
+                  set vm flags";
 
-    #[clap(required = true)]
-    pub class: String,
+#[derive(Debug, PartialEq)]
+pub enum Launch {
+    Class(String),
+    Jar(String),
+    //-version, -help, nothing to run
+    Exit,
+}
 
+#[derive(Debug)]
+pub struct Opt {
+    pub vm_opts: VMOptions,
+    //-cp, -classpath
+    pub class_path: Option<String>,
+    pub launch: Launch,
     pub args: Vec<String>,
 }
 
-pub fn parse() -> Opt {
-    Opt::parse()
+pub fn parse() -> Result<Opt, String> {
+    parse_args(std::env::args().skip(1))
+}
+
+pub fn print_version() {
+    eprintln!("java version \"1.8\"");
+    eprintln!("jvm (build {})", env!("CARGO_PKG_VERSION"));
+}
+
+fn parse_args<I>(args: I) -> Result<Opt, String>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut vm_opts = VMOptions::default();
+    let mut class_path = None;
+    let mut is_jar = false;
+    let mut args = args.into_iter();
+
+    let target = loop {
+        let arg = match args.next() {
+            Some(arg) => arg,
+            None => break None,
+        };
+
+        match arg.as_str() {
+            //"--cp" & "--classpath" kept for the old launcher
+            "-cp" | "-classpath" | "--cp" | "--classpath" => match args.next() {
+                Some(v) => class_path = Some(v),
+                None => return Err(format!("{} requires class path specification", arg)),
+            },
+            "-jar" => is_jar = true,
+            "-version" | "--version" => {
+                print_version();
+                return Ok(Opt::exit(vm_opts));
+            }
+            "-showversion" => print_version(),
+            "-?" | "-h" | "-help" | "--help" => {
+                println!("{}", USAGE);
+                return Ok(Opt::exit(vm_opts));
+            }
+            _ if arg.starts_with('-') => vm_opts.parse_option(&arg)?,
+            _ => break Some(arg),
+        }
+    };
+
+    let launch = match target {
+        Some(v) if is_jar => Launch::Jar(v),
+        Some(v) => Launch::Class(v),
+        None => return Err(USAGE.to_string()),
+    };
+
+    Ok(Opt {
+        vm_opts,
+        class_path,
+        launch,
+        args: args.collect(),
+    })
+}
+
+impl Opt {
+    fn exit(vm_opts: VMOptions) -> Self {
+        Self {
+            vm_opts,
+            class_path: None,
+            launch: Launch::Exit,
+            args: vec![],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Opt, String> {
+        parse_args(args.iter().map(|v| v.to_string()))
+    }
+
+    #[test]
+    fn t_class() {
+        let opt = parse(&["-cp", "a:b", "-Dk=v", "-ea", "my.Main", "-cp", "x"]).unwrap();
+        assert_eq!(opt.class_path, Some("a:b".to_string()));
+        assert_eq!(opt.launch, Launch::Class("my.Main".to_string()));
+        assert_eq!(opt.args, vec!["-cp".to_string(), "x".to_string()]);
+        assert_eq!(
+            opt.vm_opts.properties,
+            vec![("k".to_string(), "v".to_string())]
+        );
+        assert!(opt.vm_opts.desired_assertion_status("my/Main"));
+
+        let opt = parse(&["--cp", "a", "Main"]).unwrap();
+        assert_eq!(opt.class_path, Some("a".to_string()));
+    }
+
+    #[test]
+    fn t_jar() {
+        let opt = parse(&["-verbose:class", "-jar", "app.jar", "arg"]).unwrap();
+        assert_eq!(opt.launch, Launch::Jar("app.jar".to_string()));
+        assert_eq!(opt.args, vec!["arg".to_string()]);
+        assert!(opt.vm_opts.verbose_class);
+    }
+
+    #[test]
+    fn t_errors() {
+        assert!(parse(&[]).is_err());
+        assert!(parse(&["-cp"]).is_err());
+        assert!(parse(&["-foo", "Main"]).is_err());
+        assert_eq!(parse(&["-help"]).unwrap().launch, Launch::Exit);
+    }
 }
@@ -5,7 +5,6 @@ JAVA_HOME=/Library/Java/JavaVirtualMachines/jdk1.8.0_151.jdk/Contents/Home/jre
 JDK_SRC=/Users/douchuan/work/codes/vm/openjdk8
 ########################################
 
-JDK=$JAVA_HOME/lib/resources.jar:$JAVA_HOME/lib/rt.jar:$JAVA_HOME/lib/jsse.jar:$JAVA_HOME/lib/jce.jar:$JAVA_HOME/lib/charsets.jar:$JAVA_HOME/lib/jfr.jar
 JDK_T_LANG=$JDK_SRC/jdk/test/java/lang
 JDK_T_SM=$JDK_SRC/jdk/test/sun/misc
 MY_TEST=.:./test
@@ -19,35 +18,35 @@ export RUST_BACKTRACE=full
 
 
 ### My Test
-#cargo run -- --cp $JDK:$MY_TEST Add
-#cargo run -- --cp $JDK:$MY_TEST MyHelloWorld 123 456 789
-#cargo run -- --cp $JDK:$MY_TEST HelloWorldUnicode
-#cargo run -- --cp $JDK:$MY_TEST Ex
-#cargo run -- --cp $JDK:$MY_TEST MyFile
-#cargo run -- --cp $JDK:$MY_TEST MyInteger
-#cargo run -- --cp $JDK:$MY_TEST MyArrayCopy
-#cargo run -- --cp $JDK:$MY_TEST ThreadTest
-#cargo run -- --cp $JDK:$MY_TEST ThreadTest2
+#cargo run -- -cp $MY_TEST Add
+#cargo run -- -cp $MY_TEST MyHelloWorld 123 456 789
+#cargo run -- -cp $MY_TEST HelloWorldUnicode
+#cargo run -- -cp $MY_TEST Ex
+#cargo run -- -cp $MY_TEST MyFile
+#cargo run -- -cp $MY_TEST MyInteger
+#cargo run -- -cp $MY_TEST MyArrayCopy
+#cargo run -- -cp $MY_TEST ThreadTest
+#cargo run -- -cp $MY_TEST ThreadTest2
 ### no 'join' in main thread
-#cargo run -- --cp $JDK:$MY_TEST ThreadTest3
+#cargo run -- -cp $MY_TEST ThreadTest3
 
 ### fix Overflow
-#cargo run -- --cp $JDK:$MY_TEST SubOverflow
+#cargo run -- -cp $MY_TEST SubOverflow
 
 ### fix Enum CloneNotSupportedException
-#cargo run -- --cp $JDK:$MY_TEST EnumDemo
+#cargo run -- -cp $MY_TEST EnumDemo
 
 ### fix System.out.printf not work, resolve_again for acc_flags == 0
-#cargo run -- --cp $JDK:$MY_TEST Printf
+#cargo run -- -cp $MY_TEST Printf
 
 ### fix ThreadLocal not work, resolve_again for protected
-#cargo run -- --cp $JDK:$MY_TEST ThreadLocalTest
+#cargo run -- -cp $MY_TEST ThreadLocalTest
 
 ### load with custom package
 ## should panic
-#cargo run -- --cp $JDK:$MY_TEST test/with_package/my.ns.HelloWorld
+#cargo run -- -cp $MY_TEST test/with_package/my.ns.HelloWorld
 ## ok
-#cargo run -- --cp $JDK:$MY_TEST:test/with_package my.ns.HelloWorld
+#cargo run -- -cp $MY_TEST:test/with_package my.ns.HelloWorld
 
 
 ###regex
@@ -66,49 +65,49 @@ export RUST_BACKTRACE=full
 ##
 ## Modified UTF-8 strings 编码定义:
 ## JVM Spec, 4.4.7 The CONSTANT_Utf8_info Structure 定义
-#cargo run -- --cp $JDK:$MY_TEST:./test/regex Printf
+#cargo run -- -cp $MY_TEST:./test/regex Printf
 
 ###
 ##Float.toString(1.0f) crash
 ##ThreadLocal.initialValue not called, so NPE happend
-#cargo run -- --cp $JDK:$MY_TEST:./test/float ToString
-#cargo run -- --cp $JDK:$MY_TEST:./test/char MyCheckScript
-#cargo run -- --cp $JDK:$MY_TEST:./test/annotation AnnotationTest
+#cargo run -- -cp $MY_TEST:./test/float ToString
+#cargo run -- -cp $MY_TEST:./test/char MyCheckScript
+#cargo run -- -cp $MY_TEST:./test/annotation AnnotationTest
 
 ###############################
 ### jdk test
 ###############################
-#cargo run -- --cp $JDK:$JDK_T_LANG Compare
-#cargo run -- --cp $JDK:$JDK_T_LANG HashCode
-#cargo run -- --cp $JDK:$JDK_T_LANG ToString
-#cargo run -- --cp $JDK:$JDK_T_LANG:$JDK_T_LANG/Appendable Basic
-#cargo run -- --cp $JDK:$JDK_T_LANG:$JDK_T_LANG/AssertionError Cause
-#cargo run -- --cp $JDK:$JDK_T_LANG:$JDK_T_LANG/Boolean Factory
-#cargo run -- --cp $JDK:$JDK_T_LANG:$JDK_T_LANG/Boolean GetBoolean
-#cargo run -- --cp $JDK:$JDK_T_LANG:$JDK_T_LANG/Boolean MakeBooleanComparable
-#cargo run -- --cp $JDK:$JDK_T_LANG:$JDK_T_LANG/Boolean ParseBoolean
-#cargo run -- --cp $JDK:$JDK_T_LANG:$JDK_T_LANG/Byte Decode
-#cargo run -- --cp $JDK:$JDK_T_LANG:$JDK_T_LANG/Class ArrayMethods
-#cargo run -- --cp $JDK:$JDK_T_LANG:$JDK_T_LANG/Class GenericStringTest
-#cargo run -- --cp $JDK:$JDK_T_LANG:$JDK_T_LANG/Class IsEnum
+#cargo run -- -cp $JDK_T_LANG Compare
+#cargo run -- -cp $JDK_T_LANG HashCode
+#cargo run -- -cp $JDK_T_LANG ToString
+#cargo run -- -cp $JDK_T_LANG:$JDK_T_LANG/Appendable Basic
+#cargo run -- -cp $JDK_T_LANG:$JDK_T_LANG/AssertionError Cause
+#cargo run -- -cp $JDK_T_LANG:$JDK_T_LANG/Boolean Factory
+#cargo run -- -cp $JDK_T_LANG:$JDK_T_LANG/Boolean GetBoolean
+#cargo run -- -cp $JDK_T_LANG:$JDK_T_LANG/Boolean MakeBooleanComparable
+#cargo run -- -cp $JDK_T_LANG:$JDK_T_LANG/Boolean ParseBoolean
+#cargo run -- -cp $JDK_T_LANG:$JDK_T_LANG/Byte Decode
+#cargo run -- -cp $JDK_T_LANG:$JDK_T_LANG/Class ArrayMethods
+#cargo run -- -cp $JDK_T_LANG:$JDK_T_LANG/Class GenericStringTest
+#cargo run -- -cp $JDK_T_LANG:$JDK_T_LANG/Class IsEnum
 ##TODO: GC needed
-#cargo run -- --cp $JDK:$JDK_T_LANG:$JDK_T_LANG/Class TypeCheckMicroBenchmark
-#cargo run -- --cp $JDK:$JDK_T_LANG:$JDK_T_LANG/Class Cast
-#cargo run -- --cp $JDK:$JDK_T_LANG:$JDK_T_LANG/Class IsAnnotationType
-#cargo run -- --cp $JDK:$JDK_T_LANG:$JDK_T_LANG/Class IsSynthetic
-#cargo run -- --cp $JDK:$JDK_T_LANG:$JDK_T_LANG/Class/asSubclass BasicUnit
-#cargo run -- --cp $JDK:$JDK_T_LANG:$JDK_T_LANG/Class/forName InitArg
-#cargo run -- --cp $JDK:$JDK_T_LANG:$JDK_T_LANG/Class/forName InvalidNameWithSlash
-#cargo run -- --cp $JDK:$JDK_T_LANG:$JDK_T_LANG/Class/forName NonJavaNames
-#cargo run -- --cp $JDK:$JDK_T_LANG:$JDK_T_LANG/Class/forName Z
-#cargo run -- --cp $JDK:$JDK_T_LANG:$JDK_T_LANG/Class/getClasses Sanity
-
-#cargo run -- --cp $JDK:$JDK_T_LANG:$JDK_T_LANG/Math AbsPositiveZero
+#cargo run -- -cp $JDK_T_LANG:$JDK_T_LANG/Class TypeCheckMicroBenchmark
+#cargo run -- -cp $JDK_T_LANG:$JDK_T_LANG/Class Cast
+#cargo run -- -cp $JDK_T_LANG:$JDK_T_LANG/Class IsAnnotationType
+#cargo run -- -cp $JDK_T_LANG:$JDK_T_LANG/Class IsSynthetic
+#cargo run -- -cp $JDK_T_LANG:$JDK_T_LANG/Class/asSubclass BasicUnit
+#cargo run -- -cp $JDK_T_LANG:$JDK_T_LANG/Class/forName InitArg
+#cargo run -- -cp $JDK_T_LANG:$JDK_T_LANG/Class/forName InvalidNameWithSlash
+#cargo run -- -cp $JDK_T_LANG:$JDK_T_LANG/Class/forName NonJavaNames
+#cargo run -- -cp $JDK_T_LANG:$JDK_T_LANG/Class/forName Z
+#cargo run -- -cp $JDK_T_LANG:$JDK_T_LANG/Class/getClasses Sanity
+
+#cargo run -- -cp $JDK_T_LANG:$JDK_T_LANG/Math AbsPositiveZero
 
 ##jdk/test/sun/misc
 #TODO: CopyMemory getUnsafe return null
-#cargo run -- --cp $JDK:$JDK_T_LANG:$JDK_T_SM MyCopyMemory
-#cargo run -- --cp $JDK:$JDK_T_LANG:$JDK_T_SM Safe
+#cargo run -- -cp $JDK_T_LANG:$JDK_T_SM MyCopyMemory
+#cargo run -- -cp $JDK_T_LANG:$JDK_T_SM Safe
 
 ##TODO: depend on testng
 ##IntegralPrimitiveToString.java, PrimitiveSumMinMaxTest.java
@@ -132,23 +131,23 @@ export RUST_BACKTRACE=full
 #sum_t_println = 768
 #sum_t_int2integer = 1255
 #export TEST_SRC=$JDK_SRC/jdk/test/java/lang/Character
-#cargo run --release -- --cp $JDK:$JDK_T_LANG:$JDK_T_LANG/Character MyCheckProp
-#cargo run --release -- --cp $JDK:$JDK_T_LANG:$JDK_T_LANG/Character MyCheckProp1
-#cargo run --release -- --cp $JDK:$JDK_T_LANG:$JDK_T_LANG/Character CheckProp
-#cargo run --release -- --cp $JDK:$JDK_T_LANG:$JDK_T_LANG/Character CheckScript
+#cargo run --release -- -cp $JDK_T_LANG:$JDK_T_LANG/Character MyCheckProp
+#cargo run --release -- -cp $JDK_T_LANG:$JDK_T_LANG/Character MyCheckProp1
+#cargo run --release -- -cp $JDK_T_LANG:$JDK_T_LANG/Character CheckProp
+#cargo run --release -- -cp $JDK_T_LANG:$JDK_T_LANG/Character CheckScript
 
-#cargo run -- --cp $JDK:$JDK_T_LANG:$JDK_T_LANG/reflect/Constructor TestParameterAnnotations
+#cargo run -- -cp $JDK_T_LANG:$JDK_T_LANG/reflect/Constructor TestParameterAnnotations
 
 ##TODO: NonJavaNames just ignored currently
 ##NonJavaNames
 
 ##TODO: impl getDeclaredClasses0
-#cargo run -- --cp $JDK:$JDK_T_LANG:$JDK_T_LANG/Class/getClasses Sanity
+#cargo run -- -cp $JDK_T_LANG:$JDK_T_LANG/Class/getClasses Sanity
 
 
 ############################################
 ###perf (linux)
 ############################################
 #export TEST_SRC=$JDK_SRC/jdk/test/java/lang/Character
-#perf record --call-graph dwarf -- cargo run --release -- --cp $JDK:$JDK_T_LANG:$JDK_T_LANG/Character MyCheckProp
+#perf record --call-graph dwarf -- cargo run --release -- -cp $JDK_T_LANG:$JDK_T_LANG/Character MyCheckProp
 #perf report
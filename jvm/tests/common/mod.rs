@@ -0,0 +1,121 @@
+#![allow(dead_code)]
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/*
+Compiles sample/<name>.java and runs it in the vm, the stdout returned.
+
+Run twice, interpreted only (-Xint) and with every method compiled at its
+first invocation, the outputs must be the same.
+
+Needs JAVA_HOME of a jre 8 (lib/rt.jar as the boot class path) and javac:
+without them the sample is skipped, None returned and the reason printed.
+Set JVM_TEST_REQUIRE_JDK to panic instead, where a jdk 8 must be found.
+*/
+pub fn run_sample(name: &str) -> Option<String> {
+    run_sample_with(name, |_| true)
+}
+
+//as run_sample, the classes compiled changed by 'patch' before run
+pub fn run_sample_with(name: &str, patch: impl FnOnce(&Path) -> bool) -> Option<String> {
+    run(name, &[], patch)
+}
+
+//as run_sample, the vm run with the options 'opts' too
+pub fn run_sample_opts(name: &str, opts: &[&str]) -> Option<String> {
+    run(name, opts, |_| true)
+}
+
+//the stdout of a run not skipped compared to sample/<name>.expected
+pub fn assert_expected(name: &str, stdout: Option<String>) {
+    if let Some(stdout) = stdout {
+        assert_eq!(stdout, expected(name));
+    }
+}
+
+pub fn expected(name: &str) -> String {
+    let path = sample_path(&format!("{}.expected", name));
+    std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("{:?}: {}", path, e))
+}
+
+fn run(name: &str, opts: &[&str], patch: impl FnOnce(&Path) -> bool) -> Option<String> {
+    let java_home = find_jdk(name)?;
+
+    let out_dir = std::env::temp_dir().join(format!("jvm_test_{}_{}", name, std::process::id()));
+    std::fs::create_dir_all(&out_dir).unwrap();
+    let sample = sample_path(&format!("{}.java", name));
+    if !javac(&out_dir, &[sample]) {
+        let _ = std::fs::remove_dir_all(&out_dir);
+        panic!("javac of {} failed", name);
+    }
+    assert!(patch(&out_dir), "patch of {} failed", name);
+
+    let interpreted = [opts, &["-Xint"]].concat();
+    let compiled = [opts, &["-XX:CompileThreshold=1"]].concat();
+    let interpreted = run_vm(&java_home, &out_dir, name, &interpreted);
+    let compiled = run_vm(&java_home, &out_dir, name, &compiled);
+    let _ = std::fs::remove_dir_all(&out_dir);
+
+    assert_eq!(interpreted, compiled, "-Xint & compiled differ");
+    Some(interpreted)
+}
+
+//JAVA_HOME, if it has lib/rt.jar and javac runs
+fn find_jdk(name: &str) -> Option<String> {
+    let java_home = std::env::var("JAVA_HOME")
+        .ok()
+        .filter(|v| Path::new(v).join("lib").join("rt.jar").exists());
+    let missing = match java_home {
+        None => "JAVA_HOME of a jre 8, with lib/rt.jar",
+        Some(_) if Command::new("javac").arg("-version").output().is_err() => "javac",
+        Some(v) => return Some(v),
+    };
+
+    if std::env::var_os("JVM_TEST_REQUIRE_JDK").is_some() {
+        panic!("{} not found", missing);
+    }
+    eprintln!("skipped {}: {} not found", name, missing);
+    None
+}
+
+pub fn sample_path(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("sample")
+        .join(name)
+}
+
+//the sources compiled for java 8 into 'out_dir', which is the class path too
+pub fn javac(out_dir: &Path, sources: &[PathBuf]) -> bool {
+    let compiled = Command::new("javac")
+        .arg("-source")
+        .arg("8")
+        .arg("-target")
+        .arg("8")
+        .arg("-cp")
+        .arg(out_dir)
+        .arg("-d")
+        .arg(out_dir)
+        .args(sources)
+        .status();
+    matches!(compiled, Ok(status) if status.success())
+}
+
+fn run_vm(java_home: &str, cp: &Path, name: &str, opts: &[&str]) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_jvm"))
+        .env("JAVA_HOME", java_home)
+        .args(opts)
+        .arg("-cp")
+        .arg(cp)
+        .arg(name)
+        .output()
+        .unwrap();
+
+    assert!(
+        output.status.success(),
+        "{:?} stderr: {}",
+        opts,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8_lossy(&output.stdout).to_string()
+}
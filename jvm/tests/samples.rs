@@ -0,0 +1,124 @@
+mod common;
+
+use std::fs;
+use std::path::Path;
+
+/*
+Runs the programs of sample/ in the vm, each stdout compared to
+sample/<name>.expected; the samples needing classes changed or options
+have tests of their own below.
+*/
+
+macro_rules! samples {
+    ($($test:ident: $name:literal,)*) => {
+        $(
+            #[test]
+            fn $test() {
+                common::assert_expected($name, common::run_sample($name));
+            }
+        )*
+    };
+}
+
+samples! {
+    //class initialization of JVMS 5.5: triggers, recursive requests,
+    //ExceptionInInitializerError, NoClassDefFoundError after a failed
+    //<clinit>, a thread waiting for another one initializing, and
+    //superinterfaces with default methods
+    t_class_init: "ClassInitTest",
+    //the atomics, ConcurrentHashMap, double-checked locking and Unsafe CAS
+    //of a static field hammered by 4 threads
+    t_concurrency: "ConcurrencyTest",
+    //a daemon thread still running at exit, a non-daemon one waited for
+    t_daemon: "DaemonTest",
+    //invokevirtual & invokeinterface selecting by v_table & itables
+    t_dispatch: "DispatchTest",
+    //in a dir of java.io.tmpdir: the natives of UnixFileSystem,
+    //FileInputStream, FileOutputStream and RandomAccessFile
+    t_file: "FileTest",
+    //the hot methods compiled by the baseline compiler, and deoptimized
+    t_jit: "JitTest",
+    //a ServerSocket & Socket against each other on 127.0.0.1
+    t_socket_loopback: "SocketTest",
+    //FileChannel & direct buffers, Files.readAllBytes, a Selector over a Pipe
+    t_nio_file_channel_selector: "NioTest",
+    //child processes with pipes, environment, working dir & redirects
+    t_child_process: "ProcessTest",
+    //a proxy class defined into the loader of its interface
+    t_proxy: "ProxyTest",
+    //Method.invoke & Constructor.newInstance: the method found by its slot
+    //among overloads, the arguments unboxed & widened, the results boxed, and
+    //the exceptions of the target wrapped by InvocationTargetException
+    t_reflect: "ReflectTest",
+    //the weak & phantom references enqueued by System.gc(), finalize()
+    t_reference: "ReferenceTest",
+    //the runtime exceptions of the instructions with the messages of HotSpot
+    t_runtime_exception: "RuntimeExceptionTest",
+    //the stack traces filled without the constructors of the throwable
+    t_stack_trace: "StackTraceTest",
+    //CRC32, Adler32, Deflater & Inflater, gzip streams and a ZipFile
+    t_zip: "ZipTest",
+}
+
+//the catch type not found (GoneException.class deleted) met only when reached
+#[test]
+fn t_exception() {
+    let patch = |out_dir: &Path| fs::remove_file(out_dir.join("GoneException.class")).is_ok();
+    let stdout = common::run_sample_with("ExceptionTest", patch);
+    common::assert_expected("ExceptionTest", stdout);
+}
+
+//compiled against classes changed before the run (sample/linkage): the
+//errors of the resolution met at the sites resolving the stale references
+#[test]
+fn t_linkage() {
+    let patch = |out_dir: &Path| {
+        common::javac(out_dir, &[common::sample_path("linkage/Lib.java")])
+            && fs::remove_file(out_dir.join("Gone.class")).is_ok()
+    };
+    let stdout = common::run_sample_with("LinkageTest", patch);
+    common::assert_expected("LinkageTest", stdout);
+}
+
+//the resources of the class path of app (resource_test.properties, a
+//ServiceLoader provider of META-INF/services) and of the boot class path
+#[test]
+fn t_resource() {
+    let patch = |out_dir: &Path| {
+        let services = Path::new("META-INF").join("services");
+        let copy = |name: &Path| {
+            fs::copy(
+                common::sample_path(name.to_str().unwrap()),
+                out_dir.join(name),
+            )
+            .is_ok()
+        };
+        fs::create_dir_all(out_dir.join(&services)).is_ok()
+            && copy(Path::new("resource_test.properties"))
+            && copy(&services.join("ResourceTest$Greeter"))
+    };
+    let stdout = common::run_sample_with("ResourceTest", patch);
+    common::assert_expected("ResourceTest", stdout);
+}
+
+//the soft references cleared by -XX:SoftRefLRUPolicyMSPerMB=0 only
+#[test]
+fn t_soft_lru_policy() {
+    let opts = ["-XX:SoftRefLRUPolicyMSPerMB=0"];
+    if let Some(stdout) = common::run_sample_opts("ReferenceTest", &opts) {
+        let expected = common::expected("ReferenceTest");
+        assert_eq!(stdout, expected.replace("soft: true", "soft: false"));
+    }
+}
+
+#[test]
+fn t_max_depth() {
+    let opts = ["-XX:MaxJavaStackTraceDepth=10"];
+    if let Some(stdout) = common::run_sample_opts("StackTraceTest", &opts) {
+        assert!(
+            stdout.ends_with("deep: recurse frames = 10\n"),
+            "{}",
+            stdout
+        );
+    }
+}
@@ -3,7 +3,9 @@
 
 //https://docs.oracle.com/javase/7/docs/technotes/guides/jni/spec/invocation.html
 
-use jni_sys::{jboolean, jint, jsize, JNIInvokeInterface_, JNINativeInterface_, JavaVM};
+use jni_sys::{
+	jboolean, jint, jsize, JNIInvokeInterface_, JNINativeInterface_, JavaVM, JNI_EINVAL,
+};
 use lazy_static::lazy_static;
 use libc::c_void;
 use std::cell::RefCell;
@@ -35,8 +37,14 @@ lazy_static! {
 	static ref JVM: Mutex<Option<VMHolder>> = Mutex::new(None);
 }
 
+// Waits non-daemon threads and runs shutdown hooks, returns the exit code of vm
 unsafe extern "system" fn DestroyJavaVM(_vm: *mut JavaVM) -> jint {
-	todo!();
+	let code = match vm::runtime::vm::try_get_vm() {
+		Some(vm) => vm.destroy(),
+		None => 0,
+	};
+	JVM.lock().expect("jvm lock").take();
+	code
 }
 unsafe extern "system" fn AttachCurrentThread(
 	_vm: *mut JavaVM,
@@ -353,25 +361,31 @@ extern "C" fn JNI_CreateJavaVM(
 		-1
 	} else {
 		use std::ptr::null_mut;
-		vm::native::init();
-		vm::oop::init();
-		vm::runtime::init();
-		// To be run from jre dir
-		vm::runtime::add_class_path("./lib/rt.jar");
-		vm::runtime::add_class_path("./lib/jsse.jar");
 		let args = unsafe { &*args };
-		// TODO: Pass to jvm
-		let mut properties: std::collections::HashMap<String, String> =
-			std::collections::HashMap::new();
+		let mut vm_opts = vm::runtime::options::VMOptions::default();
 		for option in args.options() {
 			let option: String = option.string().to_string_lossy().into();
-			if option.starts_with("-D") {
-				let idx = option.find("=").expect("bad property argument format");
-				properties.insert(option[2..idx].to_owned(), option[idx..].to_owned());
-			} else if args.ignore_unrecognized == 0 {
-				panic!("unknown option: {}", option);
+			// hooks of the invocation api, 'extraInfo' not supported
+			if option == "exit" || option == "abort" || option == "vfprintf" {
+				continue;
+			}
+			if let Err(e) = vm_opts.parse_option(&option) {
+				if args.ignore_unrecognized == 0 {
+					eprintln!("{}", e);
+					return JNI_EINVAL;
+				}
 			}
 		}
+		let class_path = vm_opts.class_path.clone();
+		vm::runtime::options::set(vm_opts);
+
+		// boot class path found from JAVA_HOME or jre dir
+		vm::native::init();
+		vm::oop::init();
+		vm::runtime::init();
+		if let Some(cp) = &class_path {
+			vm::runtime::add_class_paths(cp);
+		}
 
 		let holder = VMHolder {
 			jvm: Box::new(JNIInvokeInterface_ {
@@ -1637,10 +1637,14 @@ pub unsafe extern "system" fn GetDirectBufferAddress(
 	env: *mut JNIEnv,
 	buf: jobject,
 ) -> *mut c_void {
-	todo!();
+	crate::util::jobject_to_oop(buf)
+		.and_then(vm::util::oop::direct_buffer_address)
+		.map_or(std::ptr::null_mut(), |v| v as *mut c_void)
 }
 pub unsafe extern "system" fn GetDirectBufferCapacity(env: *mut JNIEnv, buf: jobject) -> jlong {
-	todo!();
+	crate::util::jobject_to_oop(buf)
+		.and_then(vm::util::oop::direct_buffer_capacity)
+		.map_or(-1, |v| v as jlong)
 }
 pub unsafe extern "system" fn GetObjectRefType(env: *mut JNIEnv, obj: jobject) -> jobjectRefType {
 	todo!();
@@ -1,12 +1,25 @@
 #![allow(unused_imports)]
 
 /// Conversions between jvm and jni api
-use jni_sys::{jclass, JNIEnv};
+///
+/// A jobject (and so a jclass) is a handle: a pointer to a boxed Oop, the
+/// mirror for a class. Handles are never freed, there are no local frames yet.
+use jni_sys::{jclass, jobject, JNIEnv};
+
+pub fn oop_to_jobject(oop: vm::oop::Oop) -> jobject {
+	Box::into_raw(Box::new(oop)) as jobject
+}
 
 pub fn class_ref_to_jclass(class_ref: Option<vm::types::ClassRef>) -> jclass {
 	if let Some(class_ref) = class_ref {
-		class_ref.as_ref() as *const _ as jclass
+		let mirror = class_ref.get_class().get_mirror();
+		oop_to_jobject(mirror) as jclass
 	} else {
 		std::ptr::null_mut()
 	}
 }
+
+/// The Oop of a handle made by oop_to_jobject
+pub unsafe fn jobject_to_oop<'a>(obj: jobject) -> Option<&'a vm::oop::Oop> {
+	(obj as *const vm::oop::Oop).as_ref()
+}